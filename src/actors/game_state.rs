@@ -1,19 +1,63 @@
-use actix::{Actor, Context, Handler, Message};
-use std::collections::VecDeque;
-use tracing::{debug, info};
+use actix::{Actor, Addr, AsyncContext, Context, Handler, Message, MessageResult, ResponseFuture};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use chrono_tz::Tz;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{BuildHasher, RandomState};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::oneshot;
+use tracing::{debug, info, warn, Span};
 
-use crate::validation::rules::RulesValidator;
+use crate::actors::llm_validator::{ClearQueue, LLMValidatorActor, PauseBatches};
+use crate::actors::message_reaction::{
+    CleanUpReactions, FinishedWord, MessageReactionActor, PostEmbed, PostMessage, PostedMessage,
+    Reply, ResetReactions, SetVerdict, UpdatePresence, UpdateStatus,
+};
+use crate::actors::{Ping, SetMessageReaction, Shutdown, DEFAULT_MAILBOX_CAPACITY};
+use crate::audit::{AuditLog, AuditStep};
+use crate::chain::{ChainBreaks, ChainTally};
+use crate::config::Language;
+use crate::disputes::{DisputeRules, Disputes, VoteOutcome};
+use crate::error::{Error, Result};
+use crate::events::{EventBus, GameEvent};
+use crate::game::GameEngine;
+use crate::messages::Text;
+use crate::rotation::{Rotation, RotationStore};
+use crate::scoring::{
+    AttemptOutcome, Attempts, Period, PlayerScore, ScoreFormula, Scoreboard, Standing,
+};
+use crate::snapshot::{GameSnapshot, SavedScore, SnapshotStore};
+use crate::validation::dictionary::{DictionaryMatch, DictionarySource, DictionaryValidator};
+use crate::validation::neighbors::{shortest_path, PathSearch};
+use crate::validation::normalize::normalize_word;
+use crate::validation::rules::{GameRules, PendingPolicy, Violation};
+use crate::weekly::{week_id, ArchivedWeek, WeeklyArchive, WeeklySchedule};
 
-/// The maximum number of previous words to store
-const MAX_HISTORY: usize = 2;
+pub use crate::game::{
+    Delivery, RuleOutcome, RulesInfo, Validity, VerdictSource, WordEntry, WordVerdict,
+    DEFAULT_HISTORY_SIZE,
+};
 
-#[derive(Debug, Clone)]
-pub struct WordEntry {
-    pub word: String,
-    pub user_id: u64,
-    pub message_id: u64,
-    pub is_valid: bool,
-}
+/// How often to check whether the game has been idle for too long, at most
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How many similar words to suggest for a rejected word at most
+const MAX_SUGGESTIONS: usize = 3;
+
+/// How often to check whether the week has ended
+const WEEK_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How many words the search for a challenge's shortest chain looks at, at most
+const CHALLENGE_SEARCH_LIMIT: usize = 200_000;
+
+/// How many words may wait for a pending chain head by default
+pub const DEFAULT_QUEUE_SIZE: usize = 5;
+
+/// How long a queued word waits for the LLM by default before it's played anyway
+pub const DEFAULT_QUEUE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How often to check whether queued words have waited too long, at most
+const QUEUE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
 
 /// Message to register a new word
 #[derive(Message)]
@@ -31,192 +75,4405 @@ pub struct ValidateGameRules {
     pub word: String,
 }
 
+/// Message to play the word in a Discord message
+#[derive(Message)]
+#[rtype(result = "Submission")]
+pub struct SubmitWord {
+    pub content: String,
+    pub user_id: u64,
+    /// Channel or thread the message is in
+    pub channel_id: u64,
+    pub message_id: u64,
+    /// Span of the word being validated, entered while the word is played
+    pub span: Span,
+}
+
+/// The word picked out of a submitted message and what became of it
+#[derive(Debug)]
+pub struct Submission {
+    pub word: Option<String>,
+    pub verdict: WordVerdict,
+    /// What settled the word, if it was settled as soon as it was played
+    pub source: Option<VerdictSource>,
+    /// Where a [queued](WordVerdict::Queued) word's submission arrives once it's played
+    pub released: Option<oneshot::Receiver<Submission>>,
+}
+
+impl Submission {
+    fn new(word: Option<String>, verdict: WordVerdict) -> Self {
+        Self {
+            word,
+            verdict,
+            source: None,
+            released: None,
+        }
+    }
+}
+
+/// Message to judge the word in a message without playing it
+#[derive(Message)]
+#[rtype(result = "WordVerdict")]
+pub struct PreviewWord {
+    pub content: String,
+    /// Who would play the word, if known
+    pub user_id: Option<u64>,
+}
+
+/// Message to register a word and check it against the game rules in one step
+///
+/// Registering and validating atomically guarantees that each word is compared
+/// against the chain head left behind by the word processed right before it.
+#[derive(Message)]
+#[rtype(result = "RuleOutcome")]
+pub struct ProcessWord {
+    pub word: String,
+    pub user_id: u64,
+    /// Channel or thread the word was played in
+    pub channel_id: u64,
+    pub message_id: u64,
+    /// Whether the word was found in the dictionary; other words wait for the LLM
+    pub in_dictionary: bool,
+}
+
+/// Message to check a word against the game rules without playing it
+///
+/// Neither the used words nor the chain head are modified.
+#[derive(Message)]
+#[rtype(result = "RuleOutcome")]
+pub struct CheckWord {
+    pub word: String,
+    /// Who would play the word, if known
+    pub user_id: Option<u64>,
+}
+
+/// Message to get the active game rules
+#[derive(Message)]
+#[rtype(result = "RulesInfo")]
+pub struct GetRules;
+
+/// Message to get the most recently played words, newest first
+#[derive(Message)]
+#[rtype(result = "Vec<WordEntry>")]
+pub struct GetHistory {
+    pub limit: usize,
+}
+
 /// Message to get the last valid word
 #[derive(Message)]
 #[rtype(result = "Option<String>")]
 pub struct GetLastValidWord;
 
-/// Message to mark a word as valid or invalid
+/// Message to get the word the chain continued from when a message was played
 #[derive(Message)]
-#[rtype(result = "()")]
+#[rtype(result = "Option<String>")]
+pub struct GetPreviousWord {
+    pub message_id: u64,
+}
+
+/// Message to record the final verdict on a word
+///
+/// Returns false if the word had already been settled, in which case the verdict is ignored.
+#[derive(Message)]
+#[rtype(result = "bool")]
 pub struct MarkWordValidity {
     pub message_id: u64,
-    pub is_valid: bool,
+    pub validity: Validity,
+    /// What settled the word, e.g. the LLM with its explanation
+    pub source: VerdictSource,
+}
+
+/// Message from the reaction actor on whether a word's verdict reaction reached Discord
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct VerdictDelivered {
+    pub message_id: u64,
+    /// The verdict the reaction showed
+    pub validity: Validity,
+    pub delivered: bool,
+}
+
+/// Message to set the verdict reactions that failed again, e.g. after a reconnect
+///
+/// Returns how many words had their reaction set again.
+#[derive(Message)]
+#[rtype(result = "usize")]
+pub struct RedeliverVerdicts;
+
+/// Message to count an attempt settled without the game state, e.g. a blocked word
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct RecordAttempt {
+    pub user_id: u64,
+    pub outcome: AttemptOutcome,
+}
+
+/// Message to get how a player's attempts have turned out
+#[derive(Message)]
+#[rtype(result = "Attempts")]
+pub struct GetAttempts {
+    pub user_id: u64,
 }
 
+/// Message to find dictionary words a rejected word may have been a typo of
+///
+/// Empty unless suggestions are enabled with [`GameStateActor::with_suggestions`].
+#[derive(Message)]
+#[rtype(result = "Vec<String>")]
+pub struct SuggestWords {
+    pub word: String,
+}
+
+/// Message to get the players with the most points, best first
+#[derive(Message)]
+#[rtype(result = "Vec<(u64, PlayerScore)>")]
+pub struct GetLeaderboard {
+    pub period: Period,
+    pub limit: usize,
+}
+
+/// Message to get a player's points and place on the leaderboard
+#[derive(Message)]
+#[rtype(result = "Standing")]
+pub struct GetStanding {
+    pub user_id: u64,
+}
+
+/// Message to get the size and origin of the dictionary
+#[derive(Message)]
+#[rtype(result = "DictionaryStats")]
+pub struct GetDictionaryStats;
+
+/// Size and origin of the dictionary
+#[derive(Debug, Clone)]
+pub struct DictionaryStats {
+    pub words: usize,
+    pub source: DictionarySource,
+}
+
+/// Message to look a word up in the dictionary, outside the game
+#[derive(Message)]
+#[rtype(result = "WordLookup")]
+pub struct LookupWord {
+    pub word: String,
+}
+
+/// What the dictionary says about a word
+#[derive(Debug, Clone)]
+pub struct WordLookup {
+    /// The word as it was looked up
+    pub normalized: String,
+    /// None if it's not in the dictionary
+    pub found: Option<DictionaryMatch>,
+}
+
+/// Message to pick a random dictionary word
+#[derive(Message)]
+#[rtype(result = "Option<String>")]
+pub struct GetRandomWord;
+
+/// Message to get the dictionary, for checking many words without holding up the game
+#[derive(Message)]
+#[rtype(result = "Arc<DictionaryValidator>")]
+pub struct GetDictionary;
+
+/// Message to check that a challenge can be played and find its shortest chain
+///
+/// The search runs on a blocking thread, so the game goes on meanwhile.
+#[derive(Message)]
+#[rtype(result = "Result<ChallengePlan>")]
+pub struct PlanChallenge {
+    pub from: String,
+    pub to: String,
+}
+
+/// Whether a challenge can be played
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChallengePlan {
+    /// The shortest chain from the start word to the target, both included
+    Ready(Vec<String>),
+    /// The word isn't in the dictionary
+    NotInDictionary(String),
+    /// The start word is the target
+    SameWord,
+    /// No chain of dictionary words leads to the target
+    NoPath,
+    /// The search gave up after looking at this many words
+    TooFar(usize),
+}
+
+/// Message to start a challenge along a chain found with [`PlanChallenge`]
+///
+/// The game restarts from the first word of the chain, and ends when someone
+/// plays the last one.
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct StartChallenge {
+    pub path: Vec<String>,
+}
+
+/// Message to end the challenge without a winner
+///
+/// Returns the shortest chain, None if there was no challenge.
+#[derive(Message)]
+#[rtype(result = "Option<Vec<String>>")]
+pub struct GiveUpChallenge;
+
 /// Message to reset the game state
+///
+/// Players taking turns have to join again.
 #[derive(Message)]
 #[rtype(result = "()")]
 pub struct ResetGame;
 
-/// Actor that maintains the game state
-pub struct GameStateActor {
-    /// History of words in the game
-    word_history: VecDeque<WordEntry>,
+/// Message to add a player last in the turn order
+///
+/// Returns whether they joined, false if they already had, or None if
+/// players don't take turns.
+#[derive(Message)]
+#[rtype(result = "Option<bool>")]
+pub struct JoinRotation {
+    pub user_id: u64,
+}
 
-    /// Rules validator
-    rules_validator: RulesValidator,
+/// Message to take a player out of the turn order
+///
+/// Returns whether they left, false if they hadn't joined, or None if
+/// players don't take turns.
+#[derive(Message)]
+#[rtype(result = "Option<bool>")]
+pub struct LeaveRotation {
+    pub user_id: u64,
+}
 
-    /// The last valid word in the game
-    last_valid_word: Option<String>,
+/// Message to get the players taking turns, starting from the one on turn
+///
+/// Returns None if players don't take turns.
+#[derive(Message)]
+#[rtype(result = "Option<Vec<u64>>")]
+pub struct GetTurnOrder;
 
-    /// The last word that follows game rules (might be pending LLM validation)
-    last_game_rule_word: Option<String>,
+/// Message to make a word the current one, e.g. after a dispute
+///
+/// The history and the scores are kept, only the chain goes on from the word.
+#[derive(Message)]
+#[rtype(result = "SetWordOutcome")]
+pub struct SetCurrentWord {
+    pub word: String,
+    /// Set the word even if it isn't in the dictionary
+    pub force: bool,
 }
 
-impl Default for GameStateActor {
-    fn default() -> Self {
-        Self::new()
-    }
+/// Result of setting the current word
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SetWordOutcome {
+    /// The word is the current one, and this many words waiting for the LLM were rejected
+    Set {
+        word: String,
+        dropped: usize,
+    },
+    NotInDictionary(String),
+    NotAWord,
 }
 
-impl GameStateActor {
-    pub fn new() -> Self {
+/// Message to pause or resume the game, returning whether that changed anything
+///
+/// While paused, submitted words are ignored instead of played, the game
+/// doesn't reset or take the bot's turns on its own, and the LLM batches wait.
+#[derive(Message)]
+#[rtype(result = "bool")]
+pub struct SetPaused(pub bool);
+
+/// Message to ask whether the game is paused
+#[derive(Message)]
+#[rtype(result = "bool")]
+pub struct IsPaused;
+
+/// Message to ask how many players' scores were restored from the saved game,
+/// None if the game wasn't continued from one
+#[derive(Message)]
+#[rtype(result = "Option<usize>")]
+pub struct GetRestoredPlayers;
+
+/// Message to tell the game state the bot's own user ID, which it plays its turns with
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct SetBotUser(pub u64);
+
+/// Message to count a player's reaction for or against a word's verdict
+///
+/// Returns the word's new validity if the vote overturned its verdict.
+#[derive(Message)]
+#[rtype(result = "Option<Validity>")]
+pub struct DisputeVote {
+    pub message_id: u64,
+    pub user_id: u64,
+    pub reaction: char,
+    /// Whether the reaction was added, false if it was taken back
+    pub added: bool,
+}
+
+/// Message to register a word the bot has posted as its own turn
+///
+/// The event handler skips the bot's own messages, so they're registered
+/// here instead, once the message is posted and has an ID.
+#[derive(Message)]
+#[rtype(result = "RuleOutcome")]
+pub struct RegisterBotWord {
+    pub word: String,
+    pub posted: PostedMessage,
+}
+
+/// What the bot does when the game has been idle long enough
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum BotTurn {
+    /// Play the word
+    Play(String),
+    /// Warn that nothing in the dictionary continues from the word
+    DeadEnd(String),
+}
+
+/// How many words each player has had accepted today
+///
+/// The counts start over at midnight in the configured time zone.
+struct DailyLimit {
+    /// Accepted words allowed per player and day
+    limit: usize,
+    timezone: Tz,
+    /// The day the counts are for
+    day: Option<NaiveDate>,
+    counts: HashMap<u64, usize>,
+}
+
+impl DailyLimit {
+    fn new(limit: usize, timezone: Tz) -> Self {
         Self {
-            word_history: VecDeque::with_capacity(MAX_HISTORY),
-            rules_validator: RulesValidator::default(),
-            last_valid_word: None,
-            last_game_rule_word: None,
+            limit,
+            timezone,
+            day: None,
+            counts: HashMap::new(),
         }
     }
 
-    /// Add a word to the history and maintain maximum size
-    fn add_to_history(&mut self, entry: WordEntry) {
-        self.word_history.push_back(entry);
+    /// Forget the counts of previous days
+    fn roll_over(&mut self, now: DateTime<Utc>) {
+        let today = now.with_timezone(&self.timezone).date_naive();
+        if self.day != Some(today) {
+            self.day = Some(today);
+            self.counts.clear();
+        }
+    }
+
+    /// Whether the player may not have any more words accepted today
+    fn is_reached(&mut self, user_id: u64, now: DateTime<Utc>) -> bool {
+        self.roll_over(now);
+        self.counts.get(&user_id).copied().unwrap_or(0) >= self.limit
+    }
 
-        // Keep history at maximum size
-        if self.word_history.len() > MAX_HISTORY {
-            self.word_history.pop_front();
+    /// Count a word accepted from the player
+    fn record(&mut self, user_id: u64, now: DateTime<Utc>) {
+        self.roll_over(now);
+        *self.counts.entry(user_id).or_default() += 1;
+    }
+
+    /// Uncount a word played at `played_at`, e.g. a proper noun the LLM rejected
+    ///
+    /// Words played on a previous day were forgotten at midnight.
+    fn refund(&mut self, user_id: u64, played_at: DateTime<Utc>, now: DateTime<Utc>) {
+        self.roll_over(now);
+        if Some(played_at.with_timezone(&self.timezone).date_naive()) != self.day {
+            return;
+        }
+        if let Some(count) = self.counts.get_mut(&user_id) {
+            *count = count.saturating_sub(1);
         }
     }
 }
 
-impl Actor for GameStateActor {
-    type Context = Context<Self>;
+/// When weeks end, where their standings go and which week is under way
+struct WeeklyReset {
+    schedule: WeeklySchedule,
+    archive: WeeklyArchive,
+    /// Start of the current week, None until the actor has started
+    week_start: Option<DateTime<Utc>>,
 }
 
-impl Handler<RegisterWord> for GameStateActor {
-    type Result = bool;
+/// A target-word challenge under way
+struct Challenge {
+    /// The shortest chain from the start word to the target, both included
+    path: Vec<String>,
+}
 
-    fn handle(&mut self, msg: RegisterWord, _ctx: &mut Context<Self>) -> Self::Result {
-        // Create the entry (initially not validated)
-        let entry = WordEntry {
-            word: msg.word.clone(),
-            user_id: msg.user_id,
-            message_id: msg.message_id,
-            is_valid: false,
-        };
+impl Challenge {
+    fn target(&self) -> &str {
+        self.path.last().map(String::as_str).unwrap_or_default()
+    }
 
-        debug!(
-            "Registering word '{}' (message ID: {})",
-            msg.word, msg.message_id
-        );
+    /// How many moves the shortest chain takes
+    fn optimal_moves(&self) -> usize {
+        self.path.len().saturating_sub(1)
+    }
+}
 
-        // Add to history
-        self.add_to_history(entry);
+/// Players taking turns in rotation mode
+struct Turns {
+    rotation: Rotation,
+    store: RotationStore,
+    /// Pass the turn on after this long without an accepted word, None to wait however long
+    skip_after: Option<Duration>,
+    /// When the player on turn got it
+    turn_started: Instant,
+}
 
-        // Return true as acknowledgment
-        true
-    }
+/// A word held until the chain head it was played after has its verdict
+struct QueuedWord {
+    submit: SubmitWord,
+    queued_at: Instant,
+    /// Where the word's submission goes once it's played
+    release: oneshot::Sender<Submission>,
 }
 
-impl Handler<ValidateGameRules> for GameStateActor {
-    type Result = bool;
+/// Actor that maintains the game state
+pub struct GameStateActor {
+    /// The game itself
+    engine: GameEngine,
 
-    fn handle(&mut self, msg: ValidateGameRules, _ctx: &mut Context<Self>) -> Self::Result {
-        debug!("Validating game rules for word: '{}'", msg.word);
+    /// Reset the game after this long without an accepted word
+    idle_reset: Option<Duration>,
 
-        // Use last_game_rule_word if available, otherwise fall back to last_valid_word
-        let reference_word = self
-            .last_game_rule_word
-            .as_ref()
-            .or(self.last_valid_word.as_ref());
+    /// Where to announce idle resets and fix reactions of rolled back words
+    message_reaction: Option<Addr<MessageReactionActor>>,
 
-        if let Some(last_word) = reference_word {
-            debug!("Comparing with last rule-valid word: '{}'", last_word);
-            let is_valid = self.rules_validator.is_valid_move(last_word, &msg.word);
+    /// Queue to clear on idle resets
+    llm_validator: Option<Addr<LLMValidatorActor>>,
 
-            // If valid, update the last_game_rule_word and add to rules validator
-            if is_valid {
-                debug!(
-                    "Word '{}' follows game rules, updating last_game_rule_word",
-                    msg.word
-                );
-                self.last_game_rule_word = Some(msg.word.clone());
-                self.rules_validator.add_word(&msg.word);
-            }
+    /// This game's own address, for telling the shared LLM queue which words are meant
+    address: Option<Addr<GameStateActor>>,
 
-            info!("Word '{}' follows game rules: {}", msg.word, is_valid);
-            is_valid
-        } else {
-            // If there's no last valid word, consider first word valid
-            // and add it to the used words list
-            info!(
-                "No previous valid word, accepting '{}' as first word",
-                msg.word
-            );
-            self.last_game_rule_word = Some(msg.word.clone());
-            self.rules_validator.add_word(&msg.word);
-            true
-        }
+    /// How many messages may wait in the mailbox before senders have to wait
+    mailbox_capacity: usize,
+
+    /// Channel or thread of each played word, for fixing reactions on rollbacks
+    message_channels: HashMap<u64, u64>,
+
+    /// Accepted words per player today, None if there's no limit
+    daily_limit: Option<DailyLimit>,
+
+    /// How long a player has to wait after an accepted word, None for no cooldown
+    cooldown: Option<Duration>,
+
+    /// When each player last had a word accepted, and the word's message ID
+    last_accepted: HashMap<u64, (Instant, u64)>,
+
+    /// How many points words are worth
+    scoring: ScoreFormula,
+
+    /// Points of every player, kept across game resets
+    scoreboard: Scoreboard,
+
+    /// How every player's attempts have turned out, kept across game resets
+    attempts: HashMap<u64, Attempts>,
+
+    /// Weekly standings to post and reset, None to only keep all-time scores
+    weekly_reset: Option<WeeklyReset>,
+
+    /// Whether rejected words get similar dictionary words suggested
+    suggest_words: bool,
+
+    /// Play a word after this long without an accepted word, None to never play
+    bot_turn_after: Option<Duration>,
+
+    /// The bot's own user ID, known once Discord is connected
+    bot_user_id: Option<u64>,
+
+    /// When the word the bot last took its turn after was accepted, so each
+    /// word gets one turn at most
+    bot_turn_taken: Option<Instant>,
+
+    /// The challenge being played, None in the normal game
+    challenge: Option<Challenge>,
+
+    /// When the game was paused, None while it's running
+    paused_at: Option<Instant>,
+
+    /// Words waiting for the pending chain head, oldest first (queue pending policy)
+    queue: VecDeque<QueuedWord>,
+
+    /// How many words may be queued at most
+    queue_size: usize,
+
+    /// How long a queued word waits before it's played against the pending head anyway
+    queue_timeout: Duration,
+
+    /// Votes against verdicts, None if players can't overturn them
+    disputes: Option<Disputes>,
+
+    /// Language of announcements and replies
+    language: Language,
+
+    /// Where the handling of each word is noted down for /why
+    audit: Arc<AuditLog>,
+
+    /// Where what happens to words and the game is published
+    events: EventBus,
+
+    /// Chain lengths whose multiples are published as milestones, 0 for none
+    milestone_interval: usize,
+
+    /// The last milestone published, lowered when the chain shrinks below it
+    last_milestone: usize,
+
+    /// The longest chain since the bot started, including the current one
+    record: usize,
+
+    /// The record when the current chain started, what it has to beat
+    record_to_beat: usize,
+
+    /// When a rejected word breaks the chain, None if chains never break
+    chain_breaks: Option<ChainBreaks>,
+
+    /// What has been counted of the current chain for its summary
+    tally: ChainTally,
+
+    /// Whether the game is paused after the chain broke, until it's reset
+    awaiting_reset: bool,
+
+    /// Players taking turns, None if anyone may play at any time
+    turns: Option<Turns>,
+
+    /// Where the game is saved on shutdown, None to not save it
+    snapshot: Option<SnapshotStore>,
+
+    /// Start of the week the restored weekly points belong to, until the first week check
+    saved_week_start: Option<DateTime<Utc>>,
+
+    /// Standings of a week that ended before there was a channel to post them in
+    week_announcement: Option<PostEmbed>,
+
+    /// Players whose scores were restored from the snapshot, None without one
+    restored_players: Option<usize>,
+
+    /// What happens to the reactions of the words when the game resets
+    reset_reactions: ResetReactions,
+}
+
+impl Default for GameStateActor {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-impl Handler<GetLastValidWord> for GameStateActor {
-    type Result = Option<String>;
+impl GameStateActor {
+    pub fn new() -> Self {
+        Self::with_rules(GameRules::default())
+    }
 
-    fn handle(&mut self, _msg: GetLastValidWord, _ctx: &mut Context<Self>) -> Self::Result {
-        self.last_valid_word.clone()
+    /// Create a game state enforcing the given rules, without a dictionary
+    pub fn with_rules(rules: GameRules) -> Self {
+        Self::with_engine(GameEngine::new(
+            DictionaryValidator::from_words(Vec::<String>::new()),
+            rules,
+        ))
     }
-}
 
-impl Handler<MarkWordValidity> for GameStateActor {
-    type Result = ();
+    /// Create a game state around an already configured engine
+    pub fn with_engine(engine: GameEngine) -> Self {
+        Self {
+            engine,
+            idle_reset: None,
+            message_reaction: None,
+            llm_validator: None,
+            address: None,
+            mailbox_capacity: DEFAULT_MAILBOX_CAPACITY,
+            message_channels: HashMap::new(),
+            daily_limit: None,
+            cooldown: None,
+            last_accepted: HashMap::new(),
+            scoring: ScoreFormula::default(),
+            scoreboard: Scoreboard::default(),
+            attempts: HashMap::new(),
+            weekly_reset: None,
+            suggest_words: false,
+            bot_turn_after: None,
+            bot_user_id: None,
+            bot_turn_taken: None,
+            challenge: None,
+            paused_at: None,
+            queue: VecDeque::new(),
+            queue_size: DEFAULT_QUEUE_SIZE,
+            queue_timeout: DEFAULT_QUEUE_TIMEOUT,
+            disputes: None,
+            language: Language::default(),
+            audit: Arc::new(AuditLog::new(0)),
+            events: EventBus::default(),
+            milestone_interval: 0,
+            last_milestone: 0,
+            record: 0,
+            record_to_beat: 0,
+            chain_breaks: None,
+            tally: ChainTally::default(),
+            awaiting_reset: false,
+            turns: None,
+            snapshot: None,
+            saved_week_start: None,
+            week_announcement: None,
+            restored_players: None,
+            reset_reactions: ResetReactions::None,
+        }
+    }
 
-    fn handle(&mut self, msg: MarkWordValidity, _ctx: &mut Context<Self>) -> Self::Result {
-        debug!(
-            "Marking message {} as {}",
-            msg.message_id,
-            if msg.is_valid { "valid" } else { "invalid" }
-        );
-
-        // Find the entry by message ID and update its validity
-        let mut updated = false;
-        for entry in &mut self.word_history {
-            if entry.message_id == msg.message_id {
-                entry.is_valid = msg.is_valid;
-                updated = true;
-
-                // If valid, update the last valid word
-                if msg.is_valid {
-                    debug!(
-                        "Updating last valid word from {} to: {}",
-                        self.last_valid_word.as_deref().unwrap_or("<none>"),
-                        entry.word
-                    );
-                    self.last_valid_word = Some(entry.word.clone());
-                }
+    /// Let players overturn verdicts by reacting to words
+    pub fn with_disputes(mut self, rules: DisputeRules) -> Self {
+        self.disputes = Some(Disputes::new(rules));
+        self
+    }
 
-                break;
+    /// Let rejected words break long enough chains, posting a summary of the chain
+    pub fn with_chain_breaks(mut self, breaks: ChainBreaks) -> Self {
+        self.chain_breaks = Some(breaks);
+        self
+    }
+
+    /// Only accept words from the player on turn, keeping the turn order in `store`
+    ///
+    /// The turn passes on after each accepted word, or after `skip_after`
+    /// without one. A zero duration never skips a turn.
+    pub fn with_rotation(mut self, store: RotationStore, skip_after: Duration) -> Self {
+        let rotation = store.load().unwrap_or_else(|e| {
+            warn!(
+                path = %store.path().display(),
+                error = %e,
+                "Failed to load the turn order, starting without players"
+            );
+            Rotation::default()
+        });
+        self.turns = Some(Turns {
+            rotation,
+            store,
+            skip_after: (!skip_after.is_zero()).then_some(skip_after),
+            turn_started: Instant::now(),
+        });
+        self
+    }
+
+    /// Continue the game saved in `store`, and save it there on shutdown
+    ///
+    /// Only the word to continue from, the scores and whether the game is
+    /// paused are saved, see [`crate::snapshot`].
+    pub fn with_snapshot(mut self, store: SnapshotStore) -> Self {
+        match store.load() {
+            Ok(Some(snapshot)) => {
+                if let Some(word) = &snapshot.word {
+                    self.engine.start_from(word);
+                }
+                for score in &snapshot.scores {
+                    self.scoreboard
+                        .restore(score.user_id, score.all_time, score.weekly);
+                }
+                self.saved_week_start = snapshot.week_start;
+                self.restored_players = Some(snapshot.scores.len());
+                if snapshot.paused {
+                    self.paused_at = Some(Instant::now());
+                }
+                info!(
+                    word = ?snapshot.word,
+                    players = snapshot.scores.len(),
+                    paused = snapshot.paused,
+                    "Continuing the saved game"
+                );
             }
+            Ok(None) => {}
+            // Kept aside, the file isn't overwritten when the new game is saved
+            Err(e) => match store.set_aside() {
+                Ok(corrupt) => warn!(
+                    path = %store.path().display(),
+                    moved_to = %corrupt.display(),
+                    error = %e,
+                    "Failed to load the saved game, starting a new one"
+                ),
+                Err(move_error) => warn!(
+                    path = %store.path().display(),
+                    error = %e,
+                    move_error = %move_error,
+                    "Failed to load the saved game or move it aside, starting a new one"
+                ),
+            },
         }
+        self.snapshot = Some(store);
+        self
+    }
 
-        if !updated {
-            debug!("Could not find message {} to mark validity", msg.message_id);
-        }
+    /// Reset the game automatically after `idle_reset` without accepted words
+    ///
+    /// A zero duration disables the automatic reset.
+    pub fn with_idle_reset(mut self, idle_reset: Duration) -> Self {
+        self.idle_reset = (!idle_reset.is_zero()).then_some(idle_reset);
+        self
     }
-}
 
-impl Handler<ResetGame> for GameStateActor {
-    type Result = ();
+    /// Keep up to `history_size` previous words
+    pub fn with_history_size(mut self, history_size: usize) -> Self {
+        self.engine = self.engine.with_history_size(history_size);
+        self
+    }
 
-    fn handle(&mut self, _msg: ResetGame, _ctx: &mut Context<Self>) -> Self::Result {
-        // Clear history and reset rules validator
-        self.word_history.clear();
-        self.rules_validator.reset();
-        self.last_valid_word = None;
-        self.last_game_rule_word = None;
+    /// Clear or mark the reactions of the words in the history when the game resets
+    pub fn with_reset_reactions(mut self, reset_reactions: ResetReactions) -> Self {
+        self.reset_reactions = reset_reactions;
+        self
+    }
+
+    /// How many messages may wait for the actor before senders have to wait
+    pub fn with_mailbox_capacity(mut self, mailbox_capacity: usize) -> Self {
+        self.mailbox_capacity = mailbox_capacity;
+        self
+    }
+
+    /// Accept at most `limit` words per player a day, with days starting at
+    /// midnight in `timezone`
+    ///
+    /// A zero limit disables it.
+    pub fn with_daily_limit(mut self, limit: usize, timezone: Tz) -> Self {
+        self.daily_limit = (limit > 0).then(|| DailyLimit::new(limit, timezone));
+        self
+    }
+
+    /// Make players wait `cooldown` after an accepted word before playing again
+    ///
+    /// A zero duration disables the cooldown.
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = (!cooldown.is_zero()).then_some(cooldown);
+        self
+    }
+
+    /// Score words with the given formula
+    pub fn with_scoring(mut self, scoring: ScoreFormula) -> Self {
+        self.scoring = scoring;
+        self
+    }
+
+    /// Post and reset the weekly standings when a week ends, archiving them
+    pub fn with_weekly_reset(mut self, schedule: WeeklySchedule, archive: WeeklyArchive) -> Self {
+        self.weekly_reset = Some(WeeklyReset {
+            schedule,
+            archive,
+            week_start: None,
+        });
+        self
+    }
+
+    /// Suggest similar dictionary words for words that turn out not to be words
+    pub fn with_suggestions(mut self, suggest_words: bool) -> Self {
+        self.suggest_words = suggest_words;
+        self
+    }
+
+    /// Play a random word that continues the chain after `after` without accepted words
+    ///
+    /// The bot only plays once it knows its own user ID from [`SetBotUser`].
+    /// A zero duration disables it.
+    pub fn with_bot_turns(mut self, after: Duration) -> Self {
+        self.bot_turn_after = (!after.is_zero()).then_some(after);
+        self
+    }
+
+    /// Under the queue pending policy, hold up to `size` words for a pending
+    /// chain head, and play them anyway once they've waited `timeout`
+    pub fn with_pending_queue(mut self, size: usize, timeout: Duration) -> Self {
+        self.queue_size = size;
+        self.queue_timeout = timeout;
+        self
+    }
+
+    /// Announce and reply in `language`
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Clear the LLM validation queue whenever the game resets on its own
+    pub fn with_llm_validator(mut self, llm_validator: Addr<LLMValidatorActor>) -> Self {
+        self.llm_validator = Some(llm_validator);
+        self
+    }
+
+    /// Note down how each word is played and settled in `audit`
+    pub fn with_audit(mut self, audit: Arc<AuditLog>) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Publish what happens to words and the game on `events`
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Publish a milestone whenever the chain grows to a multiple of `interval`, 0 for never
+    pub fn with_milestones(mut self, interval: usize) -> Self {
+        self.milestone_interval = interval;
+        self
+    }
+
+    /// Have the reactions of the game's words cleaned up, before it's reset
+    ///
+    /// Only the words still in the history are cleaned up, newest first.
+    fn clean_up_reactions(&self) {
+        if self.reset_reactions == ResetReactions::None {
+            return;
+        }
+        let Some(message_reaction) = &self.message_reaction else {
+            return;
+        };
+        let words: Vec<_> = self
+            .engine
+            .history(usize::MAX)
+            .into_iter()
+            .filter_map(|entry| {
+                Some(FinishedWord {
+                    channel_id: *self.message_channels.get(&entry.message_id)?,
+                    message_id: entry.message_id,
+                    validity: entry.validity,
+                })
+            })
+            .collect();
+        if !words.is_empty() {
+            message_reaction.do_send(CleanUpReactions {
+                mode: self.reset_reactions,
+                words,
+            });
+        }
+    }
+
+    /// Remember where a word was played
+    ///
+    /// Only words still in the history can be rolled back, so the others are
+    /// forgotten whenever the map has grown to twice the history.
+    fn remember_channel(&mut self, message_id: u64, channel_id: u64) {
+        self.message_channels.insert(message_id, channel_id);
+        if self.message_channels.len() > 2 * self.engine.history_size() {
+            let engine = &self.engine;
+            self.message_channels
+                .retain(|&message_id, _| engine.in_history(message_id));
+        }
+    }
+
+    /// The player's daily limit if they've reached it
+    fn limit_reached(&mut self, user_id: u64, now: DateTime<Utc>) -> Option<usize> {
+        let daily_limit = self.daily_limit.as_mut()?;
+        daily_limit
+            .is_reached(user_id, now)
+            .then_some(daily_limit.limit)
+    }
+
+    /// How long the player still has to wait before playing, if at all
+    fn cooldown_left(&self, user_id: u64, now: Instant) -> Option<Duration> {
+        let cooldown = self.cooldown?;
+        let (accepted_at, _) = self.last_accepted.get(&user_id)?;
+        cooldown
+            .checked_sub(now.saturating_duration_since(*accepted_at))
+            .filter(|left| !left.is_zero())
+    }
+
+    /// Count a word towards the player's daily limit and start their cooldown
+    fn count_accepted(&mut self, user_id: u64, message_id: u64, now: Instant) {
+        if let Some(daily_limit) = &mut self.daily_limit {
+            daily_limit.record(user_id, Utc::now());
+        }
+        if let Some(cooldown) = self.cooldown {
+            // Players whose cooldown has passed needn't be remembered
+            self.last_accepted.retain(|_, (accepted_at, _)| {
+                now.saturating_duration_since(*accepted_at) < cooldown
+            });
+            self.last_accepted.insert(user_id, (now, message_id));
+        }
+    }
+
+    /// Take back a counted word that turned out to be invalid
+    ///
+    /// An invalid word doesn't lock the player out, so its cooldown is lifted.
+    fn refund_accepted(&mut self, word: &WordEntry, now: DateTime<Utc>) {
+        if let Some(daily_limit) = &mut self.daily_limit {
+            daily_limit.refund(word.user_id, word.played_at.into(), now);
+        }
+        if let Some(&(_, message_id)) = self.last_accepted.get(&word.user_id) {
+            if message_id == word.message_id {
+                self.last_accepted.remove(&word.user_id);
+            }
+        }
+    }
+
+    /// Score a word that entered the chain after `previous`
+    ///
+    /// Words waiting for the LLM get their points once they're accepted. Only
+    /// words in the history can be invalidated, so the points of the others
+    /// are forgotten whenever twice the history is remembered.
+    fn score_word(&mut self, submit: &SubmitWord, word: &str, previous: Option<&str>) {
+        let points = self.scoring.score(word, previous);
+        if self
+            .engine
+            .entry(submit.message_id)
+            .map(|entry| entry.validity)
+            == Some(Validity::Valid)
+        {
+            debug!(user_id = submit.user_id, points, "Awarding points");
+            self.scoreboard
+                .award(submit.user_id, submit.message_id, points);
+        } else {
+            self.scoreboard
+                .hold(submit.user_id, submit.message_id, points);
+        }
+
+        if self.scoreboard.remembered_words() > 2 * self.engine.history_size() {
+            let engine = &self.engine;
+            self.scoreboard
+                .forget_words(|message_id| engine.in_history(message_id));
+        }
+    }
+
+    /// Count a settled attempt
+    fn record_attempt(&mut self, user_id: u64, outcome: AttemptOutcome) {
+        debug!(user_id, ?outcome, "Recording attempt");
+        self.attempts.entry(user_id).or_default().record(outcome);
+    }
+
+    /// What a submitted word came to, None if it isn't settled yet or wasn't judged at all
+    fn attempt_outcome(verdict: &WordVerdict) -> Option<AttemptOutcome> {
+        match verdict {
+            WordVerdict::ValidInDictionary => Some(AttemptOutcome::Dictionary),
+            WordVerdict::ValidProperNoun => Some(AttemptOutcome::ProperNoun),
+            WordVerdict::NotInDictionary | WordVerdict::Implausible => {
+                Some(AttemptOutcome::NotAWord)
+            }
+            WordVerdict::InvalidRules(Violation::AlreadyUsed(_)) => {
+                Some(AttemptOutcome::AlreadyUsed)
+            }
+            WordVerdict::InvalidRules(_) => Some(AttemptOutcome::RuleViolation),
+            _ => None,
+        }
+    }
+
+    /// Similar dictionary words for a rejected word, empty if suggestions are off
+    fn suggestions(&self, word: &str) -> Vec<String> {
+        if !self.suggest_words {
+            return Vec::new();
+        }
+        self.engine.suggestions(word, MAX_SUGGESTIONS)
+    }
+
+    /// Show the last accepted word and the chain length in the bot's presence and status message
+    fn announce_progress(&mut self) {
+        if let Some(message_reaction) = &self.message_reaction {
+            message_reaction.do_send(UpdatePresence {
+                word: self.engine.last_valid_word().map(str::to_string),
+                chain_length: self.engine.stats().chain_length,
+            });
+            message_reaction.do_send(self.status());
+        }
+        self.check_milestone();
+        self.check_record();
+    }
+
+    /// Publish a milestone if the chain has grown to the next multiple of the interval
+    fn check_milestone(&mut self) {
+        if self.milestone_interval == 0 {
+            return;
+        }
+        let chain_length = self.engine.stats().chain_length;
+        let reached = chain_length - chain_length % self.milestone_interval;
+        if reached <= self.last_milestone {
+            // A rollback or reset may take the chain below it, to be reached again
+            self.last_milestone = reached;
+            return;
+        }
+        self.last_milestone = reached;
+        if let (Some(word), Some(user_id)) = (self.engine.current_word(), self.engine.last_player())
+        {
+            info!(chain_length, user_id, "Chain reached a milestone");
+            self.events.publish(GameEvent::MilestoneReached {
+                chain_length,
+                user_id,
+                word,
+            });
+        }
+    }
+
+    /// Break the chain on a rejected word once it's long enough, posting its summary
+    ///
+    /// A new chain starts right away, or the game is paused until it's reset.
+    fn check_break(&mut self, word: &str, user_id: u64) {
+        let Some(breaks) = self.chain_breaks else {
+            return;
+        };
+        if self.awaiting_reset || self.tally.len() < breaks.min_length {
+            return;
+        }
+        let summary = self
+            .tally
+            .summary(word, user_id, Utc::now(), breaks.restart);
+        info!(
+            chain_length = summary.length,
+            user_id,
+            restart = breaks.restart,
+            "Chain broken"
+        );
+        if let Some(message_reaction) = &self.message_reaction {
+            message_reaction.do_send(PostEmbed {
+                title: Text::ChainBroken(&summary).render(self.language),
+                description: Text::ChainSummary(&summary).render(self.language),
+            });
+        }
+        if breaks.restart {
+            self.start_new_chain();
+        } else {
+            self.awaiting_reset = true;
+            self.set_paused(true, Instant::now());
+        }
+    }
+
+    /// Clear the chain for a new one, dropping the words waiting for the LLM
+    fn start_new_chain(&mut self) {
+        self.clean_up_reactions();
+        self.engine.reset();
+        self.challenge = None;
+        self.tally = ChainTally::default();
+        self.announce_progress();
+        self.events.publish(GameEvent::GameReset { idle: false });
+        self.clear_llm_queue();
+        self.release_queue();
+    }
+
+    /// Publish a record the first time the chain grows longer than every earlier chain
+    fn check_record(&mut self) {
+        let chain_length = self.engine.stats().chain_length;
+        if chain_length <= 1 {
+            // A new chain starts, with the longest earlier one to beat
+            self.record_to_beat = self.record;
+        }
+        if chain_length <= self.record {
+            return;
+        }
+        let first_time = self.record == self.record_to_beat;
+        self.record = chain_length;
+        // The first chain has nothing to beat
+        if !first_time || self.record_to_beat == 0 {
+            return;
+        }
+        if let (Some(word), Some(user_id)) = (self.engine.current_word(), self.engine.last_player())
+        {
+            info!(
+                chain_length,
+                previous = self.record_to_beat,
+                user_id,
+                "Chain set a new record"
+            );
+            self.events.publish(GameEvent::ChainRecord {
+                chain_length,
+                user_id,
+                word,
+            });
+        }
+    }
+
+    /// Count a played word that went into the chain, and publish it
+    fn accept(&mut self, message_id: u64, user_id: u64, word: String) {
+        self.tally.add(user_id, &word, Utc::now());
+        self.events.publish(GameEvent::WordAccepted {
+            message_id,
+            user_id,
+            word,
+            chain_length: self.engine.stats().chain_length,
+        });
+    }
+
+    /// Publish the verdict a word got after it was played, if it's one there's an event for
+    fn publish_settled(
+        &mut self,
+        message_id: u64,
+        user_id: u64,
+        word: String,
+        validity: Validity,
+        source: VerdictSource,
+    ) {
+        let valid = match validity {
+            Validity::Valid => true,
+            Validity::Invalid => false,
+            Validity::Pending => return,
+        };
+        let accepted = word.clone();
+        let event = match source {
+            VerdictSource::Dictionary { .. } if valid => GameEvent::DictionaryAccepted {
+                message_id,
+                user_id,
+                word,
+            },
+            VerdictSource::Llm { .. } => GameEvent::LlmVerdict {
+                message_id,
+                user_id,
+                word,
+                valid,
+            },
+            VerdictSource::Admin { user_id: admin_id } => GameEvent::AdminOverride {
+                message_id,
+                user_id,
+                word,
+                valid,
+                admin_id,
+            },
+            _ => return,
+        };
+        self.events.publish(event);
+        let in_chain = self
+            .engine
+            .entry(message_id)
+            .is_some_and(|entry| entry.in_chain);
+        if valid && in_chain {
+            self.accept(message_id, user_id, accepted);
+        }
+    }
+
+    /// Publish the verdict a played word got right away, if it's one there's an event for
+    fn publish_verdict(&mut self, msg: &SubmitWord, submission: &Submission) {
+        let Some(word) = submission.word.clone() else {
+            return;
+        };
+        let (message_id, user_id) = (msg.message_id, msg.user_id);
+        let event = match &submission.verdict {
+            WordVerdict::ValidInDictionary => Some(GameEvent::DictionaryAccepted {
+                message_id,
+                user_id,
+                word: word.clone(),
+            }),
+            WordVerdict::ValidProperNoun => None,
+            WordVerdict::NotInDictionary | WordVerdict::Implausible => {
+                Some(GameEvent::DictionaryRejected {
+                    message_id,
+                    user_id,
+                    word: word.clone(),
+                })
+            }
+            WordVerdict::InvalidRules(violation) => Some(GameEvent::RuleViolation {
+                message_id,
+                user_id,
+                word: word.clone(),
+                violation: violation.clone(),
+            }),
+            _ => return,
+        };
+        if let Some(event) = event {
+            self.events.publish(event);
+        }
+        if matches!(
+            submission.verdict,
+            WordVerdict::ValidInDictionary | WordVerdict::ValidProperNoun
+        ) {
+            self.accept(message_id, user_id, word);
+        }
+    }
+
+    /// The state of the game for the status message
+    fn status(&self) -> UpdateStatus {
+        UpdateStatus {
+            word: self.engine.current_word(),
+            chain_length: self.engine.stats().chain_length,
+            user_id: self.engine.last_player(),
+        }
+    }
+
+    /// End the challenge if `word` is its target, announcing the winner
+    ///
+    /// Returns true when the challenge was won.
+    fn check_challenge(&mut self, word: &str, user_id: u64) -> bool {
+        if self
+            .challenge
+            .as_ref()
+            .is_none_or(|challenge| challenge.target() != word)
+        {
+            return false;
+        }
+        let Some(challenge) = self.challenge.take() else {
+            return false;
+        };
+
+        // The start word was in the chain before anyone moved
+        let moves = self.engine.stats().chain_length.saturating_sub(1);
+        info!(
+            user_id,
+            moves,
+            optimal = challenge.optimal_moves(),
+            "Challenge won"
+        );
+        if let Some(message_reaction) = &self.message_reaction {
+            message_reaction.do_send(PostMessage {
+                content: Text::ChallengeWon {
+                    user_id,
+                    moves,
+                    path: &challenge.path,
+                }
+                .render(self.language),
+            });
+        }
+        true
+    }
+
+    /// Show the new verdict of a word that showed `stale` before
+    fn replace_verdict(&self, message_id: u64, stale: Validity, fresh: Validity) {
+        let Some(message_reaction) = &self.message_reaction else {
+            return;
+        };
+        let Some(&channel_id) = self.message_channels.get(&message_id) else {
+            warn!(
+                "Don't know where message {} was posted, leaving its reactions",
+                message_id
+            );
+            return;
+        };
+        message_reaction.do_send(SetVerdict {
+            channel_id,
+            message_id,
+            validity: fresh,
+            replaces: Some(stale),
+            span: Span::current(),
+        });
+    }
+
+    /// Drop this game's words from the LLM queue, which other games share
+    fn clear_llm_queue(&self) {
+        if let (Some(llm_validator), Some(game_state)) = (&self.llm_validator, &self.address) {
+            llm_validator.do_send(ClearQueue {
+                game_state: game_state.clone(),
+            });
+        }
+    }
+
+    /// Pause or resume the game, returning false if it already was
+    ///
+    /// The time spent paused doesn't count towards the idle reset or the
+    /// bot's turn, so resuming picks up where the game left off.
+    fn set_paused(&mut self, paused: bool, now: Instant) -> bool {
+        match (paused, self.paused_at) {
+            (true, None) => {
+                info!("Game paused");
+                self.paused_at = Some(now);
+            }
+            (false, Some(paused_at)) => {
+                let paused_for = now.saturating_duration_since(paused_at);
+                info!(paused_secs = paused_for.as_secs(), "Game resumed");
+                self.paused_at = None;
+                // Resuming instead of resetting goes on with the broken chain
+                self.awaiting_reset = false;
+                self.engine.postpone_idle(paused_for);
+                if let Some(taken) = &mut self.bot_turn_taken {
+                    *taken += paused_for;
+                }
+                if let Some(turns) = &mut self.turns {
+                    turns.turn_started += paused_for;
+                }
+                for queued in &mut self.queue {
+                    queued.queued_at += paused_for;
+                }
+            }
+            _ => return false,
+        }
+        if let (Some(llm_validator), Some(game_state)) = (&self.llm_validator, &self.address) {
+            llm_validator.do_send(PauseBatches {
+                game_state: game_state.clone(),
+                paused,
+            });
+        }
+        // The chain head may have been settled while paused, e.g. by the remote dictionary
+        self.release_queue();
+        true
+    }
+
+    /// Why the player can't play right now, if it isn't their turn, they've
+    /// hit the daily limit or are cooling down
+    fn limit_verdict(&mut self, user_id: u64, now: Instant) -> Option<WordVerdict> {
+        if let Some(on_turn) = self.on_turn().filter(|&on_turn| on_turn != user_id) {
+            debug!(user_id, on_turn, "Player played out of turn");
+            return Some(WordVerdict::NotYourTurn(on_turn));
+        }
+        if let Some(limit) = self.limit_reached(user_id, Utc::now()) {
+            debug!(user_id, limit, "Player has reached the daily word limit");
+            return Some(WordVerdict::DailyLimitReached(limit));
+        }
+        if let Some(left) = self.cooldown_left(user_id, now) {
+            debug!(
+                user_id,
+                left_ms = left.as_millis() as u64,
+                "Player is cooling down"
+            );
+            return Some(WordVerdict::CoolingDown(left));
+        }
+        None
+    }
+
+    /// The player whose turn it is, None if anyone may play
+    ///
+    /// Anyone may play in rotation mode too until someone joins.
+    fn on_turn(&self) -> Option<u64> {
+        self.turns.as_ref()?.rotation.on_turn()
+    }
+
+    /// Give the turn to the next player if it was the player's
+    fn pass_turn(&mut self, user_id: u64, now: Instant) {
+        let Some(turns) = &mut self.turns else {
+            return;
+        };
+        if turns.rotation.on_turn() != Some(user_id) {
+            return;
+        }
+        let next = turns.rotation.advance();
+        debug!(user_id, ?next, "Turn passed on");
+        turns.turn_started = now;
+        self.save_rotation();
+    }
+
+    /// Give the turn to the next player if the player on turn has had it too long
+    ///
+    /// Returns true when the turn was skipped.
+    fn check_turn(&mut self, now: Instant) -> bool {
+        if self.paused_at.is_some() {
+            return false;
+        }
+        let Some(turns) = &mut self.turns else {
+            return false;
+        };
+        let Some(skip_after) = turns.skip_after else {
+            return false;
+        };
+        // A lone player keeps the turn however long they take
+        if turns.rotation.len() < 2
+            || now.saturating_duration_since(turns.turn_started) < skip_after
+        {
+            return false;
+        }
+        let (Some(skipped), Some(next)) = (turns.rotation.on_turn(), turns.rotation.advance())
+        else {
+            return false;
+        };
+        turns.turn_started = now;
+        info!(
+            skipped,
+            next,
+            skip_secs = skip_after.as_secs(),
+            "Player on turn was idle, skipping their turn"
+        );
+        self.save_rotation();
+        if let Some(message_reaction) = &self.message_reaction {
+            message_reaction.do_send(PostMessage {
+                content: Text::TurnSkipped { skipped, next }.render(self.language),
+            });
+        }
+        true
+    }
+
+    /// Keep the turn order for a restarted bot
+    fn save_rotation(&self) {
+        let Some(turns) = &self.turns else {
+            return;
+        };
+        if let Err(e) = turns.store.save(&turns.rotation) {
+            warn!(
+                path = %turns.store.path().display(),
+                error = %e,
+                "Failed to save the turn order"
+            );
+        }
+    }
+
+    /// Whether new words have to wait for the chain head's verdict in the queue
+    fn holds_words(&self) -> bool {
+        self.engine.rules().pending_policy == PendingPolicy::Queue
+            && (self.engine.head_is_pending() || !self.queue.is_empty())
+    }
+
+    /// Play a submitted word in the game, counting and scoring it
+    fn play(&mut self, msg: &SubmitWord, word: Option<String>, now: Instant) -> Submission {
+        let previous = self.engine.current_word();
+        let mut submission = Submission::new(
+            word,
+            self.engine
+                .submit_word(&msg.content, msg.user_id, msg.message_id),
+        );
+        if submission.verdict == WordVerdict::ValidInDictionary {
+            submission.source = self
+                .engine
+                .entry(msg.message_id)
+                .and_then(|entry| entry.source.clone());
+        }
+        self.remember_channel(msg.message_id, msg.channel_id);
+        // Before the milestones and records the word may bring
+        self.publish_verdict(msg, &submission);
+        match submission.verdict {
+            WordVerdict::ValidInDictionary | WordVerdict::ValidProperNoun => {
+                self.count_accepted(msg.user_id, msg.message_id, now);
+                self.pass_turn(msg.user_id, now);
+                self.announce_progress();
+            }
+            // Counted right away so pending words can't be used to exceed the limits
+            // or hold on to the turn
+            WordVerdict::PendingLlm => {
+                self.count_accepted(msg.user_id, msg.message_id, now);
+                self.pass_turn(msg.user_id, now);
+            }
+            _ => {}
+        }
+        if let (
+            Some(word),
+            WordVerdict::ValidInDictionary | WordVerdict::ValidProperNoun | WordVerdict::PendingLlm,
+        ) = (&submission.word, &submission.verdict)
+        {
+            self.score_word(msg, word, previous.as_deref());
+        }
+        if let (Some(word), WordVerdict::ValidInDictionary | WordVerdict::ValidProperNoun) =
+            (&submission.word, &submission.verdict)
+        {
+            self.check_challenge(word, msg.user_id);
+        }
+        // Messages that weren't words at all aren't attempts
+        let outcome = submission
+            .word
+            .as_ref()
+            .and_then(|_| Self::attempt_outcome(&submission.verdict));
+        if let Some(outcome) = outcome {
+            self.record_attempt(msg.user_id, outcome);
+        }
+        let breaks_chain = match &submission.verdict {
+            WordVerdict::NotInDictionary | WordVerdict::Implausible => true,
+            WordVerdict::InvalidRules(_) => {
+                self.chain_breaks.is_some_and(|breaks| breaks.violations)
+            }
+            _ => false,
+        };
+        if let (true, Some(word)) = (breaks_chain, &submission.word) {
+            self.check_break(word, msg.user_id);
+        }
+        submission
+    }
+
+    /// Play a queued word now, handing its submission to whoever waits for it
+    fn release(&mut self, queued: QueuedWord) {
+        let QueuedWord {
+            submit, release, ..
+        } = queued;
+        let _entered = submit.span.enter();
+        let now = Instant::now();
+        let word = self.engine.extract(&submit.content);
+        let submission = match self.limit_verdict(submit.user_id, now) {
+            Some(verdict) => Submission::new(word, verdict),
+            None => self.play(&submit, word, now),
+        };
+        debug!(verdict = ?submission.verdict, "Played queued word");
+        self.audit.record(
+            submit.message_id,
+            AuditStep::Verdict(submission.verdict.clone()),
+        );
+        if release.send(submission).is_err() {
+            warn!(
+                message_id = submit.message_id,
+                "Nobody is waiting for the queued word anymore"
+            );
+        }
+    }
+
+    /// Play queued words in order until the chain head has to wait for the LLM again
+    fn release_queue(&mut self) {
+        if self.paused_at.is_some() {
+            return;
+        }
+        while !self.engine.head_is_pending() {
+            let Some(queued) = self.queue.pop_front() else {
+                break;
+            };
+            self.release(queued);
+        }
+    }
+
+    /// Play the words that have waited `queue_timeout` against the pending
+    /// chain head anyway, along with the words queued before them
+    fn release_expired(&mut self, now: Instant) {
+        if self.paused_at.is_some() {
+            return;
+        }
+        let Some(last) = self.queue.iter().rposition(|queued| {
+            now.saturating_duration_since(queued.queued_at) >= self.queue_timeout
+        }) else {
+            return;
+        };
+        info!(
+            words = last + 1,
+            timeout_secs = self.queue_timeout.as_secs(),
+            "Queued words waited too long for the LLM, playing them anyway"
+        );
+        let expired: Vec<QueuedWord> = self.queue.drain(..=last).collect();
+        for queued in expired {
+            self.release(queued);
+        }
+        self.release_queue();
+    }
+
+    /// Reset the game if nothing has been accepted within the idle period
+    ///
+    /// Returns true when the game was reset.
+    fn check_idle(&mut self, now: Instant) -> bool {
+        let Some(idle_reset) = self.idle_reset else {
+            return false;
+        };
+        if self.paused_at.is_some() {
+            return false;
+        }
+        if !self.engine.is_idle(now, idle_reset) {
+            return false;
+        }
+
+        info!(
+            "No accepted words in {} seconds, resetting the game",
+            idle_reset.as_secs()
+        );
+        self.clean_up_reactions();
+        self.engine.reset();
+        self.challenge = None;
+        self.tally = ChainTally::default();
+        self.announce_progress();
+        self.events.publish(GameEvent::GameReset { idle: true });
+
+        self.clear_llm_queue();
+        self.release_queue();
+        if let Some(message_reaction) = &self.message_reaction {
+            message_reaction.do_send(PostMessage {
+                content: Text::IdleReset { period: idle_reset }.render(self.language),
+            });
+        }
+
+        true
+    }
+
+    /// Decide the bot's turn if nothing has been accepted within its idle period
+    ///
+    /// The bot doesn't answer its own word, and takes one turn at most after
+    /// each accepted word, so a dead end is only announced once.
+    fn bot_turn(&mut self, now: Instant) -> Option<BotTurn> {
+        let after = self.bot_turn_after?;
+        let bot_user_id = self.bot_user_id?;
+        if self.paused_at.is_some() {
+            return None;
+        }
+        let accepted_at = self.engine.last_accepted_at()?;
+        if !self.engine.is_idle(now, after)
+            || self.bot_turn_taken == Some(accepted_at)
+            || self.engine.last_player() == Some(bot_user_id)
+        {
+            return None;
+        }
+        let continuations = self.engine.continuations(bot_user_id)?;
+        let current = self.engine.current_word()?;
+        self.bot_turn_taken = Some(accepted_at);
+
+        if continuations.is_empty() {
+            info!("No dictionary word continues from '{}'", current);
+            return Some(BotTurn::DeadEnd(current));
+        }
+        // A fresh RandomState is randomly keyed, which is random enough to pick a word
+        let index = RandomState::new().hash_one(continuations.len()) as usize % continuations.len();
+        let word = continuations[index].clone();
+        info!(
+            "No accepted words in {} seconds, the bot plays '{}'",
+            after.as_secs(),
+            word
+        );
+        Some(BotTurn::Play(word))
+    }
+
+    /// Post the bot's turn if it's time for one
+    ///
+    /// A played word is registered with [`RegisterBotWord`] once it's posted.
+    fn take_bot_turn(&mut self, ctx: &mut Context<Self>) {
+        let Some(message_reaction) = self.message_reaction.clone() else {
+            return;
+        };
+        match self.bot_turn(Instant::now()) {
+            None => {}
+            Some(BotTurn::DeadEnd(word)) => message_reaction.do_send(PostMessage {
+                content: Text::DeadEnd { word: &word }.render(self.language),
+            }),
+            Some(BotTurn::Play(word)) => {
+                let game_state = ctx.address();
+                actix::spawn(async move {
+                    match message_reaction
+                        .send(PostMessage {
+                            content: word.clone(),
+                        })
+                        .await
+                    {
+                        Ok(Some(posted)) => game_state.do_send(RegisterBotWord { word, posted }),
+                        Ok(None) => {}
+                        Err(e) => warn!(error = %e, "Failed to post the bot's word"),
+                    }
+                });
+            }
+        }
+    }
+
+    /// Post the final standings and reset the weekly scores if the week has ended
+    ///
+    /// The first check goes on from the week of the saved game, so a week that
+    /// ended while the bot was down ends too. A week that is already in the
+    /// archive, e.g. because the bot restarted right after posting it, isn't
+    /// archived or posted again. Returns true when the weekly scores were reset.
+    fn check_week(&mut self, now: DateTime<Utc>) -> bool {
+        let Some(weekly) = &mut self.weekly_reset else {
+            return false;
+        };
+        let start = weekly.schedule.week_start(now);
+        let Some(ended) = weekly
+            .week_start
+            .replace(start)
+            .or_else(|| self.saved_week_start.take())
+        else {
+            return false;
+        };
+        if ended == start {
+            return false;
+        }
+
+        let week = week_id(&weekly.schedule, ended);
+        let standings = self.scoreboard.end_week();
+        info!(
+            week,
+            players = standings.len(),
+            "Week ended, resetting weekly scores"
+        );
+
+        match weekly.archive.last_week() {
+            Ok(Some(last)) if last == week => {
+                info!(week, "Week already archived, not posting it again");
+                return true;
+            }
+            Ok(_) => {}
+            Err(e) => warn!(error = %e, "Failed to read the weekly archive"),
+        }
+        if let Err(e) = weekly
+            .archive
+            .append(&ArchivedWeek::new(&week, now, &standings))
+        {
+            warn!(
+                path = %weekly.archive.path().display(),
+                error = %e,
+                "Failed to archive the weekly standings"
+            );
+        }
+
+        if standings.is_empty() {
+            return true;
+        }
+        let number = ended
+            .with_timezone(&weekly.schedule.timezone)
+            .iso_week()
+            .week();
+        let announcement = PostEmbed {
+            title: Text::WeekEnded { week: number }.render(self.language),
+            description: Text::Leaderboard(&standings).render(self.language),
+        };
+        // The check at startup runs before the channel is known
+        match &self.message_reaction {
+            Some(message_reaction) => message_reaction.do_send(announcement),
+            None => self.week_announcement = Some(announcement),
+        }
+
+        true
+    }
+}
+
+impl Actor for GameStateActor {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.set_mailbox_capacity(self.mailbox_capacity);
+        self.address = Some(ctx.address());
+        // A game restored paused holds the LLM batches like a /pause would
+        if let (Some(_), Some(llm_validator)) = (self.paused_at, &self.llm_validator) {
+            llm_validator.do_send(PauseBatches {
+                game_state: ctx.address(),
+                paused: true,
+            });
+        }
+        if let Some(idle_reset) = self.idle_reset {
+            ctx.run_interval(idle_reset.min(IDLE_CHECK_INTERVAL), |act, _ctx| {
+                act.check_idle(Instant::now());
+            });
+        }
+        if let Some(after) = self.bot_turn_after {
+            ctx.run_interval(after.min(IDLE_CHECK_INTERVAL), |act, ctx| {
+                act.take_bot_turn(ctx);
+            });
+        }
+        if let Some(skip_after) = self.turns.as_ref().and_then(|turns| turns.skip_after) {
+            ctx.run_interval(skip_after.min(IDLE_CHECK_INTERVAL), |act, _ctx| {
+                act.check_turn(Instant::now());
+            });
+        }
+        if self.engine.rules().pending_policy == PendingPolicy::Queue {
+            ctx.run_interval(self.queue_timeout.min(QUEUE_CHECK_INTERVAL), |act, _ctx| {
+                act.release_expired(Instant::now());
+            });
+        }
+        if self.weekly_reset.is_some() {
+            self.check_week(Utc::now());
+            ctx.run_interval(WEEK_CHECK_INTERVAL, |act, _ctx| {
+                act.check_week(Utc::now());
+            });
+        }
+    }
+}
+
+impl Handler<RegisterWord> for GameStateActor {
+    type Result = bool;
+
+    fn handle(&mut self, msg: RegisterWord, _ctx: &mut Context<Self>) -> Self::Result {
+        debug!(
+            "Registering word '{}' (message ID: {})",
+            msg.word, msg.message_id
+        );
+
+        self.engine
+            .register_word(&msg.word, msg.user_id, msg.message_id);
+
+        // Return true as acknowledgment
+        true
+    }
+}
+
+impl Handler<ValidateGameRules> for GameStateActor {
+    type Result = bool;
+
+    fn handle(&mut self, msg: ValidateGameRules, _ctx: &mut Context<Self>) -> Self::Result {
+        debug!("Validating game rules for word: '{}'", msg.word);
+        self.engine.check_rules(&msg.word, None).is_valid()
+    }
+}
+
+impl Handler<SubmitWord> for GameStateActor {
+    type Result = MessageResult<SubmitWord>;
+
+    fn handle(&mut self, msg: SubmitWord, _ctx: &mut Context<Self>) -> Self::Result {
+        let span = msg.span.clone();
+        let _entered = span.enter();
+        let message_id = msg.message_id;
+        let submission = self.submit(msg);
+        self.audit
+            .record(message_id, AuditStep::Verdict(submission.verdict.clone()));
+        MessageResult(submission)
+    }
+}
+
+impl GameStateActor {
+    /// Play the word in a message, or hold it for the chain head's verdict
+    fn submit(&mut self, msg: SubmitWord) -> Submission {
+        let now = Instant::now();
+        let word = self.engine.extract(&msg.content);
+        self.audit
+            .record(msg.message_id, AuditStep::Normalized(word.clone()));
+        if let Some(word) = &word {
+            let known = self.engine.dictionary().is_valid_word(word);
+            self.audit
+                .record(msg.message_id, AuditStep::Dictionary(known));
+        }
+        // Only the word is logged, the rest of the message may be chat
+        match &word {
+            Some(word) => debug!("Submitting '{}' (message ID: {})", word, msg.message_id),
+            None => debug!(
+                message_id = msg.message_id,
+                "Submitting a message with no word"
+            ),
+        }
+        if self.paused_at.is_some() {
+            debug!("Game is paused, ignoring the word");
+            return Submission::new(word, WordVerdict::Paused);
+        }
+        // Checked before the word is played, so it isn't used up
+        if word.is_some() {
+            if let Some(verdict) = self.limit_verdict(msg.user_id, now) {
+                return Submission::new(word, verdict);
+            }
+        }
+
+        if word.is_some() && self.holds_words() {
+            if self.queue.len() >= self.queue_size {
+                debug!(
+                    queued = self.queue.len(),
+                    "Queue is full, the word has to wait"
+                );
+                return Submission::new(word, WordVerdict::AwaitingVerdict);
+            }
+            debug!(
+                queued = self.queue.len(),
+                "Holding the word until the chain head has its verdict"
+            );
+            let (release, released) = oneshot::channel();
+            self.queue.push_back(QueuedWord {
+                submit: msg,
+                queued_at: now,
+                release,
+            });
+            return Submission {
+                word,
+                verdict: WordVerdict::Queued,
+                source: None,
+                released: Some(released),
+            };
+        }
+
+        self.play(&msg, word, now)
+    }
+}
+
+impl Handler<PreviewWord> for GameStateActor {
+    type Result = MessageResult<PreviewWord>;
+
+    fn handle(&mut self, msg: PreviewWord, _ctx: &mut Context<Self>) -> Self::Result {
+        debug!("Dry-run validation for word: '{}'", msg.content);
+
+        let verdict = self.engine.check_word(&msg.content, msg.user_id);
+        if let (Some(user_id), WordVerdict::ValidInDictionary) = (msg.user_id, &verdict) {
+            if let Some(on_turn) = self.on_turn().filter(|&on_turn| on_turn != user_id) {
+                return MessageResult(WordVerdict::NotYourTurn(on_turn));
+            }
+            if let Some(limit) = self.limit_reached(user_id, Utc::now()) {
+                return MessageResult(WordVerdict::DailyLimitReached(limit));
+            }
+            if let Some(left) = self.cooldown_left(user_id, Instant::now()) {
+                return MessageResult(WordVerdict::CoolingDown(left));
+            }
+        }
+        MessageResult(verdict)
+    }
+}
+
+impl Handler<ProcessWord> for GameStateActor {
+    type Result = MessageResult<ProcessWord>;
+
+    fn handle(&mut self, msg: ProcessWord, _ctx: &mut Context<Self>) -> Self::Result {
+        debug!(
+            "Processing word '{}' (message ID: {})",
+            msg.word, msg.message_id
+        );
+
+        let outcome = self.engine.process_word(
+            &msg.word,
+            msg.user_id,
+            msg.message_id,
+            msg.in_dictionary.then(|| VerdictSource::Dictionary {
+                file: self
+                    .engine
+                    .dictionary()
+                    .lookup(&msg.word)
+                    .map(|found| found.source.name.clone()),
+            }),
+        );
+        self.remember_channel(msg.message_id, msg.channel_id);
+        MessageResult(outcome)
+    }
+}
+
+impl Handler<CheckWord> for GameStateActor {
+    type Result = MessageResult<CheckWord>;
+
+    fn handle(&mut self, msg: CheckWord, _ctx: &mut Context<Self>) -> Self::Result {
+        debug!("Dry-run rules check for word: '{}'", msg.word);
+
+        MessageResult(self.engine.evaluate(&msg.word, msg.user_id))
+    }
+}
+
+impl Handler<GetRules> for GameStateActor {
+    type Result = MessageResult<GetRules>;
+
+    fn handle(&mut self, _msg: GetRules, _ctx: &mut Context<Self>) -> Self::Result {
+        MessageResult(self.engine.rules_info())
+    }
+}
+
+impl Handler<GetDictionaryStats> for GameStateActor {
+    type Result = MessageResult<GetDictionaryStats>;
+
+    fn handle(&mut self, _msg: GetDictionaryStats, _ctx: &mut Context<Self>) -> Self::Result {
+        let dictionary = self.engine.dictionary();
+        MessageResult(DictionaryStats {
+            words: dictionary.len(),
+            source: dictionary.source().clone(),
+        })
+    }
+}
+
+impl Handler<LookupWord> for GameStateActor {
+    type Result = MessageResult<LookupWord>;
+
+    fn handle(&mut self, msg: LookupWord, _ctx: &mut Context<Self>) -> Self::Result {
+        MessageResult(WordLookup {
+            normalized: normalize_word(&msg.word),
+            found: self.engine.dictionary().lookup(&msg.word),
+        })
+    }
+}
+
+impl Handler<GetRandomWord> for GameStateActor {
+    type Result = Option<String>;
+
+    fn handle(&mut self, _msg: GetRandomWord, _ctx: &mut Context<Self>) -> Self::Result {
+        self.engine.dictionary().random_word().map(str::to_string)
+    }
+}
+
+impl Handler<GetDictionary> for GameStateActor {
+    type Result = Arc<DictionaryValidator>;
+
+    fn handle(&mut self, _msg: GetDictionary, _ctx: &mut Context<Self>) -> Self::Result {
+        self.engine.shared_dictionary()
+    }
+}
+
+impl Handler<GetHistory> for GameStateActor {
+    type Result = MessageResult<GetHistory>;
+
+    fn handle(&mut self, msg: GetHistory, _ctx: &mut Context<Self>) -> Self::Result {
+        MessageResult(self.engine.history(msg.limit))
+    }
+}
+
+impl Handler<GetLastValidWord> for GameStateActor {
+    type Result = Option<String>;
+
+    fn handle(&mut self, _msg: GetLastValidWord, _ctx: &mut Context<Self>) -> Self::Result {
+        self.engine.last_valid_word().map(str::to_string)
+    }
+}
+
+impl Handler<GetPreviousWord> for GameStateActor {
+    type Result = Option<String>;
+
+    fn handle(&mut self, msg: GetPreviousWord, _ctx: &mut Context<Self>) -> Self::Result {
+        self.engine.previous_word(msg.message_id)
+    }
+}
+
+impl Handler<MarkWordValidity> for GameStateActor {
+    type Result = bool;
+
+    fn handle(&mut self, msg: MarkWordValidity, _ctx: &mut Context<Self>) -> Self::Result {
+        // Pending words in the chain were counted towards the player's limits
+        let counted = self
+            .engine
+            .entry(msg.message_id)
+            .filter(|entry| entry.in_chain && entry.validity == Validity::Pending)
+            .cloned();
+        // Pending words rejected by the LLM aren't proper nouns either, maybe a typo
+        let rejected = self
+            .engine
+            .entry(msg.message_id)
+            .filter(|entry| {
+                entry.validity == Validity::Pending && msg.validity == Validity::Invalid
+            })
+            .map(|entry| entry.word.clone());
+        let settled_user = self
+            .engine
+            .entry(msg.message_id)
+            .filter(|entry| entry.validity == Validity::Pending)
+            .map(|entry| entry.user_id);
+        // The source is kept with the word, so the attempt is worked out first
+        let outcome = match (msg.validity, &msg.source) {
+            (Validity::Valid, VerdictSource::Dictionary { .. }) => Some(AttemptOutcome::Dictionary),
+            (Validity::Valid, _) => Some(AttemptOutcome::ProperNoun),
+            (Validity::Invalid, _) => Some(AttemptOutcome::NotAWord),
+            (Validity::Pending, _) => None,
+        };
+        let settled = AuditStep::Settled {
+            validity: msg.validity,
+            source: msg.source.clone(),
+        };
+        let played = self
+            .engine
+            .entry(msg.message_id)
+            .map(|entry| (entry.word.clone(), entry.user_id));
+        let source = msg.source.clone();
+        let Some(invalidated) = self
+            .engine
+            .mark_validity(msg.message_id, msg.validity, msg.source)
+        else {
+            return false;
+        };
+        self.audit.record(msg.message_id, settled);
+        if let Some((word, user_id)) = played {
+            self.publish_settled(msg.message_id, user_id, word, msg.validity, source);
+        }
+        if let (Some(user_id), Some(outcome)) = (settled_user, outcome) {
+            self.record_attempt(user_id, outcome);
+        }
+        if let (Some(word), Validity::Invalid) = (&counted, msg.validity) {
+            self.refund_accepted(word, Utc::now());
+        }
+        self.scoreboard
+            .settle(msg.message_id, msg.validity == Validity::Valid);
+        for word in &invalidated {
+            if self.scoreboard.revoke(word.message_id) {
+                debug!(
+                    message_id = word.message_id,
+                    "Revoked points of invalidated word"
+                );
+            }
+            let counted = self
+                .engine
+                .entry(word.message_id)
+                .filter(|_| word.previous == Validity::Valid);
+            if let Some(entry) = counted {
+                self.tally.remove(entry.user_id);
+            }
+        }
+        if msg.validity == Validity::Valid || !invalidated.is_empty() {
+            self.announce_progress();
+        }
+
+        if let (Some(message_reaction), Some(word)) = (&self.message_reaction, &rejected) {
+            let suggestions = self.suggestions(word);
+            if !suggestions.is_empty() {
+                if let Some(&channel_id) = self.message_channels.get(&msg.message_id) {
+                    message_reaction.do_send(Reply {
+                        channel_id,
+                        message_id: msg.message_id,
+                        content: Text::Suggestions(&suggestions).render(self.language),
+                    });
+                }
+            }
+        }
+
+        // Words built on a rolled back word get their reactions replaced with ❌
+        for word in invalidated {
+            self.replace_verdict(word.message_id, word.previous, Validity::Invalid);
+        }
+
+        // Only now the rollback is done, so the summary counts what's left of the chain
+        if let (Some(word), Some(user_id)) = (&rejected, settled_user) {
+            self.check_break(word, user_id);
+        }
+
+        // Words held for this word can be judged against the settled chain now
+        self.release_queue();
+        true
+    }
+}
+
+impl Handler<DisputeVote> for GameStateActor {
+    type Result = Option<Validity>;
+
+    fn handle(&mut self, msg: DisputeVote, _ctx: &mut Context<Self>) -> Self::Result {
+        let disputes = self.disputes.as_mut()?;
+        let entry = self.engine.entry(msg.message_id)?;
+        // Players don't judge their own words, and the bot doesn't vote
+        if entry.user_id == msg.user_id || self.bot_user_id == Some(msg.user_id) {
+            return None;
+        }
+        // Broken rules are final, as are the bot's own words
+        if matches!(entry.source, None | Some(VerdictSource::Seed))
+            || !disputes.rules().disputes(msg.reaction, entry.validity)
+        {
+            return None;
+        }
+        let settled_at = entry.settled_at?;
+        let (player, word) = (entry.user_id, entry.word.clone());
+
+        let engine = &self.engine;
+        disputes.forget_words(|message_id| engine.in_history(message_id));
+        let votes = match disputes.vote(
+            msg.message_id,
+            msg.user_id,
+            msg.added,
+            settled_at,
+            SystemTime::now(),
+        ) {
+            VoteOutcome::Overturned(votes) => votes,
+            outcome => {
+                debug!(
+                    message_id = msg.message_id,
+                    ?outcome,
+                    "Counted dispute vote"
+                );
+                return None;
+            }
+        };
+
+        let source = VerdictSource::Vote { votes };
+        let validity = self.engine.overturn(msg.message_id, source.clone())?;
+        self.audit
+            .record(msg.message_id, AuditStep::Settled { validity, source });
+        info!(word, votes, ?validity, "Players overturned a verdict");
+        match validity {
+            Validity::Valid => {
+                if self
+                    .engine
+                    .entry(msg.message_id)
+                    .is_some_and(|entry| entry.in_chain)
+                {
+                    let previous = self.engine.previous_word(msg.message_id);
+                    let points = self.scoring.score(&word, previous.as_deref());
+                    self.scoreboard.award(player, msg.message_id, points);
+                }
+                self.replace_verdict(msg.message_id, Validity::Invalid, Validity::Valid);
+            }
+            _ => {
+                self.scoreboard.revoke(msg.message_id);
+                self.replace_verdict(msg.message_id, Validity::Valid, Validity::Invalid);
+            }
+        }
+        self.announce_progress();
+        Some(validity)
+    }
+}
+
+impl Handler<SuggestWords> for GameStateActor {
+    type Result = MessageResult<SuggestWords>;
+
+    fn handle(&mut self, msg: SuggestWords, _ctx: &mut Context<Self>) -> Self::Result {
+        MessageResult(self.suggestions(&msg.word))
+    }
+}
+
+impl Handler<GetLeaderboard> for GameStateActor {
+    type Result = MessageResult<GetLeaderboard>;
+
+    fn handle(&mut self, msg: GetLeaderboard, _ctx: &mut Context<Self>) -> Self::Result {
+        MessageResult(self.scoreboard.leaderboard(msg.period, msg.limit))
+    }
+}
+
+impl Handler<RecordAttempt> for GameStateActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: RecordAttempt, _ctx: &mut Context<Self>) -> Self::Result {
+        self.record_attempt(msg.user_id, msg.outcome);
+    }
+}
+
+impl Handler<GetAttempts> for GameStateActor {
+    type Result = MessageResult<GetAttempts>;
+
+    fn handle(&mut self, msg: GetAttempts, _ctx: &mut Context<Self>) -> Self::Result {
+        MessageResult(self.attempts.get(&msg.user_id).copied().unwrap_or_default())
+    }
+}
+
+impl Handler<GetStanding> for GameStateActor {
+    type Result = MessageResult<GetStanding>;
+
+    fn handle(&mut self, msg: GetStanding, _ctx: &mut Context<Self>) -> Self::Result {
+        MessageResult(self.scoreboard.standing(msg.user_id))
+    }
+}
+
+impl Handler<PlanChallenge> for GameStateActor {
+    type Result = ResponseFuture<Result<ChallengePlan>>;
+
+    fn handle(&mut self, msg: PlanChallenge, _ctx: &mut Context<Self>) -> Self::Result {
+        let from = normalize_word(&msg.from);
+        let to = normalize_word(&msg.to);
+        let dictionary = self.engine.shared_dictionary();
+        let policy = self.engine.rules().diacritic_policy;
+
+        Box::pin(async move {
+            for word in [&from, &to] {
+                if !dictionary.contains(word) {
+                    return Ok(ChallengePlan::NotInDictionary(word.clone()));
+                }
+            }
+            if from == to {
+                return Ok(ChallengePlan::SameWord);
+            }
+
+            let search = tokio::task::spawn_blocking(move || {
+                shortest_path(&from, &to, &dictionary, policy, CHALLENGE_SEARCH_LIMIT)
+            })
+            .await
+            .map_err(|e| Error::TaskPanic(e.to_string()))?;
+            Ok(match search {
+                PathSearch::Found(path) => ChallengePlan::Ready(path),
+                PathSearch::NoPath => ChallengePlan::NoPath,
+                PathSearch::LimitReached(words) => ChallengePlan::TooFar(words),
+            })
+        })
+    }
+}
+
+impl Handler<StartChallenge> for GameStateActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: StartChallenge, _ctx: &mut Context<Self>) -> Self::Result {
+        let (Some(from), Some(to)) = (msg.path.first(), msg.path.last()) else {
+            return;
+        };
+        info!(from = %from, to = %to, "Starting a challenge");
+
+        self.engine.start_from(from);
+        self.tally = ChainTally::default();
+        self.clear_llm_queue();
+        if let Some(message_reaction) = &self.message_reaction {
+            message_reaction.do_send(PostMessage {
+                content: Text::ChallengeStarted { path: &msg.path }.render(self.language),
+            });
+        }
+        self.challenge = Some(Challenge { path: msg.path });
+        self.announce_progress();
+        self.release_queue();
+    }
+}
+
+impl Handler<GiveUpChallenge> for GameStateActor {
+    type Result = Option<Vec<String>>;
+
+    fn handle(&mut self, _msg: GiveUpChallenge, _ctx: &mut Context<Self>) -> Self::Result {
+        let challenge = self.challenge.take()?;
+        info!(target_word = %challenge.target(), "Challenge given up");
+        Some(challenge.path)
+    }
+}
+
+impl Handler<ResetGame> for GameStateActor {
+    type Result = ();
+
+    fn handle(&mut self, _msg: ResetGame, _ctx: &mut Context<Self>) -> Self::Result {
+        self.clean_up_reactions();
+        self.engine.reset();
+        self.challenge = None;
+        self.tally = ChainTally::default();
+        self.announce_progress();
+        self.events.publish(GameEvent::GameReset { idle: false });
+        if self.awaiting_reset {
+            self.set_paused(false, Instant::now());
+        }
+        if let Some(turns) = &mut self.turns {
+            turns.rotation = Rotation::default();
+            self.save_rotation();
+        }
+        self.release_queue();
+
+        info!("Game state has been reset");
+    }
+}
+
+impl Handler<JoinRotation> for GameStateActor {
+    type Result = Option<bool>;
+
+    fn handle(&mut self, msg: JoinRotation, _ctx: &mut Context<Self>) -> Self::Result {
+        let turns = self.turns.as_mut()?;
+        if !turns.rotation.join(msg.user_id) {
+            return Some(false);
+        }
+        if turns.rotation.len() == 1 {
+            // The first player gets the whole turn from now on
+            turns.turn_started = Instant::now();
+        }
+        info!(
+            user_id = msg.user_id,
+            players = turns.rotation.len(),
+            "Player joined the turn order"
+        );
+        self.save_rotation();
+        Some(true)
+    }
+}
+
+impl Handler<LeaveRotation> for GameStateActor {
+    type Result = Option<bool>;
+
+    fn handle(&mut self, msg: LeaveRotation, _ctx: &mut Context<Self>) -> Self::Result {
+        let turns = self.turns.as_mut()?;
+        let had_turn = turns.rotation.on_turn() == Some(msg.user_id);
+        if !turns.rotation.leave(msg.user_id) {
+            return Some(false);
+        }
+        if had_turn {
+            turns.turn_started = Instant::now();
+        }
+        info!(
+            user_id = msg.user_id,
+            players = turns.rotation.len(),
+            "Player left the turn order"
+        );
+        self.save_rotation();
+        Some(true)
+    }
+}
+
+impl Handler<GetTurnOrder> for GameStateActor {
+    type Result = Option<Vec<u64>>;
+
+    fn handle(&mut self, _msg: GetTurnOrder, _ctx: &mut Context<Self>) -> Self::Result {
+        Some(self.turns.as_ref()?.rotation.order())
+    }
+}
+
+impl Handler<SetCurrentWord> for GameStateActor {
+    type Result = MessageResult<SetCurrentWord>;
+
+    fn handle(&mut self, msg: SetCurrentWord, _ctx: &mut Context<Self>) -> Self::Result {
+        let Some(word) = self.engine.extract(&msg.word) else {
+            return MessageResult(SetWordOutcome::NotAWord);
+        };
+        if !msg.force && self.engine.dictionary().lookup(&word).is_none() {
+            return MessageResult(SetWordOutcome::NotInDictionary(word));
+        }
+        info!(word = %word, force = msg.force, "Setting the current word");
+
+        // Words waiting for the LLM were played against the old word, so
+        // they're rejected and whatever they counted towards is taken back
+        let dropped = self.engine.set_current_word(&word);
+        self.clear_llm_queue();
+        let now = Utc::now();
+        for entry in &dropped {
+            if entry.in_chain {
+                self.refund_accepted(entry, now);
+            }
+            self.scoreboard.settle(entry.message_id, false);
+            self.replace_verdict(entry.message_id, Validity::Pending, Validity::Invalid);
+        }
+        if !dropped.is_empty() {
+            info!(count = dropped.len(), "Rejected words waiting for the LLM");
+        }
+
+        if let Some(message_reaction) = &self.message_reaction {
+            message_reaction.do_send(PostMessage {
+                content: Text::WordSet { word: &word }.render(self.language),
+            });
+        }
+        self.announce_progress();
+        self.release_queue();
+        MessageResult(SetWordOutcome::Set {
+            word,
+            dropped: dropped.len(),
+        })
+    }
+}
+
+impl Handler<SetPaused> for GameStateActor {
+    type Result = bool;
+
+    fn handle(&mut self, msg: SetPaused, _ctx: &mut Context<Self>) -> Self::Result {
+        self.set_paused(msg.0, Instant::now())
+    }
+}
+
+impl Handler<IsPaused> for GameStateActor {
+    type Result = bool;
+
+    fn handle(&mut self, _msg: IsPaused, _ctx: &mut Context<Self>) -> Self::Result {
+        self.paused_at.is_some()
+    }
+}
+
+impl Handler<GetRestoredPlayers> for GameStateActor {
+    type Result = Option<usize>;
+
+    fn handle(&mut self, _msg: GetRestoredPlayers, _ctx: &mut Context<Self>) -> Self::Result {
+        self.restored_players
+    }
+}
+
+impl Handler<SetBotUser> for GameStateActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: SetBotUser, _ctx: &mut Context<Self>) -> Self::Result {
+        self.bot_user_id = Some(msg.0);
+    }
+}
+
+impl Handler<RegisterBotWord> for GameStateActor {
+    type Result = MessageResult<RegisterBotWord>;
+
+    fn handle(&mut self, msg: RegisterBotWord, _ctx: &mut Context<Self>) -> Self::Result {
+        let Some(bot_user_id) = self.bot_user_id else {
+            return MessageResult(RuleOutcome::RuleViolation(Violation::Other(
+                "the bot's user ID is not known".to_string(),
+            )));
+        };
+        let PostedMessage {
+            channel_id,
+            message_id,
+        } = msg.posted;
+
+        // Someone may have played while the word was being posted
+        let outcome = self.engine.process_word(
+            &msg.word,
+            bot_user_id,
+            message_id,
+            Some(VerdictSource::Seed),
+        );
+        self.remember_channel(message_id, channel_id);
+        let validity = if outcome.is_valid() {
+            self.announce_progress();
+            self.check_challenge(&msg.word, bot_user_id);
+            Validity::Valid
+        } else {
+            info!("The bot's word '{}' no longer fits the chain", msg.word);
+            Validity::Invalid
+        };
+        if let Some(message_reaction) = &self.message_reaction {
+            message_reaction.do_send(SetVerdict {
+                channel_id,
+                message_id,
+                validity,
+                replaces: None,
+                span: Span::current(),
+            });
+        }
+        MessageResult(outcome)
+    }
+}
+
+impl Handler<VerdictDelivered> for GameStateActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: VerdictDelivered, _ctx: &mut Context<Self>) -> Self::Result {
+        let delivery = if msg.delivered {
+            Delivery::Delivered
+        } else {
+            Delivery::Failed
+        };
+        self.engine
+            .mark_delivery(msg.message_id, msg.validity, delivery);
+    }
+}
+
+impl Handler<RedeliverVerdicts> for GameStateActor {
+    type Result = usize;
+
+    fn handle(&mut self, _msg: RedeliverVerdicts, _ctx: &mut Context<Self>) -> Self::Result {
+        let Some(message_reaction) = &self.message_reaction else {
+            return 0;
+        };
+        let mut redelivered = 0;
+        for entry in self.engine.undelivered() {
+            let Some(&channel_id) = self.message_channels.get(&entry.message_id) else {
+                continue;
+            };
+            // A word the LLM settled may still show its ❓, if removing it failed too
+            let replaces = matches!(entry.source, Some(VerdictSource::Llm { .. }))
+                .then_some(Validity::Pending);
+            message_reaction.do_send(SetVerdict {
+                channel_id,
+                message_id: entry.message_id,
+                validity: entry.validity,
+                replaces,
+                span: Span::current(),
+            });
+            self.engine
+                .mark_delivery(entry.message_id, entry.validity, Delivery::Sending);
+            redelivered += 1;
+        }
+        if redelivered > 0 {
+            info!(
+                count = redelivered,
+                "Setting the verdict reactions that failed again"
+            );
+        }
+        redelivered
+    }
+}
+
+impl Handler<SetMessageReaction> for GameStateActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: SetMessageReaction, _ctx: &mut Context<Self>) -> Self::Result {
+        // A status message left from before a restart would show the old game
+        msg.0.do_send(self.status());
+        if let Some(announcement) = self.week_announcement.take() {
+            msg.0.do_send(announcement);
+        }
+        self.message_reaction = Some(msg.0);
+    }
+}
+
+impl Handler<Ping> for GameStateActor {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Ping, _ctx: &mut Context<Self>) -> Self::Result {}
+}
+
+impl Handler<Shutdown> for GameStateActor {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Shutdown, _ctx: &mut Context<Self>) -> Self::Result {
+        self.save_rotation();
+        let Some(store) = &self.snapshot else {
+            return;
+        };
+        let snapshot = GameSnapshot {
+            word: self.engine.last_valid_word().map(str::to_string),
+            week_start: self
+                .weekly_reset
+                .as_ref()
+                .and_then(|weekly| weekly.week_start),
+            scores: self
+                .scoreboard
+                .totals()
+                .into_iter()
+                .map(|(user_id, all_time, weekly)| SavedScore {
+                    user_id,
+                    all_time,
+                    weekly,
+                })
+                .collect(),
+            paused: self.paused_at.is_some(),
+        };
+        match store.save(&snapshot) {
+            Ok(()) => info!(word = ?snapshot.word, "Saved the game"),
+            Err(e) => warn!(
+                path = %store.path().display(),
+                error = %e,
+                "Failed to save the game"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::message_reaction::{EMOJI_CHECK, EMOJI_CROSS, EMOJI_FINISH, EMOJI_QUESTION};
+    use crate::actors::testing::{ReactionCall, RecordingReactionApi, FIRST_POSTED_ID};
+    use poise::serenity_prelude as serenity;
+    use std::sync::Arc;
+
+    fn by_llm() -> VerdictSource {
+        VerdictSource::Llm {
+            explanation: "Nimi".to_string(),
+            model: Some("gemini-pro".to_string()),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_process_word_interleaved() {
+        let game_state = GameStateActor::new().start();
+
+        // Queue every word before awaiting any response, as rapid Discord messages would
+        let words = ["kissa", "kassa", "kassi", "kissa", "koira", "kasvi"];
+        let requests: Vec<_> = words
+            .iter()
+            .enumerate()
+            .map(|(i, word)| {
+                game_state.send(ProcessWord {
+                    word: word.to_string(),
+                    channel_id: 1,
+                    user_id: i as u64 % 2,
+                    message_id: i as u64,
+                    in_dictionary: false,
+                })
+            })
+            .collect();
+
+        let mut outcomes = Vec::new();
+        for request in requests {
+            outcomes.push(request.await.unwrap());
+        }
+
+        assert_eq!(outcomes[0], RuleOutcome::FollowsRules);
+        assert_eq!(outcomes[1], RuleOutcome::FollowsRules);
+        assert_eq!(outcomes[2], RuleOutcome::FollowsRules);
+        assert_eq!(outcomes[3], RuleOutcome::AlreadyUsed);
+        assert!(matches!(outcomes[4], RuleOutcome::RuleViolation(_)));
+        // Rejected words must not move the chain head away from "kassi"
+        assert_eq!(outcomes[5], RuleOutcome::FollowsRules);
+    }
+
+    #[actix_rt::test]
+    async fn test_check_word_does_not_mutate() {
+        let game_state = GameStateActor::new().start();
+
+        let check = |word: &str| {
+            game_state.send(CheckWord {
+                word: word.to_string(),
+                user_id: None,
+            })
+        };
+        let process = |word: &str, message_id: u64| {
+            game_state.send(ProcessWord {
+                word: word.to_string(),
+                channel_id: 1,
+                user_id: 1,
+                message_id,
+                in_dictionary: false,
+            })
+        };
+
+        // Checking doesn't start the chain
+        assert_eq!(check("kissa").await.unwrap(), RuleOutcome::FollowsRules);
+        assert_eq!(check("koira").await.unwrap(), RuleOutcome::FollowsRules);
+
+        assert_eq!(
+            process("kissa", 1).await.unwrap(),
+            RuleOutcome::FollowsRules
+        );
+        assert_eq!(check("kassa").await.unwrap(), RuleOutcome::FollowsRules);
+        assert!(matches!(
+            check("koira").await.unwrap(),
+            RuleOutcome::RuleViolation(_)
+        ));
+        assert_eq!(check("kissa").await.unwrap(), RuleOutcome::AlreadyUsed);
+
+        // A checked word can still be played afterwards
+        assert_eq!(
+            process("kassa", 2).await.unwrap(),
+            RuleOutcome::FollowsRules
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_consecutive_turns() {
+        let game_state = GameStateActor::with_rules(GameRules {
+            allow_consecutive_turns: false,
+            ..GameRules::default()
+        })
+        .start();
+
+        let process = |word: &str, user_id: u64, message_id: u64| {
+            game_state.send(ProcessWord {
+                word: word.to_string(),
+                channel_id: 1,
+                user_id,
+                message_id,
+                in_dictionary: false,
+            })
+        };
+
+        assert_eq!(
+            process("kissa", 1, 1).await.unwrap(),
+            RuleOutcome::FollowsRules
+        );
+        assert!(matches!(
+            process("kassa", 1, 2).await.unwrap(),
+            RuleOutcome::RuleViolation(_)
+        ));
+        assert_eq!(
+            process("kassa", 2, 3).await.unwrap(),
+            RuleOutcome::FollowsRules
+        );
+        assert_eq!(
+            process("kassi", 1, 4).await.unwrap(),
+            RuleOutcome::FollowsRules
+        );
+
+        let info = game_state.send(GetRules).await.unwrap();
+        assert!(!info.rules.allow_consecutive_turns);
+        assert_eq!(info.current_word.as_deref(), Some("kassi"));
+    }
+
+    #[actix_rt::test]
+    async fn test_idle_reset() {
+        let api = RecordingReactionApi::default();
+        let announcer =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .start();
+
+        let mut game = GameStateActor::new().with_idle_reset(Duration::from_secs(48 * 3600));
+        game.message_reaction = Some(announcer);
+
+        // Nothing to reset before the first word
+        assert!(!game.check_idle(Instant::now() + Duration::from_secs(49 * 3600)));
+
+        assert!(game.engine.check_rules("kissa", Some(1)).is_valid());
+        let accepted_at = game.engine.last_accepted_at().unwrap();
+
+        assert!(!game.check_idle(accepted_at + Duration::from_secs(47 * 3600)));
+        assert_eq!(game.engine.current_word().as_deref(), Some("kissa"));
+
+        assert!(game.check_idle(accepted_at + Duration::from_secs(48 * 3600)));
+        assert_eq!(game.engine.current_word(), None);
+        // Previously used words can be played again in the new game
+        assert!(game.engine.check_rules("kissa", Some(1)).is_valid());
+
+        // Let the reaction actor handle the announcement
+        actix_rt::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(
+            api.calls(),
+            vec![ReactionCall::Post(
+                "Peli nollattu 48 tunnin hiljaisuuden jälkeen, aloita uusi sana!".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_pause_freezes_idle_reset() {
+        let mut game = GameStateActor::new().with_idle_reset(Duration::from_secs(48 * 3600));
+        assert!(game.engine.check_rules("kissa", Some(1)).is_valid());
+        let accepted_at = game.engine.last_accepted_at().unwrap();
+        let hours = |h: u64| accepted_at + Duration::from_secs(h * 3600);
+
+        assert!(game.set_paused(true, hours(1)));
+        assert!(!game.set_paused(true, hours(2)));
+        assert!(!game.check_idle(hours(100)));
+
+        // Paused for ten hours, which don't count
+        assert!(game.set_paused(false, hours(11)));
+        assert!(!game.set_paused(false, hours(12)));
+        assert!(!game.check_idle(hours(57)));
+        assert!(game.check_idle(hours(58)));
+    }
+
+    #[actix_rt::test]
+    async fn test_paused_words_ignored() {
+        let engine = GameEngine::new(
+            DictionaryValidator::from_words(["kissa", "kassa"]),
+            GameRules::default(),
+        );
+        let game_state = GameStateActor::with_engine(engine).start();
+        let submit = |content: &str, message_id: u64| SubmitWord {
+            content: content.to_string(),
+            user_id: message_id,
+            channel_id: 1,
+            message_id,
+            span: Span::none(),
+        };
+
+        let submission = game_state.send(submit("kissa", 1)).await.unwrap();
+        assert_eq!(submission.verdict, WordVerdict::ValidInDictionary);
+        assert!(game_state.send(SetPaused(true)).await.unwrap());
+        assert!(game_state.send(IsPaused).await.unwrap());
+
+        let submission = game_state.send(submit("kassa", 2)).await.unwrap();
+        assert_eq!(submission.word.as_deref(), Some("kassa"));
+        assert_eq!(submission.verdict, WordVerdict::Paused);
+        let attempts = game_state.send(GetAttempts { user_id: 2 }).await.unwrap();
+        assert_eq!(attempts.total, 0);
+
+        // Nothing was played during the pause, so the same word is accepted after it
+        assert!(game_state.send(SetPaused(false)).await.unwrap());
+        assert!(!game_state.send(IsPaused).await.unwrap());
+        let submission = game_state.send(submit("kassa", 3)).await.unwrap();
+        assert_eq!(submission.verdict, WordVerdict::ValidInDictionary);
+        let history = game_state.send(GetHistory { limit: 10 }).await.unwrap();
+        assert_eq!(history.len(), 2);
+    }
+
+    #[actix_rt::test]
+    async fn test_bot_turn() {
+        let api = RecordingReactionApi::default();
+        let announcer =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .start();
+        let engine = GameEngine::new(
+            DictionaryValidator::from_words(["kissa", "kassa"]),
+            GameRules::default(),
+        );
+        let game_state = GameStateActor::with_engine(engine)
+            .with_bot_turns(Duration::from_millis(50))
+            .start();
+        game_state.do_send(SetMessageReaction(announcer));
+        game_state.do_send(SetBotUser(99));
+
+        game_state
+            .send(ProcessWord {
+                word: "kissa".to_string(),
+                user_id: 1,
+                channel_id: 1,
+                message_id: 1,
+                in_dictionary: true,
+            })
+            .await
+            .unwrap();
+        // Long enough for several checks, the bot doesn't answer its own word
+        actix_rt::time::sleep(Duration::from_millis(400)).await;
+
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Post("kassa".to_string()),
+                ReactionCall::Add(FIRST_POSTED_ID, EMOJI_CHECK),
+            ]
+        );
+        let history = game_state.send(GetHistory { limit: 1 }).await.unwrap();
+        assert_eq!(history[0].word, "kassa");
+        assert_eq!(history[0].user_id, 99);
+        assert_eq!(history[0].message_id, FIRST_POSTED_ID);
+        assert_eq!(history[0].source, Some(VerdictSource::Seed));
+        assert_eq!(
+            game_state.send(GetLastValidWord).await.unwrap().as_deref(),
+            Some("kassa")
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_challenge() {
+        let api = RecordingReactionApi::default();
+        let announcer =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .start();
+        let engine = GameEngine::new(
+            DictionaryValidator::from_words([
+                "kissa", "kassa", "kassi", "kasvi", "kisa", "kasa", "koira",
+            ]),
+            GameRules::default(),
+        );
+        let game_state = GameStateActor::with_engine(engine).start();
+        game_state.do_send(SetMessageReaction(announcer));
+
+        let plan = |from: &str, to: &str| {
+            game_state.send(PlanChallenge {
+                from: from.to_string(),
+                to: to.to_string(),
+            })
+        };
+        assert_eq!(
+            plan("kissa", "kissu").await.unwrap().unwrap(),
+            ChallengePlan::NotInDictionary("kissu".to_string())
+        );
+        assert_eq!(
+            plan("Kissa", "kissa").await.unwrap().unwrap(),
+            ChallengePlan::SameWord
+        );
+        assert_eq!(
+            plan("kissa", "koira").await.unwrap().unwrap(),
+            ChallengePlan::NoPath
+        );
+        let ChallengePlan::Ready(path) = plan("kissa", "KASVI").await.unwrap().unwrap() else {
+            panic!("no path from kissa to kasvi");
+        };
+        assert_eq!(path, vec!["kissa", "kassa", "kassi", "kasvi"]);
+
+        game_state.send(StartChallenge { path }).await.unwrap();
+        let submit = |word: &str, user_id: u64, message_id: u64| {
+            game_state.send(SubmitWord {
+                content: word.to_string(),
+                user_id,
+                channel_id: 1,
+                message_id,
+                span: Span::none(),
+            })
+        };
+        // The chain starts from the start word
+        assert!(matches!(
+            submit("koira", 1, 1).await.unwrap().verdict,
+            WordVerdict::InvalidRules(_)
+        ));
+        assert!(matches!(
+            submit("kissa", 2, 2).await.unwrap().verdict,
+            WordVerdict::InvalidRules(_)
+        ));
+        // A detour, two moves longer than the shortest chain
+        for (i, word) in ["kisa", "kasa", "kassa", "kassi", "kasvi"]
+            .iter()
+            .enumerate()
+        {
+            let verdict = submit(word, i as u64 % 2, i as u64 + 3)
+                .await
+                .unwrap()
+                .verdict;
+            assert_eq!(verdict, WordVerdict::ValidInDictionary, "{}", word);
+        }
+
+        actix_rt::time::sleep(Duration::from_millis(50)).await;
+        let posts: Vec<_> = api
+            .calls()
+            .into_iter()
+            .filter_map(|call| match call {
+                ReactionCall::Post(content) => Some(content),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(posts.len(), 2);
+        assert!(posts[0].contains("sanasta **kissa** sanaan **kasvi**"));
+        assert!(posts[1].starts_with("🏁 <@0> pääsi sanaan **kasvi**!"));
+        assert!(posts[1].contains("5 siirtoa, lyhin reitti on 3 siirtoa"));
+
+        // Won challenges are over
+        assert_eq!(game_state.send(GiveUpChallenge).await.unwrap(), None);
+    }
+
+    #[actix_rt::test]
+    async fn test_attempts() {
+        let engine = GameEngine::new(
+            DictionaryValidator::from_words(["kissa", "koira"]),
+            GameRules::default(),
+        );
+        let game_state = GameStateActor::with_engine(engine).start();
+        let submit = |word: &str, message_id: u64| {
+            game_state.send(SubmitWord {
+                content: word.to_string(),
+                user_id: 1,
+                channel_id: 1,
+                message_id,
+                span: Span::none(),
+            })
+        };
+        let attempts = || game_state.send(GetAttempts { user_id: 1 });
+
+        // Each settled attempt counts towards exactly one outcome
+        submit("kissa", 1).await.unwrap();
+        assert_eq!(attempts().await.unwrap().dictionary, 1);
+        submit("koira", 2).await.unwrap();
+        assert_eq!(attempts().await.unwrap().rule_violations, 1);
+        submit("kissa", 3).await.unwrap();
+        assert_eq!(attempts().await.unwrap().already_used, 1);
+
+        // Words waiting for a verdict count once it lands
+        submit("kissi", 4).await.unwrap();
+        submit("kisso", 5).await.unwrap();
+        submit("kissu", 6).await.unwrap();
+        assert_eq!(attempts().await.unwrap().total, 3);
+        let settle = |message_id, validity, source| {
+            game_state.send(MarkWordValidity {
+                message_id,
+                validity,
+                source,
+            })
+        };
+        settle(4, Validity::Valid, by_llm()).await.unwrap();
+        settle(5, Validity::Valid, VerdictSource::Dictionary { file: None })
+            .await
+            .unwrap();
+        settle(6, Validity::Invalid, by_llm()).await.unwrap();
+        // Settling again changes nothing
+        settle(4, Validity::Invalid, by_llm()).await.unwrap();
+
+        game_state
+            .send(RecordAttempt {
+                user_id: 1,
+                outcome: AttemptOutcome::Blocked,
+            })
+            .await
+            .unwrap();
+        // Not a word at all, nothing to count
+        submit("🐱", 7).await.unwrap();
+
+        assert_eq!(
+            attempts().await.unwrap(),
+            Attempts {
+                total: 7,
+                dictionary: 2,
+                proper_nouns: 1,
+                rule_violations: 1,
+                already_used: 1,
+                not_words: 1,
+                blocked: 1,
+            }
+        );
+        assert_eq!(
+            game_state.send(GetAttempts { user_id: 2 }).await.unwrap(),
+            Attempts::default()
+        );
+    }
+
+    #[test]
+    fn test_bot_turn_dead_end() {
+        let engine = GameEngine::new(
+            DictionaryValidator::from_words(["kissa", "kassa", "koira"]),
+            GameRules::default(),
+        );
+        let mut game =
+            GameStateActor::with_engine(engine).with_bot_turns(Duration::from_secs(3600));
+        let hour = Duration::from_secs(3600);
+
+        assert!(game
+            .engine
+            .process_word(
+                "kissa",
+                1,
+                1,
+                Some(VerdictSource::Dictionary { file: None })
+            )
+            .is_valid());
+        let accepted_at = game.engine.last_accepted_at().unwrap();
+        // Not before the bot knows who it is
+        assert_eq!(game.bot_turn(accepted_at + hour), None);
+
+        game.bot_user_id = Some(99);
+        assert!(game
+            .engine
+            .process_word(
+                "kassa",
+                2,
+                2,
+                Some(VerdictSource::Dictionary { file: None })
+            )
+            .is_valid());
+        let accepted_at = game.engine.last_accepted_at().unwrap();
+        assert_eq!(game.bot_turn(accepted_at + hour / 2), None);
+        assert_eq!(
+            game.bot_turn(accepted_at + hour),
+            Some(BotTurn::DeadEnd("kassa".to_string()))
+        );
+        // Announced once, not on every check
+        assert_eq!(game.bot_turn(accepted_at + hour * 2), None);
+    }
+
+    #[test]
+    fn test_bot_turns_disabled() {
+        let mut game = GameStateActor::new().with_bot_turns(Duration::ZERO);
+        game.bot_user_id = Some(99);
+
+        assert!(game.engine.check_rules("kissa", Some(1)).is_valid());
+        assert_eq!(
+            game.bot_turn(Instant::now() + Duration::from_secs(365 * 86400)),
+            None
+        );
+    }
 
-        info!("Game state has been reset");
+    #[test]
+    fn test_idle_reset_disabled() {
+        let mut game = GameStateActor::new().with_idle_reset(Duration::ZERO);
+
+        assert!(game.engine.check_rules("kissa", Some(1)).is_valid());
+        assert!(!game.check_idle(Instant::now() + Duration::from_secs(365 * 86400)));
+        assert_eq!(game.engine.current_word().as_deref(), Some("kissa"));
+    }
+
+    #[actix_rt::test]
+    async fn test_history() {
+        let game_state = GameStateActor::new().with_history_size(3).start();
+
+        for (i, word) in ["kissa", "kassa", "koira", "kassi"].iter().enumerate() {
+            game_state
+                .send(ProcessWord {
+                    word: word.to_string(),
+                    channel_id: 1,
+                    user_id: 1,
+                    message_id: i as u64,
+                    in_dictionary: false,
+                })
+                .await
+                .unwrap();
+        }
+        game_state.do_send(MarkWordValidity {
+            message_id: 1,
+            validity: Validity::Valid,
+            source: by_llm(),
+        });
+
+        let history = game_state.send(GetHistory { limit: 10 }).await.unwrap();
+        let summary: Vec<_> = history
+            .iter()
+            .map(|entry| (entry.word.as_str(), entry.validity))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("kassi", Validity::Pending),
+                ("koira", Validity::Invalid),
+                ("kassa", Validity::Valid),
+            ]
+        );
+
+        let latest = game_state.send(GetHistory { limit: 1 }).await.unwrap();
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].word, "kassi");
+    }
+
+    #[actix_rt::test]
+    async fn test_mark_word_validity() {
+        let game_state = GameStateActor::new().start();
+
+        for (i, word) in ["kissa", "kassa"].iter().enumerate() {
+            game_state
+                .send(ProcessWord {
+                    word: word.to_string(),
+                    channel_id: 1,
+                    user_id: 1,
+                    message_id: i as u64,
+                    in_dictionary: false,
+                })
+                .await
+                .unwrap();
+        }
+        let mark = |message_id, validity| {
+            game_state.send(MarkWordValidity {
+                message_id,
+                validity,
+                source: by_llm(),
+            })
+        };
+        mark(0, Validity::Valid).await.unwrap();
+        mark(1, Validity::Invalid).await.unwrap();
+
+        let history = game_state.send(GetHistory { limit: 2 }).await.unwrap();
+        assert_eq!(history[0].validity, Validity::Invalid);
+        assert_eq!(history[1].validity, Validity::Valid);
+        assert_eq!(history[1].source, Some(by_llm()));
+
+        // A rejected word never becomes the last valid word
+        let last_valid = game_state.send(GetLastValidWord).await.unwrap();
+        assert_eq!(last_valid.as_deref(), Some("kissa"));
+
+        // An admin can settle a word too, and is remembered for it
+        game_state
+            .send(ProcessWord {
+                word: "kassi".to_string(),
+                channel_id: 1,
+                user_id: 2,
+                message_id: 2,
+                in_dictionary: false,
+            })
+            .await
+            .unwrap();
+        game_state
+            .send(MarkWordValidity {
+                message_id: 2,
+                validity: Validity::Valid,
+                source: VerdictSource::Admin { user_id: 7 },
+            })
+            .await
+            .unwrap();
+        let history = game_state.send(GetHistory { limit: 1 }).await.unwrap();
+        assert_eq!(history[0].validity, Validity::Valid);
+        assert_eq!(history[0].source, Some(VerdictSource::Admin { user_id: 7 }));
+    }
+
+    /// Start a game with the given pending policy, recording reactions it changes
+    fn start_with_policy(policy: PendingPolicy) -> (Addr<GameStateActor>, RecordingReactionApi) {
+        let api = RecordingReactionApi::default();
+        // No pacing, the tests only give the reactions a moment to be made
+        let message_reaction =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_reaction_interval(Duration::ZERO)
+                .start();
+        let game_state = GameStateActor::with_rules(GameRules {
+            pending_policy: policy,
+            ..GameRules::default()
+        })
+        .start();
+        game_state.do_send(SetMessageReaction(message_reaction));
+        (game_state, api)
+    }
+
+    async fn play(
+        game_state: &Addr<GameStateActor>,
+        word: &str,
+        message_id: u64,
+        in_dictionary: bool,
+    ) -> RuleOutcome {
+        game_state
+            .send(ProcessWord {
+                word: word.to_string(),
+                // Every word in its own thread, to tell where reactions go
+                channel_id: 100 + message_id,
+                user_id: message_id,
+                message_id,
+                in_dictionary,
+            })
+            .await
+            .unwrap()
+    }
+
+    async fn settle(game_state: &Addr<GameStateActor>, message_id: u64, validity: Validity) {
+        game_state
+            .send(MarkWordValidity {
+                message_id,
+                validity,
+                source: by_llm(),
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn current_word(game_state: &Addr<GameStateActor>) -> Option<String> {
+        game_state.send(GetRules).await.unwrap().current_word
+    }
+
+    #[actix_rt::test]
+    async fn test_pending_policy_optimistic() {
+        for verdict in [Validity::Valid, Validity::Invalid] {
+            let (game_state, api) = start_with_policy(PendingPolicy::Optimistic);
+
+            play(&game_state, "kissa", 1, true).await;
+            play(&game_state, "kassa", 2, false).await;
+            assert_eq!(
+                play(&game_state, "kassi", 3, true).await,
+                RuleOutcome::FollowsRules
+            );
+
+            settle(&game_state, 2, verdict).await;
+            // The chain carries on from "kassi" whatever the LLM says
+            assert_eq!(current_word(&game_state).await.as_deref(), Some("kassi"));
+            assert!(api.calls().is_empty());
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_pending_policy_strict() {
+        let (game_state, _api) = start_with_policy(PendingPolicy::Strict);
+        play(&game_state, "kissa", 1, true).await;
+        play(&game_state, "kassa", 2, false).await;
+        assert_eq!(
+            play(&game_state, "kassi", 3, true).await,
+            RuleOutcome::AwaitingVerdict
+        );
+
+        settle(&game_state, 2, Validity::Valid).await;
+        assert_eq!(
+            play(&game_state, "kassi", 4, true).await,
+            RuleOutcome::FollowsRules
+        );
+
+        let (game_state, _api) = start_with_policy(PendingPolicy::Strict);
+        play(&game_state, "kissa", 1, true).await;
+        play(&game_state, "kassa", 2, false).await;
+        assert_eq!(
+            play(&game_state, "kassi", 3, true).await,
+            RuleOutcome::AwaitingVerdict
+        );
+
+        // A rejected word is taken out of the chain and can't block it anymore
+        settle(&game_state, 2, Validity::Invalid).await;
+        assert_eq!(current_word(&game_state).await.as_deref(), Some("kissa"));
+        assert_eq!(
+            play(&game_state, "kisso", 4, true).await,
+            RuleOutcome::FollowsRules
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_pending_policy_rollback_accepted() {
+        let (game_state, api) = start_with_policy(PendingPolicy::Rollback);
+        play(&game_state, "kissa", 1, true).await;
+        play(&game_state, "kassa", 2, false).await;
+        play(&game_state, "kassi", 3, true).await;
+
+        settle(&game_state, 2, Validity::Valid).await;
+        assert_eq!(current_word(&game_state).await.as_deref(), Some("kassi"));
+        assert!(api.calls().is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn test_pending_policy_rollback_rejected() {
+        let (game_state, api) = start_with_policy(PendingPolicy::Rollback);
+        play(&game_state, "kissa", 1, true).await;
+        play(&game_state, "kassa", 2, false).await;
+        play(&game_state, "kassi", 3, true).await;
+        play(&game_state, "kasti", 4, false).await;
+
+        settle(&game_state, 2, Validity::Invalid).await;
+
+        // Neither follower is one letter away from "kissa"
+        assert_eq!(current_word(&game_state).await.as_deref(), Some("kissa"));
+        let history = game_state.send(GetHistory { limit: 4 }).await.unwrap();
+        assert!(history[..3]
+            .iter()
+            .all(|entry| entry.validity == Validity::Invalid && !entry.in_chain));
+
+        // The LLM's later verdict on the rolled back word doesn't count
+        assert!(!game_state
+            .send(MarkWordValidity {
+                message_id: 4,
+                validity: Validity::Valid,
+                source: by_llm(),
+            })
+            .await
+            .unwrap());
+
+        actix_rt::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Delete(3, EMOJI_CHECK),
+                ReactionCall::Add(3, EMOJI_CROSS),
+                ReactionCall::Delete(4, EMOJI_QUESTION),
+                ReactionCall::Add(4, EMOJI_CROSS),
+            ]
+        );
+        assert_eq!(api.call_channels(), vec![103, 103, 104, 104]);
+
+        // "kassa" is no longer used and may be played again
+        assert_eq!(
+            play(&game_state, "kassa", 5, true).await,
+            RuleOutcome::FollowsRules
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_set_current_word() {
+        let (game_state, api) = start_with_policy(PendingPolicy::Rollback);
+        play(&game_state, "kissa", 1, true).await;
+        play(&game_state, "kassa", 2, false).await;
+        let set = |word: &str, force| SetCurrentWord {
+            word: word.to_string(),
+            force,
+        };
+
+        // The test dictionary is empty
+        assert_eq!(
+            game_state.send(set("koira", false)).await.unwrap(),
+            SetWordOutcome::NotInDictionary("koira".to_string())
+        );
+        assert_eq!(
+            game_state.send(set("kissa kissa", true)).await.unwrap(),
+            SetWordOutcome::NotAWord
+        );
+        assert_eq!(
+            game_state.send(set("**Koira**!", true)).await.unwrap(),
+            SetWordOutcome::Set {
+                word: "koira".to_string(),
+                dropped: 1,
+            }
+        );
+        assert_eq!(current_word(&game_state).await.as_deref(), Some("koira"));
+
+        // The history is kept, but the word waiting for the LLM was rejected
+        let history = game_state.send(GetHistory { limit: 10 }).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].validity, Validity::Invalid);
+        assert!(!game_state
+            .send(MarkWordValidity {
+                message_id: 2,
+                validity: Validity::Valid,
+                source: by_llm(),
+            })
+            .await
+            .unwrap());
+
+        // The announcement isn't queued with the reactions, so it may come first
+        actix_rt::time::sleep(Duration::from_millis(50)).await;
+        let (posts, reactions): (Vec<_>, Vec<_>) = api
+            .calls()
+            .into_iter()
+            .partition(|call| matches!(call, ReactionCall::Post(_)));
+        assert_eq!(
+            posts,
+            vec![ReactionCall::Post(
+                "📌 Nykyinen sana on nyt **koira**, jatkakaa siitä!".to_string()
+            )]
+        );
+        assert_eq!(
+            reactions,
+            vec![
+                ReactionCall::Delete(2, EMOJI_QUESTION),
+                ReactionCall::Add(2, EMOJI_CROSS),
+            ]
+        );
+        assert_eq!(
+            play(&game_state, "koire", 3, true).await,
+            RuleOutcome::FollowsRules
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_pending_policy_rollback_keeps_valid_followers() {
+        let (game_state, api) = start_with_policy(PendingPolicy::Rollback);
+        play(&game_state, "kissa", 1, true).await;
+        play(&game_state, "kassa", 2, false).await;
+        // One letter away from both "kassa" and "kissa"
+        play(&game_state, "kussa", 3, true).await;
+
+        settle(&game_state, 2, Validity::Invalid).await;
+
+        assert_eq!(current_word(&game_state).await.as_deref(), Some("kussa"));
+        let history = game_state.send(GetHistory { limit: 1 }).await.unwrap();
+        assert_eq!(history[0].validity, Validity::Valid);
+        assert!(api.calls().is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn test_presence_follows_valid_words() {
+        let api = RecordingReactionApi::default();
+        let message_reaction =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_dynamic_presence("Finnish Word Game".to_string())
+                .start();
+        let game_state = GameStateActor::new().start();
+        game_state.do_send(SetMessageReaction(message_reaction.clone()));
+
+        play(&game_state, "kissa", 1, true).await;
+        play(&game_state, "kassa", 2, false).await;
+        settle(&game_state, 2, Validity::Valid).await;
+        message_reaction.send(Ping).await.unwrap();
+
+        assert_eq!(
+            api.calls(),
+            vec![ReactionCall::Activity(
+                "Pelataan: kassa (ketju 2)".to_string()
+            )]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_milestones_and_records() {
+        let words = ["kissa", "kassa", "kassi", "kasvi", "kasvo"];
+        let engine = GameEngine::new(DictionaryValidator::from_words(words), GameRules::default());
+        let events = EventBus::default();
+        let mut rx = events.subscribe();
+        let game_state = GameStateActor::with_engine(engine)
+            .with_events(events)
+            .with_milestones(2)
+            .start();
+        let mut next_highlights = || {
+            let mut highlights = Vec::new();
+            while let Ok(event) = rx.try_recv() {
+                match event {
+                    GameEvent::MilestoneReached {
+                        chain_length, word, ..
+                    } => highlights.push(("milestone", chain_length, word)),
+                    GameEvent::ChainRecord {
+                        chain_length, word, ..
+                    } => highlights.push(("record", chain_length, word)),
+                    _ => {}
+                }
+            }
+            highlights
+        };
+
+        // The first chain has no record to beat
+        for (message_id, word) in words[..4].iter().enumerate() {
+            submit(&game_state, word, message_id as u64).await;
+        }
+        assert_eq!(
+            next_highlights(),
+            vec![
+                ("milestone", 2, "kassa".to_string()),
+                ("milestone", 4, "kasvi".to_string())
+            ]
+        );
+
+        // Milestones are reached again once the chain starts over, the record when it's passed
+        game_state.send(ResetGame).await.unwrap();
+        for (message_id, word) in words.iter().enumerate() {
+            submit(&game_state, word, 10 + message_id as u64).await;
+        }
+        assert_eq!(
+            next_highlights(),
+            vec![
+                ("milestone", 2, "kassa".to_string()),
+                ("milestone", 4, "kasvi".to_string()),
+                ("record", 5, "kasvo".to_string())
+            ]
+        );
+    }
+
+    fn start_with_breaks(
+        rules: GameRules,
+        breaks: ChainBreaks,
+    ) -> (Addr<GameStateActor>, RecordingReactionApi) {
+        let api = RecordingReactionApi::default();
+        let message_reaction =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_reaction_interval(Duration::ZERO)
+                .start();
+        let engine = GameEngine::new(
+            DictionaryValidator::from_words(["kissa", "kassa", "kassi"]),
+            rules,
+        );
+        let game_state = GameStateActor::with_engine(engine)
+            .with_chain_breaks(breaks)
+            .start();
+        game_state.do_send(SetMessageReaction(message_reaction));
+        (game_state, api)
+    }
+
+    /// The embeds posted so far
+    fn embeds(api: &RecordingReactionApi) -> Vec<(String, String)> {
+        api.calls()
+            .into_iter()
+            .filter_map(|call| match call {
+                ReactionCall::Embed(title, description) => Some((title, description)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[actix_rt::test]
+    async fn test_chain_breaks_and_restarts() {
+        let breaks = ChainBreaks {
+            min_length: 3,
+            violations: false,
+            restart: true,
+        };
+        let (game_state, api) = start_with_breaks(GameRules::default(), breaks);
+        for (message_id, word) in [(1, "kissa"), (2, "kassa"), (3, "kassi")] {
+            submit(&game_state, word, message_id).await;
+        }
+
+        // Rule violations are only ignored
+        let submission = submit(&game_state, "koira", 4).await;
+        assert!(matches!(submission.verdict, WordVerdict::InvalidRules(_)));
+        assert_eq!(current_word(&game_state).await.as_deref(), Some("kassi"));
+
+        // A word the LLM rejects breaks the chain, which starts over
+        assert_eq!(
+            submit(&game_state, "kassu", 5).await.verdict,
+            WordVerdict::PendingLlm
+        );
+        settle(&game_state, 5, Validity::Invalid).await;
+        assert_eq!(current_word(&game_state).await, None);
+        actix_rt::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(
+            embeds(&api),
+            vec![(
+                "💥 Ketju katkesi 3 sanan jälkeen!".to_string(),
+                "<@5> katkaisi ketjun sanalla **kassu**.\n\
+                 Eniten sanoja: <@1> (1)\n\
+                 Pisin sana: **kissa**\n\
+                 Kesto: alle minuutin\n\
+                 Uusi ketju alkaa, aloita millä tahansa sanalla!"
+                    .to_string()
+            )]
+        );
+
+        // The new chain is counted from zero, so it's too short to break
+        submit(&game_state, "kissa", 6).await;
+        assert_eq!(
+            submit(&game_state, "kissu", 7).await.verdict,
+            WordVerdict::PendingLlm
+        );
+        settle(&game_state, 7, Validity::Invalid).await;
+        actix_rt::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(embeds(&api).len(), 1);
+    }
+
+    #[actix_rt::test]
+    async fn test_chain_breaks_wait_for_reset() {
+        let rules = GameRules {
+            accept_proper_nouns: false,
+            ..GameRules::default()
+        };
+        let breaks = ChainBreaks {
+            min_length: 2,
+            violations: true,
+            restart: false,
+        };
+        let (game_state, api) = start_with_breaks(rules, breaks);
+        submit(&game_state, "kissa", 1).await;
+        submit(&game_state, "kassa", 2).await;
+
+        // Playing a word again breaks a rule, and the game waits for /reset
+        let submission = submit(&game_state, "kissa", 3).await;
+        assert!(matches!(
+            submission.verdict,
+            WordVerdict::InvalidRules(Violation::AlreadyUsed(_))
+        ));
+        assert!(game_state.send(IsPaused).await.unwrap());
+        assert_eq!(
+            submit(&game_state, "kassi", 4).await.verdict,
+            WordVerdict::Paused
+        );
+        actix_rt::time::sleep(Duration::from_millis(50)).await;
+        let posted = embeds(&api);
+        assert_eq!(posted.len(), 1);
+        assert!(posted[0]
+            .1
+            .ends_with("Peli on tauolla, kunnes ketju nollataan komennolla /reset."));
+
+        game_state.send(ResetGame).await.unwrap();
+        assert!(!game_state.send(IsPaused).await.unwrap());
+        assert_eq!(current_word(&game_state).await, None);
+        assert_eq!(
+            submit(&game_state, "kissa", 5).await.verdict,
+            WordVerdict::ValidInDictionary
+        );
+    }
+
+    fn start_with_queue(size: usize, timeout: Duration) -> Addr<GameStateActor> {
+        let engine = GameEngine::new(
+            DictionaryValidator::from_words(["kissa", "kassi", "kisso"]),
+            GameRules {
+                pending_policy: PendingPolicy::Queue,
+                ..GameRules::default()
+            },
+        );
+        GameStateActor::with_engine(engine)
+            .with_pending_queue(size, timeout)
+            .start()
+    }
+
+    async fn submit(game_state: &Addr<GameStateActor>, word: &str, message_id: u64) -> Submission {
+        game_state
+            .send(SubmitWord {
+                content: word.to_string(),
+                user_id: message_id,
+                channel_id: 1,
+                message_id,
+                span: Span::none(),
+            })
+            .await
+            .unwrap()
+    }
+
+    /// The verdict a queued word gets once it's played
+    async fn released(submission: Submission) -> WordVerdict {
+        assert_eq!(submission.verdict, WordVerdict::Queued);
+        submission.released.unwrap().await.unwrap().verdict
+    }
+
+    #[actix_rt::test]
+    async fn test_pending_policy_queue_accepted() {
+        let game_state = start_with_queue(3, Duration::from_secs(60));
+        submit(&game_state, "kissa", 1).await;
+        assert_eq!(
+            submit(&game_state, "kassa", 2).await.verdict,
+            WordVerdict::PendingLlm
+        );
+        let kassi = submit(&game_state, "kassi", 3).await;
+        let kasti = submit(&game_state, "kasti", 4).await;
+        let kasto = submit(&game_state, "kasto", 5).await;
+        // Nothing is played while the head waits
+        assert_eq!(current_word(&game_state).await.as_deref(), Some("kassa"));
+
+        settle(&game_state, 2, Validity::Valid).await;
+        assert_eq!(released(kassi).await, WordVerdict::ValidInDictionary);
+        assert_eq!(released(kasti).await, WordVerdict::PendingLlm);
+
+        // "kasto" waits for "kasti" in turn
+        let mut kasto_released = kasto.released.unwrap();
+        assert!(kasto_released.try_recv().is_err());
+        settle(&game_state, 4, Validity::Valid).await;
+        assert_eq!(
+            kasto_released.await.unwrap().verdict,
+            WordVerdict::PendingLlm
+        );
+        assert_eq!(current_word(&game_state).await.as_deref(), Some("kasto"));
+    }
+
+    #[actix_rt::test]
+    async fn test_pending_policy_queue_rejected() {
+        let game_state = start_with_queue(2, Duration::from_secs(60));
+        submit(&game_state, "kissa", 1).await;
+        submit(&game_state, "kassa", 2).await;
+        let kassi = submit(&game_state, "kassi", 3).await;
+        let kisso = submit(&game_state, "kisso", 4).await;
+        // The queue is full
+        assert_eq!(
+            submit(&game_state, "kasso", 5).await.verdict,
+            WordVerdict::AwaitingVerdict
+        );
+
+        // The queued words are judged against "kissa", not the rejected word
+        settle(&game_state, 2, Validity::Invalid).await;
+        assert!(matches!(
+            released(kassi).await,
+            WordVerdict::InvalidRules(Violation::Letters(_))
+        ));
+        assert_eq!(released(kisso).await, WordVerdict::ValidInDictionary);
+        assert_eq!(current_word(&game_state).await.as_deref(), Some("kisso"));
+        let history = game_state.send(GetHistory { limit: 2 }).await.unwrap();
+        assert_eq!(history[0].message_id, 4);
+        assert_eq!(history[1].message_id, 3);
+    }
+
+    #[actix_rt::test]
+    async fn test_pending_policy_queue_timeout() {
+        let game_state = start_with_queue(2, Duration::from_millis(50));
+        submit(&game_state, "kissa", 1).await;
+        submit(&game_state, "kassa", 2).await;
+        let kassi = submit(&game_state, "kassi", 3).await;
+
+        // Without a verdict the word is played against the pending head
+        let verdict = tokio::time::timeout(Duration::from_secs(5), released(kassi))
+            .await
+            .unwrap();
+        assert_eq!(verdict, WordVerdict::ValidInDictionary);
+        assert_eq!(current_word(&game_state).await.as_deref(), Some("kassi"));
+        assert_eq!(
+            submit(&game_state, "kasti", 4).await.verdict,
+            WordVerdict::PendingLlm
+        );
+    }
+
+    #[test]
+    fn test_daily_limit_boundary() {
+        let at = |time: &str| time.parse::<DateTime<Utc>>().unwrap();
+        let mut limit = DailyLimit::new(2, chrono_tz::Europe::Helsinki);
+
+        assert!(!limit.is_reached(1, at("2025-01-15T12:00:00Z")));
+        limit.record(1, at("2025-01-15T12:00:00Z"));
+        assert!(!limit.is_reached(1, at("2025-01-15T12:01:00Z")));
+        limit.record(1, at("2025-01-15T12:01:00Z"));
+        // Exactly at the limit, so the next word is one too many
+        assert!(limit.is_reached(1, at("2025-01-15T12:02:00Z")));
+        assert!(!limit.is_reached(2, at("2025-01-15T12:02:00Z")));
+
+        // Midnight in Helsinki is 22:00 UTC in winter
+        assert!(limit.is_reached(1, at("2025-01-15T21:59:59Z")));
+        assert!(!limit.is_reached(1, at("2025-01-15T22:00:00Z")));
+
+        // Words from the previous day aren't refunded from the new day's count
+        limit.record(1, at("2025-01-15T22:01:00Z"));
+        limit.refund(1, at("2025-01-15T12:00:00Z"), at("2025-01-15T22:02:00Z"));
+        limit.record(1, at("2025-01-15T22:03:00Z"));
+        assert!(limit.is_reached(1, at("2025-01-15T22:04:00Z")));
+        limit.refund(1, at("2025-01-15T22:03:00Z"), at("2025-01-15T22:05:00Z"));
+        assert!(!limit.is_reached(1, at("2025-01-15T22:06:00Z")));
+    }
+
+    #[actix_rt::test]
+    async fn test_daily_limit_per_player() {
+        let dictionary = DictionaryValidator::from_words(["kissa", "kassa", "kassi", "kasti"]);
+        let game_state =
+            GameStateActor::with_engine(GameEngine::new(dictionary, GameRules::default()))
+                .with_daily_limit(2, chrono_tz::Europe::Helsinki)
+                .start();
+        let submit = |word: &str, user_id: u64, message_id: u64| {
+            game_state.send(SubmitWord {
+                content: word.to_string(),
+                user_id,
+                channel_id: 1,
+                message_id,
+                span: Span::none(),
+            })
+        };
+        let preview = |word: &str, user_id: u64| {
+            game_state.send(PreviewWord {
+                content: word.to_string(),
+                user_id: Some(user_id),
+            })
+        };
+
+        assert_eq!(
+            submit("kissa", 1, 1).await.unwrap().verdict,
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            submit("kassa", 1, 2).await.unwrap().verdict,
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            preview("kassi", 1).await.unwrap(),
+            WordVerdict::DailyLimitReached(2)
+        );
+        assert_eq!(
+            submit("kassi", 1, 3).await.unwrap().verdict,
+            WordVerdict::DailyLimitReached(2)
+        );
+        // The rejected word doesn't move the chain, others carry on from "kassa"
+        assert_eq!(
+            submit("kassi", 2, 4).await.unwrap().verdict,
+            WordVerdict::ValidInDictionary
+        );
+
+        // Rejected proper nouns give the word back
+        assert_eq!(
+            submit("kasvi", 2, 5).await.unwrap().verdict,
+            WordVerdict::PendingLlm
+        );
+        assert_eq!(
+            preview("kasti", 2).await.unwrap(),
+            WordVerdict::DailyLimitReached(2)
+        );
+        settle(&game_state, 5, Validity::Invalid).await;
+        assert_eq!(
+            preview("kasti", 2).await.unwrap(),
+            WordVerdict::ValidInDictionary
+        );
+    }
+
+    #[test]
+    fn test_cooldown_left() {
+        let mut game = GameStateActor::new().with_cooldown(Duration::from_secs(60));
+        let accepted_at = Instant::now();
+
+        assert_eq!(game.cooldown_left(1, accepted_at), None);
+        game.count_accepted(1, 10, accepted_at);
+        assert_eq!(
+            game.cooldown_left(1, accepted_at + Duration::from_secs(59)),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(
+            game.cooldown_left(1, accepted_at + Duration::from_secs(60)),
+            None
+        );
+        assert_eq!(game.cooldown_left(2, accepted_at), None);
+    }
+
+    #[actix_rt::test]
+    async fn test_cooldown_only_after_accepted_words() {
+        let dictionary = DictionaryValidator::from_words(["kissa", "kassa", "kasvi", "koira"]);
+        let game_state =
+            GameStateActor::with_engine(GameEngine::new(dictionary, GameRules::default()))
+                .with_cooldown(Duration::from_secs(60))
+                .start();
+        let submit = |word: &str, user_id: u64, message_id: u64| {
+            game_state.send(SubmitWord {
+                content: word.to_string(),
+                user_id,
+                channel_id: 1,
+                message_id,
+                span: Span::none(),
+            })
+        };
+
+        assert_eq!(
+            submit("kissa", 1, 1).await.unwrap().verdict,
+            WordVerdict::ValidInDictionary
+        );
+        assert!(matches!(
+            submit("kassa", 1, 2).await.unwrap().verdict,
+            WordVerdict::CoolingDown(_)
+        ));
+
+        // An invalid attempt doesn't start a cooldown
+        assert!(matches!(
+            submit("koira", 2, 3).await.unwrap().verdict,
+            WordVerdict::InvalidRules(_)
+        ));
+        // The word rejected for the cooldown wasn't used up
+        assert_eq!(
+            submit("kassa", 2, 4).await.unwrap().verdict,
+            WordVerdict::ValidInDictionary
+        );
+
+        // Neither does a proper noun the LLM rejects
+        assert_eq!(
+            submit("kasva", 3, 5).await.unwrap().verdict,
+            WordVerdict::PendingLlm
+        );
+        settle(&game_state, 5, Validity::Invalid).await;
+        assert_eq!(
+            submit("kasvi", 3, 6).await.unwrap().verdict,
+            WordVerdict::ValidInDictionary
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new(dir.path().join("game.json"));
+        let start = || {
+            let dictionary = DictionaryValidator::from_words(["kissa", "kassa", "kassi"]);
+            GameStateActor::with_engine(GameEngine::new(dictionary, GameRules::default()))
+                .with_snapshot(store.clone())
+                .start()
+        };
+        let submit = |game_state: &Addr<GameStateActor>, word: &str, user_id, message_id| {
+            game_state.send(SubmitWord {
+                content: word.to_string(),
+                user_id,
+                channel_id: 1,
+                message_id,
+                span: Span::none(),
+            })
+        };
+
+        let game_state = start();
+        submit(&game_state, "kissa", 1, 1).await.unwrap();
+        submit(&game_state, "kassa", 2, 2).await.unwrap();
+        let standing = game_state.send(GetStanding { user_id: 1 }).await.unwrap();
+        game_state.send(Shutdown).await.unwrap();
+        assert_eq!(
+            store.load().unwrap().unwrap().word.as_deref(),
+            Some("kassa")
+        );
+
+        // The restarted game goes on from the saved word with the same scores
+        let restarted = start();
+        let info = restarted.send(GetRules).await.unwrap();
+        assert_eq!(info.current_word.as_deref(), Some("kassa"));
+        assert_eq!(
+            restarted.send(GetStanding { user_id: 1 }).await.unwrap(),
+            standing
+        );
+        assert_eq!(
+            submit(&restarted, "kassi", 1, 3).await.unwrap().verdict,
+            WordVerdict::ValidInDictionary
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_paused_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new(dir.path().join("game.json"));
+
+        let game_state = GameStateActor::new().with_snapshot(store.clone()).start();
+        assert!(game_state.send(SetPaused(true)).await.unwrap());
+        game_state.send(Shutdown).await.unwrap();
+
+        // The restarted game stays paused until someone resumes it
+        let restarted = GameStateActor::new().with_snapshot(store.clone()).start();
+        assert!(restarted.send(IsPaused).await.unwrap());
+        assert!(restarted.send(SetPaused(false)).await.unwrap());
+        restarted.send(Shutdown).await.unwrap();
+        assert!(!store.load().unwrap().unwrap().paused);
+    }
+
+    #[actix_rt::test]
+    async fn test_corrupt_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("game.json");
+        std::fs::write(&path, r#"{"word":"kissa","scores":[{"user_id":1,"#).unwrap();
+        let store = SnapshotStore::new(&path);
+
+        // The truncated file is kept for a look by hand, not overwritten
+        let game_state = GameStateActor::new().with_snapshot(store.clone()).start();
+        let info = game_state.send(GetRules).await.unwrap();
+        assert_eq!(info.current_word, None);
+        game_state.send(Shutdown).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("game.json.corrupt")).unwrap(),
+            r#"{"word":"kissa","scores":[{"user_id":1,"#
+        );
+        assert_eq!(store.load().unwrap().unwrap().word, None);
+    }
+
+    #[actix_rt::test]
+    async fn test_reset_reactions() {
+        let api = RecordingReactionApi::default();
+        let message_reaction =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_reaction_interval(Duration::ZERO)
+                .start();
+        let game_state = GameStateActor::new()
+            .with_reset_reactions(ResetReactions::Mark)
+            .start();
+        game_state.do_send(SetMessageReaction(message_reaction.clone()));
+
+        play(&game_state, "kissa", 1, true).await;
+        play(&game_state, "kassa", 2, true).await;
+        game_state.send(ResetGame).await.unwrap();
+        // Nothing is left to mark after the reset
+        game_state.send(ResetGame).await.unwrap();
+        for _ in 0..50 {
+            if api.calls().len() >= 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        message_reaction.send(Ping).await.unwrap();
+
+        // The newest words first, each where it was played
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(2, EMOJI_FINISH),
+                ReactionCall::Add(1, EMOJI_FINISH)
+            ]
+        );
+        assert_eq!(api.call_channels(), vec![102, 101]);
+    }
+
+    #[actix_rt::test]
+    async fn test_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RotationStore::new(dir.path().join("turns.json"));
+        let dictionary = DictionaryValidator::from_words(["kissa", "kassa", "kassi", "kasvi"]);
+        let game_state =
+            GameStateActor::with_engine(GameEngine::new(dictionary, GameRules::default()))
+                .with_rotation(store.clone(), Duration::ZERO)
+                .start();
+        let submit = |word: &str, user_id: u64, message_id: u64| {
+            game_state.send(SubmitWord {
+                content: word.to_string(),
+                user_id,
+                channel_id: 1,
+                message_id,
+                span: Span::none(),
+            })
+        };
+
+        // Anyone may play until someone joins
+        assert_eq!(
+            submit("kissa", 3, 1).await.unwrap().verdict,
+            WordVerdict::ValidInDictionary
+        );
+        for user_id in [1, 2] {
+            assert_eq!(
+                game_state.send(JoinRotation { user_id }).await.unwrap(),
+                Some(true)
+            );
+        }
+        assert_eq!(
+            game_state.send(JoinRotation { user_id: 1 }).await.unwrap(),
+            Some(false)
+        );
+
+        assert_eq!(
+            submit("kassa", 2, 2).await.unwrap().verdict,
+            WordVerdict::NotYourTurn(1)
+        );
+        // An invalid word keeps the turn
+        assert!(matches!(
+            submit("koira", 1, 3).await.unwrap().verdict,
+            WordVerdict::InvalidRules(_)
+        ));
+        assert_eq!(
+            submit("kassa", 1, 4).await.unwrap().verdict,
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            game_state.send(GetTurnOrder).await.unwrap(),
+            Some(vec![2, 1])
+        );
+        // The order is kept for a restarted bot
+        assert_eq!(store.load().unwrap().on_turn(), Some(2));
+
+        // Leaving on turn passes the turn on
+        assert_eq!(
+            game_state.send(LeaveRotation { user_id: 2 }).await.unwrap(),
+            Some(true)
+        );
+        assert_eq!(
+            game_state.send(LeaveRotation { user_id: 2 }).await.unwrap(),
+            Some(false)
+        );
+        assert_eq!(
+            submit("kassi", 1, 5).await.unwrap().verdict,
+            WordVerdict::ValidInDictionary
+        );
+
+        game_state.send(JoinRotation { user_id: 2 }).await.unwrap();
+        game_state.send(ResetGame).await.unwrap();
+        assert_eq!(game_state.send(GetTurnOrder).await.unwrap(), Some(vec![]));
+        assert!(store.load().unwrap().is_empty());
+
+        // Without rotation mode there's no turn order at all
+        let free = GameStateActor::new().start();
+        assert_eq!(free.send(JoinRotation { user_id: 1 }).await.unwrap(), None);
+        assert_eq!(free.send(GetTurnOrder).await.unwrap(), None);
+    }
+
+    #[actix_rt::test]
+    async fn test_turn_skip() {
+        let api = RecordingReactionApi::default();
+        let announcer =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .start();
+        let dir = tempfile::tempdir().unwrap();
+        let store = RotationStore::new(dir.path().join("turns.json"));
+        let mut game = GameStateActor::new()
+            .with_rotation(store.clone(), Duration::from_secs(600))
+            .with_language(Language::English);
+        game.message_reaction = Some(announcer);
+        let started = game.turns.as_ref().unwrap().turn_started;
+        let minutes = |m: u64| started + Duration::from_secs(m * 60);
+
+        // A lone player keeps the turn
+        game.turns.as_mut().unwrap().rotation.join(1);
+        assert!(!game.check_turn(minutes(60)));
+        game.turns.as_mut().unwrap().rotation.join(2);
+
+        assert!(!game.check_turn(minutes(9)));
+        // Paused time doesn't count
+        game.set_paused(true, minutes(5));
+        assert!(!game.check_turn(minutes(30)));
+        game.set_paused(false, minutes(25));
+        assert!(!game.check_turn(minutes(29)));
+        assert!(game.check_turn(minutes(30)));
+        assert_eq!(game.on_turn(), Some(2));
+        assert_eq!(store.load().unwrap().on_turn(), Some(2));
+        // The next player gets a whole turn
+        assert!(!game.check_turn(minutes(39)));
+        assert!(game.check_turn(minutes(40)));
+        assert_eq!(game.on_turn(), Some(1));
+
+        actix_rt::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Post(
+                    "⏭️ <@1> didn't play in time, it's <@2>'s turn now.".to_string()
+                ),
+                ReactionCall::Post(
+                    "⏭️ <@2> didn't play in time, it's <@1>'s turn now.".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_scores_follow_verdicts() {
+        let dictionary = DictionaryValidator::from_words(["kissa", "kassa", "kassi"]);
+        let game_state = GameStateActor::with_engine(GameEngine::new(
+            dictionary,
+            GameRules {
+                pending_policy: PendingPolicy::Rollback,
+                ..GameRules::default()
+            },
+        ))
+        .start();
+        let submit = |word: &str, user_id: u64, message_id: u64| {
+            game_state.send(SubmitWord {
+                content: word.to_string(),
+                user_id,
+                channel_id: 1,
+                message_id,
+                span: Span::none(),
+            })
+        };
+        let standing = |user_id| game_state.send(GetStanding { user_id });
+
+        submit("kissa", 1, 1).await.unwrap();
+        submit("kassa", 2, 2).await.unwrap();
+        // Pending words score once the LLM accepts them
+        submit("passa", 3, 3).await.unwrap();
+        assert_eq!(standing(3).await.unwrap(), Standing::default());
+        settle(&game_state, 3, Validity::Valid).await;
+        assert_eq!(
+            standing(3).await.unwrap(),
+            Standing {
+                score: PlayerScore {
+                    points: 7,
+                    words: 1
+                },
+                rank: Some(1),
+                weekly: PlayerScore {
+                    points: 7,
+                    words: 1
+                },
+                weekly_rank: Some(1),
+            }
+        );
+
+        // "kassi" is built on "passi" and loses its points when "passi" is rejected
+        submit("passi", 2, 4).await.unwrap();
+        submit("kassi", 1, 5).await.unwrap();
+        settle(&game_state, 4, Validity::Invalid).await;
+
+        assert_eq!(
+            game_state
+                .send(GetLeaderboard {
+                    period: Period::AllTime,
+                    limit: 10
+                })
+                .await
+                .unwrap(),
+            vec![
+                (
+                    3,
+                    PlayerScore {
+                        points: 7,
+                        words: 1
+                    }
+                ),
+                (
+                    1,
+                    PlayerScore {
+                        points: 5,
+                        words: 1
+                    }
+                ),
+                (
+                    2,
+                    PlayerScore {
+                        points: 5,
+                        words: 1
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_suggestions_after_llm_rejection() {
+        let api = RecordingReactionApi::default();
+        let message_reaction =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_reaction_interval(Duration::ZERO)
+                .start();
+        let dictionary = DictionaryValidator::from_words(["kissa", "kassa", "kassi"]);
+        let game_state =
+            GameStateActor::with_engine(GameEngine::new(dictionary, GameRules::default()))
+                .with_suggestions(true)
+                .start();
+        game_state.do_send(SetMessageReaction(message_reaction));
+
+        play(&game_state, "kissa", 1, true).await;
+        play(&game_state, "kissu", 2, false).await;
+        settle(&game_state, 2, Validity::Invalid).await;
+        // Words the LLM accepts get nothing
+        play(&game_state, "kissi", 3, false).await;
+        settle(&game_state, 3, Validity::Valid).await;
+
+        actix_rt::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(
+            api.calls(),
+            vec![ReactionCall::Reply(2, "Tarkoititko: kissa?".to_string())]
+        );
+        assert_eq!(
+            game_state
+                .send(SuggestWords {
+                    word: "kisa".to_string()
+                })
+                .await
+                .unwrap(),
+            vec!["kissa"]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_weekly_reset() {
+        let at = |time: &str| time.parse::<DateTime<Utc>>().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let archive = WeeklyArchive::new(dir.path().join("weekly.jsonl"));
+        let schedule = WeeklySchedule {
+            day: chrono::Weekday::Mon,
+            time: chrono::NaiveTime::MIN,
+            timezone: chrono_tz::Europe::Helsinki,
+        };
+        let api = RecordingReactionApi::default();
+        let mut game_state = GameStateActor::new().with_weekly_reset(schedule, archive.clone());
+        game_state.message_reaction = Some(
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .start(),
+        );
+
+        // The first check only notes which week is under way
+        assert!(!game_state.check_week(at("2025-01-08T12:00:00Z")));
+        game_state.scoreboard.award(1, 10, 5);
+        game_state.scoreboard.award(2, 11, 8);
+        assert!(!game_state.check_week(at("2025-01-12T21:59:00Z")));
+
+        // Monday 00:00 in Helsinki
+        assert!(game_state.check_week(at("2025-01-12T22:00:30Z")));
+        assert!(!game_state.check_week(at("2025-01-12T22:01:30Z")));
+        assert!(game_state
+            .scoreboard
+            .leaderboard(Period::Weekly, 10)
+            .is_empty());
+        assert_eq!(game_state.scoreboard.player(2).points, 8);
+        assert_eq!(archive.last_week().unwrap().as_deref(), Some("2025-01-06"));
+
+        actix_rt::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(
+            api.calls(),
+            vec![ReactionCall::Embed(
+                "Viikon 2 lopputulokset".to_string(),
+                "1. <@2> – 8 pistettä (1 sanaa)\n2. <@1> – 5 pistettä (1 sanaa)\n".to_string()
+            )]
+        );
+
+        // After a restart the week that was already posted isn't posted again
+        let mut restarted = GameStateActor::new().with_weekly_reset(schedule, archive.clone());
+        restarted.message_reaction = game_state.message_reaction.clone();
+        restarted.check_week(at("2025-01-12T21:59:00Z"));
+        restarted.scoreboard.award(1, 12, 3);
+        assert!(restarted.check_week(at("2025-01-12T22:05:00Z")));
+        assert!(restarted
+            .scoreboard
+            .leaderboard(Period::Weekly, 10)
+            .is_empty());
+
+        actix_rt::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(api.calls().len(), 1);
+        let archived = std::fs::read_to_string(archive.path()).unwrap();
+        assert_eq!(archived.lines().count(), 1);
+    }
+
+    #[actix_rt::test]
+    async fn test_snapshot_from_last_week() {
+        let at = |time: &str| time.parse::<DateTime<Utc>>().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new(dir.path().join("game.json"));
+        let archive = WeeklyArchive::new(dir.path().join("weekly.jsonl"));
+        let schedule = WeeklySchedule {
+            day: chrono::Weekday::Mon,
+            time: chrono::NaiveTime::MIN,
+            timezone: chrono_tz::Europe::Helsinki,
+        };
+        let save = |week_start| {
+            store
+                .save(&GameSnapshot {
+                    word: Some("kissa".to_string()),
+                    week_start: Some(at(week_start)),
+                    scores: vec![SavedScore {
+                        user_id: 1,
+                        all_time: PlayerScore {
+                            points: 12,
+                            words: 3,
+                        },
+                        weekly: PlayerScore {
+                            points: 4,
+                            words: 1,
+                        },
+                    }],
+                    paused: false,
+                })
+                .unwrap()
+        };
+        let restart = || {
+            GameStateActor::new()
+                .with_weekly_reset(schedule, archive.clone())
+                .with_snapshot(store.clone())
+        };
+
+        // Saved during the week that is still under way, the weekly points stay
+        save("2025-01-12T22:00:00Z");
+        let mut game_state = restart();
+        assert_eq!(game_state.restored_players, Some(1));
+        assert!(!game_state.check_week(at("2025-01-13T12:00:00Z")));
+        assert_eq!(
+            game_state.scoreboard.leaderboard(Period::Weekly, 10).len(),
+            1
+        );
+
+        // Saved the week before, which ends as soon as the game starts
+        save("2025-01-05T22:00:00Z");
+        let mut game_state = restart();
+        assert!(game_state.check_week(at("2025-01-13T12:00:00Z")));
+        assert!(game_state
+            .scoreboard
+            .leaderboard(Period::Weekly, 10)
+            .is_empty());
+        assert_eq!(game_state.scoreboard.player(1).points, 12);
+        assert_eq!(archive.last_week().unwrap().as_deref(), Some("2025-01-06"));
+
+        // The standings of a week that ended while the bot was down are posted
+        // once the channel is known
+        save("2025-01-12T22:00:00Z");
+        let api = RecordingReactionApi::default();
+        let message_reaction =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .start();
+        let game_state = restart().start();
+        game_state.do_send(SetMessageReaction(message_reaction));
+        game_state.send(Ping).await.unwrap();
+        actix_rt::time::sleep(Duration::from_millis(50)).await;
+        let posted = embeds(&api);
+        assert_eq!(posted.len(), 1);
+        assert!(posted[0].1.contains("<@1>"), "{:?}", posted);
+        assert_eq!(archive.last_week().unwrap().as_deref(), Some("2025-01-13"));
+    }
+
+    #[actix_rt::test]
+    async fn test_dispute_votes() {
+        let api = RecordingReactionApi::default();
+        let message_reaction =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_reaction_interval(Duration::ZERO)
+                .start();
+        let game_state = GameStateActor::new()
+            .with_disputes(DisputeRules {
+                votes: 2,
+                window: Duration::from_secs(600),
+                accept: '👍',
+                reject: '👎',
+            })
+            .start();
+        game_state.do_send(SetMessageReaction(message_reaction));
+        play(&game_state, "kissa", 1, true).await;
+        play(&game_state, "kassa", 2, true).await;
+        let vote = |user_id, reaction, added| DisputeVote {
+            message_id: 2,
+            user_id,
+            reaction,
+            added,
+        };
+
+        // The player's own vote and votes agreeing with the verdict don't count
+        for ignored in [vote(2, '👎', true), vote(10, '👍', true)] {
+            assert_eq!(game_state.send(ignored).await.unwrap(), None);
+        }
+        // Toggling the reaction doesn't add up
+        for toggle in [true, false, true] {
+            assert_eq!(game_state.send(vote(10, '👎', toggle)).await.unwrap(), None);
+        }
+        assert_eq!(game_state.send(vote(11, '👎', true)).await.unwrap(), None);
+        assert_eq!(
+            game_state.send(vote(12, '👎', true)).await.unwrap(),
+            Some(Validity::Invalid)
+        );
+        assert_eq!(current_word(&game_state).await.as_deref(), Some("kissa"));
+        let history = game_state.send(GetHistory { limit: 1 }).await.unwrap();
+        assert_eq!(history[0].source, Some(VerdictSource::Vote { votes: 2 }));
+
+        // The new verdict stands
+        assert_eq!(game_state.send(vote(13, '👍', true)).await.unwrap(), None);
+
+        actix_rt::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Delete(2, EMOJI_CHECK),
+                ReactionCall::Add(2, EMOJI_CROSS),
+            ]
+        );
+        assert_eq!(api.call_channels(), vec![102, 102]);
     }
 }
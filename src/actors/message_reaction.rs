@@ -1,195 +1,2020 @@
-use actix::{Actor, Context, Handler, Message};
+use actix::{
+    Actor, ActorFutureExt, AsyncContext, Context, Handler, Message, Recipient, ResponseActFuture,
+    ResponseFuture, WeakAddr,
+};
+use miette::Diagnostic;
 use poise::serenity_prelude as serenity;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::thread;
-use tracing::{debug, error, warn};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot};
+use tokio::time;
+use tracing::{debug, error, info, warn, Instrument, Span};
+
+use crate::actors::game_state::{Validity, VerdictDelivered};
+use crate::actors::{Ping, Shutdown, DEFAULT_MAILBOX_CAPACITY};
+use crate::audit::{AuditLog, AuditStep};
+use crate::config::Language;
+use crate::error::{Error, Result};
+use crate::messages::Text;
+use crate::status::StatusMessageStore;
 
 /// Emoji constants for reactions
 pub const EMOJI_CHECK: char = '✅';
 pub const EMOJI_CROSS: char = '❌';
 pub const EMOJI_QUESTION: char = '❓';
+pub const EMOJI_HOURGLASS: char = '⏳';
+pub const EMOJI_NO_ENTRY: char = '⛔';
+pub const EMOJI_TIMER: char = '⏲';
+pub const EMOJI_NEXT_TRACK: char = '⏭';
+pub const EMOJI_PENCIL: char = '✏';
+pub const EMOJI_SLEEPING: char = '💤';
+pub const EMOJI_REPEAT: char = '🔁';
+pub const EMOJI_SPEECH: char = '🗨';
+pub const EMOJI_WARNING: char = '⚠';
+pub const EMOJI_FINISH: char = '🏁';
+
+/// How often the presence may change, to stay clear of Discord's presence rate limits
+pub const PRESENCE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often the status message may be edited, to stay clear of Discord's rate limits
+pub const STATUS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Content of a new status message until it's filled in
+///
+/// The status is only written in by editing, since mentions in an edit don't
+/// ping the player named in it.
+const STATUS_PLACEHOLDER: &str = "📌";
+
+/// Default minimum time between reaction API calls
+pub const DEFAULT_REACTION_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Queue length at which a warning about the backlog is logged
+const QUEUE_WARNING_LEN: usize = 50;
+
+/// How many times a call is retried after Discord rate-limited it
+const MAX_RATE_LIMIT_RETRIES: u32 = 5;
+
+/// How long to pause when Discord rate-limits a call without saying for how long
+const RATE_LIMIT_FALLBACK: Duration = Duration::from_secs(1);
+
+/// How often the same kind of problem may be reported in the admin channel
+pub const ALERT_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// How many messages' verdict reactions are remembered, the oldest forgotten first
+pub const MAX_TRACKED_VERDICTS: usize = 1000;
+
+/// How many messages are cleaned up between progress reports in the log
+const CLEANUP_PROGRESS_STEP: usize = 50;
+
+/// Future returned by [`ReactionApi`] calls
+pub type ApiFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+/// Future returned by [`ReactionApi::send_message`], resolving to the posted message's ID
+pub type PostFuture<'a> = Pin<Box<dyn Future<Output = Result<serenity::MessageId>> + Send + 'a>>;
+
+/// The Discord operations the reaction actor needs
+///
+/// Abstracted behind a trait so the actor pipeline can run without a gateway connection.
+pub trait ReactionApi: Send + Sync {
+    fn add_reaction(
+        &self,
+        channel_id: serenity::ChannelId,
+        message_id: serenity::MessageId,
+        reaction: char,
+    ) -> ApiFuture<'_>;
+
+    fn delete_reaction(
+        &self,
+        channel_id: serenity::ChannelId,
+        message_id: serenity::MessageId,
+        reaction: char,
+    ) -> ApiFuture<'_>;
+
+    fn clear_reactions(
+        &self,
+        channel_id: serenity::ChannelId,
+        message_id: serenity::MessageId,
+    ) -> ApiFuture<'_>;
+
+    fn send_message(&self, channel_id: serenity::ChannelId, content: String) -> PostFuture<'_>;
+
+    /// Post a message in reply to another one
+    fn reply(
+        &self,
+        channel_id: serenity::ChannelId,
+        message_id: serenity::MessageId,
+        content: String,
+    ) -> ApiFuture<'_>;
+
+    /// Post an embed with a title and a description
+    fn send_embed(
+        &self,
+        channel_id: serenity::ChannelId,
+        title: String,
+        description: String,
+    ) -> ApiFuture<'_>;
+
+    /// Post a file as an attachment, with a message alongside
+    fn send_file(
+        &self,
+        channel_id: serenity::ChannelId,
+        filename: String,
+        data: Vec<u8>,
+        content: String,
+    ) -> PostFuture<'_>;
+
+    /// Replace the content of a message the bot posted
+    fn edit_message(
+        &self,
+        channel_id: serenity::ChannelId,
+        message_id: serenity::MessageId,
+        content: String,
+    ) -> ApiFuture<'_>;
+
+    fn pin_message(
+        &self,
+        channel_id: serenity::ChannelId,
+        message_id: serenity::MessageId,
+    ) -> ApiFuture<'_>;
+
+    /// Replace the bot's activity in its presence
+    fn set_activity(&self, activity: serenity::ActivityData);
+}
+
+/// [`ReactionApi`] implementation backed by the serenity HTTP client
+pub struct SerenityReactionApi {
+    discord_ctx: Arc<serenity::Context>,
+}
+
+impl SerenityReactionApi {
+    pub fn new(discord_ctx: Arc<serenity::Context>) -> Self {
+        Self { discord_ctx }
+    }
+}
+
+impl ReactionApi for SerenityReactionApi {
+    fn add_reaction(
+        &self,
+        channel_id: serenity::ChannelId,
+        message_id: serenity::MessageId,
+        reaction: char,
+    ) -> ApiFuture<'_> {
+        Box::pin(async move {
+            let message = channel_id
+                .message(&self.discord_ctx, message_id)
+                .await
+                .map_err(api_error)?;
+            message
+                .react(&self.discord_ctx, reaction)
+                .await
+                .map_err(api_error)?;
+            Ok(())
+        })
+    }
+
+    fn delete_reaction(
+        &self,
+        channel_id: serenity::ChannelId,
+        message_id: serenity::MessageId,
+        reaction: char,
+    ) -> ApiFuture<'_> {
+        Box::pin(async move {
+            let message = channel_id
+                .message(&self.discord_ctx, message_id)
+                .await
+                .map_err(api_error)?;
+            message
+                .delete_reaction_emoji(&self.discord_ctx, reaction)
+                .await
+                .map_err(api_error)?;
+            Ok(())
+        })
+    }
+
+    fn clear_reactions(
+        &self,
+        channel_id: serenity::ChannelId,
+        message_id: serenity::MessageId,
+    ) -> ApiFuture<'_> {
+        Box::pin(async move {
+            let message = channel_id
+                .message(&self.discord_ctx, message_id)
+                .await
+                .map_err(api_error)?;
+            message
+                .delete_reactions(&self.discord_ctx)
+                .await
+                .map_err(api_error)?;
+            Ok(())
+        })
+    }
+
+    fn send_message(&self, channel_id: serenity::ChannelId, content: String) -> PostFuture<'_> {
+        Box::pin(async move {
+            let message = channel_id.say(&self.discord_ctx, content).await?;
+            Ok(message.id)
+        })
+    }
+
+    fn reply(
+        &self,
+        channel_id: serenity::ChannelId,
+        message_id: serenity::MessageId,
+        content: String,
+    ) -> ApiFuture<'_> {
+        Box::pin(async move {
+            let message = serenity::CreateMessage::new()
+                .content(content)
+                .reference_message((channel_id, message_id));
+            channel_id
+                .send_message(&self.discord_ctx, message)
+                .await
+                .map_err(api_error)?;
+            Ok(())
+        })
+    }
+
+    fn send_embed(
+        &self,
+        channel_id: serenity::ChannelId,
+        title: String,
+        description: String,
+    ) -> ApiFuture<'_> {
+        Box::pin(async move {
+            let embed = serenity::CreateEmbed::new()
+                .title(title)
+                .description(description);
+            channel_id
+                .send_message(
+                    &self.discord_ctx,
+                    serenity::CreateMessage::new().embed(embed),
+                )
+                .await
+                .map_err(api_error)?;
+            Ok(())
+        })
+    }
+
+    fn send_file(
+        &self,
+        channel_id: serenity::ChannelId,
+        filename: String,
+        data: Vec<u8>,
+        content: String,
+    ) -> PostFuture<'_> {
+        Box::pin(async move {
+            let message = serenity::CreateMessage::new()
+                .content(content)
+                .add_file(serenity::CreateAttachment::bytes(data, filename));
+            let message = channel_id
+                .send_message(&self.discord_ctx, message)
+                .await
+                .map_err(api_error)?;
+            Ok(message.id)
+        })
+    }
+
+    fn edit_message(
+        &self,
+        channel_id: serenity::ChannelId,
+        message_id: serenity::MessageId,
+        content: String,
+    ) -> ApiFuture<'_> {
+        Box::pin(async move {
+            let edit = serenity::EditMessage::new()
+                .content(content)
+                .allowed_mentions(serenity::CreateAllowedMentions::new());
+            channel_id
+                .edit_message(&self.discord_ctx, message_id, edit)
+                .await
+                .map_err(api_error)?;
+            Ok(())
+        })
+    }
+
+    fn pin_message(
+        &self,
+        channel_id: serenity::ChannelId,
+        message_id: serenity::MessageId,
+    ) -> ApiFuture<'_> {
+        Box::pin(async move {
+            channel_id
+                .pin(&self.discord_ctx, message_id)
+                .await
+                .map_err(api_error)?;
+            Ok(())
+        })
+    }
+
+    fn set_activity(&self, activity: serenity::ActivityData) {
+        self.discord_ctx.set_activity(Some(activity));
+    }
+}
+
+/// Turn a failed Discord call into an error, recognizing rate limits
+///
+/// Serenity already waits out most rate limits itself and doesn't hand the
+/// `Retry-After` of a 429 it gives up on, so those pause for a fixed time.
+fn api_error(e: serenity::Error) -> Error {
+    if let serenity::Error::Http(serenity::HttpError::UnsuccessfulRequest(response)) = &e {
+        if response.status_code.as_u16() == 429 {
+            return Error::RateLimited(RATE_LIMIT_FALLBACK);
+        }
+    }
+    e.into()
+}
 
 /// Message to add a reaction to a Discord message
-#[derive(Message)]
+#[derive(Message, Clone)]
 #[rtype(result = "()")]
 pub struct AddReaction {
+    /// Channel or thread the message is in
+    pub channel_id: u64,
     pub message_id: u64,
     pub reaction: char,
+    /// Span of the word being validated, usually [`Span::current`]
+    pub span: Span,
 }
 
 /// Message to clear reactions from a Discord message
 #[derive(Message)]
 #[rtype(result = "()")]
 pub struct ClearReactions {
+    /// Channel or thread the message is in
+    pub channel_id: u64,
     pub message_id: u64,
 }
 
 /// Message to delete a specific reaction from a Discord message
-#[derive(Message)]
+#[derive(Message, Clone)]
 #[rtype(result = "()")]
 pub struct DeleteReaction {
+    /// Channel or thread the message is in
+    pub channel_id: u64,
     pub message_id: u64,
     pub reaction: char,
+    /// Span of the word being validated, usually [`Span::current`]
+    pub span: Span,
+}
+
+/// Message to show a word's verdict as its only verdict reaction: ❓, ✅ or ❌
+///
+/// Only the reactions needed to get there are changed: setting the verdict
+/// the word already shows changes nothing, and a new verdict replaces the old
+/// one with one removal and one addition.
+#[derive(Message, Clone)]
+#[rtype(result = "()")]
+pub struct SetVerdict {
+    /// Channel or thread the message is in
+    pub channel_id: u64,
+    pub message_id: u64,
+    pub validity: Validity,
+    /// The verdict the word showed if this actor hasn't set one, e.g. before a restart
+    pub replaces: Option<Validity>,
+    /// Span of the word being validated, usually [`Span::current`]
+    pub span: Span,
+}
+
+/// Message to tell the game state whether each [`SetVerdict`] reached Discord
+///
+/// The game state is started before the reaction actor, so it's set once
+/// Discord is connected.
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct ReportVerdicts(pub Recipient<VerdictDelivered>);
+
+/// The reaction showing a verdict
+pub fn verdict_reaction(validity: Validity) -> char {
+    match validity {
+        Validity::Pending => EMOJI_QUESTION,
+        Validity::Valid => EMOJI_CHECK,
+        Validity::Invalid => EMOJI_CROSS,
+    }
+}
+
+/// What happens to the reactions of a game's words when the game resets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResetReactions {
+    /// Leave the verdicts as they are
+    #[default]
+    None,
+    /// Remove the bot's verdict reactions
+    Clear,
+    /// Add 🏁 to show the words belong to a finished game
+    Mark,
+}
+
+impl FromStr for ResetReactions {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "none" => Ok(ResetReactions::None),
+            "clear" => Ok(ResetReactions::Clear),
+            "mark" => Ok(ResetReactions::Mark),
+            other => Err(Error::Config(format!(
+                "Invalid RESET_REACTIONS '{}', expected 'none', 'clear' or 'mark'",
+                other
+            ))),
+        }
+    }
+}
+
+/// A word of a game that has been reset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FinishedWord {
+    /// Channel or thread the message is in
+    pub channel_id: u64,
+    pub message_id: u64,
+    /// The verdict the word got
+    pub validity: Validity,
+}
+
+/// Message to clean up the reactions of a game that has been reset
+///
+/// The changes go through the reaction queue one at a time in the
+/// background, so the new game's reactions don't wait behind a long history.
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct CleanUpReactions {
+    pub mode: ResetReactions,
+    pub words: Vec<FinishedWord>,
+}
+
+/// Message to post a new message to the game channel (or thread)
+///
+/// Returns where the message was posted, None if posting failed.
+#[derive(Message)]
+#[rtype(result = "Option<PostedMessage>")]
+pub struct PostMessage {
+    pub content: String,
+}
+
+/// Where a message was posted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PostedMessage {
+    pub channel_id: u64,
+    pub message_id: u64,
+}
+
+/// Message to post a file to a channel, e.g. an export of the game history
+///
+/// Returns where the file was posted, None if posting failed.
+#[derive(Message)]
+#[rtype(result = "Option<PostedMessage>")]
+pub struct PostFile {
+    pub channel_id: u64,
+    pub filename: String,
+    pub data: Vec<u8>,
+    /// Message posted alongside the file
+    pub content: String,
+}
+
+/// Message to post an embed to the game channel (or thread), e.g. weekly results
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct PostEmbed {
+    pub title: String,
+    pub description: String,
+}
+
+/// Message to reply to a message in the game, e.g. to explain a rejection
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct Reply {
+    /// Channel or thread the message is in
+    pub channel_id: u64,
+    pub message_id: u64,
+    pub content: String,
+}
+
+/// Message to show the game's progress in the bot's presence
+///
+/// Ignored unless dynamic presence is enabled with
+/// [`MessageReactionActor::with_dynamic_presence`].
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct UpdatePresence {
+    /// The last accepted word, None when the game has just been reset
+    pub word: Option<String>,
+    pub chain_length: usize,
+}
+
+/// Message to show the state of the game in the pinned status message
+///
+/// Ignored unless the status message is enabled with
+/// [`MessageReactionActor::with_status_message`].
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct UpdateStatus {
+    /// The word to continue from, None when the game has just been reset
+    pub word: Option<String>,
+    pub chain_length: usize,
+    /// Who played the word, None if nobody did, e.g. after /setword
+    pub user_id: Option<u64>,
+}
+
+/// High-severity problems reported in the admin channel
+///
+/// Each kind is rate-limited separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlertKind {
+    /// Several LLM batches in a row have failed
+    LlmFailures,
+    /// The bot isn't allowed to manage reactions in the game channel
+    ReactionPermissions,
+    /// The startup check found permissions missing in the game channel
+    ChannelPermissions,
+    /// A spawned task panicked
+    TaskPanic,
+    /// A message on a critical path couldn't be delivered to an actor
+    DeliveryFailure,
+    /// The daily LLM budget is used up
+    LlmBudget,
+    /// The LLM didn't answer the startup check, e.g. because of a misspelled model
+    LlmStartupCheck,
+}
+
+impl AlertKind {
+    fn summary(self) -> &'static str {
+        match self {
+            AlertKind::LlmFailures => "LLM validation keeps failing",
+            AlertKind::ReactionPermissions => "Missing permissions to manage reactions",
+            AlertKind::ChannelPermissions => "Missing permissions in the game channel",
+            AlertKind::TaskPanic => "A background task panicked",
+            AlertKind::DeliveryFailure => "Messages between actors are getting lost",
+            AlertKind::LlmBudget => "The daily LLM budget is used up",
+            AlertKind::LlmStartupCheck => "The LLM failed its startup check, check LLM_MODEL",
+        }
+    }
+}
+
+/// Message to report a problem in the admin channel, if one is configured
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct NotifyAdmin {
+    pub kind: AlertKind,
+    pub error: Error,
+}
+
+/// A change to a message's reactions
+#[derive(Debug, Clone, Copy)]
+enum ReactionChange {
+    Add(char),
+    Delete(char),
+    Clear,
+}
+
+/// A reaction API call waiting in the actor's queue
+struct QueuedCall {
+    channel_id: serenity::ChannelId,
+    message_id: serenity::MessageId,
+    change: ReactionChange,
+    span: Span,
+    /// Answered once the call has been made, with whether it succeeded
+    done: oneshot::Sender<bool>,
+}
+
+/// What the queue worker needs to make the calls
+struct QueueWorker {
+    api: Arc<dyn ReactionApi>,
+    interval: Duration,
+    queue_len: Arc<AtomicUsize>,
+    /// For reporting permission problems, weak so the worker doesn't keep the actor alive
+    actor: WeakAddr<MessageReactionActor>,
+    audit: Arc<AuditLog>,
+}
+
+/// The pinned message in the game channel showing the state of the game
+struct StatusMessage {
+    store: StatusMessageStore,
+    /// None until the first status has been posted
+    message_id: Option<serenity::MessageId>,
+    /// Content waiting for the status interval to pass
+    pending: Option<String>,
+    last_edit: Option<Instant>,
+    scheduled: bool,
 }
 
 /// Actor that manages Discord message reactions
+///
+/// Reaction changes are made one at a time in the order they arrive, at least
+/// [`DEFAULT_REACTION_INTERVAL`] (or the configured interval) apart, so a burst
+/// of words doesn't run into Discord's rate limits.
 pub struct MessageReactionActor {
-    discord_ctx: Arc<serenity::Context>,
+    api: Arc<dyn ReactionApi>,
     channel_id: serenity::ChannelId,
+    admin_channel_id: Option<serenity::ChannelId>,
+    /// When each kind of alert was last posted
+    last_alerts: HashMap<AlertKind, Instant>,
+    mailbox_capacity: usize,
+    /// Minimum time between reaction API calls
+    reaction_interval: Duration,
+    queue: mpsc::UnboundedSender<QueuedCall>,
+    /// Taken by the queue worker when the actor starts
+    queue_rx: Option<mpsc::UnboundedReceiver<QueuedCall>>,
+    queue_len: Arc<AtomicUsize>,
+    /// Static activity shown when there's no word, None if the presence stays static
+    presence_fallback: Option<String>,
+    /// Activity waiting for the presence interval to pass
+    pending_presence: Option<serenity::ActivityData>,
+    last_presence: Option<Instant>,
+    presence_scheduled: bool,
+    /// None if the game has no status message
+    status: Option<StatusMessage>,
+    /// Whether the status message says the bot is offline once it shuts down
+    offline_status: bool,
+    /// Language of the presence text
+    language: Language,
+    /// The verdict each message shows, keyed by message ID so the oldest go first
+    verdicts: BTreeMap<u64, Validity>,
+    /// Told whether each verdict reaction reached Discord
+    verdict_reports: Option<Recipient<VerdictDelivered>>,
+    /// Where every reaction change is noted down for /why
+    audit: Arc<AuditLog>,
 }
 
 impl MessageReactionActor {
     pub fn new(discord_ctx: Arc<serenity::Context>, channel_id: serenity::ChannelId) -> Self {
+        Self::with_api(Arc::new(SerenityReactionApi::new(discord_ctx)), channel_id)
+    }
+
+    /// Create the actor on top of any [`ReactionApi`] implementation
+    pub fn with_api(api: Arc<dyn ReactionApi>, channel_id: serenity::ChannelId) -> Self {
+        let (queue, queue_rx) = mpsc::unbounded_channel();
         Self {
-            discord_ctx,
+            api,
             channel_id,
+            admin_channel_id: None,
+            last_alerts: HashMap::new(),
+            mailbox_capacity: DEFAULT_MAILBOX_CAPACITY,
+            reaction_interval: DEFAULT_REACTION_INTERVAL,
+            queue,
+            queue_rx: Some(queue_rx),
+            queue_len: Arc::new(AtomicUsize::new(0)),
+            presence_fallback: None,
+            pending_presence: None,
+            last_presence: None,
+            presence_scheduled: false,
+            status: None,
+            offline_status: false,
+            language: Language::default(),
+            verdicts: BTreeMap::new(),
+            verdict_reports: None,
+            audit: Arc::new(AuditLog::new(0)),
+        }
+    }
+
+    /// Post [`NotifyAdmin`] alerts to `admin_channel_id`
+    pub fn with_admin_channel(mut self, admin_channel_id: serenity::ChannelId) -> Self {
+        self.admin_channel_id = Some(admin_channel_id);
+        self
+    }
+
+    /// How many messages may wait for the actor before senders have to wait
+    pub fn with_mailbox_capacity(mut self, mailbox_capacity: usize) -> Self {
+        self.mailbox_capacity = mailbox_capacity;
+        self
+    }
+
+    /// Wait at least `reaction_interval` between reaction API calls
+    pub fn with_reaction_interval(mut self, reaction_interval: Duration) -> Self {
+        self.reaction_interval = reaction_interval;
+        self
+    }
+
+    /// Show the current word in the presence, falling back to `activity` without one
+    pub fn with_dynamic_presence(mut self, activity: String) -> Self {
+        self.presence_fallback = Some(activity);
+        self
+    }
+
+    /// Keep a pinned message in the game channel up to date with the game
+    ///
+    /// The message's ID is kept in `store`, so the same message is edited
+    /// after a restart.
+    pub fn with_status_message(mut self, store: StatusMessageStore) -> Self {
+        let message_id = match store.get(self.channel_id.get()) {
+            Ok(message_id) => message_id.map(serenity::MessageId::new),
+            Err(e) => {
+                warn!(
+                    path = %store.path().display(),
+                    error = %e,
+                    "Failed to read the status message ID, posting a new one"
+                );
+                None
+            }
+        };
+        self.status = Some(StatusMessage {
+            store,
+            message_id,
+            pending: None,
+            last_edit: None,
+            scheduled: false,
+        });
+        self
+    }
+
+    /// Say in the status message that the bot is offline when it shuts down
+    pub fn with_offline_status(mut self, offline_status: bool) -> Self {
+        self.offline_status = offline_status;
+        self
+    }
+
+    /// Write the presence in `language`
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Note down every reaction change and whether Discord accepted it in `audit`
+    pub fn with_audit(mut self, audit: Arc<AuditLog>) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// How long the next presence change has to wait at `now`, if at all
+    fn presence_wait(&self, now: Instant) -> Option<Duration> {
+        let last = self.last_presence?;
+        Some(PRESENCE_INTERVAL.saturating_sub(now.duration_since(last))).filter(|w| !w.is_zero())
+    }
+
+    /// Apply the pending presence, if any
+    fn flush_presence(&mut self, now: Instant) {
+        if let Some(activity) = self.pending_presence.take() {
+            self.api.set_activity(activity);
+            self.last_presence = Some(now);
+        }
+    }
+
+    /// How long the next status edit has to wait at `now`, if at all
+    fn status_wait(&self, now: Instant) -> Option<Duration> {
+        let last = self.status.as_ref()?.last_edit?;
+        Some(STATUS_INTERVAL.saturating_sub(now.duration_since(last))).filter(|w| !w.is_zero())
+    }
+
+    /// Write the pending status, if any
+    fn flush_status(&mut self, ctx: &mut Context<Self>, now: Instant) {
+        let Some(status) = &mut self.status else {
+            return;
+        };
+        let Some(content) = status.pending.take() else {
+            return;
+        };
+        status.last_edit = Some(now);
+
+        let write = write_status(
+            self.api.clone(),
+            status.store.clone(),
+            self.channel_id,
+            status.message_id,
+            content,
+        );
+        ctx.spawn(
+            actix::fut::wrap_future(write).map(|message_id, act: &mut Self, _ctx| {
+                if let Some(status) = &mut act.status {
+                    status.message_id = message_id;
+                }
+            }),
+        );
+    }
+
+    /// Queue a reaction change, resolving to whether it succeeded once the call has been made
+    fn enqueue(
+        &self,
+        channel_id: u64,
+        message_id: u64,
+        change: ReactionChange,
+        span: Span,
+    ) -> ResponseFuture<bool> {
+        let (done, made) = oneshot::channel();
+        let queue_len = self.queue_len.fetch_add(1, Ordering::Relaxed) + 1;
+        if queue_len == QUEUE_WARNING_LEN {
+            warn!(queue_len, "Reaction queue is backing up");
+        }
+
+        let call = QueuedCall {
+            channel_id: serenity::ChannelId::new(channel_id),
+            message_id: serenity::MessageId::new(message_id),
+            change,
+            span,
+            done,
+        };
+        if self.queue.send(call).is_err() {
+            self.queue_len.fetch_sub(1, Ordering::Relaxed);
+            error!(
+                message_id,
+                "Reaction queue has stopped, dropping reaction change"
+            );
+        }
+
+        Box::pin(async move {
+            // An error only means the worker is gone, which has been logged
+            made.await.unwrap_or(false)
+        })
+    }
+
+    /// The reaction changes that take a message from the verdict it shows to `validity`
+    fn verdict_changes(
+        &mut self,
+        message_id: u64,
+        validity: Validity,
+        replaces: Option<Validity>,
+    ) -> Vec<ReactionChange> {
+        let shown = self.verdicts.insert(message_id, validity).or(replaces);
+        if self.verdicts.len() > MAX_TRACKED_VERDICTS {
+            self.verdicts.pop_first();
+        }
+        match shown {
+            Some(shown) if shown == validity => Vec::new(),
+            Some(shown) => vec![
+                ReactionChange::Delete(verdict_reaction(shown)),
+                ReactionChange::Add(verdict_reaction(validity)),
+            ],
+            None => vec![ReactionChange::Add(verdict_reaction(validity))],
+        }
+    }
+
+    /// Whether an alert of this kind may be posted at `now`, recording it if so
+    fn take_alert_slot(&mut self, kind: AlertKind, now: Instant) -> bool {
+        match self.last_alerts.get(&kind) {
+            Some(last) if now.duration_since(*last) < ALERT_INTERVAL => false,
+            _ => {
+                self.last_alerts.insert(kind, now);
+                true
+            }
         }
     }
 }
 
+/// Edit the status message, posting and pinning a new one if there's none or it was deleted
+///
+/// Returns the status message's ID, None if there still is none.
+async fn write_status(
+    api: Arc<dyn ReactionApi>,
+    store: StatusMessageStore,
+    channel_id: serenity::ChannelId,
+    message_id: Option<serenity::MessageId>,
+    content: String,
+) -> Option<serenity::MessageId> {
+    if let Some(message_id) = message_id {
+        match api
+            .edit_message(channel_id, message_id, content.clone())
+            .await
+        {
+            Ok(()) => {
+                debug!(message_id = message_id.get(), "Updated status message");
+                return Some(message_id);
+            }
+            Err(e) if e.to_string().contains("Unknown Message") => warn!(
+                message_id = message_id.get(),
+                "The status message was deleted, posting a new one"
+            ),
+            Err(e) => {
+                error!(
+                    message_id = message_id.get(),
+                    error = %e,
+                    "Failed to update the status message"
+                );
+                return Some(message_id);
+            }
+        }
+    }
+
+    let message_id = match api
+        .send_message(channel_id, STATUS_PLACEHOLDER.to_string())
+        .await
+    {
+        Ok(message_id) => message_id,
+        Err(e) => {
+            error!(
+                channel_id = channel_id.get(),
+                error = %e,
+                "Failed to post the status message"
+            );
+            return None;
+        }
+    };
+    if let Err(e) = api.edit_message(channel_id, message_id, content).await {
+        error!(
+            message_id = message_id.get(),
+            error = %e,
+            "Failed to fill in the status message"
+        );
+    }
+    if let Err(e) = api.pin_message(channel_id, message_id).await {
+        warn!(
+            message_id = message_id.get(),
+            error = %e,
+            "Failed to pin the status message, the bot needs the MANAGE_MESSAGES permission"
+        );
+    }
+    if let Err(e) = store.set(channel_id.get(), message_id.get()) {
+        error!(
+            path = %store.path().display(),
+            error = %e,
+            "Failed to save the status message ID, a restart will post a new one"
+        );
+    }
+    info!(message_id = message_id.get(), "Posted a new status message");
+    Some(message_id)
+}
+
+/// Text of an admin alert, including the error code for looking it up
+fn alert_text(kind: AlertKind, error: &Error) -> String {
+    let code = error
+        .code()
+        .map(|code| code.to_string())
+        .unwrap_or_else(|| "sanabotti::unknown".to_string());
+    format!("⚠️ **{}** (`{}`)\n{}", kind.summary(), code, error)
+}
+
+/// Whether a Discord API error means the bot lacks permissions in the channel
+fn is_permission_error(e: &Error) -> bool {
+    let description = e.to_string();
+    description.contains("Missing Access") || description.contains("Missing Permissions")
+}
+
 impl Actor for MessageReactionActor {
     type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Context<Self>) {
+        ctx.set_mailbox_capacity(self.mailbox_capacity);
+
+        if let Some(queue) = self.queue_rx.take() {
+            let worker = QueueWorker {
+                api: self.api.clone(),
+                interval: self.reaction_interval,
+                queue_len: self.queue_len.clone(),
+                actor: ctx.address().downgrade(),
+                audit: self.audit.clone(),
+            };
+            ctx.spawn(actix::fut::wrap_future(worker.run(queue)));
+        }
+    }
+}
+
+impl QueueWorker {
+    /// Make the queued calls in order, pausing between them and whenever Discord rate-limits us
+    async fn run(self, mut queue: mpsc::UnboundedReceiver<QueuedCall>) {
+        let mut next_call = time::Instant::now();
+        while let Some(call) = queue.recv().await {
+            self.queue_len.fetch_sub(1, Ordering::Relaxed);
+
+            let mut retries = 0;
+            let result = loop {
+                time::sleep_until(next_call).await;
+                let result = self.make(&call).instrument(call.span.clone()).await;
+                next_call = time::Instant::now() + self.interval;
+                match result {
+                    Err(Error::RateLimited(retry_after)) if retries < MAX_RATE_LIMIT_RETRIES => {
+                        retries += 1;
+                        call.span.in_scope(|| {
+                            warn!(
+                                message_id = call.message_id.get(),
+                                retry_after_ms = retry_after.as_millis() as u64,
+                                "Rate limited by Discord, pausing the reaction queue"
+                            )
+                        });
+                        next_call = time::Instant::now() + retry_after.max(self.interval);
+                    }
+                    result => break result,
+                }
+            };
+
+            let made = result.is_ok();
+            let step = match call.change {
+                ReactionChange::Add(reaction) => AuditStep::ReactionAdded { reaction, ok: made },
+                ReactionChange::Delete(reaction) => {
+                    AuditStep::ReactionRemoved { reaction, ok: made }
+                }
+                ReactionChange::Clear => AuditStep::ReactionsCleared { ok: made },
+            };
+            self.audit.record(call.message_id.get(), step);
+            call.span.in_scope(|| self.report(&call, result));
+            call.done.send(made).ok();
+        }
+    }
+
+    fn make(&self, call: &QueuedCall) -> ApiFuture<'_> {
+        match call.change {
+            ReactionChange::Add(reaction) => {
+                self.api
+                    .add_reaction(call.channel_id, call.message_id, reaction)
+            }
+            ReactionChange::Delete(reaction) => {
+                self.api
+                    .delete_reaction(call.channel_id, call.message_id, reaction)
+            }
+            ReactionChange::Clear => self.api.clear_reactions(call.channel_id, call.message_id),
+        }
+    }
+
+    /// Log the outcome of a call, alerting the admins about missing permissions
+    fn report(&self, call: &QueuedCall, result: Result<()>) {
+        let message_id = call.message_id;
+        match (call.change, result) {
+            (ReactionChange::Add(reaction), Ok(())) => debug!(
+                message_id = message_id.get(),
+                reaction = %reaction,
+                outcome = "added",
+                "Added reaction"
+            ),
+            (ReactionChange::Delete(reaction), Ok(())) => debug!(
+                message_id = message_id.get(),
+                reaction = %reaction,
+                outcome = "deleted",
+                "Deleted reaction"
+            ),
+            (ReactionChange::Clear, Ok(())) => debug!(
+                message_id = message_id.get(),
+                outcome = "cleared",
+                "Cleared all reactions"
+            ),
+            (ReactionChange::Add(reaction), Err(e)) => {
+                log_api_error("add reaction", message_id, Some(reaction), &e);
+                if is_permission_error(&e) {
+                    if let Some(actor) = self.actor.upgrade() {
+                        actor.do_send(NotifyAdmin {
+                            kind: AlertKind::ReactionPermissions,
+                            error: e,
+                        });
+                    }
+                }
+            }
+            (ReactionChange::Delete(reaction), Err(e)) => {
+                log_api_error("delete reaction", message_id, Some(reaction), &e)
+            }
+            (ReactionChange::Clear, Err(e)) => {
+                log_api_error("clear reactions", message_id, None, &e)
+            }
+        }
+    }
+}
+
+/// Log a failed Discord API call, trying to diagnose the most common causes
+fn log_api_error(action: &str, message_id: serenity::MessageId, reaction: Option<char>, e: &Error) {
+    let reaction = reaction.map(String::from);
+    error!(
+        action,
+        message_id = message_id.get(),
+        reaction = reaction.as_deref(),
+        error = %e,
+        "Discord API call failed"
+    );
+
+    if is_permission_error(e) {
+        warn!("Bot lacks permission to manage reactions. Please ensure it has the ADD_REACTIONS permission.");
+    } else if e.to_string().contains("Unknown Message") {
+        warn!(
+            message_id = message_id.get(),
+            "Message not found. It may have been deleted or the bot cannot access it."
+        );
+    }
 }
 
 impl Handler<AddReaction> for MessageReactionActor {
-    type Result = ();
+    type Result = ResponseFuture<()>;
 
     fn handle(&mut self, msg: AddReaction, _ctx: &mut Context<Self>) -> Self::Result {
-        let discord_ctx = self.discord_ctx.clone();
-        let channel_id = self.channel_id;
-        let message_id = serenity::MessageId::new(msg.message_id);
-        let reaction = msg.reaction; // Using char directly
+        msg.span.in_scope(|| {
+            debug!(
+                message_id = msg.message_id,
+                reaction = %msg.reaction,
+                "Attempting to add reaction"
+            )
+        });
+        let made = self.enqueue(
+            msg.channel_id,
+            msg.message_id,
+            ReactionChange::Add(msg.reaction),
+            msg.span,
+        );
+        Box::pin(async move {
+            made.await;
+        })
+    }
+}
 
-        debug!(
-            "Attempting to add reaction '{}' to message {}",
-            reaction, message_id
+impl Handler<DeleteReaction> for MessageReactionActor {
+    type Result = ResponseFuture<()>;
+
+    fn handle(&mut self, msg: DeleteReaction, _ctx: &mut Context<Self>) -> Self::Result {
+        let made = self.enqueue(
+            msg.channel_id,
+            msg.message_id,
+            ReactionChange::Delete(msg.reaction),
+            msg.span,
         );
+        Box::pin(async move {
+            made.await;
+        })
+    }
+}
 
-        // Use std::thread to handle Discord API calls without requiring LocalSet
-        let handle = thread::spawn(move || {
-            let rt = tokio::runtime::Builder::new_current_thread()
-                .enable_all()
-                .build()
-                .unwrap();
+impl Handler<SetVerdict> for MessageReactionActor {
+    type Result = ResponseActFuture<Self, ()>;
 
-            rt.block_on(async {
-                debug!("Starting to process reaction '{}' for message {}", reaction, message_id);
-
-                // Simplified permissions check
-                let bot_id = discord_ctx.cache.current_user().id;
-                debug!("Bot ID for permissions check: {:?}", bot_id);
-
-                match channel_id.message(&discord_ctx, message_id).await {
-                    Ok(message) => {
-                        debug!("Successfully fetched message {}, adding reaction '{}'", message_id, reaction);
-
-                        // Try to add the reaction
-                        match message.react(&discord_ctx, reaction).await {
-                            Ok(_) => {
-                                debug!("Successfully added reaction '{}' to message {}", reaction, message_id);
-                            },
-                            Err(e) => {
-                                error!("Failed to add reaction '{}' to message {}: {}", reaction, message_id, e);
-                                // Try to diagnose the issue
-                                if e.to_string().contains("Missing Access") || e.to_string().contains("Missing Permissions") {
-                                    warn!("Bot lacks permission to add reactions. Please ensure it has the ADD_REACTIONS permission.");
-                                } else if e.to_string().contains("Unknown Message") {
-                                    warn!("Message {} not found. It may have been deleted.", message_id);
-                                }
-                            }
-                        }
-                    },
-                    Err(e) => {
-                        error!("Failed to fetch message {}: {}", message_id, e);
-                        if e.to_string().contains("Unknown Message") {
-                            warn!("Message {} not found. It may have been deleted or the bot cannot access it.", message_id);
-                        }
-                    }
-                }
-            });
+    fn handle(&mut self, msg: SetVerdict, _ctx: &mut Context<Self>) -> Self::Result {
+        let changes = self.verdict_changes(msg.message_id, msg.validity, msg.replaces);
+        msg.span.in_scope(|| {
+            debug!(
+                message_id = msg.message_id,
+                validity = ?msg.validity,
+                changes = changes.len(),
+                "Setting verdict reaction"
+            )
         });
+        let made: Vec<_> = changes
+            .into_iter()
+            .map(|change| self.enqueue(msg.channel_id, msg.message_id, change, msg.span.clone()))
+            .collect();
+        let made = async move {
+            let mut delivered = true;
+            for change in made {
+                delivered &= change.await;
+            }
+            delivered
+        };
 
-        // Don't wait for the thread to complete
-        std::mem::drop(handle);
+        let SetVerdict {
+            message_id,
+            validity,
+            ..
+        } = msg;
+        Box::pin(
+            actix::fut::wrap_future(made).map(move |delivered, act: &mut Self, _ctx| {
+                // What the message shows is anyone's guess after a failed change
+                if !delivered && act.verdicts.get(&message_id) == Some(&validity) {
+                    act.verdicts.remove(&message_id);
+                }
+                if let Some(reports) = &act.verdict_reports {
+                    reports.do_send(VerdictDelivered {
+                        message_id,
+                        validity,
+                        delivered,
+                    });
+                }
+            }),
+        )
     }
 }
 
-impl Handler<DeleteReaction> for MessageReactionActor {
+impl Handler<ReportVerdicts> for MessageReactionActor {
     type Result = ();
 
-    fn handle(&mut self, msg: DeleteReaction, _ctx: &mut Context<Self>) -> Self::Result {
-        let discord_ctx = self.discord_ctx.clone();
+    fn handle(&mut self, msg: ReportVerdicts, _ctx: &mut Context<Self>) -> Self::Result {
+        self.verdict_reports = Some(msg.0);
+    }
+}
+
+impl Handler<ClearReactions> for MessageReactionActor {
+    type Result = ResponseFuture<()>;
+
+    fn handle(&mut self, msg: ClearReactions, _ctx: &mut Context<Self>) -> Self::Result {
+        self.verdicts.remove(&msg.message_id);
+        let made = self.enqueue(
+            msg.channel_id,
+            msg.message_id,
+            ReactionChange::Clear,
+            Span::current(),
+        );
+        Box::pin(async move {
+            made.await;
+        })
+    }
+}
+
+impl Handler<CleanUpReactions> for MessageReactionActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: CleanUpReactions, ctx: &mut Context<Self>) -> Self::Result {
+        let reaction = |act: &mut Self, word: &FinishedWord| match msg.mode {
+            ResetReactions::None => None,
+            // The verdict the message shows, if this actor set it
+            ResetReactions::Clear => Some(verdict_reaction(
+                act.verdicts
+                    .remove(&word.message_id)
+                    .unwrap_or(word.validity),
+            )),
+            ResetReactions::Mark => Some(EMOJI_FINISH),
+        };
+        let changes: Vec<_> = msg
+            .words
+            .iter()
+            .filter_map(|word| Some((word.channel_id, word.message_id, reaction(self, word)?)))
+            .collect();
+        if changes.is_empty() {
+            return;
+        }
+
+        let total = changes.len();
+        let mode = msg.mode;
+        info!(
+            total,
+            ?mode,
+            "Cleaning up the reactions of the finished game"
+        );
+        let actor = ctx.address();
+        let span = Span::current();
+        let cleanup = async move {
+            for (done, (channel_id, message_id, reaction)) in changes.into_iter().enumerate() {
+                // Each change waits for the one before it, so new words' reactions go in between
+                let sent = if mode == ResetReactions::Clear {
+                    actor
+                        .send(DeleteReaction {
+                            channel_id,
+                            message_id,
+                            reaction,
+                            span: span.clone(),
+                        })
+                        .await
+                } else {
+                    actor
+                        .send(AddReaction {
+                            channel_id,
+                            message_id,
+                            reaction,
+                            span: span.clone(),
+                        })
+                        .await
+                };
+                if sent.is_err() {
+                    warn!(
+                        done,
+                        total, "Reaction actor stopped, cleanup left unfinished"
+                    );
+                    return;
+                }
+                if (done + 1) % CLEANUP_PROGRESS_STEP == 0 && done + 1 < total {
+                    info!(done = done + 1, total, "Cleaning up reactions");
+                }
+            }
+            info!(total, "Cleaned up the reactions of the finished game");
+        };
+        ctx.spawn(actix::fut::wrap_future(cleanup));
+    }
+}
+
+impl Handler<PostMessage> for MessageReactionActor {
+    type Result = ResponseFuture<Option<PostedMessage>>;
+
+    fn handle(&mut self, msg: PostMessage, _ctx: &mut Context<Self>) -> Self::Result {
+        let api = self.api.clone();
         let channel_id = self.channel_id;
-        let message_id = serenity::MessageId::new(msg.message_id);
-        let reaction = msg.reaction;
-
-        // Use std::thread to handle Discord API calls without requiring LocalSet
-        let handle = thread::spawn(move || {
-            let rt = tokio::runtime::Builder::new_current_thread()
-                .enable_all()
-                .build()
-                .unwrap();
 
-            rt.block_on(async {
-                match channel_id.message(&discord_ctx, message_id).await {
-                    Ok(message) => {
-                        if let Err(e) = message.delete_reaction_emoji(&discord_ctx, reaction).await
-                        {
-                            error!(
-                                "Failed to delete reaction '{}' from message {}: {}",
-                                reaction, message_id, e
-                            );
-                        } else {
-                            debug!(
-                                "Deleted reaction '{}' from message {}",
-                                reaction, message_id
-                            );
-                        }
-                    }
-                    Err(e) => {
-                        error!("Failed to fetch message {}: {}", message_id, e);
-                    }
+        Box::pin(async move {
+            match api.send_message(channel_id, msg.content).await {
+                Ok(message_id) => {
+                    debug!(
+                        channel_id = channel_id.get(),
+                        message_id = message_id.get(),
+                        "Posted message"
+                    );
+                    Some(PostedMessage {
+                        channel_id: channel_id.get(),
+                        message_id: message_id.get(),
+                    })
+                }
+                Err(e) => {
+                    error!(
+                        channel_id = channel_id.get(),
+                        error = %e,
+                        "Failed to post message"
+                    );
+                    None
                 }
-            });
+            }
+        })
+    }
+}
+
+impl Handler<PostFile> for MessageReactionActor {
+    type Result = ResponseFuture<Option<PostedMessage>>;
+
+    fn handle(&mut self, msg: PostFile, _ctx: &mut Context<Self>) -> Self::Result {
+        let api = self.api.clone();
+
+        Box::pin(async move {
+            let channel_id = serenity::ChannelId::new(msg.channel_id);
+            let size = msg.data.len();
+            match api
+                .send_file(channel_id, msg.filename, msg.data, msg.content)
+                .await
+            {
+                Ok(message_id) => {
+                    debug!(channel_id = msg.channel_id, size, "Posted file");
+                    Some(PostedMessage {
+                        channel_id: msg.channel_id,
+                        message_id: message_id.get(),
+                    })
+                }
+                Err(e) => {
+                    error!(
+                        channel_id = msg.channel_id,
+                        size,
+                        error = %e,
+                        "Failed to post file"
+                    );
+                    None
+                }
+            }
+        })
+    }
+}
+
+impl Handler<PostEmbed> for MessageReactionActor {
+    type Result = ResponseFuture<()>;
+
+    fn handle(&mut self, msg: PostEmbed, _ctx: &mut Context<Self>) -> Self::Result {
+        let api = self.api.clone();
+        let channel_id = self.channel_id;
+
+        Box::pin(async move {
+            match api.send_embed(channel_id, msg.title, msg.description).await {
+                Ok(()) => debug!(channel_id = channel_id.get(), "Posted embed"),
+                Err(e) => error!(
+                    channel_id = channel_id.get(),
+                    error = %e,
+                    "Failed to post embed"
+                ),
+            }
+        })
+    }
+}
+
+impl Handler<Reply> for MessageReactionActor {
+    type Result = ResponseFuture<()>;
+
+    fn handle(&mut self, msg: Reply, _ctx: &mut Context<Self>) -> Self::Result {
+        let api = self.api.clone();
+
+        Box::pin(async move {
+            let channel_id = serenity::ChannelId::new(msg.channel_id);
+            let message_id = serenity::MessageId::new(msg.message_id);
+            match api.reply(channel_id, message_id, msg.content).await {
+                Ok(()) => debug!(message_id = msg.message_id, "Replied to message"),
+                Err(e) => error!(
+                    message_id = msg.message_id,
+                    error = %e,
+                    "Failed to reply to message"
+                ),
+            }
+        })
+    }
+}
+
+impl Handler<UpdatePresence> for MessageReactionActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: UpdatePresence, ctx: &mut Context<Self>) -> Self::Result {
+        let Some(fallback) = &self.presence_fallback else {
+            return;
+        };
+        self.pending_presence = Some(match msg.word {
+            Some(word) => serenity::ActivityData::custom(
+                Text::Presence {
+                    word: &word,
+                    chain_length: msg.chain_length,
+                }
+                .render(self.language),
+            ),
+            None => serenity::ActivityData::playing(fallback),
         });
 
-        // Don't wait for the thread to complete
-        std::mem::drop(handle);
+        let now = Instant::now();
+        match self.presence_wait(now) {
+            None => self.flush_presence(now),
+            // A later update just replaces the pending one
+            Some(_) if self.presence_scheduled => {}
+            Some(wait) => {
+                debug!(
+                    wait_ms = wait.as_millis() as u64,
+                    "Delaying presence update"
+                );
+                self.presence_scheduled = true;
+                ctx.run_later(wait, |act, _ctx| {
+                    act.presence_scheduled = false;
+                    act.flush_presence(Instant::now());
+                });
+            }
+        }
     }
 }
 
-impl Handler<ClearReactions> for MessageReactionActor {
+impl Handler<UpdateStatus> for MessageReactionActor {
     type Result = ();
 
-    fn handle(&mut self, msg: ClearReactions, _ctx: &mut Context<Self>) -> Self::Result {
-        let discord_ctx = self.discord_ctx.clone();
-        let channel_id = self.channel_id;
-        let message_id = serenity::MessageId::new(msg.message_id);
+    fn handle(&mut self, msg: UpdateStatus, ctx: &mut Context<Self>) -> Self::Result {
+        let language = self.language;
+        let Some(status) = &mut self.status else {
+            return;
+        };
+        status.pending = Some(
+            Text::Status {
+                word: msg.word.as_deref(),
+                chain_length: msg.chain_length,
+                user_id: msg.user_id,
+            }
+            .render(language),
+        );
+
+        let now = Instant::now();
+        match self.status_wait(now) {
+            None => self.flush_status(ctx, now),
+            // A later update just replaces the pending one
+            Some(_) if self.status.as_ref().is_some_and(|status| status.scheduled) => {}
+            Some(wait) => {
+                debug!(wait_ms = wait.as_millis() as u64, "Delaying status update");
+                if let Some(status) = &mut self.status {
+                    status.scheduled = true;
+                }
+                ctx.run_later(wait, |act, ctx| {
+                    if let Some(status) = &mut act.status {
+                        status.scheduled = false;
+                    }
+                    act.flush_status(ctx, Instant::now());
+                });
+            }
+        }
+    }
+}
+
+impl Handler<NotifyAdmin> for MessageReactionActor {
+    type Result = ResponseFuture<()>;
+
+    fn handle(&mut self, msg: NotifyAdmin, _ctx: &mut Context<Self>) -> Self::Result {
+        let Some(admin_channel_id) = self.admin_channel_id else {
+            return Box::pin(async {});
+        };
+        if !self.take_alert_slot(msg.kind, Instant::now()) {
+            debug!(kind = ?msg.kind, "Admin alert rate-limited");
+            return Box::pin(async {});
+        }
+
+        let api = self.api.clone();
+        let content = alert_text(msg.kind, &msg.error);
+        Box::pin(async move {
+            if let Err(e) = api.send_message(admin_channel_id, content).await {
+                error!(
+                    channel_id = admin_channel_id.get(),
+                    error = %e,
+                    "Failed to post admin alert"
+                );
+            }
+        })
+    }
+}
+
+impl Handler<Ping> for MessageReactionActor {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Ping, _ctx: &mut Context<Self>) -> Self::Result {}
+}
+
+impl Handler<Shutdown> for MessageReactionActor {
+    type Result = ResponseFuture<()>;
+
+    fn handle(&mut self, _msg: Shutdown, _ctx: &mut Context<Self>) -> Self::Result {
+        // Later updates, and one waiting for the interval, mustn't overwrite the offline text
+        let status = self.status.take();
+        let Some(StatusMessage {
+            store,
+            message_id: Some(message_id),
+            ..
+        }) = status.filter(|_| self.offline_status)
+        else {
+            return Box::pin(async {});
+        };
+        let content = Text::StatusOffline.render(self.language);
+        let write = write_status(
+            self.api.clone(),
+            store,
+            self.channel_id,
+            Some(message_id),
+            content,
+        );
+        Box::pin(async move {
+            write.await;
+        })
+    }
+}
 
-        // Use std::thread to handle Discord API calls without requiring LocalSet
-        let handle = thread::spawn(move || {
-            let rt = tokio::runtime::Builder::new_current_thread()
-                .enable_all()
-                .build()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::testing::{ReactionCall, RecordingReactionApi, FIRST_POSTED_ID};
+    use crate::error::LLMError;
+    use actix::Actor;
+
+    fn alert(kind: AlertKind) -> NotifyAdmin {
+        NotifyAdmin {
+            kind,
+            error: LLMError::ApiError("quota exceeded".to_string()).into(),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_admin_alerts_rate_limited() {
+        let api = RecordingReactionApi::default();
+        let actor =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_admin_channel(serenity::ChannelId::new(2))
+                .start();
+
+        actor.send(alert(AlertKind::LlmFailures)).await.unwrap();
+        actor.send(alert(AlertKind::LlmFailures)).await.unwrap();
+        actor.send(alert(AlertKind::TaskPanic)).await.unwrap();
+
+        let calls = api.calls();
+        assert_eq!(calls.len(), 2);
+        let ReactionCall::Post(content) = &calls[0] else {
+            panic!("expected an alert, got {:?}", calls[0]);
+        };
+        assert!(
+            content.contains("LLM validation keeps failing"),
+            "{}",
+            content
+        );
+        assert!(content.contains("`sanabotti::llm_error`"), "{}", content);
+        assert!(content.contains("quota exceeded"), "{}", content);
+
+        // The same kind may be reported again once the interval has passed
+        let mut actor = MessageReactionActor::with_api(Arc::new(api), serenity::ChannelId::new(1));
+        let now = Instant::now();
+        assert!(actor.take_alert_slot(AlertKind::LlmFailures, now));
+        assert!(!actor.take_alert_slot(AlertKind::LlmFailures, now + ALERT_INTERVAL / 2));
+        assert!(actor.take_alert_slot(AlertKind::LlmFailures, now + ALERT_INTERVAL));
+    }
+
+    #[actix_rt::test]
+    async fn test_permission_errors_alert_admins() {
+        let api = RecordingReactionApi::failing_reactions("Missing Permissions");
+        let actor =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_admin_channel(serenity::ChannelId::new(2))
+                .start();
+
+        for message_id in 1..=2 {
+            actor
+                .send(AddReaction {
+                    channel_id: 1,
+                    message_id,
+                    reaction: EMOJI_CHECK,
+                    span: Span::none(),
+                })
+                .await
                 .unwrap();
+        }
+        let posts = || -> Vec<String> {
+            api.calls()
+                .into_iter()
+                .filter_map(|call| match call {
+                    ReactionCall::Post(content) => Some(content),
+                    _ => None,
+                })
+                .collect()
+        };
+        // The alerts are sent back to the actor, wait for them to be posted
+        for _ in 0..100 {
+            if !posts().is_empty() {
+                break;
+            }
+            actix_rt::time::sleep(Duration::from_millis(10)).await;
+        }
+        actor.send(Ping).await.unwrap();
 
-            rt.block_on(async {
-                match channel_id.message(&discord_ctx, message_id).await {
-                    Ok(message) => {
-                        if let Err(e) = message.delete_reactions(&discord_ctx).await {
-                            error!("Failed to clear reactions from message: {}", e);
-                        } else {
-                            debug!("Cleared all reactions from message {}", message_id);
-                        }
-                    }
-                    Err(e) => {
-                        error!("Failed to fetch message {}: {}", message_id, e);
-                    }
-                }
-            });
+        let posts = posts();
+        assert_eq!(posts.len(), 1);
+        assert!(
+            posts[0].contains("`sanabotti::reaction_error`"),
+            "{}",
+            posts[0]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_no_admin_channel() {
+        let api = RecordingReactionApi::default();
+        let actor =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .start();
+
+        actor.send(alert(AlertKind::LlmFailures)).await.unwrap();
+        assert!(api.calls().is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn test_post_file() {
+        let api = RecordingReactionApi::default();
+        let actor =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .start();
+
+        // Posted where asked, not in the game channel
+        let posted = actor
+            .send(PostFile {
+                channel_id: 7,
+                filename: "export.csv".to_string(),
+                data: vec![b'x'; 10],
+                content: "📄".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            posted,
+            Some(PostedMessage {
+                channel_id: 7,
+                message_id: FIRST_POSTED_ID,
+            })
+        );
+        assert_eq!(
+            api.calls(),
+            vec![ReactionCall::File("export.csv".to_string(), 10)]
+        );
+        assert_eq!(api.call_channels(), vec![7]);
+    }
+
+    fn add(message_id: u64, reaction: char) -> AddReaction {
+        AddReaction {
+            channel_id: 1,
+            message_id,
+            reaction,
+            span: Span::none(),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_reactions_paced_in_order() {
+        let api = RecordingReactionApi::default();
+        let interval = Duration::from_millis(50);
+        let actor =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_reaction_interval(interval)
+                .start();
+
+        // A burst, as when a pending word is resolved right after another word
+        actor.do_send(add(1, EMOJI_QUESTION));
+        actor.do_send(DeleteReaction {
+            channel_id: 1,
+            message_id: 1,
+            reaction: EMOJI_QUESTION,
+            span: Span::none(),
+        });
+        actor.do_send(add(1, EMOJI_CHECK));
+        actor.send(add(2, EMOJI_CHECK)).await.unwrap();
+
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(1, EMOJI_QUESTION),
+                ReactionCall::Delete(1, EMOJI_QUESTION),
+                ReactionCall::Add(1, EMOJI_CHECK),
+                ReactionCall::Add(2, EMOJI_CHECK),
+            ]
+        );
+        let times = api.call_times();
+        for pair in times.windows(2) {
+            assert!(pair[1] - pair[0] >= interval, "{:?}", times);
+        }
+    }
+
+    fn verdict(message_id: u64, validity: Validity) -> SetVerdict {
+        SetVerdict {
+            channel_id: 1,
+            message_id,
+            validity,
+            replaces: None,
+            span: Span::none(),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_verdict_reactions() {
+        let api = RecordingReactionApi::default();
+        let actor =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_reaction_interval(Duration::ZERO)
+                .start();
+
+        actor.send(verdict(1, Validity::Pending)).await.unwrap();
+        actor.send(verdict(1, Validity::Valid)).await.unwrap();
+        // A late verdict that agrees changes nothing
+        actor.send(verdict(1, Validity::Valid)).await.unwrap();
+        // An overturned verdict swaps the one shown
+        actor.send(verdict(1, Validity::Invalid)).await.unwrap();
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(1, EMOJI_QUESTION),
+                ReactionCall::Delete(1, EMOJI_QUESTION),
+                ReactionCall::Add(1, EMOJI_CHECK),
+                ReactionCall::Delete(1, EMOJI_CHECK),
+                ReactionCall::Add(1, EMOJI_CROSS),
+            ]
+        );
+
+        // A word from before a restart shows what the caller says it did
+        let before = api.calls().len();
+        actor
+            .send(SetVerdict {
+                replaces: Some(Validity::Pending),
+                ..verdict(2, Validity::Invalid)
+            })
+            .await
+            .unwrap();
+        // Once cleared, the word shows no verdict
+        actor
+            .send(ClearReactions {
+                channel_id: 1,
+                message_id: 2,
+            })
+            .await
+            .unwrap();
+        actor.send(verdict(2, Validity::Valid)).await.unwrap();
+        assert_eq!(
+            api.calls()[before..],
+            vec![
+                ReactionCall::Delete(2, EMOJI_QUESTION),
+                ReactionCall::Add(2, EMOJI_CROSS),
+                ReactionCall::Clear(2),
+                ReactionCall::Add(2, EMOJI_CHECK),
+            ]
+        );
+    }
+
+    fn finished(message_id: u64, validity: Validity) -> FinishedWord {
+        FinishedWord {
+            channel_id: 1,
+            message_id,
+            validity,
+        }
+    }
+
+    /// Wait for the background cleanup to have made `calls` calls in all
+    async fn wait_for_calls(api: &RecordingReactionApi, calls: usize) {
+        for _ in 0..100 {
+            if api.calls().len() >= calls {
+                return;
+            }
+            time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("expected {} calls, got {:?}", calls, api.calls());
+    }
+
+    #[actix_rt::test]
+    async fn test_reset_cleanup() {
+        let api = RecordingReactionApi::default();
+        let actor =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_reaction_interval(Duration::from_millis(20))
+                .start();
+
+        // The verdict the message shows is removed, whatever the history says
+        actor.send(verdict(1, Validity::Invalid)).await.unwrap();
+        let words = (1..=5).map(|id| finished(id, Validity::Valid)).collect();
+        actor
+            .send(CleanUpReactions {
+                mode: ResetReactions::Clear,
+                words,
+            })
+            .await
+            .unwrap();
+        // The new game's first word doesn't wait for the whole cleanup
+        actor.send(verdict(10, Validity::Valid)).await.unwrap();
+        let calls = api.calls();
+        assert_eq!(calls[0], ReactionCall::Add(1, EMOJI_CROSS));
+        assert!(calls.len() < 5, "{:?}", calls);
+        assert_eq!(calls.last(), Some(&ReactionCall::Add(10, EMOJI_CHECK)));
+
+        wait_for_calls(&api, 7).await;
+        let mut cleaned: Vec<_> = api
+            .calls()
+            .into_iter()
+            .filter(|call| matches!(call, ReactionCall::Delete(..)))
+            .collect();
+        cleaned.sort_by_key(|call| format!("{:?}", call));
+        assert_eq!(
+            cleaned,
+            vec![
+                ReactionCall::Delete(1, EMOJI_CROSS),
+                ReactionCall::Delete(2, EMOJI_CHECK),
+                ReactionCall::Delete(3, EMOJI_CHECK),
+                ReactionCall::Delete(4, EMOJI_CHECK),
+                ReactionCall::Delete(5, EMOJI_CHECK),
+            ]
+        );
+
+        // Marking leaves the verdicts, and without a mode nothing happens
+        for mode in [ResetReactions::None, ResetReactions::Mark] {
+            actor
+                .send(CleanUpReactions {
+                    mode,
+                    words: vec![finished(10, Validity::Valid)],
+                })
+                .await
+                .unwrap();
+        }
+        wait_for_calls(&api, 8).await;
+        time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(api.calls()[7..], [ReactionCall::Add(10, EMOJI_FINISH)]);
+    }
+
+    #[actix_rt::test]
+    async fn test_rate_limit_pauses_queue() {
+        let retry_after = Duration::from_millis(200);
+        let api = RecordingReactionApi::rate_limited(retry_after, 1);
+        let actor =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_reaction_interval(Duration::from_millis(10))
+                .start();
+
+        actor.do_send(add(1, EMOJI_CHECK));
+        actor.send(add(2, EMOJI_CROSS)).await.unwrap();
+
+        // The rate-limited call is made again before the ones queued after it
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(1, EMOJI_CHECK),
+                ReactionCall::Add(1, EMOJI_CHECK),
+                ReactionCall::Add(2, EMOJI_CROSS),
+            ]
+        );
+        let times = api.call_times();
+        assert!(times[1] - times[0] >= retry_after, "{:?}", times);
+        assert!(times[2] - times[1] < retry_after, "{:?}", times);
+    }
+
+    #[actix_rt::test]
+    async fn test_presence_throttled() {
+        let api = RecordingReactionApi::default();
+        let actor =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_dynamic_presence("Finnish Word Game".to_string())
+                .start();
+        let update = |word: Option<&str>, chain_length| UpdatePresence {
+            word: word.map(str::to_string),
+            chain_length,
+        };
+
+        actor.send(update(Some("kissa"), 1)).await.unwrap();
+        // Too soon, waits for the interval
+        actor.send(update(Some("kassa"), 2)).await.unwrap();
+        assert_eq!(
+            api.calls(),
+            vec![ReactionCall::Activity(
+                "Pelataan: kissa (ketju 1)".to_string()
+            )]
+        );
+
+        let mut actor = MessageReactionActor::with_api(Arc::new(api), serenity::ChannelId::new(1));
+        let now = Instant::now();
+        assert_eq!(actor.presence_wait(now), None);
+        actor.last_presence = Some(now);
+        assert_eq!(
+            actor.presence_wait(now + PRESENCE_INTERVAL / 3),
+            Some(PRESENCE_INTERVAL * 2 / 3)
+        );
+        assert_eq!(actor.presence_wait(now + PRESENCE_INTERVAL), None);
+    }
+
+    #[actix_rt::test]
+    async fn test_status_message() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = StatusMessageStore::new(dir.path().join("status.json"));
+        let update = |word: &str, chain_length| UpdateStatus {
+            word: Some(word.to_string()),
+            chain_length,
+            user_id: Some(42),
+        };
+
+        let api = RecordingReactionApi::default();
+        let actor =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_status_message(store.clone())
+                .start();
+        actor.send(update("kissa", 1)).await.unwrap();
+        // Too soon, waits for the interval
+        actor.send(update("kassa", 2)).await.unwrap();
+        time::sleep(Duration::from_millis(50)).await;
+        // Posted, filled in without pinging the player and pinned
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Post(STATUS_PLACEHOLDER.to_string()),
+                ReactionCall::Edit(
+                    FIRST_POSTED_ID,
+                    "📌 Nykyinen sana: **kissa** (ketjun pituus 1), pelasi <@42>".to_string()
+                ),
+                ReactionCall::Pin(FIRST_POSTED_ID),
+            ]
+        );
+        assert_eq!(store.get(1).unwrap(), Some(FIRST_POSTED_ID));
+
+        // After a restart the same message is edited
+        let api = RecordingReactionApi::default();
+        let actor =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_status_message(store)
+                .start();
+        actor
+            .send(UpdateStatus {
+                word: None,
+                chain_length: 0,
+                user_id: None,
+            })
+            .await
+            .unwrap();
+        time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(
+            api.calls(),
+            vec![ReactionCall::Edit(
+                FIRST_POSTED_ID,
+                "📌 Uusi peli! Aloita millä tahansa sanalla.".to_string()
+            )]
+        );
+
+        let mut actor = MessageReactionActor::with_api(Arc::new(api), serenity::ChannelId::new(1));
+        let now = Instant::now();
+        assert_eq!(actor.status_wait(now), None);
+        actor.status = Some(StatusMessage {
+            store: StatusMessageStore::new(dir.path().join("other.json")),
+            message_id: None,
+            pending: None,
+            last_edit: Some(now),
+            scheduled: false,
         });
+        assert_eq!(
+            actor.status_wait(now + STATUS_INTERVAL / 2),
+            Some(STATUS_INTERVAL / 2)
+        );
+        assert_eq!(actor.status_wait(now + STATUS_INTERVAL), None);
+    }
+
+    #[actix_rt::test]
+    async fn test_offline_status() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = StatusMessageStore::new(dir.path().join("status.json"));
+        store.set(1, FIRST_POSTED_ID).unwrap();
+
+        let api = RecordingReactionApi::default();
+        let actor =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_status_message(store.clone())
+                .with_offline_status(true)
+                .start();
+        actor.send(Shutdown).await.unwrap();
+        // The edit is done by the time the shutdown is
+        assert_eq!(
+            api.calls(),
+            vec![ReactionCall::Edit(
+                FIRST_POSTED_ID,
+                "📴 Botti ei ole päällä. Peli jatkuu, kun botti palaa.".to_string()
+            )]
+        );
+
+        // Updates after the shutdown leave the message alone
+        actor
+            .send(UpdateStatus {
+                word: Some("kissa".to_string()),
+                chain_length: 1,
+                user_id: None,
+            })
+            .await
+            .unwrap();
+        time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(api.calls().len(), 1);
+
+        // Without the option the message is left as it was
+        let api = RecordingReactionApi::default();
+        let actor =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_status_message(store)
+                .start();
+        actor.send(Shutdown).await.unwrap();
+        assert!(api.calls().is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn test_static_presence() {
+        let api = RecordingReactionApi::default();
+        let actor =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .start();
 
-        // Don't wait for the thread to complete
-        std::mem::drop(handle);
+        actor
+            .send(UpdatePresence {
+                word: Some("kissa".to_string()),
+                chain_length: 1,
+            })
+            .await
+            .unwrap();
+        assert!(api.calls().is_empty());
     }
 }
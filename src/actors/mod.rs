@@ -3,6 +3,65 @@ pub mod llm_validator;
 pub mod message_reaction;
 pub mod word_validator;
 
+#[cfg(test)]
+pub(crate) mod testing;
+
+use actix::dev::ToEnvelope;
+use actix::{Actor, Addr, Handler, Message};
+use std::time::Duration;
+use tracing::warn;
+
+use crate::error::{Error, Result};
+
+/// Mailbox capacity of the actors that receive bursts of messages, unless configured
+pub const DEFAULT_MAILBOX_CAPACITY: usize = 256;
+
+/// How long a message on a critical path may take to be handled before it's retried
+pub(crate) const DELIVERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Liveness check answered by every actor, used by the health endpoint
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct Ping;
+
+/// Message to wrap up before the bot exits, answered once the actor's part is done
+///
+/// Each actor stops taking new work and saves whatever would be lost, within
+/// the grace period of [`ShutdownHandle`](crate::shutdown::ShutdownHandle).
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct Shutdown;
+
+/// Message to set the actor used to post in Discord, e.g. announcements and reports
+///
+/// The reaction actor is only available once Discord is connected, after the
+/// other actors have started.
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct SetMessageReaction(pub Addr<MessageReactionActor>);
+
+/// Send a message on a critical path and wait for it to be handled
+///
+/// A failed or timed out delivery is logged and tried once more, so only
+/// persistent failures come back as an error. Only use this for messages that
+/// are safe to handle twice, since a timed out message may still be handled.
+pub(crate) async fn deliver<A, M>(addr: &Addr<A>, msg: M, what: &str) -> Result<M::Result>
+where
+    A: Actor + Handler<M>,
+    A::Context: ToEnvelope<A, M>,
+    M: Message + Clone + Send + 'static,
+    M::Result: Send,
+{
+    match addr.send(msg.clone()).timeout(DELIVERY_TIMEOUT).await {
+        Ok(result) => return Ok(result),
+        Err(e) => warn!(what, error = %e, "Delivery failed, retrying once"),
+    }
+    addr.send(msg)
+        .timeout(DELIVERY_TIMEOUT)
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to deliver {}: {}", what, e)))
+}
+
 // Re-export actor types for easier import
 pub use game_state::GameStateActor;
 pub use llm_validator::LLMValidatorActor;
@@ -0,0 +1,384 @@
+//! Test doubles shared by the actor tests
+
+use poise::serenity_prelude as serenity;
+use std::collections::VecDeque;
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::subscriber::DefaultGuard;
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::actors::message_reaction::{ApiFuture, PostFuture, ReactionApi};
+use crate::error::{DictionaryError, Error, LLMError};
+use crate::validation::llm::{Answer, CompletionApi, CompletionFuture, TokenUsage};
+use crate::validation::remote::{LookupApi, LookupFuture};
+
+/// A reaction API call recorded by [`RecordingReactionApi`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReactionCall {
+    Add(u64, char),
+    Delete(u64, char),
+    Clear(u64),
+    Post(String),
+    /// A reply to the message with the ID
+    Reply(u64, String),
+    /// An embed's title and description
+    Embed(String, String),
+    /// A file's name and size
+    File(String, usize),
+    /// An edit of the message with the ID
+    Edit(u64, String),
+    Pin(u64),
+    /// The presence's custom status or activity name
+    Activity(String),
+}
+
+/// [`ReactionApi`] that records every call instead of talking to Discord
+#[derive(Default, Clone)]
+pub struct RecordingReactionApi {
+    calls: Arc<Mutex<Vec<ReactionCall>>>,
+    /// When each call was made
+    times: Arc<Mutex<Vec<Instant>>>,
+    /// Which channel each call was made in
+    channels: Arc<Mutex<Vec<u64>>>,
+    /// Error returned by every reaction call, posting messages still succeeds
+    reaction_error: Arc<Mutex<Option<String>>>,
+    /// Rate limits answered to the next reaction calls, one per call
+    rate_limits: Arc<Mutex<VecDeque<Duration>>>,
+    /// How many messages have been posted, to number them
+    posted: Arc<AtomicU64>,
+}
+
+/// ID of the first message posted through [`RecordingReactionApi`], the next ones count up
+pub const FIRST_POSTED_ID: u64 = 1000;
+
+impl RecordingReactionApi {
+    /// Record the calls but fail every reaction change with `error`
+    pub fn failing_reactions(error: &str) -> Self {
+        Self {
+            reaction_error: Arc::new(Mutex::new(Some(error.to_string()))),
+            ..Self::default()
+        }
+    }
+
+    /// Record the calls but rate-limit the first `times` reaction calls
+    pub fn rate_limited(retry_after: Duration, times: usize) -> Self {
+        Self {
+            rate_limits: Arc::new(Mutex::new(vec![retry_after; times].into())),
+            ..Self::default()
+        }
+    }
+
+    /// Let the reaction calls succeed again after [`Self::failing_reactions`]
+    pub fn recover(&self) {
+        *self.reaction_error.lock().unwrap() = None;
+    }
+
+    pub fn calls(&self) -> Vec<ReactionCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    pub fn call_times(&self) -> Vec<Instant> {
+        self.times.lock().unwrap().clone()
+    }
+
+    pub fn call_channels(&self) -> Vec<u64> {
+        self.channels.lock().unwrap().clone()
+    }
+
+    fn record(&self, channel_id: serenity::ChannelId, call: ReactionCall) -> ApiFuture<'_> {
+        let failure = match call {
+            ReactionCall::Post(_)
+            | ReactionCall::Reply(..)
+            | ReactionCall::Embed(..)
+            | ReactionCall::File(..)
+            | ReactionCall::Edit(..)
+            | ReactionCall::Pin(_) => None,
+            _ => match self.rate_limits.lock().unwrap().pop_front() {
+                Some(retry_after) => Some(Error::RateLimited(retry_after)),
+                None => self
+                    .reaction_error
+                    .lock()
+                    .unwrap()
+                    .clone()
+                    .map(Error::Reaction),
+            },
+        };
+        self.calls.lock().unwrap().push(call);
+        self.times.lock().unwrap().push(Instant::now());
+        self.channels.lock().unwrap().push(channel_id.get());
+        Box::pin(async move {
+            match failure {
+                Some(error) => Err(error),
+                None => Ok(()),
+            }
+        })
+    }
+}
+
+impl ReactionApi for RecordingReactionApi {
+    fn add_reaction(
+        &self,
+        channel_id: serenity::ChannelId,
+        message_id: serenity::MessageId,
+        reaction: char,
+    ) -> ApiFuture<'_> {
+        self.record(channel_id, ReactionCall::Add(message_id.get(), reaction))
+    }
+
+    fn delete_reaction(
+        &self,
+        channel_id: serenity::ChannelId,
+        message_id: serenity::MessageId,
+        reaction: char,
+    ) -> ApiFuture<'_> {
+        self.record(channel_id, ReactionCall::Delete(message_id.get(), reaction))
+    }
+
+    fn clear_reactions(
+        &self,
+        channel_id: serenity::ChannelId,
+        message_id: serenity::MessageId,
+    ) -> ApiFuture<'_> {
+        self.record(channel_id, ReactionCall::Clear(message_id.get()))
+    }
+
+    fn send_message(&self, channel_id: serenity::ChannelId, content: String) -> PostFuture<'_> {
+        let message_id = FIRST_POSTED_ID + self.posted.fetch_add(1, Ordering::SeqCst);
+        let recorded = self.record(channel_id, ReactionCall::Post(content));
+        Box::pin(async move {
+            recorded.await?;
+            Ok(serenity::MessageId::new(message_id))
+        })
+    }
+
+    fn reply(
+        &self,
+        channel_id: serenity::ChannelId,
+        message_id: serenity::MessageId,
+        content: String,
+    ) -> ApiFuture<'_> {
+        self.record(channel_id, ReactionCall::Reply(message_id.get(), content))
+    }
+
+    fn send_embed(
+        &self,
+        channel_id: serenity::ChannelId,
+        title: String,
+        description: String,
+    ) -> ApiFuture<'_> {
+        self.record(channel_id, ReactionCall::Embed(title, description))
+    }
+
+    fn send_file(
+        &self,
+        channel_id: serenity::ChannelId,
+        filename: String,
+        data: Vec<u8>,
+        _content: String,
+    ) -> PostFuture<'_> {
+        let message_id = FIRST_POSTED_ID + self.posted.fetch_add(1, Ordering::SeqCst);
+        let recorded = self.record(channel_id, ReactionCall::File(filename, data.len()));
+        Box::pin(async move {
+            recorded.await?;
+            Ok(serenity::MessageId::new(message_id))
+        })
+    }
+
+    fn edit_message(
+        &self,
+        channel_id: serenity::ChannelId,
+        message_id: serenity::MessageId,
+        content: String,
+    ) -> ApiFuture<'_> {
+        self.record(channel_id, ReactionCall::Edit(message_id.get(), content))
+    }
+
+    fn pin_message(
+        &self,
+        channel_id: serenity::ChannelId,
+        message_id: serenity::MessageId,
+    ) -> ApiFuture<'_> {
+        self.record(channel_id, ReactionCall::Pin(message_id.get()))
+    }
+
+    fn set_activity(&self, activity: serenity::ActivityData) {
+        let text = activity.state.unwrap_or(activity.name);
+        self.calls
+            .lock()
+            .unwrap()
+            .push(ReactionCall::Activity(text));
+    }
+}
+
+/// [`CompletionApi`] giving the same canned answer to every prompt
+#[derive(Clone)]
+pub struct MockCompletionApi {
+    /// The model's answer, or None to fail every request
+    response: Option<String>,
+    /// Tokens reported for every answer
+    usage: Option<TokenUsage>,
+    model: &'static str,
+    prompts: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockCompletionApi {
+    /// Name of the model the mock claims to be
+    pub const MODEL: &'static str = "mock-model";
+
+    pub fn responding(response: &str) -> Self {
+        Self {
+            response: Some(response.to_string()),
+            usage: None,
+            model: Self::MODEL,
+            prompts: Arc::default(),
+        }
+    }
+
+    pub fn failing() -> Self {
+        Self {
+            response: None,
+            usage: None,
+            model: Self::MODEL,
+            prompts: Arc::default(),
+        }
+    }
+
+    /// Claim to be `model`
+    pub fn with_model(mut self, model: &'static str) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Report `usage` for every answer
+    pub fn with_usage(mut self, usage: TokenUsage) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
+    /// How many prompts have been sent
+    pub fn calls(&self) -> usize {
+        self.prompts.lock().unwrap().len()
+    }
+
+    /// The prompts sent so far
+    pub fn prompts(&self) -> Vec<String> {
+        self.prompts.lock().unwrap().clone()
+    }
+}
+
+impl CompletionApi for MockCompletionApi {
+    fn model(&self) -> &str {
+        self.model
+    }
+
+    fn complete(&self, prompt: String) -> CompletionFuture<'_> {
+        self.prompts.lock().unwrap().push(prompt);
+        let response = self.response.clone();
+        let usage = self.usage;
+        Box::pin(async move {
+            let text =
+                response.ok_or_else(|| LLMError::ApiError("mock request failed".to_string()))?;
+            Ok(Answer { text, usage })
+        })
+    }
+}
+
+/// [`LookupApi`] that knows a fixed set of words
+#[derive(Clone)]
+pub struct MockLookupApi {
+    /// The known words, or None to fail every lookup
+    words: Option<Vec<String>>,
+    delay: Duration,
+    lookups: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockLookupApi {
+    pub fn knowing(words: &[&str]) -> Self {
+        Self {
+            words: Some(words.iter().map(|word| word.to_string()).collect()),
+            delay: Duration::ZERO,
+            lookups: Arc::default(),
+        }
+    }
+
+    pub fn failing() -> Self {
+        Self {
+            words: None,
+            delay: Duration::ZERO,
+            lookups: Arc::default(),
+        }
+    }
+
+    /// Take this long to answer
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// The words looked up, in order
+    pub fn lookups(&self) -> Vec<String> {
+        self.lookups.lock().unwrap().clone()
+    }
+}
+
+impl LookupApi for MockLookupApi {
+    fn exists(&self, word: String) -> LookupFuture<'_> {
+        self.lookups.lock().unwrap().push(word.clone());
+        Box::pin(async move {
+            tokio::time::sleep(self.delay).await;
+            match &self.words {
+                Some(words) => Ok(words.contains(&word)),
+                None => Err(DictionaryError::Remote("mock lookup failed".to_string()).into()),
+            }
+        })
+    }
+}
+
+/// Log lines captured by a subscriber installed for the current thread
+///
+/// Threads spawned by the actors pick the subscriber up as long as they
+/// propagate the dispatcher, as the LLM batch thread does.
+#[derive(Clone, Default)]
+pub struct CapturedLogs {
+    buffer: Arc<Mutex<Vec<u8>>>,
+}
+
+impl CapturedLogs {
+    /// Capture debug logs, with their spans, until the guard is dropped
+    pub fn install(&self) -> DefaultGuard {
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(self.clone())
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .finish();
+        tracing::subscriber::set_default(subscriber)
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        String::from_utf8_lossy(&self.buffer.lock().unwrap())
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+impl io::Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for CapturedLogs {
+    type Writer = Self;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
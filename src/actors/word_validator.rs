@@ -1,52 +1,1053 @@
-use actix::{Actor, Addr, Context, Handler, Message};
-use std::thread;
-use tracing::{debug, info, warn};
+use actix::{
+    Actor, ActorFutureExt, Addr, AsyncContext, Context, Handler, Message, MessageResult,
+    ResponseFuture,
+};
+use serde::Serialize;
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tracing::{debug, error, info, info_span, warn, Instrument, Span};
 
-use crate::actors::game_state::{GameStateActor, ValidateGameRules};
-use crate::actors::llm_validator::LLMValidatorActor;
-use crate::actors::message_reaction::MessageReactionActor;
-use crate::error::Result;
-use crate::validation::dictionary::DictionaryValidator;
+use crate::actors::game_state::{
+    GameStateActor, GetPreviousWord, IsPaused, MarkWordValidity, PreviewWord, RecordAttempt,
+    SubmitWord, SuggestWords, Validity, VerdictSource,
+};
+use crate::actors::llm_validator::{LLMValidatorActor, ValidateProperNoun};
+use crate::actors::message_reaction::{
+    AddReaction, AlertKind, DeleteReaction, MessageReactionActor, NotifyAdmin, Reply, SetVerdict,
+    EMOJI_CHECK, EMOJI_CROSS, EMOJI_HOURGLASS, EMOJI_NEXT_TRACK, EMOJI_NO_ENTRY, EMOJI_PENCIL,
+    EMOJI_QUESTION, EMOJI_REPEAT, EMOJI_SLEEPING, EMOJI_SPEECH, EMOJI_TIMER, EMOJI_WARNING,
+};
+use crate::actors::{deliver, Ping, Shutdown, DEFAULT_MAILBOX_CAPACITY};
+use crate::audit::{AuditLog, AuditStep};
+use crate::config::{Language, LlmBatchMode, MultiWordPolicy};
+use crate::error::{Error, Result};
+use crate::events::{EventBus, GameEvent};
+use crate::messages::Text;
+use crate::scoring::AttemptOutcome;
+use crate::validation::blocklist::Blocklist;
+use crate::validation::extract::{extract_word, is_multi_word, loggable_content, ExtractOptions};
+use crate::validation::normalize::{display_capitalize, proper_noun_casing};
+use crate::validation::remote::RemoteDictionary;
+use crate::validation::rules::Violation;
+
+pub use crate::game::WordVerdict;
+
+/// How long to wait for the game state to answer before giving up on a word
+const GAME_STATE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How many processed message IDs to remember for dropping duplicates
+pub(crate) const RECENT_MESSAGES: usize = 1000;
 
 /// Message to validate a word
 #[derive(Message)]
 #[rtype(result = "()")]
 pub struct ValidateWord {
+    /// The message content as it was written, the word is picked out of it and normalized here
+    pub word: String,
+    /// Channel or thread the message is in
+    pub channel_id: u64,
+    pub message_id: u64,
+    pub user_id: u64,
+}
+
+/// Message to validate a word and wait for the verdict
+///
+/// Runs the same pipeline as [`ValidateWord`], but resolves once the game state
+/// and reactions have been updated. LLM validation is not awaited.
+#[derive(Message)]
+#[rtype(result = "Result<WordVerdict>")]
+pub struct ValidateWordSync {
     pub word: String,
+    /// Channel or thread the message is in
+    pub channel_id: u64,
     pub message_id: u64,
     pub user_id: u64,
 }
 
-/// Actor that validates words against a dictionary and game rules
+/// Message to find out whether a word would be accepted without playing it
+///
+/// Only the dictionary and the game rules are consulted; the LLM is never called
+/// and the game state is left untouched.
+#[derive(Message)]
+#[rtype(result = "Result<WordVerdict>")]
+pub struct DryRunWord {
+    pub word: String,
+    /// Who would play the word, if known
+    pub user_id: Option<u64>,
+}
+
+/// Message to read the blocklist file again, returning how many words it blocks
+#[derive(Message)]
+#[rtype(result = "Result<usize>")]
+pub struct ReloadBlocklist;
+
+/// Message to ask how many words are being validated and waiting to be
+#[derive(Message)]
+#[rtype(result = "ValidationLoad")]
+pub struct GetValidationLoad;
+
+/// Message to free the concurrency slot of a word that waits in the game's queue
+#[derive(Message)]
+#[rtype(result = "()")]
+struct YieldSlot;
+
+/// Message to take a concurrency slot again once a queued word's turn comes,
+/// resolving when there's a free one
+#[derive(Message)]
+#[rtype(result = "()")]
+struct ResumeSlot;
+
+/// How many [`ValidateWord`] messages are being validated and waiting their turn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ValidationLoad {
+    pub in_flight: usize,
+    pub queued: usize,
+}
+
+impl std::ops::Add for ValidationLoad {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            in_flight: self.in_flight + other.in_flight,
+            queued: self.queued + other.queued,
+        }
+    }
+}
+
+/// Bounded set of recently processed message IDs
+///
+/// Once full, the least recently seen ID is forgotten first.
+pub(crate) struct RecentMessages {
+    order: VecDeque<u64>,
+    ids: HashSet<u64>,
+    capacity: usize,
+}
+
+impl RecentMessages {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            order: VecDeque::with_capacity(capacity),
+            ids: HashSet::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Remember a message ID, returning false if it was seen already
+    pub(crate) fn insert(&mut self, message_id: u64) -> bool {
+        if !self.ids.insert(message_id) {
+            // Seen again, so it's the most recent one now
+            if let Some(index) = self.order.iter().position(|&id| id == message_id) {
+                self.order.remove(index);
+            }
+            self.order.push_back(message_id);
+            return false;
+        }
+
+        self.order.push_back(message_id);
+        if self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+/// Actor that turns the game's verdicts on Discord messages into reactions and LLM checks
+///
+/// The dictionary and rules live in the [`GameEngine`](crate::game::GameEngine)
+/// owned by the game state actor.
 pub struct WordValidatorActor {
-    dictionary_validator: DictionaryValidator,
     game_state: Addr<GameStateActor>,
     llm_validator: Addr<LLMValidatorActor>,
     message_reaction: Addr<MessageReactionActor>,
+    mailbox_capacity: usize,
+    /// Messages already validated, so redelivered ones have no effect
+    recent_messages: RecentMessages,
+    /// Whether words over the daily limit or cooldown get a reply explaining why
+    limit_replies: bool,
+    /// Words rejected before the game or the LLM ever sees them
+    blocklist: Blocklist,
+    /// How the word is picked out of a message for the blocklist and alphabet checks
+    extract_options: ExtractOptions,
+    /// Whether words with letters outside the alphabet get a reply naming them
+    alphabet_replies: bool,
+    /// Reaction to words played while the game is paused
+    pause_reaction: char,
+    /// Reaction to words rejected only because they were already played
+    already_used_reaction: char,
+    /// Reaction to words over the daily limit
+    daily_limit_reaction: char,
+    /// Reaction to words played before the cooldown passed
+    cooldown_reaction: char,
+    /// Reaction to words taken for typos
+    typo_reaction: char,
+    /// Language of the replies
+    language: Language,
+    /// Asked about words missing from the dictionary before the LLM
+    remote_dictionary: Option<Arc<RemoteDictionary>>,
+    /// Whether messages that aren't word-shaped are logged, not just the words in them
+    log_message_content: bool,
+    /// Whether words are sent to the LLM at once or wait for a batch
+    llm_batch_mode: LlmBatchMode,
+    /// Where the handling of each word is noted down for /why
+    audit: Arc<AuditLog>,
+    /// Where words received and sent to the LLM are published
+    events: EventBus,
+    /// How many words may be validated at once, 0 for any number
+    concurrency: usize,
+    /// Words being validated and waiting beyond which new ones are dropped, 0 to never drop
+    shed_limit: usize,
+    /// Words being validated right now
+    in_flight: usize,
+    /// Words waiting for a validation to finish, oldest first
+    waiting: VecDeque<ValidateWord>,
+    /// Queued words whose turn came, waiting for a slot ahead of the new words
+    resuming: VecDeque<oneshot::Sender<()>>,
+    /// Whether the bot is shutting down, so new words are ignored
+    closed: bool,
 }
 
 impl WordValidatorActor {
     pub fn new(
-        dictionary_path: &str,
         game_state: Addr<GameStateActor>,
         llm_validator: Addr<LLMValidatorActor>,
         message_reaction: Addr<MessageReactionActor>,
-    ) -> Result<Self> {
-        let dictionary_validator = DictionaryValidator::new(dictionary_path)?;
-
-        Ok(Self {
-            dictionary_validator,
+    ) -> Self {
+        Self {
             game_state,
             llm_validator,
             message_reaction,
-        })
+            mailbox_capacity: DEFAULT_MAILBOX_CAPACITY,
+            recent_messages: RecentMessages::new(RECENT_MESSAGES),
+            limit_replies: true,
+            blocklist: Blocklist::default(),
+            extract_options: ExtractOptions::default(),
+            alphabet_replies: false,
+            pause_reaction: EMOJI_SLEEPING,
+            already_used_reaction: EMOJI_REPEAT,
+            daily_limit_reaction: EMOJI_NO_ENTRY,
+            cooldown_reaction: EMOJI_TIMER,
+            typo_reaction: EMOJI_PENCIL,
+            language: Language::default(),
+            remote_dictionary: None,
+            log_message_content: false,
+            llm_batch_mode: LlmBatchMode::default(),
+            audit: Arc::new(AuditLog::new(0)),
+            events: EventBus::default(),
+            concurrency: 0,
+            shed_limit: 0,
+            in_flight: 0,
+            waiting: VecDeque::new(),
+            resuming: VecDeque::new(),
+            closed: false,
+        }
+    }
+
+    /// How many messages may wait for the actor before senders have to wait
+    pub fn with_mailbox_capacity(mut self, mailbox_capacity: usize) -> Self {
+        self.mailbox_capacity = mailbox_capacity;
+        self
+    }
+
+    /// Whether to reply to words rejected by the daily limit or cooldown, not just react
+    pub fn with_limit_replies(mut self, limit_replies: bool) -> Self {
+        self.limit_replies = limit_replies;
+        self
+    }
+
+    /// Reject the words on the blocklist before anything else
+    pub fn with_blocklist(mut self, blocklist: Blocklist) -> Self {
+        self.blocklist = blocklist;
+        self
+    }
+
+    /// Pick words out of messages the same way as the game does
+    pub fn with_extract_options(mut self, extract_options: ExtractOptions) -> Self {
+        self.extract_options = extract_options;
+        self
+    }
+
+    /// Whether to reply to words with letters outside the alphabet, not just react
+    pub fn with_alphabet_replies(mut self, alphabet_replies: bool) -> Self {
+        self.alphabet_replies = alphabet_replies;
+        self
+    }
+
+    /// React with `pause_reaction` to words played while the game is paused
+    pub fn with_pause_reaction(mut self, pause_reaction: char) -> Self {
+        self.pause_reaction = pause_reaction;
+        self
+    }
+
+    /// React with `already_used_reaction` to words already played in the game
+    pub fn with_already_used_reaction(mut self, already_used_reaction: char) -> Self {
+        self.already_used_reaction = already_used_reaction;
+        self
+    }
+
+    /// React with `daily_limit_reaction` to words over the daily limit
+    pub fn with_daily_limit_reaction(mut self, daily_limit_reaction: char) -> Self {
+        self.daily_limit_reaction = daily_limit_reaction;
+        self
+    }
+
+    /// React with `cooldown_reaction` to words played before the cooldown passed
+    pub fn with_cooldown_reaction(mut self, cooldown_reaction: char) -> Self {
+        self.cooldown_reaction = cooldown_reaction;
+        self
+    }
+
+    /// React with `typo_reaction` to words the game takes for typos
+    pub fn with_typo_reaction(mut self, typo_reaction: char) -> Self {
+        self.typo_reaction = typo_reaction;
+        self
     }
+
+    /// Reply in `language`
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Ask the remote dictionary about words missing from the local one before the LLM
+    pub fn with_remote_dictionary(mut self, remote_dictionary: Arc<RemoteDictionary>) -> Self {
+        self.remote_dictionary = Some(remote_dictionary);
+        self
+    }
+
+    /// Log whole messages, not just the words picked out of them
+    pub fn with_log_message_content(mut self, log_message_content: bool) -> Self {
+        self.log_message_content = log_message_content;
+        self
+    }
+
+    /// Send the words to the LLM as `llm_batch_mode` says
+    pub fn with_llm_batch_mode(mut self, llm_batch_mode: LlmBatchMode) -> Self {
+        self.llm_batch_mode = llm_batch_mode;
+        self
+    }
+
+    /// Note down the words received and rejected here in `audit`
+    pub fn with_audit(mut self, audit: Arc<AuditLog>) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Publish the words received and sent to the LLM on `events`
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Validate at most `concurrency` words at once, queueing the rest in the
+    /// order they arrive, and drop words once `shed_limit` are being validated
+    /// or waiting
+    ///
+    /// A zero `concurrency` validates every word right away, and a zero
+    /// `shed_limit` never drops any.
+    pub fn with_concurrency(mut self, concurrency: usize, shed_limit: usize) -> Self {
+        self.concurrency = concurrency;
+        self.shed_limit = shed_limit;
+        self
+    }
+
+    fn load(&self) -> ValidationLoad {
+        ValidationLoad {
+            in_flight: self.in_flight,
+            queued: self.waiting.len(),
+        }
+    }
+
+    /// Validate a word in the background, starting the next waiting word once it's done
+    fn start(&mut self, msg: ValidateWord, ctx: &mut Context<Self>) {
+        self.in_flight += 1;
+        let word = msg.word.clone();
+        let message_id = msg.message_id;
+        let validation = self.validate(
+            msg.word,
+            msg.channel_id,
+            msg.message_id,
+            msg.user_id,
+            Some(ctx.address()),
+        );
+
+        // Don't block the actor by waiting for the game state and reactions
+        let task = actix::spawn(async move {
+            if let Err(e) = validation.await {
+                warn!(word = %word, message_id, error = %e, "Failed to validate word");
+            }
+        });
+
+        let message_reaction = self.message_reaction.clone();
+        let watch = async move {
+            if let Err(e) = task.await {
+                if e.is_panic() {
+                    error!(message_id, error = %e, "Word validation panicked");
+                    message_reaction.do_send(NotifyAdmin {
+                        kind: AlertKind::TaskPanic,
+                        error: Error::TaskPanic(format!(
+                            "validating message {}: {}",
+                            message_id, e
+                        )),
+                    });
+                }
+            }
+        };
+        ctx.spawn(actix::fut::wrap_future(watch).map(|(), act: &mut Self, ctx| act.free_slot(ctx)));
+    }
+
+    /// Hand a freed slot to a queued word whose turn came, or else start the next waiting word
+    fn free_slot(&mut self, ctx: &mut Context<Self>) {
+        self.in_flight -= 1;
+        while let Some(resume) = self.resuming.pop_front() {
+            if resume.send(()).is_ok() {
+                self.in_flight += 1;
+                return;
+            }
+        }
+        if let Some(next) = self.waiting.pop_front() {
+            self.start(next, ctx);
+        }
+    }
+
+    /// Drop a word there's no room for, showing the player it wasn't played
+    fn shed(&self, msg: ValidateWord) {
+        let load = self.load();
+        warn!(
+            message_id = msg.message_id,
+            user_id = msg.user_id,
+            in_flight = load.in_flight,
+            queued = load.queued,
+            "Too many words being validated, dropping the word"
+        );
+        self.message_reaction.do_send(AddReaction {
+            channel_id: msg.channel_id,
+            message_id: msg.message_id,
+            reaction: EMOJI_WARNING,
+            span: Span::current(),
+        });
+    }
+
+    /// Whether the word in a message is on the blocklist
+    fn is_blocked(&self, content: &str) -> bool {
+        !self.blocklist.is_empty()
+            && extract_word(content, &self.extract_options)
+                .is_some_and(|word| self.blocklist.contains(&word))
+    }
+
+    /// Letters of the word in a message that are outside the allowed alphabet
+    fn foreign_letters(&self, content: &str) -> Vec<char> {
+        extract_word(content, &self.extract_options)
+            .map(|word| self.extract_options.foreign_letters(&word))
+            .unwrap_or_default()
+    }
+
+    /// Whether the message has been validated already
+    ///
+    /// Gateway reconnects can deliver the same message again, which mustn't play
+    /// the word twice or react to it again.
+    fn is_duplicate(&mut self, message_id: u64) -> bool {
+        if self.recent_messages.insert(message_id) {
+            return false;
+        }
+        debug!(message_id, "Dropping message that was already validated");
+        true
+    }
+
+    /// Build the validation pipeline for a word
+    ///
+    /// The returned future plays the word in the game state and applies the reactions.
+    /// Everything done for the word, including in other actors, happens within a
+    /// `validate_word` span carrying its message and user IDs, and the word if the
+    /// message has one. `slots` is the actor whose concurrency slot the word holds, if any.
+    fn validate(
+        &self,
+        word: String,
+        channel_id: u64,
+        message_id: u64,
+        user_id: u64,
+        slots: Option<Addr<WordValidatorActor>>,
+    ) -> impl Future<Output = Result<WordVerdict>> + 'static {
+        // The raw message may be chat, which is kept out of the logs
+        let logged = loggable_content(&word, &self.extract_options, self.log_message_content);
+        let span = info_span!("validate_word", message_id, user_id, word = %logged);
+        span.in_scope(|| debug!("Received word for validation"));
+        self.audit
+            .record(message_id, AuditStep::Received { user_id });
+        self.events.publish(GameEvent::WordReceived {
+            message_id,
+            user_id,
+        });
+
+        let game_state = self.game_state.clone();
+        let llm_validator = self.llm_validator.clone();
+        let message_reaction = self.message_reaction.clone();
+        let limit_replies = self.limit_replies;
+        let blocked = self.is_blocked(&word);
+        let foreign_letters = self.foreign_letters(&word);
+        // Chat around the game, which the players are shown was ignored
+        let ignored_chat = self.extract_options.multi_word_policy == MultiWordPolicy::ReactIgnored
+            && is_multi_word(&word);
+        let alphabet_replies = self.alphabet_replies;
+        let pause_reaction = self.pause_reaction;
+        let already_used_reaction = self.already_used_reaction;
+        let daily_limit_reaction = self.daily_limit_reaction;
+        let cooldown_reaction = self.cooldown_reaction;
+        let typo_reaction = self.typo_reaction;
+        let language = self.language;
+        let remote_dictionary = self.remote_dictionary.clone();
+        let immediate = self.llm_batch_mode == LlmBatchMode::Immediate;
+        let audit = self.audit.clone();
+        let events = self.events.clone();
+        // The played word in the player's own casing, for the LLM
+        let written = extract_word(&word, &self.extract_options);
+
+        async move {
+            debug!(message_id, "Validating word");
+
+            // While the game is paused even these are ignored like any other word,
+            // which the game state takes care of
+            let paused = (blocked || !foreign_letters.is_empty())
+                && game_state.send(IsPaused).await.unwrap_or(false);
+
+            // Blocked words are never played, nor shown to the LLM
+            if blocked && !paused {
+                audit.record(message_id, AuditStep::Verdict(WordVerdict::Blocked));
+                debug!(message_id, reaction = %EMOJI_CROSS, "Adding reaction");
+                set_verdict(&message_reaction, channel_id, message_id, Validity::Invalid).await?;
+                // The game state never sees the word, so it's told about the attempt
+                game_state.do_send(RecordAttempt {
+                    user_id,
+                    outcome: AttemptOutcome::Blocked,
+                });
+                info!(
+                    message_id,
+                    user_id,
+                    verdict = "blocked",
+                    "Word is on the blocklist"
+                );
+                return Ok(WordVerdict::Blocked);
+            }
+
+            // Neither are words spelled with letters from outside the alphabet,
+            // e.g. Cyrillic lookalikes that would pass for new words
+            if !foreign_letters.is_empty() && !paused {
+                audit.record(
+                    message_id,
+                    AuditStep::Verdict(WordVerdict::ForeignLetters(foreign_letters.clone())),
+                );
+                debug!(message_id, reaction = %EMOJI_CROSS, "Adding reaction");
+                set_verdict(&message_reaction, channel_id, message_id, Validity::Invalid).await?;
+                game_state.do_send(RecordAttempt {
+                    user_id,
+                    outcome: AttemptOutcome::NotAWord,
+                });
+                if alphabet_replies {
+                    explain(
+                        &message_reaction,
+                        channel_id,
+                        message_id,
+                        Text::ForeignLetters(&foreign_letters).render(language),
+                    )
+                    .await;
+                }
+                info!(
+                    message_id,
+                    user_id,
+                    verdict = "foreign_letters",
+                    letters = %describe_letters(&foreign_letters),
+                    "Word has letters outside the allowed alphabet"
+                );
+                return Ok(WordVerdict::ForeignLetters(foreign_letters));
+            }
+
+            // Check and register the word in one step. A submission that timed out
+            // may still be played, so unlike the other hops it's never sent twice.
+            let mut submission = match tokio::time::timeout(
+                GAME_STATE_TIMEOUT,
+                game_state.send(SubmitWord {
+                    content: word.clone(),
+                    user_id,
+                    channel_id,
+                    message_id,
+                    span: Span::current(),
+                }),
+            )
+            .await
+            {
+                Ok(Ok(submission)) => submission,
+                Ok(Err(e)) => {
+                    return Err(delivery_failed(
+                        &message_reaction,
+                        format!("Failed to validate game rules: {}", e),
+                    ))
+                }
+                Err(_) => {
+                    return Err(delivery_failed(
+                        &message_reaction,
+                        format!("Timeout while validating game rules for '{}'", logged),
+                    ))
+                }
+            };
+
+            // A queued word is played once the word before it has its verdict. It
+            // may wait long, so its slot goes to the next word in the meantime.
+            if let Some(released) = submission.released.take() {
+                if let Some(slots) = &slots {
+                    slots.do_send(YieldSlot);
+                }
+                let queued = async {
+                    debug!(message_id, reaction = %EMOJI_HOURGLASS, "Adding reaction");
+                    send_reaction(&message_reaction, channel_id, message_id, EMOJI_HOURGLASS)
+                        .await?;
+                    debug!(
+                        word = %logged,
+                        message_id,
+                        user_id,
+                        verdict = "queued",
+                        "Word waits for the previous word's verdict"
+                    );
+                    let submission = released.await.map_err(|_| {
+                        delivery_failed(
+                            &message_reaction,
+                            format!("Queued word '{}' was dropped by the game state", logged),
+                        )
+                    })?;
+                    remove_reaction(&message_reaction, channel_id, message_id, EMOJI_HOURGLASS)
+                        .await?;
+                    Ok::<_, Error>(submission)
+                }
+                .await;
+                // The slot freed when the validation ends is taken again either way
+                if let Some(slots) = &slots {
+                    let _ = slots.send(ResumeSlot).await;
+                }
+                submission = queued?;
+            }
+
+            let word = submission.word.unwrap_or(word);
+            match &submission.verdict {
+                WordVerdict::NotAWord if ignored_chat => {
+                    debug!(message_id, reaction = %EMOJI_SPEECH, "Adding reaction");
+                    send_reaction(&message_reaction, channel_id, message_id, EMOJI_SPEECH).await?;
+                    info!(
+                        message_id,
+                        user_id,
+                        verdict = "ignored_chat",
+                        "Message has several words, ignored"
+                    );
+                }
+                WordVerdict::NotAWord
+                | WordVerdict::Queued
+                | WordVerdict::Duplicate
+                | WordVerdict::Blocked
+                | WordVerdict::ForeignLetters(_) => {}
+                WordVerdict::Paused => {
+                    debug!(message_id, reaction = %pause_reaction, "Adding reaction");
+                    send_reaction(&message_reaction, channel_id, message_id, pause_reaction)
+                        .await?;
+                    info!(
+                        word = %word,
+                        message_id,
+                        user_id,
+                        verdict = "paused",
+                        "Word played while the game is paused"
+                    );
+                }
+                WordVerdict::ValidInDictionary | WordVerdict::ValidProperNoun => {
+                    // Valid word and valid move, add checkmark
+                    debug!(message_id, reaction = %EMOJI_CHECK, "Adding reaction");
+                    set_verdict(&message_reaction, channel_id, message_id, Validity::Valid).await?;
+
+                    let dictionary = match &submission.source {
+                        Some(VerdictSource::Dictionary { file: Some(file) }) => file,
+                        _ => "",
+                    };
+                    info!(
+                        word = %word,
+                        message_id,
+                        user_id,
+                        verdict = "valid",
+                        dictionary,
+                        "Word is valid (in dictionary and follows rules)"
+                    );
+                }
+                WordVerdict::PendingLlm => {
+                    // The remote dictionary may know the word, sparing the LLM call
+                    let known = match &remote_dictionary {
+                        Some(remote) => remote.check(&word).await == Some(true),
+                        None => false,
+                    };
+                    if known {
+                        let applied = game_state
+                            .send(MarkWordValidity {
+                                message_id,
+                                validity: Validity::Valid,
+                                source: VerdictSource::Dictionary { file: None },
+                            })
+                            .await
+                            .unwrap_or(true);
+                        if applied {
+                            debug!(message_id, reaction = %EMOJI_CHECK, "Adding reaction");
+                            set_verdict(&message_reaction, channel_id, message_id, Validity::Valid)
+                                .await?;
+                        }
+                        info!(
+                            word = %word,
+                            message_id,
+                            user_id,
+                            verdict = "valid_remote",
+                            "Word found in the remote dictionary"
+                        );
+                        return Ok(WordVerdict::ValidInDictionary);
+                    }
+
+                    // Word not in dictionary but follows rules, send to LLM validator
+                    debug!(message_id, reaction = %EMOJI_QUESTION, "Adding reaction");
+                    set_verdict(&message_reaction, channel_id, message_id, Validity::Pending)
+                        .await?;
+
+                    // Send to LLM validator for proper noun check as the player wrote
+                    // the word, capitalized if they wrote it all in lowercase
+                    let capitalized_word = written
+                        .as_deref()
+                        .map(proper_noun_casing)
+                        .unwrap_or_else(|| display_capitalize(&word));
+                    let previous_word = game_state
+                        .send(GetPreviousWord { message_id })
+                        .await
+                        .unwrap_or_default();
+
+                    // Published first, so the verdict can't be published before it
+                    events.publish(GameEvent::EscalatedToLlm {
+                        message_id,
+                        user_id,
+                        word: word.clone(),
+                    });
+                    debug!(word = %capitalized_word, message_id, "Sending word to LLM validator");
+                    deliver(
+                        &llm_validator,
+                        ValidateProperNoun {
+                            word: capitalized_word,
+                            previous_word,
+                            channel_id,
+                            message_id,
+                            game_state: game_state.clone(),
+                            message_reaction: message_reaction.clone(),
+                            immediate,
+                            span: Span::current(),
+                        },
+                        "proper noun check",
+                    )
+                    .await
+                    .map_err(|e| delivery_failed(&message_reaction, e.to_string()))?;
+
+                    info!(
+                        word = %word,
+                        message_id,
+                        user_id,
+                        verdict = "pending_llm",
+                        "Word not in dictionary, sent to LLM for validation"
+                    );
+                }
+                WordVerdict::NotInDictionary => {
+                    debug!(message_id, reaction = %EMOJI_CROSS, "Adding reaction");
+                    set_verdict(&message_reaction, channel_id, message_id, Validity::Invalid)
+                        .await?;
+                    let suggestions = game_state
+                        .send(SuggestWords { word: word.clone() })
+                        .await
+                        .unwrap_or_default();
+                    if !suggestions.is_empty() {
+                        explain(
+                            &message_reaction,
+                            channel_id,
+                            message_id,
+                            Text::Suggestions(&suggestions).render(language),
+                        )
+                        .await;
+                    }
+                    info!(
+                        word = %word,
+                        message_id,
+                        user_id,
+                        verdict = "not_in_dictionary",
+                        "Word not in dictionary, marked as invalid"
+                    );
+                }
+                WordVerdict::Implausible => {
+                    debug!(message_id, reaction = %EMOJI_CROSS, "Adding reaction");
+                    set_verdict(&message_reaction, channel_id, message_id, Validity::Invalid)
+                        .await?;
+                    info!(
+                        word = %word,
+                        message_id,
+                        user_id,
+                        verdict = "implausible",
+                        "Word doesn't look like a word, marked as invalid"
+                    );
+                }
+                WordVerdict::LikelyTypo(meant) => {
+                    // Never played, so the player can simply try again
+                    debug!(message_id, reaction = %typo_reaction, "Adding reaction");
+                    send_reaction(&message_reaction, channel_id, message_id, typo_reaction).await?;
+                    info!(
+                        word = %word,
+                        message_id,
+                        user_id,
+                        meant = %meant,
+                        verdict = "likely_typo",
+                        "Word is likely a typo, not played"
+                    );
+                }
+                WordVerdict::InvalidRules(Violation::AlreadyUsed(_)) => {
+                    debug!(message_id, reaction = %already_used_reaction, "Adding reaction");
+                    send_reaction(
+                        &message_reaction,
+                        channel_id,
+                        message_id,
+                        already_used_reaction,
+                    )
+                    .await?;
+                    info!(
+                        word = %word,
+                        message_id,
+                        user_id,
+                        verdict = "already_used",
+                        "Word already played in this game, marked as invalid"
+                    );
+                }
+                WordVerdict::InvalidRules(_) => {
+                    reject(&message_reaction, &word, channel_id, message_id, user_id).await?;
+                }
+                WordVerdict::DailyLimitReached(limit) => {
+                    debug!(message_id, reaction = %daily_limit_reaction, "Adding reaction");
+                    send_reaction(
+                        &message_reaction,
+                        channel_id,
+                        message_id,
+                        daily_limit_reaction,
+                    )
+                    .await?;
+                    if limit_replies {
+                        explain(
+                            &message_reaction,
+                            channel_id,
+                            message_id,
+                            Text::DailyLimit {
+                                limit: *limit,
+                                reaction: daily_limit_reaction,
+                            }
+                            .render(language),
+                        )
+                        .await;
+                    }
+                    info!(
+                        word = %word,
+                        message_id,
+                        user_id,
+                        verdict = "daily_limit",
+                        "Player has reached the daily word limit"
+                    );
+                }
+                WordVerdict::CoolingDown(wait) => {
+                    debug!(message_id, reaction = %cooldown_reaction, "Adding reaction");
+                    send_reaction(&message_reaction, channel_id, message_id, cooldown_reaction)
+                        .await?;
+                    if limit_replies {
+                        explain(
+                            &message_reaction,
+                            channel_id,
+                            message_id,
+                            Text::Cooldown {
+                                wait: *wait,
+                                reaction: cooldown_reaction,
+                            }
+                            .render(language),
+                        )
+                        .await;
+                    }
+                    info!(
+                        word = %word,
+                        message_id,
+                        user_id,
+                        verdict = "cooling_down",
+                        "Player played again before the cooldown passed"
+                    );
+                }
+                WordVerdict::NotYourTurn(on_turn) => {
+                    debug!(message_id, reaction = %EMOJI_NEXT_TRACK, "Adding reaction");
+                    send_reaction(&message_reaction, channel_id, message_id, EMOJI_NEXT_TRACK)
+                        .await?;
+                    explain(
+                        &message_reaction,
+                        channel_id,
+                        message_id,
+                        Text::NotYourTurn { user_id: *on_turn }.render(language),
+                    )
+                    .await;
+                    info!(
+                        word = %word,
+                        message_id,
+                        user_id,
+                        on_turn,
+                        verdict = "not_your_turn",
+                        "Player played out of turn"
+                    );
+                }
+                WordVerdict::AwaitingVerdict => {
+                    debug!(message_id, reaction = %EMOJI_HOURGLASS, "Adding reaction");
+                    send_reaction(&message_reaction, channel_id, message_id, EMOJI_HOURGLASS)
+                        .await?;
+                    info!(
+                        word = %word,
+                        message_id,
+                        user_id,
+                        verdict = "awaiting_verdict",
+                        "Word played while the previous word awaits its verdict"
+                    );
+                }
+            }
+
+            Ok(submission.verdict)
+        }
+        .instrument(span)
+    }
+}
+
+/// The letters as a comma-separated list, e.g. "с, ш"
+fn describe_letters(letters: &[char]) -> String {
+    letters
+        .iter()
+        .map(char::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Add a reaction and wait until the reaction actor has handled it
+async fn send_reaction(
+    message_reaction: &Addr<MessageReactionActor>,
+    channel_id: u64,
+    message_id: u64,
+    reaction: char,
+) -> Result<()> {
+    deliver(
+        message_reaction,
+        AddReaction {
+            channel_id,
+            message_id,
+            reaction,
+            span: Span::current(),
+        },
+        "reaction",
+    )
+    .await
+    .map_err(|e| delivery_failed(message_reaction, e.to_string()))
+}
+
+/// Show the word's verdict and wait until the reaction actor has handled it
+async fn set_verdict(
+    message_reaction: &Addr<MessageReactionActor>,
+    channel_id: u64,
+    message_id: u64,
+    validity: Validity,
+) -> Result<()> {
+    deliver(
+        message_reaction,
+        SetVerdict {
+            channel_id,
+            message_id,
+            validity,
+            replaces: None,
+            span: Span::current(),
+        },
+        "verdict reaction",
+    )
+    .await
+    .map_err(|e| delivery_failed(message_reaction, e.to_string()))
+}
+
+/// Remove a reaction and wait until the reaction actor has handled it
+async fn remove_reaction(
+    message_reaction: &Addr<MessageReactionActor>,
+    channel_id: u64,
+    message_id: u64,
+    reaction: char,
+) -> Result<()> {
+    deliver(
+        message_reaction,
+        DeleteReaction {
+            channel_id,
+            message_id,
+            reaction,
+            span: Span::current(),
+        },
+        "reaction removal",
+    )
+    .await
+    .map_err(|e| delivery_failed(message_reaction, e.to_string()))
+}
+
+/// Reply to a rejected word, since the reaction alone doesn't tell the player why
+async fn explain(
+    message_reaction: &Addr<MessageReactionActor>,
+    channel_id: u64,
+    message_id: u64,
+    content: String,
+) {
+    let reply = Reply {
+        channel_id,
+        message_id,
+        content,
+    };
+    if let Err(e) = message_reaction.send(reply).await {
+        warn!(message_id, error = %e, "Failed to explain why the word was rejected");
+    }
+}
+
+/// Report a message the pipeline couldn't deliver to the admins
+///
+/// Returns the error for the caller to pass on.
+fn delivery_failed(message_reaction: &Addr<MessageReactionActor>, description: String) -> Error {
+    error!(error = %description, "Word validation couldn't reach an actor");
+    message_reaction.do_send(NotifyAdmin {
+        kind: AlertKind::DeliveryFailure,
+        error: Error::Actor(description.clone()),
+    });
+    Error::Actor(description)
+}
+
+/// Mark a word that doesn't follow the game rules (regardless of dictionary status)
+async fn reject(
+    message_reaction: &Addr<MessageReactionActor>,
+    word: &str,
+    channel_id: u64,
+    message_id: u64,
+    user_id: u64,
+) -> Result<()> {
+    debug!(message_id, reaction = %EMOJI_CROSS, "Adding reaction");
+    set_verdict(message_reaction, channel_id, message_id, Validity::Invalid).await?;
+
+    info!(
+        word,
+        message_id,
+        user_id,
+        verdict = "invalid_rules",
+        "Word doesn't follow game rules, marked as invalid"
+    );
+    Ok(())
 }
 
 impl Actor for WordValidatorActor {
     type Context = Context<Self>;
 
-    fn started(&mut self, _ctx: &mut Context<Self>) {
+    fn started(&mut self, ctx: &mut Context<Self>) {
+        ctx.set_mailbox_capacity(self.mailbox_capacity);
         info!("WordValidatorActor started");
     }
 }
@@ -54,137 +1055,1814 @@ impl Actor for WordValidatorActor {
 impl Handler<ValidateWord> for WordValidatorActor {
     type Result = ();
 
-    fn handle(&mut self, msg: ValidateWord, _ctx: &mut Context<Self>) -> Self::Result {
-        info!("===============================");
-        info!("RECEIVED WORD FOR VALIDATION: '{}'", msg.word);
-        info!("===============================");
-
-        let word = msg.word.trim().to_lowercase();
-
+    fn handle(&mut self, msg: ValidateWord, ctx: &mut Context<Self>) -> Self::Result {
+        if self.closed {
+            debug!(
+                message_id = msg.message_id,
+                "Shutting down, ignoring the word"
+            );
+            return;
+        }
+        if self.is_duplicate(msg.message_id) {
+            return;
+        }
+        if self.concurrency == 0 || self.in_flight < self.concurrency {
+            self.start(msg, ctx);
+            return;
+        }
+        let load = self.load();
+        if self.shed_limit != 0 && load.in_flight + load.queued >= self.shed_limit {
+            self.shed(msg);
+            return;
+        }
         debug!(
-            "Validating word: '{}' (message_id: {})",
-            word, msg.message_id
+            message_id = msg.message_id,
+            in_flight = load.in_flight,
+            queued = load.queued,
+            "Too many words being validated, the word waits its turn"
         );
+        self.waiting.push_back(msg);
+    }
+}
 
-        // Skip empty words
-        if word.is_empty() {
-            debug!("Skipping empty word");
-            return;
+impl Handler<YieldSlot> for WordValidatorActor {
+    type Result = ();
+
+    fn handle(&mut self, _msg: YieldSlot, ctx: &mut Context<Self>) -> Self::Result {
+        self.free_slot(ctx);
+    }
+}
+
+impl Handler<ResumeSlot> for WordValidatorActor {
+    type Result = ResponseFuture<()>;
+
+    fn handle(&mut self, _msg: ResumeSlot, _ctx: &mut Context<Self>) -> Self::Result {
+        if self.concurrency == 0 || self.in_flight < self.concurrency {
+            self.in_flight += 1;
+            return Box::pin(async {});
+        }
+        let (resume, resumed) = oneshot::channel();
+        self.resuming.push_back(resume);
+        Box::pin(async move {
+            let _ = resumed.await;
+        })
+    }
+}
+
+impl Handler<GetValidationLoad> for WordValidatorActor {
+    type Result = MessageResult<GetValidationLoad>;
+
+    fn handle(&mut self, _msg: GetValidationLoad, _ctx: &mut Context<Self>) -> Self::Result {
+        MessageResult(self.load())
+    }
+}
+
+impl Handler<ValidateWordSync> for WordValidatorActor {
+    type Result = ResponseFuture<Result<WordVerdict>>;
+
+    fn handle(&mut self, msg: ValidateWordSync, _ctx: &mut Context<Self>) -> Self::Result {
+        if self.is_duplicate(msg.message_id) {
+            return Box::pin(async { Ok(WordVerdict::Duplicate) });
         }
+        Box::pin(self.validate(msg.word, msg.channel_id, msg.message_id, msg.user_id, None))
+    }
+}
 
-        // Skip words with numbers and non-alphabetic characters
-        if !word.chars().all(|c| c.is_alphabetic()) || word.chars().any(|c| c.is_ascii_digit()) {
-            debug!("Skipping word with numbers or non-alphabetic characters");
-            return;
+impl Handler<DryRunWord> for WordValidatorActor {
+    type Result = ResponseFuture<Result<WordVerdict>>;
+
+    fn handle(&mut self, msg: DryRunWord, _ctx: &mut Context<Self>) -> Self::Result {
+        debug!(word = %msg.word, "Dry-run validation");
+        if self.is_blocked(&msg.word) {
+            return Box::pin(async { Ok(WordVerdict::Blocked) });
+        }
+        let foreign_letters = self.foreign_letters(&msg.word);
+        if !foreign_letters.is_empty() {
+            return Box::pin(async { Ok(WordVerdict::ForeignLetters(foreign_letters)) });
         }
 
-        // First, check if it follows game rules
         let game_state = self.game_state.clone();
-        let message_reaction = self.message_reaction.clone();
-        let message_id = msg.message_id;
+        Box::pin(async move {
+            game_state
+                .send(PreviewWord {
+                    content: msg.word,
+                    user_id: msg.user_id,
+                })
+                .await
+                .map_err(|e| Error::Actor(format!("Failed to check game rules: {}", e)))
+        })
+    }
+}
 
-        // Registers the word in game state
-        debug!("Registering word '{}' in game state", word);
-        self.game_state
-            .do_send(crate::actors::game_state::RegisterWord {
-                word: word.clone(),
-                user_id: msg.user_id,
-                message_id: msg.message_id,
-            });
+impl Handler<ReloadBlocklist> for WordValidatorActor {
+    type Result = Result<usize>;
 
-        // Check if the word is in dictionary
-        let is_in_dictionary = self.dictionary_validator.is_valid_word(&word);
-        debug!("Word '{}' in dictionary: {}", word, is_in_dictionary);
+    fn handle(&mut self, _msg: ReloadBlocklist, _ctx: &mut Context<Self>) -> Self::Result {
+        self.blocklist.reload()
+    }
+}
 
-        // Store word for later use
-        let word_clone = word.clone();
-        let llm_validator = self.llm_validator.clone();
+impl Handler<Ping> for WordValidatorActor {
+    type Result = ();
 
-        // Use a separate thread to handle async operations without LocalSet
-        let handle = thread::spawn(move || {
-            let rt = tokio::runtime::Builder::new_current_thread()
-                .enable_all()
-                .build()
-                .unwrap();
+    fn handle(&mut self, _msg: Ping, _ctx: &mut Context<Self>) -> Self::Result {}
+}
 
-            // Use a timeout to ensure the thread doesn't hang forever
-            rt.block_on(async {
-                // Always check game rules first
-                debug!("Checking if '{}' follows game rules", word_clone);
-                match tokio::time::timeout(
-                    std::time::Duration::from_secs(5),
-                    game_state.send(ValidateGameRules { word: word_clone.clone() })
-                ).await {
-                    Ok(result) => {
-                        match result {
-                            Ok(is_valid_move) => {
-                                if is_valid_move {
-                                    // Word follows game rules
-                                    if is_in_dictionary {
-                                        // Valid word and valid move, add checkmark
-                                        debug!("Adding ✅ reaction to message {}", message_id);
-                                        message_reaction.do_send(crate::actors::message_reaction::AddReaction {
-                                            message_id,
-                                            reaction: '✅',
-                                        });
-
-                                        // Mark as valid in game state
-                                        debug!("Marking word as valid in game state");
-                                        game_state.do_send(crate::actors::game_state::MarkWordValidity {
-                                            message_id,
-                                            is_valid: true,
-                                        });
-
-                                        info!("Word '{}' is valid (in dictionary and follows rules)", word_clone);
-                                    } else {
-                                        // Word not in dictionary but follows rules, send to LLM validator
-                                        debug!("Adding ❓ reaction to message {}", message_id);
-                                        message_reaction.do_send(crate::actors::message_reaction::AddReaction {
-                                            message_id,
-                                            reaction: '❓',
-                                        });
-
-                                        // Send to LLM validator for proper noun check with capitalized word
-                                        let capitalized_word = word_clone.chars()
-                                            .enumerate()
-                                            .map(|(i, c)| if i == 0 { c.to_uppercase().to_string() } else { c.to_string() })
-                                            .collect::<String>();
-
-                                        debug!("Sending '{}' to LLM validator", capitalized_word);
-                                        llm_validator.do_send(crate::actors::llm_validator::ValidateProperNoun {
-                                            word: capitalized_word,
-                                            message_id,
-                                            game_state: game_state.clone(),
-                                            message_reaction: message_reaction.clone(),
-                                        });
-
-                                        info!("Word '{}' not in dictionary, sent to LLM for validation", word_clone);
-                                    }
-                                } else {
-                                    // Word doesn't follow game rules, add X (regardless of dictionary status)
-                                    debug!("Adding ❌ reaction to message {}", message_id);
-                                    message_reaction.do_send(crate::actors::message_reaction::AddReaction {
-                                        message_id,
-                                        reaction: '❌',
-                                    });
-
-                                    info!("Word '{}' doesn't follow game rules, marked as invalid", word_clone);
-                                }
-                            },
-                            Err(e) => {
-                                warn!("Failed to validate game rules for '{}': {:?}", word_clone, e);
-                            }
-                        }
-                    },
-                    Err(_) => {
-                        warn!("Timeout while validating game rules for '{}'", word_clone);
-                    }
+impl Handler<Shutdown> for WordValidatorActor {
+    type Result = ();
+
+    fn handle(&mut self, _msg: Shutdown, _ctx: &mut Context<Self>) -> Self::Result {
+        self.closed = true;
+        // The words being validated finish, the ones waiting for them never start
+        if !self.waiting.is_empty() {
+            info!(
+                count = self.waiting.len(),
+                "Shutting down, dropping words waiting for validation"
+            );
+        }
+        self.waiting.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::game_state::{
+        Delivery, GetHistory, ProcessWord, RedeliverVerdicts, ResetGame, SetPaused,
+    };
+    use crate::actors::message_reaction::ReportVerdicts;
+    use crate::actors::testing::{
+        CapturedLogs, MockCompletionApi, MockLookupApi, ReactionCall, RecordingReactionApi,
+    };
+    use crate::actors::SetMessageReaction;
+    use crate::config::Config;
+    use crate::game::GameEngine;
+    use crate::rotation::{Rotation, RotationStore, TurnMode};
+    use crate::validation::dictionary::DictionaryValidator;
+    use crate::validation::llm::LLMValidator;
+    use crate::validation::rules::{GameRules, PendingPolicy};
+    use poise::serenity_prelude as serenity;
+    use std::sync::Arc;
+
+    fn start_validator(api: &RecordingReactionApi) -> Addr<WordValidatorActor> {
+        start_validator_with(api, &Config::default())
+    }
+
+    fn start_validator_with(
+        api: &RecordingReactionApi,
+        config: &Config,
+    ) -> Addr<WordValidatorActor> {
+        start_validator_with_llm(api, config, LLMValidator::default(), 10)
+    }
+
+    fn start_validator_with_llm(
+        api: &RecordingReactionApi,
+        config: &Config,
+        llm: LLMValidator,
+        llm_batch_size: usize,
+    ) -> Addr<WordValidatorActor> {
+        validator_with_llm(api, config, llm, llm_batch_size).start()
+    }
+
+    fn validator_with_llm(
+        api: &RecordingReactionApi,
+        config: &Config,
+        llm: LLMValidator,
+        llm_batch_size: usize,
+    ) -> WordValidatorActor {
+        let dictionary = DictionaryValidator::from_words(["kissa", "kassa", "kassi"]);
+        let mut game_state =
+            GameStateActor::with_engine(GameEngine::from_config(dictionary, config))
+                .with_daily_limit(config.max_words_per_user_per_day, config.timezone)
+                .with_cooldown(Duration::from_secs(config.user_cooldown_secs))
+                .with_suggestions(config.suggest_words)
+                .with_language(config.language);
+        if config.game_turn_mode == TurnMode::Rotation {
+            game_state = game_state.with_rotation(
+                RotationStore::new(&config.turn_order_path),
+                Duration::from_secs(config.turn_skip_secs),
+            );
+        }
+        let game_state = game_state.start();
+        let llm_validator = LLMValidatorActor::with_validator(llm, llm_batch_size, 86400).start();
+        // Pacing is covered by the reaction actor's own tests
+        let message_reaction =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_reaction_interval(Duration::ZERO)
+                .start();
+
+        WordValidatorActor::new(game_state, llm_validator, message_reaction)
+            .with_limit_replies(config.limit_replies)
+            .with_alphabet_replies(config.alphabet_replies)
+            .with_already_used_reaction(config.already_used_reaction)
+            .with_daily_limit_reaction(config.daily_limit_reaction)
+            .with_cooldown_reaction(config.cooldown_reaction)
+            .with_typo_reaction(config.typo_reaction)
+            .with_language(config.language)
+            .with_extract_options(ExtractOptions::from_config(config))
+    }
+
+    async fn validate(validator: &Addr<WordValidatorActor>, word: &str, id: u64) -> WordVerdict {
+        validate_as(validator, word, id, 42).await
+    }
+
+    async fn validate_as(
+        validator: &Addr<WordValidatorActor>,
+        word: &str,
+        id: u64,
+        user_id: u64,
+    ) -> WordVerdict {
+        validator
+            .send(ValidateWordSync {
+                word: word.to_string(),
+                channel_id: 1,
+                message_id: id,
+                user_id,
+            })
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    /// Start an actor on its own arbiter and stop that arbiter, closing the mailbox
+    fn start_stopped<A>(actor: A) -> Addr<A>
+    where
+        A: Actor<Context = Context<A>> + Send,
+    {
+        let arbiter = actix::Arbiter::new();
+        let addr = A::start_in_arbiter(&arbiter.handle(), |_| actor);
+        arbiter.stop();
+        arbiter.join().unwrap();
+        addr
+    }
+
+    #[actix_rt::test]
+    async fn test_validate_word_sync_verdicts() {
+        let api = RecordingReactionApi::default();
+        let validator = start_validator(&api);
+
+        assert_eq!(
+            validate(&validator, "Kissa", 1).await,
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            validate(&validator, "kassa", 2).await,
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            validate(&validator, "kassu", 3).await,
+            WordVerdict::PendingLlm
+        );
+        assert!(matches!(
+            validate(&validator, "koira", 4).await,
+            WordVerdict::InvalidRules(_)
+        ));
+        assert!(matches!(
+            validate(&validator, "kassa", 5).await,
+            WordVerdict::InvalidRules(_)
+        ));
+        assert_eq!(
+            validate(&validator, "kissa 2", 6).await,
+            WordVerdict::NotAWord
+        );
+        assert_eq!(
+            validate(&validator, "kassi 🐱", 7).await,
+            WordVerdict::ValidInDictionary
+        );
+
+        // Reactions have been applied by the time the verdict is returned
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(1, EMOJI_CHECK),
+                ReactionCall::Add(2, EMOJI_CHECK),
+                ReactionCall::Add(3, EMOJI_QUESTION),
+                ReactionCall::Add(4, EMOJI_CROSS),
+                ReactionCall::Add(5, EMOJI_REPEAT),
+                ReactionCall::Add(7, EMOJI_CHECK),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_rejection_reactions() {
+        let api = RecordingReactionApi::default();
+        let config = Config {
+            min_word_length: 5,
+            allow_consecutive_turns: false,
+            ..Config::default()
+        };
+        let validator = start_validator_with(&api, &config);
+
+        validate_as(&validator, "kissa", 1, 42).await;
+        assert_eq!(
+            validate_as(&validator, "kissa", 2, 43).await,
+            WordVerdict::InvalidRules(Violation::AlreadyUsed("kissa".to_string()))
+        );
+        assert_eq!(
+            validate_as(&validator, "kasa", 3, 43).await,
+            WordVerdict::InvalidRules(Violation::TooShort { min: 5 })
+        );
+        assert!(matches!(
+            validate_as(&validator, "koira", 4, 43).await,
+            WordVerdict::InvalidRules(Violation::Letters(_))
+        ));
+        assert_eq!(
+            validate_as(&validator, "kassa", 5, 42).await,
+            WordVerdict::InvalidRules(Violation::ConsecutiveTurns)
+        );
+
+        // Only words played before get their own reaction
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(1, EMOJI_CHECK),
+                ReactionCall::Add(2, EMOJI_REPEAT),
+                ReactionCall::Add(3, EMOJI_CROSS),
+                ReactionCall::Add(4, EMOJI_CROSS),
+                ReactionCall::Add(5, EMOJI_CROSS),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_already_used_reaction_configurable() {
+        let api = RecordingReactionApi::default();
+        let config = Config {
+            already_used_reaction: '♻',
+            ..Config::default()
+        };
+        let validator = start_validator_with(&api, &config);
+
+        validate(&validator, "kissa", 1).await;
+        validate(&validator, "kassa", 2).await;
+        validate(&validator, "kissa", 3).await;
+
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(1, EMOJI_CHECK),
+                ReactionCall::Add(2, EMOJI_CHECK),
+                ReactionCall::Add(3, '♻'),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_typo_reaction() {
+        let api = RecordingReactionApi::default();
+        let config = Config {
+            typo_check: true,
+            ..Config::default()
+        };
+        let validator = start_validator_with(&api, &config);
+
+        validate(&validator, "kissa", 1).await;
+        assert_eq!(
+            validate(&validator, "kyssa", 2).await,
+            WordVerdict::LikelyTypo("kassa".to_string())
+        );
+        // The typo wasn't played, so the player can go on with the word they meant
+        assert_eq!(
+            validate(&validator, "kassa", 3).await,
+            WordVerdict::ValidInDictionary
+        );
+
+        // Only the typo reaction, without the LLM's ❓
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(1, EMOJI_CHECK),
+                ReactionCall::Add(2, EMOJI_PENCIL),
+                ReactionCall::Add(3, EMOJI_CHECK),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_multi_word_policies() {
+        let start = |policy| {
+            let api = RecordingReactionApi::default();
+            let config = Config {
+                multi_word_policy: policy,
+                ..Config::default()
+            };
+            let validator = start_validator_with(&api, &config);
+            (api, validator)
+        };
+
+        // Chat is skipped without a trace by default
+        let (api, validator) = start(MultiWordPolicy::Ignore);
+        assert_eq!(
+            validate(&validator, "kissa — helppo!", 1).await,
+            WordVerdict::NotAWord
+        );
+        assert_eq!(
+            validate(&validator, "onpa vaikea", 2).await,
+            WordVerdict::NotAWord
+        );
+        validate(&validator, "kissa", 3).await;
+        assert_eq!(api.calls(), vec![ReactionCall::Add(3, EMOJI_CHECK)]);
+
+        // Or reacted to, but only when it's several words
+        let (api, validator) = start(MultiWordPolicy::ReactIgnored);
+        assert_eq!(
+            validate(&validator, "kissa — helppo!", 1).await,
+            WordVerdict::NotAWord
+        );
+        assert_eq!(
+            validate(&validator, "ki55a", 2).await,
+            WordVerdict::NotAWord
+        );
+        assert_eq!(
+            validate(&validator, "*kissa* :)", 3).await,
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(1, EMOJI_SPEECH),
+                ReactionCall::Add(3, EMOJI_CHECK),
+            ]
+        );
+
+        // Or the first word is played, with every usual check on it alone
+        let (api, validator) = start(MultiWordPolicy::FirstToken);
+        assert_eq!(
+            validate(&validator, "kissa — helppo!", 1).await,
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            validate(&validator, "kiссa on helppo", 2).await,
+            WordVerdict::ForeignLetters(vec!['с'])
+        );
+        assert_eq!(
+            validate(&validator, "kassa, кошка", 3).await,
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            validate(&validator, "kassa uudestaan", 4).await,
+            WordVerdict::InvalidRules(Violation::AlreadyUsed("kassa".to_string()))
+        );
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(1, EMOJI_CHECK),
+                ReactionCall::Add(2, EMOJI_CROSS),
+                ReactionCall::Add(3, EMOJI_CHECK),
+                ReactionCall::Add(4, EMOJI_REPEAT),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_dry_run_word() {
+        let api = RecordingReactionApi::default();
+        let validator = start_validator(&api);
+
+        let dry_run = |word: &str| {
+            validator.send(DryRunWord {
+                word: word.to_string(),
+                user_id: None,
+            })
+        };
+
+        assert_eq!(
+            validate(&validator, "kissa", 1).await,
+            WordVerdict::ValidInDictionary
+        );
+
+        assert_eq!(
+            dry_run("kassa").await.unwrap().unwrap(),
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            dry_run("kissu").await.unwrap().unwrap(),
+            WordVerdict::NotInDictionary
+        );
+        assert!(matches!(
+            dry_run("kissa").await.unwrap().unwrap(),
+            WordVerdict::InvalidRules(_)
+        ));
+        assert_eq!(
+            dry_run("ki ssa").await.unwrap().unwrap(),
+            WordVerdict::NotAWord
+        );
+
+        // Dry runs neither react nor advance the chain
+        assert_eq!(api.calls(), vec![ReactionCall::Add(1, EMOJI_CHECK)]);
+        assert_eq!(
+            validate(&validator, "kassa", 2).await,
+            WordVerdict::ValidInDictionary
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_proper_nouns_disabled() {
+        let api = RecordingReactionApi::default();
+        let config = Config {
+            accept_proper_nouns: false,
+            ..Config::default()
+        };
+        let validator = start_validator_with(&api, &config);
+
+        assert_eq!(
+            validate(&validator, "kissa", 1).await,
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            validate(&validator, "kissu", 2).await,
+            WordVerdict::NotInDictionary
+        );
+        // The unknown word didn't advance the chain
+        assert_eq!(
+            validate(&validator, "kassa", 3).await,
+            WordVerdict::ValidInDictionary
+        );
+
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(1, EMOJI_CHECK),
+                ReactionCall::Add(2, EMOJI_CROSS),
+                ReactionCall::Add(3, EMOJI_CHECK),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_require_capitalization() {
+        for require_capitalization in [true, false] {
+            let api = RecordingReactionApi::default();
+            let completions = MockCompletionApi::responding("[]");
+            let config = Config {
+                require_capitalization,
+                ..Config::default()
+            };
+            let llm = LLMValidator::with_api(Box::new(completions.clone()));
+            let validator = start_validator_with_llm(&api, &config, llm, 1);
+
+            assert_eq!(
+                validate_as(&validator, "kissa", 1, 1).await,
+                WordVerdict::ValidInDictionary
+            );
+            let lowercase = validate_as(&validator, "kissu", 2, 2).await;
+            let capitalized = validate_as(&validator, "KISSO", 3, 3).await;
+            assert_eq!(capitalized, WordVerdict::PendingLlm);
+
+            let expected_prompts = if require_capitalization {
+                // Rejected without asking the LLM, which sees the word as it was written
+                assert_eq!(lowercase, WordVerdict::NotInDictionary);
+                assert!(api.calls().contains(&ReactionCall::Add(2, EMOJI_CROSS)));
+                vec![r#"{"word":"KISSO","previous_word":"kissa"}"#]
+            } else {
+                assert_eq!(lowercase, WordVerdict::PendingLlm);
+                vec![
+                    r#"{"word":"Kissu","previous_word":"kissa"}"#,
+                    r#"{"word":"KISSO","previous_word":"kissu"}"#,
+                ]
+            };
+            for _ in 0..100 {
+                if completions.calls() >= expected_prompts.len() {
+                    break;
                 }
+                actix_rt::time::sleep(Duration::from_millis(20)).await;
+            }
+            let prompts = completions.prompts();
+            assert_eq!(prompts.len(), expected_prompts.len());
+            for (prompt, word) in prompts.iter().zip(expected_prompts) {
+                assert!(prompt.contains(word), "{} not in {}", word, prompt);
+            }
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_suggestions_on_rejection() {
+        let api = RecordingReactionApi::default();
+        let config = Config {
+            accept_proper_nouns: false,
+            suggest_words: true,
+            ..Config::default()
+        };
+        let validator = start_validator_with(&api, &config);
+
+        validate(&validator, "kissa", 1).await;
+        assert_eq!(
+            validate(&validator, "Kissu", 2).await,
+            WordVerdict::NotInDictionary
+        );
+        // Nothing close enough to suggest
+        assert_eq!(
+            validate(&validator, "auto", 3).await,
+            WordVerdict::NotInDictionary
+        );
+
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(1, EMOJI_CHECK),
+                ReactionCall::Add(2, EMOJI_CROSS),
+                ReactionCall::Reply(2, "Tarkoititko: kissa?".to_string()),
+                ReactionCall::Add(3, EMOJI_CROSS),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_spans_follow_the_word() {
+        let logs = CapturedLogs::default();
+        let _guard = logs.install();
+
+        let api = RecordingReactionApi::default();
+        let llm = LLMValidator::with_api(Box::new(MockCompletionApi::responding(
+            r#"[{"word": "Kissu", "is_proper_noun": true, "explanation": "Nimi"}]"#,
+        )));
+        let validator = start_validator_with_llm(&api, &Config::default(), llm, 1);
+
+        assert_eq!(
+            validate(&validator, "kissa", 1).await,
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            validate(&validator, "kissu", 2).await,
+            WordVerdict::PendingLlm
+        );
+
+        // The batch runs on its own thread, wait for it to settle
+        for _ in 0..100 {
+            if api.calls().len() >= 4 {
+                break;
+            }
+            actix_rt::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(api.calls().len(), 4);
+
+        let lines = logs.lines();
+        let logged_in = |span: &str, message: &str| {
+            lines
+                .iter()
+                .any(|line| line.contains(span) && line.contains(message))
+        };
+
+        // The game state and reaction actors log within the word's span
+        let first = "validate_word{message_id=1 user_id=42 word=kissa}";
+        assert!(logged_in(first, "Submitting 'kissa'"), "{:#?}", lines);
+        assert!(logged_in(first, "Setting verdict reaction"), "{:#?}", lines);
+        assert!(logged_in(first, "Added reaction"), "{:#?}", lines);
+
+        // So do the LLM queue and the verdict resolved on the batch thread
+        let second = "validate_word{message_id=2 user_id=42 word=kissu}";
+        assert!(
+            logged_in(second, "Queued word for LLM validation"),
+            "{:#?}",
+            lines
+        );
+        assert!(
+            logged_in(
+                &format!("{}:llm_verdict{{attempt=1}}", second),
+                "Word validated as proper noun by LLM"
+            ),
+            "{:#?}",
+            lines
+        );
+        assert!(
+            logged_in(
+                &format!("{}:llm_verdict{{attempt=1}}", second),
+                "Deleted reaction"
+            ),
+            "{:#?}",
+            lines
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_chat_kept_out_of_logs() {
+        let logs = CapturedLogs::default();
+        let _guard = logs.install();
+
+        let api = RecordingReactionApi::default();
+        let config = Config {
+            multi_word_policy: MultiWordPolicy::ReactIgnored,
+            ..Config::default()
+        };
+        let validator = start_validator_with(&api, &config);
+        assert_eq!(
+            validate(&validator, "kissa on salainen juttu", 1).await,
+            WordVerdict::NotAWord
+        );
+        assert_eq!(
+            validate(&validator, "*kissa* :)", 2).await,
+            WordVerdict::ValidInDictionary
+        );
+
+        let lines = logs.lines();
+        assert!(
+            lines.iter().all(|line| !line.contains("salainen")),
+            "{:#?}",
+            lines
+        );
+        assert!(
+            lines
+                .iter()
+                .any(|line| line.contains("message_id=1") && line.contains("word=[23 chars]")),
+            "{:#?}",
+            lines
+        );
+        // The word in a message is still logged, without the decorations
+        assert!(
+            lines
+                .iter()
+                .any(|line| line.contains("message_id=2") && line.contains("word=kissa}")),
+            "{:#?}",
+            lines
+        );
+
+        // Unless the whole message is allowed in the logs
+        let validator = validator_with_llm(&api, &config, LLMValidator::default(), 10)
+            .with_log_message_content(true)
+            .start();
+        validate(&validator, "kissa on salainen juttu", 3).await;
+        assert!(
+            logs.lines()
+                .iter()
+                .any(|line| line.contains("word=kissa on salainen juttu")),
+            "{:#?}",
+            logs.lines()
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_failed_verdicts_redelivered() {
+        let api = RecordingReactionApi::failing_reactions("Connection reset by peer");
+        let dictionary = DictionaryValidator::from_words(["kissa", "kassa"]);
+        let game_state =
+            GameStateActor::with_engine(GameEngine::from_config(dictionary, &Config::default()))
+                .start();
+        let message_reaction =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_reaction_interval(Duration::ZERO)
+                .start();
+        message_reaction
+            .send(ReportVerdicts(game_state.clone().recipient()))
+            .await
+            .unwrap();
+        game_state
+            .send(SetMessageReaction(message_reaction.clone()))
+            .await
+            .unwrap();
+        let validator = WordValidatorActor::new(
+            game_state.clone(),
+            LLMValidatorActor::default().start(),
+            message_reaction,
+        )
+        .start();
+        let delivery = || async {
+            let history = game_state.send(GetHistory { limit: 10 }).await.unwrap();
+            history
+                .iter()
+                .map(|entry| entry.delivery)
+                .collect::<Vec<_>>()
+        };
+
+        // The connection drops while the word is validated
+        assert_eq!(
+            validate(&validator, "kissa", 1).await,
+            WordVerdict::ValidInDictionary
+        );
+        actix_rt::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(delivery().await, vec![Delivery::Failed]);
+
+        // Once it's back, the reaction is set again, and only once
+        api.recover();
+        assert_eq!(game_state.send(RedeliverVerdicts).await.unwrap(), 1);
+        actix_rt::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(delivery().await, vec![Delivery::Delivered]);
+        assert_eq!(game_state.send(RedeliverVerdicts).await.unwrap(), 0);
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(1, EMOJI_CHECK),
+                ReactionCall::Add(1, EMOJI_CHECK)
+            ]
+        );
+
+        // Words whose reactions went through are left alone
+        assert_eq!(
+            validate(&validator, "kassa", 2).await,
+            WordVerdict::ValidInDictionary
+        );
+        actix_rt::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(
+            delivery().await,
+            vec![Delivery::Delivered, Delivery::Delivered]
+        );
+        assert_eq!(game_state.send(RedeliverVerdicts).await.unwrap(), 0);
+    }
+
+    #[actix_rt::test]
+    async fn test_validation_concurrency() {
+        let api = RecordingReactionApi::default();
+        let dictionary = DictionaryValidator::from_words(["kissa", "kassa", "kassi", "kasvi"]);
+        let game_state =
+            GameStateActor::with_engine(GameEngine::from_config(dictionary, &Config::default()))
+                .start();
+        // Slow reactions keep each word busy for a while
+        let message_reaction =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_reaction_interval(Duration::from_millis(30))
+                .start();
+        let validator = WordValidatorActor::new(
+            game_state,
+            LLMValidatorActor::default().start(),
+            message_reaction,
+        )
+        .with_concurrency(1, 4)
+        .start();
+
+        // Each word only follows the rules if the one before it was played first
+        let words = ["kissa", "kassa", "kassi", "kasvi", "kissa", "kassa"];
+        for (id, word) in (1..).zip(words) {
+            validator.do_send(ValidateWord {
+                word: word.to_string(),
+                channel_id: 1,
+                message_id: id,
+                user_id: id,
             });
+        }
+        assert_eq!(
+            validator.send(GetValidationLoad).await.unwrap(),
+            ValidationLoad {
+                in_flight: 1,
+                queued: 3
+            }
+        );
+
+        for _ in 0..100 {
+            if api.calls().len() >= words.len() {
+                break;
+            }
+            actix_rt::time::sleep(Duration::from_millis(20)).await;
+        }
+        let calls = api.calls();
+        // The words beyond the limit were dropped, never played
+        assert!(calls.contains(&ReactionCall::Add(5, EMOJI_WARNING)));
+        assert!(calls.contains(&ReactionCall::Add(6, EMOJI_WARNING)));
+        let accepted: Vec<_> = calls
+            .iter()
+            .filter_map(|call| match call {
+                ReactionCall::Add(id, EMOJI_CHECK) => Some(*id),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(accepted, vec![1, 2, 3, 4]);
+        assert_eq!(calls.len(), words.len());
+        assert_eq!(
+            validator.send(GetValidationLoad).await.unwrap(),
+            ValidationLoad::default()
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_shutdown() {
+        let api = RecordingReactionApi::default();
+        let validator = start_validator(&api);
+        validator.do_send(ValidateWord {
+            word: "kissa".to_string(),
+            channel_id: 1,
+            message_id: 1,
+            user_id: 42,
+        });
+        validator.send(Shutdown).await.unwrap();
+
+        // Words sent after the shutdown are ignored
+        validator.do_send(ValidateWord {
+            word: "kassa".to_string(),
+            channel_id: 1,
+            message_id: 2,
+            user_id: 43,
+        });
+        actix_rt::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(api.calls(), vec![ReactionCall::Add(1, EMOJI_CHECK)]);
+    }
+
+    #[actix_rt::test]
+    async fn test_audit_trail() {
+        let api = RecordingReactionApi::default();
+        let audit = Arc::new(AuditLog::new(10));
+        let dictionary = DictionaryValidator::from_words(["kissa"]);
+        let game_state =
+            GameStateActor::with_engine(GameEngine::from_config(dictionary, &Config::default()))
+                .with_audit(audit.clone())
+                .start();
+        let llm = LLMValidator::with_api(Box::new(MockCompletionApi::responding(
+            r#"[{"word": "Kissu", "is_proper_noun": true, "explanation": "Nimi"}]"#,
+        )));
+        let llm_validator = LLMValidatorActor::with_validator(llm, 1, 86400)
+            .with_audit(audit.clone())
+            .start();
+        let message_reaction =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_reaction_interval(Duration::ZERO)
+                .with_audit(audit.clone())
+                .start();
+        let validator = WordValidatorActor::new(game_state, llm_validator, message_reaction)
+            .with_audit(audit.clone())
+            .start();
+        let steps = |message_id| {
+            audit
+                .trail(message_id)
+                .into_iter()
+                .map(|event| event.step)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            validate(&validator, "kissa", 1).await,
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            validate_as(&validator, "kissu", 2, 43).await,
+            WordVerdict::PendingLlm
+        );
+        for _ in 0..100 {
+            if api.calls().len() >= 4 {
+                break;
+            }
+            actix_rt::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        assert_eq!(
+            steps(1),
+            vec![
+                AuditStep::Received { user_id: 42 },
+                AuditStep::Normalized(Some("kissa".to_string())),
+                AuditStep::Dictionary(true),
+                AuditStep::Verdict(WordVerdict::ValidInDictionary),
+                AuditStep::ReactionAdded {
+                    reaction: EMOJI_CHECK,
+                    ok: true
+                },
+            ]
+        );
+        let llm_steps = steps(2);
+        assert_eq!(
+            llm_steps[..5],
+            [
+                AuditStep::Received { user_id: 43 },
+                AuditStep::Normalized(Some("kissu".to_string())),
+                AuditStep::Dictionary(false),
+                AuditStep::Verdict(WordVerdict::PendingLlm),
+                AuditStep::ReactionAdded {
+                    reaction: EMOJI_QUESTION,
+                    ok: true
+                },
+            ]
+        );
+        assert!(llm_steps.contains(&AuditStep::SentToLlm { attempt: 1 }));
+        assert!(llm_steps.iter().any(|step| matches!(
+            step,
+            AuditStep::Settled {
+                validity: Validity::Valid,
+                source: VerdictSource::Llm { .. }
+            }
+        )));
+        assert_eq!(
+            llm_steps[llm_steps.len() - 2..],
+            [
+                AuditStep::ReactionRemoved {
+                    reaction: EMOJI_QUESTION,
+                    ok: true
+                },
+                AuditStep::ReactionAdded {
+                    reaction: EMOJI_CHECK,
+                    ok: true
+                },
+            ]
+        );
+        assert_eq!(audit.player(2), Some(43));
+    }
+
+    #[actix_rt::test]
+    async fn test_pipeline_events() {
+        let api = RecordingReactionApi::default();
+        let bus = EventBus::default();
+        let mut events = bus.subscribe();
+        let dictionary = DictionaryValidator::from_words(["kissa"]);
+        let game_state =
+            GameStateActor::with_engine(GameEngine::from_config(dictionary, &Config::default()))
+                .with_events(bus.clone())
+                .start();
+        let llm = LLMValidator::with_api(Box::new(MockCompletionApi::responding(
+            r#"[{"word": "Kissu", "is_proper_noun": true, "explanation": "Nimi"}]"#,
+        )));
+        let llm_validator = LLMValidatorActor::with_validator(llm, 1, 86400).start();
+        let message_reaction =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_reaction_interval(Duration::ZERO)
+                .start();
+        let validator =
+            WordValidatorActor::new(game_state.clone(), llm_validator, message_reaction)
+                .with_blocklist(Blocklist::from_words(["kissi"]))
+                .with_events(bus)
+                .start();
+        let mut next_events =
+            move || std::iter::from_fn(|| events.try_recv().ok()).collect::<Vec<_>>();
+        let kissa = |message_id| GameEvent::DictionaryAccepted {
+            message_id,
+            user_id: 42,
+            word: "kissa".to_string(),
+        };
+
+        validate(&validator, "kissa", 1).await;
+        assert_eq!(
+            next_events(),
+            vec![
+                GameEvent::WordReceived {
+                    message_id: 1,
+                    user_id: 42
+                },
+                kissa(1),
+                GameEvent::WordAccepted {
+                    message_id: 1,
+                    user_id: 42,
+                    word: "kissa".to_string(),
+                    chain_length: 1
+                },
+            ]
+        );
+
+        validate(&validator, "koira", 2).await;
+        validate(&validator, "kissa", 3).await;
+        let events = next_events();
+        assert_eq!(events.len(), 4);
+        assert!(matches!(
+            &events[1],
+            GameEvent::RuleViolation {
+                message_id: 2,
+                word,
+                violation: Violation::Letters(_),
+                ..
+            } if word == "koira"
+        ));
+        assert_eq!(
+            events[3],
+            GameEvent::RuleViolation {
+                message_id: 3,
+                user_id: 42,
+                word: "kissa".to_string(),
+                violation: Violation::AlreadyUsed("kissa".to_string()),
+            }
+        );
+
+        // Blocked words are only received
+        validate(&validator, "kissi", 4).await;
+        assert_eq!(
+            next_events(),
+            vec![GameEvent::WordReceived {
+                message_id: 4,
+                user_id: 42
+            }]
+        );
+
+        validate(&validator, "kissu", 5).await;
+        for _ in 0..100 {
+            if api.calls().len() >= 6 {
+                break;
+            }
+            actix_rt::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(
+            next_events(),
+            vec![
+                GameEvent::WordReceived {
+                    message_id: 5,
+                    user_id: 42
+                },
+                GameEvent::EscalatedToLlm {
+                    message_id: 5,
+                    user_id: 42,
+                    word: "kissu".to_string()
+                },
+                GameEvent::LlmVerdict {
+                    message_id: 5,
+                    user_id: 42,
+                    word: "kissu".to_string(),
+                    valid: true
+                },
+                GameEvent::WordAccepted {
+                    message_id: 5,
+                    user_id: 42,
+                    word: "kissu".to_string(),
+                    chain_length: 2
+                },
+            ]
+        );
+
+        // Owners settle words still waiting for the LLM
+        game_state.send(ResetGame).await.unwrap();
+        game_state
+            .send(ProcessWord {
+                word: "kissu".to_string(),
+                user_id: 43,
+                channel_id: 1,
+                message_id: 6,
+                in_dictionary: false,
+            })
+            .await
+            .unwrap();
+        game_state
+            .send(MarkWordValidity {
+                message_id: 6,
+                validity: Validity::Invalid,
+                source: VerdictSource::Admin { user_id: 7 },
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            next_events(),
+            vec![
+                GameEvent::GameReset { idle: false },
+                GameEvent::AdminOverride {
+                    message_id: 6,
+                    user_id: 43,
+                    word: "kissu".to_string(),
+                    valid: false,
+                    admin_id: 7
+                },
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_stopped_actors_are_reported() {
+        let api = RecordingReactionApi::default();
+        let message_reaction =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_admin_channel(serenity::ChannelId::new(2))
+                .start();
+        let dictionary = DictionaryValidator::from_words(["kissa"]);
+        let game_state =
+            GameStateActor::with_engine(GameEngine::from_config(dictionary, &Config::default()))
+                .start();
+
+        // The LLM validator is gone, so the unknown word can't be escalated
+        let llm_validator = start_stopped(LLMValidatorActor::with_validator(
+            LLMValidator::default(),
+            10,
+            86400,
+        ));
+        let validator =
+            WordValidatorActor::new(game_state, llm_validator.clone(), message_reaction.clone())
+                .start();
+        let send = |word: &str, message_id| {
+            validator.send(ValidateWordSync {
+                word: word.to_string(),
+                channel_id: 1,
+                message_id,
+                user_id: 42,
+            })
+        };
+
+        assert_eq!(
+            send("kissa", 1).await.unwrap().unwrap(),
+            WordVerdict::ValidInDictionary
+        );
+        let error = send("kissu", 2).await.unwrap().unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Failed to deliver proper noun check"),
+            "{}",
+            error
+        );
+
+        // So is the game state
+        let game_state = start_stopped(GameStateActor::new());
+        let validator =
+            WordValidatorActor::new(game_state, llm_validator, message_reaction.clone()).start();
+        let error = validator
+            .send(ValidateWordSync {
+                word: "kassa".to_string(),
+                channel_id: 1,
+                message_id: 3,
+                user_id: 42,
+            })
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("Failed to validate game rules"),
+            "{}",
+            error
+        );
+
+        // The first failure was posted to the admins, the second one rate-limited
+        message_reaction.send(Ping).await.unwrap();
+        let alerts = || -> Vec<String> {
+            api.calls()
+                .into_iter()
+                .filter_map(|call| match call {
+                    ReactionCall::Post(content) => Some(content),
+                    _ => None,
+                })
+                .collect()
+        };
+        for _ in 0..100 {
+            if !alerts().is_empty() {
+                break;
+            }
+            actix_rt::time::sleep(Duration::from_millis(10)).await;
+        }
+        let alerts = alerts();
+        assert_eq!(alerts.len(), 1, "{:#?}", alerts);
+        assert!(
+            alerts[0].contains("sanabotti::actor_error"),
+            "{}",
+            alerts[0]
+        );
+        assert!(alerts[0].contains("proper noun check"), "{}", alerts[0]);
+    }
+
+    #[actix_rt::test]
+    async fn test_duplicate_message() {
+        let api = RecordingReactionApi::default();
+        let validator = start_validator(&api);
+
+        assert_eq!(
+            validate(&validator, "kissa", 1).await,
+            WordVerdict::ValidInDictionary
+        );
+        // Without the check this would be rejected as already used
+        assert_eq!(
+            validate(&validator, "kissa", 1).await,
+            WordVerdict::Duplicate
+        );
+        assert_eq!(
+            validate(&validator, "kassa", 2).await,
+            WordVerdict::ValidInDictionary
+        );
+
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(1, EMOJI_CHECK),
+                ReactionCall::Add(2, EMOJI_CHECK),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_old_message_replayed() {
+        let api = RecordingReactionApi::default();
+        let validator = start_validator(&api);
+
+        assert_eq!(
+            validate(&validator, "kissa", 1).await,
+            WordVerdict::ValidInDictionary
+        );
+        for id in 2..RECENT_MESSAGES as u64 {
+            assert_eq!(
+                validate(&validator, "ki ssa", id).await,
+                WordVerdict::NotAWord
+            );
+        }
+        assert_eq!(
+            validate(&validator, "kissa", 1).await,
+            WordVerdict::Duplicate
+        );
+        assert_eq!(api.calls(), vec![ReactionCall::Add(1, EMOJI_CHECK)]);
+    }
+
+    #[test]
+    fn test_recent_messages_bounded() {
+        let mut recent = RecentMessages::new(2);
+        assert!(recent.insert(1));
+        assert!(recent.insert(2));
+        assert!(!recent.insert(1));
+
+        // 2 is now the least recently seen and makes room for 3
+        assert!(recent.insert(3));
+        assert!(!recent.insert(1));
+        assert!(recent.insert(2));
+        assert_eq!(recent.order.len(), 2);
+    }
+
+    #[actix_rt::test]
+    async fn test_reactions_in_message_channel() {
+        let api = RecordingReactionApi::default();
+        let validator = start_validator(&api);
+
+        // One word in the channel itself, the next in a thread under it
+        for (word, channel_id, message_id) in [("kissa", 1, 1), ("kassa", 5, 2)] {
+            let verdict = validator
+                .send(ValidateWordSync {
+                    word: word.to_string(),
+                    channel_id,
+                    message_id,
+                    user_id: 42,
+                })
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(verdict, WordVerdict::ValidInDictionary);
+        }
+
+        assert_eq!(api.call_channels(), vec![1, 5]);
+    }
+
+    #[actix_rt::test]
+    async fn test_daily_limit_explained() {
+        let api = RecordingReactionApi::default();
+        let validator = start_validator_with(
+            &api,
+            &Config {
+                max_words_per_user_per_day: 1,
+                daily_limit_reaction: '🚫',
+                ..Config::default()
+            },
+        );
+
+        assert_eq!(
+            validate(&validator, "kissa", 1).await,
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            validate(&validator, "kassa", 2).await,
+            WordVerdict::DailyLimitReached(1)
+        );
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(1, EMOJI_CHECK),
+                ReactionCall::Add(2, '🚫'),
+                ReactionCall::Reply(
+                    2,
+                    "🚫 Olet pelannut tänään jo 1 sanaa, jatka huomenna!".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_replies_in_english() {
+        let api = RecordingReactionApi::default();
+        let validator = start_validator_with(
+            &api,
+            &Config {
+                max_words_per_user_per_day: 1,
+                language: Language::English,
+                ..Config::default()
+            },
+        );
+
+        validate(&validator, "kissa", 1).await;
+        validate(&validator, "kassa", 2).await;
+        assert_eq!(
+            api.calls()[2],
+            ReactionCall::Reply(
+                2,
+                "⛔ You've already played 1 word today, continue tomorrow!".to_string()
+            )
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_foreign_letters_never_played() {
+        let api = RecordingReactionApi::default();
+        let validator = start_validator_with(
+            &api,
+            &Config {
+                alphabet_replies: true,
+                ..Config::default()
+            },
+        );
+
+        // "kissa" with a Cyrillic "с" in the middle
+        assert_eq!(
+            validate(&validator, "kiссa", 1).await,
+            WordVerdict::ForeignLetters(vec!['с'])
+        );
+        assert_eq!(
+            validate(&validator, "Кошка!", 2).await,
+            WordVerdict::ForeignLetters(vec!['к', 'о', 'ш', 'а'])
+        );
+        assert_eq!(
+            validate(&validator, "Kissa", 3).await,
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            validate(&validator, "kassa", 4).await,
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(1, EMOJI_CROSS),
+                ReactionCall::Reply(
+                    1,
+                    "❌ Sanassa on kirjaimia, joita pelissä ei käytetä: с".to_string()
+                ),
+                ReactionCall::Add(2, EMOJI_CROSS),
+                ReactionCall::Reply(
+                    2,
+                    "❌ Sanassa on kirjaimia, joita pelissä ei käytetä: к, о, ш, а".to_string()
+                ),
+                ReactionCall::Add(3, EMOJI_CHECK),
+                ReactionCall::Add(4, EMOJI_CHECK),
+            ]
+        );
+        assert_eq!(
+            validator
+                .send(DryRunWord {
+                    word: "kiссa".to_string(),
+                    user_id: None,
+                })
+                .await
+                .unwrap()
+                .unwrap(),
+            WordVerdict::ForeignLetters(vec!['с'])
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_cooldown_without_replies() {
+        let api = RecordingReactionApi::default();
+        let validator = start_validator_with(
+            &api,
+            &Config {
+                user_cooldown_secs: 60,
+                cooldown_reaction: '⌛',
+                limit_replies: false,
+                ..Config::default()
+            },
+        );
+
+        assert_eq!(
+            validate(&validator, "kissa", 1).await,
+            WordVerdict::ValidInDictionary
+        );
+        assert!(matches!(
+            validate(&validator, "kassa", 2).await,
+            WordVerdict::CoolingDown(_)
+        ));
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(1, EMOJI_CHECK),
+                ReactionCall::Add(2, '⌛'),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_out_of_turn() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RotationStore::new(dir.path().join("turns.json"));
+        let mut rotation = Rotation::default();
+        rotation.join(1);
+        rotation.join(2);
+        store.save(&rotation).unwrap();
+        let api = RecordingReactionApi::default();
+        let validator = start_validator_with(
+            &api,
+            &Config {
+                game_turn_mode: TurnMode::Rotation,
+                turn_order_path: store.path().to_string_lossy().into_owned(),
+                // Players are told whose turn it is regardless
+                limit_replies: false,
+                ..Config::default()
+            },
+        );
+
+        assert_eq!(
+            validate_as(&validator, "kissa", 1, 2).await,
+            WordVerdict::NotYourTurn(1)
+        );
+        assert_eq!(
+            validate_as(&validator, "kissa", 2, 1).await,
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            validate_as(&validator, "kassa", 3, 2).await,
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(1, EMOJI_NEXT_TRACK),
+                ReactionCall::Reply(1, "⏭️ Nyt on pelaajan <@1> vuoro.".to_string()),
+                ReactionCall::Add(2, EMOJI_CHECK),
+                ReactionCall::Add(3, EMOJI_CHECK),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_blocked_words_never_played() {
+        let api = RecordingReactionApi::default();
+        let completions = MockCompletionApi::responding("[]");
+        let llm = LLMValidator::with_api(Box::new(completions.clone()));
+        let game_state = GameStateActor::with_engine(GameEngine::new(
+            DictionaryValidator::from_words(["kissa", "kassa"]),
+            GameRules::default(),
+        ))
+        .start();
+        let message_reaction =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_reaction_interval(Duration::ZERO)
+                .start();
+        let validator = WordValidatorActor::new(
+            game_state.clone(),
+            LLMValidatorActor::with_validator(llm, 1, 86400).start(),
+            message_reaction,
+        )
+        .with_blocklist(Blocklist::from_words(["Kassa", "kissu"]))
+        .start();
+
+        assert_eq!(
+            validate(&validator, "kissa", 1).await,
+            WordVerdict::ValidInDictionary
+        );
+        // Blocked whatever the case, even though it's in the dictionary
+        assert_eq!(
+            validate(&validator, "KASSA!", 2).await,
+            WordVerdict::Blocked
+        );
+        // Blocked words that aren't in the dictionary never reach the LLM
+        assert_eq!(validate(&validator, "Kissu", 3).await, WordVerdict::Blocked);
+        assert_eq!(
+            validator
+                .send(DryRunWord {
+                    word: "kassa".to_string(),
+                    user_id: None,
+                })
+                .await
+                .unwrap()
+                .unwrap(),
+            WordVerdict::Blocked
+        );
+
+        actix_rt::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(completions.calls(), 0);
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(1, EMOJI_CHECK),
+                ReactionCall::Add(2, EMOJI_CROSS),
+                ReactionCall::Add(3, EMOJI_CROSS),
+            ]
+        );
+
+        // Neither word was registered, so the chain still ends in "kissa"
+        let history = game_state.send(GetHistory { limit: 10 }).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].word, "kissa");
+    }
+
+    #[actix_rt::test]
+    async fn test_queued_words_wait_for_verdict() {
+        let api = RecordingReactionApi::default();
+        let game_state = GameStateActor::with_engine(GameEngine::new(
+            DictionaryValidator::from_words(["kissa", "kassa"]),
+            GameRules {
+                pending_policy: PendingPolicy::Queue,
+                ..GameRules::default()
+            },
+        ))
+        .start();
+        let message_reaction =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_reaction_interval(Duration::ZERO)
+                .start();
+        // The batch never fills up, so the test settles the pending word itself
+        let llm_validator =
+            LLMValidatorActor::with_validator(LLMValidator::default(), 10, 86400).start();
+        let validator =
+            WordValidatorActor::new(game_state.clone(), llm_validator, message_reaction).start();
+
+        validate(&validator, "kissa", 1).await;
+        assert_eq!(
+            validate(&validator, "kissu", 2).await,
+            WordVerdict::PendingLlm
+        );
+        let queued = validator.send(ValidateWordSync {
+            word: "kassa".to_string(),
+            channel_id: 1,
+            message_id: 3,
+            user_id: 43,
         });
+        actix_rt::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(
+            api.calls().last(),
+            Some(&ReactionCall::Add(3, EMOJI_HOURGLASS))
+        );
 
-        // Don't block the actor system by waiting for the thread
-        std::mem::drop(handle);
-        debug!("Game rules validation thread for '{}' started", word);
+        // "kissu" is rejected, so "kassa" follows "kissa" instead
+        game_state
+            .send(MarkWordValidity {
+                message_id: 2,
+                validity: Validity::Invalid,
+                source: VerdictSource::Llm {
+                    explanation: "Ei erisnimi".to_string(),
+                    model: Some("gemini-pro".to_string()),
+                },
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            queued.await.unwrap().unwrap(),
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(1, EMOJI_CHECK),
+                ReactionCall::Add(2, EMOJI_QUESTION),
+                ReactionCall::Add(3, EMOJI_HOURGLASS),
+                ReactionCall::Delete(3, EMOJI_HOURGLASS),
+                ReactionCall::Add(3, EMOJI_CHECK),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_queued_words_free_their_slot() {
+        let api = RecordingReactionApi::default();
+        let game_state = GameStateActor::with_engine(GameEngine::new(
+            DictionaryValidator::from_words(["kissa", "kassa", "kassi"]),
+            GameRules {
+                pending_policy: PendingPolicy::Queue,
+                ..GameRules::default()
+            },
+        ))
+        .start();
+        let message_reaction =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_reaction_interval(Duration::ZERO)
+                .start();
+        let llm_validator =
+            LLMValidatorActor::with_validator(LLMValidator::default(), 10, 86400).start();
+        let validator =
+            WordValidatorActor::new(game_state.clone(), llm_validator, message_reaction)
+                .with_concurrency(1, 0)
+                .start();
+
+        validate(&validator, "kissa", 1).await;
+        validate(&validator, "kissu", 2).await;
+        // Both wait in the game's queue, the second one isn't kept waiting for the first
+        for (id, word) in [(3, "kassa"), (4, "kassi")] {
+            validator.do_send(ValidateWord {
+                word: word.to_string(),
+                channel_id: 1,
+                message_id: id,
+                user_id: id,
+            });
+        }
+        actix_rt::time::sleep(Duration::from_millis(50)).await;
+        assert!(api.calls().contains(&ReactionCall::Add(3, EMOJI_HOURGLASS)));
+        assert!(api.calls().contains(&ReactionCall::Add(4, EMOJI_HOURGLASS)));
+        assert_eq!(
+            validator.send(GetValidationLoad).await.unwrap(),
+            ValidationLoad::default()
+        );
+
+        // Once their turn comes they take a slot again, one at a time
+        game_state
+            .send(MarkWordValidity {
+                message_id: 2,
+                validity: Validity::Invalid,
+                source: VerdictSource::Llm {
+                    explanation: "Ei erisnimi".to_string(),
+                    model: None,
+                },
+            })
+            .await
+            .unwrap();
+        for _ in 0..100 {
+            if api.calls().contains(&ReactionCall::Add(4, EMOJI_CHECK)) {
+                break;
+            }
+            actix_rt::time::sleep(Duration::from_millis(20)).await;
+        }
+        let calls = api.calls();
+        assert!(calls.contains(&ReactionCall::Add(3, EMOJI_CHECK)));
+        assert!(calls.contains(&ReactionCall::Add(4, EMOJI_CHECK)));
+        actix_rt::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(
+            validator.send(GetValidationLoad).await.unwrap(),
+            ValidationLoad::default()
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_paused_words_ignored() {
+        let api = RecordingReactionApi::default();
+        let game_state = GameStateActor::with_engine(GameEngine::new(
+            DictionaryValidator::from_words(["kissa", "kassa"]),
+            GameRules::default(),
+        ))
+        .start();
+        let message_reaction =
+            MessageReactionActor::with_api(Arc::new(api.clone()), serenity::ChannelId::new(1))
+                .with_reaction_interval(Duration::ZERO)
+                .start();
+        let validator = WordValidatorActor::new(
+            game_state.clone(),
+            LLMValidatorActor::default().start(),
+            message_reaction,
+        )
+        .with_blocklist(Blocklist::from_words(["kassa"]))
+        .start();
+
+        game_state.send(SetPaused(true)).await.unwrap();
+        assert_eq!(validate(&validator, "kissa", 1).await, WordVerdict::Paused);
+        // Not even checked against the blocklist or the alphabet
+        assert_eq!(validate(&validator, "kassa", 2).await, WordVerdict::Paused);
+        assert_eq!(validate(&validator, "кошка", 3).await, WordVerdict::Paused);
+
+        game_state.send(SetPaused(false)).await.unwrap();
+        assert_eq!(
+            validate(&validator, "kissa", 4).await,
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(1, EMOJI_SLEEPING),
+                ReactionCall::Add(2, EMOJI_SLEEPING),
+                ReactionCall::Add(3, EMOJI_SLEEPING),
+                ReactionCall::Add(4, EMOJI_CHECK),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_remote_dictionary_before_llm() {
+        let api = RecordingReactionApi::default();
+        let lookup = MockLookupApi::knowing(&["kissu"]);
+        let remote = RemoteDictionary::new(Box::new(lookup.clone()), Duration::from_secs(1), 10);
+        let validator = validator_with_llm(&api, &Config::default(), LLMValidator::default(), 10)
+            .with_remote_dictionary(Arc::new(remote));
+        let game_state = validator.game_state.clone();
+        let validator = validator.start();
+
+        assert_eq!(
+            validate(&validator, "kissa", 1).await,
+            WordVerdict::ValidInDictionary
+        );
+        // Known remotely, so accepted without the ❓ and the LLM
+        assert_eq!(
+            validate(&validator, "kissu", 2).await,
+            WordVerdict::ValidInDictionary
+        );
+        // Unknown remotely, so the LLM decides as before
+        assert_eq!(
+            validate(&validator, "kassu", 3).await,
+            WordVerdict::PendingLlm
+        );
+
+        assert_eq!(
+            api.calls(),
+            vec![
+                ReactionCall::Add(1, EMOJI_CHECK),
+                ReactionCall::Add(2, EMOJI_CHECK),
+                ReactionCall::Add(3, EMOJI_QUESTION),
+            ]
+        );
+        // Dictionary words are never looked up
+        assert_eq!(lookup.lookups(), vec!["kissu", "kassu"]);
+        // Both dictionaries are recorded as such, the LLM hasn't answered yet
+        let history = game_state.send(GetHistory { limit: 3 }).await.unwrap();
+        let sources: Vec<_> = history.into_iter().map(|entry| entry.source).collect();
+        assert_eq!(
+            sources,
+            vec![
+                None,
+                Some(VerdictSource::Dictionary { file: None }),
+                Some(VerdictSource::Dictionary {
+                    file: Some("memory".into())
+                })
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_remote_dictionary_failure_falls_through() {
+        let api = RecordingReactionApi::default();
+        let remote = RemoteDictionary::new(
+            Box::new(MockLookupApi::failing()),
+            Duration::from_secs(1),
+            10,
+        );
+        let validator = validator_with_llm(&api, &Config::default(), LLMValidator::default(), 10)
+            .with_remote_dictionary(Arc::new(remote))
+            .start();
+
+        validate(&validator, "kissa", 1).await;
+        assert_eq!(
+            validate(&validator, "kissu", 2).await,
+            WordVerdict::PendingLlm
+        );
+        assert_eq!(api.calls()[1], ReactionCall::Add(2, EMOJI_QUESTION));
     }
 }
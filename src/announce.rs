@@ -0,0 +1,90 @@
+//! The state summary posted in the game channel when the bot starts
+//!
+//! When each channel was last announced in is kept in a file, so a bot
+//! restarting over and over doesn't fill the channel with announcements.
+
+use chrono::{DateTime, Duration, Utc};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::persist::write_atomic;
+
+/// When each game channel was last announced in, as a JSON object keyed by channel ID
+#[derive(Debug, Clone)]
+pub struct AnnouncementLog {
+    path: PathBuf,
+}
+
+impl AnnouncementLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// When the channel was last announced in, None if never
+    pub fn last(&self, channel_id: u64) -> Result<Option<DateTime<Utc>>> {
+        Ok(self.read()?.get(&channel_id.to_string()).copied())
+    }
+
+    /// Whether the channel may be announced in at `now`, at most once every `interval`
+    pub fn due(&self, channel_id: u64, now: DateTime<Utc>, interval: Duration) -> Result<bool> {
+        Ok(self
+            .last(channel_id)?
+            .is_none_or(|last| now - last >= interval))
+    }
+
+    /// Note that the channel was announced in at `at`
+    pub fn record(&self, channel_id: u64, at: DateTime<Utc>) -> Result<()> {
+        let mut announced = self.read()?;
+        announced.insert(channel_id.to_string(), at);
+        let content = serde_json::to_string_pretty(&announced).map_err(io::Error::other)?;
+        write_atomic(&self.path, content.as_bytes())?;
+        Ok(())
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, DateTime<Utc>>> {
+        match fs::read_to_string(&self.path) {
+            Ok(content) => Ok(serde_json::from_str(&content).map_err(io::Error::other)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    #[test]
+    fn test_announcement_interval() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AnnouncementLog::new(dir.path().join("data/announcements.json"));
+        let interval = Duration::hours(1);
+
+        // Nothing announced yet, and a missing file is no error
+        assert_eq!(log.last(1).unwrap(), None);
+        assert!(log.due(1, at("2025-01-13T12:00:00Z"), interval).unwrap());
+
+        log.record(1, at("2025-01-13T12:00:00Z")).unwrap();
+        assert!(!log.due(1, at("2025-01-13T12:59:59Z"), interval).unwrap());
+        assert!(log.due(1, at("2025-01-13T13:00:00Z"), interval).unwrap());
+        // Each channel has its own interval
+        assert!(log.due(2, at("2025-01-13T12:30:00Z"), interval).unwrap());
+
+        // Recording another channel keeps the first one
+        log.record(2, at("2025-01-13T12:30:00Z")).unwrap();
+        let reopened = AnnouncementLog::new(log.path());
+        assert_eq!(reopened.last(1).unwrap(), Some(at("2025-01-13T12:00:00Z")));
+        assert_eq!(reopened.last(2).unwrap(), Some(at("2025-01-13T12:30:00Z")));
+    }
+}
@@ -0,0 +1,459 @@
+//! Read-only HTTP API for showing the game elsewhere, e.g. on a community website
+//!
+//! `GET /api/state` tells the current word, the chain length and whether the
+//! game is paused, `GET /api/history?limit=N` the latest played words, newest
+//! first, and `GET /api/leaderboard?period=weekly&limit=N` the top players.
+//! With several servers the game is picked with `?guild=ID`.
+//!
+//! When a token is configured every request has to carry it as
+//! `Authorization: Bearer <token>`. When a website origin is configured the
+//! answers allow it to read them, and CORS preflight requests are answered
+//! without a token, as browsers don't send one with them.
+
+use actix::Addr;
+use axum::extract::{FromRequestParts, Query, Request, State};
+use axum::http::header::{self, HeaderMap, HeaderValue};
+use axum::http::request::Parts;
+use axum::http::{Method, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tracing::{error, info, warn};
+
+use crate::actors::game_state::{GetHistory, GetLeaderboard, GetRules, IsPaused};
+use crate::actors::GameStateActor;
+use crate::config::Config;
+use crate::error::Result;
+use crate::game::Validity;
+use crate::scoring::Period;
+use crate::Error;
+
+/// Words and players listed when the request doesn't say how many
+const DEFAULT_LIMIT: usize = 10;
+
+/// Most words or players listed at once
+const MAX_LIMIT: usize = 100;
+
+/// The games served and the access settings
+pub struct ApiState {
+    /// The game of each server, with None for the single game without servers
+    games: Vec<(Option<u64>, Addr<GameStateActor>)>,
+    /// Bearer token requests have to carry, None to allow anyone
+    token: Option<String>,
+    /// Origin allowed to read the answers in a browser
+    cors_origin: Option<HeaderValue>,
+}
+
+impl ApiState {
+    pub fn new(games: Vec<(Option<u64>, Addr<GameStateActor>)>, config: &Config) -> Self {
+        let cors_origin = match config.api_cors_origin.as_str() {
+            "" => None,
+            origin => HeaderValue::from_str(origin)
+                .inspect_err(|_| warn!("API_CORS_ORIGIN '{}' isn't a valid header", origin))
+                .ok(),
+        };
+        Self {
+            games,
+            token: Some(config.api_token.clone()).filter(|token| !token.is_empty()),
+            cors_origin,
+        }
+    }
+
+    /// Whether the request carries the token, if one is needed
+    fn authorized(&self, headers: &HeaderMap) -> bool {
+        let Some(token) = &self.token else {
+            return true;
+        };
+        headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|given| given.trim() == token)
+    }
+
+    /// The game asked for, or the only one if none was named
+    fn game(&self, guild: Option<u64>) -> std::result::Result<&Addr<GameStateActor>, Failure> {
+        match (guild, self.games.as_slice()) {
+            (None, [(_, game)]) => Ok(game),
+            (None, _) => Err(error_response(
+                StatusCode::BAD_REQUEST,
+                "several games are played, name one with ?guild=ID",
+            )),
+            (Some(guild), games) => games
+                .iter()
+                .find(|(guild_id, _)| *guild_id == Some(guild))
+                .map(|(_, game)| game)
+                .ok_or_else(|| error_response(StatusCode::NOT_FOUND, "no game in that guild")),
+        }
+    }
+}
+
+/// An error answer
+type Failure = (StatusCode, Json<ApiError>);
+
+/// Body of an error answer
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiError {
+    pub error: String,
+}
+
+/// Answer of `GET /api/state`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StateView {
+    pub current_word: Option<String>,
+    pub chain_length: usize,
+    pub paused: bool,
+}
+
+/// A played word in `GET /api/history`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub word: String,
+    /// A string, as Discord IDs don't fit in a JavaScript number
+    pub user_id: String,
+    /// `valid`, `invalid` or `pending`
+    pub verdict: String,
+    /// Whether the word is part of the current chain
+    pub in_chain: bool,
+    pub played_at: DateTime<Utc>,
+}
+
+/// A player in `GET /api/leaderboard`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub rank: usize,
+    /// A string, as Discord IDs don't fit in a JavaScript number
+    pub user_id: String,
+    pub points: u64,
+    pub words: usize,
+}
+
+/// The query string, refused with an [`ApiError`] like the other errors
+struct ApiQuery<T>(T);
+
+impl<T, S> FromRequestParts<S> for ApiQuery<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Failure;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S,
+    ) -> std::result::Result<Self, Failure> {
+        match Query::<T>::from_request_parts(parts, state).await {
+            Ok(Query(query)) => Ok(Self(query)),
+            Err(rejection) => Err(error_response(rejection.status(), &rejection.body_text())),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GameQuery {
+    guild: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HistoryQuery {
+    guild: Option<u64>,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LeaderboardQuery {
+    guild: Option<u64>,
+    limit: Option<usize>,
+    #[serde(default)]
+    period: Period,
+}
+
+/// Bind the API's address, so an address that's taken fails at startup
+pub async fn bind(addr: &str) -> Result<TcpListener> {
+    let addr: SocketAddr = addr
+        .parse()
+        .map_err(|e| Error::Config(format!("Invalid API_ADDR '{}': {}", addr, e)))?;
+    let listener = TcpListener::bind(addr).await?;
+    info!("API listening on {}", listener.local_addr()?);
+    Ok(listener)
+}
+
+/// Serve the API until `shutdown` completes
+pub async fn serve(
+    listener: TcpListener,
+    state: Arc<ApiState>,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) {
+    let app = Router::new()
+        .route("/api/state", get(state_handler))
+        .route("/api/history", get(history_handler))
+        .route("/api/leaderboard", get(leaderboard_handler))
+        .layer(middleware::from_fn_with_state(state.clone(), access))
+        .with_state(state);
+
+    match axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown)
+        .await
+    {
+        Ok(()) => info!("API stopped"),
+        Err(e) => error!("API stopped: {}", e),
+    }
+}
+
+/// Check the token and add the CORS headers
+async fn access(State(state): State<Arc<ApiState>>, request: Request, next: Next) -> Response {
+    let mut response = if request.method() == Method::OPTIONS {
+        StatusCode::NO_CONTENT.into_response()
+    } else if !state.authorized(request.headers()) {
+        error_response(StatusCode::UNAUTHORIZED, "missing or wrong bearer token").into_response()
+    } else {
+        next.run(request).await
+    };
+
+    if let Some(origin) = &state.cors_origin {
+        let headers = response.headers_mut();
+        headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin.clone());
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_METHODS,
+            HeaderValue::from_static("GET, OPTIONS"),
+        );
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_HEADERS,
+            HeaderValue::from_static("Authorization"),
+        );
+    }
+    response
+}
+
+async fn state_handler(
+    State(state): State<Arc<ApiState>>,
+    ApiQuery(query): ApiQuery<GameQuery>,
+) -> std::result::Result<Json<StateView>, Failure> {
+    let game = state.game(query.guild)?;
+    let rules = game.send(GetRules).await.map_err(unavailable)?;
+    let paused = game.send(IsPaused).await.map_err(unavailable)?;
+    Ok(Json(StateView {
+        current_word: rules.current_word,
+        chain_length: rules.chain_length,
+        paused,
+    }))
+}
+
+async fn history_handler(
+    State(state): State<Arc<ApiState>>,
+    ApiQuery(query): ApiQuery<HistoryQuery>,
+) -> std::result::Result<Json<Vec<HistoryEntry>>, Failure> {
+    let game = state.game(query.guild)?;
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    let entries = game.send(GetHistory { limit }).await.map_err(unavailable)?;
+    Ok(Json(
+        entries
+            .into_iter()
+            .map(|entry| HistoryEntry {
+                word: entry.word,
+                user_id: entry.user_id.to_string(),
+                verdict: match entry.validity {
+                    Validity::Valid => "valid",
+                    Validity::Invalid => "invalid",
+                    Validity::Pending => "pending",
+                }
+                .to_string(),
+                in_chain: entry.in_chain,
+                played_at: entry.played_at.into(),
+            })
+            .collect(),
+    ))
+}
+
+async fn leaderboard_handler(
+    State(state): State<Arc<ApiState>>,
+    ApiQuery(query): ApiQuery<LeaderboardQuery>,
+) -> std::result::Result<Json<Vec<LeaderboardEntry>>, Failure> {
+    let game = state.game(query.guild)?;
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    let leaders = game
+        .send(GetLeaderboard {
+            period: query.period,
+            limit,
+        })
+        .await
+        .map_err(unavailable)?;
+    Ok(Json(
+        leaders
+            .into_iter()
+            .enumerate()
+            .map(|(index, (user_id, score))| LeaderboardEntry {
+                rank: index + 1,
+                user_id: user_id.to_string(),
+                points: score.points,
+                words: score.words,
+            })
+            .collect(),
+    ))
+}
+
+fn error_response(status: StatusCode, error: &str) -> Failure {
+    let body = ApiError {
+        error: error.to_string(),
+    };
+    (status, Json(body))
+}
+
+/// 503 for a game actor that didn't answer
+fn unavailable(e: actix::MailboxError) -> Failure {
+    warn!("Game state actor didn't answer an API request: {}", e);
+    error_response(StatusCode::SERVICE_UNAVAILABLE, "the game isn't available")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::game_state::SubmitWord;
+    use crate::game::GameEngine;
+    use crate::validation::dictionary::DictionaryValidator;
+    use crate::validation::rules::GameRules;
+    use actix::Actor;
+    use tokio::sync::oneshot;
+    use tracing::Span;
+
+    /// Serve a game with a couple of words played on an ephemeral port
+    async fn start(config: &Config) -> (String, oneshot::Sender<()>) {
+        let engine = GameEngine::new(
+            DictionaryValidator::from_words(["kissa", "kassa"]),
+            GameRules::default(),
+        );
+        let game_state = GameStateActor::with_engine(engine).start();
+        for (message_id, word) in [(1, "kissa"), (2, "kassa")] {
+            game_state
+                .send(SubmitWord {
+                    content: word.to_string(),
+                    user_id: 123456789012345678,
+                    channel_id: 1,
+                    message_id,
+                    span: Span::none(),
+                })
+                .await
+                .unwrap();
+        }
+
+        let listener = bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let state = Arc::new(ApiState::new(vec![(None, game_state)], config));
+        let (exit_tx, exit_rx) = oneshot::channel::<()>();
+        tokio::spawn(serve(listener, state, async {
+            let _ = exit_rx.await;
+        }));
+        (url, exit_tx)
+    }
+
+    #[actix_rt::test]
+    async fn test_endpoints() {
+        let (url, _exit) = start(&Config::default()).await;
+        let client = reqwest::Client::new();
+        let get = |path: &str| client.get(format!("{}{}", url, path)).send();
+
+        let state: serde_json::Value = get("/api/state").await.unwrap().json().await.unwrap();
+        assert_eq!(
+            state,
+            serde_json::json!({"current_word": "kassa", "chain_length": 2, "paused": false})
+        );
+
+        let response = get("/api/history?limit=1").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let history: Vec<HistoryEntry> = response.json().await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].word, "kassa");
+        assert_eq!(history[0].user_id, "123456789012345678");
+        assert_eq!(history[0].verdict, "valid");
+        assert!(history[0].in_chain);
+
+        let leaders: serde_json::Value = get("/api/leaderboard?period=weekly")
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let leaders = leaders.as_array().unwrap();
+        assert_eq!(leaders.len(), 1);
+        assert_eq!(leaders[0]["rank"], 1);
+        assert_eq!(leaders[0]["user_id"], "123456789012345678");
+        assert_eq!(leaders[0]["words"], 2);
+        assert!(leaders[0]["points"].as_u64().unwrap() > 0);
+
+        let response = get("/api/leaderboard?period=monthly").await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let response = get("/api/history?limit=abc").await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let error: ApiError = response.json().await.unwrap();
+        assert!(error.error.contains("limit"), "{}", error.error);
+        let response = get("/api/state?guild=1").await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let error: ApiError = response.json().await.unwrap();
+        assert_eq!(error.error, "no game in that guild");
+        // No CORS headers unless an origin is configured
+        let response = get("/api/state").await.unwrap();
+        assert!(response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .is_none());
+    }
+
+    #[actix_rt::test]
+    async fn test_token_and_cors() {
+        let config = Config {
+            api_token: "sekret".to_string(),
+            api_cors_origin: "https://sanaketju.fi".to_string(),
+            ..Config::default()
+        };
+        let (url, exit) = start(&config).await;
+        let client = reqwest::Client::new();
+        let state_url = format!("{}/api/state", url);
+
+        let response = client.get(&state_url).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        // Browsers can read the refusal too
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://sanaketju.fi"
+        );
+        let response = client
+            .get(&state_url)
+            .bearer_auth("wrong")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = client
+            .get(&state_url)
+            .bearer_auth("sekret")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Preflight requests don't carry the token
+        let response = client
+            .request(Method::OPTIONS, &state_url)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_HEADERS],
+            "Authorization"
+        );
+
+        // The server stops when told to
+        exit.send(()).unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert!(client.get(&state_url).send().await.is_err());
+    }
+}
@@ -0,0 +1,151 @@
+//! What happened to each recently played word, for /why
+//!
+//! Each actor handling a word notes down its step under the word's message
+//! ID. Only the most recent messages are kept, in memory.
+
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use crate::game::{Validity, VerdictSource, WordVerdict};
+
+/// One step in handling a played word
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuditStep {
+    /// The message reached the word validator
+    Received {
+        user_id: u64,
+    },
+    /// The word picked out of the message, None if there was no single word
+    Normalized(Option<String>),
+    /// Whether the word was found in the dictionary
+    Dictionary(bool),
+    /// What the game made of the word
+    Verdict(WordVerdict),
+    /// Sent to the LLM, counting from 1
+    SentToLlm {
+        attempt: u32,
+    },
+    /// Not sent to the LLM since the daily budget was used up
+    OverBudget,
+    /// Not sent to the LLM since it had waited too long
+    Expired,
+    /// The word's final verdict, or a verdict overturned later
+    Settled {
+        validity: Validity,
+        source: VerdictSource,
+    },
+    /// A reaction added to the message, and whether Discord accepted it
+    ReactionAdded {
+        reaction: char,
+        ok: bool,
+    },
+    ReactionRemoved {
+        reaction: char,
+        ok: bool,
+    },
+    ReactionsCleared {
+        ok: bool,
+    },
+}
+
+/// A step and when it happened
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEvent {
+    pub at: DateTime<Utc>,
+    pub step: AuditStep,
+}
+
+#[derive(Debug, Default)]
+struct Trails {
+    steps: HashMap<u64, Vec<AuditEvent>>,
+    /// Message IDs in the order they were first seen
+    order: VecDeque<u64>,
+}
+
+/// The steps of the most recent `capacity` messages, shared by every actor
+#[derive(Debug)]
+pub struct AuditLog {
+    capacity: usize,
+    trails: Mutex<Trails>,
+}
+
+impl AuditLog {
+    /// Keep the steps of `capacity` messages, nothing at all if it's 0
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            trails: Mutex::default(),
+        }
+    }
+
+    /// Note down a step for the message, forgetting the oldest message if there are too many
+    pub fn record(&self, message_id: u64, step: AuditStep) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut trails = self.trails.lock().unwrap();
+        let event = AuditEvent {
+            at: Utc::now(),
+            step,
+        };
+        if let Some(steps) = trails.steps.get_mut(&message_id) {
+            steps.push(event);
+            return;
+        }
+        trails.steps.insert(message_id, vec![event]);
+        trails.order.push_back(message_id);
+        while trails.order.len() > self.capacity {
+            if let Some(oldest) = trails.order.pop_front() {
+                trails.steps.remove(&oldest);
+            }
+        }
+    }
+
+    /// The steps noted down for the message, oldest first
+    pub fn trail(&self, message_id: u64) -> Vec<AuditEvent> {
+        self.trails
+            .lock()
+            .unwrap()
+            .steps
+            .get(&message_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Who played the word in the message, if the trail shows it
+    pub fn player(&self, message_id: u64) -> Option<u64> {
+        self.trail(message_id)
+            .iter()
+            .find_map(|event| match event.step {
+                AuditStep::Received { user_id } => Some(user_id),
+                _ => None,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trails_capped() {
+        let log = AuditLog::new(2);
+        log.record(1, AuditStep::Received { user_id: 42 });
+        log.record(2, AuditStep::Received { user_id: 43 });
+        log.record(1, AuditStep::Dictionary(true));
+        assert_eq!(log.trail(1).len(), 2);
+        assert_eq!(log.player(2), Some(43));
+
+        // A third message pushes out the one seen first
+        log.record(3, AuditStep::Normalized(None));
+        assert!(log.trail(1).is_empty());
+        assert_eq!(log.player(1), None);
+        assert_eq!(log.trail(3)[0].step, AuditStep::Normalized(None));
+
+        // Nothing is kept without a capacity
+        let off = AuditLog::new(0);
+        off.record(1, AuditStep::Received { user_id: 42 });
+        assert!(off.trail(1).is_empty());
+    }
+}
@@ -0,0 +1,152 @@
+//! The cap on how many words are sent to the LLM a day
+//!
+//! Every word sent counts for the 24 hours after it was sent, so the budget
+//! frees up gradually instead of all at once at midnight. When the words were
+//! sent is kept in a file, so a restarted bot doesn't start from zero.
+
+use chrono::{DateTime, Duration, Utc};
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use crate::error::Result;
+use crate::persist::write_atomic;
+
+/// How long a word sent to the LLM counts against the budget
+pub const BUDGET_WINDOW: Duration = Duration::hours(24);
+
+/// How much of the budget has been used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetStatus {
+    /// Words sent within the last 24 hours
+    pub used: usize,
+    pub limit: usize,
+}
+
+impl BudgetStatus {
+    pub fn exhausted(&self) -> bool {
+        self.used >= self.limit
+    }
+}
+
+/// The words sent to the LLM within the last 24 hours, at most `limit` of them
+#[derive(Debug)]
+pub struct LlmBudget {
+    limit: usize,
+    /// When each word was sent, oldest first
+    sent: VecDeque<DateTime<Utc>>,
+    /// None to keep the count in memory only
+    path: Option<PathBuf>,
+}
+
+impl LlmBudget {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            sent: VecDeque::new(),
+            path: None,
+        }
+    }
+
+    /// Keep the count in `path`, picking up where a previous run left off
+    pub fn with_store(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        match read(&path) {
+            Ok(sent) => self.sent = sent,
+            Err(e) => warn!(
+                path = %path.display(),
+                error = %e,
+                "Failed to read the LLM budget, counting from zero"
+            ),
+        }
+        self.path = Some(path);
+        self
+    }
+
+    /// How much of the budget has been used at `now`
+    pub fn status(&self, now: DateTime<Utc>) -> BudgetStatus {
+        BudgetStatus {
+            used: self
+                .sent
+                .iter()
+                .filter(|&&sent| now - sent < BUDGET_WINDOW)
+                .count(),
+            limit: self.limit,
+        }
+    }
+
+    /// Spend the budget on up to `words` words at `now`, returning how many it covers
+    pub fn spend(&mut self, words: usize, now: DateTime<Utc>) -> usize {
+        while self
+            .sent
+            .front()
+            .is_some_and(|&sent| now - sent >= BUDGET_WINDOW)
+        {
+            self.sent.pop_front();
+        }
+        let granted = words.min(self.limit.saturating_sub(self.sent.len()));
+        if granted == 0 {
+            return 0;
+        }
+        self.sent.extend(std::iter::repeat_n(now, granted));
+        if let Some(path) = &self.path {
+            if let Err(e) = write(path, &self.sent) {
+                warn!(
+                    path = %path.display(),
+                    error = %e,
+                    "Failed to save the LLM budget"
+                );
+            }
+        }
+        granted
+    }
+}
+
+fn read(path: &Path) -> Result<VecDeque<DateTime<Utc>>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(serde_json::from_str(&content).map_err(io::Error::other)?),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(VecDeque::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn write(path: &Path, sent: &VecDeque<DateTime<Utc>>) -> Result<()> {
+    let content = serde_json::to_string(sent).map_err(io::Error::other)?;
+    write_atomic(path, content.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    #[test]
+    fn test_rolling_budget() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data/llm_budget.json");
+        let mut budget = LlmBudget::new(3).with_store(&path);
+
+        assert_eq!(budget.spend(2, at("2025-01-13T12:00:00Z")), 2);
+        // Only part of a batch fits
+        assert_eq!(budget.spend(2, at("2025-01-13T18:00:00Z")), 1);
+        assert_eq!(budget.spend(1, at("2025-01-13T20:00:00Z")), 0);
+        let status = budget.status(at("2025-01-13T20:00:00Z"));
+        assert_eq!(status, BudgetStatus { used: 3, limit: 3 });
+        assert!(status.exhausted());
+
+        // The count survives a restart
+        let mut reopened = LlmBudget::new(3).with_store(&path);
+        assert_eq!(reopened.status(at("2025-01-13T20:00:00Z")).used, 3);
+
+        // The first two words free up a day after they were sent, the third later
+        assert_eq!(reopened.status(at("2025-01-14T12:00:00Z")).used, 1);
+        assert_eq!(reopened.spend(3, at("2025-01-14T12:00:00Z")), 2);
+        assert_eq!(reopened.spend(1, at("2025-01-14T18:00:00Z")), 1);
+    }
+}
@@ -0,0 +1,357 @@
+//! Checking a word list against the dictionary, e.g. before importing one from the community
+//!
+//! The list is attached to `/dict bulkcheck` with a word on each line. It's
+//! checked as it's downloaded, a chunk at a time on a blocking thread, so a
+//! large list neither sits in memory nor holds up the bot. Lines that aren't a
+//! single word of the allowed letters are counted as malformed, and with the
+//! plausibility filter the missing words that don't look like words are
+//! listed apart from the rest.
+
+use std::collections::HashSet;
+use std::io;
+use std::mem;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use crate::config::MultiWordPolicy;
+use crate::error::{Error, Result};
+use crate::validation::dictionary::DictionaryValidator;
+use crate::validation::extract::{extract_word, ExtractOptions};
+use crate::validation::normalize::normalize_word;
+use crate::validation::plausibility::PlausibilityFilter;
+
+/// Name of the report file
+pub const REPORT_FILENAME: &str = "sanabotti_bulkcheck.txt";
+
+/// Longest line read as a word, longer ones are malformed without being kept in memory
+const MAX_LINE_BYTES: usize = 256;
+
+/// Chunks downloaded ahead of the checking
+const CHUNKS_AHEAD: usize = 4;
+
+/// How long to wait for Discord's file server to accept the connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long the download may stall before the check is given up
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// What the dictionary says about a word list
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BulkCheck {
+    /// Words in the dictionary
+    pub found: usize,
+    /// Words not in the dictionary, in the order they're listed
+    pub missing: Vec<String>,
+    /// Words not in the dictionary that don't look like words, with the plausibility filter
+    pub implausible: Vec<String>,
+    /// Lines that aren't a single word
+    pub malformed: usize,
+    /// Words listed again, counted only the first time
+    pub duplicates: usize,
+}
+
+/// How a check of a downloaded list went
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkCheckOutcome {
+    Checked(BulkCheck),
+    /// The list is larger than allowed, so it wasn't checked
+    TooLarge,
+}
+
+/// Checks a list fed to it in chunks of any size
+pub struct BulkChecker {
+    dictionary: Arc<DictionaryValidator>,
+    extract_options: ExtractOptions,
+    plausibility: Option<PlausibilityFilter>,
+    /// The line read so far
+    line: Vec<u8>,
+    /// Whether the line has grown too long to be a word
+    overlong: bool,
+    seen: HashSet<String>,
+    check: BulkCheck,
+}
+
+impl BulkChecker {
+    /// Check words as the game would extract them, without letting a line hold several
+    pub fn new(
+        dictionary: Arc<DictionaryValidator>,
+        extract_options: ExtractOptions,
+        plausibility: Option<PlausibilityFilter>,
+    ) -> Self {
+        Self {
+            dictionary,
+            extract_options: ExtractOptions {
+                multi_word_policy: MultiWordPolicy::Ignore,
+                ..extract_options
+            },
+            plausibility,
+            line: Vec::new(),
+            overlong: false,
+            seen: HashSet::new(),
+            check: BulkCheck::default(),
+        }
+    }
+
+    /// Check the lines the chunk completes, keeping the rest for the next one
+    pub fn feed(&mut self, mut chunk: &[u8]) {
+        while let Some(end) = chunk.iter().position(|&byte| byte == b'\n') {
+            self.push(&chunk[..end]);
+            self.end_line();
+            chunk = &chunk[end + 1..];
+        }
+        self.push(chunk);
+    }
+
+    /// Check the last line, if the list didn't end in a line break
+    pub fn finish(mut self) -> BulkCheck {
+        if !self.line.is_empty() || self.overlong {
+            self.end_line();
+        }
+        self.check
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        if self.overlong {
+            return;
+        }
+        if self.line.len() + bytes.len() > MAX_LINE_BYTES {
+            self.overlong = true;
+            self.line.clear();
+        } else {
+            self.line.extend_from_slice(bytes);
+        }
+    }
+
+    fn end_line(&mut self) {
+        let line = mem::take(&mut self.line);
+        if mem::take(&mut self.overlong) {
+            self.check.malformed += 1;
+            return;
+        }
+        // A multi-byte character split between chunks is whole again by the end of the line
+        match std::str::from_utf8(&line) {
+            Ok(line) => self.check_line(line),
+            Err(_) => self.check.malformed += 1,
+        }
+    }
+
+    fn check_line(&mut self, line: &str) {
+        if line.trim().is_empty() {
+            return;
+        }
+        let word = match extract_word(line, &self.extract_options) {
+            Some(word) if self.extract_options.foreign_letters(&word).is_empty() => {
+                normalize_word(&word)
+            }
+            _ => {
+                self.check.malformed += 1;
+                return;
+            }
+        };
+        if self.seen.contains(&word) {
+            self.check.duplicates += 1;
+            return;
+        }
+        self.seen.insert(word.clone());
+
+        if self.dictionary.is_valid_word(&word) {
+            self.check.found += 1;
+        } else if self
+            .plausibility
+            .is_some_and(|filter| filter.check(&word, &self.dictionary).is_err())
+        {
+            self.check.implausible.push(word);
+        } else {
+            self.check.missing.push(word);
+        }
+    }
+}
+
+/// A client for [`check_download`] that gives up on a stalled download
+pub fn download_client() -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .read_timeout(READ_TIMEOUT)
+        .build()
+        .map_err(download_error)
+}
+
+/// Download a list and check it, giving up once it's more than `max_bytes`
+///
+/// The checking runs on a blocking thread, fed the chunks as they arrive.
+pub async fn check_download(
+    client: &reqwest::Client,
+    url: &str,
+    max_bytes: usize,
+    mut checker: BulkChecker,
+) -> Result<BulkCheckOutcome> {
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(download_error)?;
+    if response
+        .content_length()
+        .is_some_and(|length| length > max_bytes as u64)
+    {
+        return Ok(BulkCheckOutcome::TooLarge);
+    }
+
+    let (chunks, mut received) = mpsc::channel::<Vec<u8>>(CHUNKS_AHEAD);
+    let checking = tokio::task::spawn_blocking(move || {
+        while let Some(chunk) = received.blocking_recv() {
+            checker.feed(&chunk);
+        }
+        checker.finish()
+    });
+
+    let downloaded = async {
+        let mut read = 0;
+        while let Some(chunk) = response.chunk().await.map_err(download_error)? {
+            // The reported size can't be relied on, so it's counted too
+            read += chunk.len();
+            if read > max_bytes {
+                return Ok(false);
+            }
+            if chunks.send(chunk.into()).await.is_err() {
+                break;
+            }
+        }
+        Ok::<_, Error>(true)
+    }
+    .await;
+    drop(chunks);
+
+    let check = checking
+        .await
+        .map_err(|e| Error::TaskPanic(e.to_string()))?;
+    Ok(if downloaded? {
+        BulkCheckOutcome::Checked(check)
+    } else {
+        BulkCheckOutcome::TooLarge
+    })
+}
+
+fn download_error(error: reqwest::Error) -> Error {
+    io::Error::other(format!("Failed to download the word list: {}", error)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use axum::Router;
+    use tokio::net::TcpListener;
+
+    const LIST: &str = "kissa\nKoira\r\nasdfgh\n\nkissa\nkaksi sanaa\nx1\nPori\nkoira\n";
+
+    fn checker(plausibility: bool) -> BulkChecker {
+        let dictionary = DictionaryValidator::from_words(["kissa", "koira", "kassa"]);
+        let plausibility = plausibility.then_some(PlausibilityFilter {
+            max_consonants: 4,
+            max_repeats: 3,
+            min_known_trigrams: 0,
+        });
+        BulkChecker::new(
+            Arc::new(dictionary),
+            ExtractOptions::default(),
+            plausibility,
+        )
+    }
+
+    /// Serve LIST on a free local port, returning its URL
+    async fn serve() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/list.txt", listener.local_addr().unwrap());
+        let app = Router::new().route("/list.txt", get(|| async { LIST }));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        url
+    }
+
+    #[test]
+    fn test_check() {
+        let mut unfiltered = checker(false);
+        unfiltered.feed(LIST.as_bytes());
+        assert_eq!(
+            unfiltered.finish(),
+            BulkCheck {
+                found: 2,
+                missing: vec!["asdfgh".to_string(), "pori".to_string()],
+                implausible: Vec::new(),
+                malformed: 2,
+                duplicates: 2,
+            }
+        );
+
+        // With the filter, keyboard mash is told apart from the words worth adding
+        let mut filtered = checker(true);
+        filtered.feed(LIST.as_bytes());
+        let check = filtered.finish();
+        assert_eq!(check.missing, ["pori"]);
+        assert_eq!(check.implausible, ["asdfgh"]);
+    }
+
+    #[test]
+    fn test_chunks_split_anywhere() {
+        let list = "kissa\nhäkki\nkassa";
+        let mut whole = checker(false);
+        whole.feed(list.as_bytes());
+        let whole = whole.finish();
+        assert_eq!(whole.found, 2);
+        assert_eq!(whole.missing, ["häkki"]);
+
+        // Even in the middle of the ä, and without a line break at the end
+        for size in 1..list.len() {
+            let mut split = checker(false);
+            for chunk in list.as_bytes().chunks(size) {
+                split.feed(chunk);
+            }
+            assert_eq!(split.finish(), whole, "chunks of {} bytes", size);
+        }
+    }
+
+    #[test]
+    fn test_long_lines_malformed() {
+        let mut checker = checker(false);
+        let long = "a".repeat(MAX_LINE_BYTES * 2);
+        for chunk in long.as_bytes().chunks(100) {
+            checker.feed(chunk);
+        }
+        checker.feed(b"\nkissa\n");
+        checker.feed(&[0xff, b'\n']);
+        let check = checker.finish();
+        assert_eq!(check.malformed, 2);
+        assert_eq!(check.found, 1);
+    }
+
+    #[actix_rt::test]
+    async fn test_check_download() {
+        let url = serve().await;
+        let client = download_client().unwrap();
+
+        let outcome = check_download(&client, &url, LIST.len(), checker(false))
+            .await
+            .unwrap();
+        match outcome {
+            BulkCheckOutcome::Checked(check) => {
+                assert_eq!(check.found, 2);
+                assert_eq!(check.missing, ["asdfgh", "pori"]);
+            }
+            other => panic!("expected the list to be checked, got {:?}", other),
+        }
+
+        let outcome = check_download(&client, &url, LIST.len() - 1, checker(false))
+            .await
+            .unwrap();
+        assert_eq!(outcome, BulkCheckOutcome::TooLarge);
+
+        let missing = url.replace("list.txt", "missing.txt");
+        assert!(
+            check_download(&client, &missing, LIST.len(), checker(false))
+                .await
+                .is_err()
+        );
+    }
+}
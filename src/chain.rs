@@ -0,0 +1,183 @@
+//! The current chain's tallies, summed up when a rejected word breaks it
+//!
+//! The game's history only keeps the latest words, so who played how many
+//! words, the longest word and when the chain started are counted as words
+//! are accepted, and cleared whenever a new chain starts.
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::config::Config;
+
+/// When a rejected word breaks the chain, and what happens then
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainBreaks {
+    /// Accepted words the chain needs before a rejected word breaks it
+    pub min_length: usize,
+    /// Whether words breaking a rule break the chain too, not only words that aren't words
+    pub violations: bool,
+    /// Whether a new chain starts right away, or the game waits for /reset
+    pub restart: bool,
+}
+
+impl ChainBreaks {
+    /// The chain breaks in the configuration, None if chains never break
+    pub fn from_config(config: &Config) -> Option<Self> {
+        (config.chain_break_length != 0).then_some(Self {
+            min_length: config.chain_break_length,
+            violations: config.violations_break_chain,
+            restart: config.restart_after_break,
+        })
+    }
+}
+
+/// What has been counted of the current chain
+#[derive(Debug, Clone, Default)]
+pub struct ChainTally {
+    /// When the first word was accepted, None before it
+    started: Option<DateTime<Utc>>,
+    length: usize,
+    /// Accepted words per player
+    contributors: HashMap<u64, usize>,
+    /// The first of the longest words; kept if it's rolled back, as the
+    /// chain no longer knows what came second
+    longest_word: Option<String>,
+}
+
+impl ChainTally {
+    /// Count a word accepted into the chain
+    pub fn add(&mut self, user_id: u64, word: &str, at: DateTime<Utc>) {
+        self.started.get_or_insert(at);
+        self.length += 1;
+        *self.contributors.entry(user_id).or_default() += 1;
+        let longer = self
+            .longest_word
+            .as_ref()
+            .is_none_or(|longest| word.chars().count() > longest.chars().count());
+        if longer {
+            self.longest_word = Some(word.to_string());
+        }
+    }
+
+    /// Take back a word that was rolled back out of the chain
+    pub fn remove(&mut self, user_id: u64) {
+        let Some(count) = self.contributors.get_mut(&user_id) else {
+            return;
+        };
+        *count -= 1;
+        if *count == 0 {
+            self.contributors.remove(&user_id);
+        }
+        self.length -= 1;
+    }
+
+    /// Accepted words in the chain
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// The chain summed up, as `word` played by `user_id` broke it at `ended`
+    pub fn summary(
+        &self,
+        word: &str,
+        user_id: u64,
+        ended: DateTime<Utc>,
+        restart: bool,
+    ) -> ChainSummary {
+        // Ties go to the lowest ID, so the summary doesn't depend on the map's order
+        let top_contributor = self
+            .contributors
+            .iter()
+            .map(|(&user_id, &words)| (user_id, words))
+            .max_by_key(|&(user_id, words)| (words, std::cmp::Reverse(user_id)));
+        let duration = self
+            .started
+            .and_then(|started| (ended - started).to_std().ok())
+            .unwrap_or_default();
+        ChainSummary {
+            length: self.length,
+            top_contributor,
+            longest_word: self.longest_word.clone(),
+            duration,
+            breaking_word: word.to_string(),
+            broken_by: user_id,
+            restart,
+        }
+    }
+}
+
+/// A broken chain, as posted in the game channel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainSummary {
+    /// Accepted words in the chain
+    pub length: usize,
+    /// The player with the most words and how many they played
+    pub top_contributor: Option<(u64, usize)>,
+    pub longest_word: Option<String>,
+    /// From the first accepted word to the breaking one
+    pub duration: Duration,
+    pub breaking_word: String,
+    pub broken_by: u64,
+    /// Whether a new chain started right away, rather than waiting for /reset
+    pub restart: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339).unwrap().into()
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut tally = ChainTally::default();
+        assert!(tally.is_empty());
+        tally.add(2, "kissa", at("2025-01-13T12:00:00Z"));
+        tally.add(1, "kassa", at("2025-01-13T12:05:00Z"));
+        tally.add(2, "kassit", at("2025-01-13T12:30:00Z"));
+        tally.add(1, "kasvit", at("2025-01-13T13:00:00Z"));
+
+        assert_eq!(
+            tally.summary("kasvix", 3, at("2025-01-13T14:15:30Z"), true),
+            ChainSummary {
+                length: 4,
+                top_contributor: Some((1, 2)),
+                longest_word: Some("kassit".to_string()),
+                duration: Duration::from_secs(2 * 3600 + 15 * 60 + 30),
+                breaking_word: "kasvix".to_string(),
+                broken_by: 3,
+                restart: true,
+            }
+        );
+
+        // Rolled back words no longer count
+        tally.remove(1);
+        tally.remove(4);
+        let summary = tally.summary("kasvix", 3, at("2025-01-13T14:15:30Z"), false);
+        assert_eq!(summary.length, 3);
+        assert_eq!(summary.top_contributor, Some((2, 2)));
+    }
+
+    #[test]
+    fn test_from_config() {
+        let mut config = Config::default();
+        assert_eq!(ChainBreaks::from_config(&config), None);
+        config.chain_break_length = 10;
+        config.violations_break_chain = true;
+        assert_eq!(
+            ChainBreaks::from_config(&config),
+            Some(ChainBreaks {
+                min_length: 10,
+                violations: true,
+                restart: true,
+            })
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Which Discord channels and threads the game is played in
+
+use poise::serenity_prelude as serenity;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tracing::{debug, warn};
+
+use crate::config::Config;
+
+/// The game channel, plus the threads the game may be played in
+///
+/// Messages in threads carry the thread's own channel ID, so a thread under the
+/// game channel is only recognized by looking up its parent.
+pub struct GameChannels {
+    channel_id: serenity::ChannelId,
+    /// A specific thread to play in
+    thread_id: Option<serenity::ChannelId>,
+    /// Whether any thread under the game channel counts
+    follow_threads: bool,
+    /// Channels looked up so far and whether they're game threads
+    known: Mutex<HashMap<serenity::ChannelId, bool>>,
+}
+
+impl GameChannels {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            channel_id: serenity::ChannelId::new(config.channel_id),
+            thread_id: (config.game_thread_id != 0)
+                .then(|| serenity::ChannelId::new(config.game_thread_id)),
+            follow_threads: config.follow_threads,
+            known: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Where announcements go: the configured thread, or else the game channel
+    pub fn home(&self) -> serenity::ChannelId {
+        self.thread_id.unwrap_or(self.channel_id)
+    }
+
+    /// The configured thread, if the game is played in one
+    pub fn thread_id(&self) -> Option<serenity::ChannelId> {
+        self.thread_id
+    }
+
+    /// Whether a channel is in scope, if that can be told without asking Discord
+    fn known(&self, channel_id: serenity::ChannelId) -> Option<bool> {
+        if channel_id == self.channel_id || Some(channel_id) == self.thread_id {
+            return Some(true);
+        }
+        if !self.follow_threads {
+            return Some(false);
+        }
+        self.known.lock().unwrap().get(&channel_id).copied()
+    }
+
+    /// Whether a channel is a thread the game is played in
+    pub fn is_game_thread(&self, channel: &serenity::GuildChannel) -> bool {
+        Some(channel.id) == self.thread_id
+            || (self.follow_threads
+                && channel.thread_metadata.is_some()
+                && channel.parent_id == Some(self.channel_id))
+    }
+
+    /// Record whether a channel is in scope, e.g. when a thread is created
+    pub fn remember(&self, channel_id: serenity::ChannelId, in_scope: bool) {
+        self.known.lock().unwrap().insert(channel_id, in_scope);
+    }
+
+    /// Whether messages in a channel are part of the game
+    ///
+    /// Channels that aren't known yet are fetched once to find their parent.
+    pub async fn contains(&self, ctx: &serenity::Context, channel_id: serenity::ChannelId) -> bool {
+        if let Some(in_scope) = self.known(channel_id) {
+            return in_scope;
+        }
+
+        let in_scope = match channel_id.to_channel(ctx).await {
+            Ok(serenity::Channel::Guild(channel)) => self.is_game_thread(&channel),
+            Ok(_) => false,
+            Err(e) => {
+                // Not remembered, the next message tries again
+                warn!(channel_id = channel_id.get(), error = %e, "Failed to look up channel");
+                return false;
+            }
+        };
+        debug!(channel_id = channel_id.get(), in_scope, "Looked up channel");
+        self.remember(channel_id, in_scope);
+        in_scope
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thread(id: u64, parent: u64) -> serenity::GuildChannel {
+        let mut channel = serenity::GuildChannel::default();
+        channel.id = serenity::ChannelId::new(id);
+        channel.parent_id = Some(serenity::ChannelId::new(parent));
+        channel.thread_metadata = Some(
+            serde_json::from_value(serde_json::json!({
+                "archived": false,
+                "auto_archive_duration": 1440,
+                "archive_timestamp": null,
+            }))
+            .unwrap(),
+        );
+        channel
+    }
+
+    #[test]
+    fn test_thread_scope() {
+        let channels = GameChannels::new(&Config {
+            channel_id: 1,
+            ..Config::default()
+        });
+        assert_eq!(channels.home(), serenity::ChannelId::new(1));
+        assert_eq!(channels.known(serenity::ChannelId::new(1)), Some(true));
+        assert_eq!(channels.known(serenity::ChannelId::new(2)), Some(false));
+        assert!(!channels.is_game_thread(&thread(2, 1)));
+
+        let channels = GameChannels::new(&Config {
+            channel_id: 1,
+            game_thread_id: 3,
+            ..Config::default()
+        });
+        assert_eq!(channels.home(), serenity::ChannelId::new(3));
+        assert_eq!(channels.known(serenity::ChannelId::new(3)), Some(true));
+        assert!(channels.is_game_thread(&thread(3, 7)));
+        assert!(!channels.is_game_thread(&thread(2, 1)));
+
+        let channels = GameChannels::new(&Config {
+            channel_id: 1,
+            follow_threads: true,
+            ..Config::default()
+        });
+        // Unknown channels have to be looked up
+        assert_eq!(channels.known(serenity::ChannelId::new(2)), None);
+        assert!(channels.is_game_thread(&thread(2, 1)));
+        assert!(!channels.is_game_thread(&thread(4, 5)));
+
+        // A channel under the game channel that isn't a thread doesn't count
+        let mut text_channel = thread(6, 1);
+        text_channel.thread_metadata = None;
+        assert!(!channels.is_game_thread(&text_channel));
+
+        channels.remember(serenity::ChannelId::new(2), true);
+        assert_eq!(channels.known(serenity::ChannelId::new(2)), Some(true));
+    }
+}
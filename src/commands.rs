@@ -0,0 +1,1220 @@
+use poise::serenity_prelude as serenity;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, warn};
+
+use crate::actors::game_state::{
+    ChallengePlan, GetAttempts, GetDictionary, GetDictionaryStats, GetHistory, GetLeaderboard,
+    GetRandomWord, GetRules, GetStanding, GetTurnOrder, GiveUpChallenge, IsPaused, JoinRotation,
+    LeaveRotation, LookupWord, PlanChallenge, ResetGame, SetCurrentWord, SetPaused, StartChallenge,
+    Validity, VerdictSource, WordEntry,
+};
+use crate::actors::llm_validator::{GetLLMStats, GetQueueStatus};
+use crate::actors::message_reaction::{verdict_reaction, PostFile, EMOJI_HOURGLASS};
+use crate::actors::word_validator::{
+    DryRunWord, GetValidationLoad, ReloadBlocklist, ValidationLoad,
+};
+use crate::bulkcheck::{
+    check_download, download_client, BulkCheckOutcome, BulkChecker, REPORT_FILENAME,
+};
+use crate::config::Language;
+use crate::export::{export_history, ExportFormat, ATTACHMENT_LIMIT};
+use crate::games::Game;
+use crate::messages::{self, HelpInfo, HelpTopic, Text};
+use crate::replay::{parse_records, records_from_history, simulate_rules as simulate};
+use crate::scoring::Period;
+use crate::validation::extract::ExtractOptions;
+use crate::validation::plausibility::PlausibilityFilter;
+use crate::validation::rules::RuleMode;
+use crate::{Data, Error};
+
+/// Poise context used by all slash commands
+pub type Context<'a> = poise::Context<'a, Data, Error>;
+
+/// All slash commands registered by the bot, described in `language`
+pub fn all(language: Language) -> Vec<poise::Command<Data, Error>> {
+    let mut commands = vec![
+        help(),
+        check(),
+        rules(),
+        history(),
+        recent(),
+        leaderboard(),
+        why(),
+        stats(),
+        dict(),
+        challenge(),
+        export(),
+        simulate_rules(),
+        setword(),
+        pause(),
+        resume(),
+        reset(),
+        join(),
+        leave(),
+        order(),
+        llmstats(),
+        reload(),
+        register(),
+    ];
+    for command in &mut commands {
+        describe(command, None, language);
+    }
+    commands
+}
+
+/// Fill in the help texts of a command, its parameters and its subcommands
+fn describe(command: &mut poise::Command<Data, Error>, parent: Option<&str>, language: Language) {
+    let key = match parent {
+        Some(parent) => format!("{} {}", parent, command.name),
+        None => command.name.clone(),
+    };
+    command.description = messages::help(language, &key).map(str::to_string);
+    for parameter in &mut command.parameters {
+        parameter.description =
+            messages::help(language, &format!("{}.{}", key, parameter.name)).map(str::to_string);
+    }
+    for subcommand in &mut command.subcommands {
+        describe(subcommand, Some(&key), language);
+    }
+}
+
+/// Render a message in the language of the server the command was used in
+fn text(ctx: Context<'_>, text: Text) -> String {
+    let data = ctx.data();
+    let language = data
+        .games
+        .get(ctx.guild_id())
+        .map_or(data.config.language, |game| game.config.language);
+    text.render(language)
+}
+
+/// The game of the server the command was used in
+///
+/// Commands that need one check for it with [`in_game`] first.
+fn game(ctx: Context<'_>) -> Result<&Game, Error> {
+    ctx.data()
+        .games
+        .get(ctx.guild_id())
+        .ok_or_else(|| Error::Config("No game is played in this server".to_string()))
+}
+
+/// Let a command through only where a game is played, telling the user otherwise
+async fn in_game(ctx: Context<'_>) -> Result<bool, Error> {
+    if ctx.data().games.get(ctx.guild_id()).is_some() {
+        return Ok(true);
+    }
+    ctx.send(
+        poise::CreateReply::default()
+            .content(text(ctx, Text::NoGameHere))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(false)
+}
+
+/// How many words /history shows when no count is given
+const DEFAULT_HISTORY_COUNT: usize = 10;
+
+/// How many words /recent shows when no count is given
+const DEFAULT_RECENT_COUNT: usize = 10;
+
+/// How many players /leaderboard shows when no count is given
+const DEFAULT_LEADERBOARD_COUNT: usize = 10;
+
+/// Discord's limit for the length of an embed description
+const EMBED_DESCRIPTION_LIMIT: usize = 4096;
+
+/// Discord's limit for the number of fields in an embed
+const EMBED_FIELD_LIMIT: usize = 25;
+
+/// Discord's limit for the length of an embed field's name
+const EMBED_FIELD_NAME_LIMIT: usize = 256;
+
+/// Discord's limit for the length of all the text in an embed
+const EMBED_TOTAL_LIMIT: usize = 6000;
+
+/// Room kept in an embed for a footer like "…and 3 older words"
+const EMBED_FOOTER_ROOM: usize = 100;
+
+/// Explain how the game is played with the current rules
+#[poise::command(slash_command, check = "in_game")]
+pub async fn help(ctx: Context<'_>) -> Result<(), Error> {
+    let game = game(ctx)?;
+    let rules = game
+        .game_state
+        .send(GetRules)
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to get game rules: {}", e)))?;
+
+    let config = &game.config;
+    let info = HelpInfo::new(rules, config);
+    let embed = serenity::CreateEmbed::new()
+        .title(text(ctx, Text::HelpTitle))
+        .fields(HelpTopic::ALL.map(|topic| {
+            (
+                text(ctx, Text::HelpHeading(topic)),
+                text(ctx, Text::HelpSection(topic, &info)),
+                false,
+            )
+        }))
+        .footer(serenity::CreateEmbedFooter::new(text(
+            ctx,
+            Text::HelpFooter(&info.game),
+        )));
+    ctx.send(
+        poise::CreateReply::default()
+            .embed(embed)
+            .ephemeral(config.rules_ephemeral),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Check whether a word would be accepted without playing it
+#[poise::command(slash_command, ephemeral, check = "in_game")]
+pub async fn check(ctx: Context<'_>, word: String) -> Result<(), Error> {
+    debug!("Checking word '{}' for {}", word, ctx.author().name);
+
+    let verdict = game(ctx)?
+        .word_validator
+        .send(DryRunWord {
+            word: word.clone(),
+            user_id: Some(ctx.author().id.get()),
+        })
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to check word: {}", e)))??;
+
+    ctx.say(text(
+        ctx,
+        Text::Check {
+            word: &word,
+            verdict: &verdict,
+        },
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Show the game rules and the current word
+#[poise::command(slash_command, check = "in_game")]
+pub async fn rules(ctx: Context<'_>) -> Result<(), Error> {
+    let info = game(ctx)?
+        .game_state
+        .send(GetRules)
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to get game rules: {}", e)))?;
+
+    let config = &game(ctx)?.config;
+    ctx.send(
+        poise::CreateReply::default()
+            .content(Text::Rules(&info).render(config.language))
+            .ephemeral(config.rules_ephemeral),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Show the most recently played words
+#[poise::command(slash_command, ephemeral, check = "in_game")]
+pub async fn history(
+    ctx: Context<'_>,
+    #[min = 1]
+    #[max = 100]
+    count: Option<usize>,
+) -> Result<(), Error> {
+    let entries = game(ctx)?
+        .game_state
+        .send(GetHistory {
+            limit: count.unwrap_or(DEFAULT_HISTORY_COUNT),
+        })
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to get word history: {}", e)))?;
+
+    if entries.is_empty() {
+        ctx.say(text(ctx, Text::NoHistory)).await?;
+        return Ok(());
+    }
+
+    let (description, omitted) = format_history(&entries, EMBED_DESCRIPTION_LIMIT);
+    let mut embed = serenity::CreateEmbed::new()
+        .title(text(ctx, Text::HistoryTitle))
+        .description(description);
+    if omitted > 0 {
+        embed = embed.footer(serenity::CreateEmbedFooter::new(text(
+            ctx,
+            Text::OlderWords { count: omitted },
+        )));
+    }
+
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// Show the latest verdicts and what settled them, bot owner only
+#[poise::command(slash_command, owners_only, ephemeral, check = "in_game")]
+pub async fn recent(
+    ctx: Context<'_>,
+    #[min = 1]
+    #[max = 25]
+    count: Option<usize>,
+) -> Result<(), Error> {
+    let game = game(ctx)?;
+    let entries = game
+        .game_state
+        .send(GetHistory {
+            limit: count.unwrap_or(DEFAULT_RECENT_COUNT).min(EMBED_FIELD_LIMIT),
+        })
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to get word history: {}", e)))?;
+
+    if entries.is_empty() {
+        ctx.say(text(ctx, Text::NoHistory)).await?;
+        return Ok(());
+    }
+
+    let title = text(ctx, Text::RecentTitle);
+    let (fields, omitted) = recent_fields(
+        &entries,
+        SystemTime::now(),
+        game.config.language,
+        EMBED_TOTAL_LIMIT - title.len() - EMBED_FOOTER_ROOM,
+    );
+    let mut embed = serenity::CreateEmbed::new()
+        .title(title)
+        .fields(fields.into_iter().map(|(name, value)| (name, value, false)));
+    if omitted > 0 {
+        embed = embed.footer(serenity::CreateEmbedFooter::new(text(
+            ctx,
+            Text::OlderWords { count: omitted },
+        )));
+    }
+
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// Show the players with the most points
+#[poise::command(slash_command, check = "in_game")]
+pub async fn leaderboard(
+    ctx: Context<'_>,
+    #[min = 1]
+    #[max = 25]
+    count: Option<usize>,
+    weekly: Option<bool>,
+) -> Result<(), Error> {
+    let period = if weekly.unwrap_or(false) {
+        Period::Weekly
+    } else {
+        Period::AllTime
+    };
+    let leaders = game(ctx)?
+        .game_state
+        .send(GetLeaderboard {
+            period,
+            limit: count.unwrap_or(DEFAULT_LEADERBOARD_COUNT),
+        })
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to get leaderboard: {}", e)))?;
+
+    if leaders.is_empty() {
+        ctx.say(text(ctx, Text::NoScores)).await?;
+        return Ok(());
+    }
+
+    let embed = serenity::CreateEmbed::new()
+        .title(text(ctx, Text::LeaderboardTitle(period)))
+        .description(text(ctx, Text::Leaderboard(&leaders)));
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// Show how the bot handled the word in a message
+///
+/// Players see their own words, bot owners anyone's. Messages that aren't
+/// remembered get the same answer as other players' words.
+#[poise::command(slash_command, ephemeral)]
+pub async fn why(ctx: Context<'_>, message: String) -> Result<(), Error> {
+    let Some(message_id) = parse_message_id(&message) else {
+        ctx.say(text(ctx, Text::NotAMessage)).await?;
+        return Ok(());
+    };
+    let audit = &ctx.data().audit;
+    let author = ctx.author().id;
+    let allowed = ctx.framework().options().owners.contains(&author)
+        || audit.player(message_id) == Some(author.get());
+    let trail = audit.trail(message_id);
+    if !allowed || trail.is_empty() {
+        ctx.say(text(ctx, Text::NoAuditTrail)).await?;
+        return Ok(());
+    }
+
+    let mut description = String::new();
+    for event in &trail {
+        let line = format!(
+            "<t:{}:T> {}\n",
+            event.at.timestamp(),
+            text(ctx, Text::AuditStep(&event.step))
+        );
+        if description.len() + line.len() > EMBED_DESCRIPTION_LIMIT {
+            break;
+        }
+        description.push_str(&line);
+    }
+    let embed = serenity::CreateEmbed::new()
+        .title(text(ctx, Text::AuditTitle))
+        .description(description);
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// Show a player's points, ranks and attempt accuracy
+#[poise::command(slash_command, ephemeral, check = "in_game")]
+pub async fn stats(ctx: Context<'_>, player: Option<serenity::User>) -> Result<(), Error> {
+    let user = player.as_ref().unwrap_or(ctx.author());
+    let user_id = user.id.get();
+    let standing = game(ctx)?
+        .game_state
+        .send(GetStanding { user_id })
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to get player stats: {}", e)))?;
+    let attempts = game(ctx)?
+        .game_state
+        .send(GetAttempts { user_id })
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to get player attempts: {}", e)))?;
+    let paused = game(ctx)?
+        .game_state
+        .send(IsPaused)
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to get game state: {}", e)))?;
+
+    let mut description = format!(
+        "{}\n\n{}",
+        text(
+            ctx,
+            Text::Standing {
+                user_id,
+                standing: &standing
+            }
+        ),
+        text(ctx, Text::Attempts(&attempts))
+    );
+    if paused {
+        description.push_str("\n\n");
+        description.push_str(&text(ctx, Text::GamePaused));
+    }
+    let embed = serenity::CreateEmbed::new()
+        .title(text(ctx, Text::StatsTitle { name: &user.name }))
+        .description(description);
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// Ask the dictionary
+#[poise::command(
+    slash_command,
+    subcommands("dict_stats", "dict_has", "dict_random", "dict_bulkcheck"),
+    subcommand_required
+)]
+pub async fn dict(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Dictionary size and source, bot owner only
+#[poise::command(
+    slash_command,
+    rename = "stats",
+    owners_only,
+    ephemeral,
+    check = "in_game"
+)]
+pub async fn dict_stats(ctx: Context<'_>) -> Result<(), Error> {
+    let stats = game(ctx)?
+        .game_state
+        .send(GetDictionaryStats)
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to get dictionary stats: {}", e)))?;
+
+    ctx.say(text(ctx, Text::DictionaryStats(&stats))).await?;
+    Ok(())
+}
+
+/// Whether a word is in the dictionary
+#[poise::command(slash_command, rename = "has", ephemeral, check = "in_game")]
+pub async fn dict_has(ctx: Context<'_>, word: String) -> Result<(), Error> {
+    let lookup = game(ctx)?
+        .game_state
+        .send(LookupWord { word })
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to look up word: {}", e)))?;
+
+    ctx.say(text(ctx, Text::Lookup(&lookup))).await?;
+    Ok(())
+}
+
+/// A random word from the dictionary
+#[poise::command(slash_command, rename = "random", check = "in_game")]
+pub async fn dict_random(ctx: Context<'_>) -> Result<(), Error> {
+    let word = game(ctx)?
+        .game_state
+        .send(GetRandomWord)
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to pick a word: {}", e)))?;
+
+    match word {
+        Some(word) => ctx.say(format!("🎲 **{}**", word)).await?,
+        None => ctx.say(text(ctx, Text::DictionaryEmpty)).await?,
+    };
+    Ok(())
+}
+
+/// Check a word list against the dictionary, bot owner only
+#[poise::command(
+    slash_command,
+    rename = "bulkcheck",
+    owners_only,
+    ephemeral,
+    check = "in_game"
+)]
+pub async fn dict_bulkcheck(
+    ctx: Context<'_>,
+    file: serenity::Attachment,
+    plausibility: Option<bool>,
+) -> Result<(), Error> {
+    let config = &game(ctx)?.config;
+    let limit_kb = config.bulk_check_max_kb;
+    // Discord tells the size, so a list that's too large isn't downloaded at all
+    if file.size as usize > limit_kb * 1024 {
+        ctx.say(text(ctx, Text::BulkCheckTooLarge { limit_kb }))
+            .await?;
+        return Ok(());
+    }
+
+    // Checking a long list may take longer than Discord waits for a reply
+    ctx.defer_ephemeral().await?;
+    let dictionary = game(ctx)?
+        .game_state
+        .send(GetDictionary)
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to get dictionary: {}", e)))?;
+    let plausibility = plausibility
+        .unwrap_or(config.plausibility_filter)
+        .then(|| PlausibilityFilter::with_limits_from(config));
+    let checker = BulkChecker::new(
+        dictionary,
+        ExtractOptions::from_config(config),
+        plausibility,
+    );
+
+    let outcome = match download_client() {
+        Ok(client) => check_download(&client, &file.url, limit_kb * 1024, checker).await,
+        Err(e) => Err(e),
+    };
+    let check = match outcome {
+        Ok(BulkCheckOutcome::Checked(check)) => check,
+        Ok(BulkCheckOutcome::TooLarge) => {
+            ctx.say(text(ctx, Text::BulkCheckTooLarge { limit_kb }))
+                .await?;
+            return Ok(());
+        }
+        Err(e) => {
+            warn!(file = %file.filename, error = %e, "Failed to check a word list");
+            ctx.say(text(ctx, Text::BulkCheckFailed)).await?;
+            return Ok(());
+        }
+    };
+    debug!(
+        file = %file.filename,
+        found = check.found,
+        missing = check.missing.len(),
+        malformed = check.malformed,
+        "Checked a word list"
+    );
+
+    let report = text(ctx, Text::BulkCheckReport(&check));
+    ctx.send(
+        poise::CreateReply::default()
+            .content(text(ctx, Text::BulkCheck(&check)))
+            .attachment(serenity::CreateAttachment::bytes(
+                report.into_bytes(),
+                REPORT_FILENAME,
+            )),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Get from one word to another in as few moves as possible
+#[poise::command(
+    slash_command,
+    subcommands("challenge_start", "challenge_giveup"),
+    subcommand_required
+)]
+pub async fn challenge(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Start a challenge, bot owner only
+#[poise::command(
+    slash_command,
+    rename = "start",
+    owners_only,
+    ephemeral,
+    check = "in_game"
+)]
+pub async fn challenge_start(ctx: Context<'_>, from: String, to: String) -> Result<(), Error> {
+    // Finding the shortest chain may take longer than Discord waits for a reply
+    ctx.defer_ephemeral().await?;
+    let plan = game(ctx)?
+        .game_state
+        .send(PlanChallenge { from, to })
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to plan challenge: {}", e)))??;
+
+    if let ChallengePlan::Ready(path) = &plan {
+        game(ctx)?
+            .game_state
+            .send(StartChallenge { path: path.clone() })
+            .await
+            .map_err(|e| Error::Actor(format!("Failed to start challenge: {}", e)))?;
+    }
+    ctx.say(text(ctx, Text::ChallengePlan(&plan))).await?;
+    Ok(())
+}
+
+/// Give up the challenge and show the shortest chain, bot owner only
+#[poise::command(slash_command, rename = "giveup", owners_only, check = "in_game")]
+pub async fn challenge_giveup(ctx: Context<'_>) -> Result<(), Error> {
+    let path = game(ctx)?
+        .game_state
+        .send(GiveUpChallenge)
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to give up challenge: {}", e)))?;
+
+    match path {
+        Some(path) => {
+            ctx.say(text(ctx, Text::ChallengeGivenUp { path: &path }))
+                .await?
+        }
+        None => ctx.say(text(ctx, Text::NoChallenge)).await?,
+    };
+    Ok(())
+}
+
+/// Export the last HISTORY_SIZE played words as a file, bot owner only
+#[poise::command(slash_command, owners_only, ephemeral, check = "in_game")]
+pub async fn export(ctx: Context<'_>, format: Option<ExportFormat>) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let format = format.unwrap_or_default();
+    let history = game(ctx)?
+        .game_state
+        .send(GetHistory { limit: usize::MAX })
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to get history: {}", e)))?;
+
+    // Only names already in the cache, looking up every player would take a request each
+    let cache = ctx.cache();
+    let export = export_history(
+        history.iter().rev(),
+        format,
+        |user_id| {
+            cache
+                .user(serenity::UserId::new(user_id))
+                .map(|user| user.name.clone())
+        },
+        ATTACHMENT_LIMIT,
+    );
+    let summary = text(ctx, Text::Export(&export));
+    let posted = game(ctx)?
+        .message_reaction
+        .send(PostFile {
+            channel_id: ctx.channel_id().get(),
+            filename: format.filename().to_string(),
+            data: export.data,
+            content: summary.clone(),
+        })
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to post export: {}", e)))?;
+
+    match posted {
+        Some(_) => ctx.say(summary).await?,
+        None => ctx.say(text(ctx, Text::ExportFailed)).await?,
+    };
+    Ok(())
+}
+
+/// See which verdicts another rule mode would change, bot owner only
+///
+/// The game's history, or the attached transcript, is replayed on engines of
+/// its own, so the live game isn't touched.
+#[poise::command(
+    slash_command,
+    rename = "simulate-rules",
+    owners_only,
+    ephemeral,
+    check = "in_game"
+)]
+pub async fn simulate_rules(
+    ctx: Context<'_>,
+    mode: RuleMode,
+    transcript: Option<serenity::Attachment>,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+    let game = game(ctx)?;
+    let records = match transcript {
+        Some(file) => {
+            let parsed = file
+                .download()
+                .await
+                .map_err(|e| Error::Replay(format!("Failed to download transcript: {}", e)))
+                .and_then(|content| {
+                    parse_records(&file.filename, &String::from_utf8_lossy(&content))
+                });
+            match parsed {
+                Ok(records) => records,
+                Err(e) => {
+                    warn!(file = %file.filename, error = %e, "Failed to read a transcript");
+                    ctx.say(text(ctx, Text::TranscriptInvalid)).await?;
+                    return Ok(());
+                }
+            }
+        }
+        None => {
+            let history = game
+                .game_state
+                .send(GetHistory { limit: usize::MAX })
+                .await
+                .map_err(|e| Error::Actor(format!("Failed to get history: {}", e)))?;
+            records_from_history(history.iter().rev())
+        }
+    };
+    let dictionary = game
+        .game_state
+        .send(GetDictionary)
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to get dictionary: {}", e)))?;
+
+    let simulation = simulate(dictionary, &game.config, mode, &records).await;
+    debug!(
+        records = simulation.records,
+        changed = simulation.changes.len(),
+        "Simulated a rule mode change"
+    );
+    ctx.say(text(ctx, Text::RulesSimulation(&simulation)))
+        .await?;
+    Ok(())
+}
+
+/// Set the current word by hand, bot owner only
+#[poise::command(slash_command, owners_only, ephemeral, check = "in_game")]
+pub async fn setword(ctx: Context<'_>, word: String, force: Option<bool>) -> Result<(), Error> {
+    let outcome = game(ctx)?
+        .game_state
+        .send(SetCurrentWord {
+            word,
+            force: force.unwrap_or(false),
+        })
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to set current word: {}", e)))?;
+
+    ctx.say(text(ctx, Text::SetWord(&outcome))).await?;
+    Ok(())
+}
+
+/// Pause the game, bot owner only
+#[poise::command(slash_command, owners_only, check = "in_game")]
+pub async fn pause(ctx: Context<'_>) -> Result<(), Error> {
+    let changed = game(ctx)?
+        .game_state
+        .send(SetPaused(true))
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to pause game: {}", e)))?;
+
+    if changed {
+        ctx.say(text(ctx, Text::Paused)).await?;
+    } else {
+        ctx.send(
+            poise::CreateReply::default()
+                .content(text(ctx, Text::AlreadyPaused))
+                .ephemeral(true),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Resume a paused game, bot owner only
+#[poise::command(slash_command, owners_only, check = "in_game")]
+pub async fn resume(ctx: Context<'_>) -> Result<(), Error> {
+    let changed = game(ctx)?
+        .game_state
+        .send(SetPaused(false))
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to resume game: {}", e)))?;
+
+    if changed {
+        ctx.say(text(ctx, Text::Resumed)).await?;
+    } else {
+        ctx.send(
+            poise::CreateReply::default()
+                .content(text(ctx, Text::NotPaused))
+                .ephemeral(true),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Clear the chain and start a new one, bot owner only
+#[poise::command(slash_command, owners_only, check = "in_game")]
+pub async fn reset(ctx: Context<'_>) -> Result<(), Error> {
+    game(ctx)?
+        .game_state
+        .send(ResetGame)
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to reset game: {}", e)))?;
+
+    ctx.say(text(ctx, Text::ChainReset)).await?;
+    Ok(())
+}
+
+/// Join the players taking turns
+#[poise::command(slash_command, check = "in_game")]
+pub async fn join(ctx: Context<'_>) -> Result<(), Error> {
+    let user_id = ctx.author().id.get();
+    let joined = game(ctx)?
+        .game_state
+        .send(JoinRotation { user_id })
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to join the turn order: {}", e)))?;
+
+    let reply = match joined {
+        Some(true) => {
+            ctx.say(text(ctx, Text::Joined { user_id })).await?;
+            return Ok(());
+        }
+        Some(false) => Text::AlreadyJoined,
+        None => Text::NoTurns,
+    };
+    ctx.send(
+        poise::CreateReply::default()
+            .content(text(ctx, reply))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Leave the players taking turns
+#[poise::command(slash_command, check = "in_game")]
+pub async fn leave(ctx: Context<'_>) -> Result<(), Error> {
+    let user_id = ctx.author().id.get();
+    let left = game(ctx)?
+        .game_state
+        .send(LeaveRotation { user_id })
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to leave the turn order: {}", e)))?;
+
+    let reply = match left {
+        Some(true) => {
+            ctx.say(text(ctx, Text::Left { user_id })).await?;
+            return Ok(());
+        }
+        Some(false) => Text::NotJoined,
+        None => Text::NoTurns,
+    };
+    ctx.send(
+        poise::CreateReply::default()
+            .content(text(ctx, reply))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Show the turn order
+#[poise::command(slash_command, ephemeral, check = "in_game")]
+pub async fn order(ctx: Context<'_>) -> Result<(), Error> {
+    let order = game(ctx)?
+        .game_state
+        .send(GetTurnOrder)
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to get the turn order: {}", e)))?;
+
+    let content = match &order {
+        Some(players) => text(ctx, Text::TurnOrder(players)),
+        None => text(ctx, Text::NoTurns),
+    };
+    ctx.say(content).await?;
+    Ok(())
+}
+
+/// Show how much the LLM has been used, bot owner only
+#[poise::command(slash_command, owners_only, ephemeral)]
+pub async fn llmstats(ctx: Context<'_>, reset: Option<bool>) -> Result<(), Error> {
+    let reset = reset.unwrap_or(false);
+    let stats = ctx
+        .data()
+        .llm_validator
+        .send(GetLLMStats { reset })
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to get LLM stats: {}", e)))?;
+    let queue = ctx
+        .data()
+        .llm_validator
+        .send(GetQueueStatus)
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to get LLM queue: {}", e)))?;
+    let mut validations = ValidationLoad::default();
+    for game in ctx.data().games.iter() {
+        validations = validations
+            + game
+                .word_validator
+                .send(GetValidationLoad)
+                .await
+                .map_err(|e| Error::Actor(format!("Failed to get validation load: {}", e)))?;
+    }
+
+    ctx.say(text(
+        ctx,
+        Text::LlmStats {
+            stats: &stats,
+            queue,
+            validations,
+            reset,
+        },
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Reload the list of blocked words, bot owner only
+#[poise::command(slash_command, owners_only, hide_in_help, ephemeral)]
+pub async fn reload(ctx: Context<'_>) -> Result<(), Error> {
+    // Every server's game reads the same file
+    let mut blocked = 0;
+    for game in ctx.data().games.iter() {
+        blocked = game
+            .word_validator
+            .send(ReloadBlocklist)
+            .await
+            .map_err(|e| Error::Actor(format!("Failed to reload blocklist: {}", e)))??;
+    }
+
+    ctx.say(text(ctx, Text::BlocklistReloaded { words: blocked }))
+        .await?;
+    Ok(())
+}
+
+/// Register the commands again, bot owner only
+#[poise::command(slash_command, owners_only, hide_in_help)]
+pub async fn register(ctx: Context<'_>) -> Result<(), Error> {
+    poise::builtins::register_application_commands_buttons(ctx).await?;
+    Ok(())
+}
+
+/// The message ID in a message link, or a bare message ID
+fn parse_message_id(message: &str) -> Option<u64> {
+    message.trim().rsplit('/').next()?.parse().ok()
+}
+
+/// One line per history entry, newest first, fitting within `max_len` bytes
+///
+/// Returns the text and how many entries were left out to stay within the limit.
+fn format_history(entries: &[WordEntry], max_len: usize) -> (String, usize) {
+    let mut text = String::new();
+
+    for (shown, entry) in entries.iter().enumerate() {
+        let emoji = verdict_reaction(entry.validity);
+        let played_at = entry
+            .played_at
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let source = match &entry.source {
+            Some(VerdictSource::Dictionary { .. }) => " 📖".to_string(),
+            Some(VerdictSource::Llm { .. }) => " 🤖".to_string(),
+            Some(VerdictSource::Admin { user_id }) => format!(" 🛡️ <@{}>", user_id),
+            Some(VerdictSource::Vote { votes }) => format!(" 🗳️ {}", votes),
+            Some(VerdictSource::Seed) => " 🌱".to_string(),
+            None => String::new(),
+        };
+        let line = format!(
+            "{} **{}** – <@{}> <t:{}:R>{}\n",
+            emoji, entry.word, entry.user_id, played_at, source
+        );
+
+        if text.len() + line.len() > max_len {
+            return (text, entries.len() - shown);
+        }
+        text.push_str(&line);
+    }
+
+    (text, 0)
+}
+
+/// An embed field per history entry, newest first, fitting within `max_len` bytes altogether
+///
+/// Pending words show how long they've waited for their verdict. Returns the
+/// fields and how many entries were left out to stay within Discord's limits.
+fn recent_fields(
+    entries: &[WordEntry],
+    now: SystemTime,
+    language: Language,
+    max_len: usize,
+) -> (Vec<(String, String)>, usize) {
+    let mut fields = Vec::new();
+    let mut len = 0;
+
+    for (shown, entry) in entries.iter().enumerate() {
+        let played_at = entry
+            .played_at
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let (emoji, verdict) = match entry.validity {
+            Validity::Pending => (
+                EMOJI_HOURGLASS,
+                Text::RecentPending {
+                    waiting: now.duration_since(entry.played_at).unwrap_or_default(),
+                },
+            ),
+            validity => (
+                verdict_reaction(validity),
+                Text::RecentSource(entry.source.as_ref()),
+            ),
+        };
+        let name: String = format!("{} {}", emoji, entry.word)
+            .chars()
+            .take(EMBED_FIELD_NAME_LIMIT)
+            .collect();
+        let value = format!(
+            "<@{}> · {} · <t:{}:R>",
+            entry.user_id,
+            verdict.render(language),
+            played_at
+        );
+
+        if shown == EMBED_FIELD_LIMIT || len + name.len() + value.len() > max_len {
+            return (fields, entries.len() - shown);
+        }
+        len += name.len() + value.len();
+        fields.push((name, value));
+    }
+
+    (fields, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Delivery;
+    use std::time::Duration;
+
+    #[test]
+    fn test_all_described() {
+        fn check(command: &poise::Command<Data, Error>, language: Language) {
+            assert!(
+                command.description.is_some(),
+                "/{} has no description in {:?}",
+                command.name,
+                language
+            );
+            for parameter in &command.parameters {
+                assert!(
+                    parameter.description.is_some(),
+                    "/{} {} has no description in {:?}",
+                    command.name,
+                    parameter.name,
+                    language
+                );
+            }
+            for subcommand in &command.subcommands {
+                check(subcommand, language);
+            }
+        }
+
+        for language in [Language::Finnish, Language::English] {
+            for command in all(language) {
+                check(&command, language);
+            }
+        }
+        let english = all(Language::English);
+        let dict = english.iter().find(|c| c.name == "dict").unwrap();
+        assert_eq!(
+            dict.subcommands[1].parameters[0].description.as_deref(),
+            Some("The word to look up")
+        );
+    }
+
+    #[test]
+    fn test_format_history() {
+        let entry = |word: &str, validity| WordEntry {
+            word: word.to_string(),
+            user_id: 42,
+            message_id: 1,
+            validity,
+            played_at: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            settled_at: None,
+            in_chain: true,
+            source: None,
+            delivery: Delivery::default(),
+        };
+        let settled = |word: &str, source| WordEntry {
+            source: Some(source),
+            ..entry(word, Validity::Valid)
+        };
+        let entries = vec![
+            entry("kassi", Validity::Pending),
+            entry("koira", Validity::Invalid),
+            settled("kassa", VerdictSource::Dictionary { file: None }),
+            settled(
+                "aamu",
+                VerdictSource::Llm {
+                    explanation: "Yleinen sana".to_string(),
+                    model: Some("gemini-pro".to_string()),
+                },
+            ),
+            settled("uuno", VerdictSource::Admin { user_id: 7 }),
+            settled("oksa", VerdictSource::Seed),
+        ];
+
+        let (text, omitted) = format_history(&entries, EMBED_DESCRIPTION_LIMIT);
+        assert_eq!(omitted, 0);
+        assert_eq!(
+            text.lines().collect::<Vec<_>>(),
+            vec![
+                "❓ **kassi** – <@42> <t:1700000000:R>",
+                "❌ **koira** – <@42> <t:1700000000:R>",
+                "✅ **kassa** – <@42> <t:1700000000:R> 📖",
+                "✅ **aamu** – <@42> <t:1700000000:R> 🤖",
+                "✅ **uuno** – <@42> <t:1700000000:R> 🛡️ <@7>",
+                "✅ **oksa** – <@42> <t:1700000000:R> 🌱",
+            ]
+        );
+
+        // Only whole lines are kept when the limit is hit
+        let two_lines = text.lines().take(2).map(|l| l.len() + 1).sum::<usize>();
+        let (truncated, omitted) = format_history(&entries, two_lines + 1);
+        assert_eq!(truncated.lines().count(), 2);
+        assert_eq!(omitted, 4);
+
+        let long: Vec<_> = (0..500).map(|_| entry("kissa", Validity::Valid)).collect();
+        let (text, omitted) = format_history(&long, EMBED_DESCRIPTION_LIMIT);
+        assert!(text.len() <= EMBED_DESCRIPTION_LIMIT);
+        assert!(omitted > 0);
+        assert_eq!(text.lines().count() + omitted, 500);
+    }
+
+    #[test]
+    fn test_recent_fields() {
+        let played_at = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let now = played_at + Duration::from_secs(150);
+        let entry = |word: &str, validity, source| WordEntry {
+            word: word.to_string(),
+            user_id: 42,
+            message_id: 1,
+            validity,
+            played_at,
+            settled_at: None,
+            in_chain: validity != Validity::Invalid,
+            source,
+            delivery: Delivery::default(),
+        };
+        let entries = vec![
+            entry("kassi", Validity::Pending, None),
+            entry("koira", Validity::Invalid, None),
+            entry(
+                "pori",
+                Validity::Invalid,
+                Some(VerdictSource::Llm {
+                    explanation: "Ei erisnimi".to_string(),
+                    model: None,
+                }),
+            ),
+            entry(
+                "kassa",
+                Validity::Valid,
+                Some(VerdictSource::Dictionary { file: None }),
+            ),
+            entry(
+                "aamu",
+                Validity::Valid,
+                Some(VerdictSource::Llm {
+                    explanation: "Yleinen sana".to_string(),
+                    model: Some("gemini-pro".to_string()),
+                }),
+            ),
+            entry(
+                "uuno",
+                Validity::Valid,
+                Some(VerdictSource::Admin { user_id: 7 }),
+            ),
+            entry(
+                "ruuna",
+                Validity::Valid,
+                Some(VerdictSource::Vote { votes: 3 }),
+            ),
+            entry("oksa", Validity::Valid, Some(VerdictSource::Seed)),
+        ];
+
+        let (fields, omitted) = recent_fields(&entries, now, Language::English, EMBED_TOTAL_LIMIT);
+        assert_eq!(omitted, 0);
+        let when = "<t:1700000000:R>";
+        let expected = [
+            ("⏳ kassi", "⏳ waiting for 2 minutes"),
+            ("❌ koira", "📏 rules"),
+            ("❌ pori", "🤖 LLM"),
+            ("✅ kassa", "📖 dictionary"),
+            ("✅ aamu", "🤖 LLM"),
+            ("✅ uuno", "🛡️ admin <@7>"),
+            ("✅ ruuna", "🗳️ 3 votes"),
+            ("✅ oksa", "🌱 the bot's word"),
+        ];
+        assert_eq!(fields.len(), expected.len());
+        for ((name, value), (expected_name, verdict)) in fields.iter().zip(expected) {
+            assert_eq!(name, expected_name);
+            assert_eq!(value, &format!("<@42> · {} · {}", verdict, when));
+        }
+
+        let (fields, _) = recent_fields(&entries[..1], now, Language::Finnish, EMBED_TOTAL_LIMIT);
+        assert_eq!(
+            fields[0].1,
+            format!("<@42> · ⏳ odottanut 2 min · {}", when)
+        );
+
+        // No more than Discord allows, in fields or in text
+        let many: Vec<_> = (0..40)
+            .map(|_| entry("kissa", Validity::Valid, None))
+            .collect();
+        let (fields, omitted) = recent_fields(&many, now, Language::English, EMBED_TOTAL_LIMIT);
+        assert_eq!((fields.len(), omitted), (EMBED_FIELD_LIMIT, 15));
+
+        let long = "a".repeat(1000);
+        let long: Vec<_> = (0..EMBED_FIELD_LIMIT)
+            .map(|_| entry(&long, Validity::Invalid, None))
+            .collect();
+        let (fields, omitted) = recent_fields(&long, now, Language::English, EMBED_TOTAL_LIMIT);
+        assert!(fields
+            .iter()
+            .all(|(name, _)| name.chars().count() == EMBED_FIELD_NAME_LIMIT));
+        let total: usize = fields
+            .iter()
+            .map(|(name, value)| name.len() + value.len())
+            .sum();
+        assert!(total <= EMBED_TOTAL_LIMIT);
+        assert!(omitted > 0);
+        assert_eq!(fields.len() + omitted, EMBED_FIELD_LIMIT);
+    }
+
+    #[test]
+    fn test_parse_message_id() {
+        assert_eq!(
+            parse_message_id("https://discord.com/channels/1/2/1234567890"),
+            Some(1234567890)
+        );
+        assert_eq!(parse_message_id(" 1234567890 "), Some(1234567890));
+        assert_eq!(parse_message_id("https://discord.com/channels/1/2/"), None);
+        assert_eq!(parse_message_id("kissa"), None);
+    }
+}
@@ -1,60 +1,2216 @@
+use chrono::{NaiveTime, Weekday};
+use chrono_tz::Tz;
 use dotenvy::dotenv;
-use miette::IntoDiagnostic;
+use miette::{NamedSource, SourceSpan};
+use serde::{de, Deserialize, Deserializer};
+use std::collections::{BTreeMap, HashMap};
 use std::env;
-use tracing::info;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::str::FromStr;
+use tracing::{info, warn};
 
+use crate::actors::game_state::{DEFAULT_QUEUE_SIZE, DEFAULT_QUEUE_TIMEOUT};
+use crate::actors::message_reaction::{
+    ResetReactions, DEFAULT_REACTION_INTERVAL, EMOJI_NO_ENTRY, EMOJI_PENCIL, EMOJI_REPEAT,
+    EMOJI_SLEEPING, EMOJI_TIMER,
+};
+use crate::actors::DEFAULT_MAILBOX_CAPACITY;
+use crate::error::{ConfigErrors, ConfigProblem};
+use crate::export::ATTACHMENT_LIMIT;
+use crate::rotation::TurnMode;
+use crate::validation::inflection::{parse_rules, DEFAULT_INFLECTION_RULES};
+use crate::validation::remote::WIKTIONARY;
+use crate::validation::rules::{DiacriticPolicy, PendingPolicy, RuleMode};
+use crate::webhook::parse_events;
 use crate::Error;
 
+/// What to do with messages that contain more than one word
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MultiWordPolicy {
+    /// Ignore the message entirely
+    #[default]
+    Ignore,
+    /// Play the first word of the message
+    FirstToken,
+    /// Ignore the message, reacting to show it wasn't played
+    ReactIgnored,
+}
+
+impl FromStr for MultiWordPolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "ignore" => Ok(MultiWordPolicy::Ignore),
+            "first_token" => Ok(MultiWordPolicy::FirstToken),
+            "react_ignored" => Ok(MultiWordPolicy::ReactIgnored),
+            other => Err(Error::Config(format!(
+                "Invalid MULTI_WORD_POLICY '{}', expected 'ignore', 'first_token' or 'react_ignored'",
+                other
+            ))),
+        }
+    }
+}
+
+/// When the words left for the LLM are sent to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LlmBatchMode {
+    /// Wait for `llm_batch_size` words, or `batch_timeout_secs` after the oldest word
+    #[default]
+    Batched,
+    /// Ask about each word on its own as soon as it's played
+    Immediate,
+}
+
+impl FromStr for LlmBatchMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "batched" => Ok(LlmBatchMode::Batched),
+            "immediate" => Ok(LlmBatchMode::Immediate),
+            other => Err(Error::Config(format!(
+                "Invalid LLM_BATCH_MODE '{}', expected 'batched' or 'immediate'",
+                other
+            ))),
+        }
+    }
+}
+
+/// What happens to the words left over once the daily LLM budget is used up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BudgetFallback {
+    /// Keep the ❓ for an admin to settle the word
+    #[default]
+    Pending,
+    /// Reject the word as if the LLM had
+    Reject,
+}
+
+impl FromStr for BudgetFallback {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "pending" => Ok(BudgetFallback::Pending),
+            "reject" => Ok(BudgetFallback::Reject),
+            other => Err(Error::Config(format!(
+                "Invalid LLM_BUDGET_FALLBACK '{}', expected 'pending' or 'reject'",
+                other
+            ))),
+        }
+    }
+}
+
+/// Language used for the bot's messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    Finnish,
+    English,
+}
+
+impl FromStr for Language {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "fi" => Ok(Language::Finnish),
+            "en" => Ok(Language::English),
+            other => Err(Error::Config(format!(
+                "Invalid BOT_LANGUAGE '{}', expected 'fi' or 'en'",
+                other
+            ))),
+        }
+    }
+}
+
+/// Format of the log output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per line, for log aggregation
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            other => Err(Error::Config(format!(
+                "Invalid LOG_FORMAT '{}', expected 'text' or 'json'",
+                other
+            ))),
+        }
+    }
+}
+
+/// Settings of one server, anything not given here comes from the top level
+///
+/// Read from a `[guilds.<guild ID>]` table of the configuration file.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct GuildSettings {
+    pub channel_id: u64,
+    /// Thread to play in instead of the channel itself, 0 for none
+    pub game_thread_id: u64,
+    #[serde(deserialize_with = "deserialize_option_from_str")]
+    pub rule_mode: Option<RuleMode>,
+    #[serde(deserialize_with = "deserialize_option_from_str")]
+    pub language: Option<Language>,
+    pub pause_reaction: Option<char>,
+    pub already_used_reaction: Option<char>,
+    #[serde(deserialize_with = "deserialize_option_from_str")]
+    pub llm_batch_mode: Option<LlmBatchMode>,
+}
+
+/// Bot configuration
+///
+/// Read from an optional TOML file whose keys are the field names, with
+/// environment variables taking precedence.
+#[derive(Clone, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub discord_token: String,
+    /// Gemini API key for proper noun checks, empty if not configured
+    pub gemini_api_key: String,
     pub channel_id: u64,
+    /// Thread to play in instead of the channel itself, 0 for none
+    pub game_thread_id: u64,
+    /// Whether any thread under the game channel is part of the game
+    pub follow_threads: bool,
+    /// Channel for error notifications to the admins, 0 to disable them
+    pub admin_channel_id: u64,
+    /// Whether startup fails when the bot lacks permissions in a game channel
+    pub strict_permissions: bool,
+    /// Guild to register the commands in instead of globally, 0 for global registration
+    pub dev_guild_id: u64,
     pub dictionary_path: String,
+    /// Further word lists loaded after `dictionary_path`, comma-separated, e.g.
+    /// place names or words added by hand; a word in several lists is from the first
+    pub extra_dictionary_paths: String,
+    /// Whether a missing `dictionary_path` is downloaded from `dictionary_url` at startup
+    pub download_dictionary: bool,
+    pub dictionary_url: String,
+    /// Hex SHA-256 the downloaded word list must have, empty to accept any
+    pub dictionary_sha256: String,
+    /// Whether inflected forms of dictionary words are accepted, found by stripping endings
+    pub lenient_dictionary: bool,
+    /// Endings stripped in lenient mode, comma-separated `suffix` or `suffix>replacement`
+    pub inflection_rules: String,
+    /// Dictionary asked about words missing from the word list before the LLM:
+    /// empty for none, `wiktionary`, or the URL of an endpoint answering 200 or 404
+    pub remote_dictionary: String,
+    pub remote_dictionary_timeout_ms: u64,
+    /// Remote lookups allowed per minute, further words go straight to the LLM
+    pub remote_dictionary_per_minute: usize,
+    /// Words that are never accepted, one per line, empty to block nothing
+    pub blocklist_path: String,
     pub bot_activity: String,
+    /// Whether the presence shows the current word, with `bot_activity` as the fallback
+    pub dynamic_presence: bool,
+    /// Whether the bot posts the state of the game in the game channel when it starts
+    pub announce_on_ready: bool,
+    /// Minimum time between two startup announcements in a channel, so crash loops stay quiet
+    pub announce_interval_secs: u64,
+    /// File recording when each channel was last announced in
+    pub announce_log_path: String,
+    /// Chain lengths whose multiples are announced in the game channel, 0 to announce none
+    pub milestone_interval: usize,
+    /// Accepted words after which a rejected word breaks the chain and its
+    /// summary is posted, 0 for chains that never break
+    pub chain_break_length: usize,
+    /// Whether words breaking a rule break the chain too, not only words that aren't words
+    pub violations_break_chain: bool,
+    /// Whether a new chain starts right after one breaks, or the game waits for /reset
+    pub restart_after_break: bool,
+    /// Endpoint the game's highlights are POSTed to as JSON, empty for none
+    pub webhook_url: String,
+    /// Events sent to the webhook, comma-separated: `word_accepted`, `milestone`, `record`, `reset`
+    pub webhook_events: String,
+    /// Whether the bot keeps a pinned message showing the current word up to date
+    pub status_message: bool,
+    /// File the status message of each channel is kept in, so restarts edit the same message
+    pub status_message_path: String,
+    /// Whether the status message says the bot is offline once it shuts down
+    pub offline_status: bool,
+    /// Models asked about proper nouns, comma-separated in order of preference
+    pub llm_model: String,
+    /// How long a fallback model is used before the preferred one is tried again
+    pub llm_fallback_reset_secs: u64,
+    /// Whether a well-known word is sent to the LLM at startup to catch a misconfigured model
+    pub llm_startup_check: bool,
     pub llm_batch_size: usize,
     pub batch_timeout_secs: u64,
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub llm_batch_mode: LlmBatchMode,
+    /// How many words may be sent to the LLM in 24 hours, 0 for no limit
+    pub llm_daily_budget: usize,
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub llm_budget_fallback: BudgetFallback,
+    /// How long a word may wait for the LLM before it gets `llm_expired_fallback`
+    /// instead, 0 to wait for the LLM however long it takes
+    pub llm_max_age_secs: u64,
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub llm_expired_fallback: BudgetFallback,
+    /// File the words sent to the LLM are counted in, to keep the count over restarts
+    pub llm_budget_path: String,
+    /// File the LLM's verdicts are saved to on shutdown, empty to keep them in memory only
+    pub llm_cache_path: String,
+    /// How often the words accepted by the LLM are reported, 0 to never report them
+    pub llm_digest_interval_secs: u64,
+    /// File the LLM's verdicts are logged to for the digests
+    pub llm_verdict_log_path: String,
+    /// File the digests are appended to, empty to post them in the admin channel
+    pub llm_digest_path: String,
+    /// Messages starting with any of these comma-separated prefixes aren't played,
+    /// e.g. commands for other bots
+    pub ignored_prefixes: String,
+    /// Whether messages mentioning other bots, or replying to messages that
+    /// aren't words, are left out of the game
+    pub ignore_side_conversations: bool,
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub multi_word_policy: MultiWordPolicy,
+    pub word_inner_chars: String,
+    /// Letters a played word may consist of, compared after lowercasing; empty allows any letter
+    pub allowed_alphabet: String,
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub rule_mode: RuleMode,
+    /// Whether a↔ä, a↔å and o↔ö changes count as the changed letter, are free, or are forbidden
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub diacritic_policy: DiacriticPolicy,
+    pub min_word_length: usize,
+    pub max_word_length: usize,
+    pub allow_consecutive_turns: bool,
+    /// Whether anyone may play at any time, or players who joined with /join take turns
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub game_turn_mode: TurnMode,
+    /// Seconds the player on turn has before the turn passes on, 0 to wait however long
+    pub turn_skip_secs: u64,
+    /// File the turn order is kept in, so restarts go on with the same players
+    pub turn_order_path: String,
+    /// File the game is saved to on shutdown, empty to start a new game after a restart
+    pub game_snapshot_path: String,
+    pub accept_proper_nouns: bool,
+    /// Whether unknown words only count as proper nouns if the player capitalized them
+    pub require_capitalization: bool,
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub language: Language,
+    pub rules_ephemeral: bool,
+    pub game_idle_reset_secs: u64,
+    /// Seconds of silence after which the bot plays a word itself, 0 to never
+    pub bot_plays_after_secs: u64,
+    pub history_size: usize,
+    /// How many recent messages /why remembers the handling of, 0 to remember none
+    pub audit_log_size: usize,
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub pending_policy: PendingPolicy,
+    /// How many words may wait for a pending word under the queue policy
+    pub pending_queue_size: usize,
+    /// Seconds a queued word waits before it's played against the pending word anyway
+    pub pending_queue_timeout_secs: u64,
+    /// Port of the `/health` endpoint, 0 to disable it
+    pub health_port: u16,
+    /// Address the read-only JSON API listens on, e.g. `0.0.0.0:8081`, empty to disable it
+    pub api_addr: String,
+    /// Bearer token the API's requests have to carry, empty to allow anyone
+    pub api_token: String,
+    /// Website origin allowed to read the API in a browser, empty for none
+    pub api_cors_origin: String,
+    /// How long the actors have to wrap up when the bot shuts down
+    pub shutdown_grace_secs: u64,
+    /// Mailbox capacity of the actors that receive bursts of messages
+    pub mailbox_capacity: usize,
+    /// How many words of a game may be validated at once, 0 for any number
+    pub validation_concurrency: usize,
+    /// Words being validated and waiting beyond which new ones are dropped, 0 to never drop
+    pub validation_shed_limit: usize,
+    /// Minimum time between reaction API calls in milliseconds
+    pub reaction_interval_ms: u64,
+    /// What happens to the reactions of the words when the game resets
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub reset_reactions: ResetReactions,
+    /// Words each player may have accepted per day, 0 for no limit
+    pub max_words_per_user_per_day: usize,
+    /// Reaction to words over the daily limit
+    pub daily_limit_reaction: char,
+    /// Time zone whose midnight starts a new day for the word limit
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub timezone: Tz,
+    /// Seconds a player has to wait after an accepted word, 0 for no cooldown
+    pub user_cooldown_secs: u64,
+    /// Reaction to words played before the cooldown passed
+    pub cooldown_reaction: char,
+    /// Whether words rejected by the daily limit or the cooldown get a reply explaining why
+    pub limit_replies: bool,
+    /// Whether words with letters outside the allowed alphabet get a reply naming them
+    pub alphabet_replies: bool,
+    /// Reaction to words played while the game is paused with /pause
+    pub pause_reaction: char,
+    /// Reaction to words rejected only because they were already played in the game
+    pub already_used_reaction: char,
+    /// Whether words rejected as not being words get a reply suggesting similar dictionary words
+    pub suggest_words: bool,
+    /// Whether unknown words a letter away from a single word that continues the chain are
+    /// taken for typos, getting `typo_reaction` instead of going to the LLM
+    pub typo_check: bool,
+    /// Reaction to words taken for typos
+    pub typo_reaction: char,
+    /// Whether unknown words that don't look like words are rejected instead of going to the LLM
+    pub plausibility_filter: bool,
+    /// Most consonants in a row in a plausible word
+    pub plausibility_max_consonants: usize,
+    /// Most times the same letter may be repeated in a row in a plausible word
+    pub plausibility_max_repeats: usize,
+    /// Fewest letter triples of a plausible word that have to be found in the dictionary
+    pub plausibility_min_known_trigrams: usize,
+    /// Largest word list /dict bulkcheck accepts, in kilobytes
+    pub bulk_check_max_kb: usize,
+    /// Players whose votes overturn a verdict, 0 to never let players overturn verdicts
+    pub dispute_votes: usize,
+    /// Seconds after a verdict during which votes against it are counted
+    pub dispute_window_secs: u64,
+    /// Reaction voting to accept a rejected word
+    pub dispute_accept_reaction: char,
+    /// Reaction voting to reject an accepted word
+    pub dispute_reject_reaction: char,
+    /// Points for every accepted word
+    pub score_base_points: u32,
+    /// Points for every letter of an accepted word
+    pub score_per_letter: u32,
+    /// Letters worth `score_rare_letter_bonus` extra each
+    pub score_rare_letters: String,
+    pub score_rare_letter_bonus: u32,
+    /// Points for starting with a different letter than the previous word
+    pub score_rut_bonus: u32,
+    /// Whether weekly scores are posted and reset once a week
+    pub weekly_reset: bool,
+    /// Day and time, in `timezone`, when a new week starts
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub weekly_reset_day: Weekday,
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub weekly_reset_time: NaiveTime,
+    /// File the final standings of each week are appended to
+    pub weekly_archive_path: String,
+    /// Whether messages that aren't word-shaped are logged too, not just the played words
+    pub log_message_content: bool,
+    /// Servers with a game of their own, keyed by guild ID; empty to play only in `channel_id`
+    pub guilds: BTreeMap<String, GuildSettings>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            discord_token: String::new(),
+            gemini_api_key: String::new(),
+            channel_id: 0,
+            game_thread_id: 0,
+            follow_threads: false,
+            admin_channel_id: 0,
+            strict_permissions: false,
+            dev_guild_id: 0,
+            dictionary_path: "./data/finnish_words.txt".to_string(),
+            extra_dictionary_paths: String::new(),
+            download_dictionary: false,
+            dictionary_url: String::new(),
+            dictionary_sha256: String::new(),
+            lenient_dictionary: false,
+            inflection_rules: DEFAULT_INFLECTION_RULES.to_string(),
+            remote_dictionary: String::new(),
+            remote_dictionary_timeout_ms: 3000,
+            remote_dictionary_per_minute: 30,
+            blocklist_path: String::new(),
+            bot_activity: "Finnish Word Game".to_string(),
+            dynamic_presence: true,
+            announce_on_ready: false,
+            announce_interval_secs: 3600,
+            announce_log_path: "./data/announcements.json".to_string(),
+            milestone_interval: 0,
+            chain_break_length: 0,
+            violations_break_chain: false,
+            restart_after_break: true,
+            webhook_url: String::new(),
+            webhook_events: "word_accepted,milestone,record,reset".to_string(),
+            status_message: false,
+            status_message_path: "./data/status_messages.json".to_string(),
+            offline_status: false,
+            llm_model: "gemini-pro".to_string(),
+            llm_fallback_reset_secs: 600,
+            llm_startup_check: true,
+            llm_batch_size: 2,
+            batch_timeout_secs: 86400, // 24 hours
+            llm_batch_mode: LlmBatchMode::default(),
+            llm_daily_budget: 0,
+            llm_budget_fallback: BudgetFallback::default(),
+            llm_max_age_secs: 3600,
+            llm_expired_fallback: BudgetFallback::default(),
+            llm_budget_path: "./data/llm_budget.json".to_string(),
+            llm_cache_path: "./data/llm_cache.json".to_string(),
+            llm_digest_interval_secs: 7 * 86400,
+            llm_verdict_log_path: "./data/llm_verdicts.jsonl".to_string(),
+            llm_digest_path: String::new(),
+            ignored_prefixes: "!".to_string(),
+            ignore_side_conversations: false,
+            multi_word_policy: MultiWordPolicy::default(),
+            word_inner_chars: "-'".to_string(),
+            allowed_alphabet: "abcdefghijklmnopqrstuvwxyzåäö-".to_string(),
+            rule_mode: RuleMode::default(),
+            diacritic_policy: DiacriticPolicy::default(),
+            min_word_length: 0,
+            max_word_length: 0,
+            allow_consecutive_turns: true,
+            game_turn_mode: TurnMode::default(),
+            turn_skip_secs: 600,
+            turn_order_path: "./data/turn_order.json".to_string(),
+            game_snapshot_path: "./data/game_snapshot.json".to_string(),
+            accept_proper_nouns: true,
+            require_capitalization: false,
+            language: Language::default(),
+            rules_ephemeral: true,
+            game_idle_reset_secs: 0,
+            bot_plays_after_secs: 0,
+            history_size: 100,
+            audit_log_size: 500,
+            pending_policy: PendingPolicy::default(),
+            pending_queue_size: DEFAULT_QUEUE_SIZE,
+            pending_queue_timeout_secs: DEFAULT_QUEUE_TIMEOUT.as_secs(),
+            health_port: 0,
+            api_addr: String::new(),
+            api_token: String::new(),
+            api_cors_origin: String::new(),
+            shutdown_grace_secs: 10,
+            mailbox_capacity: DEFAULT_MAILBOX_CAPACITY,
+            validation_concurrency: 8,
+            validation_shed_limit: 200,
+            reaction_interval_ms: DEFAULT_REACTION_INTERVAL.as_millis() as u64,
+            reset_reactions: ResetReactions::default(),
+            max_words_per_user_per_day: 0,
+            daily_limit_reaction: EMOJI_NO_ENTRY,
+            timezone: chrono_tz::Europe::Helsinki,
+            user_cooldown_secs: 0,
+            cooldown_reaction: EMOJI_TIMER,
+            limit_replies: true,
+            alphabet_replies: false,
+            pause_reaction: EMOJI_SLEEPING,
+            already_used_reaction: EMOJI_REPEAT,
+            suggest_words: false,
+            typo_check: false,
+            typo_reaction: EMOJI_PENCIL,
+            plausibility_filter: false,
+            plausibility_max_consonants: 5,
+            plausibility_max_repeats: 3,
+            plausibility_min_known_trigrams: 1,
+            bulk_check_max_kb: 1024,
+            dispute_votes: 0,
+            dispute_window_secs: 600,
+            dispute_accept_reaction: '👍',
+            dispute_reject_reaction: '👎',
+            score_base_points: 0,
+            score_per_letter: 1,
+            score_rare_letters: "äöy".to_string(),
+            score_rare_letter_bonus: 1,
+            score_rut_bonus: 2,
+            weekly_reset: true,
+            weekly_reset_day: Weekday::Mon,
+            weekly_reset_time: NaiveTime::MIN,
+            weekly_archive_path: "./data/weekly_standings.jsonl".to_string(),
+            log_message_content: false,
+            guilds: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// The models in `llm_model`, the preferred one first
+    pub fn llm_models(&self) -> Vec<String> {
+        self.llm_model
+            .split(',')
+            .map(str::trim)
+            .filter(|model| !model.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// The prefixes in `ignored_prefixes`
+    pub fn ignored_prefix_list(&self) -> Vec<&str> {
+        self.ignored_prefixes
+            .split(',')
+            .map(str::trim)
+            .filter(|prefix| !prefix.is_empty())
+            .collect()
+    }
+
+    /// Every word list to load, `dictionary_path` first
+    pub fn dictionary_paths(&self) -> Vec<String> {
+        std::iter::once(self.dictionary_path.as_str())
+            .chain(self.extra_dictionary_paths.split(','))
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// The configuration of each server in `guilds`, with its settings applied
+    ///
+    /// Guild IDs that aren't numbers are left out; they are reported when the
+    /// configuration is loaded.
+    pub fn guild_configs(&self) -> Vec<(u64, Config)> {
+        self.guilds
+            .iter()
+            .filter_map(|(guild_id, settings)| {
+                let guild_id = guild_id.trim().parse().ok()?;
+                Some((guild_id, self.for_guild(guild_id, settings)))
+            })
+            .collect()
+    }
+
+    /// This configuration with a server's settings applied
+    ///
+    /// Each server archives its weekly standings, keeps its turn order and
+    /// status message and saves its game in files of its own, named after
+    /// `weekly_archive_path`, `turn_order_path`, `status_message_path` and
+    /// `game_snapshot_path` with the guild ID before the extension, so the
+    /// games never write the same file.
+    pub fn for_guild(&self, guild_id: u64, settings: &GuildSettings) -> Config {
+        Config {
+            channel_id: settings.channel_id,
+            game_thread_id: settings.game_thread_id,
+            rule_mode: settings.rule_mode.unwrap_or(self.rule_mode),
+            language: settings.language.unwrap_or(self.language),
+            pause_reaction: settings.pause_reaction.unwrap_or(self.pause_reaction),
+            already_used_reaction: settings
+                .already_used_reaction
+                .unwrap_or(self.already_used_reaction),
+            llm_batch_mode: settings.llm_batch_mode.unwrap_or(self.llm_batch_mode),
+            weekly_archive_path: guild_path(&self.weekly_archive_path, guild_id),
+            turn_order_path: guild_path(&self.turn_order_path, guild_id),
+            status_message_path: guild_path(&self.status_message_path, guild_id),
+            game_snapshot_path: guild_path(&self.game_snapshot_path, guild_id),
+            guilds: BTreeMap::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Human-readable overview of the settings, one per line, with secrets masked
+    pub fn summary(&self) -> String {
+        let secret = |value: &str| if value.is_empty() { "not set" } else { "set" };
+        [
+            format!("discord_token: {}", secret(&self.discord_token)),
+            format!("gemini_api_key: {}", secret(&self.gemini_api_key)),
+            format!("channel_id: {}", self.channel_id),
+            format!("game_thread_id: {}", self.game_thread_id),
+            format!("follow_threads: {}", self.follow_threads),
+            format!("admin_channel_id: {}", self.admin_channel_id),
+            format!("strict_permissions: {}", self.strict_permissions),
+            format!("dev_guild_id: {}", self.dev_guild_id),
+            format!("dictionary_path: {}", self.dictionary_path),
+            format!("extra_dictionary_paths: {}", self.extra_dictionary_paths),
+            format!("download_dictionary: {}", self.download_dictionary),
+            format!("dictionary_url: {}", self.dictionary_url),
+            format!("dictionary_sha256: {}", self.dictionary_sha256),
+            format!("lenient_dictionary: {}", self.lenient_dictionary),
+            format!("inflection_rules: {}", self.inflection_rules),
+            format!("remote_dictionary: {}", self.remote_dictionary),
+            format!(
+                "remote_dictionary_timeout_ms: {}",
+                self.remote_dictionary_timeout_ms
+            ),
+            format!(
+                "remote_dictionary_per_minute: {}",
+                self.remote_dictionary_per_minute
+            ),
+            format!("blocklist_path: {}", self.blocklist_path),
+            format!("bot_activity: {}", self.bot_activity),
+            format!("dynamic_presence: {}", self.dynamic_presence),
+            format!("announce_on_ready: {}", self.announce_on_ready),
+            format!("announce_interval_secs: {}", self.announce_interval_secs),
+            format!("announce_log_path: {}", self.announce_log_path),
+            format!("milestone_interval: {}", self.milestone_interval),
+            format!("chain_break_length: {}", self.chain_break_length),
+            format!("violations_break_chain: {}", self.violations_break_chain),
+            format!("restart_after_break: {}", self.restart_after_break),
+            format!("webhook_url: {}", secret(&self.webhook_url)),
+            format!("webhook_events: {}", self.webhook_events),
+            format!("status_message: {}", self.status_message),
+            format!("status_message_path: {}", self.status_message_path),
+            format!("offline_status: {}", self.offline_status),
+            format!("llm_model: {}", self.llm_model),
+            format!("llm_fallback_reset_secs: {}", self.llm_fallback_reset_secs),
+            format!("llm_startup_check: {}", self.llm_startup_check),
+            format!("llm_batch_size: {}", self.llm_batch_size),
+            format!("batch_timeout_secs: {}", self.batch_timeout_secs),
+            format!("llm_batch_mode: {:?}", self.llm_batch_mode),
+            format!("llm_daily_budget: {}", self.llm_daily_budget),
+            format!("llm_budget_fallback: {:?}", self.llm_budget_fallback),
+            format!("llm_max_age_secs: {}", self.llm_max_age_secs),
+            format!("llm_expired_fallback: {:?}", self.llm_expired_fallback),
+            format!("llm_budget_path: {}", self.llm_budget_path),
+            format!("llm_cache_path: {}", self.llm_cache_path),
+            format!(
+                "llm_digest_interval_secs: {}",
+                self.llm_digest_interval_secs
+            ),
+            format!("llm_verdict_log_path: {}", self.llm_verdict_log_path),
+            format!("llm_digest_path: {}", self.llm_digest_path),
+            format!("ignored_prefixes: {}", self.ignored_prefixes),
+            format!(
+                "ignore_side_conversations: {}",
+                self.ignore_side_conversations
+            ),
+            format!("multi_word_policy: {:?}", self.multi_word_policy),
+            format!("word_inner_chars: {}", self.word_inner_chars),
+            format!("allowed_alphabet: {}", self.allowed_alphabet),
+            format!("rule_mode: {:?}", self.rule_mode),
+            format!("diacritic_policy: {:?}", self.diacritic_policy),
+            format!("min_word_length: {}", self.min_word_length),
+            format!("max_word_length: {}", self.max_word_length),
+            format!("allow_consecutive_turns: {}", self.allow_consecutive_turns),
+            format!("game_turn_mode: {:?}", self.game_turn_mode),
+            format!("turn_skip_secs: {}", self.turn_skip_secs),
+            format!("turn_order_path: {}", self.turn_order_path),
+            format!("game_snapshot_path: {}", self.game_snapshot_path),
+            format!("accept_proper_nouns: {}", self.accept_proper_nouns),
+            format!("require_capitalization: {}", self.require_capitalization),
+            format!("language: {:?}", self.language),
+            format!("rules_ephemeral: {}", self.rules_ephemeral),
+            format!("game_idle_reset_secs: {}", self.game_idle_reset_secs),
+            format!("bot_plays_after_secs: {}", self.bot_plays_after_secs),
+            format!("history_size: {}", self.history_size),
+            format!("audit_log_size: {}", self.audit_log_size),
+            format!("pending_policy: {:?}", self.pending_policy),
+            format!("pending_queue_size: {}", self.pending_queue_size),
+            format!(
+                "pending_queue_timeout_secs: {}",
+                self.pending_queue_timeout_secs
+            ),
+            format!("health_port: {}", self.health_port),
+            format!("api_addr: {}", self.api_addr),
+            format!("api_token: {}", secret(&self.api_token)),
+            format!("api_cors_origin: {}", self.api_cors_origin),
+            format!("shutdown_grace_secs: {}", self.shutdown_grace_secs),
+            format!("mailbox_capacity: {}", self.mailbox_capacity),
+            format!("validation_concurrency: {}", self.validation_concurrency),
+            format!("validation_shed_limit: {}", self.validation_shed_limit),
+            format!("reaction_interval_ms: {}", self.reaction_interval_ms),
+            format!("reset_reactions: {:?}", self.reset_reactions),
+            format!(
+                "max_words_per_user_per_day: {}",
+                self.max_words_per_user_per_day
+            ),
+            format!("daily_limit_reaction: {}", self.daily_limit_reaction),
+            format!("timezone: {}", self.timezone),
+            format!("user_cooldown_secs: {}", self.user_cooldown_secs),
+            format!("cooldown_reaction: {}", self.cooldown_reaction),
+            format!("limit_replies: {}", self.limit_replies),
+            format!("alphabet_replies: {}", self.alphabet_replies),
+            format!("pause_reaction: {}", self.pause_reaction),
+            format!("already_used_reaction: {}", self.already_used_reaction),
+            format!("suggest_words: {}", self.suggest_words),
+            format!("typo_check: {}", self.typo_check),
+            format!("typo_reaction: {}", self.typo_reaction),
+            format!("plausibility_filter: {}", self.plausibility_filter),
+            format!(
+                "plausibility_max_consonants: {}",
+                self.plausibility_max_consonants
+            ),
+            format!(
+                "plausibility_max_repeats: {}",
+                self.plausibility_max_repeats
+            ),
+            format!(
+                "plausibility_min_known_trigrams: {}",
+                self.plausibility_min_known_trigrams
+            ),
+            format!("bulk_check_max_kb: {}", self.bulk_check_max_kb),
+            format!("dispute_votes: {}", self.dispute_votes),
+            format!("dispute_window_secs: {}", self.dispute_window_secs),
+            format!("dispute_accept_reaction: {}", self.dispute_accept_reaction),
+            format!("dispute_reject_reaction: {}", self.dispute_reject_reaction),
+            format!("score_base_points: {}", self.score_base_points),
+            format!("score_per_letter: {}", self.score_per_letter),
+            format!("score_rare_letters: {}", self.score_rare_letters),
+            format!("score_rare_letter_bonus: {}", self.score_rare_letter_bonus),
+            format!("score_rut_bonus: {}", self.score_rut_bonus),
+            format!("weekly_reset: {}", self.weekly_reset),
+            format!("weekly_reset_day: {}", self.weekly_reset_day),
+            format!("weekly_reset_time: {}", self.weekly_reset_time),
+            format!("weekly_archive_path: {}", self.weekly_archive_path),
+            format!("log_message_content: {}", self.log_message_content),
+            format!(
+                "guilds: {}",
+                if self.guilds.is_empty() {
+                    "none".to_string()
+                } else {
+                    self.guilds.keys().cloned().collect::<Vec<_>>().join(", ")
+                }
+            ),
+        ]
+        .join("\n")
+    }
+}
+
+/// Default location of the optional configuration file
+pub const DEFAULT_CONFIG_FILE: &str = "./sanabotti.toml";
+
+/// Environment variable that points to the configuration file
+pub const CONFIG_FILE_VAR: &str = "SANABOTTI_CONFIG";
+
+/// Values given on the command line, keyed by the variable they override
+pub type Overrides = HashMap<&'static str, String>;
+
+/// Looks up configuration variables by name
+///
+/// Abstracted so that loading can be tested without touching the process environment.
+type Vars<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+fn process_env(name: &str) -> Option<String> {
+    env::var(name).ok()
+}
+
+/// Deserialize a value from its string form using [`FromStr`]
+fn deserialize_from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let value = String::deserialize(deserializer)?;
+    value.parse().map_err(de::Error::custom)
+}
+
+/// Like [`deserialize_from_str`], for settings that may be left out
+fn deserialize_option_from_str<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    deserialize_from_str(deserializer).map(Some)
+}
+
+/// Collects every configuration problem so they can be reported together
+#[derive(Default)]
+struct Problems(Vec<ConfigProblem>);
+
+impl Problems {
+    fn push(&mut self, problem: ConfigProblem) {
+        self.0.push(problem);
+    }
+
+    fn missing(&mut self, name: &str, help: &str) {
+        self.push(ConfigProblem::variable(name, None, "not set", help));
+    }
+
+    fn invalid(&mut self, name: &str, value: &str, label: &str, help: &str) {
+        self.push(ConfigProblem::variable(name, Some(value), label, help));
+    }
+
+    /// Parse an optional variable, falling back to a default when it's not set or invalid
+    fn parse<T: FromStr>(&mut self, vars: Vars, name: &str, default: T, help: &str) -> T {
+        match vars(name) {
+            Some(value) => match value.trim().parse::<T>() {
+                Ok(parsed) => parsed,
+                Err(_) => {
+                    self.invalid(name, &value, "can't be parsed", help);
+                    default
+                }
+            },
+            None => default,
+        }
+    }
+
+    /// Read a secret from `NAME`, or from the file named by `NAME_FILE`
+    ///
+    /// The file variant is meant for container secrets mounted as files.
+    fn secret(&mut self, vars: Vars, name: &str, default: String) -> String {
+        let file_var = format!("{}_FILE", name);
+        let path = vars(&file_var);
+
+        if let Some(value) = vars(name) {
+            if path.is_some() {
+                warn!("Both {} and {} are set, using {}", name, file_var, name);
+            }
+            return value;
+        }
+        let Some(path) = path else {
+            return default;
+        };
+
+        let help = format!(
+            "expected the path of a file containing the value of {}",
+            name
+        );
+        match fs::read_to_string(&path) {
+            Ok(content) if !content.trim().is_empty() => content.trim().to_string(),
+            Ok(_) => {
+                self.invalid(&file_var, &path, "the file is empty", &help);
+                default
+            }
+            Err(e) => {
+                self.invalid(&file_var, &path, &format!("can't be read: {}", e), &help);
+                default
+            }
+        }
+    }
+
+    /// Parse an optional boolean variable (true/false, yes/no, 1/0)
+    fn parse_bool(&mut self, vars: Vars, name: &str, default: bool) -> bool {
+        match vars(name) {
+            Some(value) => match value.trim().to_lowercase().as_str() {
+                "true" | "yes" | "1" => true,
+                "false" | "no" | "0" => false,
+                _ => {
+                    self.invalid(
+                        name,
+                        &value,
+                        "not a boolean",
+                        "expected true or false (or yes/no, 1/0)",
+                    );
+                    default
+                }
+            },
+            None => default,
+        }
+    }
+
+    fn into_result<T>(self, value: T) -> Result<T, Error> {
+        if self.0.is_empty() {
+            Ok(value)
+        } else {
+            Err(ConfigErrors { problems: self.0 }.into())
+        }
+    }
+}
+
+/// Parse the contents of a configuration file
+///
+/// Returns the configuration and the keys that weren't recognized.
+pub fn parse_config_file(
+    path: &str,
+    content: &str,
+) -> Result<(Config, Vec<String>), Box<ConfigProblem>> {
+    let mut unknown_keys = Vec::new();
+    let config = serde_ignored::deserialize(toml::Deserializer::new(content), |key| {
+        unknown_keys.push(key.to_string())
+    })
+    .map_err(|e| {
+        let span = e.span();
+        // Errors about a value point at it, so the key is at the start of the same line
+        let message = match span.as_ref().and_then(|span| key_at(content, span.start)) {
+            Some(key) => format!("`{}`: {}", key, e.message()),
+            None => e.message().to_string(),
+        };
+        Box::new(ConfigProblem {
+            message: format!("Invalid configuration file: {}", message),
+            source_code: NamedSource::new(path, content.to_string()),
+            label: message,
+            span: span.map(SourceSpan::from),
+            help: Some(
+                "See the Configuration section of the README for the available keys".to_string(),
+            ),
+        })
+    })?;
+
+    Ok((config, unknown_keys))
+}
+
+/// The key of the `key = value` line containing `offset`
+fn key_at(content: &str, offset: usize) -> Option<&str> {
+    let line_start = content[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let (key, _) = content[line_start..].split_once('=')?;
+    let key = key.trim().trim_matches('"');
+    (!key.is_empty()).then_some(key)
+}
+
+/// Read the configuration file, if there is one
+///
+/// The file named by `SANABOTTI_CONFIG` must exist, while the default
+/// `./sanabotti.toml` is optional.
+fn load_config_file(vars: Vars) -> Result<Option<Config>, Box<ConfigProblem>> {
+    let (path, explicit) = match vars(CONFIG_FILE_VAR) {
+        Some(path) => (path, true),
+        None => (DEFAULT_CONFIG_FILE.to_string(), false),
+    };
+
+    if !explicit && !Path::new(&path).exists() {
+        return Ok(None);
+    }
+
+    info!("Loading configuration file {}", path);
+    let content = fs::read_to_string(&path).map_err(|e| {
+        Box::new(ConfigProblem::variable(
+            CONFIG_FILE_VAR,
+            Some(&path),
+            &format!("can't be read: {}", e),
+            "expected the path of a TOML configuration file",
+        ))
+    })?;
+    let (config, unknown_keys) = parse_config_file(&path, &content)?;
+    for key in unknown_keys {
+        warn!("Ignoring unknown key '{}' in {}", key, path);
+    }
+
+    Ok(Some(config))
 }
 
+/// Load the full bot configuration, including the Discord settings
+///
+/// Every problem found is reported at once.
 pub fn load_config() -> miette::Result<Config> {
+    load_config_with(&Overrides::new())
+}
+
+/// Load the full bot configuration, with `overrides` taking precedence over
+/// both the environment and the configuration file
+pub fn load_config_with(overrides: &Overrides) -> miette::Result<Config> {
     info!("Loading configuration");
 
     // Load environment variables
     dotenv().ok();
 
-    // Get required environment variables
-    let discord_token = env::var("DISCORD_TOKEN")
-        .into_diagnostic()
-        .map_err(|_| Error::Config("Missing DISCORD_TOKEN".to_string()))?;
-
-    let channel_id = env::var("TARGET_CHANNEL_ID")
-        .into_diagnostic()
-        .map_err(|_| Error::Config("Missing TARGET_CHANNEL_ID".to_string()))?
-        .parse::<u64>()
-        .into_diagnostic()
-        .map_err(|_| Error::Config("Invalid TARGET_CHANNEL_ID".to_string()))?;
-
-    let dictionary_path =
-        env::var("DICTIONARY_FILE_PATH").unwrap_or_else(|_| "./data/finnish_words.txt".to_string());
-
-    let bot_activity = env::var("BOT_ACTIVITY").unwrap_or_else(|_| "Finnish Word Game".to_string());
-
-    let llm_batch_size = env::var("LLM_BATCH_SIZE")
-        .unwrap_or_else(|_| "2".to_string())
-        .parse::<usize>()
-        .into_diagnostic()
-        .map_err(|_| Error::Config("Invalid LLM_BATCH_SIZE".to_string()))?;
-
-    let batch_timeout_secs = env::var("LLM_BATCH_TIMEOUT_SECS")
-        .unwrap_or_else(|_| "86400".to_string()) // 24 hours default
-        .parse::<u64>()
-        .into_diagnostic()
-        .map_err(|_| Error::Config("Invalid LLM_BATCH_TIMEOUT_SECS".to_string()))?;
-
-    Ok(Config {
-        discord_token,
-        channel_id,
-        dictionary_path,
-        bot_activity,
-        llm_batch_size,
-        batch_timeout_secs,
-    })
+    let vars = with_overrides(overrides, &process_env);
+    Ok(load_from(&vars, true)?)
+}
+
+/// Read `LOG_FORMAT`, with `overrides` taking precedence over the environment
+///
+/// Read on its own since logging has to be set up before the rest of the
+/// configuration is loaded, so the configuration file isn't consulted.
+pub fn load_log_format(overrides: &Overrides) -> miette::Result<LogFormat> {
+    dotenv().ok();
+
+    let vars = with_overrides(overrides, &process_env);
+    Ok(log_format_from(&vars)?)
+}
+
+fn log_format_from(vars: Vars) -> Result<LogFormat, Error> {
+    let mut problems = Problems::default();
+    let format = problems.parse(
+        vars,
+        "LOG_FORMAT",
+        LogFormat::default(),
+        "expected `text` or `json`",
+    );
+    problems.into_result(format)
+}
+
+/// Look up variables in `overrides` first and then in `vars`
+fn with_overrides<'a>(
+    overrides: &'a Overrides,
+    vars: Vars<'a>,
+) -> impl Fn(&str) -> Option<String> + 'a {
+    move |name| overrides.get(name).cloned().or_else(|| vars(name))
+}
+
+/// Load everything except the Discord settings, which may be left empty
+///
+/// Used by tools that run the game without connecting to Discord. The
+/// dictionary path isn't checked since these tools can override it.
+pub fn load_game_config() -> miette::Result<Config> {
+    dotenv().ok();
+
+    Ok(load_from(&process_env, false)?)
+}
+
+/// Load the configuration file and apply the variables on top of it
+fn load_from(vars: Vars, bot: bool) -> Result<Config, Error> {
+    let mut problems = Problems::default();
+    let base = match load_config_file(vars) {
+        Ok(config) => config.unwrap_or_default(),
+        Err(problem) => {
+            problems.push(*problem);
+            Config::default()
+        }
+    };
+
+    let config = apply_vars(base, vars, bot, &mut problems);
+    problems.into_result(config)
+}
+
+/// Override the values in `base` with the variables that are set and check the result
+fn apply_vars(base: Config, vars: Vars, bot: bool, problems: &mut Problems) -> Config {
+    let config = Config {
+        discord_token: problems.secret(vars, "DISCORD_TOKEN", base.discord_token),
+        gemini_api_key: problems.secret(vars, "GEMINI_API_KEY", base.gemini_api_key),
+        channel_id: problems.parse(
+            vars,
+            "TARGET_CHANNEL_ID",
+            base.channel_id,
+            "expected the numeric ID of the channel, e.g. 123456789012345678",
+        ),
+        game_thread_id: problems.parse(
+            vars,
+            "GAME_THREAD_ID",
+            base.game_thread_id,
+            "expected the numeric ID of the thread, or 0 to play in the channel",
+        ),
+        follow_threads: problems.parse_bool(vars, "FOLLOW_THREADS", base.follow_threads),
+        admin_channel_id: problems.parse(
+            vars,
+            "ADMIN_CHANNEL_ID",
+            base.admin_channel_id,
+            "expected the numeric ID of the admin channel, or 0 to disable notifications",
+        ),
+        strict_permissions: problems.parse_bool(
+            vars,
+            "STRICT_PERMISSIONS",
+            base.strict_permissions,
+        ),
+        dev_guild_id: problems.parse(
+            vars,
+            "DEV_GUILD_ID",
+            base.dev_guild_id,
+            "expected the numeric ID of the guild, or 0 to register commands globally",
+        ),
+        dictionary_path: vars("DICTIONARY_FILE_PATH").unwrap_or(base.dictionary_path),
+        extra_dictionary_paths: vars("EXTRA_DICTIONARY_PATHS")
+            .unwrap_or(base.extra_dictionary_paths),
+        download_dictionary: problems.parse_bool(
+            vars,
+            "DOWNLOAD_DICTIONARY",
+            base.download_dictionary,
+        ),
+        dictionary_url: vars("DICTIONARY_URL").unwrap_or(base.dictionary_url),
+        dictionary_sha256: vars("DICTIONARY_SHA256").unwrap_or(base.dictionary_sha256),
+        lenient_dictionary: problems.parse_bool(
+            vars,
+            "LENIENT_DICTIONARY",
+            base.lenient_dictionary,
+        ),
+        inflection_rules: vars("INFLECTION_RULES").unwrap_or(base.inflection_rules),
+        remote_dictionary: vars("REMOTE_DICTIONARY").unwrap_or(base.remote_dictionary),
+        remote_dictionary_timeout_ms: problems.parse(
+            vars,
+            "REMOTE_DICTIONARY_TIMEOUT_MS",
+            base.remote_dictionary_timeout_ms,
+            "expected a number of milliseconds, e.g. 3000",
+        ),
+        remote_dictionary_per_minute: problems.parse(
+            vars,
+            "REMOTE_DICTIONARY_PER_MINUTE",
+            base.remote_dictionary_per_minute,
+            "expected a number of lookups, e.g. 30",
+        ),
+        blocklist_path: vars("BLOCKLIST_PATH").unwrap_or(base.blocklist_path),
+        bot_activity: vars("BOT_ACTIVITY").unwrap_or(base.bot_activity),
+        dynamic_presence: problems.parse_bool(vars, "DYNAMIC_PRESENCE", base.dynamic_presence),
+        announce_on_ready: problems.parse_bool(vars, "ANNOUNCE_ON_READY", base.announce_on_ready),
+        announce_interval_secs: problems.parse(
+            vars,
+            "ANNOUNCE_INTERVAL_SECS",
+            base.announce_interval_secs,
+            "expected a number of seconds, e.g. 3600",
+        ),
+        announce_log_path: vars("ANNOUNCE_LOG_PATH").unwrap_or(base.announce_log_path),
+        milestone_interval: problems.parse(
+            vars,
+            "MILESTONE_INTERVAL",
+            base.milestone_interval,
+            "expected a number of words, e.g. 100, or 0 to announce no milestones",
+        ),
+        chain_break_length: problems.parse(
+            vars,
+            "CHAIN_BREAK_LENGTH",
+            base.chain_break_length,
+            "expected a number of words, e.g. 10, or 0 for chains that never break",
+        ),
+        violations_break_chain: problems.parse_bool(
+            vars,
+            "VIOLATIONS_BREAK_CHAIN",
+            base.violations_break_chain,
+        ),
+        restart_after_break: problems.parse_bool(
+            vars,
+            "RESTART_AFTER_BREAK",
+            base.restart_after_break,
+        ),
+        webhook_url: problems.secret(vars, "WEBHOOK_URL", base.webhook_url),
+        webhook_events: vars("WEBHOOK_EVENTS").unwrap_or(base.webhook_events),
+        status_message: problems.parse_bool(vars, "STATUS_MESSAGE", base.status_message),
+        status_message_path: vars("STATUS_MESSAGE_PATH").unwrap_or(base.status_message_path),
+        offline_status: problems.parse_bool(vars, "OFFLINE_STATUS", base.offline_status),
+        llm_model: vars("LLM_MODEL").unwrap_or(base.llm_model),
+        llm_fallback_reset_secs: problems.parse(
+            vars,
+            "LLM_FALLBACK_RESET_SECS",
+            base.llm_fallback_reset_secs,
+            "expected a number of seconds, e.g. 600",
+        ),
+        llm_startup_check: problems.parse_bool(vars, "LLM_STARTUP_CHECK", base.llm_startup_check),
+        llm_batch_size: problems.parse(
+            vars,
+            "LLM_BATCH_SIZE",
+            base.llm_batch_size,
+            "expected a whole number of at least 1, e.g. 2",
+        ),
+        batch_timeout_secs: problems.parse(
+            vars,
+            "LLM_BATCH_TIMEOUT_SECS",
+            base.batch_timeout_secs,
+            "expected a number of seconds greater than 0, e.g. 86400",
+        ),
+        llm_digest_interval_secs: problems.parse(
+            vars,
+            "LLM_DIGEST_INTERVAL_SECS",
+            base.llm_digest_interval_secs,
+            "expected a number of seconds, e.g. 604800 for a week",
+        ),
+        llm_batch_mode: problems.parse(
+            vars,
+            "LLM_BATCH_MODE",
+            base.llm_batch_mode,
+            "expected `batched` or `immediate`",
+        ),
+        llm_daily_budget: problems.parse(
+            vars,
+            "LLM_DAILY_BUDGET",
+            base.llm_daily_budget,
+            "expected a whole number of words, 0 for no limit",
+        ),
+        llm_budget_fallback: problems.parse(
+            vars,
+            "LLM_BUDGET_FALLBACK",
+            base.llm_budget_fallback,
+            "expected `pending` or `reject`",
+        ),
+        llm_max_age_secs: problems.parse(
+            vars,
+            "LLM_MAX_AGE_SECS",
+            base.llm_max_age_secs,
+            "expected a number of seconds, e.g. 3600, or 0 to never expire words",
+        ),
+        llm_expired_fallback: problems.parse(
+            vars,
+            "LLM_EXPIRED_FALLBACK",
+            base.llm_expired_fallback,
+            "expected `pending` or `reject`",
+        ),
+        llm_budget_path: vars("LLM_BUDGET_PATH").unwrap_or(base.llm_budget_path),
+        llm_cache_path: vars("LLM_CACHE_PATH").unwrap_or(base.llm_cache_path),
+        llm_verdict_log_path: vars("LLM_VERDICT_LOG_PATH").unwrap_or(base.llm_verdict_log_path),
+        llm_digest_path: vars("LLM_DIGEST_PATH").unwrap_or(base.llm_digest_path),
+        ignored_prefixes: vars("IGNORED_PREFIXES").unwrap_or(base.ignored_prefixes),
+        ignore_side_conversations: problems.parse_bool(
+            vars,
+            "IGNORE_SIDE_CONVERSATIONS",
+            base.ignore_side_conversations,
+        ),
+        multi_word_policy: problems.parse(
+            vars,
+            "MULTI_WORD_POLICY",
+            base.multi_word_policy,
+            "expected `ignore`, `first_token` or `react_ignored`",
+        ),
+        word_inner_chars: vars("WORD_INNER_CHARS").unwrap_or(base.word_inner_chars),
+        allowed_alphabet: vars("ALLOWED_ALPHABET").unwrap_or(base.allowed_alphabet),
+        rule_mode: problems.parse(
+            vars,
+            "RULE_MODE",
+            base.rule_mode,
+            "expected `classic` or `anagram`",
+        ),
+        diacritic_policy: problems.parse(
+            vars,
+            "DIACRITIC_POLICY",
+            base.diacritic_policy,
+            "expected `distinct`, `free` or `forbidden`",
+        ),
+        min_word_length: problems.parse(
+            vars,
+            "MIN_WORD_LENGTH",
+            base.min_word_length,
+            "expected a number of letters, 0 for no limit",
+        ),
+        max_word_length: problems.parse(
+            vars,
+            "MAX_WORD_LENGTH",
+            base.max_word_length,
+            "expected a number of letters, 0 for no limit",
+        ),
+        allow_consecutive_turns: problems.parse_bool(
+            vars,
+            "ALLOW_CONSECUTIVE_TURNS",
+            base.allow_consecutive_turns,
+        ),
+        game_turn_mode: problems.parse(
+            vars,
+            "GAME_TURN_MODE",
+            base.game_turn_mode,
+            "expected `free` or `rotation`",
+        ),
+        turn_skip_secs: problems.parse(
+            vars,
+            "TURN_SKIP_SECS",
+            base.turn_skip_secs,
+            "expected a number of seconds, 0 to never skip a turn",
+        ),
+        turn_order_path: vars("TURN_ORDER_PATH").unwrap_or(base.turn_order_path),
+        game_snapshot_path: vars("GAME_SNAPSHOT_PATH").unwrap_or(base.game_snapshot_path),
+        accept_proper_nouns: problems.parse_bool(
+            vars,
+            "ACCEPT_PROPER_NOUNS",
+            base.accept_proper_nouns,
+        ),
+        require_capitalization: problems.parse_bool(
+            vars,
+            "REQUIRE_CAPITALIZATION",
+            base.require_capitalization,
+        ),
+        language: problems.parse(vars, "BOT_LANGUAGE", base.language, "expected `fi` or `en`"),
+        rules_ephemeral: problems.parse_bool(vars, "RULES_EPHEMERAL", base.rules_ephemeral),
+        game_idle_reset_secs: problems.parse(
+            vars,
+            "GAME_IDLE_RESET_SECS",
+            base.game_idle_reset_secs,
+            "expected a number of seconds, 0 to never reset",
+        ),
+        bot_plays_after_secs: problems.parse(
+            vars,
+            "BOT_PLAYS_AFTER_SECS",
+            base.bot_plays_after_secs,
+            "expected a number of seconds, 0 to never play",
+        ),
+        history_size: problems.parse(
+            vars,
+            "HISTORY_SIZE",
+            base.history_size,
+            "expected a number of words",
+        ),
+        audit_log_size: problems.parse(
+            vars,
+            "AUDIT_LOG_SIZE",
+            base.audit_log_size,
+            "expected a number of messages, 0 to keep none",
+        ),
+        pending_policy: problems.parse(
+            vars,
+            "PENDING_WORD_POLICY",
+            base.pending_policy,
+            "expected `optimistic`, `strict`, `rollback` or `queue`",
+        ),
+        pending_queue_size: problems.parse(
+            vars,
+            "PENDING_QUEUE_SIZE",
+            base.pending_queue_size,
+            "expected a number of words",
+        ),
+        pending_queue_timeout_secs: problems.parse(
+            vars,
+            "PENDING_QUEUE_TIMEOUT_SECS",
+            base.pending_queue_timeout_secs,
+            "expected a number of seconds",
+        ),
+        health_port: problems.parse(
+            vars,
+            "HEALTH_PORT",
+            base.health_port,
+            "expected a port number, 0 to disable the health endpoint",
+        ),
+        api_addr: vars("API_ADDR").unwrap_or(base.api_addr),
+        api_token: problems.secret(vars, "API_TOKEN", base.api_token),
+        api_cors_origin: vars("API_CORS_ORIGIN").unwrap_or(base.api_cors_origin),
+        shutdown_grace_secs: problems.parse(
+            vars,
+            "SHUTDOWN_GRACE_SECS",
+            base.shutdown_grace_secs,
+            "expected a number of seconds",
+        ),
+        mailbox_capacity: problems.parse(
+            vars,
+            "MAILBOX_CAPACITY",
+            base.mailbox_capacity,
+            "expected a number of messages",
+        ),
+        validation_concurrency: problems.parse(
+            vars,
+            "VALIDATION_CONCURRENCY",
+            base.validation_concurrency,
+            "expected a number of words, 0 for no limit",
+        ),
+        validation_shed_limit: problems.parse(
+            vars,
+            "VALIDATION_SHED_LIMIT",
+            base.validation_shed_limit,
+            "expected a number of words, 0 to never drop any",
+        ),
+        reaction_interval_ms: problems.parse(
+            vars,
+            "REACTION_INTERVAL_MS",
+            base.reaction_interval_ms,
+            "expected a number of milliseconds, 0 for no pause",
+        ),
+        reset_reactions: problems.parse(
+            vars,
+            "RESET_REACTIONS",
+            base.reset_reactions,
+            "expected `none`, `clear` or `mark`",
+        ),
+        max_words_per_user_per_day: problems.parse(
+            vars,
+            "MAX_WORDS_PER_USER_PER_DAY",
+            base.max_words_per_user_per_day,
+            "expected a number of words, 0 for no limit",
+        ),
+        daily_limit_reaction: problems.parse(
+            vars,
+            "DAILY_LIMIT_REACTION",
+            base.daily_limit_reaction,
+            "expected a single emoji, e.g. ⛔",
+        ),
+        timezone: problems.parse(
+            vars,
+            "TIMEZONE",
+            base.timezone,
+            "expected an IANA time zone name, e.g. Europe/Helsinki",
+        ),
+        user_cooldown_secs: problems.parse(
+            vars,
+            "USER_COOLDOWN_SECS",
+            base.user_cooldown_secs,
+            "expected a number of seconds, 0 for no cooldown",
+        ),
+        cooldown_reaction: problems.parse(
+            vars,
+            "COOLDOWN_REACTION",
+            base.cooldown_reaction,
+            "expected a single emoji, e.g. ⏲",
+        ),
+        limit_replies: problems.parse_bool(vars, "LIMIT_REPLIES", base.limit_replies),
+        alphabet_replies: problems.parse_bool(vars, "ALPHABET_REPLIES", base.alphabet_replies),
+        pause_reaction: problems.parse(
+            vars,
+            "PAUSE_REACTION",
+            base.pause_reaction,
+            "expected a single emoji, e.g. 💤",
+        ),
+        already_used_reaction: problems.parse(
+            vars,
+            "ALREADY_USED_REACTION",
+            base.already_used_reaction,
+            "expected a single emoji, e.g. 🔁",
+        ),
+        suggest_words: problems.parse_bool(vars, "SUGGEST_WORDS", base.suggest_words),
+        typo_check: problems.parse_bool(vars, "TYPO_CHECK", base.typo_check),
+        typo_reaction: problems.parse(
+            vars,
+            "TYPO_REACTION",
+            base.typo_reaction,
+            "expected a single emoji, e.g. ✏",
+        ),
+        plausibility_filter: problems.parse_bool(
+            vars,
+            "PLAUSIBILITY_FILTER",
+            base.plausibility_filter,
+        ),
+        plausibility_max_consonants: problems.parse(
+            vars,
+            "PLAUSIBILITY_MAX_CONSONANTS",
+            base.plausibility_max_consonants,
+            "expected a number of consonants",
+        ),
+        plausibility_max_repeats: problems.parse(
+            vars,
+            "PLAUSIBILITY_MAX_REPEATS",
+            base.plausibility_max_repeats,
+            "expected a number of repeats",
+        ),
+        plausibility_min_known_trigrams: problems.parse(
+            vars,
+            "PLAUSIBILITY_MIN_KNOWN_TRIGRAMS",
+            base.plausibility_min_known_trigrams,
+            "expected a number of letter triples",
+        ),
+        bulk_check_max_kb: problems.parse(
+            vars,
+            "BULK_CHECK_MAX_KB",
+            base.bulk_check_max_kb,
+            "expected a size in kilobytes",
+        ),
+        dispute_votes: problems.parse(
+            vars,
+            "DISPUTE_VOTES",
+            base.dispute_votes,
+            "expected a number of players, e.g. 3, or 0 to turn disputes off",
+        ),
+        dispute_window_secs: problems.parse(
+            vars,
+            "DISPUTE_WINDOW_SECS",
+            base.dispute_window_secs,
+            "expected a number of seconds, e.g. 600",
+        ),
+        dispute_accept_reaction: problems.parse(
+            vars,
+            "DISPUTE_ACCEPT_REACTION",
+            base.dispute_accept_reaction,
+            "expected a single emoji, e.g. 👍",
+        ),
+        dispute_reject_reaction: problems.parse(
+            vars,
+            "DISPUTE_REJECT_REACTION",
+            base.dispute_reject_reaction,
+            "expected a single emoji, e.g. 👎",
+        ),
+        score_base_points: problems.parse(
+            vars,
+            "SCORE_BASE_POINTS",
+            base.score_base_points,
+            "expected a number of points",
+        ),
+        score_per_letter: problems.parse(
+            vars,
+            "SCORE_PER_LETTER",
+            base.score_per_letter,
+            "expected a number of points",
+        ),
+        score_rare_letters: vars("SCORE_RARE_LETTERS").unwrap_or(base.score_rare_letters),
+        score_rare_letter_bonus: problems.parse(
+            vars,
+            "SCORE_RARE_LETTER_BONUS",
+            base.score_rare_letter_bonus,
+            "expected a number of points",
+        ),
+        score_rut_bonus: problems.parse(
+            vars,
+            "SCORE_RUT_BONUS",
+            base.score_rut_bonus,
+            "expected a number of points",
+        ),
+        weekly_reset: problems.parse_bool(vars, "WEEKLY_RESET", base.weekly_reset),
+        weekly_reset_day: problems.parse(
+            vars,
+            "WEEKLY_RESET_DAY",
+            base.weekly_reset_day,
+            "expected a weekday, e.g. `mon` or `sunday`",
+        ),
+        weekly_reset_time: problems.parse(
+            vars,
+            "WEEKLY_RESET_TIME",
+            base.weekly_reset_time,
+            "expected a time of day, e.g. 00:00 or 18:30",
+        ),
+        weekly_archive_path: vars("WEEKLY_ARCHIVE_PATH").unwrap_or(base.weekly_archive_path),
+        log_message_content: problems.parse_bool(
+            vars,
+            "LOG_MESSAGE_CONTENT",
+            base.log_message_content,
+        ),
+        // Only set in the configuration file
+        guilds: base.guilds,
+    };
+
+    check(&config, vars, bot, problems);
+    config
+}
+
+/// `path` with the guild ID before the extension, e.g. `weekly.111.jsonl`
+fn guild_path(path: &str, guild_id: u64) -> String {
+    let path = Path::new(path);
+    let mut name = path.file_stem().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", guild_id));
+    if let Some(extension) = path.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+    path.with_file_name(name).to_string_lossy().into_owned()
+}
+
+/// Semantic checks on the final values
+fn check(config: &Config, vars: Vars, bot: bool, problems: &mut Problems) {
+    if bot {
+        if config.discord_token.is_empty() {
+            problems.missing(
+                "DISCORD_TOKEN",
+                "set it (or DISCORD_TOKEN_FILE) to the bot token from the Discord developer portal",
+            );
+        }
+        if config.channel_id == 0 && config.guilds.is_empty() {
+            let help = "set it to the numeric ID of the game channel, e.g. 123456789012345678";
+            match vars("TARGET_CHANNEL_ID") {
+                None => problems.missing("TARGET_CHANNEL_ID", help),
+                Some(value) if value.trim() == "0" => {
+                    problems.invalid("TARGET_CHANNEL_ID", &value, "must not be 0", help)
+                }
+                // Unparsable values have been reported already
+                Some(_) => {}
+            }
+        }
+        match fs::File::open(&config.dictionary_path) {
+            Ok(_) => {}
+            // Downloaded at startup, or played with the built-in demo words
+            Err(e) if e.kind() == io::ErrorKind::NotFound && config.download_dictionary => {
+                if config.dictionary_url.is_empty() {
+                    problems.missing(
+                        "DICTIONARY_URL",
+                        "set it to the URL of a word list to download, or set DOWNLOAD_DICTIONARY=false",
+                    );
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound && cfg!(feature = "demo-words") => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => problems.invalid(
+                "DICTIONARY_FILE_PATH",
+                &config.dictionary_path,
+                &format!("can't be read: {}", e),
+                "no word list there yet: see the README for getting the Kotus list, \
+                 set DOWNLOAD_DICTIONARY=true and DICTIONARY_URL to download one at startup, \
+                 or build with --features demo-words to try the bot with a few hundred words",
+            ),
+            Err(e) => problems.invalid(
+                "DICTIONARY_FILE_PATH",
+                &config.dictionary_path,
+                &format!("can't be read: {}", e),
+                "expected the path of a word list with one word per line",
+            ),
+        }
+        if !config.blocklist_path.is_empty() {
+            if let Err(e) = fs::File::open(&config.blocklist_path) {
+                problems.invalid(
+                    "BLOCKLIST_PATH",
+                    &config.blocklist_path,
+                    &format!("can't be read: {}", e),
+                    "expected the path of a list of banned words, one per line, or leave it empty",
+                );
+            }
+        }
+        if config.accept_proper_nouns && config.gemini_api_key.is_empty() {
+            problems.missing(
+                "GEMINI_API_KEY",
+                "set it (or GEMINI_API_KEY_FILE) to check proper nouns, or set ACCEPT_PROPER_NOUNS=false",
+            );
+        }
+    }
+
+    for (guild_id, settings) in &config.guilds {
+        let name = format!("guilds.{}", guild_id);
+        if !matches!(guild_id.trim().parse::<u64>(), Ok(id) if id != 0) {
+            problems.invalid(
+                &name,
+                guild_id,
+                "is not a guild ID",
+                "expected tables named after the numeric guild ID, e.g. [guilds.123456789012345678]",
+            );
+        }
+        if settings.channel_id == 0 {
+            problems.missing(
+                &format!("{}.channel_id", name),
+                "set it to the numeric ID of the server's game channel, e.g. 123456789012345678",
+            );
+        }
+    }
+
+    if config.dispute_votes != 0 && config.dispute_accept_reaction == config.dispute_reject_reaction
+    {
+        problems.invalid(
+            "DISPUTE_REJECT_REACTION",
+            &config.dispute_reject_reaction.to_string(),
+            "is the same as DISPUTE_ACCEPT_REACTION",
+            "use different emoji for accepting and rejecting words, e.g. 👍 and 👎",
+        );
+    }
+    if config.llm_models().is_empty() {
+        problems.invalid(
+            "LLM_MODEL",
+            &config.llm_model,
+            "names no model",
+            "expected model names in order of preference, e.g. gemini-pro,gemini-1.5-flash",
+        );
+    }
+    if config.llm_batch_size == 0 {
+        problems.invalid(
+            "LLM_BATCH_SIZE",
+            "0",
+            "must be at least 1",
+            "expected a whole number of at least 1, e.g. 2",
+        );
+    }
+    if config.mailbox_capacity == 0 {
+        problems.invalid(
+            "MAILBOX_CAPACITY",
+            "0",
+            "must be at least 1",
+            "expected a whole number of at least 1, e.g. 256",
+        );
+    }
+    if config.validation_concurrency != 0
+        && config.validation_shed_limit != 0
+        && config.validation_shed_limit <= config.validation_concurrency
+    {
+        problems.invalid(
+            "VALIDATION_SHED_LIMIT",
+            &config.validation_shed_limit.to_string(),
+            "must be larger than VALIDATION_CONCURRENCY",
+            "expected a number of words larger than VALIDATION_CONCURRENCY, or 0",
+        );
+    }
+    if config.batch_timeout_secs == 0 {
+        problems.invalid(
+            "LLM_BATCH_TIMEOUT_SECS",
+            "0",
+            "must be greater than 0",
+            "expected a number of seconds greater than 0, e.g. 86400",
+        );
+    }
+    if config.pending_queue_size == 0 {
+        problems.invalid(
+            "PENDING_QUEUE_SIZE",
+            "0",
+            "must be at least 1",
+            "expected a whole number of at least 1, e.g. 5",
+        );
+    }
+    if config.pending_queue_timeout_secs == 0 {
+        problems.invalid(
+            "PENDING_QUEUE_TIMEOUT_SECS",
+            "0",
+            "must be greater than 0",
+            "expected a number of seconds greater than 0, e.g. 60",
+        );
+    }
+    if config
+        .word_inner_chars
+        .chars()
+        .any(|c| c.is_alphanumeric() || c.is_whitespace())
+    {
+        problems.invalid(
+            "WORD_INNER_CHARS",
+            &config.word_inner_chars,
+            "contains letters, digits or whitespace",
+            "expected only punctuation characters, e.g. -'",
+        );
+    }
+    if config.allowed_alphabet.chars().any(char::is_whitespace) {
+        problems.invalid(
+            "ALLOWED_ALPHABET",
+            &config.allowed_alphabet,
+            "contains whitespace",
+            "expected the allowed letters written together, e.g. abcdefghijklmnopqrstuvwxyzåäö-",
+        );
+    }
+    if !config.remote_dictionary.is_empty()
+        && config.remote_dictionary != WIKTIONARY
+        && !config.remote_dictionary.starts_with("http://")
+        && !config.remote_dictionary.starts_with("https://")
+    {
+        problems.invalid(
+            "REMOTE_DICTIONARY",
+            &config.remote_dictionary,
+            "is neither `wiktionary` nor an http(s) URL",
+            "expected `wiktionary`, the URL of a lookup endpoint, or leave it empty",
+        );
+    }
+    if !config.dictionary_url.is_empty()
+        && !config.dictionary_url.starts_with("http://")
+        && !config.dictionary_url.starts_with("https://")
+    {
+        problems.invalid(
+            "DICTIONARY_URL",
+            &config.dictionary_url,
+            "is not an http(s) URL",
+            "expected the URL of a word list with one word per line, or leave it empty",
+        );
+    }
+    if !config.dictionary_sha256.is_empty()
+        && (config.dictionary_sha256.len() != 64
+            || !config
+                .dictionary_sha256
+                .chars()
+                .all(|c| c.is_ascii_hexdigit()))
+    {
+        problems.invalid(
+            "DICTIONARY_SHA256",
+            &config.dictionary_sha256,
+            "is not a SHA-256 hash",
+            "expected 64 hex digits, e.g. the output of `sha256sum words.txt`, or leave it empty",
+        );
+    }
+    if !config.webhook_url.is_empty()
+        && !config.webhook_url.starts_with("http://")
+        && !config.webhook_url.starts_with("https://")
+    {
+        problems.invalid(
+            "WEBHOOK_URL",
+            &config.webhook_url,
+            "is not an http(s) URL",
+            "expected the URL of the webhook, or leave it empty",
+        );
+    }
+    if !config.api_addr.is_empty() && config.api_addr.parse::<SocketAddr>().is_err() {
+        problems.invalid(
+            "API_ADDR",
+            &config.api_addr,
+            "is not an address",
+            "expected an IP address and a port, e.g. 0.0.0.0:8081, or leave it empty",
+        );
+    }
+    if !config.api_cors_origin.is_empty()
+        && config.api_cors_origin != "*"
+        && !config.api_cors_origin.starts_with("http://")
+        && !config.api_cors_origin.starts_with("https://")
+    {
+        problems.invalid(
+            "API_CORS_ORIGIN",
+            &config.api_cors_origin,
+            "is not an http(s) origin",
+            "expected the website's origin, e.g. https://example.com, `*` for any, or leave it empty",
+        );
+    }
+    if config.shutdown_grace_secs == 0 {
+        problems.invalid(
+            "SHUTDOWN_GRACE_SECS",
+            &config.shutdown_grace_secs.to_string(),
+            "must not be 0",
+            "expected the seconds the bot has to save its state when it shuts down, e.g. 10",
+        );
+    }
+    if config.bulk_check_max_kb == 0 || config.bulk_check_max_kb > ATTACHMENT_LIMIT / 1024 {
+        problems.invalid(
+            "BULK_CHECK_MAX_KB",
+            &config.bulk_check_max_kb.to_string(),
+            "is not within Discord's attachment limit",
+            &format!(
+                "expected a size in kilobytes from 1 to {}",
+                ATTACHMENT_LIMIT / 1024
+            ),
+        );
+    }
+    if let Err(e) = parse_events(&config.webhook_events) {
+        problems.invalid(
+            "WEBHOOK_EVENTS",
+            &config.webhook_events,
+            &e,
+            "expected comma-separated events out of word_accepted, milestone, record and reset",
+        );
+    }
+    if config.lenient_dictionary {
+        if let Err(e) = parse_rules(&config.inflection_rules) {
+            problems.invalid(
+                "INFLECTION_RULES",
+                &config.inflection_rules,
+                &e,
+                "expected comma-separated endings, each `suffix` or `suffix>replacement`, e.g. lla,ssa,oja>a",
+            );
+        }
+    }
+    if config.min_word_length > 0
+        && config.max_word_length > 0
+        && config.min_word_length > config.max_word_length
+    {
+        problems.invalid(
+            "MIN_WORD_LENGTH",
+            &config.min_word_length.to_string(),
+            "is greater than MAX_WORD_LENGTH",
+            "expected MIN_WORD_LENGTH to be at most MAX_WORD_LENGTH",
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::io::Write;
+
+    fn vars_from(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    const DICTIONARY: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/golden/simulate_dictionary.txt"
+    );
+
+    fn problem_messages(vars: Vars, bot: bool) -> Vec<String> {
+        match load_from(vars, bot) {
+            Err(Error::InvalidConfig(errors)) => errors
+                .problems
+                .into_iter()
+                .map(|problem| problem.message)
+                .collect(),
+            Err(e) => panic!("unexpected error: {}", e),
+            Ok(_) => Vec::new(),
+        }
+    }
+
+    fn write_config_file(content: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        file
+    }
+
+    #[test]
+    fn test_env_only() {
+        let vars = vars_from(&[
+            (CONFIG_FILE_VAR, "/nonexistent/sanabotti.toml"),
+            ("DISCORD_TOKEN", "token"),
+            ("GEMINI_API_KEY", "key"),
+            ("TARGET_CHANNEL_ID", "123"),
+            ("DICTIONARY_FILE_PATH", DICTIONARY),
+        ]);
+        // An explicitly named file has to exist
+        assert_eq!(
+            problem_messages(&vars, true),
+            vec![format!("Invalid {}", CONFIG_FILE_VAR)]
+        );
+
+        let vars = vars_from(&[
+            ("DISCORD_TOKEN", "token"),
+            ("TARGET_CHANNEL_ID", "123"),
+            ("DICTIONARY_FILE_PATH", DICTIONARY),
+            ("MIN_WORD_LENGTH", "3"),
+            ("ACCEPT_PROPER_NOUNS", "no"),
+            ("GAME_TURN_MODE", "Rotation"),
+        ]);
+        let config = load_from(&vars, true).unwrap();
+        assert_eq!(config.discord_token, "token");
+        assert_eq!(config.channel_id, 123);
+        assert_eq!(config.min_word_length, 3);
+        assert!(!config.accept_proper_nouns);
+        assert_eq!(config.game_turn_mode, TurnMode::Rotation);
+        assert_eq!(config.history_size, Config::default().history_size);
+
+        // Only the bot needs the Discord settings and the dictionary
+        assert!(load_from(&vars_from(&[]), false).is_ok());
+    }
+
+    #[test]
+    fn test_file_only() {
+        let file = write_config_file(&format!(
+            r#"
+            discord_token = "file-token"
+            gemini_api_key = "file-key"
+            channel_id = 42
+            min_word_length = 4
+            allow_consecutive_turns = false
+            pending_policy = "strict"
+            language = "en"
+            timezone = "Europe/Stockholm"
+            weekly_reset_day = "sun"
+            weekly_reset_time = "18:30"
+            dictionary_path = "{}"
+            "#,
+            DICTIONARY
+        ));
+        let path = file.path().to_str().unwrap().to_string();
+
+        let config = load_from(&vars_from(&[(CONFIG_FILE_VAR, &path)]), true).unwrap();
+        assert_eq!(config.discord_token, "file-token");
+        assert_eq!(config.channel_id, 42);
+        assert_eq!(config.min_word_length, 4);
+        assert!(!config.allow_consecutive_turns);
+        assert_eq!(config.pending_policy, PendingPolicy::Strict);
+        assert_eq!(config.language, Language::English);
+        assert_eq!(config.timezone, chrono_tz::Europe::Stockholm);
+        assert_eq!(config.weekly_reset_day, Weekday::Sun);
+        assert_eq!(
+            config.weekly_reset_time,
+            NaiveTime::from_hms_opt(18, 30, 0).unwrap()
+        );
+        // Keys missing from the file keep their defaults
+        assert_eq!(config.history_size, Config::default().history_size);
+    }
+
+    #[test]
+    fn test_guilds() {
+        let file = write_config_file(&format!(
+            r#"
+            discord_token = "file-token"
+            gemini_api_key = "file-key"
+            dictionary_path = "{}"
+            pause_reaction = "💤"
+            weekly_archive_path = "./data/weekly.jsonl"
+
+            [guilds.111]
+            channel_id = 1
+
+            [guilds.222]
+            channel_id = 2
+            game_thread_id = 3
+            rule_mode = "classic"
+            language = "en"
+            already_used_reaction = "♻"
+            llm_batch_mode = "immediate"
+            "#,
+            DICTIONARY
+        ));
+        let path = file.path().to_str().unwrap().to_string();
+
+        // No top-level channel is needed when the servers name theirs
+        let config = load_from(&vars_from(&[(CONFIG_FILE_VAR, &path)]), true).unwrap();
+        assert_eq!(config.channel_id, 0);
+        let guilds = config.guild_configs();
+        assert_eq!(guilds.len(), 2);
+
+        let (guild_id, first) = &guilds[0];
+        assert_eq!(*guild_id, 111);
+        assert_eq!(first.channel_id, 1);
+        assert_eq!(first.language, Language::Finnish);
+        assert_eq!(first.pause_reaction, '💤');
+        assert_eq!(first.weekly_archive_path, "./data/weekly.111.jsonl");
+        assert_eq!(first.turn_order_path, "./data/turn_order.111.json");
+        assert_eq!(first.status_message_path, "./data/status_messages.111.json");
+        assert_eq!(first.llm_batch_mode, LlmBatchMode::Batched);
+        assert!(first.guilds.is_empty());
+
+        let (guild_id, second) = &guilds[1];
+        assert_eq!(*guild_id, 222);
+        assert_eq!((second.channel_id, second.game_thread_id), (2, 3));
+        assert_eq!(second.rule_mode, RuleMode::Classic);
+        assert_eq!(second.language, Language::English);
+        assert_eq!(second.already_used_reaction, '♻');
+        assert_eq!(second.llm_batch_mode, LlmBatchMode::Immediate);
+        assert_eq!(second.weekly_archive_path, "./data/weekly.222.jsonl");
+        assert_eq!(second.history_size, config.history_size);
+
+        // Without servers the top-level channel is the only game
+        assert!(Config::default().guild_configs().is_empty());
+
+        let file = write_config_file(
+            "[guilds.general]\nchannel_id = 1\n\n[guilds.333]\nlanguage = \"en\"\n",
+        );
+        let path = file.path().to_str().unwrap().to_string();
+        assert_eq!(
+            problem_messages(&vars_from(&[(CONFIG_FILE_VAR, &path)]), false),
+            vec!["Missing guilds.333.channel_id", "Invalid guilds.general"]
+        );
+    }
+
+    #[test]
+    fn test_env_overrides_file() {
+        let file = write_config_file(
+            r#"
+            discord_token = "file-token"
+            gemini_api_key = "file-key"
+            channel_id = 42
+            min_word_length = 4
+            max_word_length = 12
+            "#,
+        );
+        let path = file.path().to_str().unwrap().to_string();
+
+        let vars = vars_from(&[
+            (CONFIG_FILE_VAR, &path),
+            ("DICTIONARY_FILE_PATH", DICTIONARY),
+            ("DISCORD_TOKEN", "env-token"),
+            ("MIN_WORD_LENGTH", "2"),
+        ]);
+        let config = load_from(&vars, true).unwrap();
+        assert_eq!(config.discord_token, "env-token");
+        assert_eq!(config.channel_id, 42);
+        assert_eq!(config.min_word_length, 2);
+        assert_eq!(config.max_word_length, 12);
+    }
+
+    #[test]
+    fn test_overrides_win() {
+        let file = write_config_file("channel_id = 42\nmin_word_length = 4\n");
+        let path = file.path().to_str().unwrap().to_string();
+        let env = vars_from(&[
+            ("DISCORD_TOKEN", "env-token"),
+            ("GEMINI_API_KEY", "env-key"),
+            ("DICTIONARY_FILE_PATH", "/nonexistent/words.txt"),
+            ("MIN_WORD_LENGTH", "2"),
+        ]);
+        let overrides = Overrides::from([
+            (CONFIG_FILE_VAR, path),
+            ("DICTIONARY_FILE_PATH", DICTIONARY.to_string()),
+            ("MIN_WORD_LENGTH", "3".to_string()),
+        ]);
+
+        let config = load_from(&with_overrides(&overrides, &env), true).unwrap();
+        assert_eq!(config.channel_id, 42);
+        assert_eq!(config.dictionary_path, DICTIONARY);
+        assert_eq!(config.min_word_length, 3);
+        assert_eq!(config.discord_token, "env-token");
+
+        let summary = config.summary();
+        assert!(summary.contains("channel_id: 42"), "{}", summary);
+        assert!(summary.contains("discord_token: set"), "{}", summary);
+        assert!(!summary.contains("env-token"), "{}", summary);
+        assert!(!summary.contains("env-key"), "{}", summary);
+    }
+
+    #[test]
+    fn test_log_format() {
+        assert_eq!(log_format_from(&vars_from(&[])).unwrap(), LogFormat::Text);
+        let vars = vars_from(&[("LOG_FORMAT", "JSON")]);
+        assert_eq!(log_format_from(&vars).unwrap(), LogFormat::Json);
+        assert!(log_format_from(&vars_from(&[("LOG_FORMAT", "xml")])).is_err());
+    }
+
+    #[test]
+    fn test_config_file_errors() {
+        let (_, unknown_keys) =
+            parse_config_file("sanabotti.toml", "history_size = 10\nhistroy_size = 20\n").unwrap();
+        assert_eq!(unknown_keys, vec!["histroy_size".to_string()]);
+
+        let content = "min_word_length = 3\nllm_batch_size = \"two\"\n";
+        let Err(problem) = parse_config_file("sanabotti.toml", content) else {
+            panic!("expected a configuration file error");
+        };
+        let ConfigProblem { message, span, .. } = *problem;
+        assert!(message.contains("`llm_batch_size`"), "{}", message);
+        assert!(message.contains("expected usize"), "{}", message);
+        let span = span.unwrap();
+        assert_eq!(
+            &content[span.offset()..span.offset() + span.len()],
+            "\"two\""
+        );
+    }
+
+    #[test]
+    fn test_missing_dictionary() {
+        let base = [
+            ("DISCORD_TOKEN", "token"),
+            ("GEMINI_API_KEY", "key"),
+            ("TARGET_CHANNEL_ID", "42"),
+            ("DICTIONARY_FILE_PATH", "/nonexistent/words.txt"),
+        ];
+        let with = |extra: &[(&'static str, &'static str)]| vars_from(&[&base[..], extra].concat());
+
+        let expected: Vec<&str> = if cfg!(feature = "demo-words") {
+            vec![]
+        } else {
+            vec!["Invalid DICTIONARY_FILE_PATH"]
+        };
+        assert_eq!(problem_messages(&with(&[]), true), expected);
+        assert_eq!(
+            problem_messages(&with(&[("DOWNLOAD_DICTIONARY", "true")]), true),
+            vec!["Missing DICTIONARY_URL"]
+        );
+        let download = with(&[
+            ("DOWNLOAD_DICTIONARY", "true"),
+            ("DICTIONARY_URL", "https://example.org/words.txt"),
+            (
+                "DICTIONARY_SHA256",
+                "9F86D081884C7D659A2FEAA0C55AD015A3BF4F1B2B0B822CD15D6C15B0F00A08",
+            ),
+        ]);
+        assert!(problem_messages(&download, true).is_empty());
+    }
+
+    #[test]
+    fn test_all_problems_reported() {
+        let vars = vars_from(&[
+            ("TARGET_CHANNEL_ID", "general"),
+            ("DICTIONARY_FILE_PATH", "/nonexistent/words.txt"),
+            ("LLM_MODEL", " , "),
+            ("LLM_BATCH_SIZE", "0"),
+            ("LLM_BATCH_TIMEOUT_SECS", "0"),
+            ("PENDING_QUEUE_SIZE", "0"),
+            ("MULTI_WORD_POLICY", "all"),
+            ("DIACRITIC_POLICY", "umlauts"),
+            ("ALLOW_CONSECUTIVE_TURNS", "maybe"),
+            ("GAME_TURN_MODE", "round_robin"),
+            ("RESET_REACTIONS", "delete"),
+            ("WORD_INNER_CHARS", "-a"),
+            ("TIMEZONE", "Helsinki"),
+            ("REMOTE_DICTIONARY", "wikipedia"),
+            ("DICTIONARY_URL", "ftp://example.org/words.txt"),
+            ("DICTIONARY_SHA256", "abc123"),
+            ("LENIENT_DICTIONARY", "true"),
+            ("INFLECTION_RULES", "lla,>a"),
+            ("VALIDATION_CONCURRENCY", "8"),
+            ("VALIDATION_SHED_LIMIT", "8"),
+            ("WEBHOOK_URL", "matrix.example.org/hook"),
+            ("WEBHOOK_EVENTS", "milestone,highscore"),
+            ("API_ADDR", "8081"),
+            ("API_CORS_ORIGIN", "example.com"),
+            ("SHUTDOWN_GRACE_SECS", "0"),
+            ("BULK_CHECK_MAX_KB", "0"),
+        ]);
+
+        let mut expected = vec![
+            "Invalid TARGET_CHANNEL_ID",
+            "Invalid MULTI_WORD_POLICY",
+            "Invalid DIACRITIC_POLICY",
+            "Invalid ALLOW_CONSECUTIVE_TURNS",
+            "Invalid GAME_TURN_MODE",
+            "Invalid RESET_REACTIONS",
+            "Invalid TIMEZONE",
+            "Missing DISCORD_TOKEN",
+            "Invalid DICTIONARY_FILE_PATH",
+            "Missing GEMINI_API_KEY",
+            "Invalid LLM_MODEL",
+            "Invalid LLM_BATCH_SIZE",
+            "Invalid VALIDATION_SHED_LIMIT",
+            "Invalid LLM_BATCH_TIMEOUT_SECS",
+            "Invalid PENDING_QUEUE_SIZE",
+            "Invalid WORD_INNER_CHARS",
+            "Invalid REMOTE_DICTIONARY",
+            "Invalid DICTIONARY_URL",
+            "Invalid DICTIONARY_SHA256",
+            "Invalid WEBHOOK_URL",
+            "Invalid API_ADDR",
+            "Invalid API_CORS_ORIGIN",
+            "Invalid SHUTDOWN_GRACE_SECS",
+            "Invalid BULK_CHECK_MAX_KB",
+            "Invalid WEBHOOK_EVENTS",
+            "Invalid INFLECTION_RULES",
+        ];
+        if cfg!(feature = "demo-words") {
+            // The built-in words stand in for the missing dictionary
+            expected.retain(|message| *message != "Invalid DICTIONARY_FILE_PATH");
+        }
+        assert_eq!(problem_messages(&vars, true), expected);
+
+        // A broken file doesn't hide the problems in the variables
+        let file = write_config_file("history_size = \"many\"\n");
+        let path = file.path().to_str().unwrap().to_string();
+        let vars = vars_from(&[(CONFIG_FILE_VAR, &path), ("MIN_WORD_LENGTH", "x")]);
+        let messages = problem_messages(&vars, false);
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].starts_with("Invalid configuration file: `history_size`"));
+        assert_eq!(messages[1], "Invalid MIN_WORD_LENGTH");
+    }
+
+    #[test]
+    fn test_secrets_from_files() {
+        let write_secret = |content: &str| {
+            let mut file = tempfile::NamedTempFile::new().unwrap();
+            file.write_all(content.as_bytes()).unwrap();
+            file
+        };
+        let token = write_secret("file-token\n");
+        let key = write_secret("  file-key  ");
+        let token_path = token.path().to_str().unwrap().to_string();
+        let key_path = key.path().to_str().unwrap().to_string();
+
+        let vars = vars_from(&[
+            ("DISCORD_TOKEN_FILE", &token_path),
+            ("GEMINI_API_KEY_FILE", &key_path),
+        ]);
+        let config = load_from(&vars, false).unwrap();
+        assert_eq!(config.discord_token, "file-token");
+        assert_eq!(config.gemini_api_key, "file-key");
+
+        // The plain variable wins over the file
+        let vars = vars_from(&[
+            ("DISCORD_TOKEN", "env-token"),
+            ("DISCORD_TOKEN_FILE", &token_path),
+        ]);
+        assert_eq!(load_from(&vars, false).unwrap().discord_token, "env-token");
+
+        let empty = write_secret(" \n");
+        let empty_path = empty.path().to_str().unwrap().to_string();
+        let vars = vars_from(&[
+            ("DISCORD_TOKEN_FILE", &empty_path),
+            ("GEMINI_API_KEY_FILE", "/nonexistent/gemini_key"),
+        ]);
+        assert_eq!(
+            problem_messages(&vars, false),
+            vec!["Invalid DISCORD_TOKEN_FILE", "Invalid GEMINI_API_KEY_FILE"]
+        );
+    }
 }
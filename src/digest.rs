@@ -0,0 +1,281 @@
+//! Digests of the LLM's verdicts, for folding the words it accepted into the dictionary
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::error::Result;
+
+/// One verdict of the LLM
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoggedVerdict {
+    pub at: DateTime<Utc>,
+    pub word: String,
+    /// Whether the word was accepted as a proper noun
+    pub accepted: bool,
+    pub explanation: String,
+}
+
+/// A line of the verdict log
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum LogLine {
+    Verdict(LoggedVerdict),
+    /// Every verdict up to `until` has been reported
+    Digest {
+        until: DateTime<Utc>,
+    },
+}
+
+/// The LLM's verdicts and the digests made of them, one JSON object per line
+///
+/// The digest lines tell what has been reported, so a restart doesn't report
+/// the same words again.
+#[derive(Debug, Clone)]
+pub struct VerdictLog {
+    path: PathBuf,
+}
+
+impl VerdictLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a verdict
+    pub fn record(&self, verdict: LoggedVerdict) -> Result<()> {
+        self.append(&LogLine::Verdict(verdict))
+    }
+
+    /// Note that every verdict up to `until` has been reported
+    pub fn mark_reported(&self, until: DateTime<Utc>) -> Result<()> {
+        self.append(&LogLine::Digest { until })
+    }
+
+    /// When the last digest ended, and the verdicts made after it
+    pub fn unreported(&self) -> Result<(Option<DateTime<Utc>>, Vec<LoggedVerdict>)> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok((None, Vec::new())),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut reported = None;
+        let mut verdicts = Vec::new();
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            match serde_json::from_str(line).map_err(io::Error::other)? {
+                LogLine::Verdict(verdict) => verdicts.push(verdict),
+                LogLine::Digest { until } => reported = reported.max(Some(until)),
+            }
+        }
+        // Verdicts logged while a digest was being made may come before its line
+        verdicts.retain(|verdict| reported.is_none_or(|until| verdict.at > until));
+        Ok((reported, verdicts))
+    }
+
+    fn append(&self, line: &LogLine) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let line = serde_json::to_string(line).map_err(io::Error::other)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", line)?;
+        Ok(())
+    }
+}
+
+/// What the LLM decided since the last digest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Digest {
+    /// When the previous digest ended, None before the first one
+    pub since: Option<DateTime<Utc>>,
+    pub until: DateTime<Utc>,
+    /// Words accepted as proper nouns, once each and in alphabetical order
+    pub accepted: Vec<LoggedVerdict>,
+    /// How many different words were rejected
+    pub rejected: usize,
+}
+
+impl Digest {
+    /// Collect the verdicts made after the last digest, up to `until`
+    pub fn collect(log: &VerdictLog, until: DateTime<Utc>) -> Result<Self> {
+        let (since, verdicts) = log.unreported()?;
+        let mut accepted = BTreeMap::new();
+        let mut rejected = BTreeMap::new();
+        for verdict in verdicts.into_iter().filter(|verdict| verdict.at <= until) {
+            let words = if verdict.accepted {
+                &mut accepted
+            } else {
+                &mut rejected
+            };
+            words.entry(verdict.word.clone()).or_insert(verdict);
+        }
+
+        Ok(Self {
+            since,
+            until,
+            accepted: accepted.into_values().collect(),
+            rejected: rejected.len(),
+        })
+    }
+
+    /// Whether the LLM gave no verdicts at all in the period
+    pub fn is_empty(&self) -> bool {
+        self.accepted.is_empty() && self.rejected == 0
+    }
+
+    /// One line on the period and the counts, e.g. to post the digest with
+    pub fn summary(&self) -> String {
+        let format = |at: DateTime<Utc>| at.format("%Y-%m-%d %H:%M UTC").to_string();
+        let period = match self.since {
+            Some(since) => format!("{} – {}", format(since), format(self.until)),
+            None => format!("until {}", format(self.until)),
+        };
+        format!(
+            "LLM digest {}: {} accepted, {} rejected",
+            period,
+            self.accepted.len(),
+            self.rejected
+        )
+    }
+
+    /// The digest as Markdown, with one accepted word and its explanation per line
+    pub fn render(&self) -> String {
+        let mut text = format!("# {}\n\n", self.summary());
+        for verdict in &self.accepted {
+            text.push_str(&format!("- {}: {}\n", verdict.word, verdict.explanation));
+        }
+        text
+    }
+}
+
+/// Where the digests go
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DigestTarget {
+    /// Posted as a file in the admin channel
+    AdminChannel(u64),
+    /// Appended to a file
+    File(PathBuf),
+}
+
+impl DigestTarget {
+    /// The digest file if one is set, else the admin channel, None if neither is
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if !config.llm_digest_path.is_empty() {
+            Some(DigestTarget::File(PathBuf::from(&config.llm_digest_path)))
+        } else if config.admin_channel_id != 0 {
+            Some(DigestTarget::AdminChannel(config.admin_channel_id))
+        } else {
+            None
+        }
+    }
+}
+
+/// Append a digest to the digest file
+pub fn append_digest(path: &Path, digest: &Digest) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", digest.render())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    fn verdict(time: &str, word: &str, accepted: bool) -> LoggedVerdict {
+        LoggedVerdict {
+            at: at(time),
+            word: word.to_string(),
+            accepted,
+            explanation: format!("Selitys: {}", word),
+        }
+    }
+
+    #[test]
+    fn test_digest_collect() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = VerdictLog::new(dir.path().join("llm/verdicts.jsonl"));
+        log.record(verdict("2025-01-06T10:00:00Z", "turku", true))
+            .unwrap();
+        log.mark_reported(at("2025-01-06T12:00:00Z")).unwrap();
+        for (time, word, accepted) in [
+            ("2025-01-07T10:00:00Z", "tampere", true),
+            ("2025-01-08T10:00:00Z", "kissa", false),
+            ("2025-01-09T10:00:00Z", "espoo", true),
+            ("2025-01-10T10:00:00Z", "tampere", true),
+            ("2025-01-10T11:00:00Z", "kissa", false),
+            ("2025-01-11T10:00:00Z", "koira", false),
+            // After the digest, left for the next one
+            ("2025-01-14T10:00:00Z", "vantaa", true),
+        ] {
+            log.record(verdict(time, word, accepted)).unwrap();
+        }
+
+        // Read back from the file, as after a restart
+        let log = VerdictLog::new(log.path());
+        let digest = Digest::collect(&log, at("2025-01-13T12:00:00Z")).unwrap();
+        assert_eq!(digest.since, Some(at("2025-01-06T12:00:00Z")));
+        assert_eq!(
+            digest.accepted,
+            vec![
+                verdict("2025-01-09T10:00:00Z", "espoo", true),
+                verdict("2025-01-07T10:00:00Z", "tampere", true),
+            ]
+        );
+        assert_eq!(digest.rejected, 2);
+        assert_eq!(
+            digest.render(),
+            "# LLM digest 2025-01-06 12:00 UTC – 2025-01-13 12:00 UTC: 2 accepted, 2 rejected\n\n\
+             - espoo: Selitys: espoo\n\
+             - tampere: Selitys: tampere\n"
+        );
+
+        log.mark_reported(digest.until).unwrap();
+        let next = Digest::collect(&log, at("2025-01-20T12:00:00Z")).unwrap();
+        assert_eq!(next.since, Some(at("2025-01-13T12:00:00Z")));
+        assert_eq!(next.accepted.len(), 1);
+        assert_eq!(next.accepted[0].word, "vantaa");
+    }
+
+    #[test]
+    fn test_digest_nothing_new() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = VerdictLog::new(dir.path().join("verdicts.jsonl"));
+        let digest = Digest::collect(&log, at("2025-01-13T12:00:00Z")).unwrap();
+        assert!(digest.is_empty());
+        assert_eq!(digest.since, None);
+
+        log.record(verdict("2025-01-07T10:00:00Z", "tampere", true))
+            .unwrap();
+        log.mark_reported(at("2025-01-13T12:00:00Z")).unwrap();
+        let digest = Digest::collect(&log, at("2025-01-20T12:00:00Z")).unwrap();
+        assert!(digest.is_empty());
+        assert_eq!(
+            digest.summary(),
+            "LLM digest 2025-01-13 12:00 UTC – 2025-01-20 12:00 UTC: 0 accepted, 0 rejected"
+        );
+
+        let file = dir.path().join("digests.md");
+        append_digest(&file, &digest).unwrap();
+        assert!(fs::read_to_string(&file)
+            .unwrap()
+            .starts_with("# LLM digest 2025-01-13"));
+    }
+}
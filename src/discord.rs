@@ -1,29 +1,149 @@
-use actix::Actor;
+use actix::{Actor, Addr};
+use chrono::Utc;
 use miette::IntoDiagnostic;
 use poise::serenity_prelude as serenity;
 use std::sync::Arc;
 use std::thread;
+use std::time::Duration;
 use tokio::sync::oneshot;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use crate::{
     actors::{
-        word_validator::ValidateWord, GameStateActor, LLMValidatorActor, MessageReactionActor,
+        game_state::{DisputeVote, GetRestoredPlayers, GetRules, RedeliverVerdicts, SetBotUser},
+        message_reaction::{AlertKind, NotifyAdmin, PostMessage, ReportVerdicts},
+        word_validator::ValidateWord,
+        GameStateActor, LLMValidatorActor, MessageReactionActor, SetMessageReaction,
         WordValidatorActor,
     },
-    config::Config,
+    announce::AnnouncementLog,
+    api::{self, ApiState},
+    audit::AuditLog,
+    chain::ChainBreaks,
+    channels::GameChannels,
+    commands,
+    config::{Config, Language},
+    digest::{DigestTarget, VerdictLog},
+    disputes::DisputeRules,
+    events::{self, EventBus},
+    game::GameEngine,
+    games::{Game, Games},
+    health::{self, Health},
+    incoming::{is_side_conversation, message_to_candidate_word},
+    llm_cache::LlmCacheStore,
+    messages::Text,
+    permissions,
+    rotation::{RotationStore, TurnMode},
+    scoring::ScoreFormula,
+    shutdown::ShutdownHandle,
+    snapshot::SnapshotStore,
+    status::StatusMessageStore,
+    validation::{
+        blocklist::Blocklist, dictionary::DictionaryValidator, extract::ExtractOptions,
+        remote::RemoteDictionary,
+    },
+    webhook::Webhook,
+    weekly::{WeeklyArchive, WeeklySchedule},
     Data, Error,
 };
 
 pub async fn setup_bot(
     token: String,
-    channel_id: u64,
-    dictionary_path: String,
+    dictionary: DictionaryValidator,
     activity: String,
     config: Config,
-) -> miette::Result<()> {
+) -> miette::Result<Bot> {
     info!("Setting up Discord bot");
 
+    // Load the blocklist before starting any actors so a bad path fails fast
+    let blocklist = if config.blocklist_path.is_empty() {
+        Blocklist::default()
+    } else {
+        Blocklist::load(&config.blocklist_path).map_err(|e| {
+            error!("Failed to load blocklist: {}", e);
+            e
+        })?
+    };
+    let remote_dictionary = RemoteDictionary::from_config(&config).map_err(|e| {
+        error!("Failed to set up the remote dictionary: {}", e);
+        e
+    })?;
+    let webhook = Webhook::from_config(&config).map_err(|e| {
+        error!("Failed to set up the webhook: {}", e);
+        e
+    })?;
+
+    // Proper nouns can't be checked without an API key, unless they're not accepted at all
+    let llm_configured = !config.accept_proper_nouns || !config.gemini_api_key.is_empty();
+    let health = Arc::new(Health::new(dictionary.len(), llm_configured));
+    // The actors are registered for the shutdown as they start, like for the health checks
+    let shutdown = ShutdownHandle::new(Duration::from_secs(config.shutdown_grace_secs));
+    if config.health_port != 0 {
+        let listener = health::bind(config.health_port).await?;
+        tokio::spawn(health::serve(listener, health.clone()));
+    }
+
+    // A game in each configured server, or else a single game in the configured channel
+    let guild_configs: Vec<(Option<u64>, Config)> = match config.guild_configs() {
+        guilds if guilds.is_empty() => vec![(None, config.clone())],
+        guilds => {
+            if config.channel_id != 0 {
+                warn!("TARGET_CHANNEL_ID is ignored since servers are configured in `guilds`");
+            }
+            guilds
+                .into_iter()
+                .map(|(guild_id, config)| (Some(guild_id), config))
+                .collect()
+        }
+    };
+
+    // Find missing permissions now rather than from reactions failing one by one
+    let http = serenity::Http::new(&token);
+    let mut permission_problems = Vec::new();
+    for (index, (_, guild_config)) in guild_configs.iter().enumerate() {
+        let channel_id = serenity::ChannelId::new(guild_config.channel_id);
+        let thread_id = (guild_config.game_thread_id != 0)
+            .then(|| serenity::ChannelId::new(guild_config.game_thread_id));
+        let threads = thread_id.is_some() || guild_config.follow_threads;
+        let problem = match permissions::check_channel(&http, channel_id, thread_id, threads).await
+        {
+            Ok(missing) if missing.is_empty() => continue,
+            Ok(missing) => Error::Permissions(format!(
+                "the bot lacks {} in the game channel {}",
+                missing.join(", "),
+                channel_id
+            )),
+            // Also e.g. Discord failing to answer, which only stops a strict bot
+            Err(e) => e,
+        };
+        if config.strict_permissions {
+            return Err(problem.into());
+        }
+        warn!(
+            channel_id = channel_id.get(),
+            error = %problem,
+            "The game channel failed the startup check, the bot won't work properly there"
+        );
+        permission_problems.push((index, problem));
+    }
+
+    let channels: Vec<Arc<GameChannels>> = guild_configs
+        .iter()
+        .map(|(_, config)| Arc::new(GameChannels::new(config)))
+        .collect();
+
+    // Every actor notes down its part in handling a word in the same log
+    let audit = Arc::new(AuditLog::new(config.audit_log_size));
+    // Each game publishes its events on its own bus
+    let buses: Vec<EventBus> = guild_configs.iter().map(|_| EventBus::default()).collect();
+
+    // The actor system thread takes its own copy of the configuration
+    let actor_config = config.clone();
+    let actor_guild_configs = guild_configs.clone();
+    let actor_audit = audit.clone();
+    let actor_health = health.clone();
+    let actor_buses = buses.clone();
+
     // Create a channel to receive actor addresses from the actor system thread
     let (tx, rx) = oneshot::channel();
 
@@ -42,15 +162,48 @@ pub async fn setup_bot(
             local
                 .run_until(async {
                     // Initialize actors
-                    let game_state = GameStateActor::new().start();
-                    let llm_validator = LLMValidatorActor::new(&config).start();
+                    let mut llm_validator =
+                        LLMValidatorActor::new(&actor_config).with_health(actor_health);
+                    if actor_config.llm_digest_interval_secs != 0 {
+                        match DigestTarget::from_config(&actor_config) {
+                            Some(target) => {
+                                llm_validator = llm_validator.with_digest(
+                                    VerdictLog::new(&actor_config.llm_verdict_log_path),
+                                    Duration::from_secs(actor_config.llm_digest_interval_secs),
+                                    target,
+                                );
+                            }
+                            None => warn!(
+                                "LLM digests are off, set LLM_DIGEST_PATH or ADMIN_CHANNEL_ID"
+                            ),
+                        }
+                    }
+                    if !actor_config.llm_cache_path.is_empty() {
+                        llm_validator = llm_validator
+                            .with_cache_store(LlmCacheStore::new(&actor_config.llm_cache_path));
+                    }
+                    let llm_validator = llm_validator.with_audit(actor_audit.clone()).start();
+
+                    // Every game looks words up in the same dictionary
+                    let dictionary =
+                        Arc::new(GameEngine::prepare_dictionary(dictionary, &actor_config));
+                    let game_states: Vec<_> = actor_guild_configs
+                        .iter()
+                        .zip(actor_buses)
+                        .map(|((_, config), bus)| {
+                            game_state_actor(dictionary.clone(), config, llm_validator.clone())
+                                .with_audit(actor_audit.clone())
+                                .with_events(bus)
+                                .start()
+                        })
+                        .collect();
 
                     // Log actor addresses
-                    info!("Game state actor address: {:?}", game_state);
+                    info!("Game state actor addresses: {:?}", game_states);
                     info!("LLM validator actor address: {:?}", llm_validator);
 
                     // Send the addresses to the main thread
-                    if let Err(e) = tx.send((game_state, llm_validator)) {
+                    if let Err(e) = tx.send((game_states, llm_validator)) {
                         error!("Failed to send actor addresses: {:?}", e);
                     }
 
@@ -69,23 +222,87 @@ pub async fn setup_bot(
     });
 
     // Receive actor addresses from the actor system thread
-    let (game_state, llm_validator) = rx.await.map_err(|e| {
+    let (game_states, llm_validator) = rx.await.map_err(|e| {
         error!("Failed to receive actor addresses: {}", e);
         miette::miette!("Failed to initialize actor system")
     })?;
+    for game_state in &game_states {
+        health.register_actor("game_state", game_state.clone().recipient());
+        shutdown.register_actor("game_state", game_state.clone().recipient());
+    }
+    health.register_actor("llm_validator", llm_validator.clone().recipient());
+    shutdown.register_actor("llm_validator", llm_validator.clone().recipient());
+
+    // The API stops when this sender is dropped, along with the bot
+    let (api_exit_tx, api_exit_rx) = oneshot::channel::<()>();
+    if !config.api_addr.is_empty() {
+        let listener = api::bind(&config.api_addr).await?;
+        let games = guild_configs
+            .iter()
+            .map(|(guild_id, _)| *guild_id)
+            .zip(game_states.iter().cloned())
+            .collect();
+        let state = Arc::new(ApiState::new(games, &config));
+        tokio::spawn(api::serve(listener, state, async {
+            let _ = api_exit_rx.await;
+        }));
+    }
 
     let options = poise::FrameworkOptions {
-        event_handler: move |_ctx,
+        commands: commands::all(config.language),
+        event_handler: move |ctx,
                              event,
-                             _framework: poise::FrameworkContext<'_, Data, Error>,
+                             framework: poise::FrameworkContext<'_, Data, Error>,
                              data: &Data| {
             Box::pin(async move {
+                // Follow reconnects so the health endpoint knows when the gateway is down
+                if let serenity::FullEvent::ShardStageUpdate { event } = event {
+                    data.health
+                        .set_discord_connected(event.new == serenity::ConnectionStage::Connected);
+                }
+
+                // Reactions that failed while the connection was down are set again
+                if matches!(
+                    event,
+                    serenity::FullEvent::Ready { .. } | serenity::FullEvent::Resume { .. }
+                ) {
+                    for game in data.games.iter() {
+                        game.game_state.do_send(RedeliverVerdicts);
+                    }
+                }
+
+                // Join new game threads, the bot only gets messages from threads it's in
+                if let serenity::FullEvent::ThreadCreate { thread } = event {
+                    if let Some(game) = data.games.get(Some(thread.guild_id)) {
+                        let in_scope = game.channels.is_game_thread(thread);
+                        game.channels.remember(thread.id, in_scope);
+                        if in_scope {
+                            join_thread(ctx, thread.id).await;
+                        }
+                    }
+                }
+
+                // Reactions to played words are votes for or against their verdicts
+                if let serenity::FullEvent::ReactionAdd { add_reaction } = event {
+                    forward_vote(ctx, data, add_reaction, true).await;
+                }
+                if let serenity::FullEvent::ReactionRemove { removed_reaction } = event {
+                    forward_vote(ctx, data, removed_reaction, false).await;
+                }
+
                 if let serenity::FullEvent::Message { new_message } = event {
-                    // Process only messages from the target channel
-                    if new_message.channel_id == data.channel_id {
+                    // Only servers with a game are listened to
+                    let Some(game) = data.games.get(new_message.guild_id) else {
+                        return Ok(());
+                    };
+
+                    // Process only messages from the game channel and its threads
+                    if game.channels.contains(ctx, new_message.channel_id).await {
+                        // The content is logged by the word validator, if at all
                         debug!(
-                            "Received message in target channel: {}",
-                            new_message.content
+                            channel_id = new_message.channel_id.get(),
+                            message_id = new_message.id.get(),
+                            "Received message in target channel"
                         );
 
                         // Skip messages from the bot itself
@@ -93,26 +310,27 @@ pub async fn setup_bot(
                             return Ok(());
                         }
 
-                        // Extract the word from the message
-                        let content = new_message.content.trim();
-
-                        // Skip empty messages or commands
-                        if content.is_empty() || content.starts_with('!') {
+                        // Skip empty messages and commands for other bots
+                        let Some(content) =
+                            message_to_candidate_word(&new_message.content, &game.config)
+                        else {
+                            return Ok(());
+                        };
+                        if is_side_conversation(new_message, framework.bot_id, &game.config) {
+                            debug!(
+                                message_id = new_message.id.get(),
+                                "Skipping a side conversation"
+                            );
                             return Ok(());
                         }
 
                         // Send the word for validation
-                        debug!(
-                            "Attempting to send word '{}' to word validator actor",
-                            content
-                        );
-                        data.word_validator.do_send(ValidateWord {
-                            word: content.to_string(),
+                        game.word_validator.do_send(ValidateWord {
+                            word: content,
+                            channel_id: new_message.channel_id.get(),
                             message_id: new_message.id.get(),
                             user_id: new_message.author.id.get(),
                         });
-
-                        info!("Processing word: '{}'", content);
                     }
                 }
                 Ok(())
@@ -121,53 +339,116 @@ pub async fn setup_bot(
         ..Default::default()
     };
 
-    // Save these values for later use
-    let dictionary_path_clone = dictionary_path.clone();
-    let channel_id_clone = channel_id;
+    // The framework's setup takes the original, the bot keeps a handle of its own
+    let bot_shutdown = shutdown.clone();
 
     // Create framework
     let framework = poise::Framework::builder()
         .options(options)
         .setup(move |ctx, ready, framework| {
             // Capture moved values
-            let dictionary_path = dictionary_path_clone.clone();
-            let channel_id = channel_id_clone;
             let activity = activity.clone();
-            let game_state = game_state.clone();
             let llm_validator = llm_validator.clone();
+            let config = config.clone();
+            let health = health.clone();
+            let shutdown = shutdown.clone();
+            let audit = audit.clone();
+            let admin_channel_id = config.admin_channel_id;
+            let dev_guild_id = config.dev_guild_id;
+            // The presence is the same everywhere, so it can only follow a single game
+            let dynamic_presence =
+                (config.dynamic_presence && guild_configs.len() == 1).then(|| activity.clone());
+            let mailbox_capacity = config.mailbox_capacity;
+            let offline_status = config.offline_status;
+            let reaction_interval = Duration::from_millis(config.reaction_interval_ms);
+            let announce_on_ready = config.announce_on_ready;
+            let announce_interval = chrono::Duration::seconds(
+                i64::try_from(config.announce_interval_secs).unwrap_or(i64::MAX),
+            );
+            let announce_log_path = config.announce_log_path.clone();
 
             Box::pin(async move {
-                poise::builtins::register_globally(ctx, &framework.options().commands)
-                    .await
-                    .map_err(Error::Discord)?;
+                let guild_languages: Vec<(u64, Language)> = guild_configs
+                    .iter()
+                    .filter_map(|(guild_id, config)| Some(((*guild_id)?, config.language)))
+                    .collect();
+                register_commands(
+                    ctx,
+                    &framework.options().commands,
+                    dev_guild_id,
+                    &guild_languages,
+                )
+                .await?;
 
                 // Set the bot's status with the configured activity
                 info!("{} is connected!", ready.user.name);
+                health.set_discord_connected(true);
                 ctx.set_presence(
                     Some(serenity::ActivityData::playing(&activity)),
                     serenity::OnlineStatus::Online,
                 );
                 debug!("Setting activity to {}", activity);
 
+                for channels in &channels {
+                    if let Some(thread_id) = channels.thread_id() {
+                        join_thread(ctx, thread_id).await;
+                    }
+                }
+
                 // Create a properly type-erased, 'static Context
                 let ctx = Arc::new(ctx.clone());
-                let channel_id = serenity::ChannelId::new(channel_id);
+                // Announcements go to each game's thread if there is one
+                let announcements: Vec<_> = channels
+                    .iter()
+                    .zip(&guild_configs)
+                    .map(|(channels, (_, config))| {
+                        let status = config
+                            .status_message
+                            .then(|| StatusMessageStore::new(&config.status_message_path));
+                        (channels.home(), config.language, status)
+                    })
+                    .collect();
 
-                // Start the message_reaction actor in a new thread to avoid LocalSet issues
+                // Start the message_reaction actors in a new thread to avoid LocalSet issues
                 let (msg_tx, msg_rx) = tokio::sync::oneshot::channel();
+                let reaction_audit = audit.clone();
+                let reaction_shutdown = shutdown.clone();
                 let _message_thread = thread::spawn(move || {
                     let system = actix_rt::System::new();
                     system.block_on(async {
                         let local = tokio::task::LocalSet::new();
                         local
                             .run_until(async {
-                                let actor =
-                                    MessageReactionActor::new(ctx.clone(), channel_id).start();
-
-                                // Send actor address back
-                                if let Err(e) = msg_tx.send(actor) {
+                                let actors: Vec<_> = announcements
+                                    .into_iter()
+                                    .map(|(channel_id, language, status)| {
+                                        let mut actor =
+                                            MessageReactionActor::new(ctx.clone(), channel_id)
+                                                .with_mailbox_capacity(mailbox_capacity)
+                                                .with_reaction_interval(reaction_interval)
+                                                .with_language(language)
+                                                .with_audit(reaction_audit.clone());
+                                        if let Some(store) = status {
+                                            actor = actor
+                                                .with_status_message(store)
+                                                .with_offline_status(offline_status);
+                                        }
+                                        if let Some(fallback) = dynamic_presence.clone() {
+                                            actor = actor.with_dynamic_presence(fallback);
+                                        }
+                                        if admin_channel_id != 0 {
+                                            actor = actor.with_admin_channel(
+                                                serenity::ChannelId::new(admin_channel_id),
+                                            );
+                                        }
+                                        actor.start()
+                                    })
+                                    .collect();
+
+                                // Send actor addresses back
+                                if let Err(e) = msg_tx.send(actors) {
                                     error!(
-                                        "Failed to send message reaction actor address: {:?}",
+                                        "Failed to send message reaction actor addresses: {:?}",
                                         e
                                     );
                                 }
@@ -175,66 +456,178 @@ pub async fn setup_bot(
                                 // IMPORTANT: Keep this thread running until the application exits
                                 // This ensures the actor continues to process messages
                                 // If this is removed, the thread will exit and the actor will stop working
-                                tokio::signal::ctrl_c().await.ok();
+                                reaction_shutdown.finished().await;
                             })
                             .await
                     })
                 });
 
-                // Get the actor address without joining the thread
-                let message_reaction = msg_rx.await.map_err(|_| {
-                    Error::Actor("Failed to get message reaction actor address".into())
+                // Get the actor addresses without joining the thread
+                let message_reactions = msg_rx.await.map_err(|_| {
+                    Error::Actor("Failed to get message reaction actor addresses".into())
                 })?;
 
-                // Create the word validator actor
-                let validator = match WordValidatorActor::new(
-                    &dictionary_path,
-                    game_state,
-                    llm_validator,
-                    message_reaction,
-                ) {
-                    Ok(validator) => validator,
-                    Err(e) => {
-                        error!("Failed to initialize word validator: {}", e);
-                        return Err(e);
+                for (game_state, message_reaction) in game_states.iter().zip(&message_reactions) {
+                    health.register_actor("message_reaction", message_reaction.clone().recipient());
+                    shutdown
+                        .register_actor("message_reaction", message_reaction.clone().recipient());
+                    // The game state announces idle resets and fixes reactions on rollbacks
+                    game_state.do_send(SetMessageReaction(message_reaction.clone()));
+                    // Verdict reactions that fail are set again after a reconnect
+                    message_reaction.do_send(ReportVerdicts(game_state.clone().recipient()));
+                    // The bot plays its own turns as itself
+                    game_state.do_send(SetBotUser(ready.user.id.get()));
+                }
+                for (index, problem) in permission_problems {
+                    message_reactions[index].do_send(NotifyAdmin {
+                        kind: AlertKind::ChannelPermissions,
+                        error: problem,
+                    });
+                }
+                if announce_on_ready {
+                    let log = AnnouncementLog::new(&announce_log_path);
+                    for (index, (_, config)) in guild_configs.iter().enumerate() {
+                        let channel_id = channels[index].home();
+                        if let Err(e) = announce_ready(
+                            &log,
+                            announce_interval,
+                            &game_states[index],
+                            &message_reactions[index],
+                            channel_id,
+                            config.language,
+                        )
+                        .await
+                        {
+                            warn!(
+                                channel_id = channel_id.get(),
+                                error = %e,
+                                "Failed to announce the state of the game"
+                            );
+                        }
                     }
-                };
+                }
+                // The LLM digests are posted in the admin channel, which every game shares
+                if let Some(message_reaction) = message_reactions.first() {
+                    llm_validator.do_send(SetMessageReaction(message_reaction.clone()));
+                }
+
+                // Follow each game's events
+                for ((_, config), (bus, message_reaction)) in guild_configs
+                    .iter()
+                    .zip(buses.iter().zip(&message_reactions))
+                {
+                    tokio::spawn(events::log_events(bus.subscribe()));
+                    if config.milestone_interval != 0 {
+                        tokio::spawn(events::announce_milestones(
+                            bus.subscribe(),
+                            message_reaction.clone(),
+                            config.language,
+                        ));
+                    }
+                    if let Some(webhook) = &webhook {
+                        tokio::spawn(webhook.clone().forward(bus.subscribe()));
+                    }
+                }
 
-                // Start the word validator in a new thread
+                // Create the word validator actors
+                let validators: Vec<_> = guild_configs
+                    .iter()
+                    .zip(game_states.iter().zip(&message_reactions))
+                    .zip(&buses)
+                    .map(|(((_, config), (game_state, message_reaction)), bus)| {
+                        let mut validator = word_validator_actor(
+                            config,
+                            game_state.clone(),
+                            llm_validator.clone(),
+                            message_reaction.clone(),
+                        )
+                        .with_blocklist(blocklist.clone())
+                        .with_audit(audit.clone())
+                        .with_events(bus.clone());
+                        if let Some(remote_dictionary) = &remote_dictionary {
+                            validator = validator.with_remote_dictionary(remote_dictionary.clone());
+                        }
+                        validator
+                    })
+                    .collect();
+
+                // Start the word validators in a new thread
                 let (word_tx, word_rx) = tokio::sync::oneshot::channel();
+                let validator_shutdown = shutdown.clone();
                 let _validator_thread = thread::spawn(move || {
                     let system = actix_rt::System::new();
                     system.block_on(async {
                         let local = tokio::task::LocalSet::new();
                         local
                             .run_until(async {
-                                let actor = validator.start();
+                                let actors: Vec<_> =
+                                    validators.into_iter().map(Actor::start).collect();
 
-                                // Send actor address back
-                                if let Err(e) = word_tx.send(actor) {
-                                    error!("Failed to send word validator actor address: {:?}", e);
+                                // Send actor addresses back
+                                if let Err(e) = word_tx.send(actors) {
+                                    error!(
+                                        "Failed to send word validator actor addresses: {:?}",
+                                        e
+                                    );
                                 }
 
                                 // IMPORTANT: Keep this thread running until the application exits
                                 // This ensures the actor continues to process messages
                                 // If this is removed, the thread will exit and the actor will stop working
-                                tokio::signal::ctrl_c().await.ok();
+                                validator_shutdown.finished().await;
                             })
                             .await
                     })
                 });
 
-                // Get the actor address without joining the thread
-                let word_validator = word_rx.await.map_err(|_| {
-                    Error::Actor("Failed to get word validator actor address".into())
+                // Get the actor addresses without joining the thread
+                let word_validators = word_rx.await.map_err(|_| {
+                    Error::Actor("Failed to get word validator actor addresses".into())
                 })?;
 
+                let mut games = Vec::new();
+                for (
+                    (((guild_id, config), channels), (game_state, events)),
+                    (message_reaction, word_validator),
+                ) in guild_configs
+                    .into_iter()
+                    .zip(channels)
+                    .zip(game_states.into_iter().zip(buses))
+                    .zip(message_reactions.into_iter().zip(word_validators))
+                {
+                    health.register_actor("word_validator", word_validator.clone().recipient());
+                    shutdown.register_actor("word_validator", word_validator.clone().recipient());
+                    health.register_validator(word_validator.clone().recipient());
+                    let game = Game {
+                        config: Arc::new(config),
+                        channels,
+                        game_state,
+                        word_validator,
+                        message_reaction,
+                        events,
+                    };
+                    games.push((guild_id, game));
+                }
+                let games = match games.pop() {
+                    Some((None, game)) => Games::Single(game),
+                    last => Games::PerGuild(
+                        games
+                            .into_iter()
+                            .chain(last)
+                            .filter_map(|(guild_id, game)| Some((guild_id?, game)))
+                            .collect(),
+                    ),
+                };
+
                 info!("Word validation system initialized successfully");
 
                 // Return the data with initialized actors
                 Ok(Data {
-                    channel_id,
-                    word_validator,
+                    config: Arc::new(config),
+                    games,
+                    llm_validator,
+                    health,
+                    audit,
                 })
             })
         })
@@ -243,20 +636,270 @@ pub async fn setup_bot(
     let intents =
         serenity::GatewayIntents::non_privileged() | serenity::GatewayIntents::MESSAGE_CONTENT;
 
-    let mut client = serenity::ClientBuilder::new(token, intents)
+    let client = serenity::ClientBuilder::new(token, intents)
         .framework(framework)
         .await
         .into_diagnostic()?;
 
-    info!("Starting bot...");
-    client
-        .start()
-        .await
-        .map_err(Error::Discord)
-        .into_diagnostic()?;
+    Ok(Bot {
+        client,
+        shutdown: bot_shutdown,
+        _exit_tx: exit_tx,
+        _api_exit_tx: api_exit_tx,
+    })
+}
+
+/// The bot with its actors started, ready to connect to Discord
+pub struct Bot {
+    client: serenity::Client,
+    shutdown: ShutdownHandle,
+    /// The actor system thread and the API stop once these are dropped, along with the bot
+    _exit_tx: oneshot::Sender<()>,
+    _api_exit_tx: oneshot::Sender<()>,
+}
 
-    // Make sure to drop exit_tx when function exits to signal cleanup
-    let _exit_signal: tokio::sync::oneshot::Sender<()> = exit_tx;
+impl Bot {
+    /// Connect to Discord and play until the connection fails for good
+    pub async fn run(&mut self) -> miette::Result<()> {
+        info!("Starting bot...");
+        self.client
+            .start()
+            .await
+            .map_err(Error::from)
+            .into_diagnostic()
+    }
+
+    /// The handle that shuts the actors down, which stays usable while the bot runs
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
+}
+
+/// The game state actor of one game, set up as configured
+pub fn game_state_actor(
+    dictionary: Arc<DictionaryValidator>,
+    config: &Config,
+    llm_validator: Addr<LLMValidatorActor>,
+) -> GameStateActor {
+    let mut game_state =
+        GameStateActor::with_engine(GameEngine::from_shared_config(dictionary, config))
+            .with_idle_reset(Duration::from_secs(config.game_idle_reset_secs))
+            .with_bot_turns(Duration::from_secs(config.bot_plays_after_secs))
+            .with_history_size(config.history_size)
+            .with_reset_reactions(config.reset_reactions)
+            .with_mailbox_capacity(config.mailbox_capacity)
+            .with_daily_limit(config.max_words_per_user_per_day, config.timezone)
+            .with_cooldown(Duration::from_secs(config.user_cooldown_secs))
+            .with_pending_queue(
+                config.pending_queue_size,
+                Duration::from_secs(config.pending_queue_timeout_secs),
+            )
+            .with_scoring(ScoreFormula::from_config(config))
+            .with_suggestions(config.suggest_words)
+            .with_language(config.language)
+            .with_milestones(config.milestone_interval)
+            .with_llm_validator(llm_validator);
+    if let Some(rules) = DisputeRules::from_config(config) {
+        game_state = game_state.with_disputes(rules);
+    }
+    if let Some(breaks) = ChainBreaks::from_config(config) {
+        game_state = game_state.with_chain_breaks(breaks);
+    }
+    if config.game_turn_mode == TurnMode::Rotation {
+        game_state = game_state.with_rotation(
+            RotationStore::new(&config.turn_order_path),
+            Duration::from_secs(config.turn_skip_secs),
+        );
+    }
+    if config.weekly_reset {
+        game_state = game_state.with_weekly_reset(
+            WeeklySchedule::from_config(config),
+            WeeklyArchive::new(&config.weekly_archive_path),
+        );
+    }
+    if !config.game_snapshot_path.is_empty() {
+        game_state = game_state.with_snapshot(SnapshotStore::new(&config.game_snapshot_path));
+    }
+    game_state
+}
+
+/// The word validator actor of one game, set up as configured
+///
+/// The blocklist and the remote dictionary are loaded once for every game,
+/// so they're left for the caller to add.
+pub fn word_validator_actor(
+    config: &Config,
+    game_state: Addr<GameStateActor>,
+    llm_validator: Addr<LLMValidatorActor>,
+    message_reaction: Addr<MessageReactionActor>,
+) -> WordValidatorActor {
+    WordValidatorActor::new(game_state, llm_validator, message_reaction)
+        .with_mailbox_capacity(config.mailbox_capacity)
+        .with_limit_replies(config.limit_replies)
+        .with_alphabet_replies(config.alphabet_replies)
+        .with_pause_reaction(config.pause_reaction)
+        .with_already_used_reaction(config.already_used_reaction)
+        .with_daily_limit_reaction(config.daily_limit_reaction)
+        .with_cooldown_reaction(config.cooldown_reaction)
+        .with_typo_reaction(config.typo_reaction)
+        .with_language(config.language)
+        .with_log_message_content(config.log_message_content)
+        .with_llm_batch_mode(config.llm_batch_mode)
+        .with_concurrency(config.validation_concurrency, config.validation_shed_limit)
+        .with_extract_options(ExtractOptions::from_config(config))
+}
+
+/// Register the slash commands in the development guild if there is one, in
+/// each configured server in its own language, or else globally
+///
+/// Guild commands are updated immediately, global ones can take up to an hour.
+async fn register_commands(
+    ctx: &serenity::Context,
+    commands: &[poise::Command<Data, Error>],
+    dev_guild_id: u64,
+    guilds: &[(u64, Language)],
+) -> Result<(), Error> {
+    if dev_guild_id != 0 {
+        let guild_id = serenity::GuildId::new(dev_guild_id);
+        poise::builtins::register_in_guild(ctx, commands, guild_id).await?;
+        info!(
+            guild_id = dev_guild_id,
+            commands = commands.len(),
+            "Registered commands in the development guild only"
+        );
+    } else if !guilds.is_empty() {
+        for &(guild_id, language) in guilds {
+            // A server the bot hasn't joined yet mustn't keep it from starting
+            let described = commands::all(language);
+            match poise::builtins::register_in_guild(
+                ctx,
+                &described,
+                serenity::GuildId::new(guild_id),
+            )
+            .await
+            {
+                Ok(()) => info!(
+                    guild_id,
+                    commands = described.len(),
+                    "Registered commands in the server"
+                ),
+                Err(e) => warn!(guild_id, error = %e, "Failed to register commands in the server"),
+            }
+        }
+    } else {
+        poise::builtins::register_globally(ctx, commands).await?;
+        info!(commands = commands.len(), "Registered commands globally");
+    }
+    Ok(())
+}
+
+/// Join a game thread so its messages are delivered to the bot
+async fn join_thread(ctx: &serenity::Context, thread_id: serenity::ChannelId) {
+    match thread_id.join_thread(ctx).await {
+        Ok(()) => info!(thread_id = thread_id.get(), "Joined game thread"),
+        Err(e) => warn!(thread_id = thread_id.get(), error = %e, "Failed to join game thread"),
+    }
+}
 
+/// Post the current word and chain length in the game's channel, and whether
+/// the game was continued from the saved one
+///
+/// Skipped if the channel was announced in less than `interval` ago, so a bot
+/// stuck restarting doesn't flood the channel.
+async fn announce_ready(
+    log: &AnnouncementLog,
+    interval: chrono::Duration,
+    game_state: &Addr<GameStateActor>,
+    message_reaction: &Addr<MessageReactionActor>,
+    channel_id: serenity::ChannelId,
+    language: Language,
+) -> Result<(), Error> {
+    let now = Utc::now();
+    if !log.due(channel_id.get(), now, interval)? {
+        info!(
+            channel_id = channel_id.get(),
+            "Not announcing the game, the channel was announced in recently"
+        );
+        return Ok(());
+    }
+
+    let info = game_state
+        .send(GetRules)
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to get game rules: {}", e)))?;
+    let restored_players = game_state
+        .send(GetRestoredPlayers)
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to get the restored game: {}", e)))?;
+    let content = Text::ReadyAnnouncement {
+        current_word: info.current_word.as_deref(),
+        chain_length: info.chain_length,
+        restored_players,
+    }
+    .render(language);
+    let posted = message_reaction
+        .send(PostMessage { content })
+        .await
+        .map_err(|e| Error::Actor(format!("Failed to post the announcement: {}", e)))?;
+    // A failed post is logged by the reaction actor and tried again on the next start
+    if posted.is_some() {
+        log.record(channel_id.get(), now)?;
+    }
     Ok(())
 }
+
+/// Send a reaction to a message in a game channel to the game as a dispute vote
+///
+/// Skin tones and variation selectors are ignored, so any 👍 counts as one.
+/// Removed reactions don't say whether a bot made them, but a bot's vote was
+/// never counted in the first place.
+async fn forward_vote(
+    ctx: &serenity::Context,
+    data: &Data,
+    reaction: &serenity::Reaction,
+    added: bool,
+) {
+    let Some(game) = data.games.get(reaction.guild_id) else {
+        return;
+    };
+    if game.config.dispute_votes == 0 {
+        return;
+    }
+    let serenity::ReactionType::Unicode(emoji) = &reaction.emoji else {
+        return;
+    };
+    let mut chars = emoji
+        .chars()
+        .filter(|c| *c != '\u{fe0f}' && !('\u{1f3fb}'..='\u{1f3ff}').contains(c));
+    let (Some(emoji), None) = (chars.next(), chars.next()) else {
+        return;
+    };
+    let Some(user_id) = reaction.user_id else {
+        return;
+    };
+    if user_id == ctx.cache.current_user().id
+        || reaction
+            .member
+            .as_ref()
+            .is_some_and(|member| member.user.bot)
+    {
+        return;
+    }
+    if !game.channels.contains(ctx, reaction.channel_id).await {
+        return;
+    }
+
+    debug!(
+        message_id = reaction.message_id.get(),
+        user_id = user_id.get(),
+        reaction = %emoji,
+        added,
+        "Forwarding dispute vote"
+    );
+    game.game_state.do_send(DisputeVote {
+        message_id: reaction.message_id.get(),
+        user_id: user_id.get(),
+        reaction: emoji,
+        added,
+    });
+}
@@ -0,0 +1,270 @@
+//! Community votes that overturn the bot's verdicts
+//!
+//! Players dispute a verdict by reacting to the word: the reject emoji on an
+//! accepted word, the accept emoji on a rejected one. Once enough players
+//! have voted within the window after the verdict, it's overturned.
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, SystemTime};
+
+use crate::config::Config;
+use crate::game::Validity;
+
+/// How many votes overturn a verdict, and how they're cast
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisputeRules {
+    /// Distinct players needed to overturn a verdict
+    pub votes: usize,
+    /// How long after the verdict votes are counted
+    pub window: Duration,
+    /// Reaction voting for a rejected word
+    pub accept: char,
+    /// Reaction voting against an accepted word
+    pub reject: char,
+}
+
+impl DisputeRules {
+    /// The configured rules, None if disputes are off
+    pub fn from_config(config: &Config) -> Option<Self> {
+        (config.dispute_votes != 0).then(|| Self {
+            votes: config.dispute_votes,
+            window: Duration::from_secs(config.dispute_window_secs),
+            accept: config.dispute_accept_reaction,
+            reject: config.dispute_reject_reaction,
+        })
+    }
+
+    /// Whether a reaction disputes a word with this verdict
+    pub fn disputes(&self, reaction: char, validity: Validity) -> bool {
+        match validity {
+            Validity::Valid => reaction == self.reject,
+            Validity::Invalid => reaction == self.accept,
+            Validity::Pending => false,
+        }
+    }
+}
+
+/// What a vote came to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoteOutcome {
+    /// Not counted: too late, already decided, or a withdrawn vote cast again
+    Ignored,
+    /// Counted, with the votes now against the verdict
+    Counted(usize),
+    /// The votes reached the threshold and the verdict should be overturned
+    Overturned(usize),
+}
+
+/// The votes on one word
+#[derive(Debug, Default)]
+struct Tally {
+    voters: HashSet<u64>,
+    /// Players who took their vote back, who can't vote on the word again
+    withdrawn: HashSet<u64>,
+    /// When the word got the verdict being disputed
+    settled_at: Option<SystemTime>,
+}
+
+/// The votes on every disputed word
+#[derive(Debug)]
+pub struct Disputes {
+    rules: DisputeRules,
+    tallies: HashMap<u64, Tally>,
+    /// Words already overturned, whose new verdict stands
+    decided: HashSet<u64>,
+}
+
+impl Disputes {
+    pub fn new(rules: DisputeRules) -> Self {
+        Self {
+            rules,
+            tallies: HashMap::new(),
+            decided: HashSet::new(),
+        }
+    }
+
+    pub fn rules(&self) -> &DisputeRules {
+        &self.rules
+    }
+
+    /// Count a vote added to or taken back from a word settled at `settled_at`
+    ///
+    /// Each player counts once per word, and a player who takes their vote
+    /// back can't cast it again, so toggling the reaction does nothing. An
+    /// overturned verdict can't be voted back.
+    pub fn vote(
+        &mut self,
+        message_id: u64,
+        user_id: u64,
+        added: bool,
+        settled_at: SystemTime,
+        now: SystemTime,
+    ) -> VoteOutcome {
+        self.expire(now);
+        if self.decided.contains(&message_id) || !within(self.rules.window, settled_at, now) {
+            return VoteOutcome::Ignored;
+        }
+
+        let tally = self.tallies.entry(message_id).or_default();
+        tally.settled_at = Some(settled_at);
+        if !added {
+            if tally.voters.remove(&user_id) {
+                tally.withdrawn.insert(user_id);
+            }
+            return VoteOutcome::Counted(tally.voters.len());
+        }
+        if tally.withdrawn.contains(&user_id) {
+            return VoteOutcome::Ignored;
+        }
+        tally.voters.insert(user_id);
+
+        let votes = tally.voters.len();
+        if votes < self.rules.votes {
+            return VoteOutcome::Counted(votes);
+        }
+        self.tallies.remove(&message_id);
+        self.decided.insert(message_id);
+        VoteOutcome::Overturned(votes)
+    }
+
+    /// Forget the words `keep` says no to, e.g. ones that left the history
+    pub fn forget_words(&mut self, keep: impl Fn(u64) -> bool) {
+        self.tallies.retain(|&message_id, _| keep(message_id));
+        self.decided.retain(|&message_id| keep(message_id));
+    }
+
+    /// How many words have votes being counted
+    pub fn open_tallies(&self) -> usize {
+        self.tallies.len()
+    }
+
+    /// Drop the tallies whose window has passed
+    fn expire(&mut self, now: SystemTime) {
+        let window = self.rules.window;
+        self.tallies.retain(|_, tally| {
+            tally
+                .settled_at
+                .is_none_or(|settled_at| within(window, settled_at, now))
+        });
+    }
+}
+
+/// Whether `now` is at most `window` after `settled_at`, a clock gone backwards counting as within
+fn within(window: Duration, settled_at: SystemTime, now: SystemTime) -> bool {
+    now.duration_since(settled_at)
+        .map_or(true, |elapsed| elapsed <= window)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn disputes(votes: usize) -> Disputes {
+        Disputes::new(DisputeRules {
+            votes,
+            window: Duration::from_secs(600),
+            accept: '👍',
+            reject: '👎',
+        })
+    }
+
+    fn at(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000 + secs)
+    }
+
+    #[test]
+    fn test_votes_overturn() {
+        let mut disputes = disputes(3);
+        assert_eq!(
+            disputes.vote(1, 10, true, at(0), at(5)),
+            VoteOutcome::Counted(1)
+        );
+        // The same player counts once
+        assert_eq!(
+            disputes.vote(1, 10, true, at(0), at(6)),
+            VoteOutcome::Counted(1)
+        );
+        assert_eq!(
+            disputes.vote(1, 11, true, at(0), at(7)),
+            VoteOutcome::Counted(2)
+        );
+        // Votes on other words are separate
+        assert_eq!(
+            disputes.vote(2, 12, true, at(0), at(8)),
+            VoteOutcome::Counted(1)
+        );
+        assert_eq!(
+            disputes.vote(1, 12, true, at(0), at(9)),
+            VoteOutcome::Overturned(3)
+        );
+
+        // The new verdict stands
+        assert_eq!(
+            disputes.vote(1, 13, true, at(0), at(10)),
+            VoteOutcome::Ignored
+        );
+        assert_eq!(disputes.open_tallies(), 1);
+    }
+
+    #[test]
+    fn test_toggling_votes() {
+        let mut disputes = disputes(2);
+        assert_eq!(
+            disputes.vote(1, 10, true, at(0), at(5)),
+            VoteOutcome::Counted(1)
+        );
+        assert_eq!(
+            disputes.vote(1, 10, false, at(0), at(6)),
+            VoteOutcome::Counted(0)
+        );
+        // A withdrawn vote can't be cast again
+        assert_eq!(
+            disputes.vote(1, 10, true, at(0), at(7)),
+            VoteOutcome::Ignored
+        );
+        assert_eq!(
+            disputes.vote(1, 11, true, at(0), at(8)),
+            VoteOutcome::Counted(1)
+        );
+        // Removing a reaction that never counted changes nothing
+        assert_eq!(
+            disputes.vote(1, 12, false, at(0), at(9)),
+            VoteOutcome::Counted(1)
+        );
+        assert_eq!(
+            disputes.vote(1, 12, true, at(0), at(10)),
+            VoteOutcome::Overturned(2)
+        );
+    }
+
+    #[test]
+    fn test_vote_window() {
+        let mut disputes = disputes(2);
+        assert_eq!(
+            disputes.vote(1, 10, true, at(0), at(600)),
+            VoteOutcome::Counted(1)
+        );
+        assert_eq!(
+            disputes.vote(1, 11, true, at(0), at(601)),
+            VoteOutcome::Ignored
+        );
+        // The tally expired with the window
+        assert_eq!(disputes.open_tallies(), 0);
+
+        // Words that left the history are forgotten
+        disputes.vote(2, 10, true, at(0), at(5));
+        disputes.vote(3, 10, true, at(0), at(5));
+        disputes.forget_words(|message_id| message_id == 3);
+        assert_eq!(disputes.open_tallies(), 1);
+    }
+
+    #[test]
+    fn test_which_reactions_dispute() {
+        let rules = disputes(1).rules;
+        assert!(rules.disputes('👎', Validity::Valid));
+        assert!(!rules.disputes('👍', Validity::Valid));
+        assert!(rules.disputes('👍', Validity::Invalid));
+        assert!(!rules.disputes('👎', Validity::Invalid));
+        assert!(!rules.disputes('👍', Validity::Pending));
+    }
+}
@@ -1,18 +1,25 @@
-use miette::{Diagnostic, SourceSpan};
+use miette::{Diagnostic, NamedSource, SourceSpan};
 use std::io;
+use std::time::Duration;
 use thiserror::Error;
 
+use crate::validation::rules::Violation;
+
 /// Primary error type for the Sanabotti application
 #[derive(Error, Debug, Diagnostic)]
 pub enum BotError {
     #[error("Discord API error: {0}")]
     #[diagnostic(code(sanabotti::discord_error))]
-    Discord(#[from] serenity::Error),
+    Discord(Box<serenity::Error>),
 
     #[error("Environment configuration error: {0}")]
     #[diagnostic(code(sanabotti::config_error))]
     Config(String),
 
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    InvalidConfig(#[from] ConfigErrors),
+
     #[error("Dictionary error: {0}")]
     #[diagnostic(code(sanabotti::dictionary_error))]
     Dictionary(#[from] DictionaryError),
@@ -33,9 +40,89 @@ pub enum BotError {
     #[diagnostic(code(sanabotti::llm_error))]
     LLM(#[from] LLMError),
 
+    #[error("Game channel error: {0}")]
+    #[diagnostic(
+        code(sanabotti::channel_error),
+        help("check TARGET_CHANNEL_ID and that the bot has been invited to the channel's server")
+    )]
+    Channel(String),
+
+    #[error("Missing permissions: {0}")]
+    #[diagnostic(
+        code(sanabotti::missing_permissions),
+        help("grant them to the bot's role in the channel's settings, or set STRICT_PERMISSIONS=false to start anyway")
+    )]
+    Permissions(String),
+
     #[error("Message reaction error: {0}")]
     #[diagnostic(code(sanabotti::reaction_error))]
     Reaction(String),
+
+    #[error("Rate limited by Discord, retry after {0:?}")]
+    #[diagnostic(code(sanabotti::rate_limited))]
+    RateLimited(Duration),
+
+    #[error("Replay error: {0}")]
+    #[diagnostic(code(sanabotti::replay_error))]
+    Replay(String),
+
+    #[error("Task panicked: {0}")]
+    #[diagnostic(code(sanabotti::task_panic))]
+    TaskPanic(String),
+}
+
+impl From<serenity::Error> for BotError {
+    fn from(error: serenity::Error) -> Self {
+        // Boxed to keep the size of every Result<T> in the crate small
+        BotError::Discord(Box::new(error))
+    }
+}
+
+/// Every problem found while loading the configuration
+#[derive(Error, Debug, Diagnostic)]
+#[error("Found {} problem(s) in the configuration", problems.len())]
+#[diagnostic(code(sanabotti::config_errors))]
+pub struct ConfigErrors {
+    #[related]
+    pub problems: Vec<ConfigProblem>,
+}
+
+/// A single configuration problem, pointing at the offending value
+#[derive(Error, Debug, Diagnostic)]
+#[error("{message}")]
+#[diagnostic(code(sanabotti::config_problem))]
+pub struct ConfigProblem {
+    pub message: String,
+    #[source_code]
+    pub source_code: NamedSource<String>,
+    pub label: String,
+    #[label("{label}")]
+    pub span: Option<SourceSpan>,
+    #[help]
+    pub help: Option<String>,
+}
+
+impl ConfigProblem {
+    /// A problem with a variable, shown as `NAME=value`
+    pub fn variable(name: &str, value: Option<&str>, label: &str, help: &str) -> Self {
+        let assignment = format!("{}={}", name, value.unwrap_or_default());
+        let span = match value {
+            Some(value) => SourceSpan::from((name.len() + 1, value.len())),
+            None => SourceSpan::from((0, name.len())),
+        };
+        let message = match value {
+            Some(_) => format!("Invalid {}", name),
+            None => format!("Missing {}", name),
+        };
+
+        Self {
+            message,
+            source_code: NamedSource::new("configuration", assignment),
+            label: label.to_string(),
+            span: Some(span),
+            help: Some(help.to_string()),
+        }
+    }
 }
 
 /// Dictionary-specific errors
@@ -52,6 +139,24 @@ pub enum DictionaryError {
     #[error("Dictionary is empty")]
     #[diagnostic(code(sanabotti::dictionary::empty))]
     EmptyDictionary,
+
+    #[error("Remote dictionary lookup failed: {0}")]
+    #[diagnostic(code(sanabotti::dictionary::remote))]
+    Remote(String),
+
+    #[error("Failed to download the dictionary: {0}")]
+    #[diagnostic(
+        code(sanabotti::dictionary::download),
+        help("check DICTIONARY_URL, or download the word list by hand to DICTIONARY_FILE_PATH")
+    )]
+    Download(String),
+
+    #[error("Downloaded dictionary has sha256 {actual}, expected {expected}")]
+    #[diagnostic(
+        code(sanabotti::dictionary::checksum),
+        help("the download was discarded; check that DICTIONARY_URL and DICTIONARY_SHA256 belong to the same word list")
+    )]
+    ChecksumMismatch { expected: String, actual: String },
 }
 
 /// Validation-specific errors
@@ -70,7 +175,7 @@ pub enum ValidationError {
         #[label("This part violates the rules")]
         span: Option<SourceSpan>,
 
-        reason: String,
+        reason: Violation,
     },
 
     #[error("Word has been used before")]
@@ -96,6 +201,17 @@ pub enum LLMError {
     #[error("Timeout waiting for LLM response")]
     #[diagnostic(code(sanabotti::llm::timeout))]
     Timeout,
+
+    #[error("Word refused before reaching the LLM: {0}")]
+    #[diagnostic(code(sanabotti::llm::unsafe_word))]
+    UnsafeWord(String),
+
+    #[error("All {0} words of the daily budget have been sent to the LLM")]
+    #[diagnostic(
+        code(sanabotti::llm::budget),
+        help("Raise LLM_DAILY_BUDGET, or wait for the words sent a day ago to free up")
+    )]
+    BudgetExhausted(usize),
 }
 
 // Re-export error types for convenience
@@ -0,0 +1,177 @@
+//! Typed events published as words move through a game
+//!
+//! Features that only observe the game, like logging and milestone
+//! announcements, subscribe to the game's [`EventBus`] instead of being wired
+//! into the actors. Publishing never blocks and never fails: with nobody
+//! subscribed the events are dropped, and a subscriber that falls more than
+//! [`EVENT_CAPACITY`] events behind misses the oldest ones.
+
+use actix::Addr;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, warn};
+
+use crate::actors::message_reaction::{MessageReactionActor, PostMessage};
+use crate::config::Language;
+use crate::messages::Text;
+use crate::validation::rules::Violation;
+
+/// How many events a subscriber may fall behind before it misses some
+pub const EVENT_CAPACITY: usize = 256;
+
+/// Something that happened to a word or to the game
+///
+/// A played word gets [`WordReceived`](GameEvent::WordReceived) and then at
+/// most one of the verdict events; one escalated to the LLM gets
+/// [`LlmVerdict`](GameEvent::LlmVerdict) once it's judged. Words that are
+/// blocked, have foreign letters, are over a player's limits, or are played
+/// while the game is paused or the chain head is pending get no verdict event.
+/// Every word that makes it into the chain also gets
+/// [`WordAccepted`](GameEvent::WordAccepted), right after its verdict event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameEvent {
+    /// A message was taken up for validation; it may turn out to be chat, so
+    /// its content isn't included
+    WordReceived { message_id: u64, user_id: u64 },
+    /// The word broke a rule, including being played before, and isn't in the chain
+    RuleViolation {
+        message_id: u64,
+        user_id: u64,
+        word: String,
+        violation: Violation,
+    },
+    /// The word is in the local or the remote dictionary and follows the rules
+    DictionaryAccepted {
+        message_id: u64,
+        user_id: u64,
+        word: String,
+    },
+    /// The word isn't in the dictionary and can't be a proper noun either
+    DictionaryRejected {
+        message_id: u64,
+        user_id: u64,
+        word: String,
+    },
+    /// The word follows the rules but isn't in any dictionary, so it was sent to the LLM
+    EscalatedToLlm {
+        message_id: u64,
+        user_id: u64,
+        word: String,
+    },
+    /// The LLM judged an escalated word
+    LlmVerdict {
+        message_id: u64,
+        user_id: u64,
+        word: String,
+        valid: bool,
+    },
+    /// A bot owner settled a word
+    AdminOverride {
+        message_id: u64,
+        user_id: u64,
+        word: String,
+        valid: bool,
+        admin_id: u64,
+    },
+    /// A played word was accepted into the chain, however it was judged
+    WordAccepted {
+        message_id: u64,
+        user_id: u64,
+        word: String,
+        chain_length: usize,
+    },
+    /// The chain was cleared, by an admin, after the game was idle or when it broke
+    GameReset { idle: bool },
+    /// The chain grew to a multiple of the milestone interval, with the word
+    /// that got it there; reached again after a rollback or reset, it's
+    /// published again
+    MilestoneReached {
+        chain_length: usize,
+        user_id: u64,
+        word: String,
+    },
+    /// The chain grew longer than any earlier chain since the bot started,
+    /// published once per chain when it first passes the old record
+    ChainRecord {
+        chain_length: usize,
+        user_id: u64,
+        word: String,
+    },
+}
+
+/// Where a game's events are published, cheap to clone for every publisher
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<GameEvent>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(EVENT_CAPACITY)
+    }
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Hand the event to every subscriber, if there are any
+    pub fn publish(&self, event: GameEvent) {
+        // Only fails when nobody is subscribed, which is fine
+        let _ = self.sender.send(event);
+    }
+
+    /// Receive the events published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<GameEvent> {
+        self.sender.subscribe()
+    }
+}
+
+/// The next event, warning about any the subscriber missed, None once every publisher is gone
+pub async fn next_event(
+    events: &mut broadcast::Receiver<GameEvent>,
+    subscriber: &str,
+) -> Option<GameEvent> {
+    loop {
+        match events.recv().await {
+            Ok(event) => return Some(event),
+            Err(RecvError::Lagged(missed)) => {
+                warn!(subscriber, missed, "Event subscriber fell behind");
+            }
+            Err(RecvError::Closed) => return None,
+        }
+    }
+}
+
+/// Log every event of a game
+pub async fn log_events(mut events: broadcast::Receiver<GameEvent>) {
+    while let Some(event) = next_event(&mut events, "log").await {
+        debug!(?event, "Game event");
+    }
+}
+
+/// Congratulate the players in the game channel when the chain reaches a milestone
+pub async fn announce_milestones(
+    mut events: broadcast::Receiver<GameEvent>,
+    message_reaction: Addr<MessageReactionActor>,
+    language: Language,
+) {
+    while let Some(event) = next_event(&mut events, "milestones").await {
+        if let GameEvent::MilestoneReached {
+            chain_length,
+            user_id,
+            word,
+        } = event
+        {
+            message_reaction.do_send(PostMessage {
+                content: Text::Milestone {
+                    chain_length,
+                    user_id,
+                    word: &word,
+                }
+                .render(language),
+            });
+        }
+    }
+}
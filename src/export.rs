@@ -0,0 +1,278 @@
+//! Writing the played words out as a file, e.g. for end-of-season writeups
+
+use serde::Serialize;
+use std::time::UNIX_EPOCH;
+
+use crate::game::{Validity, VerdictSource, WordEntry};
+
+/// Discord's limit for the size of an attachment
+pub const ATTACHMENT_LIMIT: usize = 8 * 1024 * 1024;
+
+/// File format of an export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum ExportFormat {
+    #[default]
+    #[name = "CSV"]
+    Csv,
+    #[name = "JSON"]
+    Json,
+}
+
+impl ExportFormat {
+    /// Name of the exported file
+    pub fn filename(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "sanabotti_history.csv",
+            ExportFormat::Json => "sanabotti_history.json",
+        }
+    }
+}
+
+/// The exported file and how much of the history made it in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Export {
+    pub data: Vec<u8>,
+    pub rows: usize,
+    /// Words left out to stay within the size limit
+    pub truncated: usize,
+}
+
+/// One played word as it's exported
+#[derive(Serialize)]
+struct Row<'a> {
+    /// Unix timestamp in seconds
+    timestamp: u64,
+    user_id: u64,
+    username: Option<&'a str>,
+    word: &'a str,
+    verdict: &'static str,
+    /// What settled the word, if anything did
+    source: Option<&'static str>,
+    /// The admin who settled the word by hand
+    admin_id: Option<u64>,
+    /// Why the LLM judged the word the way it did
+    explanation: Option<&'a str>,
+    /// The LLM model that judged the word
+    model: Option<&'a str>,
+}
+
+/// Write the entries, oldest first, into a file of at most `limit` bytes
+///
+/// Each row is written straight into the file's buffer, and the words that
+/// don't fit anymore are left out. `username` resolves user IDs to names
+/// where it can.
+pub fn export_history<'a>(
+    entries: impl IntoIterator<Item = &'a WordEntry>,
+    format: ExportFormat,
+    username: impl Fn(u64) -> Option<String>,
+    limit: usize,
+) -> Export {
+    let (header, separator, footer): (&[u8], &[u8], &[u8]) = match format {
+        ExportFormat::Csv => (
+            b"timestamp,user_id,username,word,verdict,source,admin_id,explanation,model\n",
+            b"",
+            b"",
+        ),
+        ExportFormat::Json => (b"[\n", b",\n", b"\n]\n"),
+    };
+    let mut export = Export {
+        data: header.to_vec(),
+        rows: 0,
+        truncated: 0,
+    };
+    let mut row = Vec::new();
+
+    for entry in entries {
+        if export.truncated > 0 {
+            export.truncated += 1;
+            continue;
+        }
+
+        let name = username(entry.user_id);
+        let fields = Row {
+            timestamp: entry
+                .played_at
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            user_id: entry.user_id,
+            username: name.as_deref(),
+            word: &entry.word,
+            verdict: match entry.validity {
+                Validity::Valid => "valid",
+                Validity::Invalid => "invalid",
+                Validity::Pending => "pending",
+            },
+            source: entry.source.as_ref().map(|source| match source {
+                VerdictSource::Dictionary { .. } => "dictionary",
+                VerdictSource::Llm { .. } => "llm",
+                VerdictSource::Admin { .. } => "admin",
+                VerdictSource::Vote { .. } => "vote",
+                VerdictSource::Seed => "seed",
+            }),
+            admin_id: match entry.source {
+                Some(VerdictSource::Admin { user_id }) => Some(user_id),
+                _ => None,
+            },
+            explanation: match &entry.source {
+                Some(VerdictSource::Llm { explanation, .. }) => Some(explanation),
+                _ => None,
+            },
+            model: match &entry.source {
+                Some(VerdictSource::Llm { model, .. }) => model.as_deref(),
+                _ => None,
+            },
+        };
+        row.clear();
+        if export.rows > 0 {
+            row.extend_from_slice(separator);
+        }
+        match format {
+            ExportFormat::Csv => write_csv_row(&mut row, &fields),
+            ExportFormat::Json => {
+                serde_json::to_writer(&mut row, &fields).expect("rows serialize to JSON")
+            }
+        }
+
+        if export.data.len() + row.len() + footer.len() > limit {
+            export.truncated = 1;
+            continue;
+        }
+        export.data.extend_from_slice(&row);
+        export.rows += 1;
+    }
+
+    export.data.extend_from_slice(footer);
+    export
+}
+
+fn write_csv_row(out: &mut Vec<u8>, row: &Row) {
+    let line = format!(
+        "{},{},{},{},{},{},{},{},{}\n",
+        row.timestamp,
+        row.user_id,
+        csv_field(row.username.unwrap_or_default()),
+        csv_field(row.word),
+        row.verdict,
+        row.source.unwrap_or_default(),
+        row.admin_id.map(|id| id.to_string()).unwrap_or_default(),
+        csv_field(row.explanation.unwrap_or_default()),
+        csv_field(row.model.unwrap_or_default())
+    );
+    out.extend_from_slice(line.as_bytes());
+}
+
+/// Quote a field if it has anything CSV would misread, doubling its quotes
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Delivery;
+    use std::time::{Duration, SystemTime};
+
+    fn entry(word: &str, user_id: u64, validity: Validity) -> WordEntry {
+        WordEntry {
+            word: word.to_string(),
+            user_id,
+            message_id: 0,
+            validity,
+            played_at: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            settled_at: None,
+            in_chain: validity != Validity::Invalid,
+            source: None,
+            delivery: Delivery::default(),
+        }
+    }
+
+    fn names(user_id: u64) -> Option<String> {
+        (user_id == 1).then(|| "Matti, \"Masa\"".to_string())
+    }
+
+    #[test]
+    fn test_export_csv() {
+        let entries = [
+            WordEntry {
+                source: Some(VerdictSource::Dictionary { file: None }),
+                ..entry("kissa", 1, Validity::Valid)
+            },
+            entry("koira", 2, Validity::Invalid),
+            WordEntry {
+                source: Some(VerdictSource::Llm {
+                    explanation: "Nimi, ei sana".to_string(),
+                    model: Some("gemini-pro".to_string()),
+                }),
+                ..entry("aatu", 2, Validity::Invalid)
+            },
+            WordEntry {
+                source: Some(VerdictSource::Admin { user_id: 9 }),
+                ..entry("uuno", 2, Validity::Valid)
+            },
+        ];
+        let export = export_history(&entries, ExportFormat::Csv, names, ATTACHMENT_LIMIT);
+        assert_eq!(
+            String::from_utf8(export.data).unwrap(),
+            "timestamp,user_id,username,word,verdict,source,admin_id,explanation,model\n\
+             1700000000,1,\"Matti, \"\"Masa\"\"\",kissa,valid,dictionary,,,\n\
+             1700000000,2,,koira,invalid,,,,\n\
+             1700000000,2,,aatu,invalid,llm,,\"Nimi, ei sana\",gemini-pro\n\
+             1700000000,2,,uuno,valid,admin,9,,\n"
+        );
+        assert_eq!((export.rows, export.truncated), (4, 0));
+    }
+
+    #[test]
+    fn test_export_json() {
+        let entries = [
+            WordEntry {
+                source: Some(VerdictSource::Llm {
+                    explanation: "Yleinen sana".to_string(),
+                    model: None,
+                }),
+                ..entry("kissa", 1, Validity::Valid)
+            },
+            entry("kassa", 2, Validity::Pending),
+        ];
+        let export = export_history(&entries, ExportFormat::Json, names, ATTACHMENT_LIMIT);
+        let rows: serde_json::Value = serde_json::from_slice(&export.data).unwrap();
+        assert_eq!(rows[0]["username"], "Matti, \"Masa\"");
+        assert_eq!(rows[0]["timestamp"], 1_700_000_000);
+        assert_eq!(rows[1]["username"], serde_json::Value::Null);
+        assert_eq!(rows[1]["verdict"], "pending");
+        assert_eq!(rows[0]["source"], "llm");
+        assert_eq!(rows[0]["explanation"], "Yleinen sana");
+        assert_eq!(rows[1]["source"], serde_json::Value::Null);
+
+        let export = export_history(&[], ExportFormat::Json, names, ATTACHMENT_LIMIT);
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&export.data).unwrap(),
+            serde_json::json!([])
+        );
+    }
+
+    #[test]
+    fn test_export_truncated() {
+        let entries: Vec<_> = (0..100)
+            .map(|i| WordEntry {
+                played_at: SystemTime::now(),
+                ..entry(&format!("sana{}", i), 2, Validity::Valid)
+            })
+            .collect();
+        for format in [ExportFormat::Csv, ExportFormat::Json] {
+            let export = export_history(&entries, format, names, 1000);
+            assert!(export.data.len() <= 1000);
+            assert!(export.rows > 0);
+            assert_eq!(export.rows + export.truncated, 100);
+            if format == ExportFormat::Json {
+                let rows: Vec<serde_json::Value> = serde_json::from_slice(&export.data).unwrap();
+                assert_eq!(rows.len(), export.rows);
+            }
+        }
+    }
+}
@@ -0,0 +1,1558 @@
+//! The word game itself, independent of Discord and the actor system
+//!
+//! [`GameEngine`] owns everything needed to judge a move: the dictionary, the
+//! rules, the chain head and the history of played words. The actors wrap it
+//! and take care of reactions, LLM checks and timers.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tracing::{debug, info};
+
+use crate::config::Config;
+use crate::error::{Error, Result, ValidationError};
+use crate::validation::dictionary::DictionaryValidator;
+use crate::validation::extract::{extract_word, ExtractOptions};
+use crate::validation::inflection::parse_rules;
+use crate::validation::normalize::{is_capitalized, normalize_word};
+use crate::validation::plausibility::PlausibilityFilter;
+use crate::validation::rules::{GameRules, PendingPolicy, RulesValidator, Violation};
+
+/// The default number of previous words to store
+pub const DEFAULT_HISTORY_SIZE: usize = 100;
+
+/// A wait in whole seconds, rounded up so that it never reads as zero
+pub fn wait_secs(wait: Duration) -> u64 {
+    wait.as_secs() + u64::from(wait.subsec_nanos() > 0)
+}
+
+/// A chain of words written out, e.g. "kissa → kassa → kassi"
+pub fn describe_path(path: &[String]) -> String {
+    path.join(" → ")
+}
+
+/// Where a played word stands in validation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Validity {
+    /// Not decided yet, e.g. waiting for the LLM
+    Pending,
+    Valid,
+    Invalid,
+}
+
+/// What settled a played word
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerdictSource {
+    /// Found in the dictionary, the local or the remote one
+    Dictionary {
+        /// Name of the dictionary file that has the word, None for the remote dictionary
+        file: Option<Arc<str>>,
+    },
+    /// Judged by the LLM, which explains its verdict
+    Llm {
+        explanation: String,
+        /// The model that answered, None if none did
+        model: Option<String>,
+    },
+    /// Decided by a bot owner
+    Admin { user_id: u64 },
+    /// Overturned by the players, with how many voted for it
+    Vote { votes: usize },
+    /// Played by the bot itself to keep the chain going
+    Seed,
+}
+
+#[derive(Debug, Clone)]
+pub struct WordEntry {
+    pub word: String,
+    pub user_id: u64,
+    pub message_id: u64,
+    pub validity: Validity,
+    /// What settled the word, None while it's pending or if it broke the rules
+    ///
+    /// Like the rest of the history it's only kept in memory, the game snapshot
+    /// doesn't save it.
+    pub source: Option<VerdictSource>,
+    /// When the word was played
+    pub played_at: SystemTime,
+    /// When the word got its verdict, None while it's pending
+    pub settled_at: Option<SystemTime>,
+    /// Whether the word was accepted as a link in the chain
+    pub in_chain: bool,
+    /// Whether the reaction showing the verdict reached Discord
+    pub delivery: Delivery,
+}
+
+/// Whether a word's verdict reaction reached Discord
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Delivery {
+    /// Not known yet, e.g. still waiting in the reaction queue
+    #[default]
+    Sending,
+    Delivered,
+    /// The reaction couldn't be set, e.g. while the connection to Discord was down
+    Failed,
+}
+
+/// Outcome of checking a word against the game rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleOutcome {
+    /// The word is a legal continuation of the chain
+    FollowsRules,
+    /// The word breaks the one-letter rule
+    RuleViolation(Violation),
+    /// The word has already been played in this game
+    AlreadyUsed,
+    /// The previous word is still waiting for its verdict (strict pending policy)
+    AwaitingVerdict,
+}
+
+impl RuleOutcome {
+    pub fn is_valid(&self) -> bool {
+        matches!(self, RuleOutcome::FollowsRules)
+    }
+}
+
+impl From<Result<()>> for RuleOutcome {
+    fn from(result: Result<()>) -> Self {
+        match result {
+            Ok(()) => RuleOutcome::FollowsRules,
+            Err(Error::Validation(ValidationError::AlreadyUsed(_))) => RuleOutcome::AlreadyUsed,
+            Err(Error::Validation(ValidationError::RuleViolation { reason, .. })) => {
+                RuleOutcome::RuleViolation(reason)
+            }
+            Err(e) => RuleOutcome::RuleViolation(Violation::Other(e.to_string())),
+        }
+    }
+}
+
+/// Final (or, for LLM escalations, preliminary) outcome of validating a word
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WordVerdict {
+    /// Found in the dictionary and follows the game rules
+    ValidInDictionary,
+    /// Follows the game rules but needs the LLM to decide if it's a proper noun
+    PendingLlm,
+    /// Accepted by the LLM as a proper noun
+    ValidProperNoun,
+    /// Breaks the game rules
+    InvalidRules(Violation),
+    /// Played while the previous word was still waiting for the LLM
+    AwaitingVerdict,
+    /// Held until the previous word has its verdict, then played (queue pending policy)
+    Queued,
+    /// Not in the dictionary, and either proper nouns aren't accepted or this is a dry run
+    NotInDictionary,
+    /// Not in the dictionary and doesn't even look like a word, e.g. keyboard mash
+    Implausible,
+    /// Not something that can be played at all
+    NotAWord,
+    /// The message was already processed, e.g. delivered again after a reconnect
+    Duplicate,
+    /// The player has already had the daily maximum of words accepted
+    DailyLimitReached(usize),
+    /// The player's previous word was accepted too recently, with the time left to wait
+    CoolingDown(Duration),
+    /// Played out of turn in rotation mode, with the player whose turn it is
+    NotYourTurn(u64),
+    /// Not in the dictionary but likely a typo of the word given, never played
+    LikelyTypo(String),
+    /// On the blocklist, never played or checked further
+    Blocked,
+    /// Has letters outside the allowed alphabet, never played or checked further
+    ForeignLetters(Vec<char>),
+    /// Played while the game is paused, ignored
+    Paused,
+}
+
+/// The rules in effect and the current chain head
+#[derive(Debug, Clone)]
+pub struct RulesInfo {
+    pub rules: GameRules,
+    pub current_word: Option<String>,
+    /// Words accepted into the chain this game
+    pub chain_length: usize,
+}
+
+/// A word that was taken out of the chain when an earlier word was rolled back
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invalidated {
+    pub message_id: u64,
+    /// What the word was marked as before the rollback
+    pub previous: Validity,
+}
+
+/// Counts over the words currently in the history
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GameStats {
+    /// Words accepted into the chain this game
+    pub chain_length: usize,
+    pub valid: usize,
+    pub invalid: usize,
+    pub pending: usize,
+}
+
+/// The state and rules of one word chain game
+pub struct GameEngine {
+    /// Words accepted without asking the LLM, shared with searches run off the game
+    dictionary: Arc<DictionaryValidator>,
+
+    /// How played words are picked out of messages
+    extract_options: ExtractOptions,
+
+    /// Rules validator
+    rules_validator: RulesValidator,
+
+    /// History of words in the game
+    history: VecDeque<WordEntry>,
+
+    /// How many words to keep in history
+    history_size: usize,
+
+    /// The last valid word in the game
+    last_valid_word: Option<String>,
+
+    /// The last word that follows game rules (might be pending LLM validation)
+    last_game_rule_word: Option<String>,
+
+    /// Who played last_game_rule_word
+    last_game_rule_user: Option<u64>,
+
+    /// When the last word following the game rules was accepted
+    last_accepted_at: Option<Instant>,
+
+    /// Word the chain was started from without anyone playing it, e.g. a challenge
+    origin: Option<String>,
+
+    /// Whether unknown words that look like typos of a dictionary word are turned away
+    typo_check: bool,
+
+    /// Limits unknown words have to stay within to be worth asking the LLM about
+    plausibility: Option<PlausibilityFilter>,
+}
+
+impl GameEngine {
+    pub fn new(dictionary: DictionaryValidator, rules: GameRules) -> Self {
+        Self::with_shared_dictionary(Arc::new(dictionary), rules)
+    }
+
+    /// Create a game on a dictionary that other games use too
+    pub fn with_shared_dictionary(dictionary: Arc<DictionaryValidator>, rules: GameRules) -> Self {
+        Self {
+            dictionary,
+            extract_options: ExtractOptions::default(),
+            rules_validator: RulesValidator::new(rules),
+            history: VecDeque::with_capacity(DEFAULT_HISTORY_SIZE),
+            history_size: DEFAULT_HISTORY_SIZE,
+            last_valid_word: None,
+            last_game_rule_word: None,
+            last_game_rule_user: None,
+            last_accepted_at: None,
+            origin: None,
+            typo_check: false,
+            plausibility: None,
+        }
+    }
+
+    /// Create a game with the rules, word extraction and history size from the configuration
+    ///
+    /// In lenient mode inflected forms of dictionary words are accepted too.
+    pub fn from_config(dictionary: DictionaryValidator, config: &Config) -> Self {
+        Self::from_shared_config(
+            Arc::new(Self::prepare_dictionary(dictionary, config)),
+            config,
+        )
+    }
+
+    /// Like [`GameEngine::from_config`], on a dictionary shared between games
+    ///
+    /// The dictionary must have gone through [`GameEngine::prepare_dictionary`].
+    pub fn from_shared_config(dictionary: Arc<DictionaryValidator>, config: &Config) -> Self {
+        Self::with_shared_dictionary(dictionary, GameRules::from_config(config))
+            .with_extract_options(ExtractOptions::from_config(config))
+            .with_history_size(config.history_size)
+            .with_typo_check(config.typo_check)
+            .with_plausibility_filter(PlausibilityFilter::from_config(config))
+    }
+
+    /// The dictionary with the endings stripped in lenient mode, if it's on
+    pub fn prepare_dictionary(
+        dictionary: DictionaryValidator,
+        config: &Config,
+    ) -> DictionaryValidator {
+        if config.lenient_dictionary {
+            // The rules were checked when the configuration was loaded
+            dictionary.with_inflections(parse_rules(&config.inflection_rules).unwrap_or_default())
+        } else {
+            dictionary
+        }
+    }
+
+    /// Keep up to `history_size` previous words
+    pub fn with_history_size(mut self, history_size: usize) -> Self {
+        self.history_size = history_size.max(1);
+        self
+    }
+
+    /// Turn away unknown words that are likely typos instead of leaving them to the LLM
+    pub fn with_typo_check(mut self, typo_check: bool) -> Self {
+        self.typo_check = typo_check;
+        self
+    }
+
+    /// Reject unknown words that don't look like words instead of leaving them to the LLM
+    pub fn with_plausibility_filter(mut self, plausibility: Option<PlausibilityFilter>) -> Self {
+        self.plausibility = plausibility;
+        self
+    }
+
+    /// Pick played words out of messages with the given options
+    pub fn with_extract_options(mut self, extract_options: ExtractOptions) -> Self {
+        self.extract_options = extract_options;
+        self
+    }
+
+    /// The words this game accepts without asking the LLM
+    pub fn dictionary(&self) -> &DictionaryValidator {
+        &self.dictionary
+    }
+
+    /// The dictionary, for work too slow to do while holding the game, e.g. path searches
+    pub fn shared_dictionary(&self) -> Arc<DictionaryValidator> {
+        self.dictionary.clone()
+    }
+
+    /// The rules this game enforces
+    pub fn rules(&self) -> &GameRules {
+        self.rules_validator.rules()
+    }
+
+    /// Extract and normalize the word played in a message, if there is one
+    pub fn extract(&self, content: &str) -> Option<String> {
+        extract_word(content, &self.extract_options).map(|word| normalize_word(&word))
+    }
+
+    /// Play the word in a message, advancing the chain if it's accepted
+    pub fn submit_word(&mut self, content: &str, user_id: u64, message_id: u64) -> WordVerdict {
+        let Some(written) = extract_word(content, &self.extract_options) else {
+            // Likely chat, which is kept out of the logs
+            debug!(
+                chars = content.chars().count(),
+                "Skipping message without a single playable word"
+            );
+            return WordVerdict::NotAWord;
+        };
+        let word = normalize_word(&written);
+
+        let file = self.dictionary_file(&word);
+        let in_dictionary = file.is_some();
+        debug!(word = %word, in_dictionary, "Looked up word in the dictionary");
+
+        // Without proper nouns there's nothing more to check for unknown words,
+        // and they must not advance the chain
+        if !in_dictionary && !self.rules().accept_proper_nouns {
+            debug!(word = %word, "Word not in dictionary, marked as invalid");
+            return WordVerdict::NotInDictionary;
+        }
+        // Nor under the house rule for unknown words written in lowercase
+        if !in_dictionary && self.rules().require_capitalization && !is_capitalized(&written) {
+            debug!(
+                word = %word,
+                "Word not in dictionary nor capitalized, marked as invalid"
+            );
+            return WordVerdict::NotInDictionary;
+        }
+        if let (false, Some(filter)) = (in_dictionary, &self.plausibility) {
+            if let Err(reason) = filter.check(&word, &self.dictionary) {
+                debug!(
+                    word = %word,
+                    reason = %reason,
+                    "Word doesn't look like a word, marked as invalid"
+                );
+                return WordVerdict::Implausible;
+            }
+        }
+        if !in_dictionary && self.typo_check {
+            if let Some(meant) = self.likely_typo(&word, user_id) {
+                debug!(word = %word, meant = %meant, "Word is likely a typo, not played");
+                return WordVerdict::LikelyTypo(meant);
+            }
+        }
+
+        let known = file.map(|file| VerdictSource::Dictionary { file: Some(file) });
+        match self.process_word(&word, user_id, message_id, known) {
+            RuleOutcome::FollowsRules if in_dictionary => WordVerdict::ValidInDictionary,
+            RuleOutcome::FollowsRules => WordVerdict::PendingLlm,
+            RuleOutcome::RuleViolation(reason) => WordVerdict::InvalidRules(reason),
+            RuleOutcome::AlreadyUsed => WordVerdict::InvalidRules(Violation::AlreadyUsed(word)),
+            RuleOutcome::AwaitingVerdict => WordVerdict::AwaitingVerdict,
+        }
+    }
+
+    /// Judge the word in a message without playing it or asking the LLM
+    pub fn check_word(&self, content: &str, user_id: Option<u64>) -> WordVerdict {
+        let Some(word) = self.extract(content) else {
+            return WordVerdict::NotAWord;
+        };
+        let in_dictionary = self.dictionary_file(&word).is_some();
+
+        match self.evaluate(&word, user_id) {
+            RuleOutcome::FollowsRules if in_dictionary => WordVerdict::ValidInDictionary,
+            RuleOutcome::FollowsRules => WordVerdict::NotInDictionary,
+            RuleOutcome::RuleViolation(reason) => WordVerdict::InvalidRules(reason),
+            RuleOutcome::AlreadyUsed => WordVerdict::InvalidRules(Violation::AlreadyUsed(word)),
+            RuleOutcome::AwaitingVerdict => WordVerdict::AwaitingVerdict,
+        }
+    }
+
+    /// The dictionary word an unknown word was likely meant to be
+    ///
+    /// That's the case when the word would continue the chain and so would
+    /// exactly one of the dictionary words a letter away from it. With none or
+    /// several there's no telling, and the word may well be a proper noun.
+    fn likely_typo(&self, word: &str, user_id: u64) -> Option<String> {
+        if !self.evaluate(word, Some(user_id)).is_valid() {
+            return None;
+        }
+        let mut candidates = self
+            .dictionary
+            .neighbors(word)
+            .into_iter()
+            .filter(|neighbor| self.evaluate(neighbor, Some(user_id)).is_valid());
+        let meant = candidates.next()?;
+        candidates.next().is_none().then_some(meant)
+    }
+
+    /// Name of the dictionary file that has the word, logging the rule if it's an inflected form
+    fn dictionary_file(&self, word: &str) -> Option<Arc<str>> {
+        let found = self.dictionary.lookup(word)?;
+        if let Some(rule) = &found.rule {
+            debug!(
+                word = %word,
+                lemma = %found.lemma,
+                rule = %rule,
+                "Word accepted as an inflection"
+            );
+        }
+        debug!(word = %found.lemma, file = %found.source.name, "Word found in dictionary");
+        Some(found.source.name.clone())
+    }
+
+    /// Check an already extracted word against the rules and record it in the history
+    ///
+    /// Words `known` to be valid, e.g. from the dictionary, need no further
+    /// checks. The others stay pending until [`mark_validity`](Self::mark_validity).
+    pub fn process_word(
+        &mut self,
+        word: &str,
+        user_id: u64,
+        message_id: u64,
+        known: Option<VerdictSource>,
+    ) -> RuleOutcome {
+        let outcome = self.check_rules(word, Some(user_id));
+
+        // Breaking the rules is final, and known words need no further checks
+        let (validity, source) = match (outcome.is_valid(), known) {
+            (false, _) => (Validity::Invalid, None),
+            (true, Some(source)) => (Validity::Valid, Some(source)),
+            (true, None) => (Validity::Pending, None),
+        };
+        if validity == Validity::Valid {
+            self.last_valid_word = Some(word.to_string());
+        }
+
+        let now = SystemTime::now();
+        self.add_to_history(WordEntry {
+            word: word.to_string(),
+            user_id,
+            message_id,
+            validity,
+            source,
+            played_at: now,
+            settled_at: (validity != Validity::Pending).then_some(now),
+            in_chain: outcome.is_valid(),
+            delivery: Delivery::default(),
+        });
+
+        outcome
+    }
+
+    /// Record a word in the history without checking it
+    pub fn register_word(&mut self, word: &str, user_id: u64, message_id: u64) {
+        self.add_to_history(WordEntry {
+            word: word.to_string(),
+            user_id,
+            message_id,
+            validity: Validity::Pending,
+            source: None,
+            played_at: SystemTime::now(),
+            settled_at: None,
+            in_chain: false,
+            delivery: Delivery::default(),
+        });
+    }
+
+    /// Check a word against the current chain head without changing anything
+    pub fn evaluate(&self, word: &str, user_id: Option<u64>) -> RuleOutcome {
+        let rules = self.rules_validator.rules();
+        if !rules.allow_consecutive_turns
+            && user_id.is_some()
+            && user_id == self.last_game_rule_user
+        {
+            return RuleOutcome::RuleViolation(Violation::ConsecutiveTurns);
+        }
+
+        if rules.pending_policy == PendingPolicy::Strict && self.head_is_pending() {
+            return RuleOutcome::AwaitingVerdict;
+        }
+
+        match self.current_word() {
+            Some(last_word) => {
+                debug!("Comparing with last rule-valid word: '{}'", last_word);
+                self.rules_validator
+                    .validate_move_dry_run(&last_word, word)
+                    .into()
+            }
+            // If there's no last valid word, any word of acceptable length starts the chain
+            None => self.rules_validator.validate_length(word).into(),
+        }
+    }
+
+    /// Check a word against the current chain head, advancing the chain if it follows the rules
+    pub fn check_rules(&mut self, word: &str, user_id: Option<u64>) -> RuleOutcome {
+        if self.current_word().is_none() {
+            debug!("No previous valid word, checking '{}' as first word", word);
+        }
+
+        let outcome = self.evaluate(word, user_id);
+        if outcome.is_valid() {
+            debug!(
+                "Word '{}' follows game rules, updating last_game_rule_word",
+                word
+            );
+            self.last_game_rule_word = Some(word.to_string());
+            self.last_game_rule_user = user_id;
+            self.last_accepted_at = Some(Instant::now());
+            self.rules_validator.add_word(word);
+        }
+
+        debug!("Word '{}' follows game rules: {}", word, outcome.is_valid());
+        outcome
+    }
+
+    /// Record the final verdict on a pending word and what it came from
+    ///
+    /// Returns None if the word had already been settled, in which case the
+    /// verdict is ignored. Otherwise returns the words that had to be taken out
+    /// of the chain because they were built on a rejected word. An accepted
+    /// word only becomes the last valid word if no word after it in the chain
+    /// has been accepted yet, so a late verdict can't take it back past them.
+    pub fn mark_validity(
+        &mut self,
+        message_id: u64,
+        validity: Validity,
+        source: VerdictSource,
+    ) -> Option<Vec<Invalidated>> {
+        debug!("Marking message {} as {:?}", message_id, validity);
+
+        // Find the entry by message ID and update its validity
+        let Some(position) = self
+            .history
+            .iter()
+            .position(|entry| entry.message_id == message_id)
+        else {
+            debug!("Could not find message {} to mark validity", message_id);
+            return Some(Vec::new());
+        };
+
+        let superseded = self
+            .history
+            .iter()
+            .skip(position + 1)
+            .any(|entry| entry.in_chain && entry.validity == Validity::Valid);
+        let entry = &mut self.history[position];
+        if entry.validity != Validity::Pending {
+            debug!(
+                "Message {} was already settled as {:?}, ignoring",
+                message_id, entry.validity
+            );
+            return None;
+        }
+        entry.validity = validity;
+        entry.source = Some(source);
+        entry.settled_at = Some(SystemTime::now());
+
+        match validity {
+            Validity::Valid if superseded || !entry.in_chain => {
+                debug!(
+                    "'{}' has been played on since, keeping {} as the last valid word",
+                    entry.word,
+                    self.last_valid_word.as_deref().unwrap_or("<none>")
+                );
+                Some(Vec::new())
+            }
+            Validity::Valid => {
+                // If valid, update the last valid word
+                debug!(
+                    "Updating last valid word from {} to: {}",
+                    self.last_valid_word.as_deref().unwrap_or("<none>"),
+                    entry.word
+                );
+                self.last_valid_word = Some(entry.word.clone());
+                Some(Vec::new())
+            }
+            Validity::Invalid
+                if entry.in_chain && self.rules().pending_policy != PendingPolicy::Optimistic =>
+            {
+                Some(self.roll_back(position))
+            }
+            _ => Some(Vec::new()),
+        }
+    }
+
+    /// Reverse the verdict on a settled word, e.g. after the players voted against it
+    ///
+    /// Words that broke the rules or were played by the bot can't be
+    /// overturned. Only the chain head is adjusted: a rejected head is rolled
+    /// back, and an accepted word joins the chain if nothing was played on top
+    /// of it and it follows the rules. Returns the word's new validity, None
+    /// if it couldn't be overturned.
+    pub fn overturn(&mut self, message_id: u64, source: VerdictSource) -> Option<Validity> {
+        let position = self
+            .history
+            .iter()
+            .position(|entry| entry.message_id == message_id)?;
+        let entry = &self.history[position];
+        if matches!(entry.source, None | Some(VerdictSource::Seed)) {
+            return None;
+        }
+        let validity = match entry.validity {
+            Validity::Valid => Validity::Invalid,
+            Validity::Invalid => Validity::Valid,
+            Validity::Pending => return None,
+        };
+        let is_head = !self
+            .history
+            .iter()
+            .skip(position + 1)
+            .any(|entry| entry.in_chain);
+
+        let entry = &mut self.history[position];
+        info!(word = %entry.word, ?validity, "Overturning the verdict");
+        entry.validity = validity;
+        entry.source = Some(source);
+        entry.settled_at = Some(SystemTime::now());
+        let (word, user_id, in_chain) = (entry.word.clone(), entry.user_id, entry.in_chain);
+
+        match validity {
+            Validity::Invalid if in_chain && is_head => {
+                self.roll_back(position);
+            }
+            Validity::Valid if in_chain && is_head => self.last_valid_word = Some(word),
+            // Checking the rules adds the word to the chain if it follows them
+            Validity::Valid if is_head && self.check_rules(&word, Some(user_id)).is_valid() => {
+                self.history[position].in_chain = true;
+                self.last_valid_word = Some(word);
+            }
+            _ => {}
+        }
+        Some(validity)
+    }
+
+    /// Forget the whole chain and every word played so far
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.rules_validator.reset();
+        self.last_valid_word = None;
+        self.last_game_rule_word = None;
+        self.last_game_rule_user = None;
+        self.last_accepted_at = None;
+        self.origin = None;
+    }
+
+    /// Start a new game from `word`, as if it had just been accepted
+    ///
+    /// The word isn't in the history, as nobody played it.
+    pub fn start_from(&mut self, word: &str) {
+        self.reset();
+        self.continue_from(word);
+    }
+
+    /// Make `word` the chain head, as if it had just been accepted
+    ///
+    /// Unlike [`start_from`](Self::start_from) the history and the used words
+    /// are kept, but the words played so far are no longer part of the chain.
+    /// Words still waiting for a verdict were played against the old head, so
+    /// they're rejected. Returns them as they were before.
+    pub fn set_current_word(&mut self, word: &str) -> Vec<WordEntry> {
+        let mut dropped = Vec::new();
+        for entry in &mut self.history {
+            if entry.validity == Validity::Pending {
+                dropped.push(entry.clone());
+                if entry.in_chain {
+                    self.rules_validator.remove_word(&entry.word);
+                }
+                entry.validity = Validity::Invalid;
+            }
+            entry.in_chain = false;
+        }
+        self.continue_from(word);
+        dropped
+    }
+
+    /// Continue the chain from a word nobody played
+    fn continue_from(&mut self, word: &str) {
+        self.last_valid_word = Some(word.to_string());
+        self.last_game_rule_word = Some(word.to_string());
+        self.last_game_rule_user = None;
+        self.last_accepted_at = Some(Instant::now());
+        self.origin = Some(word.to_string());
+        self.rules_validator.add_word(word);
+    }
+
+    /// Move the time of the last accepted word `by` later, e.g. to not count a pause as idle
+    pub fn postpone_idle(&mut self, by: Duration) {
+        if let Some(last) = &mut self.last_accepted_at {
+            *last += by;
+        }
+    }
+
+    /// When the last word following the rules was accepted, if any this game
+    pub fn last_accepted_at(&self) -> Option<Instant> {
+        self.last_accepted_at
+    }
+
+    /// Whether a game in progress has gone `idle_period` without an accepted word
+    pub fn is_idle(&self, now: Instant, idle_period: Duration) -> bool {
+        self.last_accepted_at
+            .is_some_and(|last| now.saturating_duration_since(last) >= idle_period)
+    }
+
+    /// The word new moves are compared against
+    ///
+    /// Uses last_game_rule_word if available, otherwise falls back to last_valid_word.
+    pub fn current_word(&self) -> Option<String> {
+        self.last_game_rule_word
+            .clone()
+            .or_else(|| self.last_valid_word.clone())
+    }
+
+    /// Who played the word new moves are compared against, if anyone
+    pub fn last_player(&self) -> Option<u64> {
+        self.last_game_rule_user
+    }
+
+    /// Dictionary words `user_id` could play next, in a stable order
+    ///
+    /// None if there's no chain to continue or it has to wait for the LLM's
+    /// verdict on the chain head first.
+    pub fn continuations(&self, user_id: u64) -> Option<Vec<String>> {
+        let current = self.current_word()?;
+        if matches!(
+            self.rules().pending_policy,
+            PendingPolicy::Strict | PendingPolicy::Queue
+        ) && self.head_is_pending()
+        {
+            return None;
+        }
+        Some(
+            self.dictionary
+                .moves(&current, self.rules().diacritic_policy)
+                .into_iter()
+                .filter(|word| self.evaluate(word, Some(user_id)).is_valid())
+                .collect(),
+        )
+    }
+
+    /// The last word confirmed valid
+    pub fn last_valid_word(&self) -> Option<&str> {
+        self.last_valid_word.as_deref()
+    }
+
+    /// The rules in effect and the current chain head
+    pub fn rules_info(&self) -> RulesInfo {
+        RulesInfo {
+            rules: self.rules().clone(),
+            current_word: self.current_word(),
+            chain_length: self.rules_validator.word_count(),
+        }
+    }
+
+    /// Up to `limit` dictionary words one letter away from `word`
+    pub fn suggestions(&self, word: &str, limit: usize) -> Vec<String> {
+        self.dictionary.suggestions(word, limit)
+    }
+
+    /// How many played words the history keeps
+    pub fn history_size(&self) -> usize {
+        self.history_size
+    }
+
+    /// Whether the word played in a message is still in the history
+    pub fn in_history(&self, message_id: u64) -> bool {
+        self.entry(message_id).is_some()
+    }
+
+    /// The word played in a message, if it's still in the history
+    pub fn entry(&self, message_id: u64) -> Option<&WordEntry> {
+        self.history
+            .iter()
+            .find(|entry| entry.message_id == message_id)
+    }
+
+    /// Note whether the reaction showing a word's verdict reached Discord
+    ///
+    /// Reports about a verdict the word no longer has are ignored, since the
+    /// reaction showing the new one is on its way.
+    pub fn mark_delivery(&mut self, message_id: u64, validity: Validity, delivery: Delivery) {
+        if let Some(entry) = self
+            .history
+            .iter_mut()
+            .find(|entry| entry.message_id == message_id && entry.validity == validity)
+        {
+            entry.delivery = delivery;
+        }
+    }
+
+    /// The words whose verdict reaction failed, oldest first
+    pub fn undelivered(&self) -> Vec<WordEntry> {
+        self.history
+            .iter()
+            .filter(|entry| entry.delivery == Delivery::Failed)
+            .cloned()
+            .collect()
+    }
+
+    /// The word the chain continued from when a message was played, None if
+    /// the message isn't in the history or started the chain
+    pub fn previous_word(&self, message_id: u64) -> Option<String> {
+        let position = self
+            .history
+            .iter()
+            .position(|entry| entry.message_id == message_id)?;
+        self.link_before(position).map(|(word, _)| word)
+    }
+
+    /// The last link before a position in the history and who played it, or
+    /// the word the chain was started from
+    fn link_before(&self, position: usize) -> Option<(String, Option<u64>)> {
+        self.history
+            .range(..position)
+            .rev()
+            .find(|entry| entry.in_chain)
+            .map(|entry| (entry.word.clone(), Some(entry.user_id)))
+            .or_else(|| self.origin.clone().map(|word| (word, None)))
+    }
+
+    /// The most recently played words, newest first
+    pub fn history(&self, limit: usize) -> Vec<WordEntry> {
+        self.history.iter().rev().take(limit).cloned().collect()
+    }
+
+    /// Counts over the current game
+    pub fn stats(&self) -> GameStats {
+        let count = |validity| {
+            self.history
+                .iter()
+                .filter(|entry| entry.validity == validity)
+                .count()
+        };
+
+        GameStats {
+            chain_length: self.rules_validator.word_count(),
+            valid: count(Validity::Valid),
+            invalid: count(Validity::Invalid),
+            pending: count(Validity::Pending),
+        }
+    }
+
+    /// Add a word to the history and maintain maximum size
+    fn add_to_history(&mut self, entry: WordEntry) {
+        self.history.push_back(entry);
+
+        // Keep history at maximum size
+        if self.history.len() > self.history_size {
+            self.history.pop_front();
+        }
+    }
+
+    /// Whether the chain head is still waiting for the LLM
+    pub fn head_is_pending(&self) -> bool {
+        self.history
+            .iter()
+            .rev()
+            .find(|entry| entry.in_chain)
+            .is_some_and(|entry| entry.validity == Validity::Pending)
+    }
+
+    /// Take a rejected word out of the chain and re-check the words played after it
+    ///
+    /// Returns the words that no longer follow the rules against the corrected chain.
+    fn roll_back(&mut self, position: usize) -> Vec<Invalidated> {
+        let rejected = &mut self.history[position];
+        rejected.in_chain = false;
+        let rejected_word = rejected.word.clone();
+        self.rules_validator.remove_word(&rejected_word);
+
+        // Rewind the chain head to the last link before the rejected word, or
+        // to the word the chain was started from
+        let previous = self.link_before(position);
+        info!(
+            word = %rejected_word,
+            continues_from = ?previous.as_ref().map(|(word, _)| word),
+            "Rolling back word"
+        );
+        match &previous {
+            None => self.last_valid_word = None,
+            Some((origin, None)) => self.last_valid_word = Some(origin.clone()),
+            Some(_) => {}
+        }
+        (self.last_game_rule_word, self.last_game_rule_user) = match previous {
+            Some((word, user_id)) => (Some(word), user_id),
+            None => (None, None),
+        };
+
+        let followers: Vec<usize> = (position + 1..self.history.len())
+            .filter(|&i| self.history[i].in_chain)
+            .collect();
+        let mut invalidated = Vec::new();
+        for i in followers {
+            let word = self.history[i].word.clone();
+            let user_id = self.history[i].user_id;
+
+            self.rules_validator.remove_word(&word);
+            if self.check_rules(&word, Some(user_id)).is_valid() {
+                continue;
+            }
+
+            info!(word = %word, "Word no longer follows the rules after rollback");
+            let entry = &mut self.history[i];
+            invalidated.push(Invalidated {
+                message_id: entry.message_id,
+                previous: entry.validity,
+            });
+            entry.in_chain = false;
+            entry.validity = Validity::Invalid;
+        }
+
+        if let Some(entry) = self
+            .history
+            .iter()
+            .rev()
+            .find(|entry| entry.in_chain && entry.validity == Validity::Valid)
+        {
+            self.last_valid_word = Some(entry.word.clone());
+        }
+
+        invalidated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn engine() -> GameEngine {
+        engine_with(GameRules::default())
+    }
+
+    fn by_llm() -> VerdictSource {
+        VerdictSource::Llm {
+            explanation: "Nimi".to_string(),
+            model: Some("gemini-pro".to_string()),
+        }
+    }
+
+    fn engine_with(rules: GameRules) -> GameEngine {
+        GameEngine::new(
+            DictionaryValidator::from_words(["kissa", "kassa", "kassi", "kussa", "koira"]),
+            rules,
+        )
+    }
+
+    #[test]
+    fn test_submit_word_verdicts() {
+        let mut game = engine();
+
+        assert_eq!(
+            game.submit_word("Kissa", 1, 1),
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            game.submit_word("kassa", 2, 2),
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(game.submit_word("kassu", 1, 3), WordVerdict::PendingLlm);
+        assert!(matches!(
+            game.submit_word("koira", 2, 4),
+            WordVerdict::InvalidRules(_)
+        ));
+        assert!(matches!(
+            game.submit_word("kassa", 1, 5),
+            WordVerdict::InvalidRules(_)
+        ));
+        assert_eq!(game.submit_word("kissa 2", 2, 6), WordVerdict::NotAWord);
+        assert_eq!(
+            game.entry(2).unwrap().source,
+            Some(VerdictSource::Dictionary {
+                file: Some("memory".into())
+            })
+        );
+        assert_eq!(game.entry(3).unwrap().source, None);
+
+        // Chains off the pending word under the default policy
+        assert_eq!(game.current_word().as_deref(), Some("kassu"));
+        assert_eq!(
+            game.stats(),
+            GameStats {
+                chain_length: 3,
+                valid: 2,
+                invalid: 2,
+                pending: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_process_word_sequence() {
+        let mut game = engine();
+
+        let outcomes: Vec<_> = ["kissa", "kassa", "kassi", "kissa", "koira", "kasvi"]
+            .iter()
+            .enumerate()
+            .map(|(i, word)| {
+                game.process_word(
+                    word,
+                    i as u64 % 2,
+                    i as u64,
+                    Some(VerdictSource::Dictionary { file: None }),
+                )
+            })
+            .collect();
+
+        assert_eq!(outcomes[0], RuleOutcome::FollowsRules);
+        assert_eq!(outcomes[1], RuleOutcome::FollowsRules);
+        assert_eq!(outcomes[2], RuleOutcome::FollowsRules);
+        assert_eq!(outcomes[3], RuleOutcome::AlreadyUsed);
+        assert!(matches!(outcomes[4], RuleOutcome::RuleViolation(_)));
+        // Rejected words must not move the chain head away from "kassi"
+        assert_eq!(outcomes[5], RuleOutcome::FollowsRules);
+    }
+
+    #[test]
+    fn test_copied_words_already_used() {
+        let mut game = engine();
+        assert_eq!(
+            game.submit_word("kissa", 1, 1),
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            game.submit_word("kassa", 2, 2),
+            WordVerdict::ValidInDictionary
+        );
+
+        // The earlier message pasted with invisible characters is the same word
+        for (message_id, copied) in [
+            (3, "kis\u{200B}sa"),
+            (4, "kis\u{2060}sa"),
+            (5, "\u{FEFF}kissa"),
+        ] {
+            assert_eq!(
+                game.submit_word(copied, 1, message_id),
+                WordVerdict::InvalidRules(Violation::AlreadyUsed("kissa".to_string())),
+                "{:?}",
+                copied
+            );
+        }
+        assert_eq!(game.current_word().as_deref(), Some("kassa"));
+
+        // And a new word is accepted as if it was typed by hand
+        assert_eq!(
+            game.submit_word("\u{FEFF}kas\u{200B}si", 1, 6),
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(game.entry(6).unwrap().word, "kassi");
+    }
+
+    #[test]
+    fn test_check_word_does_not_mutate() {
+        let mut game = engine();
+
+        assert_eq!(
+            game.check_word("kissa", None),
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            game.check_word("koira", None),
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(game.check_word("kissu", None), WordVerdict::NotInDictionary);
+        assert_eq!(game.check_word("ki ssa", None), WordVerdict::NotAWord);
+
+        game.submit_word("kissa", 1, 1);
+        assert_eq!(
+            game.check_word("kassa", None),
+            WordVerdict::ValidInDictionary
+        );
+        assert!(matches!(
+            game.check_word("kissa", None),
+            WordVerdict::InvalidRules(_)
+        ));
+
+        // A checked word can still be played afterwards
+        assert_eq!(game.history(10).len(), 1);
+        assert_eq!(
+            game.submit_word("kassa", 1, 2),
+            WordVerdict::ValidInDictionary
+        );
+    }
+
+    #[test]
+    fn test_consecutive_turns() {
+        let mut game = engine_with(GameRules {
+            allow_consecutive_turns: false,
+            ..GameRules::default()
+        });
+
+        assert_eq!(
+            game.submit_word("kissa", 1, 1),
+            WordVerdict::ValidInDictionary
+        );
+        assert!(matches!(
+            game.submit_word("kassa", 1, 2),
+            WordVerdict::InvalidRules(_)
+        ));
+        assert_eq!(
+            game.submit_word("kassa", 2, 3),
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            game.submit_word("kassi", 1, 4),
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(game.rules_info().current_word.as_deref(), Some("kassi"));
+    }
+
+    #[test]
+    fn test_proper_nouns_disabled() {
+        let mut game = engine_with(GameRules {
+            accept_proper_nouns: false,
+            ..GameRules::default()
+        });
+
+        assert_eq!(
+            game.submit_word("kissa", 1, 1),
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            game.submit_word("kissu", 1, 2),
+            WordVerdict::NotInDictionary
+        );
+        // The unknown word didn't advance the chain
+        assert_eq!(
+            game.submit_word("kassa", 1, 3),
+            WordVerdict::ValidInDictionary
+        );
+    }
+
+    #[test]
+    fn test_plausibility_filter() {
+        let filter = PlausibilityFilter {
+            max_consonants: 5,
+            max_repeats: 3,
+            min_known_trigrams: 1,
+        };
+        let mut game = engine_with(GameRules::default()).with_plausibility_filter(Some(filter));
+        game.submit_word("kissa", 1, 1);
+
+        // Mash is rejected without being played or sent to the LLM
+        assert_eq!(game.submit_word("kxzqa", 2, 2), WordVerdict::Implausible);
+        assert!(!game.in_history(2));
+        assert_eq!(game.submit_word("kissu", 2, 3), WordVerdict::PendingLlm);
+        // Dictionary words are never filtered
+        let mut game = GameEngine::new(
+            DictionaryValidator::from_words(["kissa", "kssss"]),
+            GameRules::default(),
+        )
+        .with_plausibility_filter(Some(filter));
+        assert_eq!(
+            game.submit_word("kssss", 1, 1),
+            WordVerdict::ValidInDictionary
+        );
+    }
+
+    #[test]
+    fn test_likely_typo() {
+        let game_with = |words: &[&str], typo_check: bool| {
+            let mut game = GameEngine::new(
+                DictionaryValidator::from_words(words.iter().copied()),
+                GameRules::default(),
+            )
+            .with_typo_check(typo_check);
+            game.submit_word("kissa", 1, 1);
+            game
+        };
+
+        // kissa is a letter away too, but it can't be played again
+        let mut game = game_with(&["kissa", "kassa", "koira"], true);
+        assert_eq!(
+            game.submit_word("kyssa", 2, 2),
+            WordVerdict::LikelyTypo("kassa".to_string())
+        );
+        // Nothing was played, so the player can try again
+        assert_eq!(game.current_word().as_deref(), Some("kissa"));
+        assert!(!game.in_history(2));
+        assert_eq!(
+            game.submit_word("kassa", 2, 3),
+            WordVerdict::ValidInDictionary
+        );
+
+        // Without a dictionary word that would continue the chain, the LLM decides
+        let mut game = game_with(&["kissa", "kassa", "koira"], true);
+        assert_eq!(game.submit_word("kisssa", 2, 2), WordVerdict::PendingLlm);
+
+        // Nor can the typo be told apart from several candidates
+        let mut game = game_with(&["kissa", "kassa", "kussa", "koira"], true);
+        assert_eq!(game.submit_word("kyssa", 2, 2), WordVerdict::PendingLlm);
+
+        // Only words that go to the LLM are checked
+        let mut game = game_with(&["kissa", "kassa", "koira"], true);
+        assert!(matches!(
+            game.submit_word("kyssä", 1, 2),
+            WordVerdict::InvalidRules(_)
+        ));
+        let mut game = game_with(&["kissa", "kassa", "koira"], false);
+        assert_eq!(game.submit_word("kyssa", 2, 2), WordVerdict::PendingLlm);
+    }
+
+    #[test]
+    fn test_require_capitalization() {
+        let mut game = engine_with(GameRules {
+            require_capitalization: true,
+            ..GameRules::default()
+        });
+        assert_eq!(
+            game.submit_word("kissa", 1, 1),
+            WordVerdict::ValidInDictionary
+        );
+        // Unknown words only go to the LLM if capitalized
+        assert_eq!(
+            game.submit_word("kissu", 2, 2),
+            WordVerdict::NotInDictionary
+        );
+        assert_eq!(game.submit_word("Kissu", 2, 3), WordVerdict::PendingLlm);
+        // Dictionary words count however they're written
+        let mut game = engine_with(GameRules {
+            require_capitalization: true,
+            ..GameRules::default()
+        });
+        assert_eq!(
+            game.submit_word("KISSA", 1, 1),
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(
+            game.submit_word("kassa", 2, 2),
+            WordVerdict::ValidInDictionary
+        );
+
+        // Without the rule, the casing doesn't matter
+        let mut game = engine_with(GameRules::default());
+        game.submit_word("kissa", 1, 1);
+        assert_eq!(game.submit_word("kissu", 2, 2), WordVerdict::PendingLlm);
+        let mut game = engine_with(GameRules::default());
+        game.submit_word("kissa", 1, 1);
+        assert_eq!(game.submit_word("Kissu", 2, 2), WordVerdict::PendingLlm);
+    }
+
+    #[test]
+    fn test_lenient_dictionary() {
+        let dictionary = || DictionaryValidator::from_words(["kissa", "kassa"]);
+        let mut strict = GameEngine::from_config(dictionary(), &Config::default());
+        strict.submit_word("kissa", 1, 1);
+        assert_eq!(strict.submit_word("kissan", 2, 2), WordVerdict::PendingLlm);
+
+        let config = Config {
+            lenient_dictionary: true,
+            ..Config::default()
+        };
+        let mut lenient = GameEngine::from_config(dictionary(), &config);
+        lenient.submit_word("kissa", 1, 1);
+        assert_eq!(
+            lenient.submit_word("kissan", 2, 2),
+            WordVerdict::ValidInDictionary
+        );
+        // The inflected form is the chain head, not its base form
+        assert_eq!(lenient.current_word().as_deref(), Some("kissan"));
+        assert_eq!(
+            lenient.check_word("kassan", None),
+            WordVerdict::ValidInDictionary
+        );
+    }
+
+    #[test]
+    fn test_history_and_mark_validity() {
+        let mut game = engine().with_history_size(3);
+
+        for (id, word) in (1..).zip(["kissa", "kisso", "koira", "kassi"]) {
+            game.submit_word(word, 1, id);
+        }
+        assert_eq!(
+            game.mark_validity(2, Validity::Valid, by_llm()),
+            Some(Vec::new())
+        );
+        // Settled words can't be overruled
+        assert_eq!(game.mark_validity(2, Validity::Invalid, by_llm()), None);
+
+        let summary: Vec<_> = game
+            .history(10)
+            .iter()
+            .map(|entry| (entry.word.clone(), entry.validity))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("kassi".to_string(), Validity::Invalid),
+                ("koira".to_string(), Validity::Invalid),
+                ("kisso".to_string(), Validity::Valid),
+            ]
+        );
+        assert_eq!(game.last_valid_word(), Some("kisso"));
+
+        // The first verdict is the one that's kept, and rule breakers weren't
+        // settled by anyone
+        assert_eq!(game.entry(2).unwrap().source, Some(by_llm()));
+        assert_eq!(game.entry(3).unwrap().source, None);
+    }
+
+    #[test]
+    fn test_late_verdict_keeps_the_chain_head() {
+        let mut game = GameEngine::new(
+            DictionaryValidator::from_words(["kassa", "kassi", "kasvi"]),
+            GameRules::default(),
+        );
+        game.submit_word("kassa", 1, 1);
+        assert_eq!(game.submit_word("kasso", 2, 2), WordVerdict::PendingLlm);
+        game.submit_word("kassi", 1, 3);
+        game.submit_word("kasvi", 2, 4);
+
+        // The verdict on "kasso" arrives long after the chain moved on
+        assert_eq!(
+            game.mark_validity(2, Validity::Valid, by_llm()),
+            Some(Vec::new())
+        );
+        assert_eq!(game.entry(2).unwrap().validity, Validity::Valid);
+        assert_eq!(game.last_valid_word(), Some("kasvi"));
+        assert_eq!(game.current_word().as_deref(), Some("kasvi"));
+        assert_eq!(game.stats().chain_length, 4);
+    }
+
+    #[test]
+    fn test_strict_pending_policy() {
+        let mut game = GameEngine::new(
+            DictionaryValidator::from_words(["kissa", "kassi", "kissi"]),
+            GameRules {
+                pending_policy: PendingPolicy::Strict,
+                ..GameRules::default()
+            },
+        );
+        game.submit_word("kissa", 1, 1);
+        assert_eq!(game.submit_word("kassa", 2, 2), WordVerdict::PendingLlm);
+        assert_eq!(
+            game.submit_word("kassi", 1, 3),
+            WordVerdict::AwaitingVerdict
+        );
+
+        // A rejected word is taken out of the chain and can't block it anymore
+        assert_eq!(
+            game.mark_validity(2, Validity::Invalid, by_llm()),
+            Some(Vec::new())
+        );
+        assert_eq!(game.current_word().as_deref(), Some("kissa"));
+        assert_eq!(
+            game.submit_word("kissi", 1, 4),
+            WordVerdict::ValidInDictionary
+        );
+    }
+
+    #[test]
+    fn test_rollback_pending_policy() {
+        let mut game = GameEngine::new(
+            DictionaryValidator::from_words(["kissa", "kassi"]),
+            GameRules {
+                pending_policy: PendingPolicy::Rollback,
+                ..GameRules::default()
+            },
+        );
+        game.submit_word("kissa", 1, 1);
+        game.submit_word("kassa", 2, 2);
+        game.submit_word("kassi", 1, 3);
+
+        // "kassi" is two letters away from "kissa"
+        let invalidated = game.mark_validity(2, Validity::Invalid, by_llm()).unwrap();
+        assert_eq!(
+            invalidated,
+            vec![Invalidated {
+                message_id: 3,
+                previous: Validity::Valid,
+            }]
+        );
+        assert_eq!(game.current_word().as_deref(), Some("kissa"));
+        assert_eq!(game.last_valid_word(), Some("kissa"));
+        assert_eq!(game.stats().chain_length, 1);
+    }
+
+    #[test]
+    fn test_overturn() {
+        let mut game = engine_with(GameRules {
+            pending_policy: PendingPolicy::Rollback,
+            ..GameRules::default()
+        });
+        let vote = || VerdictSource::Vote { votes: 3 };
+        game.submit_word("kissa", 1, 1);
+        game.submit_word("kassa", 2, 2);
+
+        // A rejected head is rolled back
+        assert_eq!(game.overturn(2, vote()), Some(Validity::Invalid));
+        assert_eq!(game.current_word().as_deref(), Some("kissa"));
+        assert_eq!(game.stats().chain_length, 1);
+        // and joins the chain again when accepted, as nothing was played after it
+        assert_eq!(game.overturn(2, vote()), Some(Validity::Valid));
+        assert_eq!(game.current_word().as_deref(), Some("kassa"));
+        assert_eq!(game.last_player(), Some(2));
+        assert_eq!(game.stats().chain_length, 2);
+
+        // Below the head only the verdict changes
+        game.submit_word("kasso", 3, 3);
+        game.mark_validity(3, Validity::Invalid, by_llm());
+        game.submit_word("kassi", 1, 4);
+        assert_eq!(game.overturn(3, vote()), Some(Validity::Valid));
+        assert!(!game.entry(3).unwrap().in_chain);
+        assert_eq!(game.current_word().as_deref(), Some("kassi"));
+
+        // Broken rules and pending words can't be overturned
+        game.submit_word("koira", 5, 5);
+        assert_eq!(game.overturn(5, vote()), None);
+        game.submit_word("kassu", 6, 6);
+        assert_eq!(game.overturn(6, vote()), None);
+        assert_eq!(game.overturn(99, vote()), None);
+    }
+
+    #[test]
+    fn test_idle_and_reset() {
+        let mut game = engine();
+        let idle = Duration::from_secs(60);
+        assert!(!game.is_idle(Instant::now() + idle * 2, idle));
+
+        game.submit_word("kissa", 1, 1);
+        let accepted_at = game.last_accepted_at().unwrap();
+        assert!(!game.is_idle(accepted_at + idle / 2, idle));
+        assert!(game.is_idle(accepted_at + idle, idle));
+
+        game.reset();
+        assert_eq!(game.current_word(), None);
+        assert!(game.history(10).is_empty());
+        assert_eq!(
+            game.submit_word("kissa", 1, 2),
+            WordVerdict::ValidInDictionary
+        );
+    }
+
+    #[test]
+    fn test_continuations() {
+        let mut game = engine();
+        assert_eq!(game.continuations(1), None);
+
+        game.submit_word("kissa", 1, 1);
+        assert_eq!(game.last_player(), Some(1));
+        assert_eq!(game.continuations(2).unwrap(), vec!["kassa", "kussa"]);
+        game.submit_word("kassa", 2, 2);
+        // Used words can't be played again
+        assert_eq!(game.continuations(1).unwrap(), vec!["kussa", "kassi"]);
+
+        let mut game = engine_with(GameRules {
+            allow_consecutive_turns: false,
+            pending_policy: PendingPolicy::Strict,
+            ..GameRules::default()
+        });
+        game.submit_word("kissa", 1, 1);
+        assert!(game.continuations(1).unwrap().is_empty());
+        assert_eq!(game.continuations(2).unwrap(), vec!["kassa", "kussa"]);
+
+        // Nothing to continue from until the LLM has had its say
+        game.submit_word("kisso", 2, 2);
+        assert_eq!(game.continuations(1), None);
+    }
+
+    #[test]
+    fn test_start_from() {
+        let mut game = engine();
+        game.submit_word("koira", 1, 1);
+
+        game.start_from("kissa");
+        assert_eq!(game.current_word().as_deref(), Some("kissa"));
+        assert!(game.history(10).is_empty());
+        assert_eq!(game.stats().chain_length, 1);
+        assert!(matches!(
+            game.submit_word("kissa", 1, 2),
+            WordVerdict::InvalidRules(_)
+        ));
+        assert_eq!(
+            game.submit_word("kassa", 1, 3),
+            WordVerdict::ValidInDictionary
+        );
+        assert_eq!(game.stats().chain_length, 2);
+    }
+
+    #[test]
+    fn test_previous_word() {
+        let mut game = engine();
+        game.submit_word("kissa", 1, 1);
+        game.submit_word("koira", 2, 2);
+        game.submit_word("kassa", 2, 3);
+        assert_eq!(game.previous_word(1), None);
+        // Skips the word that broke the rules
+        assert_eq!(game.previous_word(3).as_deref(), Some("kissa"));
+        assert_eq!(game.previous_word(4), None);
+
+        game.start_from("kassi");
+        game.submit_word("kassa", 1, 5);
+        assert_eq!(game.previous_word(5).as_deref(), Some("kassi"));
+    }
+
+    #[test]
+    fn test_set_current_word() {
+        let mut game = engine_with(GameRules {
+            pending_policy: PendingPolicy::Rollback,
+            ..GameRules::default()
+        });
+        game.submit_word("kissa", 1, 1);
+        game.submit_word("kassa", 2, 2);
+        assert_eq!(game.submit_word("kassu", 1, 3), WordVerdict::PendingLlm);
+
+        let dropped = game.set_current_word("koira");
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].message_id, 3);
+        assert_eq!(dropped[0].validity, Validity::Pending);
+        assert_eq!(game.current_word().as_deref(), Some("koira"));
+        assert_eq!(game.last_player(), None);
+        assert_eq!(game.history(10).len(), 3);
+        assert_eq!(game.entry(3).unwrap().validity, Validity::Invalid);
+        // A late verdict on the dropped word changes nothing
+        assert!(game.mark_validity(3, Validity::Valid, by_llm()).is_none());
+
+        // The word is used, the history is kept, and the chain goes on from it
+        assert!(matches!(
+            game.submit_word("koira", 2, 4),
+            WordVerdict::InvalidRules(_)
+        ));
+        assert_eq!(game.submit_word("koire", 2, 5), WordVerdict::PendingLlm);
+
+        // Rolling back the first word returns to the word that was set
+        assert!(game
+            .mark_validity(5, Validity::Invalid, by_llm())
+            .unwrap()
+            .is_empty());
+        assert_eq!(game.current_word().as_deref(), Some("koira"));
+        assert_eq!(game.last_valid_word(), Some("koira"));
+    }
+}
@@ -0,0 +1,108 @@
+//! The games the bot runs, one per configured server
+
+use actix::Addr;
+use poise::serenity_prelude as serenity;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::actors::{GameStateActor, MessageReactionActor, WordValidatorActor};
+use crate::channels::GameChannels;
+use crate::config::Config;
+use crate::events::EventBus;
+
+/// One server's game: its settings and the actors playing it
+pub struct Game {
+    /// The configuration with the server's settings applied
+    pub config: Arc<Config>,
+    pub channels: Arc<GameChannels>,
+    pub game_state: Addr<GameStateActor>,
+    pub word_validator: Addr<WordValidatorActor>,
+    pub message_reaction: Addr<MessageReactionActor>,
+    /// Where what happens in the game is published, for anything that wants to follow it
+    pub events: EventBus,
+}
+
+/// Finds the game of the server a message or command came from
+pub enum Games {
+    /// No servers configured: one game in the configured channel, whichever server it's in
+    Single(Game),
+    /// A game for each configured server, keyed by guild ID
+    PerGuild(HashMap<u64, Game>),
+}
+
+impl Games {
+    /// The game played in a server, if there is one
+    pub fn get(&self, guild_id: Option<serenity::GuildId>) -> Option<&Game> {
+        match self {
+            Games::Single(game) => Some(game),
+            Games::PerGuild(games) => games.get(&guild_id?.get()),
+        }
+    }
+
+    /// Every game, in no particular order
+    pub fn iter(&self) -> Box<dyn Iterator<Item = &Game> + Send + '_> {
+        match self {
+            Games::Single(game) => Box::new(std::iter::once(game)),
+            Games::PerGuild(games) => Box::new(games.values()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::testing::{MockCompletionApi, RecordingReactionApi};
+    use crate::actors::LLMValidatorActor;
+    use crate::validation::llm::LLMValidator;
+    use actix::Actor;
+
+    fn game(channel_id: u64) -> Game {
+        let config = Config {
+            channel_id,
+            ..Config::default()
+        };
+        let game_state = GameStateActor::new().start();
+        let message_reaction = MessageReactionActor::with_api(
+            Arc::new(RecordingReactionApi::default()),
+            serenity::ChannelId::new(channel_id),
+        )
+        .start();
+        let word_validator = WordValidatorActor::new(
+            game_state.clone(),
+            LLMValidatorActor::with_validator(
+                LLMValidator::with_api(Box::new(MockCompletionApi::failing())),
+                1,
+                86400,
+            )
+            .start(),
+            message_reaction.clone(),
+        )
+        .start();
+        Game {
+            channels: Arc::new(GameChannels::new(&config)),
+            config: Arc::new(config),
+            game_state,
+            word_validator,
+            message_reaction,
+            events: EventBus::default(),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_route_by_guild() {
+        let guild = |id| Some(serenity::GuildId::new(id));
+
+        let single = Games::Single(game(1));
+        assert_eq!(single.get(guild(10)).unwrap().config.channel_id, 1);
+        assert_eq!(single.get(None).unwrap().config.channel_id, 1);
+        assert_eq!(single.iter().count(), 1);
+
+        let per_guild = Games::PerGuild(HashMap::from([(10, game(1)), (20, game(2))]));
+        assert_eq!(per_guild.get(guild(10)).unwrap().config.channel_id, 1);
+        assert_eq!(per_guild.get(guild(20)).unwrap().config.channel_id, 2);
+        assert!(per_guild.get(guild(30)).is_none());
+        // Direct messages belong to no server
+        assert!(per_guild.get(None).is_none());
+        assert_eq!(per_guild.iter().count(), 2);
+    }
+}
@@ -0,0 +1,299 @@
+//! HTTP health endpoint for container orchestration
+//!
+//! `GET /health` answers 200 once the gateway is connected, the dictionary is
+//! loaded, the LLM client is configured and passed its startup check if one
+//! was made, and every actor answers a [`Ping`], and 503 with the failing
+//! components otherwise. Either way the body also
+//! tells how many words are being validated and waiting to be.
+
+use actix::Recipient;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tracing::{error, info};
+
+use crate::actors::word_validator::{GetValidationLoad, ValidationLoad};
+use crate::actors::Ping;
+use crate::error::Result;
+
+/// How long an actor may take to answer a [`Ping`] before it counts as unresponsive
+const PING_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Actors that have to answer pings for the bot to be ready
+pub const ACTORS: [&str; 4] = [
+    "game_state",
+    "llm_validator",
+    "message_reaction",
+    "word_validator",
+];
+
+/// Readiness of the bot's components, shared with the health endpoint
+///
+/// Actors are registered as they are started, so the bot isn't ready until
+/// every actor in [`ACTORS`] exists and answers. With several servers each
+/// has its own game actors, and all of them have to answer.
+pub struct Health {
+    discord_connected: AtomicBool,
+    dictionary_words: usize,
+    llm_configured: bool,
+    /// Outcome of the LLM startup check: the model that answered, or the error
+    llm_check: Mutex<Option<std::result::Result<String, String>>>,
+    actors: Mutex<HashMap<&'static str, Vec<Recipient<Ping>>>>,
+    /// The word validators of every game, asked for their load
+    validators: Mutex<Vec<Recipient<GetValidationLoad>>>,
+}
+
+/// Result of a health check, returned as the endpoint's JSON body
+#[derive(Debug, Serialize)]
+pub struct HealthReport {
+    pub ready: bool,
+    pub discord_connected: bool,
+    pub dictionary_words: usize,
+    pub llm_configured: bool,
+    /// The model that answered the startup check
+    pub llm_model: Option<String>,
+    /// Why the startup check failed, e.g. an unknown model
+    pub llm_error: Option<String>,
+    /// Names of the components that aren't ready, e.g. `discord` or `actor:game_state`
+    pub failing: Vec<String>,
+    /// Words being validated and waiting to be, in every game
+    pub validations: ValidationLoad,
+}
+
+impl Health {
+    pub fn new(dictionary_words: usize, llm_configured: bool) -> Self {
+        Self {
+            discord_connected: AtomicBool::new(false),
+            dictionary_words,
+            llm_configured,
+            llm_check: Mutex::new(None),
+            actors: Mutex::new(HashMap::new()),
+            validators: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Record whether the Discord gateway is connected
+    pub fn set_discord_connected(&self, connected: bool) {
+        self.discord_connected.store(connected, Ordering::Relaxed);
+    }
+
+    /// Record the outcome of the LLM startup check
+    pub fn set_llm_check(&self, outcome: std::result::Result<String, String>) {
+        *self.llm_check.lock().unwrap() = Some(outcome);
+    }
+
+    /// Include an actor in the health checks
+    pub fn register_actor(&self, name: &'static str, actor: Recipient<Ping>) {
+        self.actors
+            .lock()
+            .unwrap()
+            .entry(name)
+            .or_default()
+            .push(actor);
+    }
+
+    /// Include a word validator's load in the reports
+    pub fn register_validator(&self, validator: Recipient<GetValidationLoad>) {
+        self.validators.lock().unwrap().push(validator);
+    }
+
+    /// Check every component, pinging the actors
+    pub async fn check(&self) -> HealthReport {
+        let discord_connected = self.discord_connected.load(Ordering::Relaxed);
+        let mut failing = Vec::new();
+        if !discord_connected {
+            failing.push("discord".to_string());
+        }
+        if self.dictionary_words == 0 {
+            failing.push("dictionary".to_string());
+        }
+        let llm_check = self.llm_check.lock().unwrap().clone();
+        if !self.llm_configured || matches!(llm_check, Some(Err(_))) {
+            failing.push("llm".to_string());
+        }
+        let (llm_model, llm_error) = match llm_check {
+            Some(Ok(model)) => (Some(model), None),
+            Some(Err(error)) => (None, Some(error)),
+            None => (None, None),
+        };
+
+        // Don't hold the lock while waiting for the answers
+        let actors = self.actors.lock().unwrap().clone();
+        for name in ACTORS {
+            let mut responsive = false;
+            for actor in actors.get(name).into_iter().flatten() {
+                responsive = actor.send(Ping).timeout(PING_TIMEOUT).await.is_ok();
+                if !responsive {
+                    break;
+                }
+            }
+            if !responsive {
+                failing.push(format!("actor:{}", name));
+            }
+        }
+
+        let validators = self.validators.lock().unwrap().clone();
+        let mut validations = ValidationLoad::default();
+        for validator in validators {
+            // An unresponsive validator is reported as failing above
+            if let Ok(load) = validator
+                .send(GetValidationLoad)
+                .timeout(PING_TIMEOUT)
+                .await
+            {
+                validations = validations + load;
+            }
+        }
+
+        HealthReport {
+            ready: failing.is_empty(),
+            discord_connected,
+            dictionary_words: self.dictionary_words,
+            llm_configured: self.llm_configured,
+            llm_model,
+            llm_error,
+            failing,
+            validations,
+        }
+    }
+}
+
+/// Bind the health endpoint's port, so a port that's taken fails at startup
+pub async fn bind(port: u16) -> Result<TcpListener> {
+    let listener = TcpListener::bind(("0.0.0.0", port)).await?;
+    info!("Health endpoint listening on port {}", port);
+    Ok(listener)
+}
+
+/// Serve `GET /health` until the process exits
+pub async fn serve(listener: TcpListener, health: Arc<Health>) {
+    let app = Router::new()
+        .route("/health", get(health_handler))
+        .with_state(health);
+
+    if let Err(e) = axum::serve(listener, app).await {
+        error!("Health endpoint stopped: {}", e);
+    }
+}
+
+/// 200 when every component is ready, 503 otherwise
+async fn health_handler(State(health): State<Arc<Health>>) -> (StatusCode, Json<HealthReport>) {
+    let report = health.check().await;
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::testing::{MockCompletionApi, RecordingReactionApi};
+    use crate::actors::{GameStateActor, LLMValidatorActor, MessageReactionActor};
+    use crate::validation::llm::LLMValidator;
+    use actix::{Actor, ActorContext};
+    use poise::serenity_prelude as serenity;
+
+    #[actix_rt::test]
+    async fn test_health_check() {
+        let health = Arc::new(Health::new(3, true));
+
+        let report = health.check().await;
+        assert!(!report.ready);
+        assert_eq!(
+            report.failing,
+            vec![
+                "discord",
+                "actor:game_state",
+                "actor:llm_validator",
+                "actor:message_reaction",
+                "actor:word_validator",
+            ]
+        );
+
+        let game_state = GameStateActor::new().start();
+        let llm_validator = LLMValidatorActor::with_validator(
+            LLMValidator::with_api(Box::new(MockCompletionApi::failing())),
+            2,
+            60,
+        )
+        .start();
+        let message_reaction = MessageReactionActor::with_api(
+            Arc::new(RecordingReactionApi::default()),
+            serenity::ChannelId::new(1),
+        )
+        .start();
+        let word_validator = crate::actors::WordValidatorActor::new(
+            game_state.clone(),
+            llm_validator.clone(),
+            message_reaction.clone(),
+        )
+        .start();
+
+        health.register_actor("game_state", game_state.recipient());
+        health.register_actor("llm_validator", llm_validator.recipient());
+        health.register_actor("message_reaction", message_reaction.recipient());
+        health.set_discord_connected(true);
+        let (status, Json(report)) = health_handler(State(health.clone())).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(report.failing, vec!["actor:word_validator"]);
+
+        health.register_actor("word_validator", word_validator.clone().recipient());
+        health.register_validator(word_validator.recipient());
+        let (status, Json(report)) = health_handler(State(health.clone())).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(report.ready);
+        assert_eq!(report.dictionary_words, 3);
+        assert_eq!(report.validations, ValidationLoad::default());
+
+        // With a game per server, every server's actor has to answer
+        let stopped = GameStateActor::create(|ctx| {
+            ctx.stop();
+            GameStateActor::new()
+        });
+        health.register_actor("game_state", stopped.recipient());
+        let report = health.check().await;
+        assert_eq!(report.failing, vec!["actor:game_state"]);
+    }
+
+    #[actix_rt::test]
+    async fn test_missing_components() {
+        let health = Health::new(0, false);
+        health.set_discord_connected(true);
+
+        let report = health.check().await;
+        assert_eq!(&report.failing[..2], ["dictionary", "llm"]);
+    }
+
+    #[actix_rt::test]
+    async fn test_llm_startup_check() {
+        let health = Health::new(3, true);
+        health.set_discord_connected(true);
+        let report = health.check().await;
+        assert!(!report.failing.contains(&"llm".to_string()));
+        assert_eq!((report.llm_model, report.llm_error), (None, None));
+
+        health.set_llm_check(Err("model gemini-por not found".to_string()));
+        let report = health.check().await;
+        assert_eq!(report.failing[0], "llm");
+        assert_eq!(
+            report.llm_error.as_deref(),
+            Some("model gemini-por not found")
+        );
+
+        health.set_llm_check(Ok("gemini-pro".to_string()));
+        let report = health.check().await;
+        assert!(!report.failing.contains(&"llm".to_string()));
+        assert_eq!(report.llm_model.as_deref(), Some("gemini-pro"));
+        assert_eq!(report.llm_error, None);
+    }
+}
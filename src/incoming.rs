@@ -0,0 +1,153 @@
+//! Which messages in the game channel are passed on to be played
+//!
+//! Commands for other bots and talk around the game are dropped here, before
+//! the word validator ever sees them or reacts to them.
+
+use poise::serenity_prelude as serenity;
+
+use crate::config::Config;
+use crate::validation::extract::{extract_word, ExtractOptions};
+
+/// The text of a message to play, None if it's empty or starts with an ignored prefix
+pub fn message_to_candidate_word(content: &str, config: &Config) -> Option<String> {
+    let content = content.trim();
+    if content.is_empty() {
+        return None;
+    }
+    if config
+        .ignored_prefix_list()
+        .iter()
+        .any(|prefix| content.starts_with(prefix))
+    {
+        return None;
+    }
+    Some(content.to_string())
+}
+
+/// Whether the message mentions another bot, or replies to another bot's message
+/// or to one without a playable word, when such messages are ignored
+pub fn is_side_conversation(
+    message: &serenity::Message,
+    bot_id: serenity::UserId,
+    config: &Config,
+) -> bool {
+    if !config.ignore_side_conversations {
+        return false;
+    }
+    let mentions_bot = message
+        .mentions
+        .iter()
+        .any(|user| user.bot && user.id != bot_id);
+    let replies_to_chat = message
+        .referenced_message
+        .as_deref()
+        .is_some_and(|replied| {
+            (replied.author.bot && replied.author.id != bot_id)
+                || message_to_candidate_word(&replied.content, config)
+                    .and_then(|content| {
+                        extract_word(&content, &ExtractOptions::from_config(config))
+                    })
+                    .is_none()
+        });
+    mentions_bot || replies_to_chat
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOT_ID: u64 = 1;
+
+    fn user(id: u64, bot: bool) -> serenity::User {
+        let mut user = serenity::User::default();
+        user.id = serenity::UserId::new(id);
+        user.bot = bot;
+        user
+    }
+
+    fn message(content: &str, author: serenity::User) -> serenity::Message {
+        let mut message = serenity::Message::default();
+        message.content = content.to_string();
+        message.author = author;
+        message
+    }
+
+    #[test]
+    fn test_ignored_prefixes() {
+        let mut config = Config::default();
+        assert_eq!(
+            message_to_candidate_word("  kissa \n", &config).as_deref(),
+            Some("kissa")
+        );
+        assert_eq!(message_to_candidate_word("!kissa", &config), None);
+        assert_eq!(message_to_candidate_word(" ! kissa", &config), None);
+        assert_eq!(message_to_candidate_word("   ", &config), None);
+        assert_eq!(
+            message_to_candidate_word("?kissa", &config).as_deref(),
+            Some("?kissa")
+        );
+
+        config.ignored_prefixes = "!, ?, ., $$".to_string();
+        for content in ["!play", "?help", ".roll", "$$bal", "...", "?"] {
+            assert_eq!(
+                message_to_candidate_word(content, &config),
+                None,
+                "{content}"
+            );
+        }
+        assert_eq!(
+            message_to_candidate_word("$kissa", &config).as_deref(),
+            Some("$kissa")
+        );
+
+        // No prefixes plays everything that isn't empty
+        config.ignored_prefixes = String::new();
+        assert_eq!(
+            message_to_candidate_word("!kissa", &config).as_deref(),
+            Some("!kissa")
+        );
+        assert_eq!(message_to_candidate_word("", &config), None);
+    }
+
+    #[test]
+    fn test_side_conversations() {
+        let mut config = Config {
+            ignore_side_conversations: true,
+            ..Config::default()
+        };
+        let bot_id = serenity::UserId::new(BOT_ID);
+        let player = user(10, false);
+
+        let plain = message("kissa", player.clone());
+        assert!(!is_side_conversation(&plain, bot_id, &config));
+
+        // Mentions of other bots, but not of people or this bot
+        let mut mention = message("kissa <@20>", player.clone());
+        mention.mentions = vec![user(20, true)];
+        assert!(is_side_conversation(&mention, bot_id, &config));
+        mention.mentions = vec![user(21, false)];
+        assert!(!is_side_conversation(&mention, bot_id, &config));
+        mention.mentions = vec![user(BOT_ID, true)];
+        assert!(!is_side_conversation(&mention, bot_id, &config));
+
+        // Replies to played words are part of the game, replies to chat or other bots aren't
+        let mut reply = message("kassa", player.clone());
+        reply.referenced_message = Some(Box::new(message("kissa", user(11, false))));
+        assert!(!is_side_conversation(&reply, bot_id, &config));
+        reply.referenced_message = Some(Box::new(message("mitä kuuluu?", user(11, false))));
+        assert!(is_side_conversation(&reply, bot_id, &config));
+        reply.referenced_message = Some(Box::new(message("!rank", user(11, false))));
+        assert!(is_side_conversation(&reply, bot_id, &config));
+        reply.referenced_message = Some(Box::new(message("kissa", user(20, true))));
+        assert!(is_side_conversation(&reply, bot_id, &config));
+        // The words this bot played on its turn are part of the game too
+        reply.referenced_message = Some(Box::new(message("kissa", user(BOT_ID, true))));
+        assert!(!is_side_conversation(&reply, bot_id, &config));
+
+        // Nothing is a side conversation unless they're ignored
+        config.ignore_side_conversations = false;
+        assert!(!is_side_conversation(&reply, bot_id, &config));
+        mention.mentions = vec![user(20, true)];
+        assert!(!is_side_conversation(&mention, bot_id, &config));
+    }
+}
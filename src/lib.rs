@@ -1,14 +1,50 @@
 pub mod actors;
+pub mod announce;
+pub mod api;
+pub mod audit;
+pub mod budget;
+pub mod bulkcheck;
+pub mod chain;
+pub mod channels;
+pub mod commands;
 pub mod config;
+pub mod digest;
 pub mod discord;
+pub mod disputes;
 pub mod error;
+pub mod events;
+pub mod export;
+pub mod game;
+pub mod games;
+pub mod health;
+pub mod incoming;
+pub mod llm_cache;
+pub mod messages;
+pub mod permissions;
+pub mod persist;
+pub mod replay;
+pub mod rotation;
+pub mod scoring;
+pub mod self_test;
+pub mod shutdown;
+pub mod simulate;
+pub mod snapshot;
+pub mod status;
 pub mod validation;
+pub mod webhook;
+pub mod weekly;
 
 // Re-export error types for convenience
 pub use error::{DictionaryError, Error, LLMError, Result, ValidationError};
 
 // Common types used across the application
 pub struct Data {
-    pub channel_id: poise::serenity_prelude::ChannelId,
-    pub word_validator: actix::Addr<actors::WordValidatorActor>,
+    /// The configuration before any server's settings are applied
+    pub config: std::sync::Arc<config::Config>,
+    pub games: games::Games,
+    /// Shared by the games of every server
+    pub llm_validator: actix::Addr<actors::LLMValidatorActor>,
+    pub health: std::sync::Arc<health::Health>,
+    /// How the recent words were handled, for /why
+    pub audit: std::sync::Arc<audit::AuditLog>,
 }
@@ -0,0 +1,115 @@
+//! The LLM's verdicts kept across restarts
+//!
+//! Verdicts are cached in memory so the LLM is asked about each word once,
+//! which a restart would undo. They're saved when the bot shuts down, together
+//! with the words still waiting for the LLM. Those can't be settled after a
+//! restart, since their messages are no longer in the game, so they're only
+//! logged for the admins at the next startup.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::persist::write_atomic;
+use crate::validation::llm::ProperNounResponse;
+
+/// A verdict as it's saved, along with the model that gave it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedVerdict {
+    pub word: String,
+    pub is_proper_noun: bool,
+    pub explanation: String,
+    pub model: Option<String>,
+}
+
+impl From<ProperNounResponse> for SavedVerdict {
+    fn from(response: ProperNounResponse) -> Self {
+        Self {
+            word: response.word,
+            is_proper_noun: response.is_proper_noun,
+            explanation: response.explanation,
+            model: response.model,
+        }
+    }
+}
+
+impl From<SavedVerdict> for ProperNounResponse {
+    fn from(verdict: SavedVerdict) -> Self {
+        Self {
+            word: verdict.word,
+            is_proper_noun: verdict.is_proper_noun,
+            explanation: verdict.explanation,
+            model: verdict.model,
+        }
+    }
+}
+
+/// What the LLM validator saves when the bot shuts down
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedCache {
+    pub verdicts: Vec<SavedVerdict>,
+    /// Words that were still waiting for the LLM, in the order they were queued
+    pub queued: Vec<String>,
+}
+
+/// Where the cache is kept, as a JSON object
+#[derive(Debug, Clone)]
+pub struct LlmCacheStore {
+    path: PathBuf,
+}
+
+impl LlmCacheStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The saved cache, empty if none has been saved yet
+    pub fn load(&self) -> Result<SavedCache> {
+        match fs::read_to_string(&self.path) {
+            Ok(content) => Ok(serde_json::from_str(&content).map_err(io::Error::other)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(SavedCache::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, cache: &SavedCache) -> Result<()> {
+        let content = serde_json::to_string_pretty(cache).map_err(io::Error::other)?;
+        write_atomic(&self.path, content.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LlmCacheStore::new(dir.path().join("data").join("llm_cache.json"));
+        assert_eq!(store.load().unwrap(), SavedCache::default());
+
+        let cache = SavedCache {
+            verdicts: vec![SavedVerdict {
+                word: "Helsinki".to_string(),
+                is_proper_noun: true,
+                explanation: "Suomen pääkaupunki".to_string(),
+                model: Some("gemini-2.0-flash".to_string()),
+            }],
+            queued: vec!["Pori".to_string()],
+        };
+        store.save(&cache).unwrap();
+        assert_eq!(store.load().unwrap(), cache);
+
+        // The model is kept, unlike when parsing the LLM's answers
+        let response = ProperNounResponse::from(cache.verdicts[0].clone());
+        assert_eq!(response.model.as_deref(), Some("gemini-2.0-flash"));
+        assert_eq!(SavedVerdict::from(response), cache.verdicts[0]);
+    }
+}
@@ -1,25 +1,902 @@
+use clap::Parser;
+use miette::IntoDiagnostic;
 use tokio::signal;
 use tokio::task::LocalSet;
-use tracing::info;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing::{error, info};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
-use sanabotti::{config, discord};
+use sanabotti::config::{LogFormat, Overrides, CONFIG_FILE_VAR};
+use sanabotti::validation::{dictionary::DictionaryValidator, download};
+use sanabotti::{config, discord, replay, self_test, simulate};
+
+/// Command-line options of the bot
+///
+/// Each setting overrides both the environment and the configuration file.
+#[derive(Parser, Debug)]
+#[command(
+    name = "sanabotti",
+    version,
+    about = "Finnish word chain game bot for Discord",
+    after_help = "Other commands:\n  sanabotti simulate       Play words from stdin\n  sanabotti replay         Replay a recorded session\n  sanabotti compile-dict   Compile a word list for faster startup"
+)]
+struct Cli {
+    /// Configuration file to read (SANABOTTI_CONFIG)
+    #[arg(long, value_name = "PATH")]
+    config: Option<String>,
+
+    /// Discord bot token (DISCORD_TOKEN)
+    #[arg(long, value_name = "TOKEN")]
+    discord_token: Option<String>,
+
+    /// Gemini API key for proper noun checks (GEMINI_API_KEY)
+    #[arg(long, value_name = "KEY")]
+    gemini_api_key: Option<String>,
+
+    /// ID of the game channel (TARGET_CHANNEL_ID)
+    #[arg(long, value_name = "ID")]
+    channel_id: Option<u64>,
+
+    /// ID of a thread to play in instead of the channel, 0 for none (GAME_THREAD_ID)
+    #[arg(long, value_name = "ID")]
+    game_thread_id: Option<u64>,
+
+    /// Whether threads under the game channel are part of the game (FOLLOW_THREADS)
+    #[arg(long, value_name = "BOOL")]
+    follow_threads: Option<bool>,
+
+    /// ID of the channel for error notifications, 0 disables them (ADMIN_CHANNEL_ID)
+    #[arg(long, value_name = "ID")]
+    admin_channel_id: Option<u64>,
+
+    /// Whether startup fails when the bot lacks permissions in a game channel (STRICT_PERMISSIONS)
+    #[arg(long, value_name = "BOOL")]
+    strict_permissions: Option<bool>,
+
+    /// Register the commands in this guild only, 0 registers them globally (DEV_GUILD_ID)
+    #[arg(long, value_name = "ID")]
+    dev_guild_id: Option<u64>,
+
+    /// Word list with one word per line (DICTIONARY_FILE_PATH)
+    #[arg(long, value_name = "PATH")]
+    dictionary: Option<String>,
+
+    /// Further word lists, comma-separated (EXTRA_DICTIONARY_PATHS)
+    #[arg(long, value_name = "PATHS")]
+    extra_dictionary_paths: Option<String>,
+
+    /// Whether a missing dictionary is downloaded at startup (DOWNLOAD_DICTIONARY)
+    #[arg(long, value_name = "BOOL")]
+    download_dictionary: Option<bool>,
+
+    /// Word list to download when the dictionary is missing (DICTIONARY_URL)
+    #[arg(long, value_name = "URL")]
+    dictionary_url: Option<String>,
+
+    /// SHA-256 the downloaded word list must have (DICTIONARY_SHA256)
+    #[arg(long, value_name = "HEX")]
+    dictionary_sha256: Option<String>,
+
+    /// Whether inflected forms of dictionary words are accepted (LENIENT_DICTIONARY)
+    #[arg(long, value_name = "BOOL")]
+    lenient_dictionary: Option<bool>,
+
+    /// Endings stripped in lenient mode, e.g. lla,ssa,oja>a (INFLECTION_RULES)
+    #[arg(long, value_name = "RULES")]
+    inflection_rules: Option<String>,
+
+    /// Dictionary asked before the LLM: `wiktionary` or a lookup URL (REMOTE_DICTIONARY)
+    #[arg(long, value_name = "SOURCE")]
+    remote_dictionary: Option<String>,
+
+    /// How long to wait for the remote dictionary (REMOTE_DICTIONARY_TIMEOUT_MS)
+    #[arg(long, value_name = "MS")]
+    remote_dictionary_timeout_ms: Option<u64>,
+
+    /// Remote dictionary lookups allowed per minute (REMOTE_DICTIONARY_PER_MINUTE)
+    #[arg(long, value_name = "N")]
+    remote_dictionary_per_minute: Option<usize>,
+
+    /// Banned words, one per line (BLOCKLIST_PATH)
+    #[arg(long, value_name = "PATH")]
+    blocklist_path: Option<String>,
+
+    /// Activity status of the bot (BOT_ACTIVITY)
+    #[arg(long, value_name = "TEXT")]
+    bot_activity: Option<String>,
+
+    /// Whether the activity shows the current word (DYNAMIC_PRESENCE)
+    #[arg(long, value_name = "BOOL")]
+    dynamic_presence: Option<bool>,
+
+    /// Whether the state of the game is posted when the bot starts (ANNOUNCE_ON_READY)
+    #[arg(long, value_name = "BOOL")]
+    announce_on_ready: Option<bool>,
+
+    /// Minimum seconds between two startup announcements in a channel (ANNOUNCE_INTERVAL_SECS)
+    #[arg(long, value_name = "SECS")]
+    announce_interval_secs: Option<u64>,
+
+    /// File recording when each channel was last announced in (ANNOUNCE_LOG_PATH)
+    #[arg(long, value_name = "PATH")]
+    announce_log_path: Option<String>,
+
+    /// Chain lengths whose multiples are announced, 0 for none (MILESTONE_INTERVAL)
+    #[arg(long, value_name = "WORDS")]
+    milestone_interval: Option<usize>,
+
+    /// Words after which a rejected word breaks the chain, 0 for never (CHAIN_BREAK_LENGTH)
+    #[arg(long, value_name = "WORDS")]
+    chain_break_length: Option<usize>,
+
+    /// Whether rule violations break the chain too (VIOLATIONS_BREAK_CHAIN)
+    #[arg(long, value_name = "BOOL")]
+    violations_break_chain: Option<bool>,
+
+    /// Whether a new chain starts right after a break, or waits for /reset (RESTART_AFTER_BREAK)
+    #[arg(long, value_name = "BOOL")]
+    restart_after_break: Option<bool>,
+
+    /// Endpoint game highlights are POSTed to (WEBHOOK_URL)
+    #[arg(long, value_name = "URL")]
+    webhook_url: Option<String>,
+
+    /// Events sent to the webhook, e.g. milestone,record (WEBHOOK_EVENTS)
+    #[arg(long, value_name = "EVENTS")]
+    webhook_events: Option<String>,
+
+    /// Whether a pinned message shows the current word (STATUS_MESSAGE)
+    #[arg(long, value_name = "BOOL")]
+    status_message: Option<bool>,
+
+    /// File the status message IDs are kept in (STATUS_MESSAGE_PATH)
+    #[arg(long, value_name = "PATH")]
+    status_message_path: Option<String>,
+
+    /// Whether the status message says the bot is offline once it shuts down (OFFLINE_STATUS)
+    #[arg(long, value_name = "BOOL")]
+    offline_status: Option<bool>,
+
+    /// Models to ask, comma-separated in order of preference (LLM_MODEL)
+    #[arg(long, value_name = "MODELS")]
+    llm_model: Option<String>,
+
+    /// How long to stay on a fallback model in seconds (LLM_FALLBACK_RESET_SECS)
+    #[arg(long, value_name = "SECS")]
+    llm_fallback_reset_secs: Option<u64>,
+
+    /// Whether the LLM is asked about a word at startup (LLM_STARTUP_CHECK)
+    #[arg(long, value_name = "BOOL")]
+    llm_startup_check: Option<bool>,
+
+    /// Number of words to batch for LLM validation (LLM_BATCH_SIZE)
+    #[arg(long, value_name = "N")]
+    llm_batch_size: Option<usize>,
+
+    /// Timeout for LLM batching in seconds (LLM_BATCH_TIMEOUT_SECS)
+    #[arg(long, value_name = "SECS")]
+    batch_timeout_secs: Option<u64>,
+
+    /// Whether words wait for a batch or go to the LLM at once (LLM_BATCH_MODE)
+    #[arg(long, value_parser = ["batched", "immediate"])]
+    llm_batch_mode: Option<String>,
+
+    /// How many words may be sent to the LLM in 24 hours, 0 for no limit (LLM_DAILY_BUDGET)
+    #[arg(long, value_name = "WORDS")]
+    llm_daily_budget: Option<usize>,
+
+    /// What happens to words over the budget (LLM_BUDGET_FALLBACK)
+    #[arg(long, value_parser = ["pending", "reject"])]
+    llm_budget_fallback: Option<String>,
+
+    /// How long a word may wait for the LLM, 0 for no limit (LLM_MAX_AGE_SECS)
+    #[arg(long, value_name = "SECS")]
+    llm_max_age_secs: Option<u64>,
+
+    /// What happens to words that waited too long (LLM_EXPIRED_FALLBACK)
+    #[arg(long, value_parser = ["pending", "reject"])]
+    llm_expired_fallback: Option<String>,
+
+    /// File the words sent to the LLM are counted in (LLM_BUDGET_PATH)
+    #[arg(long, value_name = "PATH")]
+    llm_budget_path: Option<String>,
+
+    /// File the LLM's verdicts are saved to on shutdown (LLM_CACHE_PATH)
+    #[arg(long, value_name = "PATH")]
+    llm_cache_path: Option<String>,
+
+    /// How often the LLM's accepted words are reported in seconds, 0 for never (LLM_DIGEST_INTERVAL_SECS)
+    #[arg(long, value_name = "SECS")]
+    llm_digest_interval_secs: Option<u64>,
+
+    /// File the LLM's verdicts are logged to (LLM_VERDICT_LOG_PATH)
+    #[arg(long, value_name = "PATH")]
+    llm_verdict_log_path: Option<String>,
+
+    /// File the LLM digests are appended to instead of the admin channel (LLM_DIGEST_PATH)
+    #[arg(long, value_name = "PATH")]
+    llm_digest_path: Option<String>,
+
+    /// Messages starting with these comma-separated prefixes aren't played (IGNORED_PREFIXES)
+    #[arg(long, value_name = "PREFIXES")]
+    ignored_prefixes: Option<String>,
+
+    /// Whether messages mentioning other bots or replying to chat are ignored (IGNORE_SIDE_CONVERSATIONS)
+    #[arg(long, value_name = "BOOL")]
+    ignore_side_conversations: Option<bool>,
+
+    /// What to do with messages containing several words (MULTI_WORD_POLICY)
+    #[arg(long, value_parser = ["ignore", "first_token"])]
+    multi_word_policy: Option<String>,
+
+    /// Non-letter characters allowed once inside a word (WORD_INNER_CHARS)
+    #[arg(long, value_name = "CHARS")]
+    word_inner_chars: Option<String>,
+
+    /// Letters a played word may consist of, empty for any (ALLOWED_ALPHABET)
+    #[arg(long, value_name = "LETTERS")]
+    allowed_alphabet: Option<String>,
+
+    /// Which moves are legal (RULE_MODE)
+    #[arg(long, value_parser = ["classic", "anagram"])]
+    rule_mode: Option<String>,
+
+    /// How a↔ä, a↔å and o↔ö changes count towards the one-letter rule (DIACRITIC_POLICY)
+    #[arg(long, value_parser = ["distinct", "free", "forbidden"])]
+    diacritic_policy: Option<String>,
+
+    /// Minimum word length in letters, 0 for no limit (MIN_WORD_LENGTH)
+    #[arg(long, value_name = "N")]
+    min_word_length: Option<usize>,
+
+    /// Maximum word length in letters, 0 for no limit (MAX_WORD_LENGTH)
+    #[arg(long, value_name = "N")]
+    max_word_length: Option<usize>,
+
+    /// Whether the same player may play two words in a row (ALLOW_CONSECUTIVE_TURNS)
+    #[arg(long, value_name = "BOOL")]
+    allow_consecutive_turns: Option<bool>,
+
+    /// Who may play: free or rotation (GAME_TURN_MODE)
+    #[arg(long, value_name = "MODE")]
+    game_turn_mode: Option<String>,
+
+    /// Seconds before the turn passes on in rotation mode, 0 to never skip (TURN_SKIP_SECS)
+    #[arg(long, value_name = "SECS")]
+    turn_skip_secs: Option<u64>,
+
+    /// File the turn order is kept in (TURN_ORDER_PATH)
+    #[arg(long, value_name = "PATH")]
+    turn_order_path: Option<String>,
+
+    /// File the game is saved to on shutdown (GAME_SNAPSHOT_PATH)
+    #[arg(long, value_name = "PATH")]
+    game_snapshot_path: Option<String>,
+
+    /// Whether unknown words are checked as proper nouns (ACCEPT_PROPER_NOUNS)
+    #[arg(long, value_name = "BOOL")]
+    accept_proper_nouns: Option<bool>,
+
+    /// Whether proper nouns only count if capitalized (REQUIRE_CAPITALIZATION)
+    #[arg(long, value_name = "BOOL")]
+    require_capitalization: Option<bool>,
+
+    /// Language of the bot's messages (BOT_LANGUAGE)
+    #[arg(long, value_parser = ["fi", "en"])]
+    language: Option<String>,
+
+    /// Whether /rules and /help replies are only visible to the caller (RULES_EPHEMERAL)
+    #[arg(long, value_name = "BOOL")]
+    rules_ephemeral: Option<bool>,
+
+    /// Reset the game after this many idle seconds, 0 disables (GAME_IDLE_RESET_SECS)
+    #[arg(long, value_name = "SECS")]
+    game_idle_reset_secs: Option<u64>,
+
+    /// Play a word after this many idle seconds, 0 disables (BOT_PLAYS_AFTER_SECS)
+    #[arg(long, value_name = "SECS")]
+    bot_plays_after_secs: Option<u64>,
+
+    /// How many played words to remember for /history (HISTORY_SIZE)
+    #[arg(long, value_name = "N")]
+    history_size: Option<usize>,
+
+    /// How many recent messages /why remembers, 0 for none (AUDIT_LOG_SIZE)
+    #[arg(long, value_name = "N")]
+    audit_log_size: Option<usize>,
+
+    /// How words played after a pending word are handled (PENDING_WORD_POLICY)
+    #[arg(long, value_parser = ["optimistic", "strict", "rollback", "queue"])]
+    pending_policy: Option<String>,
+
+    /// How many words may wait for a pending word under the queue policy (PENDING_QUEUE_SIZE)
+    #[arg(long, value_name = "N")]
+    pending_queue_size: Option<usize>,
+
+    /// Seconds a queued word waits before it's played anyway (PENDING_QUEUE_TIMEOUT_SECS)
+    #[arg(long, value_name = "SECS")]
+    pending_queue_timeout_secs: Option<u64>,
+
+    /// Port of the /health endpoint, 0 disables it (HEALTH_PORT)
+    #[arg(long, value_name = "PORT")]
+    health_port: Option<u16>,
+
+    /// Address of the read-only JSON API, e.g. 0.0.0.0:8081 (API_ADDR)
+    #[arg(long, value_name = "ADDR")]
+    api_addr: Option<String>,
+
+    /// Bearer token the API's requests have to carry (API_TOKEN)
+    #[arg(long, value_name = "TOKEN")]
+    api_token: Option<String>,
+
+    /// Website origin allowed to read the API in a browser (API_CORS_ORIGIN)
+    #[arg(long, value_name = "ORIGIN")]
+    api_cors_origin: Option<String>,
+
+    /// Seconds the bot has to save its state when it shuts down (SHUTDOWN_GRACE_SECS)
+    #[arg(long, value_name = "SECS")]
+    shutdown_grace_secs: Option<u64>,
+
+    /// Mailbox capacity of the busiest actors (MAILBOX_CAPACITY)
+    #[arg(long, value_name = "N")]
+    mailbox_capacity: Option<usize>,
+
+    /// How many words of a game may be validated at once, 0 for no limit (VALIDATION_CONCURRENCY)
+    #[arg(long, value_name = "N")]
+    validation_concurrency: Option<usize>,
+
+    /// Words validated and waiting beyond which new ones are dropped, 0 for never (VALIDATION_SHED_LIMIT)
+    #[arg(long, value_name = "N")]
+    validation_shed_limit: Option<usize>,
+
+    /// Minimum milliseconds between reaction API calls (REACTION_INTERVAL_MS)
+    #[arg(long, value_name = "MS")]
+    reaction_interval_ms: Option<u64>,
+
+    /// What happens to the words' reactions when the game resets: none, clear or mark (RESET_REACTIONS)
+    #[arg(long, value_name = "MODE")]
+    reset_reactions: Option<String>,
+
+    /// Words each player may have accepted per day, 0 for no limit (MAX_WORDS_PER_USER_PER_DAY)
+    #[arg(long, value_name = "N")]
+    max_words_per_user_per_day: Option<usize>,
+
+    /// Reaction to words over the daily limit (DAILY_LIMIT_REACTION)
+    #[arg(long, value_name = "EMOJI")]
+    daily_limit_reaction: Option<char>,
+
+    /// Time zone whose midnight resets the daily word limit (TIMEZONE)
+    #[arg(long, value_name = "TZ")]
+    timezone: Option<String>,
+
+    /// Seconds a player has to wait after an accepted word, 0 disables (USER_COOLDOWN_SECS)
+    #[arg(long, value_name = "SECS")]
+    user_cooldown_secs: Option<u64>,
+
+    /// Reaction to words played before the cooldown passed (COOLDOWN_REACTION)
+    #[arg(long, value_name = "EMOJI")]
+    cooldown_reaction: Option<char>,
+
+    /// Whether words over the daily limit or cooldown get an explaining reply (LIMIT_REPLIES)
+    #[arg(long, value_name = "BOOL")]
+    limit_replies: Option<bool>,
+
+    /// Whether words with letters outside the alphabet get a reply (ALPHABET_REPLIES)
+    #[arg(long, value_name = "BOOL")]
+    alphabet_replies: Option<bool>,
+
+    /// Reaction to words played while the game is paused (PAUSE_REACTION)
+    #[arg(long, value_name = "EMOJI")]
+    pause_reaction: Option<char>,
+
+    /// Reaction to words that were already played in the game (ALREADY_USED_REACTION)
+    #[arg(long, value_name = "EMOJI")]
+    already_used_reaction: Option<char>,
+
+    /// Whether rejected words get a reply suggesting dictionary words (SUGGEST_WORDS)
+    #[arg(long, value_name = "BOOL")]
+    suggest_words: Option<bool>,
+
+    /// Whether likely typos get a reaction instead of going to the LLM (TYPO_CHECK)
+    #[arg(long, value_name = "BOOL")]
+    typo_check: Option<bool>,
+
+    /// Reaction to words taken for typos (TYPO_REACTION)
+    #[arg(long, value_name = "EMOJI")]
+    typo_reaction: Option<char>,
+
+    /// Whether unknown words that don't look like words skip the LLM (PLAUSIBILITY_FILTER)
+    #[arg(long, value_name = "BOOL")]
+    plausibility_filter: Option<bool>,
+
+    /// Most consonants in a row in a plausible word (PLAUSIBILITY_MAX_CONSONANTS)
+    #[arg(long, value_name = "N")]
+    plausibility_max_consonants: Option<usize>,
+
+    /// Most repeats of a letter in a row in a plausible word (PLAUSIBILITY_MAX_REPEATS)
+    #[arg(long, value_name = "N")]
+    plausibility_max_repeats: Option<usize>,
+
+    /// Fewest letter triples of a plausible word found in the dictionary (PLAUSIBILITY_MIN_KNOWN_TRIGRAMS)
+    #[arg(long, value_name = "N")]
+    plausibility_min_known_trigrams: Option<usize>,
+
+    /// Largest word list /dict bulkcheck accepts, in kilobytes (BULK_CHECK_MAX_KB)
+    #[arg(long, value_name = "KB")]
+    bulk_check_max_kb: Option<usize>,
+
+    /// Players whose votes overturn a verdict, 0 for never (DISPUTE_VOTES)
+    #[arg(long, value_name = "COUNT")]
+    dispute_votes: Option<usize>,
+
+    /// Seconds after a verdict during which votes are counted (DISPUTE_WINDOW_SECS)
+    #[arg(long, value_name = "SECS")]
+    dispute_window_secs: Option<u64>,
+
+    /// Reaction voting to accept a rejected word (DISPUTE_ACCEPT_REACTION)
+    #[arg(long, value_name = "EMOJI")]
+    dispute_accept_reaction: Option<char>,
+
+    /// Reaction voting to reject an accepted word (DISPUTE_REJECT_REACTION)
+    #[arg(long, value_name = "EMOJI")]
+    dispute_reject_reaction: Option<char>,
+
+    /// Points for every accepted word (SCORE_BASE_POINTS)
+    #[arg(long, value_name = "POINTS")]
+    score_base_points: Option<u32>,
+
+    /// Points for every letter of an accepted word (SCORE_PER_LETTER)
+    #[arg(long, value_name = "POINTS")]
+    score_per_letter: Option<u32>,
+
+    /// Letters that earn a bonus, e.g. `äöy` (SCORE_RARE_LETTERS)
+    #[arg(long, value_name = "LETTERS")]
+    score_rare_letters: Option<String>,
+
+    /// Bonus points for every rare letter (SCORE_RARE_LETTER_BONUS)
+    #[arg(long, value_name = "POINTS")]
+    score_rare_letter_bonus: Option<u32>,
+
+    /// Bonus points for changing the first letter of the chain (SCORE_RUT_BONUS)
+    #[arg(long, value_name = "POINTS")]
+    score_rut_bonus: Option<u32>,
+
+    /// Whether weekly standings are posted and reset (WEEKLY_RESET)
+    #[arg(long, value_name = "BOOL")]
+    weekly_reset: Option<bool>,
+
+    /// Weekday a new week starts, e.g. `mon` (WEEKLY_RESET_DAY)
+    #[arg(long, value_name = "DAY")]
+    weekly_reset_day: Option<String>,
+
+    /// Time of day a new week starts, e.g. `00:00` (WEEKLY_RESET_TIME)
+    #[arg(long, value_name = "HH:MM")]
+    weekly_reset_time: Option<String>,
+
+    /// File the weekly standings are archived to (WEEKLY_ARCHIVE_PATH)
+    #[arg(long, value_name = "PATH")]
+    weekly_archive_path: Option<String>,
+
+    /// Whether messages that aren't words are logged too (LOG_MESSAGE_CONTENT)
+    #[arg(long, value_name = "BOOL")]
+    log_message_content: Option<bool>,
+
+    /// Tracing filter, e.g. `debug` or `sanabotti=trace` (RUST_LOG)
+    #[arg(long, value_name = "FILTER", value_parser = parse_log_filter)]
+    log_level: Option<String>,
+
+    /// Log output format (LOG_FORMAT)
+    #[arg(long, value_parser = ["text", "json"])]
+    log_format: Option<String>,
+
+    /// Load and check the configuration and dictionary, print a summary and exit
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Play a scripted game through the bot's actors without Discord, print PASS or FAIL and exit
+    #[arg(long)]
+    self_test: bool,
+}
+
+impl Cli {
+    /// The settings given on the command line, keyed by the variable they override
+    fn overrides(&self) -> Overrides {
+        [
+            (CONFIG_FILE_VAR, self.config.clone()),
+            ("DISCORD_TOKEN", self.discord_token.clone()),
+            ("GEMINI_API_KEY", self.gemini_api_key.clone()),
+            ("TARGET_CHANNEL_ID", self.channel_id.map(|v| v.to_string())),
+            ("GAME_THREAD_ID", self.game_thread_id.map(|v| v.to_string())),
+            ("FOLLOW_THREADS", self.follow_threads.map(|v| v.to_string())),
+            (
+                "ADMIN_CHANNEL_ID",
+                self.admin_channel_id.map(|v| v.to_string()),
+            ),
+            (
+                "STRICT_PERMISSIONS",
+                self.strict_permissions.map(|v| v.to_string()),
+            ),
+            ("DEV_GUILD_ID", self.dev_guild_id.map(|v| v.to_string())),
+            ("DICTIONARY_FILE_PATH", self.dictionary.clone()),
+            (
+                "EXTRA_DICTIONARY_PATHS",
+                self.extra_dictionary_paths.clone(),
+            ),
+            (
+                "DOWNLOAD_DICTIONARY",
+                self.download_dictionary.map(|v| v.to_string()),
+            ),
+            ("DICTIONARY_URL", self.dictionary_url.clone()),
+            ("DICTIONARY_SHA256", self.dictionary_sha256.clone()),
+            (
+                "LENIENT_DICTIONARY",
+                self.lenient_dictionary.map(|v| v.to_string()),
+            ),
+            ("INFLECTION_RULES", self.inflection_rules.clone()),
+            ("REMOTE_DICTIONARY", self.remote_dictionary.clone()),
+            (
+                "REMOTE_DICTIONARY_TIMEOUT_MS",
+                self.remote_dictionary_timeout_ms.map(|v| v.to_string()),
+            ),
+            (
+                "REMOTE_DICTIONARY_PER_MINUTE",
+                self.remote_dictionary_per_minute.map(|v| v.to_string()),
+            ),
+            ("BLOCKLIST_PATH", self.blocklist_path.clone()),
+            ("BOT_ACTIVITY", self.bot_activity.clone()),
+            (
+                "DYNAMIC_PRESENCE",
+                self.dynamic_presence.map(|v| v.to_string()),
+            ),
+            (
+                "ANNOUNCE_ON_READY",
+                self.announce_on_ready.map(|v| v.to_string()),
+            ),
+            (
+                "ANNOUNCE_INTERVAL_SECS",
+                self.announce_interval_secs.map(|v| v.to_string()),
+            ),
+            ("ANNOUNCE_LOG_PATH", self.announce_log_path.clone()),
+            (
+                "MILESTONE_INTERVAL",
+                self.milestone_interval.map(|v| v.to_string()),
+            ),
+            (
+                "CHAIN_BREAK_LENGTH",
+                self.chain_break_length.map(|v| v.to_string()),
+            ),
+            (
+                "VIOLATIONS_BREAK_CHAIN",
+                self.violations_break_chain.map(|v| v.to_string()),
+            ),
+            (
+                "RESTART_AFTER_BREAK",
+                self.restart_after_break.map(|v| v.to_string()),
+            ),
+            ("WEBHOOK_URL", self.webhook_url.clone()),
+            ("WEBHOOK_EVENTS", self.webhook_events.clone()),
+            ("STATUS_MESSAGE", self.status_message.map(|v| v.to_string())),
+            ("STATUS_MESSAGE_PATH", self.status_message_path.clone()),
+            ("OFFLINE_STATUS", self.offline_status.map(|v| v.to_string())),
+            ("LLM_MODEL", self.llm_model.clone()),
+            (
+                "LLM_FALLBACK_RESET_SECS",
+                self.llm_fallback_reset_secs.map(|v| v.to_string()),
+            ),
+            (
+                "LLM_STARTUP_CHECK",
+                self.llm_startup_check.map(|v| v.to_string()),
+            ),
+            ("LLM_BATCH_SIZE", self.llm_batch_size.map(|v| v.to_string())),
+            (
+                "LLM_BATCH_TIMEOUT_SECS",
+                self.batch_timeout_secs.map(|v| v.to_string()),
+            ),
+            ("LLM_BATCH_MODE", self.llm_batch_mode.clone()),
+            (
+                "LLM_DAILY_BUDGET",
+                self.llm_daily_budget.map(|v| v.to_string()),
+            ),
+            ("LLM_BUDGET_FALLBACK", self.llm_budget_fallback.clone()),
+            (
+                "LLM_MAX_AGE_SECS",
+                self.llm_max_age_secs.map(|v| v.to_string()),
+            ),
+            ("LLM_EXPIRED_FALLBACK", self.llm_expired_fallback.clone()),
+            ("LLM_BUDGET_PATH", self.llm_budget_path.clone()),
+            ("LLM_CACHE_PATH", self.llm_cache_path.clone()),
+            (
+                "LLM_DIGEST_INTERVAL_SECS",
+                self.llm_digest_interval_secs.map(|v| v.to_string()),
+            ),
+            ("LLM_VERDICT_LOG_PATH", self.llm_verdict_log_path.clone()),
+            ("LLM_DIGEST_PATH", self.llm_digest_path.clone()),
+            ("IGNORED_PREFIXES", self.ignored_prefixes.clone()),
+            (
+                "IGNORE_SIDE_CONVERSATIONS",
+                self.ignore_side_conversations.map(|v| v.to_string()),
+            ),
+            ("MULTI_WORD_POLICY", self.multi_word_policy.clone()),
+            ("WORD_INNER_CHARS", self.word_inner_chars.clone()),
+            ("ALLOWED_ALPHABET", self.allowed_alphabet.clone()),
+            ("RULE_MODE", self.rule_mode.clone()),
+            ("DIACRITIC_POLICY", self.diacritic_policy.clone()),
+            (
+                "MIN_WORD_LENGTH",
+                self.min_word_length.map(|v| v.to_string()),
+            ),
+            (
+                "MAX_WORD_LENGTH",
+                self.max_word_length.map(|v| v.to_string()),
+            ),
+            (
+                "ALLOW_CONSECUTIVE_TURNS",
+                self.allow_consecutive_turns.map(|v| v.to_string()),
+            ),
+            ("GAME_TURN_MODE", self.game_turn_mode.clone()),
+            ("TURN_SKIP_SECS", self.turn_skip_secs.map(|v| v.to_string())),
+            ("TURN_ORDER_PATH", self.turn_order_path.clone()),
+            ("GAME_SNAPSHOT_PATH", self.game_snapshot_path.clone()),
+            (
+                "ACCEPT_PROPER_NOUNS",
+                self.accept_proper_nouns.map(|v| v.to_string()),
+            ),
+            (
+                "REQUIRE_CAPITALIZATION",
+                self.require_capitalization.map(|v| v.to_string()),
+            ),
+            ("BOT_LANGUAGE", self.language.clone()),
+            (
+                "RULES_EPHEMERAL",
+                self.rules_ephemeral.map(|v| v.to_string()),
+            ),
+            (
+                "GAME_IDLE_RESET_SECS",
+                self.game_idle_reset_secs.map(|v| v.to_string()),
+            ),
+            (
+                "BOT_PLAYS_AFTER_SECS",
+                self.bot_plays_after_secs.map(|v| v.to_string()),
+            ),
+            ("HISTORY_SIZE", self.history_size.map(|v| v.to_string())),
+            ("AUDIT_LOG_SIZE", self.audit_log_size.map(|v| v.to_string())),
+            ("PENDING_WORD_POLICY", self.pending_policy.clone()),
+            (
+                "PENDING_QUEUE_SIZE",
+                self.pending_queue_size.map(|v| v.to_string()),
+            ),
+            (
+                "PENDING_QUEUE_TIMEOUT_SECS",
+                self.pending_queue_timeout_secs.map(|v| v.to_string()),
+            ),
+            ("HEALTH_PORT", self.health_port.map(|v| v.to_string())),
+            ("API_ADDR", self.api_addr.clone()),
+            ("API_TOKEN", self.api_token.clone()),
+            ("API_CORS_ORIGIN", self.api_cors_origin.clone()),
+            (
+                "SHUTDOWN_GRACE_SECS",
+                self.shutdown_grace_secs.map(|v| v.to_string()),
+            ),
+            (
+                "MAILBOX_CAPACITY",
+                self.mailbox_capacity.map(|v| v.to_string()),
+            ),
+            (
+                "VALIDATION_CONCURRENCY",
+                self.validation_concurrency.map(|v| v.to_string()),
+            ),
+            (
+                "VALIDATION_SHED_LIMIT",
+                self.validation_shed_limit.map(|v| v.to_string()),
+            ),
+            (
+                "REACTION_INTERVAL_MS",
+                self.reaction_interval_ms.map(|v| v.to_string()),
+            ),
+            ("RESET_REACTIONS", self.reset_reactions.clone()),
+            (
+                "MAX_WORDS_PER_USER_PER_DAY",
+                self.max_words_per_user_per_day.map(|v| v.to_string()),
+            ),
+            (
+                "DAILY_LIMIT_REACTION",
+                self.daily_limit_reaction.map(|v| v.to_string()),
+            ),
+            ("TIMEZONE", self.timezone.clone()),
+            (
+                "USER_COOLDOWN_SECS",
+                self.user_cooldown_secs.map(|v| v.to_string()),
+            ),
+            (
+                "COOLDOWN_REACTION",
+                self.cooldown_reaction.map(|v| v.to_string()),
+            ),
+            ("LIMIT_REPLIES", self.limit_replies.map(|v| v.to_string())),
+            (
+                "ALPHABET_REPLIES",
+                self.alphabet_replies.map(|v| v.to_string()),
+            ),
+            ("PAUSE_REACTION", self.pause_reaction.map(|v| v.to_string())),
+            (
+                "ALREADY_USED_REACTION",
+                self.already_used_reaction.map(|v| v.to_string()),
+            ),
+            ("SUGGEST_WORDS", self.suggest_words.map(|v| v.to_string())),
+            ("TYPO_CHECK", self.typo_check.map(|v| v.to_string())),
+            ("TYPO_REACTION", self.typo_reaction.map(|v| v.to_string())),
+            (
+                "PLAUSIBILITY_FILTER",
+                self.plausibility_filter.map(|v| v.to_string()),
+            ),
+            (
+                "PLAUSIBILITY_MAX_CONSONANTS",
+                self.plausibility_max_consonants.map(|v| v.to_string()),
+            ),
+            (
+                "PLAUSIBILITY_MAX_REPEATS",
+                self.plausibility_max_repeats.map(|v| v.to_string()),
+            ),
+            (
+                "PLAUSIBILITY_MIN_KNOWN_TRIGRAMS",
+                self.plausibility_min_known_trigrams.map(|v| v.to_string()),
+            ),
+            (
+                "BULK_CHECK_MAX_KB",
+                self.bulk_check_max_kb.map(|v| v.to_string()),
+            ),
+            ("DISPUTE_VOTES", self.dispute_votes.map(|v| v.to_string())),
+            (
+                "DISPUTE_WINDOW_SECS",
+                self.dispute_window_secs.map(|v| v.to_string()),
+            ),
+            (
+                "DISPUTE_ACCEPT_REACTION",
+                self.dispute_accept_reaction.map(|v| v.to_string()),
+            ),
+            (
+                "DISPUTE_REJECT_REACTION",
+                self.dispute_reject_reaction.map(|v| v.to_string()),
+            ),
+            (
+                "SCORE_BASE_POINTS",
+                self.score_base_points.map(|v| v.to_string()),
+            ),
+            (
+                "SCORE_PER_LETTER",
+                self.score_per_letter.map(|v| v.to_string()),
+            ),
+            ("SCORE_RARE_LETTERS", self.score_rare_letters.clone()),
+            (
+                "SCORE_RARE_LETTER_BONUS",
+                self.score_rare_letter_bonus.map(|v| v.to_string()),
+            ),
+            (
+                "SCORE_RUT_BONUS",
+                self.score_rut_bonus.map(|v| v.to_string()),
+            ),
+            ("WEEKLY_RESET", self.weekly_reset.map(|v| v.to_string())),
+            ("WEEKLY_RESET_DAY", self.weekly_reset_day.clone()),
+            ("WEEKLY_RESET_TIME", self.weekly_reset_time.clone()),
+            ("WEEKLY_ARCHIVE_PATH", self.weekly_archive_path.clone()),
+            (
+                "LOG_MESSAGE_CONTENT",
+                self.log_message_content.map(|v| v.to_string()),
+            ),
+            ("LOG_FORMAT", self.log_format.clone()),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, value?)))
+        .collect()
+    }
+}
+
+/// Accept only filters that tracing can parse
+fn parse_log_filter(filter: &str) -> Result<String, String> {
+    EnvFilter::try_new(filter)
+        .map(|_| filter.to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Install the global tracing subscriber
+fn init_logging(filter: EnvFilter, format: LogFormat, writer: BoxMakeWriter) {
+    let layer = tracing_subscriber::fmt::layer().with_writer(writer);
+    let registry = tracing_subscriber::registry().with(filter);
+    match format {
+        LogFormat::Text => registry.with(layer).init(),
+        LogFormat::Json => registry
+            .with(layer.json().with_current_span(true).with_span_list(true))
+            .init(),
+    }
+}
 
 #[actix_rt::main]
 async fn main() -> miette::Result<()> {
-    // Set up logging
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "sanabotti=debug,tower_http=debug".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = args.first().map(String::as_str);
+    if matches!(command, Some("simulate" | "replay")) {
+        // Keep stdout for the verdicts
+        init_logging(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| "sanabotti=warn".into()),
+            config::load_log_format(&Overrides::new())?,
+            BoxMakeWriter::new(std::io::stderr),
+        );
+
+        let exit_code = if command == Some("replay") {
+            replay::run_cli(&args[1..]).await?
+        } else {
+            simulate::run_cli(&args[1..]).await?
+        };
+        std::process::exit(exit_code);
+    }
+
+    if command == Some("compile-dict") {
+        let [input, output] = &args[1..] else {
+            return Err(miette::miette!(
+                "Usage: sanabotti compile-dict <words.txt> <output.bin>"
+            ));
+        };
+        let count = DictionaryValidator::compile(input, output)?;
+        println!("Compiled {} words into {}", count, output);
+        println!("Point DICTIONARY_FILE_PATH at it to load it instead of the word list");
+        return Ok(());
+    }
+
+    let cli = Cli::parse();
+    let overrides = cli.overrides();
+
+    // Set up logging, keeping the self-test's report on stdout readable
+    let filter = match &cli.log_level {
+        Some(filter) => EnvFilter::new(filter),
+        None if cli.self_test => {
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| "sanabotti=warn".into())
+        }
+        None => EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| "sanabotti=debug,tower_http=debug".into()),
+    };
+    let writer = if cli.self_test {
+        BoxMakeWriter::new(std::io::stderr)
+    } else {
+        BoxMakeWriter::new(std::io::stdout)
+    };
+    init_logging(filter, config::load_log_format(&overrides)?, writer);
 
     info!("Starting Finnish Word Game Discord Bot");
 
     // Load configuration
-    let config = config::load_config()?;
+    let config = config::load_config_with(&overrides)?;
+
+    if cli.dry_run {
+        println!("{}", config.summary());
+        // A dry run only checks things, so a missing dictionary isn't fetched
+        if config.download_dictionary && !std::path::Path::new(&config.dictionary_path).exists() {
+            println!(
+                "Dictionary: {} is missing, it would be downloaded from {}",
+                config.dictionary_path, config.dictionary_url
+            );
+        } else {
+            let paths = config.dictionary_paths();
+            let dictionary = download::load_dictionary(&config).await?;
+            println!(
+                "Dictionary: {} words from {}",
+                dictionary.len(),
+                paths.join(", ")
+            );
+        }
+        println!("Configuration OK, not connecting to Discord (--dry-run)");
+        return Ok(());
+    }
+
+    if cli.self_test {
+        let dictionary = download::load_dictionary(&config).await?;
+        let results = self_test::run(dictionary, &config).await?;
+        let exit_code = self_test::report(&results, &mut std::io::stdout()).into_diagnostic()?;
+        std::process::exit(exit_code);
+    }
+
+    let dictionary = download::load_dictionary(&config).await.map_err(|e| {
+        error!("Failed to load dictionary: {}", e);
+        e
+    })?;
 
     // Create a local task set to ensure local tasks work properly
     let local = LocalSet::new();
@@ -28,19 +905,85 @@ async fn main() -> miette::Result<()> {
     // Also handle application shutdown gracefully
     local
         .run_until(async {
-            tokio::select! {
-                result = discord::setup_bot(
+            let mut bot = tokio::select! {
+                bot = discord::setup_bot(
                     config.discord_token.clone(),
-                    config.channel_id,
-                    config.dictionary_path.clone(),
+                    dictionary,
                     config.bot_activity.clone(),
                     config
-                ) => result,
-                _ = signal::ctrl_c() => {
+                ) => bot?,
+                _ = shutdown_signal() => {
+                    info!("Received shutdown signal before the bot started, stopping");
+                    return Ok(());
+                }
+            };
+            let shutdown = bot.shutdown_handle();
+            tokio::select! {
+                result = bot.run() => result,
+                _ = shutdown_signal() => {
                     info!("Received shutdown signal, stopping bot");
+                    shutdown.shutdown().await;
                     Ok(())
                 }
             }
         })
         .await
 }
+
+/// Wait for Ctrl+C, or for SIGTERM on Unix, e.g. from `docker stop`
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        let mut terminate = match signal::unix::signal(signal::unix::SignalKind::terminate()) {
+            Ok(terminate) => terminate,
+            Err(e) => {
+                error!(error = %e, "Failed to listen for SIGTERM, only stopping on Ctrl+C");
+                signal::ctrl_c().await.ok();
+                return;
+            }
+        };
+        tokio::select! {
+            _ = signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    signal::ctrl_c().await.ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides() {
+        let cli = Cli::try_parse_from(["sanabotti"]).unwrap();
+        assert!(cli.overrides().is_empty());
+        assert!(!cli.dry_run);
+
+        let cli = Cli::try_parse_from([
+            "sanabotti",
+            "--dictionary",
+            "./test_words.txt",
+            "--channel-id",
+            "123",
+            "--allow-consecutive-turns",
+            "false",
+            "--pending-policy",
+            "strict",
+            "--dry-run",
+        ])
+        .unwrap();
+        let overrides = cli.overrides();
+        assert_eq!(overrides.len(), 4);
+        assert_eq!(overrides["DICTIONARY_FILE_PATH"], "./test_words.txt");
+        assert_eq!(overrides["TARGET_CHANNEL_ID"], "123");
+        assert_eq!(overrides["ALLOW_CONSECUTIVE_TURNS"], "false");
+        assert_eq!(overrides["PENDING_WORD_POLICY"], "strict");
+        assert!(cli.dry_run);
+
+        assert!(Cli::try_parse_from(["sanabotti", "--channel-id", "abc"]).is_err());
+        assert!(Cli::try_parse_from(["sanabotti", "--language", "sv"]).is_err());
+        assert!(Cli::try_parse_from(["sanabotti", "--log-level", "sanabotti=loud"]).is_err());
+    }
+}
@@ -0,0 +1,1016 @@
+//! English messages
+
+use std::time::Duration;
+
+use super::{HelpInfo, HelpTopic, Text};
+use crate::actors::game_state::{ChallengePlan, SetWordOutcome};
+use crate::actors::message_reaction::{
+    verdict_reaction, EMOJI_CHECK, EMOJI_CROSS, EMOJI_HOURGLASS, EMOJI_NEXT_TRACK, EMOJI_QUESTION,
+};
+use crate::audit::AuditStep;
+use crate::chain::ChainSummary;
+use crate::game::{describe_path, wait_secs, Validity, VerdictSource, WordVerdict};
+use crate::replay::SHOWN_CHANGES;
+use crate::scoring::Period;
+use crate::validation::dictionary::DictionaryFormat;
+use crate::validation::rules::{DiacriticPolicy, GameRules, PendingPolicy, RuleMode, Violation};
+
+/// Help texts of the slash commands and their parameters
+pub(super) const HELP: &[(&str, &str)] = &[
+    (
+        "help",
+        "Explain how the game is played with the current rules",
+    ),
+    (
+        "check",
+        "Check whether a word would be accepted without playing it",
+    ),
+    ("check.word", "The word to check"),
+    ("rules", "Show the game rules and the current word"),
+    ("history", "Show the most recently played words"),
+    ("history.count", "How many words to show"),
+    (
+        "recent",
+        "Show the latest verdicts and what settled them, bot owner only",
+    ),
+    ("recent.count", "How many words to show, at most 25"),
+    ("leaderboard", "Show the players with the most points"),
+    ("leaderboard.count", "How many players to show"),
+    ("leaderboard.weekly", "Only show this week's points"),
+    (
+        "stats",
+        "Show a player's points, ranks and attempt accuracy",
+    ),
+    ("stats.player", "The player, yourself by default"),
+    ("dict", "Ask the dictionary"),
+    ("dict stats", "Dictionary size and source, bot owner only"),
+    ("dict has", "Whether a word is in the dictionary"),
+    ("dict has.word", "The word to look up"),
+    ("dict random", "A random word from the dictionary"),
+    (
+        "dict bulkcheck",
+        "Check a word list against the dictionary, bot owner only",
+    ),
+    (
+        "dict bulkcheck.file",
+        "A text file with a word on each line",
+    ),
+    (
+        "dict bulkcheck.plausibility",
+        "List the words that don't look like words apart, by default as configured",
+    ),
+    (
+        "challenge",
+        "Get from one word to another in as few moves as possible",
+    ),
+    ("challenge start", "Start a challenge, bot owner only"),
+    ("challenge start.from", "The word to start from"),
+    ("challenge start.to", "The word to reach"),
+    (
+        "challenge giveup",
+        "Give up the challenge and show the shortest chain, bot owner only",
+    ),
+    (
+        "export",
+        "Export the last HISTORY_SIZE played words as a file, bot owner only",
+    ),
+    ("export.format", "File format, CSV by default"),
+    (
+        "simulate-rules",
+        "See which verdicts another rule mode would change, bot owner only",
+    ),
+    ("simulate-rules.mode", "The rule mode to try"),
+    (
+        "simulate-rules.transcript",
+        "A .csv or .json transcript to replay instead of the game's history",
+    ),
+    ("setword", "Set the current word by hand, bot owner only"),
+    ("setword.word", "The word the game continues from"),
+    (
+        "setword.force",
+        "Set the word even if it isn't in the dictionary",
+    ),
+    ("pause", "Pause the game, bot owner only"),
+    ("resume", "Resume a paused game, bot owner only"),
+    (
+        "reset",
+        "Clear the chain and start a new one, bot owner only",
+    ),
+    (
+        "llmstats",
+        "Show how much the LLM has been used, bot owner only",
+    ),
+    (
+        "llmstats.reset",
+        "Start counting from zero after showing the counts",
+    ),
+    ("reload", "Reload the list of blocked words, bot owner only"),
+    ("register", "Register the commands again, bot owner only"),
+    (
+        "why",
+        "Show how the bot handled a word, your own or as bot owner any",
+    ),
+    ("why.message", "Link to the message, or its ID"),
+    ("join", "Join the players taking turns"),
+    ("leave", "Leave the players taking turns"),
+    ("order", "Show the turn order"),
+];
+
+pub(super) fn render(text: &Text) -> String {
+    match *text {
+        Text::ForeignLetters(letters) => format!(
+            "❌ The word has letters that aren't used in the game: {}",
+            super::join_letters(letters, ", ")
+        ),
+        Text::DailyLimit { limit, reaction } => format!(
+            "{} You've already played {} today, continue tomorrow!",
+            reaction,
+            plural(limit, "word", "words")
+        ),
+        Text::Cooldown { wait, reaction } => format!(
+            "{} Wait another {} before your next word.",
+            reaction,
+            plural(wait_secs(wait), "second", "seconds")
+        ),
+        Text::NotYourTurn { user_id } => format!("⏭️ It's <@{}>'s turn.", user_id),
+        Text::Suggestions(suggestions) => format!("Did you mean: {}?", suggestions.join(", ")),
+        Text::Violation(violation) => describe_violation(violation),
+
+        Text::IdleReset { period } => format!(
+            "The game was reset after {} of silence, start a new word!",
+            describe_period(period)
+        ),
+        Text::ReadyAnnouncement {
+            current_word,
+            chain_length,
+            restored_players,
+        } => {
+            let state = match current_word {
+                None => "New game! Start with any word.".to_string(),
+                Some(word) => format!(
+                    "The game goes on! The current word is **{}** and the chain has {}.",
+                    word,
+                    plural(chain_length, "word", "words")
+                ),
+            };
+            match restored_players {
+                Some(players) => format!(
+                    "{} The game and the scores of {} were restored from the save.",
+                    state,
+                    plural(players, "player", "players")
+                ),
+                None => state,
+            }
+        }
+        Text::TurnSkipped { skipped, next } => format!(
+            "⏭️ <@{}> didn't play in time, it's <@{}>'s turn now.",
+            skipped, next
+        ),
+        Text::DeadEnd { word } => {
+            format!("Dead end: no dictionary word continues from \"{}\".", word)
+        }
+        Text::ChallengeStarted { path } => format!(
+            "🎯 Challenge: can you get from **{}** to **{}**? The shortest chain takes {}.",
+            path.first().map_or("", String::as_str),
+            path.last().map_or("", String::as_str),
+            plural(path.len().saturating_sub(1), "move", "moves")
+        ),
+        Text::ChallengeWon {
+            user_id,
+            moves,
+            path,
+        } => format!(
+            "🏁 <@{}> reached **{}**! The chain took {}, the shortest takes {}: {}",
+            user_id,
+            path.last().map_or("", String::as_str),
+            plural(moves, "move", "moves"),
+            path.len().saturating_sub(1),
+            describe_path(path)
+        ),
+        Text::Milestone {
+            chain_length,
+            user_id,
+            word,
+        } => format!(
+            "🎉 The chain has reached {}! <@{}> played **{}**.",
+            plural(chain_length, "word", "words"),
+            user_id,
+            word
+        ),
+        Text::ChainBroken(summary) => format!(
+            "💥 The chain broke after {}!",
+            plural(summary.length, "word", "words")
+        ),
+        Text::ChainSummary(summary) => describe_chain(summary),
+        Text::WordSet { word } => {
+            format!("📌 The current word is now **{}**, continue from it!", word)
+        }
+        Text::WeekEnded { week } => format!("Final standings of week {}", week),
+        Text::Leaderboard(leaders) => leaders
+            .iter()
+            .enumerate()
+            .map(|(index, (user_id, score))| {
+                format!(
+                    "{}. <@{}> – {} ({})\n",
+                    index + 1,
+                    user_id,
+                    plural(score.points, "point", "points"),
+                    plural(score.words, "word", "words")
+                )
+            })
+            .collect(),
+        Text::Presence { word, chain_length } => {
+            format!("Playing: {} (chain {})", word, chain_length)
+        }
+        Text::Status { word: None, .. } => "📌 New game! Start with any word.".to_string(),
+        Text::Status {
+            word: Some(word),
+            chain_length,
+            user_id,
+        } => {
+            let player = user_id
+                .map(|user_id| format!(", played by <@{}>", user_id))
+                .unwrap_or_default();
+            format!(
+                "📌 Current word: **{}** (chain {}){}",
+                word, chain_length, player
+            )
+        }
+        Text::StatusOffline => {
+            "📴 The bot is offline. The game continues when it's back.".to_string()
+        }
+
+        Text::Check { word, verdict } => describe_check(word.trim(), verdict),
+        Text::Rules(info) => {
+            let rules = &info.rules;
+            let mode = match rules.mode {
+                RuleMode::Classic => "classic: change, add, or remove one letter",
+                RuleMode::Anagram => {
+                    "anagram: change, add, or remove one letter, or rearrange the letters"
+                }
+            };
+            let diacritics = match rules.diacritic_policy {
+                DiacriticPolicy::Distinct => "count as a changed letter",
+                DiacriticPolicy::Free => "don't use up the move",
+                DiacriticPolicy::Forbidden => "aren't a move on their own",
+            };
+            let length = describe_length(rules);
+            let consecutive = describe_consecutive(rules);
+            let proper_nouns = match (rules.accept_proper_nouns, rules.require_capitalization) {
+                (true, false) => "accepted (checked by an LLM)",
+                (true, true) => "accepted when capitalized (checked by an LLM)",
+                (false, _) => "not accepted",
+            };
+            let current_word = info
+                .current_word
+                .as_ref()
+                .map_or("no word yet".to_string(), |word| format!("**{}**", word));
+
+            format!(
+                "**Word game rules**\n\
+                 • Rule mode: {}\n\
+                 • a↔ä, a↔å and o↔ö changes: {}\n\
+                 • Word length: {}\n\
+                 • Same player twice in a row: {}\n\
+                 • Proper nouns: {}\n\
+                 • Current word: {}",
+                mode, diacritics, length, consecutive, proper_nouns, current_word
+            )
+        }
+        Text::HelpTitle => "How to play Sanabotti".to_string(),
+        Text::HelpHeading(topic) => match topic {
+            HelpTopic::Moves => "Moves",
+            HelpTopic::Reactions => "Reactions",
+            HelpTopic::ProperNouns => "Proper nouns",
+            HelpTopic::Limits => "Limits",
+        }
+        .to_string(),
+        Text::HelpSection(topic, info) => describe_help(topic, info),
+        Text::HelpFooter(info) => match &info.current_word {
+            Some(word) => format!(
+                "Current word: {} · {} in the chain",
+                word,
+                plural(info.chain_length, "word", "words")
+            ),
+            None => "No word yet, start with any word!".to_string(),
+        },
+        Text::NoGameHere => "The word game isn't played in this server.".to_string(),
+        Text::NoHistory => "No words have been played in this game yet.".to_string(),
+        Text::HistoryTitle => "Latest words".to_string(),
+        Text::NotAMessage => "Give a message link or a message ID.".to_string(),
+        Text::NoAuditTrail => {
+            "Nothing is known about that message, or it's someone else's word.".to_string()
+        }
+        Text::AuditTitle => "How the word was handled".to_string(),
+        Text::AuditStep(step) => describe_step(step),
+        Text::OlderWords { count } => {
+            format!("…and {}", plural(count, "older word", "older words"))
+        }
+        Text::RecentTitle => "Recent verdicts".to_string(),
+        Text::RecentSource(source) => match source {
+            Some(VerdictSource::Dictionary { .. }) => "📖 dictionary".to_string(),
+            Some(VerdictSource::Llm { .. }) => "🤖 LLM".to_string(),
+            Some(VerdictSource::Admin { user_id }) => format!("🛡️ admin <@{}>", user_id),
+            Some(VerdictSource::Vote { votes }) => {
+                format!("🗳️ {}", plural(*votes, "vote", "votes"))
+            }
+            Some(VerdictSource::Seed) => "🌱 the bot's word".to_string(),
+            None => "📏 rules".to_string(),
+        },
+        Text::RecentPending { waiting } => {
+            format!(
+                "{} waiting for {}",
+                EMOJI_HOURGLASS,
+                describe_duration(waiting)
+            )
+        }
+        Text::NoScores => "Nobody has scored any points yet.".to_string(),
+        Text::LeaderboardTitle(Period::Weekly) => "Weekly standings".to_string(),
+        Text::LeaderboardTitle(Period::AllTime) => "Standings".to_string(),
+        Text::StatsTitle { name } => format!("Stats: {}", name),
+        Text::Standing { user_id, standing } => {
+            let Some(rank) = standing.rank else {
+                return format!("<@{}> hasn't scored any points yet.", user_id);
+            };
+            let weekly = match standing.weekly_rank {
+                Some(weekly_rank) => format!(
+                    "This week {}, ranked #{}.",
+                    plural(standing.weekly.points, "point", "points"),
+                    weekly_rank
+                ),
+                None => "No points this week yet.".to_string(),
+            };
+            format!(
+                "<@{}>: {} from {}, ranked #{}. {}",
+                user_id,
+                plural(standing.score.points, "point", "points"),
+                plural(standing.score.words, "word", "words"),
+                rank,
+                weekly
+            )
+        }
+        Text::Attempts(attempts) => {
+            if attempts.total == 0 {
+                return "No settled attempts yet.".to_string();
+            }
+            let line = |label: &str, count: usize| {
+                format!("{}: {} ({} %)", label, count, attempts.percent(count))
+            };
+            [
+                format!("Attempts: {}", attempts.total),
+                line("From the dictionary", attempts.dictionary),
+                line("As proper nouns", attempts.proper_nouns),
+                line("Rule violations", attempts.rule_violations),
+                line("Already used", attempts.already_used),
+                line("Not words", attempts.not_words),
+                line("Blocked", attempts.blocked),
+            ]
+            .join("\n")
+        }
+        Text::GamePaused => "💤 The game is paused.".to_string(),
+        Text::DictionaryStats(stats) => {
+            let format = match stats.source.format {
+                DictionaryFormat::Text => "text file",
+                DictionaryFormat::KotusXml => "Kotus XML word list",
+                DictionaryFormat::Compiled => "compiled dictionary",
+                DictionaryFormat::InMemory => "in memory",
+            };
+            format!(
+                "📖 **{}** words\nFile: `{}` ({})\nLoad time: {} ms",
+                stats.words,
+                stats.source.path.as_deref().unwrap_or("-"),
+                format,
+                stats.source.load_time.as_millis()
+            )
+        }
+        Text::Lookup(lookup) => match &lookup.found {
+            None => format!("❌ **{}** isn't in the dictionary.", lookup.normalized),
+            Some(found) => match &found.rule {
+                None => format!("✅ **{}** is in the dictionary.", lookup.normalized),
+                Some(rule) => format!(
+                    "✅ **{}** is accepted as an inflected form of **{}** ({}).",
+                    lookup.normalized, found.lemma, rule
+                ),
+            },
+        },
+        Text::BulkCheck(check) => {
+            let mut text = format!(
+                "📋 {} in the dictionary, {} missing, {}.",
+                plural(check.found, "word", "words"),
+                check.missing.len(),
+                plural(check.malformed, "malformed line", "malformed lines")
+            );
+            if !check.implausible.is_empty() {
+                text.push_str(&format!(
+                    " {} more missing don't look like words.",
+                    check.implausible.len()
+                ));
+            }
+            if check.duplicates > 0 {
+                text.push_str(&format!(
+                    " {} listed more than once.",
+                    plural(check.duplicates, "word was", "words were")
+                ));
+            }
+            text
+        }
+        Text::BulkCheckReport(check) => {
+            let mut text = render(&Text::BulkCheck(check));
+            for (title, words) in [
+                ("Missing words", &check.missing),
+                ("Don't look like words", &check.implausible),
+            ] {
+                if !words.is_empty() {
+                    text.push_str(&format!("\n\n{}:\n{}", title, words.join("\n")));
+                }
+            }
+            text.push('\n');
+            text
+        }
+        Text::BulkCheckTooLarge { limit_kb } => format!(
+            "❌ The file is too large, at most {} KB can be checked.",
+            limit_kb
+        ),
+        Text::BulkCheckFailed => "❌ Failed to download the file.".to_string(),
+        Text::DictionaryEmpty => "The dictionary is empty.".to_string(),
+        Text::RulesSimulation(simulation) => {
+            let mut text = format!(
+                "🧪 **Simulation only**, the game isn't affected. Replayed {} under the {} rules \
+                 instead of {}: ",
+                plural(simulation.records, "word", "words"),
+                describe_mode(simulation.proposed_mode),
+                describe_mode(simulation.current_mode)
+            );
+            if simulation.changes.is_empty() {
+                text.push_str("no verdict would change.");
+                return text;
+            }
+            text.push_str(&format!(
+                "{} would change.",
+                plural(simulation.changes.len(), "verdict", "verdicts")
+            ));
+            for change in simulation.changes.iter().take(SHOWN_CHANGES) {
+                text.push_str(&format!(
+                    "\n• #{} **{}**: {} → {}",
+                    change.index, change.input, change.current, change.proposed
+                ));
+            }
+            if simulation.changes.len() > SHOWN_CHANGES {
+                text.push_str(&format!(
+                    "\n…and {} more.",
+                    simulation.changes.len() - SHOWN_CHANGES
+                ));
+            }
+            text
+        }
+        Text::TranscriptInvalid => {
+            "❌ Couldn't read the transcript, expected a .csv or .json file of \
+             timestamp, user_id and word."
+                .to_string()
+        }
+        Text::ChallengePlan(plan) => match plan {
+            ChallengePlan::Ready(path) => format!(
+                "Challenge started, the shortest chain takes {}: {}",
+                plural(path.len() - 1, "move", "moves"),
+                describe_path(path)
+            ),
+            ChallengePlan::NotInDictionary(word) => {
+                format!("❌ **{}** isn't in the dictionary.", word)
+            }
+            ChallengePlan::SameWord => "❌ The start and target are the same word.".to_string(),
+            ChallengePlan::NoPath => {
+                "❌ No chain of dictionary words connects the words.".to_string()
+            }
+            ChallengePlan::TooFar(words) => format!(
+                "❌ No chain found after looking at {}, pick words closer to each other.",
+                plural(*words, "word", "words")
+            ),
+        },
+        Text::ChallengeGivenUp { path } => format!(
+            "🏳️ The challenge is over. The shortest chain ({}): {}",
+            plural(path.len().saturating_sub(1), "move", "moves"),
+            describe_path(path)
+        ),
+        Text::NoChallenge => "There's no challenge going on.".to_string(),
+        Text::Export(export) => {
+            let mut text = format!("📄 Exported {}.", plural(export.rows, "word", "words"));
+            if export.truncated > 0 {
+                text.push_str(&format!(
+                    " ⚠️ The newest {} had to be left out to fit the file within Discord's 8 MB limit.",
+                    plural(export.truncated, "word", "words")
+                ));
+            }
+            text
+        }
+        Text::ExportFailed => "❌ Failed to post the file.".to_string(),
+        Text::SetWord(outcome) => match outcome {
+            SetWordOutcome::Set { word, dropped: 0 } => {
+                format!("The current word is now **{}**.", word)
+            }
+            SetWordOutcome::Set { word, dropped } => format!(
+                "The current word is now **{}**. Rejected {} waiting to be checked.",
+                word,
+                plural(*dropped, "word", "words")
+            ),
+            SetWordOutcome::NotInDictionary(word) => format!(
+                "❌ **{}** isn't in the dictionary, set it with the `force` option.",
+                word
+            ),
+            SetWordOutcome::NotAWord => "❌ Give a single word.".to_string(),
+        },
+        Text::Paused => "💤 The game is paused. Words played meanwhile are ignored.".to_string(),
+        Text::AlreadyPaused => "The game is already paused.".to_string(),
+        Text::Resumed => "▶️ The game goes on!".to_string(),
+        Text::NotPaused => "The game isn't paused.".to_string(),
+        Text::ChainReset => "🔄 The chain was reset, start a new one with any word!".to_string(),
+        Text::NoTurns => "Players don't take turns in this game, anyone may play.".to_string(),
+        Text::Joined { user_id } => format!("✅ <@{}> joined the turn order.", user_id),
+        Text::AlreadyJoined => "You're already in the turn order.".to_string(),
+        Text::Left { user_id } => format!("👋 <@{}> left the turn order.", user_id),
+        Text::NotJoined => "You're not in the turn order.".to_string(),
+        Text::TurnOrder([]) => {
+            "Nobody has joined yet, join with /join! Until then anyone may play.".to_string()
+        }
+        Text::TurnOrder(players) => std::iter::once("Turn order:".to_string())
+            .chain(players.iter().enumerate().map(|(index, user_id)| {
+                let on_turn = if index == 0 { " – on turn" } else { "" };
+                format!("{}. <@{}>{}", index + 1, user_id, on_turn)
+            }))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Text::BlocklistReloaded { words } => {
+            format!("The blocklist now has {}.", plural(words, "word", "words"))
+        }
+        Text::LlmStats {
+            stats,
+            queue,
+            validations,
+            reset,
+        } => {
+            let mut lines = vec![format!(
+                "🤖 LLM usage since {}",
+                stats.since.format("%Y-%m-%d %H:%M UTC")
+            )];
+            if stats.models.is_empty() {
+                lines.push("No calls to the LLM.".to_string());
+            }
+            for (model, usage) in &stats.models {
+                let tokens = match usage.calls_without_usage {
+                    0 => format!(
+                        "{} prompt + {} response tokens",
+                        usage.tokens.prompt, usage.tokens.response
+                    ),
+                    missing if missing == usage.calls => "tokens not reported".to_string(),
+                    missing => format!(
+                        "{} prompt + {} response tokens ({} not reported)",
+                        usage.tokens.prompt,
+                        usage.tokens.response,
+                        plural(missing, "call", "calls")
+                    ),
+                };
+                lines.push(format!(
+                    "**{}**: {} ({} failed), {}, {} ms per call, {}",
+                    model,
+                    plural(usage.calls, "call", "calls"),
+                    usage.failures,
+                    plural(usage.words, "word", "words"),
+                    usage.average_latency().as_millis(),
+                    tokens
+                ));
+            }
+            lines.push(format!(
+                "Cache: {} answered from the cache, {} sent to the LLM ({} % from the cache)",
+                plural(stats.cache_hits, "word", "words"),
+                plural(stats.escalations(), "word", "words"),
+                stats.cache_hit_percent()
+            ));
+            lines.push(match queue.oldest {
+                Some(oldest) => format!(
+                    "Queue: {} waiting, the oldest for {}",
+                    plural(queue.queued, "word", "words"),
+                    plural(wait_secs(oldest), "second", "seconds")
+                ),
+                None => "Queue: empty".to_string(),
+            });
+            if let Some(budget) = queue.budget {
+                lines.push(format!(
+                    "Daily budget: {} of {} used in the last 24 hours{}",
+                    budget.used,
+                    plural(budget.limit, "word", "words"),
+                    if budget.exhausted() {
+                        ", words over it aren't sent to the LLM"
+                    } else {
+                        ""
+                    }
+                ));
+            }
+            lines.push(format!(
+                "Validation: {} in progress, {} waiting its turn",
+                plural(validations.in_flight, "word", "words"),
+                plural(validations.queued, "word", "words")
+            ));
+            if reset {
+                lines.push("The counters were reset.".to_string());
+            }
+            lines.join("\n")
+        }
+    }
+}
+
+fn describe_help(topic: HelpTopic, info: &HelpInfo) -> String {
+    let rules = &info.game.rules;
+    match topic {
+        HelpTopic::Moves => match rules.mode {
+            RuleMode::Classic => "Continue the chain with a word made from the previous one by \
+                 changing, adding, or removing one letter:\n\
+                 • kissa → kassa (change)\n\
+                 • kisa → kissa (add)\n\
+                 • kassa → kasa (remove)\n\
+                 The same word can't be played twice in a game."
+                .to_string(),
+            RuleMode::Anagram => "Continue the chain with a word made from the previous one by \
+                 changing, adding, or removing one letter, or by rearranging its letters:\n\
+                 • kissa → kassa (change)\n\
+                 • kisa → kissa (add)\n\
+                 • kassa → kasa (remove)\n\
+                 • sika → kisa (rearrange)\n\
+                 The same word can't be played twice in a game."
+                .to_string(),
+        },
+        HelpTopic::Reactions => {
+            let mut lines = vec![
+                format!("{} the word was accepted", EMOJI_CHECK),
+                format!(
+                    "{} the word was rejected: not in the dictionary or against the rules",
+                    EMOJI_CROSS
+                ),
+                format!(
+                    "{} the word was already played in this game",
+                    info.already_used_reaction
+                ),
+            ];
+            if rules.accept_proper_nouns {
+                lines.push(format!(
+                    "{} the word is still being checked as a proper noun",
+                    EMOJI_QUESTION
+                ));
+            }
+            match rules.pending_policy {
+                PendingPolicy::Strict => lines.push(format!(
+                    "{} the previous word is waiting to be checked, try again in a moment",
+                    EMOJI_HOURGLASS
+                )),
+                PendingPolicy::Queue => lines.push(format!(
+                    "{} the word is judged once the previous word has been checked, \
+                     or if it stays, too many words are waiting and you can try again later",
+                    EMOJI_HOURGLASS
+                )),
+                PendingPolicy::Optimistic | PendingPolicy::Rollback => {}
+            }
+            if info.daily_limit.is_some() {
+                lines.push(format!(
+                    "{} you've played all your words for today",
+                    info.daily_limit_reaction
+                ));
+            }
+            if info.cooldown.is_some() {
+                lines.push(format!(
+                    "{} wait a moment before your next word",
+                    info.cooldown_reaction
+                ));
+            }
+            if let Some(reaction) = info.typo_reaction {
+                lines.push(format!("{} the word is likely a typo, try again", reaction));
+            }
+            if info.rotation {
+                lines.push(format!(
+                    "{} it isn't your turn, join with /join and wait for your turn",
+                    EMOJI_NEXT_TRACK
+                ));
+            }
+            lines.push(format!(
+                "{} the game is paused, the word was ignored",
+                info.pause_reaction
+            ));
+            lines.join("\n")
+        }
+        HelpTopic::ProperNouns if !rules.accept_proper_nouns => {
+            "Only dictionary words count, proper nouns aren't accepted.".to_string()
+        }
+        HelpTopic::ProperNouns => {
+            let pending = match rules.pending_policy {
+                PendingPolicy::Optimistic => "the chain already goes on from it",
+                PendingPolicy::Strict => "the next word can't be played",
+                PendingPolicy::Rollback => {
+                    "the chain already goes on from it, but if it's rejected, \
+                     the words played after it are checked again"
+                }
+                PendingPolicy::Queue => "the next words wait and are judged in order after it",
+            };
+            let capitalization = if rules.require_capitalization {
+                " Proper nouns must be capitalized, unknown words written in lowercase \
+                 are rejected right away."
+            } else {
+                ""
+            };
+            format!(
+                "Words missing from the dictionary are checked by an LLM, and proper nouns \
+                 like Helsinki are accepted. While a word is being checked, {}.{}",
+                pending, capitalization
+            )
+        }
+        HelpTopic::Limits => {
+            let mut lines = vec![
+                format!("• Word length: {}", describe_length(rules)),
+                format!(
+                    "• Same player twice in a row: {}",
+                    describe_consecutive(rules)
+                ),
+                match info.daily_limit {
+                    Some(limit) => format!("• Words a day: at most {}", limit),
+                    None => "• Words a day: unlimited".to_string(),
+                },
+                match info.cooldown {
+                    Some(cooldown) => format!(
+                        "• Wait between words: {}",
+                        plural(wait_secs(cooldown), "second", "seconds")
+                    ),
+                    None => "• Wait between words: none".to_string(),
+                },
+            ];
+            if info.rotation {
+                lines.push(
+                    "• Turns: players who joined with /join play in turn, \
+                     /order shows the order"
+                        .to_string(),
+                );
+            }
+            if let Some(idle_reset) = info.idle_reset {
+                lines.push(format!(
+                    "• The game resets after {} of silence",
+                    describe_period(idle_reset)
+                ));
+            }
+            lines.join("\n")
+        }
+    }
+}
+
+fn describe_length(rules: &GameRules) -> String {
+    match (rules.min_length, rules.max_length) {
+        (None, None) => "unlimited".to_string(),
+        (Some(min), None) => format!("at least {} letters", min),
+        (None, Some(max)) => format!("at most {} letters", max),
+        (Some(min), Some(max)) => format!("{}–{} letters", min, max),
+    }
+}
+
+fn describe_consecutive(rules: &GameRules) -> &'static str {
+    if rules.allow_consecutive_turns {
+        "allowed"
+    } else {
+        "not allowed"
+    }
+}
+
+fn describe_check(word: &str, verdict: &WordVerdict) -> String {
+    match verdict {
+        WordVerdict::ValidInDictionary | WordVerdict::ValidProperNoun => {
+            format!("✅ **{}** would be accepted as the next word.", word)
+        }
+        WordVerdict::NotInDictionary | WordVerdict::PendingLlm => {
+            format!("❌ **{}** isn't accepted: not in the dictionary.", word)
+        }
+        WordVerdict::InvalidRules(violation) => format!(
+            "❌ **{}** isn't accepted: {}",
+            word,
+            describe_violation(violation)
+        ),
+        WordVerdict::AwaitingVerdict => format!(
+            "⏳ **{}** isn't accepted yet: the previous word is waiting to be checked.",
+            word
+        ),
+        WordVerdict::Queued => format!(
+            "⏳ **{}** would wait until the previous word has been checked.",
+            word
+        ),
+        WordVerdict::NotAWord => format!("❌ **{}** isn't a single word.", word),
+        WordVerdict::Duplicate => format!("**{}** has already been handled.", word),
+        WordVerdict::DailyLimitReached(limit) => format!(
+            "⛔ You've already played {} today, **{}** has to wait until tomorrow.",
+            plural(*limit, "word", "words"),
+            word
+        ),
+        WordVerdict::CoolingDown(wait) => format!(
+            "⏲️ **{}** isn't accepted yet: wait {}.",
+            word,
+            plural(wait_secs(*wait), "second", "seconds")
+        ),
+        WordVerdict::NotYourTurn(user_id) => {
+            format!("⏭️ **{}** has to wait: it's <@{}>'s turn.", word, user_id)
+        }
+        WordVerdict::LikelyTypo(meant) => format!(
+            "✏️ **{}** looks like a typo: did you mean **{}**?",
+            word, meant
+        ),
+        WordVerdict::Implausible => format!(
+            "❌ **{}** isn't accepted: it doesn't look like a word.",
+            word
+        ),
+        WordVerdict::Blocked => format!("❌ **{}** isn't allowed in the game.", word),
+        WordVerdict::Paused => format!("💤 The game is paused, **{}** would be ignored.", word),
+        WordVerdict::ForeignLetters(letters) => format!(
+            "❌ **{}**: letters not used in the game: {}",
+            word,
+            super::join_letters(letters, "")
+        ),
+    }
+}
+
+fn describe_step(step: &AuditStep) -> String {
+    match step {
+        AuditStep::Received { user_id } => format!("📨 Received from <@{}>", user_id),
+        AuditStep::Normalized(Some(word)) => format!("🔤 Read as **{}**", word),
+        AuditStep::Normalized(None) => "🔤 No single word in the message".to_string(),
+        AuditStep::Dictionary(true) => "📖 Found in the dictionary".to_string(),
+        AuditStep::Dictionary(false) => "📖 Not in the dictionary".to_string(),
+        AuditStep::Verdict(verdict) => format!("⚖️ {}", describe_verdict(verdict)),
+        AuditStep::SentToLlm { attempt: 1 } => "🤖 Sent to the LLM".to_string(),
+        AuditStep::SentToLlm { attempt } => {
+            format!("🤖 Sent to the LLM again, attempt {}", attempt)
+        }
+        AuditStep::OverBudget => "💸 Not sent to the LLM, the daily budget is used up".to_string(),
+        AuditStep::Expired => "⌛ Not sent to the LLM, the word waited too long".to_string(),
+        AuditStep::Settled { validity, source } => {
+            let outcome = match validity {
+                Validity::Valid => "Accepted",
+                Validity::Invalid => "Rejected",
+                Validity::Pending => "Left pending",
+            };
+            let by = match source {
+                VerdictSource::Dictionary { .. } => "by the dictionary".to_string(),
+                VerdictSource::Llm {
+                    explanation,
+                    model: Some(model),
+                } => format!("by the LLM ({}): {}", model, explanation),
+                VerdictSource::Llm {
+                    explanation,
+                    model: None,
+                } => format!("without the LLM: {}", explanation),
+                VerdictSource::Admin { user_id } => format!("by <@{}>", user_id),
+                VerdictSource::Vote { votes } => {
+                    format!("by the players, {}", plural(*votes, "vote", "votes"))
+                }
+                VerdictSource::Seed => "as the bot's own word".to_string(),
+            };
+            format!("{} {} {}", verdict_reaction(*validity), outcome, by)
+        }
+        AuditStep::ReactionAdded { reaction, ok: true } => format!("Added {}", reaction),
+        AuditStep::ReactionAdded {
+            reaction,
+            ok: false,
+        } => {
+            format!("⚠️ Failed to add {}", reaction)
+        }
+        AuditStep::ReactionRemoved { reaction, ok: true } => format!("Removed {}", reaction),
+        AuditStep::ReactionRemoved {
+            reaction,
+            ok: false,
+        } => {
+            format!("⚠️ Failed to remove {}", reaction)
+        }
+        AuditStep::ReactionsCleared { ok: true } => "Cleared the reactions".to_string(),
+        AuditStep::ReactionsCleared { ok: false } => "⚠️ Failed to clear the reactions".to_string(),
+    }
+}
+
+/// What the game made of a word, for /why
+fn describe_verdict(verdict: &WordVerdict) -> String {
+    match verdict {
+        WordVerdict::ValidInDictionary => "Valid dictionary word".to_string(),
+        WordVerdict::PendingLlm => "Not in the dictionary, waits for the LLM".to_string(),
+        WordVerdict::ValidProperNoun => "Accepted as a proper noun".to_string(),
+        WordVerdict::InvalidRules(violation) => {
+            format!("Breaks the rules: {}", describe_violation(violation))
+        }
+        WordVerdict::AwaitingVerdict => {
+            "Played while the previous word waited to be checked".to_string()
+        }
+        WordVerdict::Queued => "Waits until the previous word has been checked".to_string(),
+        WordVerdict::NotInDictionary => "Not in the dictionary".to_string(),
+        WordVerdict::NotAWord => "Not a single word".to_string(),
+        WordVerdict::Duplicate => "Already handled".to_string(),
+        WordVerdict::DailyLimitReached(limit) => format!(
+            "Over the daily limit of {}",
+            plural(*limit, "word", "words")
+        ),
+        WordVerdict::CoolingDown(wait) => format!(
+            "Played {} before the cooldown ended",
+            plural(wait_secs(*wait), "second", "seconds")
+        ),
+        WordVerdict::NotYourTurn(user_id) => format!("Played on <@{}>'s turn", user_id),
+        WordVerdict::LikelyTypo(meant) => {
+            format!("Likely a typo of {}, not played", meant)
+        }
+        WordVerdict::Implausible => "Doesn't look like a word".to_string(),
+        WordVerdict::Blocked => "On the blocklist".to_string(),
+        WordVerdict::ForeignLetters(letters) => format!(
+            "Letters not used in the game: {}",
+            super::join_letters(letters, "")
+        ),
+        WordVerdict::Paused => "Played while the game was paused".to_string(),
+    }
+}
+
+fn describe_violation(violation: &Violation) -> String {
+    match violation {
+        Violation::TooShort { min } => {
+            format!("the word is too short (at least {} letters)", min)
+        }
+        Violation::TooLong { max } => format!("the word is too long (at most {} letters)", max),
+        Violation::Letters(difference) => match difference.changes.as_slice() {
+            [] => "the word is the same as the previous one".to_string(),
+            [change] => format!("you changed 1 letter: {}", change),
+            changes => format!(
+                "you changed {} letters: {}",
+                changes.len(),
+                super::join_changes(changes)
+            ),
+        },
+        Violation::DiacriticOnly(change) => format!(
+            "you only changed {}, which isn't a move of its own in this game",
+            change
+        ),
+        Violation::ConsecutiveTurns => "the same player can't play two words in a row".to_string(),
+        Violation::AlreadyUsed(_) => "the word has already been played this game".to_string(),
+        Violation::Other(reason) => reason.clone(),
+    }
+}
+
+/// The summary of a broken chain, one fact per line
+fn describe_chain(summary: &ChainSummary) -> String {
+    let mut lines = vec![format!(
+        "<@{}> broke the chain with **{}**.",
+        summary.broken_by, summary.breaking_word
+    )];
+    if let Some((user_id, words)) = summary.top_contributor {
+        lines.push(format!(
+            "Most words: <@{}> ({})",
+            user_id,
+            plural(words, "word", "words")
+        ));
+    }
+    if let Some(word) = &summary.longest_word {
+        lines.push(format!("Longest word: **{}**", word));
+    }
+    lines.push(format!("Lasted: {}", describe_duration(summary.duration)));
+    lines.push(if summary.restart {
+        "A new chain starts, begin with any word!".to_string()
+    } else {
+        "The game is paused until the chain is reset with /reset.".to_string()
+    });
+    lines.join("\n")
+}
+
+/// How long something lasted in hours and minutes, e.g. "2 hours 5 minutes"
+fn describe_duration(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    match (minutes / 60, minutes % 60) {
+        (0, 0) => "less than a minute".to_string(),
+        (0, minutes) => plural(minutes, "minute", "minutes"),
+        (hours, 0) => plural(hours, "hour", "hours"),
+        (hours, minutes) => format!(
+            "{} {}",
+            plural(hours, "hour", "hours"),
+            plural(minutes, "minute", "minutes")
+        ),
+    }
+}
+
+/// An idle period, e.g. "48 hours"
+fn describe_period(period: Duration) -> String {
+    let secs = period.as_secs();
+    if secs >= 3600 && secs.is_multiple_of(3600) {
+        plural(secs / 3600, "hour", "hours")
+    } else if secs >= 60 && secs.is_multiple_of(60) {
+        plural(secs / 60, "minute", "minutes")
+    } else {
+        plural(secs, "second", "seconds")
+    }
+}
+
+fn describe_mode(mode: RuleMode) -> &'static str {
+    match mode {
+        RuleMode::Classic => "classic",
+        RuleMode::Anagram => "anagram",
+    }
+}
+
+/// A count with the noun agreeing with it, e.g. "1 word" or "3 words"
+fn plural<N>(count: N, one: &str, many: &str) -> String
+where
+    N: Copy + PartialEq + From<u8> + std::fmt::Display,
+{
+    let noun = if count == N::from(1) { one } else { many };
+    format!("{} {}", count, noun)
+}
@@ -0,0 +1,989 @@
+//! Finnish messages, the bot's original language
+
+use std::time::Duration;
+
+use super::{HelpInfo, HelpTopic, Text};
+use crate::actors::game_state::{ChallengePlan, SetWordOutcome};
+use crate::actors::message_reaction::{
+    verdict_reaction, EMOJI_CHECK, EMOJI_CROSS, EMOJI_HOURGLASS, EMOJI_NEXT_TRACK, EMOJI_QUESTION,
+};
+use crate::audit::AuditStep;
+use crate::chain::ChainSummary;
+use crate::game::{describe_path, wait_secs, Validity, VerdictSource, WordVerdict};
+use crate::replay::SHOWN_CHANGES;
+use crate::scoring::Period;
+use crate::simulate::Status;
+use crate::validation::dictionary::DictionaryFormat;
+use crate::validation::rules::{DiacriticPolicy, GameRules, PendingPolicy, RuleMode, Violation};
+
+/// Help texts of the slash commands and their parameters
+pub(super) const HELP: &[(&str, &str)] = &[
+    ("help", "Näytä, miten peliä pelataan nykyisillä säännöillä"),
+    ("check", "Tarkista kelpaisiko sana pelaamatta sitä"),
+    ("check.word", "Tarkistettava sana"),
+    ("rules", "Näytä pelin säännöt ja nykyinen sana"),
+    ("history", "Näytä viimeksi pelatut sanat"),
+    ("history.count", "Näytettävien sanojen määrä"),
+    (
+        "recent",
+        "Näytä viimeisimmät tulokset ja mistä ne tulivat, vain botin omistajalle",
+    ),
+    ("recent.count", "Näytettävien sanojen määrä, enintään 25"),
+    ("leaderboard", "Näytä eniten pisteitä keränneet pelaajat"),
+    ("leaderboard.count", "Näytettävien pelaajien määrä"),
+    ("leaderboard.weekly", "Näytä vain tämän viikon pisteet"),
+    (
+        "stats",
+        "Näytä pelaajan pisteet, sijoitus ja yritysten osumatarkkuus",
+    ),
+    ("stats.player", "Pelaaja, oletuksena sinä itse"),
+    ("dict", "Kysy sanakirjalta"),
+    (
+        "dict stats",
+        "Sanakirjan koko ja lähde, vain botin omistajalle",
+    ),
+    ("dict has", "Onko sana sanakirjassa"),
+    ("dict has.word", "Haettava sana"),
+    ("dict random", "Satunnainen sana sanakirjasta"),
+    (
+        "dict bulkcheck",
+        "Tarkista sanalista sanakirjasta, vain botin omistajalle",
+    ),
+    (
+        "dict bulkcheck.file",
+        "Tekstitiedosto, jossa on sana joka rivillä",
+    ),
+    (
+        "dict bulkcheck.plausibility",
+        "Erottele sanoilta näyttämättömät, oletuksena asetuksen mukaan",
+    ),
+    (
+        "challenge",
+        "Pääse sanasta toiseen mahdollisimman vähin siirroin",
+    ),
+    ("challenge start", "Aloita haaste, vain botin omistajalle"),
+    ("challenge start.from", "Sana, josta lähdetään"),
+    ("challenge start.to", "Sana, johon pitää päästä"),
+    (
+        "challenge giveup",
+        "Luovuta haaste ja näytä lyhin reitti, vain botin omistajalle",
+    ),
+    (
+        "export",
+        "Vie viimeiset HISTORY_SIZE pelattua sanaa tiedostona, vain botin omistajalle",
+    ),
+    ("export.format", "Tiedostomuoto, oletuksena CSV"),
+    (
+        "simulate-rules",
+        "Katso, mitkä tulokset toinen sääntötila muuttaisi, vain botin omistajalle",
+    ),
+    ("simulate-rules.mode", "Kokeiltava sääntötila"),
+    (
+        "simulate-rules.transcript",
+        "Pelihistorian sijaan toistettava .csv- tai .json-tallenne",
+    ),
+    (
+        "setword",
+        "Aseta nykyinen sana käsin, vain botin omistajalle",
+    ),
+    ("setword.word", "Sana, josta peli jatkuu"),
+    (
+        "setword.force",
+        "Aseta sana, vaikka se ei olisi sanakirjassa",
+    ),
+    ("pause", "Keskeytä peli, vain botin omistajalle"),
+    ("resume", "Jatka keskeytettyä peliä, vain botin omistajalle"),
+    (
+        "reset",
+        "Nollaa ketju ja aloita uusi, vain botin omistajalle",
+    ),
+    (
+        "llmstats",
+        "Näytä, kuinka paljon LLM:ää on käytetty, vain botin omistajalle",
+    ),
+    (
+        "llmstats.reset",
+        "Aloita laskenta alusta lukemien näyttämisen jälkeen",
+    ),
+    (
+        "reload",
+        "Lataa estettyjen sanojen lista uudelleen, vain botin omistajalle",
+    ),
+    (
+        "register",
+        "Rekisteröi komennot uudelleen, vain botin omistajalle",
+    ),
+    (
+        "why",
+        "Näytä, miten botti käsitteli sanan, omasi tai omistajana minkä tahansa",
+    ),
+    ("why.message", "Viestin linkki tai ID"),
+    ("join", "Liity vuorotellen pelaaviin"),
+    ("leave", "Poistu vuorotellen pelaavista"),
+    ("order", "Näytä vuorojärjestys"),
+];
+
+pub(super) fn render(text: &Text) -> String {
+    match *text {
+        Text::ForeignLetters(letters) => format!(
+            "❌ Sanassa on kirjaimia, joita pelissä ei käytetä: {}",
+            super::join_letters(letters, ", ")
+        ),
+        Text::DailyLimit { limit, reaction } => format!(
+            "{} Olet pelannut tänään jo {} sanaa, jatka huomenna!",
+            reaction, limit
+        ),
+        Text::Cooldown { wait, reaction } => format!(
+            "{} Odota vielä {} s ennen seuraavaa sanaa.",
+            reaction,
+            wait_secs(wait)
+        ),
+        Text::NotYourTurn { user_id } => format!("⏭️ Nyt on pelaajan <@{}> vuoro.", user_id),
+        Text::Suggestions(suggestions) => format!("Tarkoititko: {}?", suggestions.join(", ")),
+        Text::Violation(violation) => describe_violation(violation),
+
+        Text::IdleReset { period } => format!(
+            "Peli nollattu {} hiljaisuuden jälkeen, aloita uusi sana!",
+            describe_period(period)
+        ),
+        Text::ReadyAnnouncement {
+            current_word,
+            chain_length,
+            restored_players,
+        } => {
+            let state = match current_word {
+                None => "Uusi peli! Aloita millä tahansa sanalla.".to_string(),
+                Some(word) => format!(
+                    "Sanapeli jatkuu! Nykyinen sana on **{}**, ketjun pituus on {}.",
+                    word, chain_length
+                ),
+            };
+            match restored_players {
+                Some(players) => format!(
+                    "{} Peli ja {} pelaajan pisteet palautettiin tallennuksesta.",
+                    state, players
+                ),
+                None => state,
+            }
+        }
+        Text::TurnSkipped { skipped, next } => format!(
+            "⏭️ <@{}> ei pelannut ajoissa, nyt on pelaajan <@{}> vuoro.",
+            skipped, next
+        ),
+        Text::DeadEnd { word } => format!(
+            "Umpikuja: sanasta \"{}\" ei pääse eteenpäin yhdelläkään sanakirjan sanalla.",
+            word
+        ),
+        Text::ChallengeStarted { path } => format!(
+            "🎯 Haaste: pääsettekö sanasta **{}** sanaan **{}**? Lyhin reitti on {} siirtoa.",
+            path.first().map_or("", String::as_str),
+            path.last().map_or("", String::as_str),
+            path.len().saturating_sub(1)
+        ),
+        Text::ChallengeWon {
+            user_id,
+            moves,
+            path,
+        } => format!(
+            "🏁 <@{}> pääsi sanaan **{}**! Ketjussa oli {} siirtoa, lyhin reitti on {} siirtoa: {}",
+            user_id,
+            path.last().map_or("", String::as_str),
+            moves,
+            path.len().saturating_sub(1),
+            describe_path(path)
+        ),
+        Text::Milestone {
+            chain_length,
+            user_id,
+            word,
+        } => format!(
+            "🎉 Ketjussa on nyt {} sanaa! <@{}> pelasi sanan **{}**.",
+            chain_length, user_id, word
+        ),
+        Text::ChainBroken(summary) => format!("💥 Ketju katkesi {} sanan jälkeen!", summary.length),
+        Text::ChainSummary(summary) => describe_chain(summary),
+        Text::WordSet { word } => format!("📌 Nykyinen sana on nyt **{}**, jatkakaa siitä!", word),
+        Text::WeekEnded { week } => format!("Viikon {} lopputulokset", week),
+        Text::Leaderboard(leaders) => leaders
+            .iter()
+            .enumerate()
+            .map(|(index, (user_id, score))| {
+                format!(
+                    "{}. <@{}> – {} pistettä ({} sanaa)\n",
+                    index + 1,
+                    user_id,
+                    score.points,
+                    score.words
+                )
+            })
+            .collect(),
+        Text::Presence { word, chain_length } => {
+            format!("Pelataan: {} (ketju {})", word, chain_length)
+        }
+        Text::Status { word: None, .. } => "📌 Uusi peli! Aloita millä tahansa sanalla.".to_string(),
+        Text::Status {
+            word: Some(word),
+            chain_length,
+            user_id,
+        } => {
+            let player = user_id
+                .map(|user_id| format!(", pelasi <@{}>", user_id))
+                .unwrap_or_default();
+            format!(
+                "📌 Nykyinen sana: **{}** (ketjun pituus {}){}",
+                word, chain_length, player
+            )
+        }
+        Text::StatusOffline => "📴 Botti ei ole päällä. Peli jatkuu, kun botti palaa.".to_string(),
+
+        Text::Check { word, verdict } => describe_check(word.trim(), verdict),
+        Text::Rules(info) => {
+            let rules = &info.rules;
+            let mode = match rules.mode {
+                RuleMode::Classic => "klassinen: vaihda, lisää tai poista yksi kirjain",
+                RuleMode::Anagram => "anagrammi: vaihda, lisää tai poista yksi kirjain, tai järjestä kirjaimet uudelleen",
+            };
+            let diacritics = match rules.diacritic_policy {
+                DiacriticPolicy::Distinct => "lasketaan kirjaimen vaihdoksi",
+                DiacriticPolicy::Free => "eivät kuluta siirtoa",
+                DiacriticPolicy::Forbidden => "eivät yksinään ole siirto",
+            };
+            let length = describe_length(rules);
+            let consecutive = describe_consecutive(rules);
+            let proper_nouns = match (rules.accept_proper_nouns, rules.require_capitalization) {
+                (true, false) => "hyväksytään (tarkistetaan tekoälyllä)",
+                (true, true) => {
+                    "hyväksytään isolla alkukirjaimella kirjoitettuina (tarkistetaan tekoälyllä)"
+                }
+                (false, _) => "ei hyväksytä",
+            };
+            let current_word = info
+                .current_word
+                .as_ref()
+                .map_or("ei vielä sanaa".to_string(), |word| format!("**{}**", word));
+
+            format!(
+                "**Sanapelin säännöt**\n\
+                 • Sääntötila: {}\n\
+                 • Muutokset a↔ä, a↔å ja o↔ö: {}\n\
+                 • Sanan pituus: {}\n\
+                 • Sama pelaaja kahdesti peräkkäin: {}\n\
+                 • Erisnimet: {}\n\
+                 • Nykyinen sana: {}",
+                mode, diacritics, length, consecutive, proper_nouns, current_word
+            )
+        }
+        Text::HelpTitle => "Näin Sanabottia pelataan".to_string(),
+        Text::HelpHeading(topic) => match topic {
+            HelpTopic::Moves => "Siirrot",
+            HelpTopic::Reactions => "Reaktiot",
+            HelpTopic::ProperNouns => "Erisnimet",
+            HelpTopic::Limits => "Rajoitukset",
+        }
+        .to_string(),
+        Text::HelpSection(topic, info) => describe_help(topic, info),
+        Text::HelpFooter(info) => match &info.current_word {
+            Some(word) => format!(
+                "Nykyinen sana: {} · ketjussa {} sanaa",
+                word, info.chain_length
+            ),
+            None => "Ei vielä sanaa, aloita millä tahansa sanalla!".to_string(),
+        },
+        Text::NoGameHere => "Tällä palvelimella ei pelata sanapeliä.".to_string(),
+        Text::NoHistory => "Tässä pelissä ei ole vielä pelattu sanoja.".to_string(),
+        Text::HistoryTitle => "Viimeisimmät sanat".to_string(),
+        Text::NotAMessage => "Anna viestin linkki tai viestin ID.".to_string(),
+        Text::NoAuditTrail => {
+            "Viestistä ei tiedetä mitään, tai sana on jonkun muun.".to_string()
+        }
+        Text::AuditTitle => "Näin sana käsiteltiin".to_string(),
+        Text::AuditStep(step) => describe_step(step),
+        Text::OlderWords { count } => format!("…ja {} vanhempaa sanaa", count),
+        Text::RecentTitle => "Viimeisimmät tulokset".to_string(),
+        Text::RecentSource(source) => match source {
+            Some(VerdictSource::Dictionary { .. }) => "📖 sanakirja".to_string(),
+            Some(VerdictSource::Llm { .. }) => "🤖 tekoäly".to_string(),
+            Some(VerdictSource::Admin { user_id }) => format!("🛡️ ylläpitäjä <@{}>", user_id),
+            Some(VerdictSource::Vote { votes }) => format!("🗳️ {} ääntä", votes),
+            Some(VerdictSource::Seed) => "🌱 botin sana".to_string(),
+            None => "📏 säännöt".to_string(),
+        },
+        Text::RecentPending { waiting } => {
+            format!("{} odottanut {}", EMOJI_HOURGLASS, describe_duration(waiting))
+        }
+        Text::NoScores => "Kukaan ei ole vielä saanut pisteitä.".to_string(),
+        Text::LeaderboardTitle(Period::Weekly) => "Viikon pistetilanne".to_string(),
+        Text::LeaderboardTitle(Period::AllTime) => "Pistetilanne".to_string(),
+        Text::StatsTitle { name } => format!("Tilastot: {}", name),
+        Text::Standing { user_id, standing } => {
+            let Some(rank) = standing.rank else {
+                return format!("<@{}> ei ole vielä saanut pisteitä.", user_id);
+            };
+            let weekly = match standing.weekly_rank {
+                Some(weekly_rank) => format!(
+                    "Tällä viikolla {} pistettä, sijalla {}.",
+                    standing.weekly.points, weekly_rank
+                ),
+                None => "Tällä viikolla ei vielä pisteitä.".to_string(),
+            };
+            format!(
+                "<@{}>: {} pistettä {} sanasta, sijalla {}. {}",
+                user_id, standing.score.points, standing.score.words, rank, weekly
+            )
+        }
+        Text::Attempts(attempts) => {
+            if attempts.total == 0 {
+                return "Ei vielä yhtään ratkaistua yritystä.".to_string();
+            }
+            let line = |label: &str, count: usize| {
+                format!("{}: {} ({} %)", label, count, attempts.percent(count))
+            };
+            [
+                format!("Yrityksiä: {}", attempts.total),
+                line("Sanakirjasta", attempts.dictionary),
+                line("Erisniminä", attempts.proper_nouns),
+                line("Sääntörikkeitä", attempts.rule_violations),
+                line("Jo käytettyjä", attempts.already_used),
+                line("Ei sanoja", attempts.not_words),
+                line("Estettyjä", attempts.blocked),
+            ]
+            .join("\n")
+        }
+        Text::GamePaused => "💤 Peli on tauolla.".to_string(),
+        Text::DictionaryStats(stats) => {
+            let format = match stats.source.format {
+                DictionaryFormat::Text => "tekstitiedosto",
+                DictionaryFormat::KotusXml => "Kotuksen XML-sanalista",
+                DictionaryFormat::Compiled => "käännetty sanakirja",
+                DictionaryFormat::InMemory => "muistissa",
+            };
+            format!(
+                "📖 **{}** sanaa\nTiedosto: `{}` ({})\nLatausaika: {} ms",
+                stats.words,
+                stats.source.path.as_deref().unwrap_or("-"),
+                format,
+                stats.source.load_time.as_millis()
+            )
+        }
+        Text::Lookup(lookup) => match &lookup.found {
+            None => format!("❌ **{}** ei ole sanakirjassa.", lookup.normalized),
+            Some(found) => match &found.rule {
+                None => format!("✅ **{}** on sanakirjassa.", lookup.normalized),
+                Some(rule) => format!(
+                    "✅ **{}** hyväksytään sanan **{}** taivutusmuotona ({}).",
+                    lookup.normalized, found.lemma, rule
+                ),
+            },
+        },
+        Text::DictionaryEmpty => "Sanakirja on tyhjä.".to_string(),
+        Text::RulesSimulation(simulation) => {
+            let mut text = format!(
+                "🧪 **Pelkkä simulaatio**, peliin ei kosketa. {} sanaa toistettiin sääntötilassa {} \
+                 nykyisen ({}) sijaan: ",
+                simulation.records,
+                describe_mode(simulation.proposed_mode),
+                describe_mode(simulation.current_mode)
+            );
+            if simulation.changes.is_empty() {
+                text.push_str("yksikään tulos ei muuttuisi.");
+                return text;
+            }
+            text.push_str(&format!(
+                "{} tulosta muuttuisi.",
+                simulation.changes.len()
+            ));
+            for change in simulation.changes.iter().take(SHOWN_CHANGES) {
+                text.push_str(&format!(
+                    "\n• #{} **{}**: {} → {}",
+                    change.index,
+                    change.input,
+                    describe_status(change.current),
+                    describe_status(change.proposed)
+                ));
+            }
+            if simulation.changes.len() > SHOWN_CHANGES {
+                text.push_str(&format!(
+                    "\n…ja {} muuta.",
+                    simulation.changes.len() - SHOWN_CHANGES
+                ));
+            }
+            text
+        }
+        Text::TranscriptInvalid => {
+            "❌ Tallennetta ei voitu lukea, odotettiin .csv- tai .json-tiedostoa, jossa on \
+             timestamp, user_id ja word."
+                .to_string()
+        }
+        Text::BulkCheck(check) => {
+            let mut text = format!(
+                "📋 Sanakirjassa {} sanaa, puuttuu {}, virheellisiä rivejä {}.",
+                check.found,
+                check.missing.len(),
+                check.malformed
+            );
+            if !check.implausible.is_empty() {
+                text.push_str(&format!(
+                    " {} puuttuvaa ei näytä sanalta.",
+                    check.implausible.len()
+                ));
+            }
+            if check.duplicates > 0 {
+                text.push_str(&format!(" {} sanaa oli listassa useammin kuin kerran.", check.duplicates));
+            }
+            text
+        }
+        Text::BulkCheckReport(check) => {
+            let mut text = render(&Text::BulkCheck(check));
+            for (title, words) in [
+                ("Puuttuvat sanat", &check.missing),
+                ("Eivät näytä sanoilta", &check.implausible),
+            ] {
+                if !words.is_empty() {
+                    text.push_str(&format!("\n\n{}:\n{}", title, words.join("\n")));
+                }
+            }
+            text.push('\n');
+            text
+        }
+        Text::BulkCheckTooLarge { limit_kb } => format!(
+            "❌ Tiedosto on liian suuri, enintään {} kt voidaan tarkistaa.",
+            limit_kb
+        ),
+        Text::BulkCheckFailed => "❌ Tiedoston lataaminen epäonnistui.".to_string(),
+        Text::ChallengePlan(plan) => match plan {
+            ChallengePlan::Ready(path) => format!(
+                "Haaste aloitettu, lyhin reitti on {} siirtoa: {}",
+                path.len() - 1,
+                describe_path(path)
+            ),
+            ChallengePlan::NotInDictionary(word) => {
+                format!("❌ **{}** ei ole sanakirjassa.", word)
+            }
+            ChallengePlan::SameWord => "❌ Lähtö- ja maalisana ovat sama sana.".to_string(),
+            ChallengePlan::NoPath => {
+                "❌ Sanojen välillä ei ole reittiä sanakirjan sanoilla.".to_string()
+            }
+            ChallengePlan::TooFar(words) => format!(
+                "❌ Reittiä ei löytynyt {} sanan tutkimisen jälkeen, valitse lähempänä olevat sanat.",
+                words
+            ),
+        },
+        Text::ChallengeGivenUp { path } => format!(
+            "🏳️ Haaste päättyi. Lyhin reitti ({} siirtoa): {}",
+            path.len().saturating_sub(1),
+            describe_path(path)
+        ),
+        Text::NoChallenge => "Haastetta ei ole käynnissä.".to_string(),
+        Text::Export(export) => {
+            let mut text = format!("📄 {} sanaa viety.", export.rows);
+            if export.truncated > 0 {
+                text.push_str(&format!(
+                    " ⚠️ {} uusinta sanaa jätettiin pois, jotta tiedosto mahtuu Discordin 8 Mt:n rajaan.",
+                    export.truncated
+                ));
+            }
+            text
+        }
+        Text::ExportFailed => "❌ Tiedoston lähettäminen epäonnistui.".to_string(),
+        Text::SetWord(outcome) => match outcome {
+            SetWordOutcome::Set { word, dropped: 0 } => {
+                format!("Nykyinen sana on nyt **{}**.", word)
+            }
+            SetWordOutcome::Set { word, dropped } => format!(
+                "Nykyinen sana on nyt **{}**. {} tarkistusta odottanutta sanaa hylättiin.",
+                word, dropped
+            ),
+            SetWordOutcome::NotInDictionary(word) => format!(
+                "❌ **{}** ei ole sanakirjassa, aseta se `force`-valinnalla.",
+                word
+            ),
+            SetWordOutcome::NotAWord => "❌ Anna yksi sana.".to_string(),
+        },
+        Text::Paused => {
+            "💤 Peli on tauolla. Tauon aikana pelattuja sanoja ei huomioida.".to_string()
+        }
+        Text::AlreadyPaused => "Peli on jo tauolla.".to_string(),
+        Text::Resumed => "▶️ Peli jatkuu!".to_string(),
+        Text::NotPaused => "Peli ei ole tauolla.".to_string(),
+        Text::ChainReset => "🔄 Ketju on nollattu, aloita uusi millä tahansa sanalla!".to_string(),
+        Text::NoTurns => {
+            "Tässä pelissä ei pelata vuorotellen, kuka tahansa saa pelata.".to_string()
+        }
+        Text::Joined { user_id } => format!("✅ <@{}> liittyi vuorojärjestykseen.", user_id),
+        Text::AlreadyJoined => "Olet jo vuorojärjestyksessä.".to_string(),
+        Text::Left { user_id } => format!("👋 <@{}> poistui vuorojärjestyksestä.", user_id),
+        Text::NotJoined => "Et ole vuorojärjestyksessä.".to_string(),
+        Text::TurnOrder([]) => "Kukaan ei ole vielä liittynyt, liity komennolla /join! \
+             Siihen asti kuka tahansa saa pelata."
+            .to_string(),
+        Text::TurnOrder(players) => std::iter::once("Vuorojärjestys:".to_string())
+            .chain(players.iter().enumerate().map(|(index, user_id)| {
+                let on_turn = if index == 0 { " – vuorossa" } else { "" };
+                format!("{}. <@{}>{}", index + 1, user_id, on_turn)
+            }))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Text::BlocklistReloaded { words } => format!("Estolistassa on nyt {} sanaa.", words),
+        Text::LlmStats {
+            stats,
+            queue,
+            validations,
+            reset,
+        } => {
+            let mut lines = vec![format!(
+                "🤖 LLM:n käyttö {} alkaen",
+                stats.since.format("%Y-%m-%d %H:%M UTC")
+            )];
+            if stats.models.is_empty() {
+                lines.push("LLM:ää ei ole kutsuttu.".to_string());
+            }
+            for (model, usage) in &stats.models {
+                let tokens = match usage.calls_without_usage {
+                    0 => format!(
+                        "{} + {} tokenia (kehote + vastaus)",
+                        usage.tokens.prompt, usage.tokens.response
+                    ),
+                    missing if missing == usage.calls => "tokeneita ei ilmoitettu".to_string(),
+                    missing => format!(
+                        "{} + {} tokenia (kehote + vastaus, {} kutsulta ei ilmoitettu)",
+                        usage.tokens.prompt, usage.tokens.response, missing
+                    ),
+                };
+                lines.push(format!(
+                    "**{}**: {} kutsua ({} epäonnistui), {} sanaa, {} ms / kutsu, {}",
+                    model,
+                    usage.calls,
+                    usage.failures,
+                    usage.words,
+                    usage.average_latency().as_millis(),
+                    tokens
+                ));
+            }
+            lines.push(format!(
+                "Välimuisti: {} sanaa välimuistista, {} lähetetty LLM:lle ({} % välimuistista)",
+                stats.cache_hits,
+                stats.escalations(),
+                stats.cache_hit_percent()
+            ));
+            lines.push(match queue.oldest {
+                Some(oldest) => format!(
+                    "Jono: {} sanaa odottaa, vanhin {} s",
+                    queue.queued,
+                    wait_secs(oldest)
+                ),
+                None => "Jono: tyhjä".to_string(),
+            });
+            if let Some(budget) = queue.budget {
+                lines.push(format!(
+                    "Päiväbudjetti: {} / {} sanaa käytetty viimeisen vuorokauden aikana{}",
+                    budget.used,
+                    budget.limit,
+                    if budget.exhausted() {
+                        ", loput sanat eivät mene LLM:lle"
+                    } else {
+                        ""
+                    }
+                ));
+            }
+            lines.push(format!(
+                "Tarkistus: {} sanaa käsittelyssä, {} odottaa vuoroaan",
+                validations.in_flight, validations.queued
+            ));
+            if reset {
+                lines.push("Laskurit nollattiin.".to_string());
+            }
+            lines.join("\n")
+        }
+    }
+}
+
+fn describe_help(topic: HelpTopic, info: &HelpInfo) -> String {
+    let rules = &info.game.rules;
+    match topic {
+        HelpTopic::Moves => match rules.mode {
+            RuleMode::Classic => "Jatka ketjua sanalla, joka syntyy edellisestä vaihtamalla, \
+                 lisäämällä tai poistamalla yksi kirjain:\n\
+                 • kissa → kassa (vaihda)\n\
+                 • kisa → kissa (lisää)\n\
+                 • kassa → kasa (poista)\n\
+                 Samaa sanaa ei voi pelata kahdesti samassa pelissä."
+                .to_string(),
+            RuleMode::Anagram => "Jatka ketjua sanalla, joka syntyy edellisestä vaihtamalla, \
+                 lisäämällä tai poistamalla yksi kirjain, tai järjestämällä sen kirjaimet \
+                 uudelleen:\n\
+                 • kissa → kassa (vaihda)\n\
+                 • kisa → kissa (lisää)\n\
+                 • kassa → kasa (poista)\n\
+                 • sika → kisa (järjestä)\n\
+                 Samaa sanaa ei voi pelata kahdesti samassa pelissä."
+                .to_string(),
+        },
+        HelpTopic::Reactions => {
+            let mut lines = vec![
+                format!("{} sana hyväksyttiin", EMOJI_CHECK),
+                format!(
+                    "{} sana hylättiin: ei sanakirjassa tai rikkoo sääntöjä",
+                    EMOJI_CROSS
+                ),
+                format!(
+                    "{} sana on jo pelattu tässä pelissä",
+                    info.already_used_reaction
+                ),
+            ];
+            if rules.accept_proper_nouns {
+                lines.push(format!(
+                    "{} sanaa tarkistetaan vielä erisnimenä",
+                    EMOJI_QUESTION
+                ));
+            }
+            match rules.pending_policy {
+                PendingPolicy::Strict => lines.push(format!(
+                    "{} edellinen sana odottaa tarkistusta, yritä hetken päästä uudelleen",
+                    EMOJI_HOURGLASS
+                )),
+                PendingPolicy::Queue => lines.push(format!(
+                    "{} sana tarkistetaan, kun edellinen sana on tarkistettu, \
+                     tai jos merkki jää, jonossa on liikaa sanoja ja voit yrittää myöhemmin uudelleen",
+                    EMOJI_HOURGLASS
+                )),
+                PendingPolicy::Optimistic | PendingPolicy::Rollback => {}
+            }
+            if info.daily_limit.is_some() {
+                lines.push(format!(
+                    "{} päivän sanat on jo pelattu",
+                    info.daily_limit_reaction
+                ));
+            }
+            if info.cooldown.is_some() {
+                lines.push(format!(
+                    "{} odota hetki ennen seuraavaa sanaa",
+                    info.cooldown_reaction
+                ));
+            }
+            if let Some(reaction) = info.typo_reaction {
+                lines.push(format!(
+                    "{} sana on luultavasti kirjoitusvirhe, yritä uudelleen",
+                    reaction
+                ));
+            }
+            if info.rotation {
+                lines.push(format!(
+                    "{} ei ole vuorosi, liity komennolla /join ja odota vuoroasi",
+                    EMOJI_NEXT_TRACK
+                ));
+            }
+            lines.push(format!(
+                "{} peli on tauolla, sanaa ei huomioitu",
+                info.pause_reaction
+            ));
+            lines.join("\n")
+        }
+        HelpTopic::ProperNouns if !rules.accept_proper_nouns => {
+            "Vain sanakirjan sanat kelpaavat, erisnimiä ei hyväksytä.".to_string()
+        }
+        HelpTopic::ProperNouns => {
+            let pending = match rules.pending_policy {
+                PendingPolicy::Optimistic => "ketju jatkuu siitä jo sillä välin",
+                PendingPolicy::Strict => "seuraavaa sanaa ei voi pelata ennen tarkistusta",
+                PendingPolicy::Rollback => {
+                    "ketju jatkuu siitä jo sillä välin, mutta jos sana hylätään, \
+                     sen jälkeen pelatut sanat tarkistetaan uudelleen"
+                }
+                PendingPolicy::Queue => {
+                    "seuraavat sanat odottavat ja tarkistetaan järjestyksessä sen jälkeen"
+                }
+            };
+            let capitalization = if rules.require_capitalization {
+                " Erisnimi pitää kirjoittaa isolla alkukirjaimella, \
+                 pienellä kirjoitettu tuntematon sana hylätään heti."
+            } else {
+                ""
+            };
+            format!(
+                "Sanakirjasta puuttuvat sanat tarkistetaan tekoälyllä, ja erisnimet kuten \
+                 Helsinki kelpaavat. Tarkistuksen ajan {}.{}",
+                pending, capitalization
+            )
+        }
+        HelpTopic::Limits => {
+            let mut lines = vec![
+                format!("• Sanan pituus: {}", describe_length(rules)),
+                format!(
+                    "• Sama pelaaja kahdesti peräkkäin: {}",
+                    describe_consecutive(rules)
+                ),
+                match info.daily_limit {
+                    Some(limit) => format!("• Sanoja päivässä: enintään {}", limit),
+                    None => "• Sanoja päivässä: ei rajoitusta".to_string(),
+                },
+                match info.cooldown {
+                    Some(cooldown) => {
+                        format!("• Odotus sanojen välillä: {} s", wait_secs(cooldown))
+                    }
+                    None => "• Odotus sanojen välillä: ei odotusta".to_string(),
+                },
+            ];
+            if info.rotation {
+                lines.push(
+                    "• Vuorot: /join-komennolla liittyneet pelaavat vuorotellen, \
+                     /order näyttää järjestyksen"
+                        .to_string(),
+                );
+            }
+            if let Some(idle_reset) = info.idle_reset {
+                lines.push(format!(
+                    "• Peli nollataan {} hiljaisuuden jälkeen",
+                    describe_period(idle_reset)
+                ));
+            }
+            lines.join("\n")
+        }
+    }
+}
+
+fn describe_mode(mode: RuleMode) -> &'static str {
+    match mode {
+        RuleMode::Classic => "klassinen",
+        RuleMode::Anagram => "anagrammi",
+    }
+}
+
+fn describe_status(status: Status) -> &'static str {
+    match status {
+        Status::Valid => "hyväksytty",
+        Status::Invalid => "hylätty",
+        Status::Pending => "odottaa LLM:ää",
+        Status::Skipped => "ohitettu",
+        Status::Error => "virhe",
+    }
+}
+
+fn describe_length(rules: &GameRules) -> String {
+    match (rules.min_length, rules.max_length) {
+        (None, None) => "ei rajoitusta".to_string(),
+        (Some(min), None) => format!("vähintään {} kirjainta", min),
+        (None, Some(max)) => format!("enintään {} kirjainta", max),
+        (Some(min), Some(max)) => format!("{}–{} kirjainta", min, max),
+    }
+}
+
+fn describe_consecutive(rules: &GameRules) -> &'static str {
+    if rules.allow_consecutive_turns {
+        "sallittu"
+    } else {
+        "ei sallittu"
+    }
+}
+
+fn describe_check(word: &str, verdict: &WordVerdict) -> String {
+    match verdict {
+        WordVerdict::ValidInDictionary | WordVerdict::ValidProperNoun => {
+            format!("✅ **{}** kelpaisi seuraavaksi sanaksi.", word)
+        }
+        WordVerdict::NotInDictionary | WordVerdict::PendingLlm => {
+            format!("❌ **{}** ei kelpaa: ei sanakirjassa.", word)
+        }
+        WordVerdict::InvalidRules(violation) => {
+            format!(
+                "❌ **{}** ei kelpaa: {}",
+                word,
+                describe_violation(violation)
+            )
+        }
+        WordVerdict::AwaitingVerdict => format!(
+            "⏳ **{}** ei kelpaa vielä: edellinen sana odottaa tarkistusta.",
+            word
+        ),
+        WordVerdict::Queued => format!(
+            "⏳ **{}** odottaisi, kunnes edellinen sana on tarkistettu.",
+            word
+        ),
+        WordVerdict::NotAWord => format!("❌ **{}** ei ole yksittäinen sana.", word),
+        WordVerdict::Duplicate => format!("**{}** on jo käsitelty.", word),
+        WordVerdict::DailyLimitReached(limit) => format!(
+            "⛔ Olet pelannut tänään jo {} sanaa, **{}** ei käy ennen huomista.",
+            limit, word
+        ),
+        WordVerdict::CoolingDown(wait) => format!(
+            "⏲️ **{}** ei käy vielä: odota {} s.",
+            word,
+            wait_secs(*wait)
+        ),
+        WordVerdict::NotYourTurn(user_id) => {
+            format!("⏭️ **{}** ei käy nyt: vuorossa on <@{}>.", word, user_id)
+        }
+        WordVerdict::LikelyTypo(meant) => format!(
+            "✏️ **{}** taitaa olla kirjoitusvirhe: tarkoititko **{}**?",
+            word, meant
+        ),
+        WordVerdict::Implausible => format!("❌ **{}** ei kelpaa: ei näytä sanalta.", word),
+        WordVerdict::Blocked => format!("❌ **{}** ei ole sallittu pelissä.", word),
+        WordVerdict::Paused => format!("💤 Peli on tauolla, **{}** jätettäisiin huomiotta.", word),
+        WordVerdict::ForeignLetters(letters) => format!(
+            "❌ **{}**: kirjaimia ei käytetä pelissä: {}",
+            word,
+            super::join_letters(letters, "")
+        ),
+    }
+}
+
+fn describe_step(step: &AuditStep) -> String {
+    match step {
+        AuditStep::Received { user_id } => format!("📨 Vastaanotettu pelaajalta <@{}>", user_id),
+        AuditStep::Normalized(Some(word)) => format!("🔤 Luettu sanaksi **{}**", word),
+        AuditStep::Normalized(None) => "🔤 Viestissä ei ole yksittäistä sanaa".to_string(),
+        AuditStep::Dictionary(true) => "📖 Löytyy sanakirjasta".to_string(),
+        AuditStep::Dictionary(false) => "📖 Ei sanakirjassa".to_string(),
+        AuditStep::Verdict(verdict) => format!("⚖️ {}", describe_verdict(verdict)),
+        AuditStep::SentToLlm { attempt: 1 } => "🤖 Lähetetty LLM:lle".to_string(),
+        AuditStep::SentToLlm { attempt } => {
+            format!("🤖 Lähetetty LLM:lle uudelleen, {}. yritys", attempt)
+        }
+        AuditStep::OverBudget => "💸 Ei lähetetty LLM:lle, päiväbudjetti on käytetty".to_string(),
+        AuditStep::Expired => "⌛ Ei lähetetty LLM:lle, sana odotti liian kauan".to_string(),
+        AuditStep::Settled { validity, source } => {
+            let outcome = match validity {
+                Validity::Valid => "Hyväksytty",
+                Validity::Invalid => "Hylätty",
+                Validity::Pending => "Jätetty odottamaan",
+            };
+            let by = match source {
+                VerdictSource::Dictionary { .. } => "sanakirjan perusteella".to_string(),
+                VerdictSource::Llm {
+                    explanation,
+                    model: Some(model),
+                } => format!("LLM:n ({}) perusteella: {}", model, explanation),
+                VerdictSource::Llm {
+                    explanation,
+                    model: None,
+                } => format!("ilman LLM:ää: {}", explanation),
+                VerdictSource::Admin { user_id } => format!("ylläpitäjän <@{}> toimesta", user_id),
+                VerdictSource::Vote { votes } => format!("pelaajien {} äänellä", votes),
+                VerdictSource::Seed => "botin omana sanana".to_string(),
+            };
+            format!("{} {} {}", verdict_reaction(*validity), outcome, by)
+        }
+        AuditStep::ReactionAdded { reaction, ok: true } => format!("Lisätty {}", reaction),
+        AuditStep::ReactionAdded {
+            reaction,
+            ok: false,
+        } => {
+            format!("⚠️ Reaktion {} lisääminen epäonnistui", reaction)
+        }
+        AuditStep::ReactionRemoved { reaction, ok: true } => format!("Poistettu {}", reaction),
+        AuditStep::ReactionRemoved {
+            reaction,
+            ok: false,
+        } => {
+            format!("⚠️ Reaktion {} poistaminen epäonnistui", reaction)
+        }
+        AuditStep::ReactionsCleared { ok: true } => "Reaktiot tyhjennetty".to_string(),
+        AuditStep::ReactionsCleared { ok: false } => {
+            "⚠️ Reaktioiden tyhjentäminen epäonnistui".to_string()
+        }
+    }
+}
+
+/// What the game made of a word, for /why
+fn describe_verdict(verdict: &WordVerdict) -> String {
+    match verdict {
+        WordVerdict::ValidInDictionary => "Kelpaava sanakirjan sana".to_string(),
+        WordVerdict::PendingLlm => "Ei sanakirjassa, odottaa LLM:ää".to_string(),
+        WordVerdict::ValidProperNoun => "Hyväksytty erisnimenä".to_string(),
+        WordVerdict::InvalidRules(violation) => {
+            format!("Rikkoo sääntöjä: {}", describe_violation(violation))
+        }
+        WordVerdict::AwaitingVerdict => {
+            "Pelattu edellisen sanan odottaessa tarkistusta".to_string()
+        }
+        WordVerdict::Queued => "Odottaa, kunnes edellinen sana on tarkistettu".to_string(),
+        WordVerdict::NotInDictionary => "Ei sanakirjassa".to_string(),
+        WordVerdict::NotAWord => "Ei yksittäinen sana".to_string(),
+        WordVerdict::Duplicate => "Jo käsitelty".to_string(),
+        WordVerdict::DailyLimitReached(limit) => {
+            format!("Päivän {} sanan raja on täynnä", limit)
+        }
+        WordVerdict::CoolingDown(wait) => {
+            format!("Pelattu {} s ennen odotusajan loppua", wait_secs(*wait))
+        }
+        WordVerdict::NotYourTurn(user_id) => format!("Pelattu pelaajan <@{}> vuorolla", user_id),
+        WordVerdict::LikelyTypo(meant) => {
+            format!("Luultavasti kirjoitusvirhe sanasta {}, ei pelattu", meant)
+        }
+        WordVerdict::Implausible => "Ei näytä sanalta".to_string(),
+        WordVerdict::Blocked => "Estettyjen sanojen listalla".to_string(),
+        WordVerdict::ForeignLetters(letters) => format!(
+            "Kirjaimia ei käytetä pelissä: {}",
+            super::join_letters(letters, "")
+        ),
+        WordVerdict::Paused => "Pelattu pelin ollessa tauolla".to_string(),
+    }
+}
+
+fn describe_violation(violation: &Violation) -> String {
+    match violation {
+        Violation::TooShort { min } => format!("sana on liian lyhyt (vähintään {} kirjainta)", min),
+        Violation::TooLong { max } => format!("sana on liian pitkä (enintään {} kirjainta)", max),
+        Violation::Letters(difference) => match difference.changes.as_slice() {
+            [] => "sana on sama kuin edellinen".to_string(),
+            [change] => format!("muutit 1 kirjaimen: {}", change),
+            changes => format!(
+                "muutit {} kirjainta: {}",
+                changes.len(),
+                super::join_changes(changes)
+            ),
+        },
+        Violation::DiacriticOnly(change) => {
+            format!("muutit vain {}, mikä ei tässä pelissä ole siirto", change)
+        }
+        Violation::ConsecutiveTurns => {
+            "sama pelaaja ei voi pelata kahta sanaa peräkkäin".to_string()
+        }
+        Violation::AlreadyUsed(_) => "sana on jo pelattu tässä pelissä".to_string(),
+        Violation::Other(reason) => reason.clone(),
+    }
+}
+
+/// The summary of a broken chain, one fact per line
+fn describe_chain(summary: &ChainSummary) -> String {
+    let mut lines = vec![format!(
+        "<@{}> katkaisi ketjun sanalla **{}**.",
+        summary.broken_by, summary.breaking_word
+    )];
+    if let Some((user_id, words)) = summary.top_contributor {
+        lines.push(format!("Eniten sanoja: <@{}> ({})", user_id, words));
+    }
+    if let Some(word) = &summary.longest_word {
+        lines.push(format!("Pisin sana: **{}**", word));
+    }
+    lines.push(format!("Kesto: {}", describe_duration(summary.duration)));
+    lines.push(if summary.restart {
+        "Uusi ketju alkaa, aloita millä tahansa sanalla!".to_string()
+    } else {
+        "Peli on tauolla, kunnes ketju nollataan komennolla /reset.".to_string()
+    });
+    lines.join("\n")
+}
+
+/// How long something lasted in hours and minutes, e.g. "2 h 5 min"
+fn describe_duration(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    match (minutes / 60, minutes % 60) {
+        (0, 0) => "alle minuutin".to_string(),
+        (0, minutes) => format!("{} min", minutes),
+        (hours, 0) => format!("{} h", hours),
+        (hours, minutes) => format!("{} h {} min", hours, minutes),
+    }
+}
+
+/// An idle period in the genitive, e.g. "48 tunnin"
+fn describe_period(period: Duration) -> String {
+    let secs = period.as_secs();
+    if secs >= 3600 && secs.is_multiple_of(3600) {
+        format!("{} tunnin", secs / 3600)
+    } else if secs >= 60 && secs.is_multiple_of(60) {
+        format!("{} minuutin", secs / 60)
+    } else {
+        format!("{} sekunnin", secs)
+    }
+}
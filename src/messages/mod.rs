@@ -0,0 +1,1013 @@
+//! Everything the bot says to players, in the configured language
+//!
+//! A message is a [`Text`] carrying the values it's filled in with, and each
+//! language has its own module rendering every one of them, plus the help
+//! texts of the slash commands. Adding a language takes a new module and a
+//! [`Language`] variant; the compiler points out anything left untranslated.
+
+mod en;
+mod fi;
+
+use std::time::Duration;
+
+use crate::actors::game_state::{ChallengePlan, DictionaryStats, SetWordOutcome, WordLookup};
+use crate::actors::llm_validator::QueueStatus;
+use crate::actors::word_validator::ValidationLoad;
+use crate::audit::AuditStep;
+use crate::bulkcheck::BulkCheck;
+use crate::chain::ChainSummary;
+use crate::config::Config;
+use crate::export::Export;
+use crate::game::{RulesInfo, VerdictSource, WordVerdict};
+use crate::replay::RulesSimulation;
+use crate::rotation::TurnMode;
+use crate::scoring::{Attempts, Period, PlayerScore, Standing};
+use crate::validation::llm::LLMStats;
+use crate::validation::rules::{LetterChange, Violation};
+
+pub use crate::config::Language;
+
+/// What /help explains, taken from the configuration and the running game
+#[derive(Debug, Clone)]
+pub struct HelpInfo {
+    pub game: RulesInfo,
+    /// Accepted words per player a day, None if unlimited
+    pub daily_limit: Option<usize>,
+    /// Reaction to words over the daily limit
+    pub daily_limit_reaction: char,
+    /// Wait after an accepted word, None for no cooldown
+    pub cooldown: Option<Duration>,
+    /// Reaction to words played before the cooldown passed
+    pub cooldown_reaction: char,
+    /// Silence after which the game resets, None if it never does
+    pub idle_reset: Option<Duration>,
+    /// Reaction to words played while the game is paused
+    pub pause_reaction: char,
+    /// Reaction to words already played in the game
+    pub already_used_reaction: char,
+    /// Whether players who joined take turns
+    pub rotation: bool,
+    /// Reaction to words taken for typos, None if typos aren't caught
+    pub typo_reaction: Option<char>,
+}
+
+impl HelpInfo {
+    pub fn new(game: RulesInfo, config: &Config) -> Self {
+        let duration = |secs| (secs > 0).then(|| Duration::from_secs(secs));
+        Self {
+            game,
+            daily_limit: (config.max_words_per_user_per_day > 0)
+                .then_some(config.max_words_per_user_per_day),
+            daily_limit_reaction: config.daily_limit_reaction,
+            cooldown: duration(config.user_cooldown_secs),
+            cooldown_reaction: config.cooldown_reaction,
+            idle_reset: duration(config.game_idle_reset_secs),
+            pause_reaction: config.pause_reaction,
+            already_used_reaction: config.already_used_reaction,
+            rotation: config.game_turn_mode == TurnMode::Rotation,
+            typo_reaction: config.typo_check.then_some(config.typo_reaction),
+        }
+    }
+}
+
+/// The sections of /help
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelpTopic {
+    /// Which words continue the chain, with examples
+    Moves,
+    /// What each reaction on a played word means
+    Reactions,
+    /// How words missing from the dictionary are handled
+    ProperNouns,
+    /// The configured limits
+    Limits,
+}
+
+impl HelpTopic {
+    /// Every section in the order they're shown
+    pub const ALL: [HelpTopic; 4] = [
+        HelpTopic::Moves,
+        HelpTopic::Reactions,
+        HelpTopic::ProperNouns,
+        HelpTopic::Limits,
+    ];
+}
+
+/// A message to players, with the values it's filled in with
+#[derive(Debug, Clone, Copy)]
+pub enum Text<'a> {
+    /// Reply to a word with letters outside the allowed alphabet
+    ForeignLetters(&'a [char]),
+    /// Reply to a word over the daily limit, starting with its reaction
+    DailyLimit { limit: usize, reaction: char },
+    /// Reply to a word played before the cooldown passed, starting with its reaction
+    Cooldown { wait: Duration, reaction: char },
+    /// Reply to a word played out of turn, naming the player on turn
+    NotYourTurn { user_id: u64 },
+    /// Reply suggesting dictionary words close to a rejected one
+    Suggestions(&'a [String]),
+    /// Why a word breaks the rules
+    Violation(&'a Violation),
+
+    /// Announcement of a reset after the game was idle for `period`
+    IdleReset { period: Duration },
+    /// Announcement of the state of the game when the bot starts
+    ReadyAnnouncement {
+        current_word: Option<&'a str>,
+        chain_length: usize,
+        /// Players whose scores came from the saved game, None if it wasn't restored
+        restored_players: Option<usize>,
+    },
+    /// Announcement of a turn passed on because the player on turn didn't play in time
+    TurnSkipped { skipped: u64, next: u64 },
+    /// Warning that no dictionary word continues from the word
+    DeadEnd { word: &'a str },
+    /// Announcement of a challenge along the shortest path
+    ChallengeStarted { path: &'a [String] },
+    /// Announcement of a challenge won in `moves` moves
+    ChallengeWon {
+        user_id: u64,
+        moves: usize,
+        path: &'a [String],
+    },
+    /// Announcement of the chain reaching a milestone length with `word`
+    Milestone {
+        chain_length: usize,
+        user_id: u64,
+        word: &'a str,
+    },
+    /// Title of the summary of a broken chain
+    ChainBroken(&'a ChainSummary),
+    /// Summary of a broken chain, and what happens next
+    ChainSummary(&'a ChainSummary),
+    /// Announcement of a word set with /setword
+    WordSet { word: &'a str },
+    /// Title of the final standings of a week
+    WeekEnded { week: u32 },
+    /// One line per player, best first
+    Leaderboard(&'a [(u64, PlayerScore)]),
+    /// The bot's presence while a game is going on
+    Presence { word: &'a str, chain_length: usize },
+    /// The pinned status message, before any word or after a reset if `word` is None
+    Status {
+        word: Option<&'a str>,
+        chain_length: usize,
+        /// Who played the word, None if nobody did
+        user_id: Option<u64>,
+    },
+    /// The pinned status message once the bot has shut down
+    StatusOffline,
+
+    /// Answer to /check
+    Check {
+        word: &'a str,
+        verdict: &'a WordVerdict,
+    },
+    /// Answer to /rules
+    Rules(&'a RulesInfo),
+    /// Title of /help
+    HelpTitle,
+    /// Heading of a /help section
+    HelpHeading(HelpTopic),
+    /// A /help section
+    HelpSection(HelpTopic, &'a HelpInfo),
+    /// Footer of /help with the current word and chain length
+    HelpFooter(&'a RulesInfo),
+    /// Answer to a game command in a server without a game
+    NoGameHere,
+    /// Answer to /history before any words
+    NoHistory,
+    /// Title of /history
+    HistoryTitle,
+    /// Footer of /history and /recent when older words didn't fit
+    OlderWords { count: usize },
+    /// Title of /recent
+    RecentTitle,
+    /// What settled a word in /recent, None if it broke the rules
+    RecentSource(Option<&'a VerdictSource>),
+    /// A word in /recent still waiting for its verdict, and for how long
+    RecentPending { waiting: Duration },
+    /// Answer to /why with neither a message link nor a message ID
+    NotAMessage,
+    /// Answer to /why about a message that isn't remembered, or someone else's word
+    NoAuditTrail,
+    /// Title of /why
+    AuditTitle,
+    /// A step in handling a word, in /why
+    AuditStep(&'a AuditStep),
+    /// Answer to /leaderboard before any points
+    NoScores,
+    /// Title of /leaderboard
+    LeaderboardTitle(Period),
+    /// Title of /stats
+    StatsTitle { name: &'a str },
+    /// A player's points and places in /stats
+    Standing {
+        user_id: u64,
+        standing: &'a Standing,
+    },
+    /// Breakdown of a player's attempts in /stats
+    Attempts(&'a Attempts),
+    /// Note in /stats while the game is paused
+    GamePaused,
+    /// Answer to /dict stats
+    DictionaryStats(&'a DictionaryStats),
+    /// Answer to /dict has
+    Lookup(&'a WordLookup),
+    /// Answer to /dict random with an empty dictionary
+    DictionaryEmpty,
+    /// Summary of /dict bulkcheck
+    BulkCheck(&'a BulkCheck),
+    /// The file /dict bulkcheck answers with: the summary and the words missing from the dictionary
+    BulkCheckReport(&'a BulkCheck),
+    /// Answer to /dict bulkcheck when the list is larger than allowed
+    BulkCheckTooLarge { limit_kb: usize },
+    /// Answer to /dict bulkcheck when the list couldn't be downloaded
+    BulkCheckFailed,
+    /// Answer to /simulate-rules, labelled as a simulation
+    RulesSimulation(&'a RulesSimulation),
+    /// Answer to /simulate-rules when the transcript couldn't be read
+    TranscriptInvalid,
+    /// Answer to /challenge start
+    ChallengePlan(&'a ChallengePlan),
+    /// Answer to /challenge giveup, with the shortest path
+    ChallengeGivenUp { path: &'a [String] },
+    /// Answer to /challenge giveup without a challenge
+    NoChallenge,
+    /// Summary of /export
+    Export(&'a Export),
+    /// Answer to /export when the file couldn't be posted
+    ExportFailed,
+    /// Answer to /setword
+    SetWord(&'a SetWordOutcome),
+    /// Answer to /pause
+    Paused,
+    /// Answer to /pause while already paused
+    AlreadyPaused,
+    /// Answer to /resume
+    Resumed,
+    /// Answer to /resume while not paused
+    NotPaused,
+    /// Answer to /reset
+    ChainReset,
+    /// Answer to /join, /leave or /order when anyone may play at any time
+    NoTurns,
+    /// Answer to /join
+    Joined { user_id: u64 },
+    /// Answer to /join from a player who had already joined
+    AlreadyJoined,
+    /// Answer to /leave
+    Left { user_id: u64 },
+    /// Answer to /leave from a player who hadn't joined
+    NotJoined,
+    /// Answer to /order, the player on turn first
+    TurnOrder(&'a [u64]),
+    /// Answer to /reload
+    BlocklistReloaded { words: usize },
+    /// Answer to /llmstats, with the words waiting for the LLM, the words being
+    /// validated and whether the counters were reset after it
+    LlmStats {
+        stats: &'a LLMStats,
+        queue: QueueStatus,
+        validations: ValidationLoad,
+        reset: bool,
+    },
+}
+
+impl Text<'_> {
+    /// The message in `language`
+    pub fn render(&self, language: Language) -> String {
+        match language {
+            Language::Finnish => fi::render(self),
+            Language::English => en::render(self),
+        }
+    }
+}
+
+/// Help text of a slash command, or of one of its parameters
+///
+/// Commands are named as they're invoked, e.g. `dict has`, and parameters
+/// follow their command after a dot, e.g. `dict has.word`.
+pub fn help(language: Language, key: &str) -> Option<&'static str> {
+    let texts = match language {
+        Language::Finnish => fi::HELP,
+        Language::English => en::HELP,
+    };
+    texts
+        .iter()
+        .find(|(name, _)| *name == key)
+        .map(|(_, text)| *text)
+}
+
+/// Letter changes separated by commas, e.g. "i→o, s→i"
+fn join_changes(changes: &[LetterChange]) -> String {
+    changes
+        .iter()
+        .map(|change| change.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn join_letters(letters: &[char], separator: &str) -> String {
+    letters
+        .iter()
+        .map(char::to_string)
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::budget::BudgetStatus;
+    use crate::replay::{ChangedVerdict, SHOWN_CHANGES};
+    use crate::simulate::Status;
+    use crate::validation::llm::{ModelUsage, TokenUsage};
+    use crate::validation::rules::{analyze_difference, DiacriticPolicy, GameRules, RuleMode};
+
+    fn both(text: Text) -> (String, String) {
+        (
+            text.render(Language::Finnish),
+            text.render(Language::English),
+        )
+    }
+
+    #[test]
+    fn test_replies_interpolated() {
+        assert_eq!(
+            both(Text::DailyLimit {
+                limit: 5,
+                reaction: '⛔'
+            }),
+            (
+                "⛔ Olet pelannut tänään jo 5 sanaa, jatka huomenna!".to_string(),
+                "⛔ You've already played 5 words today, continue tomorrow!".to_string()
+            )
+        );
+        assert_eq!(
+            both(Text::Cooldown {
+                wait: Duration::from_millis(1500),
+                reaction: '⏲'
+            }),
+            (
+                "⏲ Odota vielä 2 s ennen seuraavaa sanaa.".to_string(),
+                "⏲ Wait another 2 seconds before your next word.".to_string()
+            )
+        );
+        assert_eq!(
+            both(Text::TurnOrder(&[2, 1])),
+            (
+                "Vuorojärjestys:\n1. <@2> – vuorossa\n2. <@1>".to_string(),
+                "Turn order:\n1. <@2> – on turn\n2. <@1>".to_string()
+            )
+        );
+        let suggestions = ["kissa".to_string(), "kassa".to_string()];
+        assert_eq!(
+            both(Text::Suggestions(&suggestions)),
+            (
+                "Tarkoititko: kissa, kassa?".to_string(),
+                "Did you mean: kissa, kassa?".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_announcements_interpolated() {
+        let path = [
+            "kissa".to_string(),
+            "kassa".to_string(),
+            "kassi".to_string(),
+        ];
+        assert_eq!(
+            both(Text::ChallengeWon {
+                user_id: 42,
+                moves: 3,
+                path: &path
+            }),
+            (
+                "🏁 <@42> pääsi sanaan **kassi**! Ketjussa oli 3 siirtoa, lyhin reitti on 2 siirtoa: kissa → kassa → kassi".to_string(),
+                "🏁 <@42> reached **kassi**! The chain took 3 moves, the shortest takes 2: kissa → kassa → kassi".to_string()
+            )
+        );
+        assert_eq!(
+            both(Text::IdleReset {
+                period: Duration::from_secs(3600)
+            }),
+            (
+                "Peli nollattu 1 tunnin hiljaisuuden jälkeen, aloita uusi sana!".to_string(),
+                "The game was reset after 1 hour of silence, start a new word!".to_string()
+            )
+        );
+        assert_eq!(
+            both(Text::Milestone {
+                chain_length: 100,
+                user_id: 42,
+                word: "kassi"
+            }),
+            (
+                "🎉 Ketjussa on nyt 100 sanaa! <@42> pelasi sanan **kassi**.".to_string(),
+                "🎉 The chain has reached 100 words! <@42> played **kassi**.".to_string()
+            )
+        );
+        let mut summary = ChainSummary {
+            length: 12,
+            top_contributor: Some((42, 1)),
+            longest_word: Some("kasvit".to_string()),
+            duration: Duration::from_secs(2 * 3600 + 5 * 60),
+            breaking_word: "kasvix".to_string(),
+            broken_by: 7,
+            restart: false,
+        };
+        assert_eq!(
+            both(Text::ChainBroken(&summary)).1,
+            "💥 The chain broke after 12 words!"
+        );
+        assert_eq!(
+            both(Text::ChainSummary(&summary)),
+            (
+                "<@7> katkaisi ketjun sanalla **kasvix**.\n\
+                 Eniten sanoja: <@42> (1)\n\
+                 Pisin sana: **kasvit**\n\
+                 Kesto: 2 h 5 min\n\
+                 Peli on tauolla, kunnes ketju nollataan komennolla /reset."
+                    .to_string(),
+                "<@7> broke the chain with **kasvix**.\n\
+                 Most words: <@42> (1 word)\n\
+                 Longest word: **kasvit**\n\
+                 Lasted: 2 hours 5 minutes\n\
+                 The game is paused until the chain is reset with /reset."
+                    .to_string()
+            )
+        );
+        summary.duration = Duration::from_secs(60);
+        assert!(both(Text::ChainSummary(&summary))
+            .1
+            .contains("Lasted: 1 minute\n"));
+        let (fi, en) = both(Text::IdleReset {
+            period: Duration::from_secs(90),
+        });
+        assert!(fi.contains("90 sekunnin"));
+        assert!(en.contains("90 seconds"));
+        let (fi, en) = both(Text::IdleReset {
+            period: Duration::from_secs(48 * 3600),
+        });
+        assert!(fi.contains("48 tunnin"));
+        assert!(en.contains("48 hours"));
+        assert_eq!(
+            both(Text::ReadyAnnouncement {
+                current_word: None,
+                chain_length: 0,
+                restored_players: None,
+            })
+            .0,
+            "Uusi peli! Aloita millä tahansa sanalla."
+        );
+        assert_eq!(
+            both(Text::ReadyAnnouncement {
+                current_word: Some("kissa"),
+                chain_length: 1,
+                restored_players: None,
+            }),
+            (
+                "Sanapeli jatkuu! Nykyinen sana on **kissa**, ketjun pituus on 1.".to_string(),
+                "The game goes on! The current word is **kissa** and the chain has 1 word."
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            both(Text::ReadyAnnouncement {
+                current_word: Some("kissa"),
+                chain_length: 1,
+                restored_players: Some(3),
+            }),
+            (
+                "Sanapeli jatkuu! Nykyinen sana on **kissa**, ketjun pituus on 1. \
+                 Peli ja 3 pelaajan pisteet palautettiin tallennuksesta."
+                    .to_string(),
+                "The game goes on! The current word is **kissa** and the chain has 1 word. \
+                 The game and the scores of 3 players were restored from the save."
+                    .to_string()
+            )
+        );
+
+        let leaders = [(
+            1,
+            PlayerScore {
+                points: 42,
+                words: 7,
+            },
+        )];
+        assert_eq!(
+            both(Text::Leaderboard(&leaders)),
+            (
+                "1. <@1> – 42 pistettä (7 sanaa)\n".to_string(),
+                "1. <@1> – 42 points (7 words)\n".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_violations() {
+        let difference = analyze_difference("kissa", "koira");
+        let violation = Violation::Letters(difference);
+        assert_eq!(
+            both(Text::Violation(&violation)),
+            (
+                "muutit 3 kirjainta: i→o, s→i, s→r".to_string(),
+                "you changed 3 letters: i→o, s→i, s→r".to_string()
+            )
+        );
+        // The default language is used outside of replies
+        assert_eq!(violation.to_string(), "muutit 3 kirjainta: i→o, s→i, s→r");
+
+        let used = Violation::AlreadyUsed("kassa".to_string());
+        let verdict = WordVerdict::InvalidRules(used);
+        assert_eq!(
+            both(Text::Check {
+                word: " kassa ",
+                verdict: &verdict
+            }),
+            (
+                "❌ **kassa** ei kelpaa: sana on jo pelattu tässä pelissä".to_string(),
+                "❌ **kassa** isn't accepted: the word has already been played this game"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_audit_steps() {
+        use crate::game::{Validity, VerdictSource};
+
+        assert_eq!(
+            both(Text::AuditStep(&AuditStep::Verdict(
+                WordVerdict::InvalidRules(Violation::ConsecutiveTurns)
+            ))),
+            (
+                "⚖️ Rikkoo sääntöjä: sama pelaaja ei voi pelata kahta sanaa peräkkäin".to_string(),
+                "⚖️ Breaks the rules: the same player can't play two words in a row".to_string()
+            )
+        );
+        assert_eq!(
+            both(Text::AuditStep(&AuditStep::Settled {
+                validity: Validity::Invalid,
+                source: VerdictSource::Llm {
+                    explanation: "Ei nimi".to_string(),
+                    model: Some("gemini-pro".to_string()),
+                },
+            })),
+            (
+                "❌ Hylätty LLM:n (gemini-pro) perusteella: Ei nimi".to_string(),
+                "❌ Rejected by the LLM (gemini-pro): Ei nimi".to_string()
+            )
+        );
+        assert_eq!(
+            both(Text::AuditStep(&AuditStep::SentToLlm { attempt: 2 })),
+            (
+                "🤖 Lähetetty LLM:lle uudelleen, 2. yritys".to_string(),
+                "🤖 Sent to the LLM again, attempt 2".to_string()
+            )
+        );
+        assert_eq!(
+            both(Text::AuditStep(&AuditStep::ReactionAdded {
+                reaction: '✅',
+                ok: false
+            })),
+            (
+                "⚠️ Reaktion ✅ lisääminen epäonnistui".to_string(),
+                "⚠️ Failed to add ✅".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_rules() {
+        let info = RulesInfo {
+            rules: GameRules {
+                min_length: Some(3),
+                allow_consecutive_turns: false,
+                ..GameRules::default()
+            },
+            current_word: Some("kissa".to_string()),
+            chain_length: 1,
+        };
+
+        let finnish = Text::Rules(&info).render(Language::Finnish);
+        assert!(finnish.contains("vähintään 3 kirjainta"));
+        assert!(finnish.contains("Sama pelaaja kahdesti peräkkäin: ei sallittu"));
+        assert!(finnish.contains("Nykyinen sana: **kissa**"));
+
+        let info = RulesInfo {
+            current_word: None,
+            ..info
+        };
+        let english = Text::Rules(&info).render(Language::English);
+        assert!(english.contains("Word length: at least 3 letters"));
+        assert!(english.contains("Proper nouns: accepted"));
+        assert!(english.contains("Current word: no word yet"));
+        assert!(english.contains("a↔ä, a↔å and o↔ö changes: count as a changed letter"));
+
+        let info = RulesInfo {
+            rules: GameRules {
+                diacritic_policy: DiacriticPolicy::Free,
+                ..GameRules::default()
+            },
+            ..info
+        };
+        let finnish = Text::Rules(&info).render(Language::Finnish);
+        assert!(finnish.contains("Muutokset a↔ä, a↔å ja o↔ö: eivät kuluta siirtoa"));
+
+        let violation = Violation::DiacriticOnly(LetterChange::Substituted {
+            position: 1,
+            from: 'a',
+            to: 'ä',
+        });
+        assert_eq!(
+            both(Text::Violation(&violation)),
+            (
+                "muutit vain a→ä, mikä ei tässä pelissä ole siirto".to_string(),
+                "you only changed a→ä, which isn't a move of its own in this game".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_standing() {
+        let standing = Standing {
+            score: PlayerScore {
+                points: 42,
+                words: 7,
+            },
+            rank: Some(1),
+            weekly: PlayerScore {
+                points: 5,
+                words: 1,
+            },
+            weekly_rank: Some(2),
+        };
+        assert_eq!(
+            both(Text::Standing {
+                user_id: 1,
+                standing: &standing
+            }),
+            (
+                "<@1>: 42 pistettä 7 sanasta, sijalla 1. Tällä viikolla 5 pistettä, sijalla 2."
+                    .to_string(),
+                "<@1>: 42 points from 7 words, ranked #1. This week 5 points, ranked #2."
+                    .to_string()
+            )
+        );
+        let standing = Standing {
+            weekly: PlayerScore::default(),
+            weekly_rank: None,
+            ..standing
+        };
+        assert_eq!(
+            Text::Standing {
+                user_id: 1,
+                standing: &standing
+            }
+            .render(Language::Finnish),
+            "<@1>: 42 pistettä 7 sanasta, sijalla 1. Tällä viikolla ei vielä pisteitä."
+        );
+        assert_eq!(
+            both(Text::Standing {
+                user_id: 3,
+                standing: &Standing::default()
+            }),
+            (
+                "<@3> ei ole vielä saanut pisteitä.".to_string(),
+                "<@3> hasn't scored any points yet.".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_dictionary() {
+        use crate::validation::dictionary::{DictionaryFormat, DictionaryMatch, DictionarySource};
+        use crate::validation::inflection::InflectionRule;
+
+        let stats = DictionaryStats {
+            words: 94110,
+            source: DictionarySource {
+                name: "finnish_words.bin".into(),
+                path: Some("./data/finnish_words.bin".to_string()),
+                format: DictionaryFormat::Compiled,
+                load_time: Duration::from_millis(42),
+            },
+        };
+        let (fi, en) = both(Text::DictionaryStats(&stats));
+        assert!(fi.contains("**94110** sanaa"));
+        assert!(fi.contains("`./data/finnish_words.bin` (käännetty sanakirja)"));
+        assert!(fi.contains("42 ms"));
+        assert!(en.contains("**94110** words"));
+        assert!(en.contains("`./data/finnish_words.bin` (compiled dictionary)"));
+
+        let mut lookup = WordLookup {
+            normalized: "kissalla".to_string(),
+            found: None,
+        };
+        assert_eq!(
+            Text::Lookup(&lookup).render(Language::Finnish),
+            "❌ **kissalla** ei ole sanakirjassa."
+        );
+        lookup.found = Some(DictionaryMatch {
+            lemma: "kissa".to_string(),
+            rule: Some("lla".parse::<InflectionRule>().unwrap()),
+            source: stats.source.clone().into(),
+        });
+        assert_eq!(
+            both(Text::Lookup(&lookup)),
+            (
+                "✅ **kissalla** hyväksytään sanan **kissa** taivutusmuotona (-lla).".to_string(),
+                "✅ **kissalla** is accepted as an inflected form of **kissa** (-lla).".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_command_answers() {
+        let path = [
+            "kissa".to_string(),
+            "kassa".to_string(),
+            "kassi".to_string(),
+        ];
+        let plan = ChallengePlan::Ready(path.to_vec());
+        assert_eq!(
+            both(Text::ChallengePlan(&plan)),
+            (
+                "Haaste aloitettu, lyhin reitti on 2 siirtoa: kissa → kassa → kassi".to_string(),
+                "Challenge started, the shortest chain takes 2 moves: kissa → kassa → kassi"
+                    .to_string()
+            )
+        );
+        let (fi, en) = both(Text::ChallengePlan(&ChallengePlan::TooFar(200_000)));
+        assert!(fi.contains("200000 sanan"));
+        assert!(en.contains("200000 words"));
+
+        assert_eq!(
+            Text::Attempts(&Attempts::default()).render(Language::Finnish),
+            "Ei vielä yhtään ratkaistua yritystä."
+        );
+        let attempts = Attempts {
+            total: 4,
+            dictionary: 2,
+            proper_nouns: 1,
+            already_used: 1,
+            ..Attempts::default()
+        };
+        let (fi, en) = both(Text::Attempts(&attempts));
+        assert!(fi.starts_with("Yrityksiä: 4\n"));
+        assert!(fi.contains("Sanakirjasta: 2 (50 %)"));
+        assert!(fi.contains("Jo käytettyjä: 1 (25 %)"));
+        assert!(fi.contains("Estettyjä: 0 (0 %)"));
+        assert!(en.contains("Already used: 1 (25 %)"));
+
+        let mut export = Export {
+            data: Vec::new(),
+            rows: 100,
+            truncated: 0,
+        };
+        assert_eq!(
+            both(Text::Export(&export)),
+            (
+                "📄 100 sanaa viety.".to_string(),
+                "📄 Exported 100 words.".to_string()
+            )
+        );
+        export.truncated = 1;
+        let (fi, en) = both(Text::Export(&export));
+        assert!(fi.contains("1 uusinta sanaa jätettiin pois"));
+        assert!(en.contains("The newest 1 word had to be left out"));
+
+        let mut check = BulkCheck {
+            found: 10,
+            missing: vec!["pori".to_string(), "häkki".to_string()],
+            implausible: Vec::new(),
+            malformed: 1,
+            duplicates: 0,
+        };
+        assert_eq!(
+            both(Text::BulkCheck(&check)),
+            (
+                "📋 Sanakirjassa 10 sanaa, puuttuu 2, virheellisiä rivejä 1.".to_string(),
+                "📋 10 words in the dictionary, 2 missing, 1 malformed line.".to_string()
+            )
+        );
+        check.implausible.push("asdfgh".to_string());
+        let (fi, en) = both(Text::BulkCheckReport(&check));
+        assert!(fi.ends_with(
+            "1 puuttuvaa ei näytä sanalta.\n\nPuuttuvat sanat:\npori\nhäkki\n\nEivät näytä sanoilta:\nasdfgh\n"
+        ));
+        assert!(en.contains("\n\nMissing words:\npori\nhäkki\n"));
+
+        let mut simulation = RulesSimulation {
+            current_mode: RuleMode::Classic,
+            proposed_mode: RuleMode::Anagram,
+            records: 3,
+            changes: Vec::new(),
+        };
+        let (fi, en) = both(Text::RulesSimulation(&simulation));
+        assert!(fi.starts_with("🧪 **Pelkkä simulaatio**"));
+        assert!(fi.ends_with("yksikään tulos ei muuttuisi."));
+        assert!(
+            en.ends_with("under the anagram rules instead of classic: no verdict would change.")
+        );
+        simulation.changes = (1..=SHOWN_CHANGES + 2)
+            .map(|index| ChangedVerdict {
+                index,
+                input: "laka".to_string(),
+                current: Status::Invalid,
+                proposed: Status::Valid,
+                reason: "in dictionary".to_string(),
+            })
+            .collect();
+        let (fi, en) = both(Text::RulesSimulation(&simulation));
+        assert!(fi.contains("12 tulosta muuttuisi.\n• #1 **laka**: hylätty → hyväksytty"));
+        assert!(fi.ends_with("…ja 2 muuta."));
+        assert!(en.contains("12 verdicts would change.\n• #1 **laka**: invalid → valid"));
+        assert_eq!(en.matches('•').count(), SHOWN_CHANGES);
+
+        let outcome = SetWordOutcome::Set {
+            word: "koira".to_string(),
+            dropped: 2,
+        };
+        assert_eq!(
+            both(Text::SetWord(&outcome)),
+            (
+                "Nykyinen sana on nyt **koira**. 2 tarkistusta odottanutta sanaa hylättiin."
+                    .to_string(),
+                "The current word is now **koira**. Rejected 2 words waiting to be checked."
+                    .to_string()
+            )
+        );
+
+        let mut stats = LLMStats::new("2025-01-06T12:00:00Z".parse().unwrap());
+        stats.models.insert(
+            "gemini-pro".to_string(),
+            ModelUsage {
+                calls: 4,
+                failures: 1,
+                words: 9,
+                latency: Duration::from_millis(4800),
+                tokens: TokenUsage {
+                    prompt: 4000,
+                    response: 300,
+                },
+                calls_without_usage: 1,
+            },
+        );
+        stats.cache_hits = 3;
+        let (fi, en) = both(Text::LlmStats {
+            stats: &stats,
+            queue: QueueStatus {
+                queued: 2,
+                oldest: Some(Duration::from_millis(4500)),
+                budget: Some(BudgetStatus {
+                    used: 200,
+                    limit: 200,
+                }),
+            },
+            validations: ValidationLoad {
+                in_flight: 8,
+                queued: 1,
+            },
+            reset: true,
+        });
+        assert_eq!(
+            en,
+            "🤖 LLM usage since 2025-01-06 12:00 UTC\n\
+             **gemini-pro**: 4 calls (1 failed), 9 words, 1200 ms per call, \
+             4000 prompt + 300 response tokens (1 call not reported)\n\
+             Cache: 3 words answered from the cache, 9 words sent to the LLM (25 % from the cache)\n\
+             Queue: 2 words waiting, the oldest for 5 seconds\n\
+             Daily budget: 200 of 200 words used in the last 24 hours, words over it aren't sent to the LLM\n\
+             Validation: 8 words in progress, 1 word waiting its turn\n\
+             The counters were reset."
+        );
+        assert!(fi.contains("4 kutsua (1 epäonnistui), 9 sanaa, 1200 ms / kutsu"));
+        assert!(fi.contains("(25 % välimuistista)"));
+        assert!(fi.contains("Jono: 2 sanaa odottaa, vanhin 5 s"));
+        assert!(fi.contains("Päiväbudjetti: 200 / 200 sanaa käytetty"));
+        assert!(fi.contains("Tarkistus: 8 sanaa käsittelyssä, 1 odottaa vuoroaan"));
+        let (fi, en) = both(Text::LlmStats {
+            stats: &LLMStats::new(stats.since),
+            queue: QueueStatus::default(),
+            validations: ValidationLoad::default(),
+            reset: false,
+        });
+        assert!(fi.contains("LLM:ää ei ole kutsuttu."));
+        assert!(en.contains("No calls to the LLM."));
+        assert!(fi.contains("Jono: tyhjä"));
+        assert!(en.contains("Queue: empty"));
+        assert!(!en.contains("Daily budget"));
+        assert!(!en.contains("reset"));
+    }
+
+    #[test]
+    fn test_help_sections() {
+        let game = RulesInfo {
+            rules: GameRules {
+                min_length: Some(3),
+                accept_proper_nouns: false,
+                ..GameRules::default()
+            },
+            current_word: Some("kissa".to_string()),
+            chain_length: 4,
+        };
+        let config = Config {
+            max_words_per_user_per_day: 20,
+            user_cooldown_secs: 30,
+            game_idle_reset_secs: 48 * 3600,
+            ..Config::default()
+        };
+        let info = HelpInfo::new(game, &config);
+        assert_eq!(info.daily_limit, Some(20));
+        assert_eq!(info.cooldown, Some(Duration::from_secs(30)));
+
+        let (fi, en) = both(Text::HelpSection(HelpTopic::Limits, &info));
+        assert!(fi.contains("Sanan pituus: vähintään 3 kirjainta"));
+        assert!(fi.contains("Sanoja päivässä: enintään 20"));
+        assert!(fi.contains("Odotus sanojen välillä: 30 s"));
+        assert!(fi.contains("Peli nollataan 48 tunnin hiljaisuuden jälkeen"));
+        assert!(en.contains("Word length: at least 3 letters"));
+        assert!(en.contains("Words a day: at most 20"));
+        assert!(en.contains("Wait between words: 30 seconds"));
+        assert!(en.contains("The game resets after 48 hours of silence"));
+
+        // Only the reactions the configuration can produce are explained
+        let (fi, en) = both(Text::HelpSection(HelpTopic::Reactions, &info));
+        assert!(fi.contains("⛔") && fi.contains("⏲"));
+        assert!(!fi.contains("❓") && !en.contains("❓"));
+        assert!(en.contains("💤 the game is paused"));
+        assert!(fi.contains("🔁 sana on jo pelattu"));
+        let (fi, _) = both(Text::HelpSection(HelpTopic::ProperNouns, &info));
+        assert!(fi.contains("erisnimiä ei hyväksytä"));
+
+        assert_eq!(
+            both(Text::HelpFooter(&info.game)),
+            (
+                "Nykyinen sana: kissa · ketjussa 4 sanaa".to_string(),
+                "Current word: kissa · 4 words in the chain".to_string()
+            )
+        );
+
+        let info = HelpInfo::new(
+            RulesInfo {
+                rules: GameRules::default(),
+                current_word: None,
+                chain_length: 0,
+            },
+            &Config::default(),
+        );
+        let (fi, en) = both(Text::HelpSection(HelpTopic::Reactions, &info));
+        assert!(fi.contains("❓") && !fi.contains("⛔"));
+        assert!(en.contains("❓") && !en.contains("⏲"));
+        let (_, en) = both(Text::HelpSection(HelpTopic::Limits, &info));
+        assert!(en.contains("Words a day: unlimited"));
+        assert!(!en.contains("resets"));
+        assert_eq!(
+            Text::HelpFooter(&info.game).render(Language::English),
+            "No word yet, start with any word!"
+        );
+        let (_, en) = both(Text::HelpSection(HelpTopic::ProperNouns, &info));
+        assert!(!en.contains("capitalized"));
+
+        let info = HelpInfo::new(
+            RulesInfo {
+                rules: GameRules {
+                    require_capitalization: true,
+                    ..GameRules::default()
+                },
+                current_word: None,
+                chain_length: 0,
+            },
+            &Config::default(),
+        );
+        let (fi, en) = both(Text::HelpSection(HelpTopic::ProperNouns, &info));
+        assert!(fi.contains("isolla alkukirjaimella"));
+        assert!(en.contains("Proper nouns must be capitalized"));
+    }
+
+    #[test]
+    fn test_help_complete() {
+        for (key, _) in fi::HELP {
+            assert!(
+                help(Language::English, key).is_some(),
+                "no English help for {}",
+                key
+            );
+        }
+        for (key, _) in en::HELP {
+            assert!(
+                help(Language::Finnish, key).is_some(),
+                "no Finnish help for {}",
+                key
+            );
+        }
+        assert_eq!(
+            help(Language::English, "check.word"),
+            Some("The word to check")
+        );
+    }
+}
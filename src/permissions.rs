@@ -0,0 +1,163 @@
+//! Startup check that the bot can play in the game channel
+//!
+//! Missing permissions otherwise only show up as failing reactions, one
+//! message at a time.
+
+use poise::serenity_prelude as serenity;
+use serenity::Permissions;
+use tracing::info;
+
+use crate::error::{Error, Result};
+
+/// What the bot needs in the game channel: to see words, react to them and answer
+pub const REQUIRED_PERMISSIONS: Permissions = Permissions::VIEW_CHANNEL
+    .union(Permissions::READ_MESSAGE_HISTORY)
+    .union(Permissions::ADD_REACTIONS)
+    .union(Permissions::SEND_MESSAGES);
+
+/// What the bot needs on top when the game is played in threads under the channel
+pub const THREAD_PERMISSIONS: Permissions = Permissions::SEND_MESSAGES_IN_THREADS;
+
+/// Names of the required permissions that `granted` lacks, with the ones for
+/// threads if the game is played in them
+pub fn missing_permissions(granted: Permissions, threads: bool) -> Vec<&'static str> {
+    let required = if threads {
+        REQUIRED_PERMISSIONS | THREAD_PERMISSIONS
+    } else {
+        REQUIRED_PERMISSIONS
+    };
+    required.difference(granted).get_permission_names()
+}
+
+/// Make sure the channel is one words can be played in
+fn check_kind(channel: &serenity::GuildChannel) -> Result<()> {
+    match channel.kind {
+        serenity::ChannelType::Text
+        | serenity::ChannelType::News
+        | serenity::ChannelType::Forum => Ok(()),
+        kind => Err(Error::Channel(format!(
+            "{} ({}) is a {} channel, not a text channel",
+            channel.id,
+            channel.name,
+            kind.name()
+        ))),
+    }
+}
+
+/// Make sure the game thread is a thread or a forum post
+fn check_thread_kind(thread: &serenity::GuildChannel) -> Result<()> {
+    match thread.kind {
+        serenity::ChannelType::PublicThread
+        | serenity::ChannelType::PrivateThread
+        | serenity::ChannelType::NewsThread => Ok(()),
+        kind => Err(Error::Channel(format!(
+            "{} ({}) is a {} channel, not a thread",
+            thread.id,
+            thread.name,
+            kind.name()
+        ))),
+    }
+}
+
+/// Look up the game channel and return the required permissions the bot lacks there
+///
+/// With `threads` the game is played in threads under the channel, which
+/// takes a permission of its own. A `thread_id` to play in is looked up too.
+/// Fails if the channel or the thread doesn't exist, can't be seen by the bot
+/// or is of the wrong kind.
+pub async fn check_channel(
+    http: &serenity::Http,
+    channel_id: serenity::ChannelId,
+    thread_id: Option<serenity::ChannelId>,
+    threads: bool,
+) -> Result<Vec<&'static str>> {
+    let channel = match channel_id.to_channel(http).await {
+        Ok(serenity::Channel::Guild(channel)) => channel,
+        Ok(_) => {
+            return Err(Error::Channel(format!(
+                "{} is not a channel in a server",
+                channel_id
+            )))
+        }
+        Err(e) => {
+            return Err(Error::Channel(format!(
+                "{} can't be found or the bot can't see it: {}",
+                channel_id, e
+            )))
+        }
+    };
+    check_kind(&channel)?;
+    if let Some(thread_id) = thread_id {
+        match thread_id.to_channel(http).await {
+            Ok(serenity::Channel::Guild(thread)) => check_thread_kind(&thread)?,
+            Ok(_) => {
+                return Err(Error::Channel(format!(
+                    "{} is not a thread in a server",
+                    thread_id
+                )))
+            }
+            Err(e) => {
+                return Err(Error::Channel(format!(
+                    "{} can't be found or the bot can't see it: {}",
+                    thread_id, e
+                )))
+            }
+        }
+    }
+
+    let bot = http.get_current_user().await?;
+    let guild = channel.guild_id.to_partial_guild(http).await?;
+    let member = channel.guild_id.member(http, bot.id).await?;
+    let granted = guild.user_permissions_in(&channel, &member);
+    info!(
+        channel_id = channel_id.get(),
+        channel = %channel.name,
+        permissions = ?granted,
+        "Checked the bot's permissions in the game channel"
+    );
+    Ok(missing_permissions(granted, threads))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_permissions() {
+        assert!(missing_permissions(REQUIRED_PERMISSIONS, false).is_empty());
+        assert!(missing_permissions(Permissions::all(), true).is_empty());
+        assert_eq!(
+            missing_permissions(
+                Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES,
+                false
+            ),
+            vec!["Add Reactions", "Read Message History"]
+        );
+        assert_eq!(missing_permissions(Permissions::empty(), false).len(), 4);
+
+        // Playing in threads takes a permission of its own
+        assert_eq!(
+            missing_permissions(REQUIRED_PERMISSIONS, true),
+            vec!["Send Messages in Threads"]
+        );
+    }
+
+    #[test]
+    fn test_channel_kind() {
+        let mut channel = serenity::GuildChannel::default();
+        channel.kind = serenity::ChannelType::Text;
+        assert!(check_kind(&channel).is_ok());
+
+        channel.kind = serenity::ChannelType::Voice;
+        channel.name = "aula".to_string();
+        let error = check_kind(&channel).unwrap_err().to_string();
+        assert!(error.contains("aula"), "{}", error);
+        assert!(error.contains("voice"), "{}", error);
+
+        channel.kind = serenity::ChannelType::PublicThread;
+        assert!(check_thread_kind(&channel).is_ok());
+        channel.kind = serenity::ChannelType::Text;
+        let error = check_thread_kind(&channel).unwrap_err().to_string();
+        assert!(error.contains("not a thread"), "{}", error);
+    }
+}
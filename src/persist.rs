@@ -0,0 +1,73 @@
+//! Writing the files the bot keeps its state in
+//!
+//! A file is never written in place: the content goes to a temporary file
+//! next to it, which is synced to disk and only then renamed over the old
+//! one, the same way the dictionary download is. A bot killed mid-write thus
+//! leaves either the old file or the new one behind, never a truncated one.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Replace the file at `path` with `content`, creating its directory if needed
+pub fn write_atomic(path: &Path, content: &[u8]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let temporary = with_suffix(path, "tmp");
+    let written = fs::File::create(&temporary)
+        .and_then(|mut file| {
+            file.write_all(content)?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&temporary, path));
+    if written.is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+    written
+}
+
+/// Move a file that couldn't be read to `<path>.corrupt`, so that the next
+/// save doesn't overwrite what may still be recovered from it by hand
+pub fn set_aside(path: &Path) -> io::Result<PathBuf> {
+    let corrupt = with_suffix(path, "corrupt");
+    fs::rename(path, &corrupt)?;
+    Ok(corrupt)
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_atomic() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data").join("state.json");
+
+        write_atomic(&path, b"{\"word\":\"kissa\"}").unwrap();
+        write_atomic(&path, b"{\"word\":\"kassa\"}").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"word\":\"kassa\"}");
+        assert!(!with_suffix(&path, "tmp").exists());
+    }
+
+    #[test]
+    fn test_set_aside() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        fs::write(&path, "{\"word\":").unwrap();
+
+        let corrupt = set_aside(&path).unwrap();
+
+        assert_eq!(corrupt, dir.path().join("state.json.corrupt"));
+        assert_eq!(fs::read_to_string(corrupt).unwrap(), "{\"word\":");
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,688 @@
+//! Replay of recorded game sessions for regression testing
+//!
+//! A session is a list of (timestamp, user_id, word) records, stored either as a
+//! JSON array of objects or as CSV with a `timestamp,user_id,word` header. The
+//! records are played in file order and each verdict can be compared against
+//! the output of an earlier run. The same replay backs `/simulate-rules`, which
+//! plays the game's history under another rule mode to see what would change.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::game::{GameEngine, WordEntry};
+use crate::simulate::{
+    engine_for, llm_for, to_json, write_summary, Simulator, Status, Summary, Verdict,
+};
+use crate::validation::dictionary::DictionaryValidator;
+use crate::validation::rules::RuleMode;
+
+pub const USAGE: &str =
+    "Usage: sanabotti replay RECORDS [--expected FILE] [--dictionary PATH] [--json] [--llm]";
+
+/// One message from a recorded session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Record {
+    /// Unix timestamp in seconds
+    pub timestamp: u64,
+    pub user_id: u64,
+    pub word: String,
+}
+
+/// Read records from a `.json` or `.csv` file
+pub fn load_records(path: &str) -> Result<Vec<Record>> {
+    let content = std::fs::read_to_string(path)?;
+    parse_records(path, &content)
+}
+
+/// Parse records in the format told by the extension of `filename`
+pub fn parse_records(filename: &str, content: &str) -> Result<Vec<Record>> {
+    match Path::new(filename).extension().and_then(|ext| ext.to_str()) {
+        Some("json") => parse_json(content),
+        Some("csv") => parse_csv(content),
+        _ => Err(Error::Replay(format!(
+            "Unknown format of '{}', expected a .json or .csv file",
+            filename
+        ))),
+    }
+}
+
+/// Records of the words in a game's history, oldest first
+pub fn records_from_history<'a>(entries: impl IntoIterator<Item = &'a WordEntry>) -> Vec<Record> {
+    entries
+        .into_iter()
+        .map(|entry| Record {
+            timestamp: entry
+                .played_at
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            user_id: entry.user_id,
+            word: entry.word.clone(),
+        })
+        .collect()
+}
+
+/// Parse a JSON array of records
+pub fn parse_json(content: &str) -> Result<Vec<Record>> {
+    serde_json::from_str(content)
+        .map_err(|e| Error::Replay(format!("Failed to parse JSON records: {}", e)))
+}
+
+/// Parse `timestamp,user_id,word` lines, with or without a header
+pub fn parse_csv(content: &str) -> Result<Vec<Record>> {
+    let mut records = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || (index == 0 && line.starts_with("timestamp")) {
+            continue;
+        }
+
+        let invalid = |what: &str| {
+            Error::Replay(format!(
+                "Invalid {} on line {} of CSV records: '{}'",
+                what,
+                index + 1,
+                line
+            ))
+        };
+
+        let mut fields = line
+            .splitn(3, ',')
+            .map(|field| field.trim().trim_matches('"'));
+        let timestamp = fields
+            .next()
+            .and_then(|field| field.parse().ok())
+            .ok_or_else(|| invalid("timestamp"))?;
+        let user_id = fields
+            .next()
+            .and_then(|field| field.parse().ok())
+            .ok_or_else(|| invalid("user id"))?;
+        let word = fields.next().ok_or_else(|| invalid("word"))?;
+
+        records.push(Record {
+            timestamp,
+            user_id,
+            word: word.to_string(),
+        });
+    }
+
+    Ok(records)
+}
+
+/// The verdict on one replayed record
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RecordResult {
+    /// Position of the record in the session, starting from 1
+    pub index: usize,
+    pub timestamp: u64,
+    pub user_id: u64,
+    pub input: String,
+    #[serde(flatten)]
+    pub verdict: Verdict,
+}
+
+/// The parts of an earlier run's JSON output that have to match
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ExpectedVerdict {
+    pub input: String,
+    pub status: Status,
+}
+
+/// Read the expected verdicts from the `--json` output of an earlier replay
+pub fn parse_expected(content: &str) -> Result<Vec<ExpectedVerdict>> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("{\"summary\""))
+        .map(|line| {
+            serde_json::from_str(line)
+                .map_err(|e| Error::Replay(format!("Invalid expected verdict '{}': {}", line, e)))
+        })
+        .collect()
+}
+
+/// A difference between the replayed and the expected verdicts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+    /// The record at `index` got a different verdict
+    Verdict {
+        index: usize,
+        input: String,
+        expected: Status,
+        actual: Status,
+        reason: String,
+    },
+    /// The expected file lists a different word at `index`
+    Input {
+        index: usize,
+        expected: String,
+        actual: String,
+    },
+    /// The expected file has a different number of verdicts
+    Count { expected: usize, actual: usize },
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mismatch::Verdict {
+                index,
+                input,
+                expected,
+                actual,
+                reason,
+            } => write!(
+                f,
+                "#{} {}: expected {}, got {} ({})",
+                index, input, expected, actual, reason
+            ),
+            Mismatch::Input {
+                index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "#{}: expected word '{}', got '{}'",
+                index, expected, actual
+            ),
+            Mismatch::Count { expected, actual } => {
+                write!(f, "expected {} verdicts, got {}", expected, actual)
+            }
+        }
+    }
+}
+
+/// Compare replayed verdicts with the expected ones
+pub fn compare(results: &[RecordResult], expected: &[ExpectedVerdict]) -> Vec<Mismatch> {
+    let mut mismatches: Vec<_> = results
+        .iter()
+        .zip(expected)
+        .filter_map(|(result, expected)| {
+            if result.input != expected.input {
+                Some(Mismatch::Input {
+                    index: result.index,
+                    expected: expected.input.clone(),
+                    actual: result.input.clone(),
+                })
+            } else if result.verdict.status != expected.status {
+                Some(Mismatch::Verdict {
+                    index: result.index,
+                    input: result.input.clone(),
+                    expected: expected.status,
+                    actual: result.verdict.status,
+                    reason: result.verdict.reason.clone(),
+                })
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    if results.len() != expected.len() {
+        mismatches.push(Mismatch::Count {
+            expected: expected.len(),
+            actual: results.len(),
+        });
+    }
+
+    mismatches
+}
+
+/// Play the records in order
+///
+/// With `idle_reset_secs` set, a gap of that long since the last accepted word
+/// starts a new game, like the bot's idle reset.
+pub async fn replay(
+    simulator: &mut Simulator,
+    records: &[Record],
+    idle_reset_secs: u64,
+) -> Vec<RecordResult> {
+    let mut results = Vec::with_capacity(records.len());
+    let mut last_accepted: Option<u64> = None;
+
+    for (index, record) in records.iter().enumerate() {
+        if let Some(accepted_at) = last_accepted {
+            if idle_reset_secs > 0
+                && record.timestamp.saturating_sub(accepted_at) >= idle_reset_secs
+            {
+                simulator.reset();
+                last_accepted = None;
+            }
+        }
+
+        let verdict = simulator
+            .play_as(record.user_id, index as u64 + 1, &record.word)
+            .await;
+        if verdict.status == Status::Valid || verdict.status == Status::Pending {
+            last_accepted = Some(record.timestamp);
+        }
+
+        results.push(RecordResult {
+            index: index + 1,
+            timestamp: record.timestamp,
+            user_id: record.user_id,
+            input: record.word.clone(),
+            verdict,
+        });
+    }
+
+    results
+}
+
+/// Changed verdicts listed in the answer to `/simulate-rules`
+pub const SHOWN_CHANGES: usize = 10;
+
+/// A record that would get another verdict under the proposed rule mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedVerdict {
+    /// Position of the record in the session, starting from 1
+    pub index: usize,
+    pub input: String,
+    pub current: Status,
+    pub proposed: Status,
+    /// Why the proposed mode gives its verdict
+    pub reason: String,
+}
+
+/// What replaying the records under another rule mode changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RulesSimulation {
+    pub current_mode: RuleMode,
+    pub proposed_mode: RuleMode,
+    /// Number of records replayed
+    pub records: usize,
+    /// The records whose verdict changed, in order
+    pub changes: Vec<ChangedVerdict>,
+}
+
+/// Replay the records under both the configured and the proposed rule mode
+///
+/// Each replay gets an engine of its own around the shared dictionary, so the
+/// live game isn't touched. Words that would need the LLM stay pending in
+/// both, as asking it about a whole history would take too long.
+pub async fn simulate_rules(
+    dictionary: Arc<DictionaryValidator>,
+    config: &Config,
+    proposed_mode: RuleMode,
+    records: &[Record],
+) -> RulesSimulation {
+    let proposed_config = Config {
+        rule_mode: proposed_mode,
+        ..config.clone()
+    };
+    let mut current = Simulator::new(GameEngine::from_shared_config(dictionary.clone(), config));
+    let mut proposed = Simulator::new(GameEngine::from_shared_config(dictionary, &proposed_config));
+
+    let current = replay(&mut current, records, config.game_idle_reset_secs).await;
+    let proposed = replay(&mut proposed, records, config.game_idle_reset_secs).await;
+    let changes = current
+        .into_iter()
+        .zip(proposed)
+        .filter(|(current, proposed)| current.verdict.status != proposed.verdict.status)
+        .map(|(current, proposed)| ChangedVerdict {
+            index: proposed.index,
+            input: proposed.input,
+            current: current.verdict.status,
+            proposed: proposed.verdict.status,
+            reason: proposed.verdict.reason,
+        })
+        .collect();
+
+    RulesSimulation {
+        current_mode: config.rule_mode,
+        proposed_mode,
+        records: records.len(),
+        changes,
+    }
+}
+
+/// Write the verdicts, the summary and any mismatches; returns the summary
+pub fn report<W: Write>(
+    output: &mut W,
+    results: &[RecordResult],
+    mismatches: &[Mismatch],
+    json: bool,
+) -> Result<Summary> {
+    let mut summary = Summary::default();
+
+    for result in results {
+        summary.record(result.verdict.status);
+        if json {
+            writeln!(output, "{}", to_json(result)?)?;
+        } else {
+            writeln!(
+                output,
+                "{}\t{}\t{}\t{}\t{}",
+                result.timestamp,
+                result.user_id,
+                result.input,
+                result.verdict.status,
+                result.verdict.reason
+            )?;
+        }
+    }
+
+    write_summary(output, &summary, json)?;
+
+    if !mismatches.is_empty() {
+        // Keep the JSON output parseable by sending the report elsewhere
+        let mut report = String::new();
+        report.push_str(&format!(
+            "{} verdicts differ from the expected ones:\n",
+            mismatches.len()
+        ));
+        for mismatch in mismatches {
+            report.push_str(&format!("  {}\n", mismatch));
+        }
+
+        if json {
+            eprint!("{}", report);
+        } else {
+            write!(output, "\n{}", report)?;
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Command line options of the replay subcommand
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ReplayOptions {
+    pub records_path: String,
+    /// JSON output of an earlier replay to compare against
+    pub expected_path: Option<String>,
+    pub dictionary_path: Option<String>,
+    pub json: bool,
+    pub llm: bool,
+}
+
+impl ReplayOptions {
+    pub fn parse<I, S>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut options = Self::default();
+        let mut records_path = None;
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_ref() {
+                "--expected" | "--dictionary" => {
+                    let flag = arg.as_ref().to_string();
+                    let path = args
+                        .next()
+                        .ok_or_else(|| Error::Config(format!("{} needs a path\n{}", flag, USAGE)))?
+                        .as_ref()
+                        .to_string();
+                    if flag == "--expected" {
+                        options.expected_path = Some(path);
+                    } else {
+                        options.dictionary_path = Some(path);
+                    }
+                }
+                "--json" => options.json = true,
+                "--llm" => options.llm = true,
+                other if !other.starts_with("--") && records_path.is_none() => {
+                    records_path = Some(other.to_string());
+                }
+                other => {
+                    return Err(Error::Config(format!(
+                        "Unknown argument '{}'\n{}",
+                        other, USAGE
+                    )))
+                }
+            }
+        }
+
+        options.records_path = records_path
+            .ok_or_else(|| Error::Config(format!("Missing records file\n{}", USAGE)))?;
+        Ok(options)
+    }
+}
+
+/// Entry point of `sanabotti replay`: returns the process exit code
+///
+/// The exit code is non-zero if a word couldn't be judged or a verdict differs
+/// from the expected ones.
+pub async fn run_cli<I, S>(args: I) -> miette::Result<i32>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let options = ReplayOptions::parse(args)?;
+    let config = crate::config::load_game_config()?;
+    let dictionary_path = options
+        .dictionary_path
+        .clone()
+        .unwrap_or_else(|| config.dictionary_path.clone());
+
+    let records = load_records(&options.records_path)?;
+    let expected = match &options.expected_path {
+        Some(path) => Some(parse_expected(
+            &std::fs::read_to_string(path).map_err(Error::from)?,
+        )?),
+        None => None,
+    };
+
+    let mut simulator = Simulator::new(engine_for(&dictionary_path, &config)?);
+    if options.llm {
+        simulator = simulator.with_llm(llm_for(&config)?);
+    }
+
+    let results = replay(&mut simulator, &records, config.game_idle_reset_secs).await;
+    let mismatches = expected
+        .map(|expected| compare(&results, &expected))
+        .unwrap_or_default();
+
+    let stdout = std::io::stdout();
+    let summary = report(&mut stdout.lock(), &results, &mismatches, options.json)?;
+
+    Ok(if mismatches.is_empty() {
+        summary.exit_code()
+    } else {
+        1
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    const DICTIONARY: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/golden/simulate_dictionary.txt"
+    );
+
+    fn simulator() -> Simulator {
+        Simulator::new(engine_for(DICTIONARY, &Config::default()).unwrap())
+    }
+
+    fn record(timestamp: u64, user_id: u64, word: &str) -> Record {
+        Record {
+            timestamp,
+            user_id,
+            word: word.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_records() {
+        let expected = vec![record(100, 1, "kissa"), record(160, 2, "kassa")];
+
+        let csv = "timestamp,user_id,word\n100,1,kissa\n\n160, 2, \"kassa\"\n";
+        assert_eq!(parse_csv(csv).unwrap(), expected);
+        assert_eq!(parse_csv("100,1,kissa\n160,2,kassa").unwrap(), expected);
+        assert!(parse_csv("100,x,kissa").is_err());
+
+        let json = r#"[
+            {"timestamp": 100, "user_id": 1, "word": "kissa"},
+            {"timestamp": 160, "user_id": 2, "word": "kassa"}
+        ]"#;
+        assert_eq!(parse_json(json).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let options =
+            ReplayOptions::parse(["session.csv", "--expected", "verdicts.jsonl", "--json"])
+                .unwrap();
+        assert_eq!(options.records_path, "session.csv");
+        assert_eq!(options.expected_path.as_deref(), Some("verdicts.jsonl"));
+        assert!(options.json);
+
+        assert!(ReplayOptions::parse(["--json"]).is_err());
+        assert!(ReplayOptions::parse(["a.csv", "b.csv"]).is_err());
+    }
+
+    #[actix_rt::test]
+    async fn test_replay_matches_its_own_output() {
+        let records = vec![
+            record(100, 1, "kissa"),
+            record(110, 2, "kassa"),
+            record(120, 1, "kassa"),
+            record(130, 2, "kassi"),
+        ];
+        let results = replay(&mut simulator(), &records, 0).await;
+        let statuses: Vec<_> = results.iter().map(|r| r.verdict.status).collect();
+        assert_eq!(
+            statuses,
+            vec![Status::Valid, Status::Valid, Status::Invalid, Status::Valid]
+        );
+
+        let mut output = Vec::new();
+        report(&mut output, &results, &[], true).unwrap();
+        let expected = parse_expected(&String::from_utf8(output).unwrap()).unwrap();
+        assert!(compare(&results, &expected).is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn test_mismatch_report() {
+        let records = vec![record(100, 1, "kissa"), record(110, 2, "koira")];
+        let results = replay(&mut simulator(), &records, 0).await;
+
+        let expected = parse_expected(
+            "{\"input\":\"kissa\",\"status\":\"valid\"}\n\
+             {\"input\":\"koira\",\"status\":\"valid\"}\n\
+             {\"input\":\"kassa\",\"status\":\"valid\"}\n",
+        )
+        .unwrap();
+        let mismatches = compare(&results, &expected);
+        assert_eq!(
+            mismatches,
+            vec![
+                Mismatch::Verdict {
+                    index: 2,
+                    input: "koira".to_string(),
+                    expected: Status::Valid,
+                    actual: Status::Invalid,
+                    reason: results[1].verdict.reason.clone(),
+                },
+                Mismatch::Count {
+                    expected: 3,
+                    actual: 2,
+                },
+            ]
+        );
+
+        let mut output = Vec::new();
+        report(&mut output, &results, &mismatches, false).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("2 verdicts differ from the expected ones:"));
+        assert!(output.contains("  #2 koira: expected valid, got invalid ("));
+        assert!(output.contains("  expected 3 verdicts, got 2"));
+    }
+
+    #[actix_rt::test]
+    async fn test_simulate_rules() {
+        let dictionary = Arc::new(DictionaryValidator::from_words(["kala", "laka", "lapa"]));
+        // laka is an anagram of kala, and lapa is a letter away from laka but not from kala
+        let records = vec![
+            record(100, 1, "kala"),
+            record(110, 2, "laka"),
+            record(120, 1, "lapa"),
+        ];
+
+        let simulation = simulate_rules(
+            dictionary.clone(),
+            &Config::default(),
+            RuleMode::Anagram,
+            &records,
+        )
+        .await;
+        assert_eq!(simulation.current_mode, RuleMode::Classic);
+        assert_eq!(simulation.records, 3);
+        let changed: Vec<_> = simulation
+            .changes
+            .iter()
+            .map(|change| {
+                (
+                    change.index,
+                    change.input.as_str(),
+                    change.current,
+                    change.proposed,
+                )
+            })
+            .collect();
+        assert_eq!(
+            changed,
+            [
+                (2, "laka", Status::Invalid, Status::Valid),
+                (3, "lapa", Status::Invalid, Status::Valid),
+            ]
+        );
+
+        // Nothing changes when the mode stays the same
+        let simulation =
+            simulate_rules(dictionary, &Config::default(), RuleMode::Classic, &records).await;
+        assert!(simulation.changes.is_empty());
+    }
+
+    #[test]
+    fn test_records_from_history() {
+        use crate::game::{Delivery, Validity};
+        use std::time::Duration;
+
+        let entry = WordEntry {
+            word: "kissa".to_string(),
+            user_id: 7,
+            message_id: 1,
+            validity: Validity::Valid,
+            source: None,
+            played_at: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            settled_at: None,
+            in_chain: true,
+            delivery: Delivery::default(),
+        };
+        assert_eq!(
+            records_from_history([&entry]),
+            [record(1_700_000_000, 7, "kissa")]
+        );
+        assert!(parse_records("session.txt", "").is_err());
+    }
+
+    #[actix_rt::test]
+    async fn test_idle_reset_between_records() {
+        let records = vec![
+            record(100, 1, "kissa"),
+            record(200, 2, "kassa"),
+            // A new game starts, so the used word can be played again
+            record(10_000, 1, "kissa"),
+        ];
+        let results = replay(&mut simulator(), &records, 3600).await;
+        assert!(results
+            .iter()
+            .all(|result| result.verdict.status == Status::Valid));
+
+        let results = replay(&mut simulator(), &records, 0).await;
+        assert_eq!(results[2].verdict.status, Status::Invalid);
+    }
+}
@@ -0,0 +1,222 @@
+//! Players taking turns in a fixed order
+//!
+//! In rotation mode players sign up with /join and play in the order they
+//! joined: only the player on turn may play, and the turn moves on to the next
+//! player after each accepted word. The order is kept in a file so a restarted
+//! bot goes on with the same players and the same player on turn.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::error::Result;
+use crate::persist::write_atomic;
+use crate::Error;
+
+/// Who may play a word
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TurnMode {
+    /// Anyone may play at any time
+    #[default]
+    Free,
+    /// Players who joined take turns in order
+    Rotation,
+}
+
+impl FromStr for TurnMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "free" => Ok(TurnMode::Free),
+            "rotation" => Ok(TurnMode::Rotation),
+            other => Err(Error::Config(format!(
+                "Invalid GAME_TURN_MODE '{}', expected 'free' or 'rotation'",
+                other
+            ))),
+        }
+    }
+}
+
+/// The players in the order they take turns, and whose turn it is
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rotation {
+    players: Vec<u64>,
+    /// Index of the player on turn in `players`
+    turn: usize,
+}
+
+impl Rotation {
+    /// The player on turn, None if nobody has joined
+    pub fn on_turn(&self) -> Option<u64> {
+        self.players.get(self.turn).copied()
+    }
+
+    /// Every player, starting from the one on turn
+    pub fn order(&self) -> Vec<u64> {
+        let (before, after) = self.players.split_at(self.turn.min(self.players.len()));
+        after.iter().chain(before).copied().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.players.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.players.is_empty()
+    }
+
+    /// Add a player last in the order
+    ///
+    /// Returns false if they had already joined.
+    pub fn join(&mut self, user_id: u64) -> bool {
+        if self.players.contains(&user_id) {
+            return false;
+        }
+        // The last place is right before the player on turn
+        self.players.insert(self.turn, user_id);
+        if self.players.len() > 1 {
+            self.turn += 1;
+        }
+        true
+    }
+
+    /// Take a player out of the order, the next player taking their turn if it was theirs
+    ///
+    /// Returns false if they hadn't joined.
+    pub fn leave(&mut self, user_id: u64) -> bool {
+        let Some(index) = self.players.iter().position(|&player| player == user_id) else {
+            return false;
+        };
+        self.players.remove(index);
+        if index < self.turn {
+            self.turn -= 1;
+        }
+        if self.turn >= self.players.len() {
+            self.turn = 0;
+        }
+        true
+    }
+
+    /// Give the turn to the next player, returning who that is
+    pub fn advance(&mut self) -> Option<u64> {
+        if self.players.is_empty() {
+            return None;
+        }
+        self.turn = (self.turn + 1) % self.players.len();
+        self.on_turn()
+    }
+}
+
+/// Where the rotation is kept, as a JSON object
+#[derive(Debug, Clone)]
+pub struct RotationStore {
+    path: PathBuf,
+}
+
+impl RotationStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The saved rotation, empty if none has been saved yet
+    ///
+    /// A turn past the last player, e.g. in a file edited by hand, goes to the first one.
+    pub fn load(&self) -> Result<Rotation> {
+        match fs::read_to_string(&self.path) {
+            Ok(content) => {
+                let mut rotation: Rotation =
+                    serde_json::from_str(&content).map_err(io::Error::other)?;
+                if rotation.turn >= rotation.players.len() {
+                    rotation.turn = 0;
+                }
+                Ok(rotation)
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Rotation::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, rotation: &Rotation) -> Result<()> {
+        let content = serde_json::to_string_pretty(rotation).map_err(io::Error::other)?;
+        write_atomic(&self.path, content.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turns() {
+        let mut rotation = Rotation::default();
+        assert_eq!(rotation.on_turn(), None);
+        assert_eq!(rotation.advance(), None);
+
+        assert!(rotation.join(1));
+        assert!(rotation.join(2));
+        assert!(!rotation.join(1));
+        assert_eq!(rotation.on_turn(), Some(1));
+        assert_eq!(rotation.advance(), Some(2));
+
+        // A new player is last, after everyone who joined before them
+        assert!(rotation.join(3));
+        assert_eq!(rotation.order(), vec![2, 1, 3]);
+        assert_eq!(rotation.advance(), Some(1));
+        assert_eq!(rotation.advance(), Some(3));
+        assert_eq!(rotation.advance(), Some(2));
+    }
+
+    #[test]
+    fn test_leave() {
+        let mut rotation = Rotation::default();
+        for player in [1, 2, 3, 4] {
+            rotation.join(player);
+        }
+        rotation.advance();
+        rotation.advance();
+        assert_eq!(rotation.on_turn(), Some(3));
+
+        // Leaving before the player on turn keeps the turn where it is
+        assert!(rotation.leave(1));
+        assert_eq!(rotation.order(), vec![3, 4, 2]);
+        // The player on turn leaving passes the turn on
+        assert!(rotation.leave(3));
+        assert_eq!(rotation.on_turn(), Some(4));
+        assert!(rotation.leave(4));
+        assert_eq!(rotation.on_turn(), Some(2));
+        assert!(!rotation.leave(4));
+        assert!(rotation.leave(2));
+        assert!(rotation.is_empty());
+        assert_eq!(rotation.on_turn(), None);
+    }
+
+    #[test]
+    fn test_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RotationStore::new(dir.path().join("data").join("turns.json"));
+        assert_eq!(store.load().unwrap(), Rotation::default());
+
+        let mut rotation = Rotation::default();
+        rotation.join(1);
+        rotation.join(2);
+        rotation.advance();
+        store.save(&rotation).unwrap();
+        assert_eq!(store.load().unwrap(), rotation);
+        assert_eq!(store.load().unwrap().on_turn(), Some(2));
+
+        // A turn past the players is taken to be the first player's
+        fs::write(store.path(), r#"{"players":[1],"turn":5}"#).unwrap();
+        let mut rotation = store.load().unwrap();
+        assert_eq!(rotation.on_turn(), Some(1));
+        assert!(rotation.join(2));
+        assert_eq!(rotation.order(), vec![1, 2]);
+    }
+}
@@ -0,0 +1,500 @@
+//! Points for accepted words and the players' running totals
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::config::Config;
+
+/// How many points a word is worth
+///
+/// A word earns the base points, points per letter, a bonus for every rare
+/// letter in it and a bonus for breaking out of a letter rut, i.e. starting
+/// with a different letter than the word before it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoreFormula {
+    pub base_points: u32,
+    pub per_letter: u32,
+    /// Letters that earn `rare_letter_bonus` each time they appear
+    pub rare_letters: Vec<char>,
+    pub rare_letter_bonus: u32,
+    pub rut_bonus: u32,
+}
+
+impl Default for ScoreFormula {
+    fn default() -> Self {
+        Self {
+            base_points: 0,
+            per_letter: 1,
+            rare_letters: vec!['ä', 'ö', 'y'],
+            rare_letter_bonus: 1,
+            rut_bonus: 2,
+        }
+    }
+}
+
+impl ScoreFormula {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            base_points: config.score_base_points,
+            per_letter: config.score_per_letter,
+            rare_letters: config.score_rare_letters.to_lowercase().chars().collect(),
+            rare_letter_bonus: config.score_rare_letter_bonus,
+            rut_bonus: config.score_rut_bonus,
+        }
+    }
+
+    /// Points for playing `word` after `previous`, the chain head it was compared against
+    pub fn score(&self, word: &str, previous: Option<&str>) -> u32 {
+        let letters: Vec<char> = word
+            .chars()
+            .filter(|c| c.is_alphabetic())
+            .flat_map(char::to_lowercase)
+            .collect();
+        let rare = letters
+            .iter()
+            .filter(|c| self.rare_letters.contains(c))
+            .count() as u32;
+
+        let first = |word: &str| word.chars().next().and_then(|c| c.to_lowercase().next());
+        let broke_rut = previous.is_some_and(|previous| first(previous) != first(word));
+
+        self.base_points
+            + self.per_letter * letters.len() as u32
+            + self.rare_letter_bonus * rare
+            + if broke_rut { self.rut_bonus } else { 0 }
+    }
+}
+
+/// A player's points and accepted words
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerScore {
+    pub points: u64,
+    pub words: usize,
+}
+
+/// Which points a leaderboard covers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Period {
+    /// Since the last weekly reset
+    Weekly,
+    #[default]
+    AllTime,
+}
+
+/// A player's scores and places on the leaderboards
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Standing {
+    pub score: PlayerScore,
+    /// Place starting from 1, None before the player has any points
+    pub rank: Option<usize>,
+    pub weekly: PlayerScore,
+    pub weekly_rank: Option<usize>,
+}
+
+/// What a word a player tried came to, once it was settled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttemptOutcome {
+    /// Accepted as a dictionary word, including ones found in the remote dictionary
+    Dictionary,
+    /// Accepted as a proper noun by the LLM
+    ProperNoun,
+    /// Not a move the rules allow from the chain head
+    RuleViolation,
+    /// Played before in this game
+    AlreadyUsed,
+    /// Neither in the dictionary nor a proper noun
+    NotAWord,
+    /// On the blocklist
+    Blocked,
+}
+
+/// How a player's attempts have turned out
+///
+/// Every settled attempt counts towards the total and exactly one of the outcomes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Attempts {
+    pub total: usize,
+    pub dictionary: usize,
+    pub proper_nouns: usize,
+    pub rule_violations: usize,
+    pub already_used: usize,
+    pub not_words: usize,
+    pub blocked: usize,
+}
+
+impl Attempts {
+    pub fn record(&mut self, outcome: AttemptOutcome) {
+        self.total += 1;
+        *match outcome {
+            AttemptOutcome::Dictionary => &mut self.dictionary,
+            AttemptOutcome::ProperNoun => &mut self.proper_nouns,
+            AttemptOutcome::RuleViolation => &mut self.rule_violations,
+            AttemptOutcome::AlreadyUsed => &mut self.already_used,
+            AttemptOutcome::NotAWord => &mut self.not_words,
+            AttemptOutcome::Blocked => &mut self.blocked,
+        } += 1;
+    }
+
+    /// The share of all attempts `count` is, in whole percents
+    pub fn percent(&self, count: usize) -> usize {
+        if self.total == 0 {
+            return 0;
+        }
+        (count * 100 + self.total / 2) / self.total
+    }
+}
+
+/// Points a word earned
+#[derive(Debug, Clone, Copy)]
+struct Award {
+    user_id: u64,
+    points: u32,
+    /// The week the points went to
+    week: u64,
+}
+
+/// Running totals of every player, all-time and for the current week
+///
+/// Remembers what each word earned, so the points can be taken back if the
+/// word is invalidated later.
+#[derive(Debug, Default)]
+pub struct Scoreboard {
+    all_time: HashMap<u64, PlayerScore>,
+    weekly: HashMap<u64, PlayerScore>,
+    /// How many weeks have ended, to tell which week an award belongs to
+    week: u64,
+    /// Points of accepted words by message ID
+    awarded: HashMap<u64, Award>,
+    /// Points of words waiting for the LLM, awarded once they're accepted
+    held: HashMap<u64, (u64, u32)>,
+}
+
+impl Scoreboard {
+    /// Give a player the points for an accepted word
+    pub fn award(&mut self, user_id: u64, message_id: u64, points: u32) {
+        for scores in [&mut self.all_time, &mut self.weekly] {
+            let score = scores.entry(user_id).or_default();
+            score.points += u64::from(points);
+            score.words += 1;
+        }
+        self.awarded.insert(
+            message_id,
+            Award {
+                user_id,
+                points,
+                week: self.week,
+            },
+        );
+    }
+
+    /// Keep the points of a pending word until it's settled
+    pub fn hold(&mut self, user_id: u64, message_id: u64, points: u32) {
+        self.held.insert(message_id, (user_id, points));
+    }
+
+    /// Award or drop the points held for a pending word
+    pub fn settle(&mut self, message_id: u64, accepted: bool) {
+        if let Some((user_id, points)) = self.held.remove(&message_id) {
+            if accepted {
+                self.award(user_id, message_id, points);
+            }
+        }
+    }
+
+    /// Take back what a word earned, returning false if it hadn't earned anything
+    ///
+    /// Points from a week that has already ended only come off the all-time score.
+    pub fn revoke(&mut self, message_id: u64) -> bool {
+        if self.held.remove(&message_id).is_some() {
+            return true;
+        }
+        let Some(award) = self.awarded.remove(&message_id) else {
+            return false;
+        };
+        let mut scores = vec![&mut self.all_time];
+        if award.week == self.week {
+            scores.push(&mut self.weekly);
+        }
+        for scores in scores {
+            if let Some(score) = scores.get_mut(&award.user_id) {
+                score.points = score.points.saturating_sub(u64::from(award.points));
+                score.words = score.words.saturating_sub(1);
+            }
+        }
+        true
+    }
+
+    /// End the week, returning its final standings, best first
+    ///
+    /// The all-time scores are left as they are.
+    pub fn end_week(&mut self) -> Vec<(u64, PlayerScore)> {
+        let standings = ranking(&self.weekly);
+        self.weekly.clear();
+        self.week += 1;
+        standings
+    }
+
+    /// Forget what words earned, except those `keep` returns true for
+    ///
+    /// The totals stay, only the words' points can no longer be revoked.
+    pub fn forget_words(&mut self, keep: impl Fn(u64) -> bool) {
+        self.awarded.retain(|&message_id, _| keep(message_id));
+        self.held.retain(|&message_id, _| keep(message_id));
+    }
+
+    /// How many words' points are remembered
+    pub fn remembered_words(&self) -> usize {
+        self.awarded.len() + self.held.len()
+    }
+
+    /// Every player's all-time and weekly scores, in order of user ID
+    pub fn totals(&self) -> Vec<(u64, PlayerScore, PlayerScore)> {
+        let mut totals: Vec<_> = self
+            .all_time
+            .iter()
+            .map(|(&user_id, &score)| {
+                let weekly = self.weekly.get(&user_id).copied().unwrap_or_default();
+                (user_id, score, weekly)
+            })
+            .collect();
+        totals.sort_by_key(|&(user_id, _, _)| user_id);
+        totals
+    }
+
+    /// Set a player's scores, e.g. to the ones saved before a restart
+    pub fn restore(&mut self, user_id: u64, all_time: PlayerScore, weekly: PlayerScore) {
+        self.all_time.insert(user_id, all_time);
+        if weekly != PlayerScore::default() {
+            self.weekly.insert(user_id, weekly);
+        }
+    }
+
+    pub fn player(&self, user_id: u64) -> PlayerScore {
+        self.all_time.get(&user_id).copied().unwrap_or_default()
+    }
+
+    pub fn standing(&self, user_id: u64) -> Standing {
+        let rank = |scores| {
+            ranking(scores)
+                .iter()
+                .position(|&(id, _)| id == user_id)
+                .map(|index| index + 1)
+        };
+        Standing {
+            score: self.player(user_id),
+            rank: rank(&self.all_time),
+            weekly: self.weekly.get(&user_id).copied().unwrap_or_default(),
+            weekly_rank: rank(&self.weekly),
+        }
+    }
+
+    /// The `limit` players with the most points in the period, best first
+    pub fn leaderboard(&self, period: Period, limit: usize) -> Vec<(u64, PlayerScore)> {
+        let scores = match period {
+            Period::Weekly => &self.weekly,
+            Period::AllTime => &self.all_time,
+        };
+        let mut ranking = ranking(scores);
+        ranking.truncate(limit);
+        ranking
+    }
+}
+
+/// Every player with points, best first, ties broken by fewer words and then by ID
+fn ranking(scores: &HashMap<u64, PlayerScore>) -> Vec<(u64, PlayerScore)> {
+    let mut ranking: Vec<_> = scores
+        .iter()
+        .filter(|(_, score)| score.words > 0)
+        .map(|(&user_id, &score)| (user_id, score))
+        .collect();
+    ranking.sort_by(|(a_id, a), (b_id, b)| {
+        b.points
+            .cmp(&a.points)
+            .then(a.words.cmp(&b.words))
+            .then(a_id.cmp(b_id))
+    });
+    ranking
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attempts() {
+        let outcomes = [
+            AttemptOutcome::Dictionary,
+            AttemptOutcome::ProperNoun,
+            AttemptOutcome::RuleViolation,
+            AttemptOutcome::AlreadyUsed,
+            AttemptOutcome::NotAWord,
+            AttemptOutcome::Blocked,
+        ];
+        for outcome in outcomes {
+            let mut attempts = Attempts::default();
+            attempts.record(outcome);
+            let counts = [
+                attempts.dictionary,
+                attempts.proper_nouns,
+                attempts.rule_violations,
+                attempts.already_used,
+                attempts.not_words,
+                attempts.blocked,
+            ];
+            assert_eq!(attempts.total, 1, "{:?}", outcome);
+            assert_eq!(counts.iter().sum::<usize>(), 1, "{:?}", outcome);
+        }
+
+        let mut attempts = Attempts::default();
+        assert_eq!(attempts.percent(0), 0);
+        attempts.record(AttemptOutcome::Dictionary);
+        attempts.record(AttemptOutcome::Dictionary);
+        attempts.record(AttemptOutcome::NotAWord);
+        assert_eq!(attempts.percent(attempts.dictionary), 67);
+        assert_eq!(attempts.percent(attempts.not_words), 33);
+    }
+
+    #[test]
+    fn test_score_formula() {
+        let formula = ScoreFormula::default();
+        assert_eq!(formula.score("kissa", None), 5);
+        // Same first letter as the previous word, no rut bonus
+        assert_eq!(formula.score("kassa", Some("kissa")), 5);
+        assert_eq!(formula.score("passa", Some("kassa")), 5 + 2);
+        // Each rare letter counts
+        assert_eq!(formula.score("pöytä", Some("pöytä")), 5 + 3);
+        assert_eq!(formula.score("linja-auto", None), 9);
+
+        let formula = ScoreFormula {
+            base_points: 10,
+            per_letter: 2,
+            rare_letters: vec!['k'],
+            rare_letter_bonus: 5,
+            rut_bonus: 0,
+        };
+        assert_eq!(formula.score("Kissa", Some("passi")), 10 + 2 * 5 + 5);
+    }
+
+    #[test]
+    fn test_scoreboard_accumulation() {
+        let mut scoreboard = Scoreboard::default();
+        scoreboard.award(1, 10, 5);
+        scoreboard.award(2, 11, 7);
+        scoreboard.award(1, 12, 4);
+        scoreboard.hold(2, 13, 6);
+
+        assert_eq!(
+            scoreboard.player(1),
+            PlayerScore {
+                points: 9,
+                words: 2
+            }
+        );
+        assert_eq!(
+            scoreboard.player(2),
+            PlayerScore {
+                points: 7,
+                words: 1
+            }
+        );
+        assert_eq!(scoreboard.standing(1).rank, Some(1));
+        assert_eq!(scoreboard.standing(3).rank, None);
+
+        scoreboard.settle(13, true);
+        assert_eq!(
+            scoreboard.player(2),
+            PlayerScore {
+                points: 13,
+                words: 2
+            }
+        );
+        assert_eq!(
+            scoreboard.leaderboard(Period::AllTime, 1),
+            vec![(2, scoreboard.player(2))]
+        );
+
+        // Invalidated words lose their points
+        assert!(scoreboard.revoke(13));
+        assert!(!scoreboard.revoke(13));
+        assert_eq!(
+            scoreboard.player(2),
+            PlayerScore {
+                points: 7,
+                words: 1
+            }
+        );
+
+        // Rejected pending words never earn anything
+        scoreboard.hold(1, 14, 3);
+        scoreboard.settle(14, false);
+        assert_eq!(
+            scoreboard.player(1),
+            PlayerScore {
+                points: 9,
+                words: 2
+            }
+        );
+
+        scoreboard.forget_words(|message_id| message_id == 12);
+        assert_eq!(scoreboard.remembered_words(), 1);
+        assert!(!scoreboard.revoke(10));
+        assert_eq!(
+            scoreboard.player(1),
+            PlayerScore {
+                points: 9,
+                words: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_weekly_scores() {
+        let mut scoreboard = Scoreboard::default();
+        scoreboard.award(1, 10, 5);
+        scoreboard.award(2, 11, 3);
+
+        let standings = scoreboard.end_week();
+        assert_eq!(
+            standings,
+            vec![
+                (
+                    1,
+                    PlayerScore {
+                        points: 5,
+                        words: 1
+                    }
+                ),
+                (
+                    2,
+                    PlayerScore {
+                        points: 3,
+                        words: 1
+                    }
+                ),
+            ]
+        );
+        assert!(scoreboard.leaderboard(Period::Weekly, 10).is_empty());
+        assert_eq!(scoreboard.leaderboard(Period::AllTime, 10), standings);
+
+        scoreboard.award(2, 12, 4);
+        // Last week's points only come off the all-time score
+        assert!(scoreboard.revoke(10));
+        assert_eq!(
+            scoreboard.standing(2),
+            Standing {
+                score: PlayerScore {
+                    points: 7,
+                    words: 2
+                },
+                rank: Some(1),
+                weekly: PlayerScore {
+                    points: 4,
+                    words: 1
+                },
+                weekly_rank: Some(1),
+            }
+        );
+        assert_eq!(scoreboard.standing(1).weekly_rank, None);
+    }
+}
@@ -0,0 +1,501 @@
+//! Self-test of the word pipeline, run with `--self-test` before going live
+//!
+//! Plays a short scripted game through the same actors the bot runs, with the
+//! configured dictionary and rules, but without connecting to Discord: the
+//! reactions are recorded instead of added and the LLM is never asked. The
+//! words are picked from the dictionary, so any word list can be tested.
+
+use actix::Actor;
+use poise::serenity_prelude as serenity;
+use std::fmt;
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::actors::message_reaction::{
+    ApiFuture, PostFuture, ReactionApi, EMOJI_CHECK, EMOJI_CROSS, EMOJI_QUESTION,
+};
+use crate::actors::word_validator::ValidateWordSync;
+use crate::actors::{LLMValidatorActor, MessageReactionActor, SetMessageReaction};
+use crate::config::Config;
+use crate::discord::{game_state_actor, word_validator_actor};
+use crate::error::{Error, Result};
+use crate::game::{GameEngine, WordVerdict};
+use crate::rotation::TurnMode;
+use crate::validation::dictionary::DictionaryValidator;
+use crate::validation::extract::{extract_word, ExtractOptions};
+use crate::validation::llm::LLMValidator;
+use crate::validation::neighbors::FINNISH_ALPHABET;
+use crate::validation::rules::{is_diacritic_pair, GameRules, RulesValidator, Violation};
+
+/// Channel the scripted game is played in, which nothing is posted to
+const SELF_TEST_CHANNEL: u64 = 1;
+
+/// What a scripted word should come to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expected {
+    /// Accepted from the dictionary
+    Valid,
+    /// Rejected for not being one letter away from the previous word
+    BreaksRules,
+    /// Rejected for having been played already
+    AlreadyUsed,
+    /// Not in the dictionary, left for the LLM
+    PendingLlm,
+    /// Not in the dictionary, and proper nouns don't count
+    NotInDictionary,
+}
+
+impl Expected {
+    fn matches(self, verdict: &WordVerdict) -> bool {
+        match self {
+            Expected::Valid => *verdict == WordVerdict::ValidInDictionary,
+            Expected::BreaksRules => {
+                matches!(verdict, WordVerdict::InvalidRules(Violation::Letters(_)))
+            }
+            Expected::AlreadyUsed => {
+                matches!(
+                    verdict,
+                    WordVerdict::InvalidRules(Violation::AlreadyUsed(_))
+                )
+            }
+            Expected::PendingLlm => *verdict == WordVerdict::PendingLlm,
+            Expected::NotInDictionary => *verdict == WordVerdict::NotInDictionary,
+        }
+    }
+
+    /// The reaction the word should get
+    fn reaction(self, config: &Config) -> char {
+        match self {
+            Expected::Valid => EMOJI_CHECK,
+            Expected::BreaksRules | Expected::NotInDictionary => EMOJI_CROSS,
+            Expected::AlreadyUsed => config.already_used_reaction,
+            Expected::PendingLlm => EMOJI_QUESTION,
+        }
+    }
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Expected::Valid => "valid",
+            Expected::BreaksRules => "against the rules",
+            Expected::AlreadyUsed => "already used",
+            Expected::PendingLlm => "left for the LLM",
+            Expected::NotInDictionary => "not in the dictionary",
+        })
+    }
+}
+
+/// One word of the scripted game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub word: String,
+    pub expected: Expected,
+}
+
+/// How one step of the scripted game went
+#[derive(Debug)]
+pub struct StepResult {
+    pub step: Step,
+    pub expected_reaction: char,
+    /// The verdict, or why there was none
+    pub verdict: Result<WordVerdict>,
+    /// Reactions the word was left with
+    pub reactions: Vec<char>,
+}
+
+impl StepResult {
+    pub fn passed(&self) -> bool {
+        self.verdict
+            .as_ref()
+            .is_ok_and(|verdict| self.step.expected.matches(verdict))
+            && self.reactions == [self.expected_reaction]
+    }
+}
+
+impl fmt::Display for StepResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = match &self.verdict {
+            // The violation as the players are told it, not its every letter change
+            Ok(WordVerdict::InvalidRules(violation)) => format!("InvalidRules ({})", violation),
+            Ok(verdict) => format!("{:?}", verdict),
+            Err(e) => format!("error: {}", e),
+        };
+        let reactions = if self.reactions.is_empty() {
+            "no reaction".to_string()
+        } else {
+            self.reactions.iter().collect()
+        };
+        write!(
+            f,
+            "{}  '{}' should be {} with {}, got {} with {}",
+            if self.passed() { "PASS" } else { "FAIL" },
+            self.step.word,
+            self.step.expected,
+            self.expected_reaction,
+            verdict,
+            reactions
+        )
+    }
+}
+
+/// The scripted game for a dictionary and configuration
+///
+/// Two dictionary words one letter apart make the chain, with a third word
+/// that isn't one letter from the first breaking the rules in between. The
+/// first word is then played again, and last a word one letter from the
+/// second that isn't in the dictionary. Fails if the dictionary has no such words.
+pub fn script(dictionary: &DictionaryValidator, config: &Config) -> Result<Vec<Step>> {
+    let rules = RulesValidator::new(GameRules::from_config(config));
+    let options = ExtractOptions::from_config(config);
+    let playable = |word: &str| {
+        rules.validate_length(word).is_ok()
+            && options.foreign_letters(word).is_empty()
+            && extract_word(word, &options).is_some()
+    };
+
+    // Sorted, so the same dictionary always gives the same game
+    let mut words: Vec<&str> = dictionary.words().filter(|word| playable(word)).collect();
+    words.sort_unstable();
+
+    let (first, second) = words
+        .iter()
+        .find_map(|&first| {
+            let second = dictionary
+                .moves(first, config.diacritic_policy)
+                .into_iter()
+                .filter(|word| playable(word))
+                .min()?;
+            Some((first.to_string(), second))
+        })
+        .ok_or_else(|| {
+            Error::Config("The dictionary has no two playable words one letter apart".to_string())
+        })?;
+    let unrelated = words
+        .iter()
+        .find(|&&word| {
+            word != first && word != second && rules.validate_move_dry_run(&first, word).is_err()
+        })
+        .ok_or_else(|| {
+            Error::Config(format!(
+                "The dictionary has no playable word that can't follow '{}'",
+                first
+            ))
+        })?
+        .to_string();
+    let unknown = second
+        .char_indices()
+        .flat_map(|(index, letter)| {
+            let (before, after) = (&second[..index], &second[index + letter.len_utf8()..]);
+            FINNISH_ALPHABET
+                .iter()
+                .filter(move |&&other| other != letter && !is_diacritic_pair(other, letter))
+                .map(move |other| format!("{}{}{}", before, other, after))
+        })
+        .find(|word| *word != first && playable(word) && !dictionary.is_valid_word(word))
+        .ok_or_else(|| {
+            Error::Config(format!(
+                "No word one letter from '{}' is missing from the dictionary",
+                second
+            ))
+        })?;
+
+    // Unknown words written in lowercase are rejected outright under the capitalization rule
+    let unknown_expected = if config.accept_proper_nouns && !config.require_capitalization {
+        Expected::PendingLlm
+    } else {
+        Expected::NotInDictionary
+    };
+    Ok(vec![
+        Step {
+            word: first.clone(),
+            expected: Expected::Valid,
+        },
+        Step {
+            word: unrelated,
+            expected: Expected::BreaksRules,
+        },
+        Step {
+            word: second,
+            expected: Expected::Valid,
+        },
+        Step {
+            word: first,
+            expected: Expected::AlreadyUsed,
+        },
+        Step {
+            word: unknown,
+            expected: unknown_expected,
+        },
+    ])
+}
+
+/// Play the scripted game through the bot's actors and tell how each step went
+///
+/// Must be called within an actix system.
+pub async fn run(dictionary: DictionaryValidator, config: &Config) -> Result<Vec<StepResult>> {
+    // Nothing of the scripted game is archived, no saved game or turn order is
+    // continued, and the scripted players may play in any order
+    let config = Config {
+        weekly_reset: false,
+        game_snapshot_path: String::new(),
+        game_turn_mode: TurnMode::Free,
+        turn_order_path: String::new(),
+        ..config.clone()
+    };
+    let dictionary = Arc::new(GameEngine::prepare_dictionary(dictionary, &config));
+    let steps = script(&dictionary, &config)?;
+
+    let api = OfflineReactionApi::default();
+    // The words left for the LLM are never batched while the test runs
+    let llm_validator =
+        LLMValidatorActor::with_validator(LLMValidator::default(), usize::MAX, 86400).start();
+    let game_state = game_state_actor(dictionary, &config, llm_validator.clone()).start();
+    let message_reaction = MessageReactionActor::with_api(
+        Arc::new(api.clone()),
+        serenity::ChannelId::new(SELF_TEST_CHANNEL),
+    )
+    .with_reaction_interval(Duration::ZERO)
+    .with_language(config.language)
+    .start();
+    game_state.do_send(SetMessageReaction(message_reaction.clone()));
+    let validator =
+        word_validator_actor(&config, game_state, llm_validator, message_reaction).start();
+
+    let mut results = Vec::new();
+    for (index, step) in steps.into_iter().enumerate() {
+        // Everyone plays once, so no turn, cooldown or daily limit gets in the way
+        let id = index as u64 + 1;
+        let verdict = validator
+            .send(ValidateWordSync {
+                word: step.word.clone(),
+                channel_id: SELF_TEST_CHANNEL,
+                message_id: id,
+                user_id: id,
+            })
+            .await
+            .map_err(|e| Error::Actor(e.to_string()))
+            .and_then(|verdict| verdict);
+        results.push(StepResult {
+            expected_reaction: step.expected.reaction(&config),
+            verdict,
+            reactions: api.reactions(id),
+            step,
+        });
+    }
+    Ok(results)
+}
+
+/// Print each step and whether they all passed, returning the process exit code
+pub fn report<W: Write>(results: &[StepResult], out: &mut W) -> std::io::Result<i32> {
+    for result in results {
+        writeln!(out, "{}", result)?;
+    }
+    let passed = results.iter().filter(|result| result.passed()).count();
+    let all_passed = passed == results.len();
+    writeln!(
+        out,
+        "{}: {}/{} steps passed",
+        if all_passed { "PASS" } else { "FAIL" },
+        passed,
+        results.len()
+    )?;
+    Ok(if all_passed { 0 } else { 1 })
+}
+
+/// [`ReactionApi`] that keeps the reactions to itself and posts nothing
+#[derive(Default, Clone)]
+struct OfflineReactionApi {
+    /// Reactions on each message, by message ID
+    reactions: Arc<Mutex<Vec<(u64, char)>>>,
+    /// How many messages would have been posted, to number them
+    posted: Arc<AtomicU64>,
+}
+
+impl OfflineReactionApi {
+    fn reactions(&self, message_id: u64) -> Vec<char> {
+        self.reactions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(id, _)| *id == message_id)
+            .map(|(_, reaction)| *reaction)
+            .collect()
+    }
+
+    fn post(&self) -> PostFuture<'_> {
+        let message_id = self.posted.fetch_add(1, Ordering::SeqCst) + 1;
+        Box::pin(async move { Ok(serenity::MessageId::new(message_id)) })
+    }
+}
+
+impl ReactionApi for OfflineReactionApi {
+    fn add_reaction(
+        &self,
+        _channel_id: serenity::ChannelId,
+        message_id: serenity::MessageId,
+        reaction: char,
+    ) -> ApiFuture<'_> {
+        self.reactions
+            .lock()
+            .unwrap()
+            .push((message_id.get(), reaction));
+        Box::pin(async { Ok(()) })
+    }
+
+    fn delete_reaction(
+        &self,
+        _channel_id: serenity::ChannelId,
+        message_id: serenity::MessageId,
+        reaction: char,
+    ) -> ApiFuture<'_> {
+        self.reactions
+            .lock()
+            .unwrap()
+            .retain(|&added| added != (message_id.get(), reaction));
+        Box::pin(async { Ok(()) })
+    }
+
+    fn clear_reactions(
+        &self,
+        _channel_id: serenity::ChannelId,
+        message_id: serenity::MessageId,
+    ) -> ApiFuture<'_> {
+        self.reactions
+            .lock()
+            .unwrap()
+            .retain(|(id, _)| *id != message_id.get());
+        Box::pin(async { Ok(()) })
+    }
+
+    fn send_message(&self, _channel_id: serenity::ChannelId, _content: String) -> PostFuture<'_> {
+        self.post()
+    }
+
+    fn reply(
+        &self,
+        _channel_id: serenity::ChannelId,
+        _message_id: serenity::MessageId,
+        _content: String,
+    ) -> ApiFuture<'_> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn send_embed(
+        &self,
+        _channel_id: serenity::ChannelId,
+        _title: String,
+        _description: String,
+    ) -> ApiFuture<'_> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn send_file(
+        &self,
+        _channel_id: serenity::ChannelId,
+        _filename: String,
+        _data: Vec<u8>,
+        _content: String,
+    ) -> PostFuture<'_> {
+        self.post()
+    }
+
+    fn edit_message(
+        &self,
+        _channel_id: serenity::ChannelId,
+        _message_id: serenity::MessageId,
+        _content: String,
+    ) -> ApiFuture<'_> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn pin_message(
+        &self,
+        _channel_id: serenity::ChannelId,
+        _message_id: serenity::MessageId,
+    ) -> ApiFuture<'_> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn set_activity(&self, _activity: serenity::ActivityData) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rotation::{Rotation, RotationStore};
+
+    fn dictionary() -> DictionaryValidator {
+        DictionaryValidator::from_words(["kissa", "kassa", "kassi", "koira"])
+    }
+
+    #[actix_rt::test]
+    async fn test_self_test_passes() {
+        let results = run(dictionary(), &Config::default()).await.unwrap();
+        let words: Vec<_> = results
+            .iter()
+            .map(|result| result.step.word.as_str())
+            .collect();
+        assert_eq!(words, ["kassa", "koira", "kassi", "kassa", "aassi"]);
+
+        let mut output = Vec::new();
+        assert_eq!(report(&results, &mut output).unwrap(), 0);
+        let output = String::from_utf8(output).unwrap();
+        assert!(
+            output.contains("PASS  'kassa' should be valid with ✅, got ValidInDictionary with ✅"),
+            "{}",
+            output
+        );
+        assert!(output.ends_with("PASS: 5/5 steps passed\n"), "{}", output);
+
+        // Without proper nouns the unknown word is rejected outright
+        let config = Config {
+            accept_proper_nouns: false,
+            ..Config::default()
+        };
+        let results = run(dictionary(), &config).await.unwrap();
+        assert!(results.iter().all(StepResult::passed), "{:#?}", results);
+        assert_eq!(results[4].step.expected, Expected::NotInDictionary);
+
+        // The real game's turns don't hold up the scripted players
+        let dir = tempfile::tempdir().unwrap();
+        let store = RotationStore::new(dir.path().join("turns.json"));
+        let mut rotation = Rotation::default();
+        rotation.join(1);
+        store.save(&rotation).unwrap();
+        let config = Config {
+            game_turn_mode: TurnMode::Rotation,
+            turn_order_path: store.path().to_string_lossy().into_owned(),
+            ..Config::default()
+        };
+        let results = run(dictionary(), &config).await.unwrap();
+        assert!(results.iter().all(StepResult::passed), "{:#?}", results);
+        assert_eq!(store.load().unwrap(), rotation);
+    }
+
+    #[test]
+    fn test_failed_steps() {
+        let results = [StepResult {
+            step: Step {
+                word: "kassa".to_string(),
+                expected: Expected::AlreadyUsed,
+            },
+            expected_reaction: '🔁',
+            verdict: Ok(WordVerdict::ValidInDictionary),
+            reactions: vec![EMOJI_CHECK],
+        }];
+        let mut output = Vec::new();
+        assert_eq!(report(&results, &mut output).unwrap(), 1);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "FAIL  'kassa' should be already used with 🔁, got ValidInDictionary with ✅\n\
+             FAIL: 0/1 steps passed\n"
+        );
+
+        // A dictionary without words one letter apart can't be tested
+        let lonely = DictionaryValidator::from_words(["kissa", "koira"]);
+        assert!(script(&lonely, &Config::default()).is_err());
+    }
+}
@@ -0,0 +1,239 @@
+//! Wrapping up before the bot exits
+//!
+//! On SIGTERM or Ctrl+C every actor is sent a [`Shutdown`]. The word
+//! validators go first so no new words come in, then the LLM validator saves
+//! its cache and queue, the game states save the games and the reaction
+//! actors mark the status messages offline, all at once. The whole shutdown
+//! has a grace period, after which the actors that haven't finished are given
+//! up on so the bot still exits.
+
+use actix::Recipient;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use crate::actors::Shutdown;
+
+/// Actors shut down in each stage, a stage starting once the one before it has finished
+pub const STAGES: [&[&str]; 2] = [
+    &["word_validator"],
+    &["llm_validator", "game_state", "message_reaction"],
+];
+
+/// The registered actors by name
+type Actors = HashMap<&'static str, Vec<Recipient<Shutdown>>>;
+
+/// The actors to shut down, shared by the bot and whoever catches the signal
+///
+/// Actors are registered as they are started, like for the health checks.
+#[derive(Clone)]
+pub struct ShutdownHandle {
+    grace: Duration,
+    actors: Arc<Mutex<Actors>>,
+    finished: Arc<watch::Sender<bool>>,
+}
+
+impl ShutdownHandle {
+    /// Give the actors `grace` altogether to finish
+    pub fn new(grace: Duration) -> Self {
+        Self {
+            grace,
+            actors: Arc::default(),
+            finished: Arc::new(watch::Sender::new(false)),
+        }
+    }
+
+    /// Include an actor in the shutdown
+    pub fn register_actor(&self, name: &'static str, actor: Recipient<Shutdown>) {
+        self.actors
+            .lock()
+            .unwrap()
+            .entry(name)
+            .or_default()
+            .push(actor);
+    }
+
+    /// Shut every actor down stage by stage, returning the ones that didn't finish in time
+    pub async fn shutdown(&self) -> Vec<&'static str> {
+        let deadline = Instant::now() + self.grace;
+        info!(
+            grace_secs = self.grace.as_secs(),
+            "Shutting down, waiting for the actors to finish"
+        );
+
+        // Don't hold the lock while waiting for the answers
+        let actors = self.actors.lock().unwrap().clone();
+        let mut unfinished = Vec::new();
+        for stage in STAGES {
+            // Every actor of the stage gets the message before any answer is waited for
+            let requests: Vec<_> = stage
+                .iter()
+                .flat_map(|&name| {
+                    actors
+                        .get(name)
+                        .into_iter()
+                        .flatten()
+                        .map(move |actor| (name, actor.send(Shutdown)))
+                })
+                .collect();
+            for (name, request) in requests {
+                match tokio::time::timeout_at(deadline, request).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => {
+                        warn!(actor = name, error = %e, "Actor stopped before shutting down");
+                        unfinished.push(name);
+                    }
+                    Err(_) => {
+                        warn!(actor = name, "Actor didn't finish within the grace period");
+                        unfinished.push(name);
+                    }
+                }
+            }
+        }
+        unfinished.dedup();
+
+        info!("Shutdown finished");
+        self.finished.send_replace(true);
+        unfinished
+    }
+
+    /// Wait until [`shutdown`](Self::shutdown) has finished
+    ///
+    /// The actor threads keep running until then, so the actors can still answer.
+    pub async fn finished(&self) {
+        let mut finished = self.finished.subscribe();
+        // The sender is kept in self, so it can't be dropped while waiting
+        let _ = finished.wait_for(|&finished| finished).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix::{Actor, ActorFutureExt, Context, Handler, ResponseActFuture, WrapFuture};
+    use std::time::Instant as StdInstant;
+
+    type Flushes = Arc<Mutex<Vec<(&'static str, StdInstant)>>>;
+
+    /// Notes down when it finished shutting down, after taking `delay`
+    struct MockActor {
+        name: &'static str,
+        delay: Duration,
+        flushes: Flushes,
+    }
+
+    impl Actor for MockActor {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Shutdown> for MockActor {
+        type Result = ResponseActFuture<Self, ()>;
+
+        fn handle(&mut self, _msg: Shutdown, _ctx: &mut Context<Self>) -> Self::Result {
+            Box::pin(
+                tokio::time::sleep(self.delay)
+                    .into_actor(self)
+                    .map(|_, act, _ctx| {
+                        act.flushes
+                            .lock()
+                            .unwrap()
+                            .push((act.name, StdInstant::now()));
+                    }),
+            )
+        }
+    }
+
+    fn register(handle: &ShutdownHandle, flushes: &Flushes, name: &'static str, delay_ms: u64) {
+        let actor = MockActor {
+            name,
+            delay: Duration::from_millis(delay_ms),
+            flushes: flushes.clone(),
+        }
+        .start();
+        handle.register_actor(name, actor.recipient());
+    }
+
+    #[actix_rt::test]
+    async fn test_shutdown_in_stages() {
+        let handle = ShutdownHandle::new(Duration::from_secs(2));
+        let flushes = Flushes::default();
+        // Registered in the wrong order, the word validator is still shut down first
+        register(&handle, &flushes, "message_reaction", 0);
+        register(&handle, &flushes, "game_state", 0);
+        register(&handle, &flushes, "game_state", 0);
+        register(&handle, &flushes, "llm_validator", 0);
+        register(&handle, &flushes, "word_validator", 50);
+
+        let start = StdInstant::now();
+        assert!(handle.shutdown().await.is_empty());
+        handle.finished().await;
+
+        let flushes = flushes.lock().unwrap();
+        assert_eq!(flushes.len(), 5);
+        assert_eq!(flushes[0].0, "word_validator");
+        let mut rest: Vec<_> = flushes[1..].iter().map(|(name, _)| *name).collect();
+        rest.sort();
+        assert_eq!(
+            rest,
+            [
+                "game_state",
+                "game_state",
+                "llm_validator",
+                "message_reaction"
+            ]
+        );
+        assert!(flushes
+            .iter()
+            .all(|(_, at)| at.duration_since(start) < Duration::from_secs(2)));
+    }
+
+    #[actix_rt::test]
+    async fn test_grace_period() {
+        let handle = ShutdownHandle::new(Duration::from_millis(300));
+        let flushes = Flushes::default();
+        register(&handle, &flushes, "word_validator", 0);
+        register(&handle, &flushes, "llm_validator", 5000);
+        register(&handle, &flushes, "game_state", 50);
+        register(&handle, &flushes, "message_reaction", 100);
+
+        // The slow actor doesn't hold up the others or the bot
+        let start = StdInstant::now();
+        assert_eq!(handle.shutdown().await, ["llm_validator"]);
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        let flushed: Vec<_> = flushes
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, _)| *name)
+            .collect();
+        assert_eq!(
+            flushed,
+            ["word_validator", "game_state", "message_reaction"]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_stopped_actor() {
+        let handle = ShutdownHandle::new(Duration::from_secs(1));
+        let flushes = Flushes::default();
+        let arbiter = actix::Arbiter::new();
+        let actor = MockActor::start_in_arbiter(&arbiter.handle(), {
+            let flushes = flushes.clone();
+            move |_| MockActor {
+                name: "game_state",
+                delay: Duration::ZERO,
+                flushes,
+            }
+        });
+        arbiter.stop();
+        arbiter.join().unwrap();
+        handle.register_actor("game_state", actor.recipient());
+
+        assert_eq!(handle.shutdown().await, ["game_state"]);
+        assert!(flushes.lock().unwrap().is_empty());
+    }
+}
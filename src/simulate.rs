@@ -0,0 +1,471 @@
+//! Offline simulation of the game for tuning dictionaries and rules
+//!
+//! Reads one word per line and plays it through the same [`GameEngine`] the bot
+//! uses. Every line is played by a different player, so the consecutive turn
+//! rule never gets in the way.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::{BufRead, Write};
+
+use crate::config::Config;
+use crate::error::{Error, LLMError, Result};
+use crate::game::{GameEngine, Validity, VerdictSource, WordVerdict};
+use crate::validation::dictionary::DictionaryValidator;
+use crate::validation::llm::{LLMValidator, ProperNounResponse, WordInContext};
+use crate::validation::normalize::display_capitalize;
+
+pub const USAGE: &str = "Usage: sanabotti simulate [--dictionary PATH] [--json] [--llm]";
+
+/// Command line options of the simulate subcommand
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SimulateOptions {
+    /// Dictionary to use instead of DICTIONARY_FILE_PATH
+    pub dictionary_path: Option<String>,
+    /// Print one JSON object per line instead of text
+    pub json: bool,
+    /// Ask the LLM about words that aren't in the dictionary
+    pub llm: bool,
+}
+
+impl SimulateOptions {
+    pub fn parse<I, S>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_ref() {
+                "--dictionary" => {
+                    let path = args.next().ok_or_else(|| {
+                        Error::Config(format!("--dictionary needs a path\n{}", USAGE))
+                    })?;
+                    options.dictionary_path = Some(path.as_ref().to_string());
+                }
+                "--json" => options.json = true,
+                "--llm" => options.llm = true,
+                other => {
+                    return Err(Error::Config(format!(
+                        "Unknown argument '{}'\n{}",
+                        other, USAGE
+                    )))
+                }
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// How a simulated line turned out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Valid,
+    Invalid,
+    /// Follows the rules but only the LLM could accept it
+    Pending,
+    /// Not a single playable word
+    Skipped,
+    /// Couldn't be judged, e.g. the LLM call failed
+    Error,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Status::Valid => "valid",
+            Status::Invalid => "invalid",
+            Status::Pending => "pending",
+            Status::Skipped => "skipped",
+            Status::Error => "error",
+        };
+        f.write_str(name)
+    }
+}
+
+/// What the game made of one played message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Verdict {
+    /// The word that was played, once extracted and normalized
+    pub word: Option<String>,
+    pub status: Status,
+    pub reason: String,
+}
+
+/// The verdict on one input line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LineResult {
+    pub line: usize,
+    pub input: String,
+    #[serde(flatten)]
+    pub verdict: Verdict,
+}
+
+/// Totals printed once the input ends
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Summary {
+    pub total: usize,
+    pub valid: usize,
+    pub invalid: usize,
+    pub pending: usize,
+    pub skipped: usize,
+    pub errors: usize,
+}
+
+impl Summary {
+    pub(crate) fn record(&mut self, status: Status) {
+        self.total += 1;
+        match status {
+            Status::Valid => self.valid += 1,
+            Status::Invalid => self.invalid += 1,
+            Status::Pending => self.pending += 1,
+            Status::Skipped => self.skipped += 1,
+            Status::Error => self.errors += 1,
+        }
+    }
+
+    /// Process exit code: non-zero only if a word couldn't be judged
+    pub fn exit_code(&self) -> i32 {
+        if self.errors > 0 {
+            1
+        } else {
+            0
+        }
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} words: {} valid, {} invalid, {} pending, {} skipped, {} errors",
+            self.total, self.valid, self.invalid, self.pending, self.skipped, self.errors
+        )
+    }
+}
+
+/// Last line of the JSON output
+#[derive(Serialize)]
+struct SummaryLine<'a> {
+    summary: &'a Summary,
+}
+
+/// Plays words through a [`GameEngine`], optionally checking unknown words with the LLM
+pub struct Simulator {
+    engine: GameEngine,
+    llm: Option<LLMValidator>,
+}
+
+impl Simulator {
+    pub fn new(engine: GameEngine) -> Self {
+        Self { engine, llm: None }
+    }
+
+    /// Ask the LLM about words that follow the rules but aren't in the dictionary
+    pub fn with_llm(mut self, llm: LLMValidator) -> Self {
+        self.llm = Some(llm);
+        self
+    }
+
+    /// Start a new game, forgetting the chain and the used words
+    pub fn reset(&mut self) {
+        self.engine.reset();
+    }
+
+    /// Play one line of input
+    pub async fn play(&mut self, line: usize, input: &str) -> LineResult {
+        // Line numbers double as player and message ids
+        let id = line as u64;
+        LineResult {
+            line,
+            input: input.to_string(),
+            verdict: self.play_as(id, id, input).await,
+        }
+    }
+
+    /// Play a message sent by `user_id`
+    pub async fn play_as(&mut self, user_id: u64, message_id: u64, input: &str) -> Verdict {
+        let word = self.engine.extract(input);
+        let verdict = self.engine.submit_word(input, user_id, message_id);
+
+        let (status, reason) = match verdict {
+            WordVerdict::ValidInDictionary => (Status::Valid, "in dictionary".to_string()),
+            WordVerdict::ValidProperNoun => (Status::Valid, "proper noun".to_string()),
+            WordVerdict::PendingLlm => match (&mut self.llm, &word) {
+                (Some(llm), Some(word)) => {
+                    match ask_llm(llm, word, self.engine.previous_word(message_id)).await {
+                        Ok(response) if response.is_proper_noun => {
+                            self.engine.mark_validity(
+                                message_id,
+                                Validity::Valid,
+                                VerdictSource::Llm {
+                                    explanation: response.explanation,
+                                    model: response.model,
+                                },
+                            );
+                            (Status::Valid, "proper noun".to_string())
+                        }
+                        Ok(response) => {
+                            self.engine.mark_validity(
+                                message_id,
+                                Validity::Invalid,
+                                VerdictSource::Llm {
+                                    explanation: response.explanation,
+                                    model: response.model,
+                                },
+                            );
+                            (
+                                Status::Invalid,
+                                "not in dictionary or a proper noun".to_string(),
+                            )
+                        }
+                        Err(e) => {
+                            // The bot gives up on words the LLM can't answer for
+                            self.engine.mark_validity(
+                                message_id,
+                                Validity::Invalid,
+                                VerdictSource::Llm {
+                                    explanation: e.to_string(),
+                                    model: None,
+                                },
+                            );
+                            (Status::Error, e.to_string())
+                        }
+                    }
+                }
+                _ => (
+                    Status::Pending,
+                    "not in dictionary, proper noun check skipped".to_string(),
+                ),
+            },
+            WordVerdict::InvalidRules(reason) => (Status::Invalid, reason.to_string()),
+            WordVerdict::AwaitingVerdict | WordVerdict::Queued => (
+                Status::Invalid,
+                "previous word awaits its verdict".to_string(),
+            ),
+            WordVerdict::NotInDictionary => (Status::Invalid, "not in dictionary".to_string()),
+            WordVerdict::Implausible => (Status::Invalid, "doesn't look like a word".to_string()),
+            WordVerdict::NotAWord => (Status::Skipped, "not a single word".to_string()),
+            WordVerdict::Duplicate => (Status::Skipped, "message already played".to_string()),
+            WordVerdict::DailyLimitReached(_) => {
+                (Status::Invalid, "daily word limit reached".to_string())
+            }
+            WordVerdict::CoolingDown(_) => (Status::Invalid, "cooling down".to_string()),
+            WordVerdict::NotYourTurn(_) => (Status::Invalid, "not their turn".to_string()),
+            WordVerdict::LikelyTypo(meant) => {
+                (Status::Invalid, format!("likely a typo of {}", meant))
+            }
+            WordVerdict::Blocked => (Status::Invalid, "blocked".to_string()),
+            WordVerdict::Paused => (Status::Skipped, "game paused".to_string()),
+            WordVerdict::ForeignLetters(letters) => (
+                Status::Invalid,
+                format!(
+                    "letters outside the alphabet: {}",
+                    letters.iter().collect::<String>()
+                ),
+            ),
+        };
+
+        Verdict {
+            word,
+            status,
+            reason,
+        }
+    }
+
+    /// Play every non-empty line of `input`, writing a verdict per line and a summary at the end
+    pub async fn run<R: BufRead, W: Write>(
+        &mut self,
+        input: R,
+        output: &mut W,
+        json: bool,
+    ) -> Result<Summary> {
+        let mut summary = Summary::default();
+
+        for (index, line) in input.lines().enumerate() {
+            let result = match line {
+                Ok(line) if line.trim().is_empty() => continue,
+                Ok(line) => self.play(index + 1, line.trim()).await,
+                Err(e) => LineResult {
+                    line: index + 1,
+                    input: String::new(),
+                    verdict: Verdict {
+                        word: None,
+                        status: Status::Error,
+                        reason: format!("failed to read line: {}", e),
+                    },
+                },
+            };
+            summary.record(result.verdict.status);
+
+            if json {
+                writeln!(output, "{}", to_json(&result)?)?;
+            } else {
+                writeln!(
+                    output,
+                    "{}\t{}\t{}",
+                    result.input, result.verdict.status, result.verdict.reason
+                )?;
+            }
+        }
+
+        write_summary(output, &summary, json)?;
+        Ok(summary)
+    }
+}
+
+/// The LLM's verdict on whether the word is a proper noun, played after `previous_word`
+async fn ask_llm(
+    llm: &mut LLMValidator,
+    word: &str,
+    previous_word: Option<String>,
+) -> Result<ProperNounResponse> {
+    let name = display_capitalize(word);
+    let words_json = serde_json::to_string(&[WordInContext {
+        word: name.clone(),
+        previous_word,
+    }])
+    .map_err(|e| LLMError::ApiError(format!("Failed to serialize words to JSON: {}", e)))?;
+
+    let results = llm.validate_json_batch(&words_json).await?;
+    results
+        .get(&name)
+        .cloned()
+        .ok_or_else(|| LLMError::ParseError(format!("No verdict for '{}'", name)).into())
+}
+
+/// Write the summary that ends the output
+pub(crate) fn write_summary<W: Write>(output: &mut W, summary: &Summary, json: bool) -> Result<()> {
+    if json {
+        writeln!(output, "{}", to_json(&SummaryLine { summary })?)?;
+    } else {
+        writeln!(output, "\n{}", summary)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn to_json<T: Serialize>(value: &T) -> Result<String> {
+    serde_json::to_string(value).map_err(|e| {
+        Error::Io(std::io::Error::other(format!(
+            "Failed to write JSON: {}",
+            e
+        )))
+    })
+}
+
+/// Entry point of `sanabotti simulate`: plays stdin and returns the process exit code
+pub async fn run_cli<I, S>(args: I) -> miette::Result<i32>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let options = SimulateOptions::parse(args)?;
+    let config = crate::config::load_game_config()?;
+    let dictionary_path = options
+        .dictionary_path
+        .clone()
+        .unwrap_or_else(|| config.dictionary_path.clone());
+
+    let mut simulator = Simulator::new(engine_for(&dictionary_path, &config)?);
+    if options.llm {
+        simulator = simulator.with_llm(llm_for(&config)?);
+    }
+
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+    let summary = simulator
+        .run(stdin.lock(), &mut stdout.lock(), options.json)
+        .await?;
+
+    Ok(summary.exit_code())
+}
+
+/// The engine the bot would run with this dictionary and configuration
+pub(crate) fn engine_for(dictionary_path: &str, config: &Config) -> Result<GameEngine> {
+    let dictionary = DictionaryValidator::new(dictionary_path)?;
+    Ok(GameEngine::from_config(dictionary, config))
+}
+
+/// The Gemini-backed validator used by `--llm`
+pub(crate) fn llm_for(config: &Config) -> Result<LLMValidator> {
+    if config.gemini_api_key.is_empty() {
+        return Err(Error::Config(
+            "--llm needs GEMINI_API_KEY or GEMINI_API_KEY_FILE to be set".to_string(),
+        ));
+    }
+    Ok(
+        LLMValidator::new(&config.gemini_api_key, &config.llm_models()).with_fallback_reset(
+            std::time::Duration::from_secs(config.llm_fallback_reset_secs),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::testing::MockCompletionApi;
+
+    const GOLDEN_DICTIONARY: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/golden/simulate_dictionary.txt"
+    );
+    const GOLDEN_INPUT: &str = include_str!("../tests/golden/simulate_input.txt");
+
+    async fn simulate(simulator: &mut Simulator, json: bool) -> (String, Summary) {
+        let mut output = Vec::new();
+        let summary = simulator
+            .run(GOLDEN_INPUT.as_bytes(), &mut output, json)
+            .await
+            .unwrap();
+        (String::from_utf8(output).unwrap(), summary)
+    }
+
+    fn golden_simulator() -> Simulator {
+        Simulator::new(engine_for(GOLDEN_DICTIONARY, &Config::default()).unwrap())
+    }
+
+    #[test]
+    fn test_parse_options() {
+        assert_eq!(
+            SimulateOptions::parse(["--json", "--dictionary", "words.txt"]).unwrap(),
+            SimulateOptions {
+                dictionary_path: Some("words.txt".to_string()),
+                json: true,
+                llm: false,
+            }
+        );
+        assert!(SimulateOptions::parse(["--dictionary"]).is_err());
+        assert!(SimulateOptions::parse(["--verbose"]).is_err());
+    }
+
+    #[actix_rt::test]
+    async fn test_golden_text() {
+        let (output, summary) = simulate(&mut golden_simulator(), false).await;
+        assert_eq!(output, include_str!("../tests/golden/simulate_output.txt"));
+        assert_eq!(summary.exit_code(), 0);
+    }
+
+    #[actix_rt::test]
+    async fn test_golden_json() {
+        let (output, _) = simulate(&mut golden_simulator(), true).await;
+        assert_eq!(
+            output,
+            include_str!("../tests/golden/simulate_output.jsonl")
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_llm_failures_are_errors() {
+        let llm = LLMValidator::with_api(Box::new(MockCompletionApi::failing()));
+        let mut simulator = golden_simulator().with_llm(llm);
+
+        let (_, summary) = simulate(&mut simulator, false).await;
+        assert_eq!(summary.pending, 0);
+        assert!(summary.errors > 0);
+        assert_eq!(summary.exit_code(), 1);
+    }
+}
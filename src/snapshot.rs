@@ -0,0 +1,112 @@
+//! The game saved when the bot shuts down
+//!
+//! The chain and the scores live in memory, so a restart would start a new
+//! game with everyone at zero points. On shutdown the last word confirmed
+//! valid and every player's points are saved, and the restarted bot continues
+//! from that word with the points restored, paused if it was. The words played
+//! before it aren't kept, so they may be played again, and neither are the
+//! attempt counts or the words played today towards the daily limit. The weekly points are saved with the week
+//! they were scored in, so a week that ended while the bot was down still ends.
+//! A saved game that can't be read is moved aside as `*.corrupt` instead of
+//! being overwritten by the new game.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::persist::{self, write_atomic};
+use crate::scoring::PlayerScore;
+
+/// A player's points as they're saved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedScore {
+    pub user_id: u64,
+    pub all_time: PlayerScore,
+    pub weekly: PlayerScore,
+}
+
+/// What the game state saves when the bot shuts down
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameSnapshot {
+    /// The word to continue from, None if there was no chain
+    pub word: Option<String>,
+    /// Start of the week the weekly points were scored in, None without weekly resets
+    #[serde(default)]
+    pub week_start: Option<DateTime<Utc>>,
+    pub scores: Vec<SavedScore>,
+    /// Whether the game was paused, so that a restart doesn't resume it
+    #[serde(default)]
+    pub paused: bool,
+}
+
+/// Where the game is kept, as a JSON object
+#[derive(Debug, Clone)]
+pub struct SnapshotStore {
+    path: PathBuf,
+}
+
+impl SnapshotStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The saved game, None if none has been saved yet
+    pub fn load(&self) -> Result<Option<GameSnapshot>> {
+        match fs::read_to_string(&self.path) {
+            Ok(content) => Ok(Some(
+                serde_json::from_str(&content).map_err(io::Error::other)?,
+            )),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Move a saved game that couldn't be loaded to `*.corrupt`
+    pub fn set_aside(&self) -> Result<PathBuf> {
+        Ok(persist::set_aside(&self.path)?)
+    }
+
+    pub fn save(&self, snapshot: &GameSnapshot) -> Result<()> {
+        let content = serde_json::to_string_pretty(snapshot).map_err(io::Error::other)?;
+        write_atomic(&self.path, content.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new(dir.path().join("data").join("game.json"));
+        assert_eq!(store.load().unwrap(), None);
+
+        let snapshot = GameSnapshot {
+            word: Some("kissa".to_string()),
+            week_start: Some("2025-01-05T22:00:00Z".parse().unwrap()),
+            scores: vec![SavedScore {
+                user_id: 1,
+                all_time: PlayerScore {
+                    points: 12,
+                    words: 3,
+                },
+                weekly: PlayerScore {
+                    points: 4,
+                    words: 1,
+                },
+            }],
+            paused: true,
+        };
+        store.save(&snapshot).unwrap();
+        assert_eq!(store.load().unwrap(), Some(snapshot));
+    }
+}
@@ -0,0 +1,52 @@
+//! Where the pinned status message of each game channel is
+//!
+//! The message is edited as the game goes on, so its ID is kept in a file
+//! for a restarted bot to keep editing the same message instead of pinning
+//! a new one. Each game has a file of its own, see [`crate::config::Config::for_guild`],
+//! so the file is only ever written by that game's reaction actor.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::persist::write_atomic;
+
+/// The status message of each channel, as a JSON object of message IDs keyed by channel ID
+#[derive(Debug, Clone)]
+pub struct StatusMessageStore {
+    path: PathBuf,
+}
+
+impl StatusMessageStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The channel's status message, None if it has none yet
+    pub fn get(&self, channel_id: u64) -> Result<Option<u64>> {
+        Ok(self.read()?.get(&channel_id.to_string()).copied())
+    }
+
+    /// Remember the channel's status message
+    pub fn set(&self, channel_id: u64, message_id: u64) -> Result<()> {
+        let mut messages = self.read()?;
+        messages.insert(channel_id.to_string(), message_id);
+        let content = serde_json::to_string_pretty(&messages).map_err(io::Error::other)?;
+        write_atomic(&self.path, content.as_bytes())?;
+        Ok(())
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, u64>> {
+        match fs::read_to_string(&self.path) {
+            Ok(content) => Ok(serde_json::from_str(&content).map_err(io::Error::other)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
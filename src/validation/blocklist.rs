@@ -0,0 +1,134 @@
+//! Words that may never be played, whatever the dictionary or the LLM says
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::error::Result;
+use crate::validation::normalize::normalize_word;
+
+/// Banned words, normalized with [`normalize_word`] like every other word list
+#[derive(Debug, Clone, Default)]
+pub struct Blocklist {
+    words: HashSet<String>,
+    /// File the words were loaded from, None for an in-memory list
+    path: Option<PathBuf>,
+}
+
+impl Blocklist {
+    /// Load a newline-separated word list
+    ///
+    /// Blank lines and lines starting with `#` are skipped.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let words = read_words(&path)?;
+        info!(
+            "Loaded {} blocked words from {}",
+            words.len(),
+            path.display()
+        );
+        Ok(Self {
+            words,
+            path: Some(path),
+        })
+    }
+
+    /// Build a blocklist from an in-memory word list
+    pub fn from_words<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            words: words
+                .into_iter()
+                .map(|word| normalize_word(word.as_ref()))
+                .filter(|word| !word.is_empty())
+                .collect(),
+            path: None,
+        }
+    }
+
+    /// Read the file again, returning how many words are blocked now
+    ///
+    /// The current list is kept if the file can't be read. Lists not loaded
+    /// from a file stay as they are.
+    pub fn reload(&mut self) -> Result<usize> {
+        if let Some(path) = &self.path {
+            self.words = read_words(path)?;
+            info!(
+                "Reloaded {} blocked words from {}",
+                self.words.len(),
+                path.display()
+            );
+        }
+        Ok(self.words.len())
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        !self.words.is_empty() && self.words.contains(&normalize_word(word))
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+fn read_words(path: &Path) -> Result<HashSet<String>> {
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .map(normalize_word)
+        .filter(|word| !word.is_empty())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_blocklist_normalization() {
+        // "Äijä" with decomposed umlauts, as some keyboards send it
+        let blocklist = Blocklist::from_words(["  Kielletty ", "A\u{308}ija\u{308}", ""]);
+        assert_eq!(blocklist.len(), 2);
+        assert!(blocklist.contains("kielletty"));
+        assert!(blocklist.contains("KIELLETTY"));
+        assert!(blocklist.contains("äijä"));
+        assert!(blocklist.contains("ÄIJÄ"));
+        assert!(!blocklist.contains("kissa"));
+        assert!(!Blocklist::default().contains("kissa"));
+    }
+
+    #[test]
+    fn test_blocklist_reload() -> std::io::Result<()> {
+        let mut file = NamedTempFile::new()?;
+        writeln!(file, "# Banned on this server")?;
+        writeln!(file, "Kielletty")?;
+        writeln!(file)?;
+
+        let mut blocklist = Blocklist::load(file.path()).unwrap();
+        assert_eq!(blocklist.len(), 1);
+        assert!(blocklist.contains("kielletty"));
+
+        writeln!(file, "toinen")?;
+        assert_eq!(blocklist.reload().unwrap(), 2);
+        assert!(blocklist.contains("Toinen"));
+
+        // A file that disappeared leaves the list as it was
+        let path = file.into_temp_path();
+        path.close()?;
+        assert!(blocklist.reload().is_err());
+        assert_eq!(blocklist.len(), 2);
+
+        assert!(Blocklist::load("/nonexistent/blocklist.txt").is_err());
+        Ok(())
+    }
+}
@@ -1,51 +1,485 @@
-use std::collections::HashSet;
-use std::fs::File;
-use std::io::{self, BufRead};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::hash::BuildHasher;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
 use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 use tracing::info;
+use xml::reader::{EventReader, XmlEvent};
 
 use crate::error::{DictionaryError, Result};
+use crate::validation::inflection::InflectionRule;
+use crate::validation::neighbors::{fold_diacritics, moves_with, neighbors_with};
+use crate::validation::normalize::normalize_word;
+use crate::validation::rules::DiacriticPolicy;
+
+/// Words read from a list between two progress lines
+const PROGRESS_STEP: usize = 50_000;
+
+/// First bytes of a dictionary compiled with `sanabotti compile-dict`
+const COMPILED_MAGIC: &[u8; 8] = b"SANADICT";
+
+/// Version of the compiled format, bumped whenever it or word normalization changes
+pub const COMPILED_FORMAT_VERSION: u32 = 2;
+
+/// What follows the magic bytes of a compiled dictionary, before the words
+#[derive(Debug, Serialize, Deserialize)]
+struct CompiledHeader {
+    version: u32,
+    /// SHA-256 of the word list it was compiled from, in hex
+    source_hash: String,
+}
+
+/// What kind of file the words were loaded from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DictionaryFormat {
+    /// One word per line
+    Text,
+    /// The Kotus XML word list
+    KotusXml,
+    /// Made with `sanabotti compile-dict`
+    Compiled,
+    /// Built from a list in memory, e.g. in tests
+    InMemory,
+}
+
+/// Where the words came from and how long loading them took
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DictionarySource {
+    /// The file name without its directory, `memory` for dictionaries built in memory
+    pub name: Arc<str>,
+    /// None for dictionaries built in memory
+    pub path: Option<String>,
+    pub format: DictionaryFormat,
+    pub load_time: Duration,
+}
 
 pub struct DictionaryValidator {
-    words: HashSet<String>,
+    /// Each word with the index of the first source that has it
+    words: HashMap<String, u16>,
+    /// Where the words came from, in order of precedence
+    sources: Vec<Arc<DictionarySource>>,
+    /// Every character used in the words, for generating suggestions
+    alphabet: Vec<char>,
+    /// Every three characters in a row in the words, for telling whether a word looks like one
+    trigrams: HashSet<[char; 3]>,
+    /// Endings stripped from words that aren't found as such, none in strict mode
+    inflections: Vec<InflectionRule>,
+    /// The words by their letters without dots and rings, built when first needed
+    plain_letters: OnceLock<HashMap<String, Vec<String>>>,
+}
+
+/// How a word was found in the dictionary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DictionaryMatch {
+    /// The dictionary word, the played word itself unless an ending was stripped
+    pub lemma: String,
+    /// The rule that gave the lemma, None for an exact match
+    pub rule: Option<InflectionRule>,
+    /// The dictionary file the lemma is from
+    pub source: Arc<DictionarySource>,
 }
 
 impl DictionaryValidator {
+    /// Load a word list with one word per line, the Kotus XML word list (by its
+    /// `.xml` extension), or a dictionary compiled from either
     pub fn new(dictionary_path: &str) -> Result<Self> {
-        let mut words = HashSet::new();
-
-        info!("Loading dictionary from {}", dictionary_path);
-
-        let file = File::open(Path::new(dictionary_path)).map_err(DictionaryError::LoadError)?;
+        Self::load(&[dictionary_path])
+    }
 
-        let reader = io::BufReader::new(file);
+    /// Load several word lists as one dictionary, in any of the formats [`new`](Self::new) reads
+    ///
+    /// A word in more than one list is counted as from the first of them.
+    pub fn load<S: AsRef<str>>(paths: &[S]) -> Result<Self> {
+        let started_all = Instant::now();
+        let mut words = HashMap::new();
+        let mut sources = Vec::new();
+        for path in paths {
+            let path = path.as_ref();
+            info!("Loading dictionary from {}", path);
+            let started = Instant::now();
+            let (file_words, format) = read_file(path)?;
+            if file_words.is_empty() {
+                return Err(DictionaryError::EmptyDictionary.into());
+            }
 
-        for line in reader.lines() {
-            let line = line.map_err(DictionaryError::LoadError)?;
-            let word = line.trim().to_lowercase();
-            if !word.is_empty() {
-                words.insert(word);
+            let index = u16::try_from(sources.len()).map_err(|_| {
+                DictionaryError::FormatError("Too many dictionary files".to_string())
+            })?;
+            let count = file_words.len();
+            for word in file_words {
+                words.entry(word).or_insert(index);
             }
+            let source = DictionarySource {
+                name: Path::new(path)
+                    .file_name()
+                    .map_or_else(|| path.into(), |name| name.to_string_lossy().into()),
+                path: Some(path.to_string()),
+                format,
+                load_time: started.elapsed(),
+            };
+            info!(
+                "Loaded {} words from {} in {:?}",
+                count, source.name, source.load_time
+            );
+            sources.push(Arc::new(source));
         }
+        if words.is_empty() {
+            return Err(DictionaryError::EmptyDictionary.into());
+        }
+
+        let dictionary = Self::with_alphabet(words, sources);
+        info!(
+            "Dictionary of {} words loaded from {} files in {:?}",
+            dictionary.len(),
+            dictionary.sources.len(),
+            started_all.elapsed()
+        );
+        Ok(dictionary)
+    }
 
+    /// Normalize a word list once and save it in the compiled format, returning the word count
+    pub fn compile(input_path: &str, output_path: &str) -> Result<usize> {
+        let source = fs::read(input_path).map_err(DictionaryError::LoadError)?;
+        let mut progress = Progress::new(input_path);
+        let words = if is_xml(input_path) {
+            read_kotus_xml(source.as_slice(), &mut progress)?
+        } else {
+            read_text(source.as_slice(), &mut progress)?
+        };
         if words.is_empty() {
             return Err(DictionaryError::EmptyDictionary.into());
         }
+        // Sorted, so compiling the same list twice gives the same file
+        let mut sorted: Vec<&String> = words.iter().collect();
+        sorted.sort_unstable();
+
+        let header = CompiledHeader {
+            version: COMPILED_FORMAT_VERSION,
+            source_hash: Sha256::digest(&source)
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect(),
+        };
+        let mut writer =
+            BufWriter::new(File::create(output_path).map_err(DictionaryError::LoadError)?);
+        writer
+            .write_all(COMPILED_MAGIC)
+            .map_err(DictionaryError::LoadError)?;
+        bincode::serialize_into(&mut writer, &header).map_err(format_error)?;
+        bincode::serialize_into(&mut writer, &sorted).map_err(format_error)?;
+        writer.flush().map_err(DictionaryError::LoadError)?;
+
+        info!(
+            "Compiled {} words from {} (sha256 {}) into {}",
+            sorted.len(),
+            input_path,
+            header.source_hash,
+            output_path
+        );
+        Ok(sorted.len())
+    }
+
+    /// Build a dictionary from an in-memory word list
+    pub fn from_words<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let words = words
+            .into_iter()
+            .map(|word| normalize_word(word.as_ref()))
+            .filter(|word| !word.is_empty())
+            .map(|word| (word, 0))
+            .collect();
+        let source = DictionarySource {
+            name: "memory".into(),
+            path: None,
+            format: DictionaryFormat::InMemory,
+            load_time: Duration::ZERO,
+        };
+
+        Self::with_alphabet(words, vec![Arc::new(source)])
+    }
+
+    fn with_alphabet(words: HashMap<String, u16>, sources: Vec<Arc<DictionarySource>>) -> Self {
+        let mut alphabet: Vec<char> = words
+            .keys()
+            .flat_map(|word| word.chars())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        alphabet.sort_unstable();
+        let mut trigrams = HashSet::new();
+        for word in words.keys() {
+            let chars: Vec<char> = word.chars().collect();
+            trigrams.extend(
+                chars
+                    .windows(3)
+                    .map(|window| [window[0], window[1], window[2]]),
+            );
+        }
+        Self {
+            words,
+            sources,
+            alphabet,
+            trigrams,
+            inflections: Vec::new(),
+            plain_letters: OnceLock::new(),
+        }
+    }
+
+    /// Also accept words that are in the dictionary once one of `rules` is applied
+    pub fn with_inflections(mut self, rules: Vec<InflectionRule>) -> Self {
+        self.inflections = rules;
+        self
+    }
+
+    /// Number of distinct words in the dictionary
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Where the words were loaded from, the first file if there are several
+    pub fn source(&self) -> &DictionarySource {
+        &self.sources[0]
+    }
+
+    /// Every file the words were loaded from, in order of precedence
+    pub fn sources(&self) -> &[Arc<DictionarySource>] {
+        &self.sources
+    }
+
+    /// A word picked at random, None if the dictionary is empty
+    pub fn random_word(&self) -> Option<&str> {
+        if self.words.is_empty() {
+            return None;
+        }
+        // A fresh RandomState is randomly keyed, which is random enough to pick a word
+        let index = RandomState::new().hash_one(self.words.len()) as usize % self.words.len();
+        self.words.keys().nth(index).map(String::as_str)
+    }
 
-        info!("Loaded {} words from dictionary", words.len());
+    /// Whether any word has the three characters in a row
+    pub fn has_trigram(&self, trigram: [char; 3]) -> bool {
+        self.trigrams.contains(&trigram)
+    }
+
+    /// Every word, in no particular order
+    pub fn words(&self) -> impl Iterator<Item = &str> {
+        self.words.keys().map(String::as_str)
+    }
 
-        Ok(Self { words })
+    /// Whether the word is in the dictionary as such, ignoring the inflection rules
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains_key(&normalize_word(word))
     }
 
     pub fn is_valid_word(&self, word: &str) -> bool {
-        let word = word.trim().to_lowercase();
-        self.words.contains(&word)
+        self.lookup(word).is_some()
+    }
+
+    /// Find the word, or the first base form the inflection rules give for it
+    pub fn lookup(&self, word: &str) -> Option<DictionaryMatch> {
+        let word = normalize_word(word);
+        if let Some(&index) = self.words.get(&word) {
+            return Some(DictionaryMatch {
+                lemma: word,
+                rule: None,
+                source: self.sources[usize::from(index)].clone(),
+            });
+        }
+        self.inflections.iter().find_map(|rule| {
+            let lemma = rule.strip(&word)?;
+            let index = *self.words.get(&lemma)?;
+            Some(DictionaryMatch {
+                lemma,
+                rule: Some(rule.clone()),
+                source: self.sources[usize::from(index)].clone(),
+            })
+        })
+    }
+
+    /// Up to `limit` dictionary words one letter away from `word`, e.g. to
+    /// suggest what a typo was meant to be
+    ///
+    /// Only letters that appear in the dictionary are tried.
+    pub fn suggestions(&self, word: &str, limit: usize) -> Vec<String> {
+        let mut suggestions = self.neighbors(word);
+        suggestions.truncate(limit);
+        suggestions
+    }
+
+    /// Every dictionary word one letter away from `word`, trying only letters
+    /// that appear in the dictionary
+    pub fn neighbors(&self, word: &str) -> Vec<String> {
+        neighbors_with(word, self, &self.alphabet)
+    }
+
+    /// Every dictionary word a move away from `word` under the diacritic `policy`,
+    /// trying only letters that appear in the dictionary
+    pub fn moves(&self, word: &str, policy: DiacriticPolicy) -> Vec<String> {
+        moves_with(word, self, &self.alphabet, policy)
+    }
+
+    /// The dictionary words that are `plain` once the dots and rings are taken
+    /// off their letters, in a stable order
+    pub fn with_plain_letters(&self, plain: &str) -> &[String] {
+        self.plain_letters
+            .get_or_init(|| {
+                let mut index: HashMap<String, Vec<String>> = HashMap::new();
+                for word in self.words.keys() {
+                    index
+                        .entry(fold_diacritics(word))
+                        .or_default()
+                        .push(word.clone());
+                }
+                for words in index.values_mut() {
+                    words.sort_unstable();
+                }
+                index
+            })
+            .get(plain)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
     }
 }
 
+/// Normalized words of a dictionary file and the format they were read in
+fn read_file(path: &str) -> Result<(HashSet<String>, DictionaryFormat)> {
+    let file = File::open(Path::new(path)).map_err(DictionaryError::LoadError)?;
+    let mut reader = BufReader::new(file);
+
+    let compiled = reader
+        .fill_buf()
+        .map_err(DictionaryError::LoadError)?
+        .starts_with(COMPILED_MAGIC);
+    Ok(if compiled {
+        (read_compiled(&mut reader)?, DictionaryFormat::Compiled)
+    } else if is_xml(path) {
+        (
+            read_kotus_xml(reader, &mut Progress::new(path))?,
+            DictionaryFormat::KotusXml,
+        )
+    } else {
+        (
+            read_text(reader, &mut Progress::new(path))?,
+            DictionaryFormat::Text,
+        )
+    })
+}
+
+/// Normalized words of a text word list
+fn read_text(reader: impl BufRead, progress: &mut Progress) -> Result<HashSet<String>> {
+    let mut words = HashSet::new();
+    for line in reader.lines() {
+        let line = line.map_err(DictionaryError::LoadError)?;
+        let word = normalize_word(&line);
+        if !word.is_empty() {
+            progress.word();
+            words.insert(word);
+        }
+    }
+    Ok(words)
+}
+
+fn is_xml(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("xml"))
+}
+
+/// Normalized words of the Kotus word list, the contents of its `<s>` elements
+///
+/// The XML is read as a stream, so the document is never in memory as a whole.
+/// Homographs are separate entries with the same word, and end up as one.
+fn read_kotus_xml(reader: impl Read, progress: &mut Progress) -> Result<HashSet<String>> {
+    let mut words = HashSet::new();
+    let mut in_word = false;
+    let mut word = String::new();
+    for event in EventReader::new(reader) {
+        // The error starts with the line and column, e.g. "3:12 Unexpected closing tag"
+        let event = event
+            .map_err(|e| DictionaryError::FormatError(format!("malformed Kotus XML at {}", e)))?;
+        match event {
+            XmlEvent::StartElement { name, .. } if name.local_name == "s" => {
+                in_word = true;
+                word.clear();
+            }
+            XmlEvent::Characters(text) if in_word => word.push_str(&text),
+            XmlEvent::EndElement { name } if name.local_name == "s" => {
+                in_word = false;
+                let normalized = normalize_word(&word);
+                if !normalized.is_empty() {
+                    progress.word();
+                    words.insert(normalized);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(words)
+}
+
+/// Words of a compiled dictionary, which were normalized when it was compiled
+fn read_compiled(reader: &mut impl Read) -> Result<HashSet<String>> {
+    let mut magic = [0; COMPILED_MAGIC.len()];
+    reader
+        .read_exact(&mut magic)
+        .map_err(DictionaryError::LoadError)?;
+    let header: CompiledHeader = bincode::deserialize_from(&mut *reader).map_err(format_error)?;
+    if header.version != COMPILED_FORMAT_VERSION {
+        return Err(DictionaryError::FormatError(format!(
+            "compiled dictionary has format version {}, expected {}; \
+             recompile it with `sanabotti compile-dict <words.txt> <output.bin>`",
+            header.version, COMPILED_FORMAT_VERSION
+        ))
+        .into());
+    }
+    info!(
+        "Dictionary was compiled from a word list with sha256 {}",
+        header.source_hash
+    );
+    let words: Vec<String> = bincode::deserialize_from(reader).map_err(format_error)?;
+    Ok(words.into_iter().collect())
+}
+
+/// Logs how far reading a large word list has got
+struct Progress<'a> {
+    path: &'a str,
+    words: usize,
+}
+
+impl<'a> Progress<'a> {
+    fn new(path: &'a str) -> Self {
+        Self { path, words: 0 }
+    }
+
+    /// Count a word read, with a line every PROGRESS_STEP words
+    fn word(&mut self) {
+        self.words += 1;
+        if self.words.is_multiple_of(PROGRESS_STEP) {
+            info!("Read {} words from {}", self.words, self.path);
+        }
+    }
+}
+
+fn format_error(error: bincode::Error) -> DictionaryError {
+    DictionaryError::FormatError(error.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::validation::inflection::{parse_rules, DEFAULT_INFLECTION_RULES};
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -58,6 +492,8 @@ mod tests {
         writeln!(file, "talo")?;
 
         let validator = DictionaryValidator::new(file.path().to_str().unwrap()).unwrap();
+        assert_eq!(validator.source().format, DictionaryFormat::Text);
+        assert_eq!(validator.source().path.as_deref(), file.path().to_str());
 
         assert!(validator.is_valid_word("kissa"));
         assert!(validator.is_valid_word("KISSA")); // Case insensitive
@@ -69,6 +505,281 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_hyphenated_words() -> std::io::Result<()> {
+        let mut file = NamedTempFile::new()?;
+        writeln!(file, "linja-auto")?;
+        writeln!(file, "vaa'an")?;
+
+        let validator = DictionaryValidator::new(file.path().to_str().unwrap()).unwrap();
+
+        assert!(validator.is_valid_word("linja-auto"));
+        assert!(validator.is_valid_word("Linja-Auto"));
+        assert!(validator.is_valid_word("vaa'an"));
+        // The hyphen is part of the word
+        assert!(!validator.is_valid_word("linjaauto"));
+        assert!(!validator.is_valid_word("vaaan"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_finnish_letters() {
+        // Decomposed and uppercase entries are stored like any other word
+        let validator = DictionaryValidator::from_words(["äiti", "PO\u{308}YTÄ", "Åland", "kesää"]);
+
+        assert!(validator.is_valid_word("ÄITI"));
+        assert!(validator.is_valid_word("a\u{308}iti"));
+        assert!(validator.is_valid_word("pöytä"));
+        assert!(validator.is_valid_word("Pöytä"));
+        assert!(validator.is_valid_word("A\u{30A}LAND"));
+        assert!(validator.is_valid_word("kesÄÄ"));
+        assert!(validator.is_valid_word("kesa\u{308}a\u{308}"));
+        assert!(!validator.is_valid_word("kesaa"));
+    }
+
+    #[test]
+    fn test_inflected_forms() {
+        let strict = DictionaryValidator::from_words(["kissa", "talo", "yö", "pöytä", "o"]);
+        assert!(!strict.is_valid_word("kissalla"));
+        assert_eq!(strict.lookup("kissalla"), None);
+
+        let lenient = strict.with_inflections(parse_rules(DEFAULT_INFLECTION_RULES).unwrap());
+        for (word, lemma, suffix) in [
+            ("kissalla", "kissa", "lla"),
+            ("talossa", "talo", "ssa"),
+            ("pöytästä", "pöytä", "stä"),
+            ("Talon", "talo", "n"),
+            ("kissat", "kissa", "t"),
+            ("kissoja", "kissa", "oja"),
+            ("taloja", "talo", "ja"),
+            ("yön", "yö", "n"),
+        ] {
+            let found = lenient
+                .lookup(word)
+                .unwrap_or_else(|| panic!("{} not found", word));
+            assert_eq!(found.lemma, lemma);
+            assert_eq!(found.rule.unwrap().suffix, suffix);
+        }
+
+        let exact = lenient.lookup("kissa").unwrap();
+        assert_eq!(exact.rule, None);
+        // Consonant gradation isn't undone
+        assert!(!lenient.is_valid_word("pöydästä"));
+        // Stripping never leaves a one-letter stem, even if it's in the dictionary
+        assert!(!lenient.is_valid_word("on"));
+        assert!(!lenient.is_valid_word("ot"));
+        // Endings alone or on unknown stems aren't words
+        assert!(!lenient.is_valid_word("lla"));
+        assert!(!lenient.is_valid_word("koiralla"));
+        assert!(!lenient.contains("kissalla"));
+    }
+
+    #[test]
+    fn test_random_word() {
+        assert_eq!(DictionaryValidator::from_words([""; 0]).random_word(), None);
+
+        let validator = DictionaryValidator::from_words(["kissa", "koira", "talo"]);
+        let mut seen = HashSet::new();
+        for _ in 0..200 {
+            let word = validator.random_word().unwrap();
+            assert!(validator.contains(word));
+            seen.insert(word.to_string());
+        }
+        // Not always the same word
+        assert!(seen.len() > 1);
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let validator =
+            DictionaryValidator::from_words(["kissa", "kisa", "kassa", "käsi", "kääpiö", "koira"]);
+
+        assert_eq!(validator.suggestions("kisssa", 3), vec!["kissa"]);
+        // Removals first, then changes
+        assert_eq!(validator.suggestions("Kiisa", 3), vec!["kisa", "kissa"]);
+        // Letters outside ASCII are changed and added too
+        assert_eq!(validator.suggestions("kasi", 3), vec!["käsi"]);
+        assert_eq!(validator.suggestions("kääpiä", 3), vec!["kääpiö"]);
+        assert_eq!(validator.suggestions("kisa", 1).len(), 1);
+        assert!(validator.suggestions("auto", 3).is_empty());
+    }
+
+    #[test]
+    fn test_compiled_dictionary() -> std::io::Result<()> {
+        let mut source = NamedTempFile::new()?;
+        for word in ["kissa", "KOIRA", "A\u{308}iti", "linja-auto", "", "kissa"] {
+            writeln!(source, "{}", word)?;
+        }
+        let source_path = source.path().to_str().unwrap();
+        let compiled = NamedTempFile::new()?;
+        let compiled_path = compiled.path().to_str().unwrap();
+
+        assert_eq!(
+            DictionaryValidator::compile(source_path, compiled_path).unwrap(),
+            4
+        );
+        assert!(fs::read(compiled_path)?.starts_with(COMPILED_MAGIC));
+
+        let text = DictionaryValidator::new(source_path).unwrap();
+        let binary = DictionaryValidator::new(compiled_path).unwrap();
+        assert_eq!(binary.source().format, DictionaryFormat::Compiled);
+        assert_eq!(binary.len(), text.len());
+        assert_eq!(binary.alphabet, text.alphabet);
+        for word in [
+            "kissa",
+            "Koira",
+            "äiti",
+            "a\u{308}iti",
+            "linja-auto",
+            "kissu",
+            "",
+            "auto",
+        ] {
+            assert_eq!(
+                binary.is_valid_word(word),
+                text.is_valid_word(word),
+                "{:?}",
+                word
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_compiled_version_mismatch() -> std::io::Result<()> {
+        let mut file = NamedTempFile::new()?;
+        file.write_all(COMPILED_MAGIC)?;
+        let header = CompiledHeader {
+            version: COMPILED_FORMAT_VERSION + 1,
+            source_hash: String::new(),
+        };
+        bincode::serialize_into(&mut file, &header).unwrap();
+        bincode::serialize_into(&mut file, &vec!["kissa"]).unwrap();
+
+        match DictionaryValidator::new(file.path().to_str().unwrap()) {
+            Err(crate::error::Error::Dictionary(DictionaryError::FormatError(message))) => {
+                assert!(message.contains("compile-dict"), "{}", message)
+            }
+            _ => panic!("Expected FormatError"),
+        }
+        Ok(())
+    }
+
+    /// The start of the Kotus word list, with a homograph and a duplicate
+    const KOTUS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<kotus-sanalista>
+<st><s>aakkonen</s><t><tn>38</tn></t></st>
+<st><s>kuusi</s><hn>1</hn><t><tn>24</tn><av>D</av></t></st>
+<st><s>kuusi</s><hn>2</hn><t><tn>27</tn><av>F</av></t></st>
+<st><s>Äiti</s><t><tn>5</tn></t></st>
+<st><s>linja-auto</s><t><tn>1</tn></t></st>
+<st><s>aakkonen</s></st>
+</kotus-sanalista>
+"#;
+
+    #[test]
+    fn test_kotus_xml() -> std::io::Result<()> {
+        let mut file = tempfile::Builder::new().suffix(".XML").tempfile()?;
+        file.write_all(KOTUS_XML.as_bytes())?;
+
+        let validator = DictionaryValidator::new(file.path().to_str().unwrap()).unwrap();
+        assert_eq!(validator.source().format, DictionaryFormat::KotusXml);
+        assert_eq!(validator.len(), 4);
+        assert!(validator.is_valid_word("aakkonen"));
+        assert!(validator.is_valid_word("kuusi"));
+        assert!(validator.is_valid_word("äiti"));
+        assert!(validator.is_valid_word("linja-auto"));
+        // Inflection class numbers aren't words
+        assert!(!validator.is_valid_word("38"));
+
+        // The same words as the text format
+        let words = read_text(
+            "aakkonen\nkuusi\nkuusi\näiti\nlinja-auto\n".as_bytes(),
+            &mut Progress::new("test"),
+        )
+        .unwrap();
+        assert_eq!(
+            validator
+                .words()
+                .map(str::to_string)
+                .collect::<HashSet<_>>(),
+            words
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_several_sources() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().join("kotus.txt");
+        let places = dir.path().join("places.txt");
+        let added = dir.path().join("added.txt");
+        fs::write(&base, "kissa\nkoira\nturku\n")?;
+        fs::write(&places, "turku\nhelsinki\nkoira\n")?;
+        fs::write(&added, "helsinki\nmummo\nkissa\n")?;
+        let paths = [&base, &places, &added].map(|path| path.to_str().unwrap().to_string());
+
+        let validator = DictionaryValidator::load(&paths)
+            .unwrap()
+            .with_inflections(parse_rules("lla").unwrap());
+        assert_eq!(validator.len(), 5);
+        let names: Vec<_> = validator
+            .sources()
+            .iter()
+            .map(|source| source.name.as_ref())
+            .collect();
+        assert_eq!(names, ["kotus.txt", "places.txt", "added.txt"]);
+        assert_eq!(validator.source().name.as_ref(), "kotus.txt");
+
+        // A word in several files is from the first of them, whatever order they list it in
+        let source = |word: &str| {
+            validator
+                .lookup(word)
+                .map(|found| found.source.name.clone())
+        };
+        assert_eq!(source("kissa").as_deref(), Some("kotus.txt"));
+        assert_eq!(source("koira").as_deref(), Some("kotus.txt"));
+        assert_eq!(source("turku").as_deref(), Some("kotus.txt"));
+        assert_eq!(source("helsinki").as_deref(), Some("places.txt"));
+        assert_eq!(source("mummo").as_deref(), Some("added.txt"));
+        assert_eq!(source("mummolla").as_deref(), Some("added.txt"));
+        assert_eq!(source("tampere"), None);
+
+        // The order of the files decides
+        let reversed: Vec<_> = paths.iter().rev().collect();
+        let validator = DictionaryValidator::load(&reversed).unwrap();
+        let found = validator.lookup("kissa").unwrap();
+        assert_eq!(found.source.name.as_ref(), "added.txt");
+        assert_eq!(found.source.format, DictionaryFormat::Text);
+        assert_eq!(
+            validator.lookup("koira").unwrap().source.name.as_ref(),
+            "places.txt"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_malformed_kotus_xml() -> std::io::Result<()> {
+        let mut file = tempfile::Builder::new().suffix(".xml").tempfile()?;
+        write!(
+            file,
+            "<kotus-sanalista>\n<st><s>aakkonen</s></st>\n<st><s>kuusi</t></st>\n"
+        )?;
+
+        match DictionaryValidator::new(file.path().to_str().unwrap()) {
+            Err(crate::error::Error::Dictionary(DictionaryError::FormatError(message))) => {
+                assert!(
+                    message.starts_with("malformed Kotus XML at 3:"),
+                    "{}",
+                    message
+                )
+            }
+            _ => panic!("Expected FormatError"),
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_empty_dictionary() -> std::io::Result<()> {
         // Create an empty dictionary file
@@ -0,0 +1,294 @@
+//! Getting a word list when there's none at `dictionary_path`
+//!
+//! With `download_dictionary` set, the list at `dictionary_url` is downloaded
+//! next to the configured path and renamed over it only once it's complete
+//! and matches `dictionary_sha256`, so an interrupted or tampered download
+//! never leaves a broken dictionary behind. Builds with the `demo-words`
+//! feature fall back to a small embedded list instead, for trying the bot out.
+
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::info;
+#[cfg(feature = "demo-words")]
+use tracing::warn;
+
+use crate::config::Config;
+use crate::error::{DictionaryError, Error, Result};
+use crate::validation::dictionary::DictionaryValidator;
+
+/// How long to wait for the dictionary server to accept the connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long the download may stall before it's given up
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Downloaded bytes between two progress lines
+const PROGRESS_STEP: u64 = 1024 * 1024;
+
+/// A few hundred common words, enough to play a short demo game
+#[cfg(feature = "demo-words")]
+pub const DEMO_WORDS: &str = include_str!("demo_words.txt");
+
+/// Load the configured dictionary, downloading the main word list first if it's missing
+pub async fn load_dictionary(config: &Config) -> Result<DictionaryValidator> {
+    let path = Path::new(&config.dictionary_path);
+    if !path.exists() {
+        if config.download_dictionary {
+            let client = reqwest::Client::builder()
+                .connect_timeout(CONNECT_TIMEOUT)
+                .read_timeout(READ_TIMEOUT)
+                .build()
+                .map_err(download_error)?;
+            let sha256 = Some(config.dictionary_sha256.as_str()).filter(|hash| !hash.is_empty());
+            download(&client, &config.dictionary_url, path, sha256).await?;
+        } else {
+            #[cfg(feature = "demo-words")]
+            {
+                warn!("!!! No dictionary at {} !!!", config.dictionary_path);
+                warn!("!!! DEMO MODE: only a few hundred built-in words are accepted !!!");
+                warn!(
+                    "!!! Set DOWNLOAD_DICTIONARY=true or provide a word list to play for real !!!"
+                );
+                return Ok(DictionaryValidator::from_words(DEMO_WORDS.lines()));
+            }
+        }
+    }
+    // Reading a large list takes a while, so it's kept off the async threads
+    let paths = config.dictionary_paths();
+    tokio::task::spawn_blocking(move || DictionaryValidator::load(&paths))
+        .await
+        .map_err(|e| Error::TaskPanic(e.to_string()))?
+}
+
+/// Download `url` to `path`, checking it against the hex `sha256` if there is one
+///
+/// Returns the number of bytes written.
+pub async fn download(
+    client: &reqwest::Client,
+    url: &str,
+    path: &Path,
+    sha256: Option<&str>,
+) -> Result<u64> {
+    info!(
+        "Downloading the dictionary from {} to {}",
+        url,
+        path.display()
+    );
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(download_error)?;
+    let total = response.content_length();
+
+    let partial = partial_path(path);
+    let written = async {
+        let mut file = blocking({
+            let partial = partial.clone();
+            move || {
+                if let Some(parent) = partial.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::File::create(&partial)
+            }
+        })
+        .await?;
+        let mut hasher = Sha256::new();
+        let mut written = 0;
+        let mut next_report = PROGRESS_STEP;
+        while let Some(chunk) = response.chunk().await.map_err(download_error)? {
+            hasher.update(&chunk);
+            written += chunk.len() as u64;
+            file = blocking(move || file.write_all(&chunk).map(|()| file)).await?;
+            if written >= next_report {
+                log_progress(written, total);
+                next_report = written + PROGRESS_STEP;
+            }
+        }
+        blocking(move || file.sync_all()).await?;
+
+        let actual: String = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        if let Some(expected) = sha256 {
+            if !actual.eq_ignore_ascii_case(expected) {
+                return Err(DictionaryError::ChecksumMismatch {
+                    expected: expected.to_lowercase(),
+                    actual,
+                }
+                .into());
+            }
+        }
+        let (from, to) = (partial.clone(), path.to_path_buf());
+        blocking(move || fs::rename(from, to)).await?;
+        info!("Downloaded {} bytes, sha256 {}", written, actual);
+        Ok(written)
+    }
+    .await;
+
+    if written.is_err() {
+        // Nothing is left behind to be mistaken for a dictionary later
+        let _ = fs::remove_file(&partial);
+    }
+    written
+}
+
+/// Run file I/O on the blocking pool so a slow disk doesn't stall the async threads
+async fn blocking<T, F>(io: F) -> Result<T>
+where
+    F: FnOnce() -> std::io::Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(io)
+        .await
+        .map_err(|e| Error::TaskPanic(e.to_string()))?
+        .map_err(Into::into)
+}
+
+/// Where the download is written until it's complete
+fn partial_path(path: &Path) -> PathBuf {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".part");
+    PathBuf::from(partial)
+}
+
+fn log_progress(written: u64, total: Option<u64>) {
+    let mib = written as f64 / PROGRESS_STEP as f64;
+    match total {
+        Some(total) if total > 0 => info!(
+            "Downloaded {:.1} MiB ({}%)",
+            mib,
+            written.saturating_mul(100) / total
+        ),
+        _ => info!("Downloaded {:.1} MiB", mib),
+    }
+}
+
+fn download_error(error: reqwest::Error) -> crate::error::BotError {
+    DictionaryError::Download(error.to_string()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::BotError;
+    use axum::routing::get;
+    use axum::Router;
+    use tokio::net::TcpListener;
+
+    const WORDS: &str = "kissa\nkassa\nkassi\n";
+    const WRONG_SHA256: &str = "1c5d8f3a8e2f4b2c3e0ab4f3f4a2bd6c0d2ad9a3a47e8c4bd1b0e8d62ef6f3f0";
+
+    /// Serve WORDS on a free local port, returning its URL
+    async fn serve() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/words.txt", listener.local_addr().unwrap());
+        let app = Router::new().route("/words.txt", get(|| async { WORDS }));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        url
+    }
+
+    fn sha256(text: &str) -> String {
+        Sha256::digest(text.as_bytes())
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    #[actix_rt::test]
+    async fn test_download() {
+        let url = serve().await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lists").join("words.txt");
+        let client = reqwest::Client::new();
+
+        let written = download(&client, &url, &path, Some(&sha256(WORDS).to_uppercase()))
+            .await
+            .unwrap();
+        assert_eq!(written, WORDS.len() as u64);
+        assert_eq!(fs::read_to_string(&path).unwrap(), WORDS);
+        assert!(!partial_path(&path).exists());
+
+        // Without a checksum anything is accepted
+        fs::remove_file(&path).unwrap();
+        download(&client, &url, &path, None).await.unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), WORDS);
+    }
+
+    #[actix_rt::test]
+    async fn test_checksum_mismatch_is_rejected() {
+        let url = serve().await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.txt");
+
+        let result = download(&reqwest::Client::new(), &url, &path, Some(WRONG_SHA256)).await;
+        match result {
+            Err(BotError::Dictionary(DictionaryError::ChecksumMismatch { expected, actual })) => {
+                assert_eq!(expected, WRONG_SHA256);
+                assert_eq!(actual, sha256(WORDS));
+            }
+            other => panic!("expected a checksum mismatch, got {:?}", other),
+        }
+        assert!(!path.exists());
+        assert!(!partial_path(&path).exists());
+
+        // A missing file is a download error, not an empty dictionary
+        let missing = url.replace("words.txt", "missing.txt");
+        let result = download(&reqwest::Client::new(), &missing, &path, None).await;
+        assert!(matches!(
+            result,
+            Err(BotError::Dictionary(DictionaryError::Download(_)))
+        ));
+        assert!(!path.exists());
+    }
+
+    #[actix_rt::test]
+    async fn test_load_dictionary_downloads_missing_file() {
+        let url = serve().await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.txt");
+        let config = Config {
+            dictionary_path: path.to_string_lossy().into_owned(),
+            download_dictionary: true,
+            dictionary_url: url,
+            dictionary_sha256: sha256(WORDS),
+            ..Config::default()
+        };
+
+        let dictionary = load_dictionary(&config).await.unwrap();
+        assert_eq!(dictionary.len(), 3);
+        assert!(path.exists());
+    }
+
+    #[actix_rt::test]
+    async fn test_load_large_dictionary() {
+        const WORD_COUNT: usize = 200_000;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.txt");
+        // Every four-letter word of a to z, in order, until there are enough
+        let mut words = String::new();
+        for index in 0..WORD_COUNT {
+            let mut rest = index;
+            for _ in 0..4 {
+                words.push(char::from(b'a' + (rest % 26) as u8));
+                rest /= 26;
+            }
+            words.push('\n');
+        }
+        fs::write(&path, words).unwrap();
+
+        let config = Config {
+            dictionary_path: path.to_string_lossy().into_owned(),
+            ..Config::default()
+        };
+        let dictionary = load_dictionary(&config).await.unwrap();
+        assert_eq!(dictionary.len(), WORD_COUNT);
+        assert!(dictionary.is_valid_word("aaaa"));
+    }
+}
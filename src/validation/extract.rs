@@ -0,0 +1,325 @@
+use crate::config::{Config, MultiWordPolicy};
+use crate::validation::normalize::{normalize_word, strip_invisible};
+
+/// Typographic apostrophes that mobile keyboards produce instead of `'`
+const APOSTROPHE_VARIANTS: [char; 2] = ['’', 'ʼ'];
+
+/// Most characters of a message written to the logs
+pub const MAX_LOGGED_CHARS: usize = 64;
+
+/// Settings controlling how the played word is extracted from a message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractOptions {
+    pub multi_word_policy: MultiWordPolicy,
+    /// Non-letter characters allowed inside a word ("linja-auto", "vaa'an").
+    /// Each may appear at most once and never at either end of the word.
+    pub inner_chars: Vec<char>,
+    /// Lowercase letters a word may consist of, besides the inner characters.
+    /// Empty allows any letter.
+    pub alphabet: Vec<char>,
+}
+
+impl Default for ExtractOptions {
+    fn default() -> Self {
+        Self {
+            multi_word_policy: MultiWordPolicy::default(),
+            inner_chars: vec!['-', '\''],
+            alphabet: "abcdefghijklmnopqrstuvwxyzåäö-".chars().collect(),
+        }
+    }
+}
+
+impl ExtractOptions {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            multi_word_policy: config.multi_word_policy,
+            inner_chars: config.word_inner_chars.chars().collect(),
+            alphabet: normalize_word(&config.allowed_alphabet).chars().collect(),
+        }
+    }
+
+    /// Letters of a word outside the alphabet, each once in the order they appear
+    ///
+    /// The word is normalized first, so uppercase and decomposed letters are
+    /// checked as their lowercase, composed forms.
+    pub fn foreign_letters(&self, word: &str) -> Vec<char> {
+        if self.alphabet.is_empty() {
+            return Vec::new();
+        }
+        let mut foreign = Vec::new();
+        for c in normalize_word(word).chars() {
+            if !self.alphabet.contains(&c)
+                && !self.inner_chars.contains(&c)
+                && !foreign.contains(&c)
+            {
+                foreign.push(c);
+            }
+        }
+        foreign
+    }
+}
+
+/// Extract the played word from a chat message
+///
+/// Invisible format and control characters are removed first, see
+/// [`strip_invisible`]. Leading and trailing punctuation, Discord markdown (`*`, `_`, `~`, `` ` ``)
+/// and emoji are stripped from every whitespace-separated token, and tokens
+/// with nothing left (like ":)" or "🐱") are dropped. If more than one token
+/// remains, the multi-word policy decides whether the first one is played or
+/// the message is ignored. Returns `None` if the result isn't a single word.
+pub fn extract_word(content: &str, options: &ExtractOptions) -> Option<String> {
+    let content = strip_invisible(content);
+    let mut tokens = tokens(&content);
+
+    let word = tokens.next()?;
+    if tokens.next().is_some() && options.multi_word_policy != MultiWordPolicy::FirstToken {
+        return None;
+    }
+
+    let word: String = word
+        .chars()
+        .map(|c| {
+            if APOSTROPHE_VARIANTS.contains(&c) {
+                '\''
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    if is_word_shaped(&word, &options.inner_chars) {
+        Some(word)
+    } else {
+        None
+    }
+}
+
+/// Whether a message has more than one token left after stripping decorations,
+/// e.g. "kissa — helppo!" but not "kissa!" or "kissa :)"
+pub fn is_multi_word(content: &str) -> bool {
+    tokens(&strip_invisible(content)).nth(1).is_some()
+}
+
+/// How a message appears in the logs
+///
+/// Only the word picked out of the message is logged, keeping the chat around
+/// the game out of the logs, unless `log_content` allows the whole message.
+/// Either way it's cut to [`MAX_LOGGED_CHARS`]. A message that isn't logged
+/// shows only its length, e.g. "[42 chars]".
+pub fn loggable_content(content: &str, options: &ExtractOptions, log_content: bool) -> String {
+    let shown = match extract_word(content, options) {
+        Some(word) => word,
+        None if log_content => content.to_string(),
+        None => return format!("[{} chars]", content.chars().count()),
+    };
+    match shown.char_indices().nth(MAX_LOGGED_CHARS) {
+        Some((end, _)) => format!("{}…", &shown[..end]),
+        None => shown,
+    }
+}
+
+/// The message's whitespace-separated tokens, stripped, leaving out empty ones
+fn tokens(content: &str) -> impl Iterator<Item = &str> {
+    content
+        .split_whitespace()
+        .map(strip_decorations)
+        .filter(|token| !token.is_empty())
+}
+
+/// Remove everything that isn't a letter or a digit from both ends of a token
+fn strip_decorations(token: &str) -> &str {
+    token.trim_matches(|c: char| !c.is_alphanumeric())
+}
+
+/// Check that a token consists of letters, allowing each inner character once
+/// inside the word (never first or last)
+fn is_word_shaped(word: &str, inner_chars: &[char]) -> bool {
+    let chars: Vec<char> = word.chars().collect();
+    let (Some(first), Some(last)) = (chars.first(), chars.last()) else {
+        return false;
+    };
+    if !first.is_alphabetic() || !last.is_alphabetic() {
+        return false;
+    }
+
+    chars
+        .iter()
+        .all(|c| c.is_alphabetic() || inner_chars.contains(c))
+        && inner_chars
+            .iter()
+            .all(|inner| chars.iter().filter(|c| *c == inner).count() <= 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract(content: &str) -> Option<String> {
+        extract_word(content, &ExtractOptions::default())
+    }
+
+    fn first_token() -> ExtractOptions {
+        ExtractOptions {
+            multi_word_policy: MultiWordPolicy::FirstToken,
+            ..ExtractOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_extract_messy_inputs() {
+        assert_eq!(extract("kissa").as_deref(), Some("kissa"));
+        assert_eq!(extract("  kissa  ").as_deref(), Some("kissa"));
+        assert_eq!(extract("kissa.").as_deref(), Some("kissa"));
+        assert_eq!(extract("kissa!?").as_deref(), Some("kissa"));
+        assert_eq!(extract("**kissa**").as_deref(), Some("kissa"));
+        assert_eq!(extract("_kissa_").as_deref(), Some("kissa"));
+        assert_eq!(extract("~~kissa~~").as_deref(), Some("kissa"));
+        assert_eq!(extract("`kissa`").as_deref(), Some("kissa"));
+        assert_eq!(extract("(kissa)").as_deref(), Some("kissa"));
+        assert_eq!(extract("kissa :)").as_deref(), Some("kissa"));
+        assert_eq!(extract("kissa 🐱").as_deref(), Some("kissa"));
+        assert_eq!(extract("*äänestää*!").as_deref(), Some("äänestää"));
+        assert_eq!(extract("linja-auto").as_deref(), Some("linja-auto"));
+        assert_eq!(extract("-kissa-").as_deref(), Some("kissa"));
+    }
+
+    #[test]
+    fn test_extract_copied_words() {
+        assert_eq!(extract("kis\u{200B}sa").as_deref(), Some("kissa"));
+        assert_eq!(extract("kis\u{2060}sa").as_deref(), Some("kissa"));
+        assert_eq!(extract("\u{FEFF}kissa").as_deref(), Some("kissa"));
+        assert_eq!(extract("linja\u{200B}-auto").as_deref(), Some("linja-auto"));
+        // A zero-width space alone doesn't make two words, nor join them
+        assert!(!is_multi_word("kissa\u{200B}"));
+        assert_eq!(extract("kissa \u{200B} koira"), None);
+    }
+
+    #[test]
+    fn test_extract_rejects_non_words() {
+        assert_eq!(extract(""), None);
+        assert_eq!(extract("   "), None);
+        assert_eq!(extract(":)"), None);
+        assert_eq!(extract("🐱"), None);
+        assert_eq!(extract("ki55a"), None);
+        assert_eq!(extract("kis.sa"), None);
+    }
+
+    #[test]
+    fn test_extract_multiple_tokens() {
+        assert_eq!(extract("kissa kissa"), None);
+        assert_eq!(extract("kissa, koira"), None);
+        assert_eq!(extract("kissa 2"), None);
+        assert_eq!(
+            extract_word("kissa kissa", &first_token()).as_deref(),
+            Some("kissa")
+        );
+        assert_eq!(
+            extract_word("**kissa** on paras", &first_token()).as_deref(),
+            Some("kissa")
+        );
+        assert_eq!(extract_word("12 kissaa", &first_token()), None);
+
+        // Chat is never played when reacting to it instead
+        let react_ignored = ExtractOptions {
+            multi_word_policy: MultiWordPolicy::ReactIgnored,
+            ..ExtractOptions::default()
+        };
+        assert_eq!(extract_word("kissa — helppo!", &react_ignored), None);
+        assert_eq!(
+            extract_word("kissa!", &react_ignored).as_deref(),
+            Some("kissa")
+        );
+    }
+
+    #[test]
+    fn test_multi_word_messages() {
+        assert!(is_multi_word("kissa — helppo!"));
+        assert!(is_multi_word("onpa vaikea kierros"));
+        assert!(is_multi_word("**kassa** vai kassi?"));
+        assert!(is_multi_word("kissa 2"));
+
+        // Decorations and emoji don't make a message chat
+        assert!(!is_multi_word("kissa"));
+        assert!(!is_multi_word("  *kissa*!  "));
+        assert!(!is_multi_word("kissa :) 🐱"));
+        assert!(!is_multi_word("— kissa —"));
+        assert!(!is_multi_word(""));
+    }
+
+    #[test]
+    fn test_extract_inner_characters() {
+        assert_eq!(extract("linja-auto").as_deref(), Some("linja-auto"));
+        assert_eq!(extract("**linja-auto**!").as_deref(), Some("linja-auto"));
+        assert_eq!(extract("vaa'an").as_deref(), Some("vaa'an"));
+        assert_eq!(extract("vaa’an").as_deref(), Some("vaa'an"));
+        assert_eq!(extract("'kissa'").as_deref(), Some("kissa"));
+
+        // Only one of each, and only inside the word
+        assert_eq!(extract("linja-auto-asema"), None);
+        assert_eq!(extract("kis--sa"), None);
+        assert_eq!(extract("kis_sa"), None);
+
+        // Inner characters can be restricted in config
+        let hyphen_only = ExtractOptions {
+            inner_chars: vec!['-'],
+            ..ExtractOptions::default()
+        };
+        assert_eq!(
+            extract_word("linja-auto", &hyphen_only).as_deref(),
+            Some("linja-auto")
+        );
+        assert_eq!(extract_word("vaa'an", &hyphen_only), None);
+
+        let letters_only = ExtractOptions {
+            inner_chars: Vec::new(),
+            ..ExtractOptions::default()
+        };
+        assert_eq!(extract_word("linja-auto", &letters_only), None);
+    }
+
+    #[test]
+    fn test_foreign_letters() {
+        let options = ExtractOptions::default();
+        assert!(options.foreign_letters("kissa").is_empty());
+        assert!(options.foreign_letters("Äiti").is_empty());
+        assert!(options.foreign_letters("a\u{308}iti").is_empty());
+        assert!(options.foreign_letters("linja-auto").is_empty());
+        assert!(options.foreign_letters("vaa'an").is_empty());
+
+        // Cyrillic lookalikes among Latin letters, and pure Cyrillic
+        assert_eq!(options.foreign_letters("kiссa"), vec!['с']);
+        assert_eq!(options.foreign_letters("КОШКА"), vec!['к', 'о', 'ш', 'а']);
+        assert_eq!(options.foreign_letters("façade"), vec!['ç']);
+
+        // The inner characters are allowed even when the alphabet leaves them out
+        let letters = ExtractOptions {
+            alphabet: "abcdefghijklmnopqrstuvwxyzåäö".chars().collect(),
+            ..ExtractOptions::default()
+        };
+        assert!(letters.foreign_letters("vaa'an").is_empty());
+        assert!(letters.foreign_letters("linja-auto").is_empty());
+
+        let anything = ExtractOptions {
+            alphabet: Vec::new(),
+            ..ExtractOptions::default()
+        };
+        assert!(anything.foreign_letters("кошка").is_empty());
+    }
+
+    #[test]
+    fn test_loggable_content() {
+        let options = ExtractOptions::default();
+        assert_eq!(loggable_content("**kissa**!", &options, false), "kissa");
+        // Chat shows only its length unless message content is logged
+        assert_eq!(
+            loggable_content("moi kaikki", &options, false),
+            "[10 chars]"
+        );
+        assert_eq!(loggable_content("moi kaikki", &options, true), "moi kaikki");
+
+        let long = "ä".repeat(MAX_LOGGED_CHARS + 10);
+        let logged = loggable_content(&format!("{} jee", long), &options, true);
+        assert_eq!(logged.chars().count(), MAX_LOGGED_CHARS + 1);
+        assert!(logged.ends_with('…'));
+    }
+}
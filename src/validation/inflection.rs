@@ -0,0 +1,118 @@
+//! Suffix-stripping rules for accepting inflected forms of dictionary words
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::validation::normalize::normalize_word;
+
+/// Common case endings and plural markers, tried in this order
+pub const DEFAULT_INFLECTION_RULES: &str =
+    "lla,llä,ssa,ssä,sta,stä,lle,lta,ltä,ksi,na,nä,oja>a,öjä>ä,ja,jä,n,t";
+
+/// Stems shorter than this are never looked up, so "on" isn't taken for "o" + "-n"
+const MIN_STEM_LETTERS: usize = 2;
+
+/// An ending to strip, and what to put back in its place
+///
+/// Written `suffix` or `suffix>replacement`, e.g. `lla` turns "kissalla"
+/// into "kissa" and `oja>a` turns "kissoja" into "kissa".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InflectionRule {
+    pub suffix: String,
+    pub replacement: String,
+}
+
+impl InflectionRule {
+    /// The base form this rule would give `word`, if it applies
+    ///
+    /// `word` is expected to be normalized already.
+    pub fn strip(&self, word: &str) -> Option<String> {
+        let stem = word.strip_suffix(self.suffix.as_str())?;
+        if stem.chars().count() < MIN_STEM_LETTERS {
+            return None;
+        }
+        Some(format!("{}{}", stem, self.replacement))
+    }
+}
+
+impl FromStr for InflectionRule {
+    type Err = String;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let (suffix, replacement) = rule.split_once('>').unwrap_or((rule, ""));
+        let suffix = normalize_word(suffix);
+        if suffix.is_empty() {
+            return Err(format!("rule '{}' has no suffix to strip", rule.trim()));
+        }
+        Ok(Self {
+            suffix,
+            replacement: normalize_word(replacement),
+        })
+    }
+}
+
+impl fmt::Display for InflectionRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.replacement.is_empty() {
+            write!(f, "-{}", self.suffix)
+        } else {
+            write!(f, "-{} → -{}", self.suffix, self.replacement)
+        }
+    }
+}
+
+/// Parse a comma-separated list of rules, e.g. [`DEFAULT_INFLECTION_RULES`]
+pub fn parse_rules(rules: &str) -> Result<Vec<InflectionRule>, String> {
+    rules
+        .split(',')
+        .filter(|rule| !rule.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rules() {
+        let rules = parse_rules(" lla, oja>a ,,N").unwrap();
+        assert_eq!(
+            rules,
+            vec![
+                InflectionRule {
+                    suffix: "lla".to_string(),
+                    replacement: String::new(),
+                },
+                InflectionRule {
+                    suffix: "oja".to_string(),
+                    replacement: "a".to_string(),
+                },
+                InflectionRule {
+                    suffix: "n".to_string(),
+                    replacement: String::new(),
+                },
+            ]
+        );
+        assert_eq!(rules[1].to_string(), "-oja → -a");
+        assert!(parse_rules("lla,>a").is_err());
+        assert!(parse_rules(DEFAULT_INFLECTION_RULES).is_ok());
+        assert!(parse_rules("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_strip() {
+        let rule: InflectionRule = "lla".parse().unwrap();
+        assert_eq!(rule.strip("kissalla").as_deref(), Some("kissa"));
+        assert_eq!(rule.strip("kissa"), None);
+
+        let rule: InflectionRule = "oja>a".parse().unwrap();
+        assert_eq!(rule.strip("kissoja").as_deref(), Some("kissa"));
+
+        // Never down to a one-letter stem
+        let rule: InflectionRule = "n".parse().unwrap();
+        assert_eq!(rule.strip("on"), None);
+        assert_eq!(rule.strip("n"), None);
+        assert_eq!(rule.strip("yön").as_deref(), Some("yö"));
+    }
+}
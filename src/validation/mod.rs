@@ -1,8 +1,22 @@
+pub mod blocklist;
 pub mod dictionary;
+pub mod download;
+pub mod extract;
+pub mod inflection;
 pub mod llm;
+pub mod neighbors;
+pub mod normalize;
+pub mod plausibility;
+pub mod remote;
 pub mod rules;
 
 // Re-export common types
+pub use blocklist::Blocklist;
 pub use dictionary::DictionaryValidator;
+pub use extract::extract_word;
+pub use inflection::InflectionRule;
 pub use llm::LLMValidator;
+pub use normalize::{display_capitalize, normalize_word, proper_noun_casing};
+pub use plausibility::PlausibilityFilter;
+pub use remote::RemoteDictionary;
 pub use rules::RulesValidator;
@@ -0,0 +1,424 @@
+//! Words one legal move away: one letter removed, changed or added
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::validation::dictionary::DictionaryValidator;
+use crate::validation::normalize::normalize_word;
+use crate::validation::rules::{check_free_diacritics, is_diacritic_pair, DiacriticPolicy};
+
+/// The letters of Finnish words, including the ones only seen in loanwords
+pub const FINNISH_ALPHABET: &[char] = &[
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's',
+    't', 'u', 'v', 'w', 'x', 'y', 'z', 'å', 'ä', 'ö',
+];
+
+/// Whether `b` is `a` with exactly one letter removed, changed or added
+///
+/// Characters are compared as they are, so both words should be normalized
+/// the same way first. Identical words are not one letter apart.
+pub fn edit_distance_one(a: &str, b: &str) -> bool {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > 1 {
+        return false;
+    }
+
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    // What's left between the common prefix and suffix is the edit itself
+    let left_a = a.len() - prefix - suffix;
+    let left_b = b.len() - prefix - suffix;
+    left_a.max(left_b) == 1
+}
+
+/// Every word one letter away from `word`, with letters changed or added from `alphabet`
+///
+/// Each result satisfies [`edit_distance_one`] against `word`. The same word
+/// may be generated more than once, e.g. by removing either "s" of "kissa".
+pub fn one_letter_edits(word: &str, alphabet: &[char]) -> Vec<String> {
+    let chars: Vec<char> = word.chars().collect();
+    let with = |position: usize, skip: usize, letter: Option<char>| -> String {
+        chars[..position]
+            .iter()
+            .copied()
+            .chain(letter)
+            .chain(chars[position + skip..].iter().copied())
+            .collect()
+    };
+
+    let mut edits = Vec::with_capacity(chars.len() + alphabet.len() * (2 * chars.len() + 1));
+    for position in 0..chars.len() {
+        edits.push(with(position, 1, None));
+    }
+    for (position, &current) in chars.iter().enumerate() {
+        for &letter in alphabet.iter().filter(|&&letter| letter != current) {
+            edits.push(with(position, 1, Some(letter)));
+        }
+    }
+    for position in 0..=chars.len() {
+        for &letter in alphabet {
+            edits.push(with(position, 0, Some(letter)));
+        }
+    }
+    edits
+}
+
+/// Dictionary words one letter away from `word`, over the Finnish alphabet
+pub fn neighbors(word: &str, dict: &DictionaryValidator) -> Vec<String> {
+    neighbors_with(word, dict, FINNISH_ALPHABET)
+}
+
+/// Dictionary words one letter away from `word`, changing and adding letters from `alphabet`
+///
+/// Candidates are generated and looked up rather than the dictionary scanned,
+/// so the cost depends on the word's length, not the dictionary's size. The
+/// order is stable: removals first, then changes, then additions, each from
+/// the start of the word.
+pub fn neighbors_with(word: &str, dict: &DictionaryValidator, alphabet: &[char]) -> Vec<String> {
+    let word = normalize_word(word);
+    let mut found: Vec<String> = Vec::new();
+    for edit in one_letter_edits(&word, alphabet) {
+        if dict.contains(&edit) && !found.contains(&edit) {
+            found.push(edit);
+        }
+    }
+    found
+}
+
+/// Dictionary words a move away from `word` under `policy`, changing and adding
+/// letters from `alphabet`
+///
+/// Moves that only add or remove the dots or the ring of a letter are left
+/// out when they're forbidden. When they're free, the words are looked up by
+/// their plain letters, so any number of such changes may go along with the
+/// one letter changed.
+pub fn moves_with(
+    word: &str,
+    dict: &DictionaryValidator,
+    alphabet: &[char],
+    policy: DiacriticPolicy,
+) -> Vec<String> {
+    let word = normalize_word(word);
+    match policy {
+        DiacriticPolicy::Distinct => neighbors_with(&word, dict, alphabet),
+        DiacriticPolicy::Forbidden => neighbors_with(&word, dict, alphabet)
+            .into_iter()
+            .filter(|next| {
+                next.chars().count() != word.chars().count()
+                    || !word
+                        .chars()
+                        .zip(next.chars())
+                        .any(|(a, b)| is_diacritic_pair(a, b))
+            })
+            .collect(),
+        DiacriticPolicy::Free => {
+            let plain = fold_diacritics(&word);
+            let mut plain_alphabet: Vec<char> =
+                alphabet.iter().map(|&letter| fold_letter(letter)).collect();
+            plain_alphabet.sort_unstable();
+            plain_alphabet.dedup();
+
+            let mut found: Vec<String> = Vec::new();
+            for key in
+                std::iter::once(plain.clone()).chain(one_letter_edits(&plain, &plain_alphabet))
+            {
+                for next in dict.with_plain_letters(&key) {
+                    if *next != word
+                        && !found.contains(next)
+                        && check_free_diacritics(&word, next).is_ok()
+                    {
+                        found.push(next.clone());
+                    }
+                }
+            }
+            found
+        }
+    }
+}
+
+/// The letter without its dots or ring, e.g. a for ä and å
+fn fold_letter(letter: char) -> char {
+    match letter {
+        'ä' | 'å' => 'a',
+        'ö' => 'o',
+        other => other,
+    }
+}
+
+/// The word with the dots and rings taken off its letters
+pub fn fold_diacritics(word: &str) -> String {
+    word.chars().map(fold_letter).collect()
+}
+
+/// What searching for the shortest chain between two words found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSearch {
+    /// The words of the chain from start to goal, both included
+    Found(Vec<String>),
+    /// No chain of dictionary words joins them
+    NoPath,
+    /// The search gave up after looking at this many words
+    LimitReached(usize),
+}
+
+/// The shortest chain of one-letter moves from `from` to `to` over dictionary words
+///
+/// A breadth-first search that looks at most `max_words` words, since the
+/// whole dictionary may be reachable from a common word. The moves follow
+/// `policy` on diacritic changes. The words are expected to be normalized already.
+pub fn shortest_path(
+    from: &str,
+    to: &str,
+    dict: &DictionaryValidator,
+    policy: DiacriticPolicy,
+    max_words: usize,
+) -> PathSearch {
+    // The word each reached word was reached from, the start from nothing
+    let mut came_from: HashMap<String, Option<String>> = HashMap::new();
+    came_from.insert(from.to_string(), None);
+    let mut queue = VecDeque::from([from.to_string()]);
+    let mut looked_at = 0;
+
+    while let Some(word) = queue.pop_front() {
+        if word == to {
+            let mut path = vec![word];
+            while let Some(Some(previous)) = came_from.get(path.last().unwrap()) {
+                path.push(previous.clone());
+            }
+            path.reverse();
+            return PathSearch::Found(path);
+        }
+        if looked_at == max_words {
+            return PathSearch::LimitReached(looked_at);
+        }
+        looked_at += 1;
+
+        for next in dict.moves(&word, policy) {
+            if !came_from.contains_key(&next) {
+                came_from.insert(next.clone(), Some(word.clone()));
+                queue.push_back(next);
+            }
+        }
+    }
+    PathSearch::NoPath
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random numbers, enough to vary the generated words
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self, bound: usize) -> usize {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((self.0 >> 33) % bound as u64) as usize
+        }
+
+        fn word(&mut self, alphabet: &[char], max_len: usize) -> String {
+            let len = 1 + self.next(max_len);
+            (0..len)
+                .map(|_| alphabet[self.next(alphabet.len())])
+                .collect()
+        }
+    }
+
+    /// Textbook Levenshtein distance, the reference for [`edit_distance_one`]
+    fn levenshtein(a: &str, b: &str) -> usize {
+        let b: Vec<char> = b.chars().collect();
+        let mut row: Vec<usize> = (0..=b.len()).collect();
+        for (i, ca) in a.chars().enumerate() {
+            let mut previous = row[0];
+            row[0] = i + 1;
+            for (j, &cb) in b.iter().enumerate() {
+                let substitution = previous + usize::from(ca != cb);
+                previous = row[j + 1];
+                row[j + 1] = substitution.min(row[j] + 1).min(previous + 1);
+            }
+        }
+        row[b.len()]
+    }
+
+    /// Every word up to `max_len` letters over `alphabet`, including the empty word
+    fn all_words(alphabet: &[char], max_len: usize) -> Vec<String> {
+        let mut words = vec![String::new()];
+        let mut last = words.clone();
+        for _ in 0..max_len {
+            last = last
+                .iter()
+                .flat_map(|word| alphabet.iter().map(move |c| format!("{}{}", word, c)))
+                .collect();
+            words.extend(last.iter().cloned());
+        }
+        words
+    }
+
+    #[test]
+    fn test_edit_distance_one() {
+        assert!(edit_distance_one("kissa", "kassa"));
+        assert!(edit_distance_one("kissa", "kissan"));
+        assert!(edit_distance_one("kissan", "kissa"));
+        assert!(edit_distance_one("äiti", "äitä"));
+        assert!(edit_distance_one("", "ö"));
+        assert!(!edit_distance_one("kissa", "kissa"));
+        assert!(!edit_distance_one("kissa", "koira"));
+        assert!(!edit_distance_one("abc", "abxyc"));
+        assert!(!edit_distance_one("kissa", "iss"));
+        // Swapped letters are two changes
+        assert!(!edit_distance_one("kissa", "iksa"));
+    }
+
+    #[test]
+    fn test_edit_distance_one_matches_levenshtein() {
+        // Every pair of short words over a few letters, including repeated ones
+        let words = all_words(&['a', 'ä', 'b'], 4);
+        for a in &words {
+            for b in &words {
+                assert_eq!(
+                    edit_distance_one(a, b),
+                    levenshtein(a, b) == 1,
+                    "{:?} {:?}",
+                    a,
+                    b
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_edits_are_one_letter_away() {
+        let mut rng = Lcg(7);
+        for _ in 0..200 {
+            let word = rng.word(FINNISH_ALPHABET, 12);
+            let edits = one_letter_edits(&word, FINNISH_ALPHABET);
+            let len = word.chars().count();
+            assert_eq!(
+                edits.len(),
+                len + (FINNISH_ALPHABET.len() - 1) * len + FINNISH_ALPHABET.len() * (len + 1)
+            );
+            for edit in &edits {
+                assert!(edit_distance_one(&word, edit), "{:?} {:?}", word, edit);
+            }
+        }
+
+        // And nothing one letter away over the alphabet is missed
+        let alphabet = ['a', 'ä', 'y'];
+        for word in all_words(&alphabet, 3) {
+            let edits = one_letter_edits(&word, &alphabet);
+            for other in all_words(&alphabet, 4) {
+                assert_eq!(
+                    edits.contains(&other),
+                    edit_distance_one(&word, &other),
+                    "{:?} {:?}",
+                    word,
+                    other
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let dict = DictionaryValidator::from_words(["kissa", "kisa", "kassa", "kiss", "pöytä"]);
+        assert_eq!(neighbors("kissa", &dict), vec!["kisa", "kiss", "kassa"]);
+        assert_eq!(neighbors(" Pöyta ", &dict), vec!["pöytä"]);
+        assert!(neighbors("koira", &dict).is_empty());
+        assert!(neighbors_with("kissa", &dict, &[]).contains(&"kisa".to_string()));
+        assert!(!neighbors_with("kissa", &dict, &[]).contains(&"kassa".to_string()));
+    }
+
+    #[test]
+    fn test_moves() {
+        let dict =
+            DictionaryValidator::from_words(["kala", "käla", "kalo", "kälö", "kölä", "kissa"]);
+        let moves = |policy| moves_with("kala", &dict, FINNISH_ALPHABET, policy);
+        assert_eq!(moves(DiacriticPolicy::Distinct), vec!["käla", "kalo"]);
+        assert_eq!(moves(DiacriticPolicy::Forbidden), vec!["kalo"]);
+        // Dots come and go along with the letter changed, or make the move by themselves
+        assert_eq!(
+            moves(DiacriticPolicy::Free),
+            vec!["käla", "kölä", "kalo", "kälö"]
+        );
+        assert_eq!(
+            dict.moves("kala", DiacriticPolicy::Free),
+            moves(DiacriticPolicy::Free)
+        );
+
+        let path = |policy| shortest_path("kala", "kälö", &dict, policy, 100);
+        assert_eq!(
+            path(DiacriticPolicy::Free),
+            PathSearch::Found(vec!["kala".to_string(), "kälö".to_string()])
+        );
+        assert_eq!(
+            path(DiacriticPolicy::Distinct),
+            PathSearch::Found(vec![
+                "kala".to_string(),
+                "käla".to_string(),
+                "kälö".to_string()
+            ])
+        );
+        assert_eq!(path(DiacriticPolicy::Forbidden), PathSearch::NoPath);
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        let dict = DictionaryValidator::from_words([
+            "kissa", "kassa", "kassi", "kasvi", "kisa", "kasa", "koira",
+        ]);
+        let found =
+            |words: &[&str]| PathSearch::Found(words.iter().map(|word| word.to_string()).collect());
+        assert_eq!(
+            shortest_path("kissa", "kasvi", &dict, DiacriticPolicy::Distinct, 100),
+            found(&["kissa", "kassa", "kassi", "kasvi"])
+        );
+        assert_eq!(
+            shortest_path("kisa", "kasa", &dict, DiacriticPolicy::Distinct, 100),
+            found(&["kisa", "kasa"])
+        );
+        assert_eq!(
+            shortest_path("kissa", "kissa", &dict, DiacriticPolicy::Distinct, 100),
+            found(&["kissa"])
+        );
+        assert_eq!(
+            shortest_path("kissa", "koira", &dict, DiacriticPolicy::Distinct, 100),
+            PathSearch::NoPath
+        );
+        assert_eq!(
+            shortest_path("kissa", "kasvi", &dict, DiacriticPolicy::Distinct, 2),
+            PathSearch::LimitReached(2)
+        );
+    }
+
+    #[test]
+    fn test_neighbors_on_large_dictionary() {
+        // About the size of the Finnish word list
+        let mut rng = Lcg(42);
+        let words: Vec<String> = (0..100_000)
+            .map(|_| rng.word(FINNISH_ALPHABET, 10))
+            .collect();
+        let dict = DictionaryValidator::from_words(&words);
+
+        // Checked against scanning the whole dictionary
+        for word in words.iter().step_by(5000) {
+            let found = neighbors(word, &dict);
+            let mut expected: Vec<&String> = words
+                .iter()
+                .filter(|other| edit_distance_one(word, other))
+                .collect();
+            expected.sort();
+            expected.dedup();
+            let mut found_sorted: Vec<&String> = found.iter().collect();
+            found_sorted.sort();
+            assert_eq!(found_sorted, expected, "{:?}", word);
+        }
+    }
+}
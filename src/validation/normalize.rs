@@ -0,0 +1,171 @@
+//! The one way words are normalized before they're compared or looked up
+
+use std::ops::RangeInclusive;
+use unicode_normalization::UnicodeNormalization;
+
+/// The Unicode format characters (general category Cf), which don't show
+const FORMAT_CHARS: &[RangeInclusive<char>] = &[
+    '\u{AD}'..='\u{AD}',
+    '\u{600}'..='\u{605}',
+    '\u{61C}'..='\u{61C}',
+    '\u{6DD}'..='\u{6DD}',
+    '\u{70F}'..='\u{70F}',
+    '\u{890}'..='\u{891}',
+    '\u{8E2}'..='\u{8E2}',
+    '\u{180E}'..='\u{180E}',
+    '\u{200B}'..='\u{200F}',
+    '\u{202A}'..='\u{202E}',
+    '\u{2060}'..='\u{2064}',
+    '\u{2066}'..='\u{206F}',
+    '\u{FEFF}'..='\u{FEFF}',
+    '\u{FFF9}'..='\u{FFFB}',
+    '\u{110BD}'..='\u{110BD}',
+    '\u{110CD}'..='\u{110CD}',
+    '\u{13430}'..='\u{1343F}',
+    '\u{1BCA0}'..='\u{1BCA3}',
+    '\u{1D173}'..='\u{1D17A}',
+    '\u{E0001}'..='\u{E0001}',
+    '\u{E0020}'..='\u{E007F}',
+];
+
+/// Whether a character is a format (Cf) or control (Cc) character
+pub fn is_invisible(c: char) -> bool {
+    c.is_control() || FORMAT_CHARS.iter().any(|range| range.contains(&c))
+}
+
+/// Remove format and control characters, collapsing whitespace to single spaces
+///
+/// Copying a message can bring along zero-width spaces, word joiners and
+/// byte order marks, which would otherwise make a word differ from the same
+/// word typed by hand. Whitespace is split on first, so line breaks and tabs
+/// separate words like spaces do.
+pub fn strip_invisible(text: &str) -> String {
+    text.split_whitespace()
+        .map(|token| token.chars().filter(|&c| !is_invisible(c)).collect())
+        .filter(|token: &String| !token.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Normalize a word for comparisons and lookups: without invisible characters,
+/// trimmed, NFC-composed and lowercase
+///
+/// Composing before and after lowercasing makes decomposed input (as sent by
+/// some mobile keyboards) and case mappings that expand into combining marks
+/// compare equal to their precomposed forms.
+pub fn normalize_word(word: &str) -> String {
+    let composed: String = strip_invisible(word).nfc().collect();
+    composed.to_lowercase().nfc().collect()
+}
+
+/// The word as a name is written, e.g. for asking the LLM whether it's a proper noun
+///
+/// The word is normalized first, so only the first letter ends up uppercase.
+pub fn display_capitalize(word: &str) -> String {
+    let word = normalize_word(word);
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// The word as the LLM is asked about it: as the player wrote it if they used
+/// any capitals, like `McDonald's` or `iPhone`, otherwise capitalized as a name
+///
+/// Only invisible characters are removed and the letters composed, so
+/// decomposed umlauts don't reach the prompt.
+pub fn proper_noun_casing(word: &str) -> String {
+    let written: String = strip_invisible(word).nfc().collect();
+    if written.chars().any(char::is_uppercase) {
+        written
+    } else {
+        display_capitalize(&written)
+    }
+}
+
+/// Whether the word was written with a capital first letter, as names are
+pub fn is_capitalized(word: &str) -> bool {
+    word.trim_start()
+        .chars()
+        .next()
+        .is_some_and(char::is_uppercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_word() {
+        let decomposed = "a\u{308}iti";
+        assert_ne!(decomposed, "äiti");
+        assert_eq!(normalize_word(decomposed), "äiti");
+        assert_eq!(normalize_word("ÄITI"), "äiti");
+        assert_eq!(normalize_word("A\u{308}ITI"), "äiti");
+
+        // Umlauts and rings anywhere in the word, composed or not
+        assert_eq!(normalize_word(" PÖYTÄ "), "pöytä");
+        assert_eq!(normalize_word("PO\u{308}YTA\u{308}"), "pöytä");
+        assert_eq!(normalize_word("Åland"), "åland");
+        assert_eq!(normalize_word("A\u{30A}LAND"), "åland");
+        assert_eq!(normalize_word("kesÄÄ"), "kesää");
+    }
+
+    #[test]
+    fn test_invisible_characters() {
+        // Zero-width space, word joiner and byte order mark
+        for copied in [
+            "kis\u{200B}sa",
+            "kissa\u{200B}",
+            "kis\u{2060}sa",
+            "\u{FEFF}kissa",
+        ] {
+            assert_eq!(normalize_word(copied), "kissa", "{:?}", copied);
+        }
+        assert_eq!(normalize_word("\u{FEFF}KIS\u{AD}SA\u{7}"), "kissa");
+        assert_eq!(
+            strip_invisible(" kissa \u{200B} \t\nkoira\u{2060}  "),
+            "kissa koira"
+        );
+        assert_eq!(strip_invisible("\u{200B}\u{FEFF}"), "");
+        assert!(!is_invisible('ä'));
+        assert!(!is_invisible(' '));
+    }
+
+    #[test]
+    fn test_display_capitalize() {
+        assert_eq!(display_capitalize("helsinki"), "Helsinki");
+        assert_eq!(display_capitalize("hELSINKI"), "Helsinki");
+        assert_eq!(display_capitalize("ähtäri"), "Ähtäri");
+        assert_eq!(display_capitalize("a\u{308}hta\u{308}ri"), "Ähtäri");
+        assert_eq!(display_capitalize("ÅLAND"), "Åland");
+        assert_eq!(display_capitalize(""), "");
+    }
+
+    #[test]
+    fn test_proper_noun_casing() {
+        // All lowercase is capitalized as a name
+        assert_eq!(proper_noun_casing("helsinki"), "Helsinki");
+        assert_eq!(proper_noun_casing("ähtäri"), "Ähtäri");
+        assert_eq!(proper_noun_casing("a\u{308}hta\u{308}ri"), "Ähtäri");
+        assert_eq!(proper_noun_casing("ßtraße"), "SStraße");
+
+        // Any capital keeps the player's casing
+        assert_eq!(proper_noun_casing("McDonald's"), "McDonald's");
+        assert_eq!(proper_noun_casing("iPhone"), "iPhone");
+        assert_eq!(proper_noun_casing("ÅLAND"), "ÅLAND");
+        assert_eq!(proper_noun_casing("A\u{30A}land\u{200B}"), "Åland");
+        assert_eq!(proper_noun_casing(""), "");
+    }
+
+    #[test]
+    fn test_is_capitalized() {
+        assert!(is_capitalized("Helsinki"));
+        assert!(is_capitalized("ÄHTÄRI"));
+        assert!(is_capitalized("A\u{308}hta\u{308}ri"));
+        assert!(!is_capitalized("helsinki"));
+        assert!(!is_capitalized("hELSINKI"));
+        assert!(!is_capitalized(""));
+    }
+}
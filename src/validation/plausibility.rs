@@ -0,0 +1,217 @@
+//! Telling keyboard mash apart from unknown words that could be real
+//!
+//! Words missing from the dictionary go to the LLM in case they're proper
+//! nouns, but there's no point in asking about `asdfgh`. A word doesn't look
+//! like one if it has no vowels, too many consonants or the same letter too
+//! many times in a row, or hardly any of its letter triples are found in the
+//! dictionary's words. The limits are loose enough for foreign names like
+//! Schwarzenegger.
+
+use std::fmt;
+
+use crate::config::Config;
+use crate::validation::dictionary::DictionaryValidator;
+
+/// Letters counted as vowels, including the ones of foreign names
+const VOWELS: &str = "aeiouyåäöáàâãéèêëíìîïóòôõúùûüý";
+
+/// Why a word doesn't look like a word
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Implausibility {
+    NoVowels,
+    /// More consonants in a row than allowed
+    Consonants(String),
+    /// The same letter more times in a row than allowed
+    Repeated(char),
+    /// Too few letter triples found in the dictionary's words, with how many were
+    UnknownTrigrams {
+        known: usize,
+    },
+}
+
+impl fmt::Display for Implausibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Implausibility::NoVowels => write!(f, "no vowels"),
+            Implausibility::Consonants(run) => write!(f, "consonants '{}' in a row", run),
+            Implausibility::Repeated(letter) => write!(f, "'{}' repeated in a row", letter),
+            Implausibility::UnknownTrigrams { known } => {
+                write!(f, "{} letter triples found in the dictionary", known)
+            }
+        }
+    }
+}
+
+/// The limits a word missing from the dictionary has to stay within
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlausibilityFilter {
+    /// Most consonants allowed in a row
+    pub max_consonants: usize,
+    /// Most times the same letter may be repeated in a row
+    pub max_repeats: usize,
+    /// Fewest letter triples of the word that have to be found in the dictionary's
+    /// words, or all of them in shorter words
+    pub min_known_trigrams: usize,
+}
+
+impl PlausibilityFilter {
+    /// The filter set up in the configuration, None if it's off
+    pub fn from_config(config: &Config) -> Option<Self> {
+        config
+            .plausibility_filter
+            .then(|| Self::with_limits_from(config))
+    }
+
+    /// The filter with the configured limits, whether it's on or not
+    pub fn with_limits_from(config: &Config) -> Self {
+        Self {
+            max_consonants: config.plausibility_max_consonants,
+            max_repeats: config.plausibility_max_repeats,
+            min_known_trigrams: config.plausibility_min_known_trigrams,
+        }
+    }
+
+    /// Check a normalized word, telling why it doesn't look like a word if it doesn't
+    ///
+    /// Only letters count, so e.g. a hyphen ends a run of consonants.
+    pub fn check(
+        &self,
+        word: &str,
+        dictionary: &DictionaryValidator,
+    ) -> Result<(), Implausibility> {
+        if !word.chars().any(is_vowel) {
+            return Err(Implausibility::NoVowels);
+        }
+
+        let mut consonants = String::new();
+        let mut repeated = (None, 0);
+        for c in word.chars() {
+            if c.is_alphabetic() && !is_vowel(c) {
+                consonants.push(c);
+                if consonants.chars().count() > self.max_consonants {
+                    return Err(Implausibility::Consonants(consonants));
+                }
+            } else {
+                consonants.clear();
+            }
+
+            repeated = match repeated {
+                (Some(previous), count) if previous == c => (Some(c), count + 1),
+                _ => (Some(c), 1),
+            };
+            if c.is_alphabetic() && repeated.1 > self.max_repeats {
+                return Err(Implausibility::Repeated(c));
+            }
+        }
+
+        let letters: Vec<char> = word.chars().collect();
+        let trigrams: Vec<[char; 3]> = letters
+            .windows(3)
+            .filter(|window| window.iter().all(|c| c.is_alphabetic()))
+            .map(|window| [window[0], window[1], window[2]])
+            .collect();
+        let known = trigrams
+            .iter()
+            .filter(|&&trigram| dictionary.has_trigram(trigram))
+            .count();
+        if known < self.min_known_trigrams.min(trigrams.len()) {
+            return Err(Implausibility::UnknownTrigrams { known });
+        }
+        Ok(())
+    }
+}
+
+fn is_vowel(c: char) -> bool {
+    VOWELS.contains(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::normalize::normalize_word;
+
+    /// A small list of common Finnish words, standing in for the dictionary
+    const FINNISH_WORDS: &str = include_str!("demo_words.txt");
+
+    fn filter() -> PlausibilityFilter {
+        let config = Config {
+            plausibility_filter: true,
+            ..Config::default()
+        };
+        PlausibilityFilter::from_config(&config).unwrap()
+    }
+
+    fn check(word: &str) -> Result<(), Implausibility> {
+        let dictionary = DictionaryValidator::from_words(FINNISH_WORDS.lines());
+        filter().check(&normalize_word(word), &dictionary)
+    }
+
+    #[test]
+    fn test_finnish_words() {
+        for word in [
+            "hiiri",
+            "lumiukko",
+            "saippua",
+            "kauppa",
+            "järvenpää",
+            "kirjasto",
+            "Tampere",
+            "Jyväskylä",
+            "vaahtera",
+        ] {
+            assert_eq!(check(word), Ok(()), "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_foreign_names() {
+        for word in ["Schwarzenegger", "Lindström", "Washington", "Tolkien"] {
+            assert_eq!(check(word), Ok(()), "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_mash() {
+        assert_eq!(check("xkcd"), Err(Implausibility::NoVowels));
+        assert_eq!(
+            check("asdfghjkl"),
+            Err(Implausibility::Consonants("sdfghj".to_string()))
+        );
+        assert_eq!(check("kissaaaa"), Err(Implausibility::Repeated('a')));
+        for word in ["asdfgh", "qwfpag", "zxcvbe", "ghjgha"] {
+            assert_eq!(
+                check(word),
+                Err(Implausibility::UnknownTrigrams { known: 0 }),
+                "{}",
+                word
+            );
+        }
+    }
+
+    #[test]
+    fn test_limits() {
+        let dictionary = DictionaryValidator::from_words(["kissa"]);
+        let strict = PlausibilityFilter {
+            max_consonants: 1,
+            max_repeats: 1,
+            min_known_trigrams: 4,
+        };
+        assert_eq!(
+            strict.check("kissa", &dictionary),
+            Err(Implausibility::Consonants("ss".to_string()))
+        );
+        let loose = PlausibilityFilter {
+            max_consonants: 2,
+            max_repeats: 2,
+            ..strict
+        };
+        // The word has only three triples to find
+        assert_eq!(loose.check("kissa", &dictionary), Ok(()));
+        assert_eq!(
+            loose.check("kissan", &dictionary),
+            Err(Implausibility::UnknownTrigrams { known: 3 })
+        );
+        // Words too short for a triple only need a vowel
+        assert_eq!(loose.check("ok", &dictionary), Ok(()));
+    }
+}
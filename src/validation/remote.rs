@@ -0,0 +1,294 @@
+//! Asking a web dictionary about words missing from the local word list
+
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use crate::config::Config;
+use crate::error::{DictionaryError, Result};
+use crate::validation::normalize::normalize_word;
+
+/// Value of `remote_dictionary` that selects the Finnish Wiktionary
+pub const WIKTIONARY: &str = "wiktionary";
+
+const WIKTIONARY_API: &str = "https://fi.wiktionary.org/w/api.php";
+
+/// Wikimedia asks API clients to identify themselves
+const USER_AGENT: &str = concat!("sanabotti/", env!("CARGO_PKG_VERSION"));
+
+/// Future returned by [`LookupApi`] calls
+pub type LookupFuture<'a> = Pin<Box<dyn Future<Output = Result<bool>> + Send + 'a>>;
+
+/// The one question a remote dictionary answers
+///
+/// Abstracted behind a trait so lookups can be tested without the network.
+pub trait LookupApi: Send + Sync {
+    /// Whether the word exists
+    fn exists(&self, word: String) -> LookupFuture<'_>;
+}
+
+/// [`LookupApi`] asking fi.wiktionary.org whether it has a Finnish entry for the word
+///
+/// A page alone isn't enough, since the Finnish Wiktionary has pages for
+/// words of every language. The page has to have a section for Finnish.
+pub struct WiktionaryApi {
+    client: reqwest::Client,
+}
+
+impl WiktionaryApi {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+impl LookupApi for WiktionaryApi {
+    fn exists(&self, word: String) -> LookupFuture<'_> {
+        Box::pin(async move {
+            let response: serde_json::Value = self
+                .client
+                .get(WIKTIONARY_API)
+                .query(&[
+                    ("action", "query"),
+                    ("format", "json"),
+                    ("formatversion", "2"),
+                    ("prop", "revisions"),
+                    ("rvprop", "content"),
+                    ("rvslots", "main"),
+                    ("titles", word.as_str()),
+                ])
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status)
+                .map_err(remote_error)?
+                .json()
+                .await
+                .map_err(remote_error)?;
+            has_finnish_entry(&response)
+        })
+    }
+}
+
+/// Whether a Wiktionary query response has a page with a `==Suomi==` section
+fn has_finnish_entry(response: &serde_json::Value) -> Result<bool> {
+    // Pages that don't exist are marked missing, titles that can't exist invalid
+    let page = response["query"]["pages"]
+        .get(0)
+        .ok_or_else(|| DictionaryError::Remote(format!("unexpected response: {}", response)))?;
+    if page.get("missing").is_some() || page.get("invalid").is_some() {
+        return Ok(false);
+    }
+    let content = page["revisions"][0]["slots"]["main"]["content"]
+        .as_str()
+        .unwrap_or_default();
+    Ok(content.lines().any(|line| {
+        line.trim()
+            .strip_prefix("==")
+            .and_then(|heading| heading.strip_suffix("=="))
+            .is_some_and(|language| language.trim() == "Suomi")
+    }))
+}
+
+/// [`LookupApi`] asking any endpoint with `GET <url>?word=<word>`
+///
+/// 200 OK means the word exists and 404 Not Found that it doesn't. Anything
+/// else is a failed lookup.
+pub struct HttpLookupApi {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpLookupApi {
+    pub fn new(client: reqwest::Client, url: &str) -> Self {
+        Self {
+            client,
+            url: url.to_string(),
+        }
+    }
+}
+
+impl LookupApi for HttpLookupApi {
+    fn exists(&self, word: String) -> LookupFuture<'_> {
+        Box::pin(async move {
+            let response = self
+                .client
+                .get(&self.url)
+                .query(&[("word", word.as_str())])
+                .send()
+                .await
+                .map_err(remote_error)?;
+            match response.status() {
+                reqwest::StatusCode::OK => Ok(true),
+                reqwest::StatusCode::NOT_FOUND => Ok(false),
+                status => {
+                    Err(DictionaryError::Remote(format!("unexpected status {}", status)).into())
+                }
+            }
+        })
+    }
+}
+
+fn remote_error(error: reqwest::Error) -> crate::error::Error {
+    DictionaryError::Remote(error.to_string()).into()
+}
+
+/// Lookups made and answers received, shared by concurrent checks
+#[derive(Default)]
+struct LookupState {
+    /// Answers by normalized word, only definite ones
+    answers: HashMap<String, bool>,
+    /// When the requests of the last minute were made, oldest first
+    requests: VecDeque<Instant>,
+}
+
+/// A second dictionary consulted when a word isn't in the local one
+///
+/// Answers are cached for as long as the bot runs. Lookups that time out,
+/// fail or would go over the per-minute limit give no answer, and the word
+/// goes to the LLM as if there were no remote dictionary.
+pub struct RemoteDictionary {
+    api: Box<dyn LookupApi>,
+    timeout: Duration,
+    per_minute: usize,
+    state: Mutex<LookupState>,
+}
+
+impl RemoteDictionary {
+    pub fn new(api: Box<dyn LookupApi>, timeout: Duration, per_minute: usize) -> Self {
+        Self {
+            api,
+            timeout,
+            per_minute,
+            state: Mutex::default(),
+        }
+    }
+
+    /// The remote dictionary selected in the configuration, None if there isn't one
+    pub fn from_config(config: &Config) -> Result<Option<Arc<Self>>> {
+        if config.remote_dictionary.is_empty() {
+            return Ok(None);
+        }
+        let client = reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .build()
+            .map_err(remote_error)?;
+        let api: Box<dyn LookupApi> = if config.remote_dictionary == WIKTIONARY {
+            Box::new(WiktionaryApi::new(client))
+        } else {
+            Box::new(HttpLookupApi::new(client, &config.remote_dictionary))
+        };
+        Ok(Some(Arc::new(Self::new(
+            api,
+            Duration::from_millis(config.remote_dictionary_timeout_ms),
+            config.remote_dictionary_per_minute,
+        ))))
+    }
+
+    /// Whether the remote dictionary knows the word, None if it couldn't be asked
+    pub async fn check(&self, word: &str) -> Option<bool> {
+        let word = normalize_word(word);
+        {
+            let mut state = self.state.lock().unwrap();
+            if let Some(&exists) = state.answers.get(&word) {
+                return Some(exists);
+            }
+            let now = Instant::now();
+            while state
+                .requests
+                .front()
+                .is_some_and(|&at| now.duration_since(at) >= Duration::from_secs(60))
+            {
+                state.requests.pop_front();
+            }
+            if state.requests.len() >= self.per_minute {
+                debug!(word = %word, "Remote dictionary rate limit reached, skipping lookup");
+                return None;
+            }
+            state.requests.push_back(now);
+        }
+
+        let exists = match tokio::time::timeout(self.timeout, self.api.exists(word.clone())).await {
+            Ok(Ok(exists)) => exists,
+            Ok(Err(e)) => {
+                warn!(word = %word, error = %e, "Remote dictionary lookup failed");
+                return None;
+            }
+            Err(_) => {
+                warn!(word = %word, "Remote dictionary lookup timed out");
+                return None;
+            }
+        };
+        debug!(word = %word, exists, "Remote dictionary answered");
+        self.state.lock().unwrap().answers.insert(word, exists);
+        Some(exists)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::testing::MockLookupApi;
+
+    #[actix_rt::test]
+    async fn test_answers_are_cached() {
+        let api = MockLookupApi::knowing(&["kissa"]);
+        let remote = RemoteDictionary::new(Box::new(api.clone()), Duration::from_secs(1), 10);
+
+        assert_eq!(remote.check("kissa").await, Some(true));
+        assert_eq!(remote.check("KISSA").await, Some(true));
+        assert_eq!(remote.check("kissu").await, Some(false));
+        assert_eq!(remote.check("kissu").await, Some(false));
+        assert_eq!(api.lookups(), vec!["kissa", "kissu"]);
+    }
+
+    #[actix_rt::test]
+    async fn test_rate_limit() {
+        let api = MockLookupApi::knowing(&["kissa"]);
+        let remote = RemoteDictionary::new(Box::new(api.clone()), Duration::from_secs(1), 2);
+
+        assert_eq!(remote.check("kissa").await, Some(true));
+        assert_eq!(remote.check("kassa").await, Some(false));
+        assert_eq!(remote.check("kassi").await, None);
+        // Cached answers don't count towards the limit
+        assert_eq!(remote.check("kissa").await, Some(true));
+        assert_eq!(api.lookups().len(), 2);
+    }
+
+    #[actix_rt::test]
+    async fn test_failures_give_no_answer() {
+        let api = MockLookupApi::failing();
+        let remote = RemoteDictionary::new(Box::new(api.clone()), Duration::from_secs(1), 10);
+        assert_eq!(remote.check("kissa").await, None);
+        // Failures aren't cached, the next play asks again
+        assert_eq!(remote.check("kissa").await, None);
+        assert_eq!(api.lookups().len(), 2);
+
+        let api = MockLookupApi::knowing(&["kissa"]).with_delay(Duration::from_secs(5));
+        let remote = RemoteDictionary::new(Box::new(api), Duration::from_millis(50), 10);
+        let started = Instant::now();
+        assert_eq!(remote.check("kissa").await, None);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_finnish_entry() {
+        let page = |content: &str| {
+            serde_json::json!({"query": {"pages": [{
+                "title": "kissa",
+                "revisions": [{"slots": {"main": {"content": content}}}]
+            }]}})
+        };
+        assert!(has_finnish_entry(&page("==Suomi==\n===Substantiivi===\n'''kissa'''")).unwrap());
+        assert!(has_finnish_entry(&page("==Ruotsi==\n...\n== Suomi ==\n")).unwrap());
+        // A page for a word in some other language only
+        assert!(!has_finnish_entry(&page("==Englanti==\n===Substantiivi===")).unwrap());
+        assert!(!has_finnish_entry(&page("===Suomi===")).unwrap());
+
+        let missing =
+            serde_json::json!({"query": {"pages": [{"title": "kissu", "missing": true}]}});
+        assert!(!has_finnish_entry(&missing).unwrap());
+        assert!(has_finnish_entry(&serde_json::json!({"error": "x"})).is_err());
+    }
+}
@@ -1,44 +1,259 @@
 use miette::SourceSpan;
 use std::collections::HashSet;
+use std::fmt;
 
-use crate::error::{Result, ValidationError};
+use crate::config::{Config, Language};
+use crate::error::{Error, Result, ValidationError};
+use crate::messages::Text;
+use crate::validation::neighbors::edit_distance_one;
+use crate::validation::normalize::normalize_word;
+
+/// Which moves count as legal continuations of the chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, poise::ChoiceParameter)]
+pub enum RuleMode {
+    /// Change, add, or remove exactly one letter
+    #[default]
+    #[name = "classic"]
+    Classic,
+    /// Like classic, but rearranging all the letters of the previous word is also allowed
+    #[name = "anagram"]
+    Anagram,
+}
+
+impl std::str::FromStr for RuleMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "classic" => Ok(RuleMode::Classic),
+            "anagram" => Ok(RuleMode::Anagram),
+            other => Err(Error::Config(format!(
+                "Invalid RULE_MODE '{}', expected 'classic' or 'anagram'",
+                other
+            ))),
+        }
+    }
+}
+
+/// How changing a letter to or from its dotted or ringed form (a↔ä, a↔å, o↔ö) counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiacriticPolicy {
+    /// It's a letter change like any other
+    #[default]
+    Distinct,
+    /// It doesn't use up the move, so another letter may be changed too
+    Free,
+    /// A move that does nothing else is rejected
+    Forbidden,
+}
+
+impl std::str::FromStr for DiacriticPolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "distinct" => Ok(DiacriticPolicy::Distinct),
+            "free" => Ok(DiacriticPolicy::Free),
+            "forbidden" => Ok(DiacriticPolicy::Forbidden),
+            other => Err(Error::Config(format!(
+                "Invalid DIACRITIC_POLICY '{}', expected 'distinct', 'free' or 'forbidden'",
+                other
+            ))),
+        }
+    }
+}
+
+/// How words played after a word still waiting for the LLM are handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PendingPolicy {
+    /// Chain off the pending word as if it were valid, even if it's later rejected
+    #[default]
+    Optimistic,
+    /// Reject new words until the pending word has been resolved
+    Strict,
+    /// Chain off the pending word, re-checking the words that followed it if it's rejected
+    Rollback,
+    /// Hold new words until the pending word has been resolved, then judge them in order
+    Queue,
+}
+
+impl std::str::FromStr for PendingPolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "optimistic" => Ok(PendingPolicy::Optimistic),
+            "strict" => Ok(PendingPolicy::Strict),
+            "rollback" => Ok(PendingPolicy::Rollback),
+            "queue" => Ok(PendingPolicy::Queue),
+            other => Err(Error::Config(format!(
+                "Invalid PENDING_WORD_POLICY '{}', expected 'optimistic', 'strict', 'rollback' or 'queue'",
+                other
+            ))),
+        }
+    }
+}
+
+/// The configurable rules of the game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRules {
+    pub mode: RuleMode,
+    /// How a↔ä, a↔å and o↔ö changes count towards the one-letter rule
+    pub diacritic_policy: DiacriticPolicy,
+    /// Minimum word length in letters, if limited
+    pub min_length: Option<usize>,
+    /// Maximum word length in letters, if limited
+    pub max_length: Option<usize>,
+    /// Whether the same player may play two words in a row
+    pub allow_consecutive_turns: bool,
+    /// Whether words missing from the dictionary are checked as proper nouns
+    pub accept_proper_nouns: bool,
+    /// Whether only words the player capitalized are checked as proper nouns
+    pub require_capitalization: bool,
+    /// What to do with words played while the chain head waits for the LLM
+    pub pending_policy: PendingPolicy,
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        Self {
+            mode: RuleMode::default(),
+            diacritic_policy: DiacriticPolicy::default(),
+            min_length: None,
+            max_length: None,
+            allow_consecutive_turns: true,
+            accept_proper_nouns: true,
+            require_capitalization: false,
+            pending_policy: PendingPolicy::default(),
+        }
+    }
+}
+
+impl GameRules {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            mode: config.rule_mode,
+            diacritic_policy: config.diacritic_policy,
+            min_length: (config.min_word_length > 0).then_some(config.min_word_length),
+            max_length: (config.max_word_length > 0).then_some(config.max_word_length),
+            allow_consecutive_turns: config.allow_consecutive_turns,
+            accept_proper_nouns: config.accept_proper_nouns,
+            require_capitalization: config.require_capitalization,
+            pending_policy: config.pending_policy,
+        }
+    }
+}
 
 /// Validates that a word follows the game rules in relation to a previous word
 #[derive(Debug, Clone, Default)]
 pub struct RulesValidator {
     /// Set of previously used words in the current game
     used_words: HashSet<String>,
+
+    /// The rules in effect
+    rules: GameRules,
 }
 
 impl RulesValidator {
+    pub fn new(rules: GameRules) -> Self {
+        Self {
+            used_words: HashSet::new(),
+            rules,
+        }
+    }
+
+    /// The rules this validator enforces
+    pub fn rules(&self) -> &GameRules {
+        &self.rules
+    }
+
+    /// Check that a word is within the configured length limits
+    pub fn validate_length(&self, word: &str) -> Result<()> {
+        let word = normalize_word(word);
+        let length = word.chars().count();
+
+        let reason = match (self.rules.min_length, self.rules.max_length) {
+            (Some(min), _) if length < min => Violation::TooShort { min },
+            (_, Some(max)) if length > max => Violation::TooLong { max },
+            _ => return Ok(()),
+        };
+
+        Err(ValidationError::RuleViolation {
+            span: Some(SourceSpan::from((0, word.len()))),
+            word,
+            reason,
+        }
+        .into())
+    }
+
     /// Check if the new word follows the game rules in relation to the previous word:
-    /// 1. One letter changed, added, or removed
+    /// 1. One letter changed, added, or removed (or, in anagram mode, the letters rearranged)
     /// 2. Not previously used in this game session
     ///
     /// Returns Ok(()) if valid, or appropriate error if not
     pub fn validate_move(&mut self, previous_word: &str, new_word: &str) -> Result<()> {
-        let previous = previous_word.trim().to_lowercase();
-        let new = new_word.trim().to_lowercase();
+        self.validate_move_dry_run(previous_word, new_word)?;
+
+        // Valid move - add the word to the used words set
+        self.used_words.insert(normalize_word(new_word));
+        Ok(())
+    }
+
+    /// Check a move like [`validate_move`](Self::validate_move) without recording the word as used
+    pub fn validate_move_dry_run(&self, previous_word: &str, new_word: &str) -> Result<()> {
+        let previous = normalize_word(previous_word);
+        let new = normalize_word(new_word);
 
         // Check if the word has been used before
         if self.used_words.contains(&new) {
             return Err(ValidationError::AlreadyUsed(new.clone()).into());
         }
 
+        self.validate_length(&new)?;
+
+        if self.rules.mode == RuleMode::Anagram && is_anagram(&previous, &new) {
+            return Ok(());
+        }
+
+        if self.rules.diacritic_policy == DiacriticPolicy::Free {
+            return check_free_diacritics(&previous, &new);
+        }
+
         // Check if the word follows the one-letter rule
         let (is_valid, violation_span) = check_one_letter_difference(&previous, &new);
+        if is_valid && self.rules.diacritic_policy == DiacriticPolicy::Forbidden {
+            if let [change @ LetterChange::Substituted { from, to, .. }] =
+                analyze_difference(&previous, &new).changes.as_slice()
+            {
+                if is_diacritic_pair(*from, *to) {
+                    return Err(ValidationError::RuleViolation {
+                        span: Some(char_span(&new, change.position())),
+                        word: new,
+                        reason: Violation::DiacriticOnly(change.clone()),
+                    }
+                    .into());
+                }
+            }
+        }
         if !is_valid {
+            let difference = analyze_difference(&previous, &new);
+            // Where letters were added or removed the span covers the whole
+            // stretch that differs, not only the first change in it
+            let span = violation_span.or_else(|| {
+                difference
+                    .changes
+                    .first()
+                    .map(|change| char_span(&new, change.position()))
+            });
+
             return Err(ValidationError::RuleViolation {
                 word: new.clone(),
-                span: violation_span,
-                reason: "Word must differ by exactly one letter (added, removed, or changed)"
-                    .to_string(),
+                span,
+                reason: Violation::Letters(difference),
             }
             .into());
         }
 
-        // Valid move - add the word to the used words set
-        self.used_words.insert(new);
         Ok(())
     }
 
@@ -49,8 +264,12 @@ impl RulesValidator {
 
     /// Add a word to the list of used words (for initialization)
     pub fn add_word(&mut self, word: &str) {
-        let word = word.trim().to_lowercase();
-        self.used_words.insert(word);
+        self.used_words.insert(normalize_word(word));
+    }
+
+    /// Forget that a word has been used, e.g. when it's rolled back out of the chain
+    pub fn remove_word(&mut self, word: &str) {
+        self.used_words.remove(&normalize_word(word));
     }
 
     /// Get the number of words used so far
@@ -58,81 +277,261 @@ impl RulesValidator {
         self.used_words.len()
     }
 
-    /// Reset the game state
+    /// Reset the game state (the rules stay in effect)
     pub fn reset(&mut self) {
         self.used_words.clear();
     }
 }
 
-/// Check if two words differ by exactly one letter (changed, added, or removed)
-/// Returns (is_valid, optional_violation_span)
-fn check_one_letter_difference(word1: &str, word2: &str) -> (bool, Option<SourceSpan>) {
-    let len1 = word1.chars().count();
-    let len2 = word2.chars().count();
+/// A single letter that differs between two words
+///
+/// Positions are character indices into the new word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LetterChange {
+    /// The letter `from` was replaced with `to`
+    Substituted {
+        position: usize,
+        from: char,
+        to: char,
+    },
+    /// The letter was added at `position`
+    Inserted { position: usize, letter: char },
+    /// The letter was removed from just before `position`
+    Removed { position: usize, letter: char },
+}
 
-    // If length difference is more than 1, return false
-    if (len1 as isize - len2 as isize).abs() > 1 {
-        return (false, None);
+impl LetterChange {
+    /// Character index into the new word where the change is
+    pub fn position(&self) -> usize {
+        match self {
+            LetterChange::Substituted { position, .. }
+            | LetterChange::Inserted { position, .. }
+            | LetterChange::Removed { position, .. } => *position,
+        }
     }
+}
 
-    // Convert to character vectors for easier comparison
-    let chars1: Vec<char> = word1.chars().collect();
-    let chars2: Vec<char> = word2.chars().collect();
+impl fmt::Display for LetterChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LetterChange::Substituted { from, to, .. } => write!(f, "{}→{}", from, to),
+            LetterChange::Inserted { letter, .. } => write!(f, "+{}", letter),
+            LetterChange::Removed { letter, .. } => write!(f, "-{}", letter),
+        }
+    }
+}
 
-    // If lengths are equal, one letter might have been changed
-    if len1 == len2 {
-        let mut differences = 0;
+/// The smallest set of letter changes that turns one word into another
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Difference {
+    /// Changes ordered by position in the new word
+    pub changes: Vec<LetterChange>,
+}
 
-        for i in 0..len1 {
-            if chars1[i] != chars2[i] {
-                differences += 1;
-                if differences > 1 {
-                    return (false, None);
-                }
-            }
+/// Why a word isn't a legal move, rendered in the bot's language by [`crate::messages`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    TooShort {
+        min: usize,
+    },
+    TooLong {
+        max: usize,
+    },
+    /// Not exactly one letter changed, added, or removed
+    Letters(Difference),
+    /// Only a↔ä, a↔å or o↔ö changed, which the diacritic policy forbids
+    DiacriticOnly(LetterChange),
+    /// The same player played the previous word
+    ConsecutiveTurns,
+    /// Played before in this game
+    AlreadyUsed(String),
+    /// Anything else, already explained
+    Other(String),
+}
+
+/// In the default language, e.g. for logs and simulations
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&Text::Violation(self).render(Language::default()))
+    }
+}
+
+/// Find exactly which letters differ between two (normalized) words
+///
+/// Uses a Levenshtein alignment, so insertions and removals are reported as
+/// such instead of as a run of substitutions.
+pub fn analyze_difference(previous: &str, new: &str) -> Difference {
+    align(previous, new, |a, b| usize::from(a != b))
+}
+
+/// The changes of the alignment that costs the least, a substitution costing `cost`
+fn align(previous: &str, new: &str, cost: impl Fn(char, char) -> usize) -> Difference {
+    let a: Vec<char> = previous.chars().collect();
+    let b: Vec<char> = new.chars().collect();
+    let (n, m) = (a.len(), b.len());
+
+    // distance[i][j] = edit distance between a[..i] and b[..j]
+    let mut distance = vec![vec![0usize; m + 1]; n + 1];
+    for (i, row) in distance.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in distance[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=n {
+        for j in 1..=m {
+            let substitution = distance[i - 1][j - 1] + cost(a[i - 1], b[j - 1]);
+            let removal = distance[i - 1][j] + 1;
+            let insertion = distance[i][j - 1] + 1;
+            distance[i][j] = substitution.min(removal).min(insertion);
         }
+    }
 
-        // One letter change is valid, no change is invalid
-        if differences == 1 {
-            return (true, None);
+    // Walk back from the end to recover the changes
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (n, m);
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 && distance[i][j] == distance[i - 1][j - 1] + cost(a[i - 1], b[j - 1]) {
+            if a[i - 1] != b[j - 1] {
+                changes.push(LetterChange::Substituted {
+                    position: j - 1,
+                    from: a[i - 1],
+                    to: b[j - 1],
+                });
+            }
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && distance[i][j] == distance[i - 1][j] + 1 {
+            changes.push(LetterChange::Removed {
+                position: j,
+                letter: a[i - 1],
+            });
+            i -= 1;
         } else {
-            // No change - the words are identical
-            return (false, Some(SourceSpan::from((0, word2.len()))));
+            changes.push(LetterChange::Inserted {
+                position: j - 1,
+                letter: b[j - 1],
+            });
+            j -= 1;
         }
     }
 
-    // At this point we know lengths differ by exactly 1
-    // Check if one letter was added or removed
+    changes.reverse();
+    Difference { changes }
+}
+
+/// Whether the letters are the same but for the dots or the ring, e.g. a and ä
+pub fn is_diacritic_pair(a: char, b: char) -> bool {
+    matches!(
+        (a, b),
+        ('a', 'ä') | ('ä', 'a') | ('a', 'å') | ('å', 'a') | ('o', 'ö') | ('ö', 'o')
+    )
+}
 
-    let (shorter, longer) = if len1 < len2 {
-        (&chars1, &chars2)
-    } else {
-        (&chars2, &chars1)
-    };
+/// Check the one-letter rule with diacritic changes not counting as the changed letter
+///
+/// Any number of a↔ä, a↔å and o↔ö changes may go along with one other change,
+/// or make up the move by themselves. The violation lists only the changes
+/// that count. Both words are expected to be normalized.
+pub fn check_free_diacritics(previous: &str, new: &str) -> Result<()> {
+    let difference = align(previous, new, |a, b| {
+        usize::from(a != b && !is_diacritic_pair(a, b))
+    });
+    let counted: Vec<LetterChange> = difference
+        .changes
+        .iter()
+        .filter(|change| {
+            !matches!(change, LetterChange::Substituted { from, to, .. } if is_diacritic_pair(*from, *to))
+        })
+        .cloned()
+        .collect();
+    if !difference.changes.is_empty() && counted.len() <= 1 {
+        return Ok(());
+    }
 
-    let mut long_idx = 0;
-    let mut short_idx = 0;
-    let mut found_difference = false;
+    Err(ValidationError::RuleViolation {
+        span: Some(match counted.first() {
+            Some(change) => char_span(new, change.position()),
+            None => SourceSpan::from((0, new.len())),
+        }),
+        word: new.to_string(),
+        reason: Violation::Letters(Difference { changes: counted }),
+    }
+    .into())
+}
 
-    // Check if we can match the shorter word to the longer one
-    // by skipping exactly one letter in the longer word
-    while short_idx < shorter.len() && long_idx < longer.len() {
-        if shorter[short_idx] == longer[long_idx] {
-            short_idx += 1;
-            long_idx += 1;
-        } else {
-            // Found a difference, can only have one
-            if found_difference {
-                return (false, None);
-            }
-            found_difference = true;
-            long_idx += 1;
-        }
+/// Span covering the character at `char_idx` of `word`, in byte offsets
+///
+/// Points at the end of the word if the index is past the last character.
+fn char_span(word: &str, char_idx: usize) -> SourceSpan {
+    match word.char_indices().nth(char_idx) {
+        Some((offset, c)) => SourceSpan::from((offset, c.len_utf8())),
+        None => SourceSpan::from((word.len(), 0)),
     }
+}
 
-    // Should have processed all chars in the shorter word
-    // and either all or all but one in the longer word
-    (true, None)
+/// Number of leading characters the two words have in common
+fn common_prefix_len(chars1: &[char], chars2: &[char]) -> usize {
+    chars1
+        .iter()
+        .zip(chars2)
+        .take_while(|(a, b)| a == b)
+        .count()
+}
+
+/// Check if one word is the other with its letters rearranged
+///
+/// Both words are expected to be normalized. The same word isn't its own anagram.
+pub fn is_anagram(word1: &str, word2: &str) -> bool {
+    if word1 == word2 {
+        return false;
+    }
+    let mut chars1: Vec<char> = word1.chars().collect();
+    let mut chars2: Vec<char> = word2.chars().collect();
+    chars1.sort_unstable();
+    chars2.sort_unstable();
+    chars1 == chars2
+}
+
+/// Check if two words differ by exactly one letter (changed, added, or removed)
+/// Returns (is_valid, optional_violation_span)
+///
+/// Both words are expected to be normalized. The violation span is given in
+/// byte offsets into `word2`. If the lengths are the same it points at the
+/// first character that differs from `word1`. Otherwise it covers what was
+/// added between the parts the words have in common, or is empty where
+/// letters were removed. Identical words are covered whole.
+fn check_one_letter_difference(word1: &str, word2: &str) -> (bool, Option<SourceSpan>) {
+    if edit_distance_one(word1, word2) {
+        return (true, None);
+    }
+    if word1 == word2 {
+        return (false, Some(SourceSpan::from((0, word2.len()))));
+    }
+
+    let chars1: Vec<char> = word1.chars().collect();
+    let chars2: Vec<char> = word2.chars().collect();
+    let prefix = common_prefix_len(&chars1, &chars2);
+    if chars1.len() == chars2.len() {
+        return (false, Some(char_span(word2, prefix)));
+    }
+
+    let suffix = chars1[prefix..]
+        .iter()
+        .rev()
+        .zip(chars2[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let start = byte_offset(word2, prefix);
+    let end = byte_offset(word2, chars2.len() - suffix);
+    (false, Some(SourceSpan::from((start, end - start))))
+}
+
+/// Byte offset of the character at `char_idx`, or the length past the last one
+fn byte_offset(word: &str, char_idx: usize) -> usize {
+    word.char_indices()
+        .nth(char_idx)
+        .map_or(word.len(), |(offset, _)| offset)
 }
 
 #[cfg(test)]
@@ -160,6 +559,260 @@ mod tests {
         assert!(!check_one_letter_difference("kissa", "kissoilla").0);
     }
 
+    #[test]
+    fn test_length_limits() {
+        let validator = RulesValidator::new(GameRules {
+            min_length: Some(3),
+            max_length: Some(5),
+            ..GameRules::default()
+        });
+
+        assert!(validator.validate_length("kuu").is_ok());
+        assert!(validator.validate_length("kissa").is_ok());
+        assert!(validator.validate_length("yö").is_err());
+        assert!(validator.validate_length("kissat").is_err());
+
+        assert!(validator.validate_move_dry_run("kuu", "kuut").is_ok());
+        assert!(validator.validate_move_dry_run("kuu", "ku").is_err());
+        assert!(validator.validate_move_dry_run("kissa", "kissan").is_err());
+
+        // Unlimited by default
+        assert!(RulesValidator::default().validate_length("a").is_ok());
+    }
+
+    #[test]
+    fn test_analyze_difference() {
+        // Substitution
+        assert_eq!(
+            analyze_difference("kissa", "kassa").changes,
+            vec![LetterChange::Substituted {
+                position: 1,
+                from: 'i',
+                to: 'a'
+            }]
+        );
+
+        // Insertion
+        assert_eq!(
+            analyze_difference("kissa", "kissan").changes,
+            vec![LetterChange::Inserted {
+                position: 5,
+                letter: 'n'
+            }]
+        );
+
+        // Deletion
+        assert_eq!(
+            analyze_difference("äiti", "äti").changes,
+            vec![LetterChange::Removed {
+                position: 1,
+                letter: 'i'
+            }]
+        );
+
+        // Identical words have no changes
+        assert!(analyze_difference("kissa", "kissa").changes.is_empty());
+
+        // Multiple differences
+        let difference = analyze_difference("kissa", "kosse");
+        assert_eq!(
+            difference.changes,
+            vec![
+                LetterChange::Substituted {
+                    position: 1,
+                    from: 'i',
+                    to: 'o'
+                },
+                LetterChange::Substituted {
+                    position: 4,
+                    from: 'a',
+                    to: 'e'
+                },
+            ]
+        );
+        assert_eq!(
+            Violation::Letters(difference).to_string(),
+            "muutit 2 kirjainta: i→o, a→e"
+        );
+
+        let difference = analyze_difference("talo", "taloissa");
+        assert_eq!(difference.changes.len(), 4);
+        assert_eq!(
+            Violation::Letters(difference).to_string(),
+            "muutit 4 kirjainta: +i, +s, +s, +a"
+        );
+    }
+
+    #[test]
+    fn test_violation_reason_and_span() {
+        let validator = RulesValidator::default();
+
+        match validator.validate_move_dry_run("pöytä", "pääty") {
+            Err(crate::error::Error::Validation(ValidationError::RuleViolation {
+                word,
+                span,
+                reason,
+            })) => {
+                assert_eq!(reason.to_string(), "muutit 3 kirjainta: ö→ä, y→ä, ä→y");
+                let span = span.expect("span should point at the first change");
+                assert_eq!(&word[span.offset()..span.offset() + span.len()], "ä");
+                assert_eq!(span.offset(), 'p'.len_utf8());
+            }
+            other => panic!("Expected RuleViolation, got {:?}", other),
+        }
+    }
+
+    /// Replace the characters at the given char positions
+    fn substitute(word: &str, replacements: &[(usize, char)]) -> String {
+        word.chars()
+            .enumerate()
+            .map(|(i, c)| {
+                replacements
+                    .iter()
+                    .find(|(idx, _)| *idx == i)
+                    .map_or(c, |(_, replacement)| *replacement)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_multibyte_substitutions() {
+        let words = ["äiti", "öljy", "kärpänen", "åländska", "pöytä", "ääliö"];
+
+        for word in words {
+            let len = word.chars().count();
+            for i in 0..len {
+                for replacement in ['x', 'ä', 'ö', 'å'] {
+                    let changed = substitute(word, &[(i, replacement)]);
+                    if changed == word {
+                        continue;
+                    }
+                    assert!(
+                        check_one_letter_difference(word, &changed).0,
+                        "{} -> {}",
+                        word,
+                        changed
+                    );
+
+                    // A second change makes the move invalid, and the span points
+                    // at the first changed character
+                    for j in (i + 1)..len {
+                        let twice = substitute(&changed, &[(j, 'z')]);
+                        if twice.chars().nth(j) == word.chars().nth(j) {
+                            continue;
+                        }
+                        let (is_valid, span) = check_one_letter_difference(word, &twice);
+                        assert!(!is_valid, "{} -> {}", word, twice);
+
+                        let span = span.expect("violation should have a span");
+                        let offending = &twice[span.offset()..span.offset() + span.len()];
+                        assert_eq!(offending, replacement.to_string(), "{} -> {}", word, twice);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_move_violation_spans() {
+        let span = |previous: &str, new: &str| {
+            let mut validator = RulesValidator::new(GameRules::default());
+            match validator.validate_move(previous, new) {
+                Err(Error::Validation(ValidationError::RuleViolation {
+                    span: Some(span), ..
+                })) => (span.offset(), span.len()),
+                other => panic!("{} -> {}: {:?}", previous, new, other),
+            }
+        };
+
+        // The added letters are covered, up to where the tails match again
+        assert_eq!(span("kissa", "kisxysa"), (3, 2));
+        assert_eq!(span("kissa", "kissaxy"), (5, 2));
+        // Removed letters leave an empty span where they were
+        assert_eq!(span("kissa", "kia"), (2, 0));
+        assert_eq!(span("kissa", "kis"), (3, 0));
+        // A changed letter is pointed at as before
+        assert_eq!(span("kissa", "kalsa"), (1, 1));
+    }
+
+    #[test]
+    fn test_insertion_and_deletion_spans() {
+        let check = |word1: &str, word2: &str| {
+            let (is_valid, span) = check_one_letter_difference(word1, word2);
+            (is_valid, span.map(|span| (span.offset(), span.len())))
+        };
+
+        // Two letters added in the middle, then the tails match again
+        assert_eq!(check("abc", "abxyc"), (false, Some((2, 2))));
+        // One letter skipped, but the tails still differ
+        assert_eq!(check("abcd", "abxcde"), (false, Some((2, 4))));
+        assert_eq!(check("kissa", "kisxsz"), (false, Some((3, 3))));
+        assert_eq!(check("kisxsz", "kissa"), (false, Some((3, 2))));
+
+        // At the very start
+        assert_eq!(check("kissa", "akissa"), (true, None));
+        assert_eq!(check("kissa", "issa"), (true, None));
+        assert_eq!(check("kissa", "xykissa"), (false, Some((0, 2))));
+        assert_eq!(check("kissa", "ssa"), (false, Some((0, 0))));
+
+        // At the very end
+        assert_eq!(check("kissa", "kissan"), (true, None));
+        assert_eq!(check("kissa", "kiss"), (true, None));
+        assert_eq!(check("kissa", "kissaxy"), (false, Some((5, 2))));
+        assert_eq!(check("kissa", "kis"), (false, Some((3, 0))));
+    }
+
+    #[test]
+    fn test_multibyte_spans() {
+        // Identical words span the whole word in bytes
+        let (is_valid, span) = check_one_letter_difference("pöytä", "pöytä");
+        assert!(!is_valid);
+        assert_eq!(span, Some(SourceSpan::from((0, "pöytä".len()))));
+
+        // Too long: span covers the added letters
+        let (is_valid, span) = check_one_letter_difference("äiti", "äitiäää");
+        assert!(!is_valid);
+        assert_eq!(
+            span,
+            Some(SourceSpan::from(("äiti".len(), 3 * 'ä'.len_utf8())))
+        );
+
+        // Too short: the new word is a prefix, so the span is empty at its end
+        let (is_valid, span) = check_one_letter_difference("ääliöt", "ääli");
+        assert!(!is_valid);
+        assert_eq!(span, Some(SourceSpan::from(("ääli".len(), 0))));
+    }
+
+    #[test]
+    fn test_unicode_normalization() {
+        let decomposed = "a\u{308}iti";
+
+        let mut validator = RulesValidator::default();
+        validator.add_word("äiti");
+
+        // The decomposed form is the same word, not a one-letter change
+        assert!(validator.validate_move_dry_run("äiti", decomposed).is_err());
+        assert!(validator.validate_move_dry_run(decomposed, "äidi").is_ok());
+        assert!(validator.is_valid_move("a\u{308}iti", "A\u{308}ITIT"));
+        assert!(!validator.is_valid_move("äiti", "äitit"));
+    }
+
+    #[test]
+    fn test_hyphenated_words() {
+        // The hyphen counts as a character of its own
+        assert!(check_one_letter_difference("linja-auto", "linjaauto").0);
+        assert!(check_one_letter_difference("linjaauto", "linja-auto").0);
+        assert!(check_one_letter_difference("linja-auto", "linja-autot").0);
+        assert!(check_one_letter_difference("linja-auto", "linja-aato").0);
+        assert!(check_one_letter_difference("vaa'an", "vaa'in").0);
+        assert!(!check_one_letter_difference("linja-auto", "linjaaato").0);
+
+        let mut validator = RulesValidator::default();
+        validator.add_word("linja-auto");
+        assert!(validator.is_valid_move("linja-auto", "linja-autot"));
+        assert!(!validator.is_valid_move("linja-autot", "linja-auto"));
+    }
+
     #[test]
     fn test_rules_validator() {
         let mut validator = RulesValidator::default();
@@ -170,7 +823,7 @@ mod tests {
         // Valid moves: one letter changed, added, or removed
         assert!(validator.is_valid_move("kissa", "kassa")); // Change
         assert!(validator.is_valid_move("kissa", "kissat")); // Add
-        assert!(validator.is_valid_move("kissat", "kissa")); // Remove
+        assert!(validator.is_valid_move("kissat", "kisat")); // Remove
 
         // Invalid: No change
         assert!(!validator.is_valid_move("kissa", "kissa"));
@@ -194,10 +847,212 @@ mod tests {
         }
 
         // Check word count
-        assert_eq!(validator.word_count(), 4); // "kissa", "kassa", "kissat", plus "kissa" from initialization
+        assert_eq!(validator.word_count(), 4); // "kissa" from initialization, "kassa", "kissat", "kisat"
+
+        // Dry runs never record the word as used
+        assert!(validator.validate_move_dry_run("kisat", "kiat").is_ok());
+        assert!(validator.validate_move_dry_run("kisat", "kiat").is_ok());
+        assert!(validator.validate_move_dry_run("kissat", "kassa").is_err());
+        assert_eq!(validator.word_count(), 4);
 
         // Reset and check
         validator.reset();
         assert_eq!(validator.word_count(), 0);
     }
+
+    #[test]
+    fn test_anagram_mode() {
+        assert!(is_anagram("sika", "kisa"));
+        assert!(is_anagram("äiti", "itiä"));
+        assert!(!is_anagram("sika", "sika"));
+        assert!(!is_anagram("sika", "kisat"));
+        assert!(!is_anagram("ääli", "aali"));
+
+        let mut validator = RulesValidator::new(GameRules {
+            mode: RuleMode::Anagram,
+            ..GameRules::default()
+        });
+        validator.add_word("sika");
+
+        // Rearranged letters, decomposed input normalizes first
+        assert!(validator.is_valid_move("sika", "kisa"));
+        assert!(validator.is_valid_move("kisa", "aiks"));
+        assert!(validator.is_valid_move("äiti", "ti\u{0061}\u{0308}i"));
+
+        // The one-letter rule still applies
+        assert!(validator.is_valid_move("aiks", "aika"));
+
+        // Near-anagrams: rearranged and one letter changed
+        let result = validator.validate_move("aika", "kaio");
+        assert!(matches!(
+            result,
+            Err(Error::Validation(ValidationError::RuleViolation {
+                reason: Violation::Letters(_),
+                ..
+            }))
+        ));
+
+        // The identical word isn't an anagram of itself
+        assert!(!validator.is_valid_move("aika", "aika"));
+
+        // Already used words stay used, even as anagrams
+        let result = validator.validate_move("aika", "sika");
+        assert!(matches!(
+            result,
+            Err(Error::Validation(ValidationError::AlreadyUsed(_)))
+        ));
+
+        // Classic mode doesn't allow anagrams
+        let mut classic = RulesValidator::default();
+        assert!(!classic.is_valid_move("sika", "kisa"));
+
+        assert_eq!("Anagram".parse::<RuleMode>().unwrap(), RuleMode::Anagram);
+        assert!("scrabble".parse::<RuleMode>().is_err());
+    }
+
+    fn with_diacritics(policy: DiacriticPolicy) -> RulesValidator {
+        RulesValidator::new(GameRules {
+            diacritic_policy: policy,
+            ..GameRules::default()
+        })
+    }
+
+    fn violation(validator: &RulesValidator, previous: &str, new: &str) -> Option<Violation> {
+        match validator.validate_move_dry_run(previous, new) {
+            Ok(()) => None,
+            Err(Error::Validation(ValidationError::RuleViolation { reason, .. })) => Some(reason),
+            Err(e) => panic!("unexpected error for {} → {}: {}", previous, new, e),
+        }
+    }
+
+    #[test]
+    fn test_diacritic_pairs() {
+        for (a, b) in [('a', 'ä'), ('a', 'å'), ('o', 'ö')] {
+            assert!(is_diacritic_pair(a, b));
+            assert!(is_diacritic_pair(b, a));
+        }
+        assert!(!is_diacritic_pair('a', 'a'));
+        assert!(!is_diacritic_pair('ä', 'å'));
+        assert!(!is_diacritic_pair('a', 'ö'));
+        assert!(!is_diacritic_pair('u', 'y'));
+
+        assert_eq!(
+            " Free ".parse::<DiacriticPolicy>().unwrap(),
+            DiacriticPolicy::Free
+        );
+        assert_eq!(
+            "forbidden".parse::<DiacriticPolicy>().unwrap(),
+            DiacriticPolicy::Forbidden
+        );
+        assert!("umlauts".parse::<DiacriticPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_distinct_diacritics() {
+        assert_eq!(
+            GameRules::default().diacritic_policy,
+            DiacriticPolicy::Distinct
+        );
+        let validator = with_diacritics(DiacriticPolicy::Distinct);
+
+        // An umlaut change is the changed letter, like any other
+        assert!(validator.validate_move_dry_run("lakki", "läkki").is_ok());
+        assert!(validator.validate_move_dry_run("sata", "såta").is_ok());
+        assert!(validator.validate_move_dry_run("läkki", "läkkä").is_ok());
+
+        // So it can't go along with another change
+        assert!(matches!(
+            violation(&validator, "sata", "sätä"),
+            Some(Violation::Letters(difference)) if difference.changes.len() == 2
+        ));
+        assert!(validator.validate_move_dry_run("aamu", "ämu").is_err());
+        assert!(validator.validate_move_dry_run("kala", "kälö").is_err());
+    }
+
+    #[test]
+    fn test_free_diacritics() {
+        let validator = with_diacritics(DiacriticPolicy::Free);
+
+        // Umlaut changes alone still make a move
+        assert!(validator.validate_move_dry_run("lakki", "läkki").is_ok());
+        assert!(validator.validate_move_dry_run("sata", "sätä").is_ok());
+        assert!(validator.validate_move_dry_run("sata", "såta").is_ok());
+        assert!(validator.validate_move_dry_run("kato", "kätö").is_ok());
+
+        // Along with one other change of any kind
+        assert!(validator.validate_move_dry_run("kala", "kälö").is_ok());
+        assert!(validator.validate_move_dry_run("kota", "kötat").is_ok());
+        assert!(validator.validate_move_dry_run("pöllö", "pollo").is_ok());
+        assert!(validator.validate_move_dry_run("pöllö", "pollot").is_ok());
+        // The alignment favours the free changes, a removal and a→ä instead of two changes
+        assert!(validator.validate_move_dry_run("aamu", "ämu").is_ok());
+
+        // Changes between vowels that aren't a pair count as usual
+        assert!(validator.validate_move_dry_run("tuli", "tyli").is_ok());
+        assert!(validator.validate_move_dry_run("tuli", "tylö").is_err());
+        assert!(validator.validate_move_dry_run("kävi", "kåvi").is_ok());
+
+        // Only the changes that count are reported, not a→ä
+        match violation(&validator, "kala", "kälöt") {
+            Some(Violation::Letters(difference)) => {
+                assert_eq!(difference.changes.len(), 2);
+                assert!(difference
+                    .changes
+                    .iter()
+                    .all(|change| change.position() > 1));
+            }
+            other => panic!("expected a letter violation, got {:?}", other),
+        }
+
+        // Some change is still needed, and input is normalized first
+        assert!(matches!(
+            violation(&validator, "sata", "SATA"),
+            Some(Violation::Letters(difference)) if difference.changes.is_empty()
+        ));
+        assert!(validator
+            .validate_move_dry_run("LAKKI", "la\u{0308}kkö")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_forbidden_diacritics() {
+        let validator = with_diacritics(DiacriticPolicy::Forbidden);
+
+        let result = validator.validate_move_dry_run("lakki", "läkki");
+        match result {
+            Err(Error::Validation(ValidationError::RuleViolation { word, span, reason })) => {
+                assert_eq!(word, "läkki");
+                assert_eq!(span, Some(SourceSpan::from((1, 2))));
+                assert_eq!(
+                    reason,
+                    Violation::DiacriticOnly(LetterChange::Substituted {
+                        position: 1,
+                        from: 'a',
+                        to: 'ä',
+                    })
+                );
+            }
+            other => panic!("expected a diacritic violation, got {:?}", other),
+        }
+        assert!(matches!(
+            violation(&validator, "pöllö", "pöllo"),
+            Some(Violation::DiacriticOnly(_))
+        ));
+        assert!(matches!(
+            violation(&validator, "sata", "såta"),
+            Some(Violation::DiacriticOnly(_))
+        ));
+
+        // Other changes involving the same letters are fine
+        assert!(validator.validate_move_dry_run("läkki", "läkkä").is_ok());
+        assert!(validator.validate_move_dry_run("kävi", "kåvi").is_ok());
+        assert!(validator.validate_move_dry_run("kis", "kisä").is_ok());
+        assert!(validator.validate_move_dry_run("pöllö", "pöllöt").is_ok());
+
+        // Two changes break the one-letter rule as before
+        assert!(matches!(
+            violation(&validator, "sata", "sätä"),
+            Some(Violation::Letters(_))
+        ));
+    }
 }
@@ -0,0 +1,401 @@
+//! Mirroring game highlights to a webhook
+//!
+//! The events of a game picked in `webhook_events` are turned into small JSON
+//! payloads and POSTed to `webhook_url` by a task of their own, so the game
+//! never waits for the endpoint: at most [`QUEUE_SIZE`] payloads wait to be
+//! sent and further ones are dropped, and a payload is given up after
+//! [`ATTEMPTS`] failed posts.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+use tracing::{debug, warn};
+
+use crate::config::Config;
+use crate::error::{BotError, Result};
+use crate::events::{next_event, GameEvent};
+
+/// Payloads waiting to be sent before new ones are dropped
+pub const QUEUE_SIZE: usize = 64;
+
+/// Posts made for a payload before it's dropped
+pub const ATTEMPTS: usize = 2;
+
+/// How long a post may take, kept short as the endpoint only mirrors the game
+pub const TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Kinds of events that can be sent to the webhook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEvent {
+    /// A word was accepted into the chain
+    WordAccepted,
+    /// The chain reached a multiple of `milestone_interval`
+    Milestone,
+    /// The chain grew longer than every earlier one
+    Record,
+    /// The chain was cleared
+    Reset,
+}
+
+impl WebhookEvent {
+    pub const ALL: [WebhookEvent; 4] = [
+        WebhookEvent::WordAccepted,
+        WebhookEvent::Milestone,
+        WebhookEvent::Record,
+        WebhookEvent::Reset,
+    ];
+
+    /// The name used in `webhook_events` and in the payload
+    pub fn name(self) -> &'static str {
+        match self {
+            WebhookEvent::WordAccepted => "word_accepted",
+            WebhookEvent::Milestone => "milestone",
+            WebhookEvent::Record => "record",
+            WebhookEvent::Reset => "reset",
+        }
+    }
+}
+
+impl FromStr for WebhookEvent {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.name() == s)
+            .ok_or_else(|| format!("unknown event `{}`", s))
+    }
+}
+
+/// The kinds in a comma-separated list of event names
+pub fn parse_events(list: &str) -> std::result::Result<Vec<WebhookEvent>, String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// What is posted for an event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Payload {
+    /// One of the names of [`WebhookEvent`]
+    pub event: &'static str,
+    pub word: Option<String>,
+    /// A string, as Discord IDs don't fit in a JavaScript number
+    pub user_id: Option<String>,
+    pub chain_length: Option<usize>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Payload {
+    /// The payload for an event of one of the `kinds`, None for other events
+    pub fn from_event(
+        event: &GameEvent,
+        kinds: &[WebhookEvent],
+        timestamp: DateTime<Utc>,
+    ) -> Option<Self> {
+        let (kind, word, user_id, chain_length) = match event {
+            GameEvent::WordAccepted {
+                user_id,
+                word,
+                chain_length,
+                ..
+            } => (
+                WebhookEvent::WordAccepted,
+                Some(word),
+                Some(*user_id),
+                Some(*chain_length),
+            ),
+            GameEvent::MilestoneReached {
+                chain_length,
+                user_id,
+                word,
+            } => (
+                WebhookEvent::Milestone,
+                Some(word),
+                Some(*user_id),
+                Some(*chain_length),
+            ),
+            GameEvent::ChainRecord {
+                chain_length,
+                user_id,
+                word,
+            } => (
+                WebhookEvent::Record,
+                Some(word),
+                Some(*user_id),
+                Some(*chain_length),
+            ),
+            GameEvent::GameReset { .. } => (WebhookEvent::Reset, None, None, Some(0)),
+            _ => return None,
+        };
+        if !kinds.contains(&kind) {
+            return None;
+        }
+        Some(Self {
+            event: kind.name(),
+            word: word.cloned(),
+            user_id: user_id.map(|id| id.to_string()),
+            chain_length,
+            timestamp,
+        })
+    }
+}
+
+/// Where a game's highlights are posted, cloned for every game
+#[derive(Clone)]
+pub struct Webhook {
+    client: reqwest::Client,
+    url: String,
+    kinds: Vec<WebhookEvent>,
+}
+
+impl Webhook {
+    pub fn new(url: &str, kinds: Vec<WebhookEvent>, timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| BotError::Config(format!("Can't create the webhook client: {}", e)))?;
+        Ok(Self {
+            client,
+            url: url.to_string(),
+            kinds,
+        })
+    }
+
+    /// The webhook set in the configuration, None if there isn't one
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        if config.webhook_url.is_empty() {
+            return Ok(None);
+        }
+        let kinds = parse_events(&config.webhook_events).map_err(BotError::Config)?;
+        Self::new(&config.webhook_url, kinds, TIMEOUT).map(Some)
+    }
+
+    /// Post the payloads of a game's events until the game is gone
+    pub async fn forward(self, mut events: broadcast::Receiver<GameEvent>) {
+        let (queue, payloads) = mpsc::channel(QUEUE_SIZE);
+        tokio::spawn(deliver(self.client, self.url, payloads));
+        while let Some(event) = next_event(&mut events, "webhook").await {
+            let Some(payload) = Payload::from_event(&event, &self.kinds, Utc::now()) else {
+                continue;
+            };
+            if let Err(mpsc::error::TrySendError::Full(payload)) = queue.try_send(payload) {
+                warn!(
+                    event = payload.event,
+                    "Webhook is falling behind, dropping the event"
+                );
+            }
+        }
+    }
+}
+
+/// Post the queued payloads one at a time, dropping those that fail every attempt
+async fn deliver(client: reqwest::Client, url: String, mut payloads: mpsc::Receiver<Payload>) {
+    while let Some(payload) = payloads.recv().await {
+        for attempt in 1..=ATTEMPTS {
+            let sent = client
+                .post(&url)
+                .json(&payload)
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status);
+            match sent {
+                Ok(_) => {
+                    debug!(event = payload.event, "Posted event to the webhook");
+                    break;
+                }
+                Err(e) if attempt < ATTEMPTS => {
+                    debug!(event = payload.event, error = %e, "Webhook post failed, retrying");
+                }
+                Err(e) => {
+                    warn!(event = payload.event, error = %e, "Webhook post failed, dropping the event");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::game_state::{ResetGame, SubmitWord};
+    use crate::actors::GameStateActor;
+    use crate::events::EventBus;
+    use crate::game::GameEngine;
+    use crate::validation::dictionary::DictionaryValidator;
+    use crate::validation::rules::GameRules;
+    use actix::Actor;
+    use axum::extract::State;
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use std::sync::{Arc, Mutex};
+    use std::time::Instant;
+    use tokio::net::TcpListener;
+    use tracing::Span;
+
+    /// Bodies posted to the mock endpoint, and how many posts to fail first
+    #[derive(Clone, Default)]
+    struct Endpoint {
+        posts: Arc<Mutex<Vec<serde_json::Value>>>,
+        failures: Arc<Mutex<usize>>,
+    }
+
+    async fn receive(
+        State(endpoint): State<Endpoint>,
+        Json(body): Json<serde_json::Value>,
+    ) -> StatusCode {
+        endpoint.posts.lock().unwrap().push(body);
+        let mut failures = endpoint.failures.lock().unwrap();
+        if *failures > 0 {
+            *failures -= 1;
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+        StatusCode::NO_CONTENT
+    }
+
+    /// Serve the endpoint on a free local port, returning its URL
+    async fn serve(endpoint: Endpoint) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let app = Router::new()
+            .route("/hook", post(receive))
+            .with_state(endpoint);
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        url
+    }
+
+    async fn posted(endpoint: &Endpoint, count: usize) -> Vec<serde_json::Value> {
+        for _ in 0..100 {
+            if endpoint.posts.lock().unwrap().len() >= count {
+                break;
+            }
+            actix_rt::time::sleep(Duration::from_millis(20)).await;
+        }
+        endpoint.posts.lock().unwrap().clone()
+    }
+
+    fn start_game(bus: &EventBus) -> actix::Addr<GameStateActor> {
+        let engine = GameEngine::new(
+            DictionaryValidator::from_words(["kissa", "kassa", "kassi"]),
+            GameRules::default(),
+        );
+        GameStateActor::with_engine(engine)
+            .with_events(bus.clone())
+            .with_milestones(2)
+            .start()
+    }
+
+    async fn play(game_state: &actix::Addr<GameStateActor>, word: &str, message_id: u64) {
+        game_state
+            .send(SubmitWord {
+                content: word.to_string(),
+                user_id: 123456789012345678,
+                channel_id: 1,
+                message_id,
+                span: Span::none(),
+            })
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_parse_events() {
+        assert_eq!(
+            parse_events(" milestone, reset ,"),
+            Ok(vec![WebhookEvent::Milestone, WebhookEvent::Reset])
+        );
+        assert_eq!(parse_events(""), Ok(Vec::new()));
+        assert!(parse_events("milestone,highscore").is_err());
+    }
+
+    #[actix_rt::test]
+    async fn test_payloads() {
+        let endpoint = Endpoint::default();
+        let url = serve(endpoint.clone()).await;
+        let bus = EventBus::default();
+        let webhook = Webhook::new(
+            &url,
+            vec![WebhookEvent::Milestone, WebhookEvent::Reset],
+            TIMEOUT,
+        )
+        .unwrap();
+        tokio::spawn(webhook.forward(bus.subscribe()));
+        let game_state = start_game(&bus);
+
+        play(&game_state, "kissa", 1).await;
+        play(&game_state, "kassa", 2).await;
+        game_state.send(ResetGame).await.unwrap();
+
+        let posts = posted(&endpoint, 2).await;
+        assert_eq!(posts.len(), 2);
+        let timestamp = posts[0]["timestamp"].as_str().unwrap();
+        assert!(DateTime::parse_from_rfc3339(timestamp).is_ok());
+        assert_eq!(
+            posts[0],
+            serde_json::json!({
+                "event": "milestone",
+                "word": "kassa",
+                "user_id": "123456789012345678",
+                "chain_length": 2,
+                "timestamp": timestamp,
+            })
+        );
+        assert_eq!(posts[1]["event"], "reset");
+        assert_eq!(posts[1]["word"], serde_json::Value::Null);
+        assert_eq!(posts[1]["chain_length"], 0);
+    }
+
+    #[actix_rt::test]
+    async fn test_failed_posts_are_retried_once() {
+        let endpoint = Endpoint::default();
+        *endpoint.failures.lock().unwrap() = 3;
+        let url = serve(endpoint.clone()).await;
+        let bus = EventBus::default();
+        let webhook = Webhook::new(&url, vec![WebhookEvent::WordAccepted], TIMEOUT).unwrap();
+        tokio::spawn(webhook.forward(bus.subscribe()));
+        let game_state = start_game(&bus);
+
+        // The first word fails both attempts and is dropped, the second gets through on the retry
+        play(&game_state, "kissa", 1).await;
+        play(&game_state, "kassa", 2).await;
+
+        let posts = posted(&endpoint, 4).await;
+        let words: Vec<_> = posts.iter().map(|post| post["word"].clone()).collect();
+        assert_eq!(words, ["kissa", "kissa", "kassa", "kassa"]);
+        actix_rt::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(endpoint.posts.lock().unwrap().len(), 4);
+    }
+
+    #[actix_rt::test]
+    async fn test_dead_endpoint_does_not_hold_up_the_game() {
+        // Accepts connections but never answers
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((connection, _)) = listener.accept().await {
+                connections.push(connection);
+            }
+        });
+        let bus = EventBus::default();
+        let webhook =
+            Webhook::new(&url, WebhookEvent::ALL.to_vec(), Duration::from_secs(30)).unwrap();
+        tokio::spawn(webhook.forward(bus.subscribe()));
+        let game_state = start_game(&bus);
+
+        // Far more events than fit in the queue, with every post hanging
+        let started = Instant::now();
+        for round in 0..QUEUE_SIZE as u64 {
+            play(&game_state, "kissa", round * 3).await;
+            play(&game_state, "kassa", round * 3 + 1).await;
+            play(&game_state, "kassi", round * 3 + 2).await;
+            game_state.send(ResetGame).await.unwrap();
+        }
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
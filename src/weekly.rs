@@ -0,0 +1,221 @@
+//! Weekly competitions: when a week ends and where its final standings are kept
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::scoring::PlayerScore;
+
+/// When weeks end, e.g. Monday 00:00 in Helsinki
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeeklySchedule {
+    pub day: Weekday,
+    pub time: NaiveTime,
+    pub timezone: Tz,
+}
+
+impl WeeklySchedule {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            day: config.weekly_reset_day,
+            time: config.weekly_reset_time,
+            timezone: config.timezone,
+        }
+    }
+
+    /// When the week that `now` falls in began
+    pub fn week_start(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let local = now.with_timezone(&self.timezone).date_naive();
+        let days_back =
+            (local.weekday().num_days_from_monday() + 7 - self.day.num_days_from_monday()) % 7;
+        let start = self.at(local - Duration::days(days_back.into()));
+        if start > now {
+            self.at(start.with_timezone(&self.timezone).date_naive() - Duration::days(7))
+        } else {
+            start
+        }
+    }
+
+    /// The reset time on a day, moved past a skipped hour when DST starts then
+    fn at(&self, date: NaiveDate) -> DateTime<Utc> {
+        let local = date.and_time(self.time);
+        self.timezone
+            .from_local_datetime(&local)
+            .earliest()
+            .or_else(|| {
+                self.timezone
+                    .from_local_datetime(&(local + Duration::hours(1)))
+                    .earliest()
+            })
+            .map_or_else(|| Utc.from_utc_datetime(&local), |start| start.to_utc())
+    }
+}
+
+/// A player's final score of a week
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedScore {
+    pub user_id: u64,
+    pub points: u64,
+    pub words: usize,
+}
+
+/// Final standings of a week, one line of the archive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedWeek {
+    /// Day the week started, e.g. "2025-01-13", which identifies the week
+    pub week: String,
+    pub ended_at: DateTime<Utc>,
+    pub standings: Vec<ArchivedScore>,
+}
+
+impl ArchivedWeek {
+    pub fn new(week: &str, ended_at: DateTime<Utc>, standings: &[(u64, PlayerScore)]) -> Self {
+        Self {
+            week: week.to_string(),
+            ended_at,
+            standings: standings
+                .iter()
+                .map(|&(user_id, score)| ArchivedScore {
+                    user_id,
+                    points: score.points,
+                    words: score.words,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Identifier of the week starting at `start`, in the schedule's time zone
+pub fn week_id(schedule: &WeeklySchedule, start: DateTime<Utc>) -> String {
+    start
+        .with_timezone(&schedule.timezone)
+        .date_naive()
+        .to_string()
+}
+
+/// Past weeks' standings, one JSON object per line
+///
+/// Also tells which week was posted last, so a restart doesn't post it again.
+#[derive(Debug, Clone)]
+pub struct WeeklyArchive {
+    path: PathBuf,
+}
+
+impl WeeklyArchive {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The most recently archived week, None if nothing has been archived
+    pub fn last_week(&self) -> Result<Option<String>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let last = content.lines().rev().find(|line| !line.trim().is_empty());
+        let Some(line) = last else {
+            return Ok(None);
+        };
+        let week: ArchivedWeek = serde_json::from_str(line).map_err(io::Error::other)?;
+        Ok(Some(week.week))
+    }
+
+    /// Append a week's standings
+    pub fn append(&self, week: &ArchivedWeek) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let line = serde_json::to_string(week).map_err(io::Error::other)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", line)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    #[test]
+    fn test_week_start() {
+        let schedule = WeeklySchedule {
+            day: Weekday::Mon,
+            time: NaiveTime::MIN,
+            timezone: chrono_tz::Europe::Helsinki,
+        };
+
+        // Monday 00:00 in Helsinki is Sunday 22:00 UTC in winter
+        assert_eq!(
+            schedule.week_start(at("2025-01-15T10:00:00Z")),
+            at("2025-01-12T22:00:00Z")
+        );
+        assert_eq!(
+            schedule.week_start(at("2025-01-12T21:59:59Z")),
+            at("2025-01-05T22:00:00Z")
+        );
+        assert_eq!(
+            schedule.week_start(at("2025-01-12T22:00:00Z")),
+            at("2025-01-12T22:00:00Z")
+        );
+        // and 21:00 UTC in summer
+        assert_eq!(
+            schedule.week_start(at("2025-07-02T12:00:00Z")),
+            at("2025-06-29T21:00:00Z")
+        );
+        assert_eq!(week_id(&schedule, at("2025-01-12T22:00:00Z")), "2025-01-13");
+
+        let schedule = WeeklySchedule {
+            day: Weekday::Fri,
+            time: NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+            timezone: chrono_tz::UTC,
+        };
+        // Later on the same weekday, before the reset time
+        assert_eq!(
+            schedule.week_start(at("2025-01-17T17:00:00Z")),
+            at("2025-01-10T18:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = WeeklyArchive::new(dir.path().join("weekly/standings.jsonl"));
+        assert_eq!(archive.last_week().unwrap(), None);
+
+        let score = PlayerScore {
+            points: 12,
+            words: 3,
+        };
+        let week = ArchivedWeek::new("2025-01-06", at("2025-01-12T22:00:00Z"), &[(1, score)]);
+        archive.append(&week).unwrap();
+        archive
+            .append(&ArchivedWeek::new(
+                "2025-01-13",
+                at("2025-01-19T22:00:00Z"),
+                &[],
+            ))
+            .unwrap();
+
+        assert_eq!(archive.last_week().unwrap().as_deref(), Some("2025-01-13"));
+        let first = fs::read_to_string(archive.path()).unwrap();
+        let first: ArchivedWeek = serde_json::from_str(first.lines().next().unwrap()).unwrap();
+        assert_eq!(first, week);
+    }
+}